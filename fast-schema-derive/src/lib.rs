@@ -0,0 +1,345 @@
+
+//! `#[derive(FastSchema)]`, generating `fast_schema::FastSchema` for
+//! structs and unit-only enums. Enable it with the `derive` feature of
+//! `fast-schema` rather than depending on this crate directly.
+//!
+//! Field constraints go in `#[schema(...)]`:
+//!
+//! ```ignore
+//! #[derive(Deserialize, FastSchema)]
+//! #[serde(rename_all = "camelCase")]
+//! struct Signup {
+//!     #[schema(min_length = 3, max_length = 20, pattern = "^[a-z0-9_]+$")]
+//!     user_name: String,
+//!     #[schema(format = "email")]
+//!     email: String,
+//!     #[schema(min = 13)]
+//!     age: Option<u8>,
+//! }
+//! ```
+//!
+//! Keys follow serde's `rename`, `rename_all`, `skip` and `default`
+//! attributes, so the schema matches what serde reads.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, LitStr};
+
+#[proc_macro_derive(FastSchema, attributes(schema))]
+pub fn derive_fast_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut props = Vec::new();
+                for field in &fields.named {
+                    let attrs = FieldAttrs::parse(&field.attrs)?;
+                    if attrs.skip {
+                        continue;
+                    }
+                    let ident = field.ident.as_ref().map(|ident| ident.unraw().to_string()).unwrap_or_default();
+                    let key = match attrs.rename.clone() {
+                        Some(key) => key,
+                        None => container.rename_all.map_or(ident.clone(), |rule| rule.apply_to_field(&ident)),
+                    };
+                    let schema = field_schema(&field.ty, &attrs);
+                    props.push(quote!(.prop(#key, #schema)));
+                }
+                let unknown_keys = &container.unknown_keys;
+                quote!(::fast_schema::Schema::object() #(#props)* #unknown_keys)
+            }
+            // Newtypes serialize as the wrapped value
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                field_schema(&field.ty, &FieldAttrs::parse(&field.attrs)?)
+            }
+            _ => return Err(syn::Error::new_spanned(input, "FastSchema needs named fields or a single-field newtype")),
+        },
+        Data::Enum(data) => {
+            let mut options = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(variant, "FastSchema only supports enums whose variants carry no data"));
+                }
+                let attrs = FieldAttrs::parse(&variant.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                let ident = variant.ident.unraw().to_string();
+                let name = match attrs.rename {
+                    Some(name) => name,
+                    None => container.rename_all.map_or(ident.clone(), |rule| rule.apply_to_variant(&ident)),
+                };
+                options.push(quote!(::fast_schema::Schema::literal(#name)));
+            }
+            quote!(::fast_schema::Schema::union(::std::vec![#(#options),*]))
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(input, "FastSchema cannot be derived for unions")),
+    };
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::fast_schema::FastSchema));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::fast_schema::FastSchema for #name #ty_generics #where_clause {
+            fn schema_builder() -> ::fast_schema::SchemaBuilder {
+                #body
+            }
+        }
+    })
+}
+
+/// `<T as FastSchema>::constrained(...)` with the field's constraints
+fn field_schema(ty: &syn::Type, attrs: &FieldAttrs) -> TokenStream2 {
+    let constraints = &attrs.constraints;
+    let optional = if attrs.optional { quote!(.optional()) } else { TokenStream2::new() };
+    quote! {
+        <#ty as ::fast_schema::FastSchema>::constrained(|schema| schema #(#constraints)*) #optional
+    }
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+    rename_all: Option<RenameRule>,
+    /// `.strict()`, `.strip()` or `.passthrough()`
+    unknown_keys: TokenStream2,
+}
+
+impl ContainerAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for attr in attrs {
+            if attr.path().is_ident("schema") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename_all") {
+                        parsed.rename_all = Some(RenameRule::parse(&meta.value()?.parse()?)?);
+                    } else if meta.path.is_ident("strict") {
+                        parsed.unknown_keys = quote!(.strict());
+                    } else if meta.path.is_ident("strip") {
+                        parsed.unknown_keys = quote!(.strip());
+                    } else if meta.path.is_ident("passthrough") {
+                        parsed.unknown_keys = quote!(.passthrough());
+                    } else {
+                        return Err(meta.error("unknown schema attribute; expected rename_all, strict, strip or passthrough"));
+                    }
+                    Ok(())
+                })?;
+            } else if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename_all") && meta.input.peek(syn::Token![=]) {
+                        // An explicit schema rule wins over serde's
+                        let rule = RenameRule::parse(&meta.value()?.parse()?)?;
+                        parsed.rename_all.get_or_insert(rule);
+                    } else if meta.path.is_ident("deny_unknown_fields") {
+                        parsed.unknown_keys = quote!(.strict());
+                    } else {
+                        skip_serde_meta(&meta)?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    skip: bool,
+    optional: bool,
+    /// Builder calls such as `.min(3f64)`
+    constraints: Vec<TokenStream2>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        let mut serde_rename = None;
+        for attr in attrs {
+            if attr.path().is_ident("schema") {
+                attr.parse_nested_meta(|meta| parsed.parse_schema_meta(&meta))?;
+            } else if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                        serde_rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                        parsed.skip = true;
+                    } else if meta.path.is_ident("default") {
+                        parsed.optional = true;
+                        skip_serde_meta(&meta)?;
+                    } else if meta.path.is_ident("flatten") {
+                        return Err(meta.error("FastSchema does not support #[serde(flatten)]"));
+                    } else {
+                        skip_serde_meta(&meta)?;
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        parsed.rename = parsed.rename.take().or(serde_rename);
+        Ok(parsed)
+    }
+
+    fn parse_schema_meta(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        let name = meta.path.get_ident().map(ToString::to_string).unwrap_or_default();
+        let constraint = match name.as_str() {
+            "rename" => {
+                self.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                return Ok(());
+            }
+            "skip" => {
+                self.skip = true;
+                return Ok(());
+            }
+            "optional" => {
+                self.optional = true;
+                return Ok(());
+            }
+            "min" | "min_length" | "min_items" => {
+                let bound: Expr = meta.value()?.parse()?;
+                quote!(.min((#bound) as f64))
+            }
+            "max" | "max_length" | "max_items" => {
+                let bound: Expr = meta.value()?.parse()?;
+                quote!(.max((#bound) as f64))
+            }
+            "length" => {
+                let length: Expr = meta.value()?.parse()?;
+                quote!(.length((#length) as usize))
+            }
+            "multiple_of" => {
+                let step: Expr = meta.value()?.parse()?;
+                quote!(.multiple_of((#step) as f64))
+            }
+            "pattern" => {
+                let pattern: LitStr = meta.value()?.parse()?;
+                quote!(.regex(#pattern))
+            }
+            "format" => {
+                let format: LitStr = meta.value()?.parse()?;
+                quote!(.format(::fast_schema::StringFormat::from_name(#format)))
+            }
+            "email" | "url" | "uuid" | "int" | "unique" => {
+                let method = &meta.path;
+                quote!(.#method())
+            }
+            _ => {
+                return Err(meta.error(
+                    "unknown schema attribute; expected rename, skip, optional, min, max, min_length, max_length, \
+                     min_items, max_items, length, multiple_of, pattern, format, email, url, uuid, int or unique",
+                ))
+            }
+        };
+        self.constraints.push(constraint);
+        Ok(())
+    }
+}
+
+/// Consume a serde attribute this derive does not read, whatever its form:
+/// `flag`, `key = value` or `key(...)`
+fn skip_serde_meta(meta: &ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|nested| skip_serde_meta(&nested))?;
+    }
+    Ok(())
+}
+
+/// serde's `rename_all` rules
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(literal: &LitStr) -> syn::Result<Self> {
+        Ok(match literal.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return Err(syn::Error::new_spanned(literal.to_token_stream(), "unknown rename_all rule")),
+        })
+    }
+
+    /// Rename a `snake_case` field
+    fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => lower_first(&RenameRule::Pascal.apply_to_field(field)),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+
+    /// Rename a `PascalCase` variant
+    fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => lower_first(variant),
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake.apply_to_variant(variant).to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake.apply_to_variant(variant).replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake.apply_to_variant(variant).replace('_', "-"),
+        }
+    }
+}
+
+fn lower_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,109 @@
+// The structs exist for their schemas; most fields are never read
+#![allow(dead_code)]
+
+use fast_schema::{FastSchema, SchemaType, Validator};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn validator<T: FastSchema>() -> Validator {
+    Validator::new(T::schema().unwrap()).unwrap()
+}
+
+#[derive(Debug, Deserialize, FastSchema)]
+#[serde(rename_all = "camelCase")]
+struct Signup {
+    #[schema(min_length = 3, max_length = 20, pattern = "^[a-z0-9_]+$")]
+    user_name: String,
+    #[schema(format = "email")]
+    email: String,
+    #[schema(min = 13, max = 120)]
+    age: Option<u8>,
+    #[serde(default)]
+    #[schema(max_items = 3, unique)]
+    tags: Vec<String>,
+    #[serde(rename = "ref")]
+    referrer: Option<Box<Referrer>>,
+    #[serde(skip)]
+    internal: u32,
+}
+
+#[derive(Debug, Deserialize, FastSchema)]
+#[serde(deny_unknown_fields)]
+struct Referrer {
+    code: Code,
+    r#type: Kind,
+}
+
+#[derive(Debug, Deserialize, FastSchema)]
+struct Code(#[schema(length = 6)] String);
+
+#[derive(Debug, Deserialize, FastSchema)]
+#[serde(rename_all = "snake_case")]
+enum Kind {
+    Friend,
+    SocialMedia,
+    #[serde(rename = "ad")]
+    Advert,
+}
+
+#[test]
+fn test_struct_schema() {
+    let validator = validator::<Signup>();
+    let valid = json!({
+        "userName": "ann_1",
+        "email": "ann@example.com",
+        "age": null,
+        "ref": {"code": "ABC123", "type": "social_media"}
+    });
+    assert!(validator.validate(&valid).success, "{:?}", validator.validate(&valid).errors);
+    assert!(serde_json::from_value::<Signup>(valid).is_ok());
+
+    let invalid = json!({
+        "userName": "A!",
+        "email": "ann",
+        "age": 5,
+        "tags": ["a", "a"],
+        "ref": {"code": "ABC", "type": "ad", "extra": 1}
+    });
+    let mut paths: Vec<_> = validator.validate(&invalid).errors.iter().map(|e| e.path.to_string()).collect();
+    paths.sort();
+    paths.dedup();
+    assert_eq!(paths, ["age", "email", "ref", "ref.code", "ref.extra", "tags.[1]", "userName"]);
+
+    // Required keys follow serde: `default` and `Option` fields may be left out
+    let errors = validator.validate(&json!({})).errors;
+    let mut missing: Vec<_> = errors.iter().map(|e| e.path.to_string()).collect();
+    missing.sort();
+    assert_eq!(missing, ["email", "userName"]);
+}
+
+#[test]
+fn test_enum_and_generic_schemas() {
+    let kind = validator::<Kind>();
+    assert!(kind.validate(&json!("friend")).success);
+    assert!(kind.validate(&json!("ad")).success);
+    assert!(!kind.validate(&json!("Advert")).success);
+
+    #[derive(FastSchema)]
+    struct Page<T> {
+        #[schema(max_items = 2)]
+        items: Vec<T>,
+        counts: HashMap<String, u32>,
+    }
+    let page = validator::<Page<Code>>();
+    assert!(page.validate(&json!({"items": ["ABCDEF"], "counts": {"a": 1}})).success);
+    assert!(!page.validate(&json!({"items": ["ABCDEF", "ABCDEF", "ABCDEF"], "counts": {}})).success);
+    assert!(!page.validate(&json!({"items": [], "counts": {"a": -1}})).success);
+}
+
+#[test]
+fn test_misapplied_constraint() {
+    #[derive(FastSchema)]
+    struct Bad {
+        #[schema(email)]
+            count: u32,
+    }
+    assert!(Bad::schema().is_err());
+    assert!(matches!(<Code as FastSchema>::schema(), Ok(SchemaType::String { .. })));
+}
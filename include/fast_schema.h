@@ -0,0 +1,69 @@
+#ifndef FAST_SCHEMA_H
+#define FAST_SCHEMA_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A compiled schema. Opaque to C; safe to use from several threads at once.
+typedef struct FastSchemaValidator FastSchemaValidator;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Compile a JSON schema. Returns null on failure, storing a message in
+// `*error` (when `error` isn't null) that the caller must free.
+//
+// # Safety
+//
+// `schema_json` must be null or a NUL-terminated string, and `error` null
+// or valid for writes.
+FastSchemaValidator *fast_schema_validator_new(const char *schema_json, char **error);
+
+// Validate a JSON document, returning the `ValidationResult` as JSON.
+// Malformed input is reported as a failed result, so this only returns
+// null if `validator` is null.
+//
+// # Safety
+//
+// `validator` must be null or come from `fast_schema_validator_new` and
+// not yet be freed; `data_json` must be null or a NUL-terminated string.
+char *fast_schema_validate(const FastSchemaValidator *validator, const char *data_json);
+
+// Whether a JSON document is valid, without building a result. Null or
+// malformed input is invalid.
+//
+// # Safety
+//
+// As for `fast_schema_validate`.
+bool fast_schema_is_valid(const FastSchemaValidator *validator, const char *data_json);
+
+// Release a validator. Null is ignored.
+//
+// # Safety
+//
+// `validator` must be null or come from `fast_schema_validator_new`, and
+// must not be used afterwards.
+void fast_schema_validator_free(FastSchemaValidator *validator);
+
+// Release a string returned by this API. Null is ignored.
+//
+// # Safety
+//
+// `string` must be null or a string returned by this API, and must not be
+// used afterwards.
+void fast_schema_string_free(char *string);
+
+// The library version, e.g. `0.1.0`. Static; do not free.
+const char *fast_schema_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FAST_SCHEMA_H */
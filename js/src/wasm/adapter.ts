@@ -5,17 +5,49 @@ import { ValidationError, SafeParseReturnType } from '../base/types';
 // WASM module types (will be generated by wasm-pack)
 export interface WasmValidatorInstance {
   validate(data_json: string): string;
+  validate_value(value: unknown): string;
+  validate_js(value: unknown): WasmValidationResult;
+  validate_result(value: unknown): WasmFastValidationResult;
+  parse(value: unknown): unknown;
+  safeParse(value: unknown): { success: true; data: unknown } | { success: false; error: { name: string; message: string; issues: NonNullable<WasmValidationResult['errors']> } };
   validate_many(data_array_json: string): string;
+  validate_many_js(values: unknown[]): WasmValidationResult[];
+  format_errors(result: WasmValidationResult): Record<string, unknown>;
+  flatten_errors(result: WasmValidationResult): { formErrors: string[]; fieldErrors: Record<string, string[]> };
   validate_with_options(data_json: string, options_json: string): string;
+  validate_async(data_json: string): Promise<WasmValidationResult>;
+  validate_many_async(data_array_json: string, chunk_size: number): Promise<WasmValidationResult[]>;
+  register_async_format(name: string, callback: (value: string) => boolean | Promise<boolean>): void;
   get_schema(): string;
+  update_schema(schema_json: string): void;
+  set_error_map(callback: (issue: { code: string; path: (string | number)[]; params: Record<string, unknown> }) => string | undefined): void;
+  clear_error_map(): void;
   get_stats(): string;
+  get_stats_js(): Record<string, unknown>;
+  reset_stats(): void;
+  get_profile(): string;
+  reset_profile(): void;
   reset_caches(): void;
   get_memory_info(): string;
+  clone(): WasmValidatorInstance;
+  dispose(): void;
+  free(): void;
 }
 
 export interface WasmBatchValidatorInstance {
   validate_dataset(data_array_json: string): string;
+  validate_dataset_with_signal(data_array_json: string, signal: AbortSignal): string;
+  validate_dataset_compact(data_array_json: string, failures_only: boolean): string;
+  validate_dataset_partitioned(data_array_json: string, include_items: boolean): string;
+  partition_dataset(data_array_json: string, workers: number): string;
+  validate_partition(data_array_json: string, offset: number): string;
+  begin_array(): void;
+  feed(items_json: string): string;
+  finish(): string;
   get_batch_stats(): string;
+  reset_batch_stats(): void;
+  set_frame_budget(target_ms?: number): void;
+  get_batch_size(): number;
 }
 
 export interface WasmUltraFastValidatorInstance {
@@ -46,13 +78,44 @@ export interface WasmModule {
 }
 
 // WASM validation result types
+// Accessor classes exported by the WASM module
+interface WasmValidationIssue {
+  readonly path: (string | number)[];
+  readonly pointer: string;
+  readonly code: string;
+  readonly message: string;
+  readonly params: Record<string, unknown>;
+  readonly severity: 'error' | 'warning';
+}
+
+interface WasmFastValidationResult {
+  readonly success: boolean;
+  readonly data: unknown;
+  readonly issues: WasmValidationIssue[];
+  readonly warnings: WasmValidationIssue[];
+  to_object(): WasmValidationResult;
+}
+
 interface WasmValidationResult {
   success: boolean;
   data?: any;
   errors?: Array<{
-    path: string;
+    path: (string | number)[];
+    pointer: string;
+    message: string;
+    code: string;
+    params?: Record<string, unknown>;
+    severity: 'error' | 'warning';
+    location?: { line: number; column: number; start: number; end: number };
+  }>;
+  warnings?: Array<{
+    path: (string | number)[];
+    pointer: string;
     message: string;
     code: string;
+    params?: Record<string, unknown>;
+    severity: 'error' | 'warning';
+    location?: { line: number; column: number; start: number; end: number };
   }>;
   performance?: {
     validation_time_ns: number;
@@ -66,6 +129,14 @@ interface WasmPerformanceStats {
   max_depth: number;
   has_patterns: boolean;
   regex_cache_size: number;
+  regex_cache_hits: number;
+  regex_cache_misses: number;
+  regex_cache_hit_rate: number;
+  validations: number;
+  invalid: number;
+  errors_by_code: Record<string, number>;
+  node_counts: Record<string, number>;
+  throughput: number;
   estimated_validation_time_us?: number;
   recommendations?: string[];
 }
@@ -103,6 +174,13 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
 
   private async loadWasmModule(): Promise<WasmModule | null> {
     try {
+      // Prefer the native addon under Node.js when it's installed
+      const { NativeLoader } = await import('./native-loader');
+      const native = NativeLoader.load();
+      if (native) {
+        return native;
+      }
+
       // Check if we're in Node.js environment first
       const { NodeWasmLoader } = await import('./node-loader');
 
@@ -120,6 +198,30 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
                 // Call WASM functions through wasmInstance.exports
                 return JSON.stringify({ success: true, data: JSON.parse(data_json) });
               }
+              validate_value(value: unknown): string {
+                return JSON.stringify({ success: true, data: value });
+              }
+              validate_js(value: unknown): WasmValidationResult {
+                return { success: true, data: value };
+              }
+              validate_result(value: unknown): WasmFastValidationResult {
+                return { success: true, data: value, issues: [], warnings: [], to_object: () => ({ success: true, data: value }) };
+              }
+              parse(value: unknown): unknown {
+                return value;
+              }
+              safeParse(value: unknown): { success: true; data: unknown } {
+                return { success: true, data: value };
+              }
+              validate_many_js(values: unknown[]): WasmValidationResult[] {
+                return values.map(value => ({ success: true, data: value }));
+              }
+              format_errors(result: WasmValidationResult): Record<string, unknown> {
+                return { _errors: [] };
+              }
+              flatten_errors(result: WasmValidationResult): { formErrors: string[]; fieldErrors: Record<string, string[]> } {
+                return { formErrors: [], fieldErrors: {} };
+              }
               validate_many(data_array_json: string): string {
                 const dataArray = JSON.parse(data_array_json);
                 const results = dataArray.map((item: any) => ({ success: true, data: item }));
@@ -128,19 +230,62 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
               validate_with_options(data_json: string, options_json: string): string {
                 return JSON.stringify({ success: true, data: JSON.parse(data_json) });
               }
+              async validate_async(data_json: string): Promise<WasmValidationResult> {
+                return { success: true, data: JSON.parse(data_json) };
+              }
+              async validate_many_async(data_array_json: string, chunk_size: number): Promise<WasmValidationResult[]> {
+                return JSON.parse(data_array_json).map((item: unknown) => ({ success: true, data: item }));
+              }
+              register_async_format(name: string, callback: (value: string) => boolean | Promise<boolean>): void {}
               get_schema(): string { return "{}"; }
+              update_schema(schema_json: string): void {}
+              set_error_map(callback: (issue: { code: string; path: (string | number)[]; params: Record<string, unknown> }) => string | undefined): void {}
+              clear_error_map(): void {}
               get_stats(): string { return "{}"; }
+              get_stats_js(): Record<string, unknown> { return {}; }
+              reset_stats(): void {}
+              get_profile(): string { return "null"; }
+              reset_profile(): void {}
               reset_caches(): void {}
               get_memory_info(): string { return "{}"; }
+              clone(): WasmValidatorInstance { return this; }
+              dispose(): void {}
+              free(): void {}
             },
             FastBatchValidator: class {
-              constructor(schema_json: string, batch_size: number) {}
+              constructor(schema_json: string, private batch_size: number) {}
               validate_dataset(data_array_json: string): string {
                 const dataArray = JSON.parse(data_array_json);
                 const results = dataArray.map((item: any) => ({ success: true, data: item }));
                 return JSON.stringify(results);
               }
+              validate_dataset_with_signal(data_array_json: string, signal: AbortSignal): string {
+                return JSON.stringify({ results: JSON.parse(this.validate_dataset(data_array_json)), cancelled: false });
+              }
+              validate_dataset_compact(data_array_json: string, failures_only: boolean): string {
+                const total_items = JSON.parse(data_array_json).length;
+                const valid_bitset = new Array(Math.ceil(total_items / 32)).fill(0xffffffff);
+                return JSON.stringify(failures_only ? { total_items, failed: [] } : { total_items, valid_bitset, failed: [] });
+              }
+              validate_dataset_partitioned(data_array_json: string, include_items: boolean): string {
+                const items = JSON.parse(data_array_json);
+                const valid = include_items ? items : items.map((_: unknown, index: number) => index);
+                const stats = { total_items: items.length, valid_items: items.length, invalid_items: 0, total_errors: 0, validation_time_ms: 0, throughput: 0 };
+                return JSON.stringify({ valid, invalid: [], stats });
+              }
+              partition_dataset(data_array_json: string, workers: number): string {
+                return JSON.stringify([{ offset: 0, data: JSON.parse(data_array_json) }]);
+              }
+              validate_partition(data_array_json: string, offset: number): string {
+                return this.validate_dataset(data_array_json);
+              }
+              begin_array(): void {}
+              feed(items_json: string): string { return "[]"; }
+              finish(): string { return "{}"; }
               get_batch_stats(): string { return "{}"; }
+              reset_batch_stats(): void {}
+              set_frame_budget(target_ms?: number): void {}
+              get_batch_size(): number { return this.batch_size; }
             },
             UltraFastValidator: class {
               constructor(validator_type: string, config: string) {}
@@ -246,16 +391,14 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
       throw new Error('WASM validator not initialized');
     }
 
-    const dataJson = JSON.stringify(data);
-    const resultJson = this.wasmValidator.validate(dataJson);
-    const result: WasmValidationResult = JSON.parse(resultJson);
+    const result = this.wasmValidator.validate_js(data);
 
     if (result.success) {
       return result.data as T;
     } else {
       const issues = (result.errors || []).map(error => ({
         code: error.code,
-        path: error.path.split('.').filter(p => p !== ''),
+        path: error.path,
         message: error.message
       }));
       throw new ValidationError(issues);
@@ -266,16 +409,14 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
   safeParse(data: unknown): SafeParseReturnType<unknown, T> {
     if (this.useWasm && this.wasmValidator) {
       try {
-        const dataJson = JSON.stringify(data);
-        const resultJson = this.wasmValidator.validate(dataJson);
-        const result: WasmValidationResult = JSON.parse(resultJson);
+        const result = this.wasmValidator.validate_js(data);
 
         if (result.success) {
           return { success: true, data: result.data as T };
         } else {
           const issues = (result.errors || []).map(error => ({
             code: error.code,
-            path: error.path.split('.').filter(p => p !== ''),
+            path: error.path,
             message: error.message
           }));
           return { success: false, error: new ValidationError(issues) };
@@ -288,6 +429,38 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
     return this.fallbackSchema.safeParse(data);
   }
 
+  // Async parsing yields to the event loop and awaits async formats
+  async safeParseAsync(data: unknown): Promise<SafeParseReturnType<unknown, T>> {
+    if (this.useWasm && this.wasmValidator) {
+      try {
+        const result = await this.wasmValidator.validate_async(JSON.stringify(data));
+
+        if (result.success) {
+          return { success: true, data: result.data as T };
+        } else {
+          const issues = (result.errors || []).map(error => ({
+            code: error.code,
+            path: error.path,
+            message: error.message
+          }));
+          return { success: false, error: new ValidationError(issues) };
+        }
+      } catch (error) {
+        console.warn('WASM safeParseAsync failed, falling back to TypeScript:', error);
+      }
+    }
+
+    return this.fallbackSchema.safeParseAsync(data);
+  }
+
+  async parseAsync(data: unknown): Promise<T> {
+    const result = await this.safeParseAsync(data);
+    if (!result.success) {
+      throw result.error;
+    }
+    return result.data;
+  }
+
   // Batch validation with WASM optimization
   validateMany(dataArray: unknown[]): Array<SafeParseReturnType<unknown, T>> {
     if (this.useWasm && this.wasmValidator) {
@@ -302,7 +475,7 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
           } else {
             const issues = (result.errors || []).map(error => ({
               code: error.code,
-              path: error.path.split('.').filter(p => p !== ''),
+              path: error.path,
               message: error.message
             }));
             return { success: false, error: new ValidationError(issues) };
@@ -349,6 +522,13 @@ export class WasmSchemaAdapter<T> extends Schema<T> {
     return null;
   }
 
+  // Free the WASM validator; validation falls back to TypeScript afterwards
+  dispose(): void {
+    this.wasmValidator?.free();
+    this.wasmValidator = undefined;
+    this.useWasm = false;
+  }
+
   // Check if WASM is being used
   isUsingWasm(): boolean {
     return this.useWasm;
@@ -406,6 +586,13 @@ export class WasmBatchProcessor<T> {
 
   private async loadWasmModule(): Promise<WasmModule | null> {
     try {
+      // Prefer the native addon under Node.js when it's installed
+      const { NativeLoader } = await import('./native-loader');
+      const native = NativeLoader.load();
+      if (native) {
+        return native;
+      }
+
       // Check if we're in Node.js environment first
       const { NodeWasmLoader } = await import('./node-loader');
 
@@ -419,6 +606,30 @@ export class WasmBatchProcessor<T> {
               validate(data_json: string): string {
                 return JSON.stringify({ success: true, data: JSON.parse(data_json) });
               }
+              validate_value(value: unknown): string {
+                return JSON.stringify({ success: true, data: value });
+              }
+              validate_js(value: unknown): WasmValidationResult {
+                return { success: true, data: value };
+              }
+              validate_result(value: unknown): WasmFastValidationResult {
+                return { success: true, data: value, issues: [], warnings: [], to_object: () => ({ success: true, data: value }) };
+              }
+              parse(value: unknown): unknown {
+                return value;
+              }
+              safeParse(value: unknown): { success: true; data: unknown } {
+                return { success: true, data: value };
+              }
+              validate_many_js(values: unknown[]): WasmValidationResult[] {
+                return values.map(value => ({ success: true, data: value }));
+              }
+              format_errors(result: WasmValidationResult): Record<string, unknown> {
+                return { _errors: [] };
+              }
+              flatten_errors(result: WasmValidationResult): { formErrors: string[]; fieldErrors: Record<string, string[]> } {
+                return { formErrors: [], fieldErrors: {} };
+              }
               validate_many(data_array_json: string): string {
                 const dataArray = JSON.parse(data_array_json);
                 const results = dataArray.map((item: any) => ({ success: true, data: item }));
@@ -427,19 +638,62 @@ export class WasmBatchProcessor<T> {
               validate_with_options(data_json: string, options_json: string): string {
                 return JSON.stringify({ success: true, data: JSON.parse(data_json) });
               }
+              async validate_async(data_json: string): Promise<WasmValidationResult> {
+                return { success: true, data: JSON.parse(data_json) };
+              }
+              async validate_many_async(data_array_json: string, chunk_size: number): Promise<WasmValidationResult[]> {
+                return JSON.parse(data_array_json).map((item: unknown) => ({ success: true, data: item }));
+              }
+              register_async_format(name: string, callback: (value: string) => boolean | Promise<boolean>): void {}
               get_schema(): string { return "{}"; }
+              update_schema(schema_json: string): void {}
+              set_error_map(callback: (issue: { code: string; path: (string | number)[]; params: Record<string, unknown> }) => string | undefined): void {}
+              clear_error_map(): void {}
               get_stats(): string { return "{}"; }
+              get_stats_js(): Record<string, unknown> { return {}; }
+              reset_stats(): void {}
+              get_profile(): string { return "null"; }
+              reset_profile(): void {}
               reset_caches(): void {}
               get_memory_info(): string { return "{}"; }
+              clone(): WasmValidatorInstance { return this; }
+              dispose(): void {}
+              free(): void {}
             },
             FastBatchValidator: class {
-              constructor(schema_json: string, batch_size: number) {}
+              constructor(schema_json: string, private batch_size: number) {}
               validate_dataset(data_array_json: string): string {
                 const dataArray = JSON.parse(data_array_json);
                 const results = dataArray.map((item: any) => ({ success: true, data: item }));
                 return JSON.stringify(results);
               }
+              validate_dataset_with_signal(data_array_json: string, signal: AbortSignal): string {
+                return JSON.stringify({ results: JSON.parse(this.validate_dataset(data_array_json)), cancelled: false });
+              }
+              validate_dataset_compact(data_array_json: string, failures_only: boolean): string {
+                const total_items = JSON.parse(data_array_json).length;
+                const valid_bitset = new Array(Math.ceil(total_items / 32)).fill(0xffffffff);
+                return JSON.stringify(failures_only ? { total_items, failed: [] } : { total_items, valid_bitset, failed: [] });
+              }
+              validate_dataset_partitioned(data_array_json: string, include_items: boolean): string {
+                const items = JSON.parse(data_array_json);
+                const valid = include_items ? items : items.map((_: unknown, index: number) => index);
+                const stats = { total_items: items.length, valid_items: items.length, invalid_items: 0, total_errors: 0, validation_time_ms: 0, throughput: 0 };
+                return JSON.stringify({ valid, invalid: [], stats });
+              }
+              partition_dataset(data_array_json: string, workers: number): string {
+                return JSON.stringify([{ offset: 0, data: JSON.parse(data_array_json) }]);
+              }
+              validate_partition(data_array_json: string, offset: number): string {
+                return this.validate_dataset(data_array_json);
+              }
+              begin_array(): void {}
+              feed(items_json: string): string { return "[]"; }
+              finish(): string { return "{}"; }
               get_batch_stats(): string { return "{}"; }
+              reset_batch_stats(): void {}
+              set_frame_budget(target_ms?: number): void {}
+              get_batch_size(): number { return this.batch_size; }
             },
             UltraFastValidator: class {
               constructor(validator_type: string, config: string) {}
@@ -504,7 +758,7 @@ export class WasmBatchProcessor<T> {
           } else {
             const issues = (result.errors || []).map(error => ({
               code: error.code,
-              path: error.path.split('.').filter(p => p !== ''),
+              path: error.path,
               message: error.message
             }));
             return { success: false, error: new ValidationError(issues) };
@@ -576,6 +830,30 @@ export class WasmUltraFastValidator {
               validate(data_json: string): string {
                 return JSON.stringify({ success: true, data: JSON.parse(data_json) });
               }
+              validate_value(value: unknown): string {
+                return JSON.stringify({ success: true, data: value });
+              }
+              validate_js(value: unknown): WasmValidationResult {
+                return { success: true, data: value };
+              }
+              validate_result(value: unknown): WasmFastValidationResult {
+                return { success: true, data: value, issues: [], warnings: [], to_object: () => ({ success: true, data: value }) };
+              }
+              parse(value: unknown): unknown {
+                return value;
+              }
+              safeParse(value: unknown): { success: true; data: unknown } {
+                return { success: true, data: value };
+              }
+              validate_many_js(values: unknown[]): WasmValidationResult[] {
+                return values.map(value => ({ success: true, data: value }));
+              }
+              format_errors(result: WasmValidationResult): Record<string, unknown> {
+                return { _errors: [] };
+              }
+              flatten_errors(result: WasmValidationResult): { formErrors: string[]; fieldErrors: Record<string, string[]> } {
+                return { formErrors: [], fieldErrors: {} };
+              }
               validate_many(data_array_json: string): string {
                 const dataArray = JSON.parse(data_array_json);
                 const results = dataArray.map((item: any) => ({ success: true, data: item }));
@@ -584,19 +862,62 @@ export class WasmUltraFastValidator {
               validate_with_options(data_json: string, options_json: string): string {
                 return JSON.stringify({ success: true, data: JSON.parse(data_json) });
               }
+              async validate_async(data_json: string): Promise<WasmValidationResult> {
+                return { success: true, data: JSON.parse(data_json) };
+              }
+              async validate_many_async(data_array_json: string, chunk_size: number): Promise<WasmValidationResult[]> {
+                return JSON.parse(data_array_json).map((item: unknown) => ({ success: true, data: item }));
+              }
+              register_async_format(name: string, callback: (value: string) => boolean | Promise<boolean>): void {}
               get_schema(): string { return "{}"; }
+              update_schema(schema_json: string): void {}
+              set_error_map(callback: (issue: { code: string; path: (string | number)[]; params: Record<string, unknown> }) => string | undefined): void {}
+              clear_error_map(): void {}
               get_stats(): string { return "{}"; }
+              get_stats_js(): Record<string, unknown> { return {}; }
+              reset_stats(): void {}
+              get_profile(): string { return "null"; }
+              reset_profile(): void {}
               reset_caches(): void {}
               get_memory_info(): string { return "{}"; }
+              clone(): WasmValidatorInstance { return this; }
+              dispose(): void {}
+              free(): void {}
             },
             FastBatchValidator: class {
-              constructor(schema_json: string, batch_size: number) {}
+              constructor(schema_json: string, private batch_size: number) {}
               validate_dataset(data_array_json: string): string {
                 const dataArray = JSON.parse(data_array_json);
                 const results = dataArray.map((item: any) => ({ success: true, data: item }));
                 return JSON.stringify(results);
               }
+              validate_dataset_with_signal(data_array_json: string, signal: AbortSignal): string {
+                return JSON.stringify({ results: JSON.parse(this.validate_dataset(data_array_json)), cancelled: false });
+              }
+              validate_dataset_compact(data_array_json: string, failures_only: boolean): string {
+                const total_items = JSON.parse(data_array_json).length;
+                const valid_bitset = new Array(Math.ceil(total_items / 32)).fill(0xffffffff);
+                return JSON.stringify(failures_only ? { total_items, failed: [] } : { total_items, valid_bitset, failed: [] });
+              }
+              validate_dataset_partitioned(data_array_json: string, include_items: boolean): string {
+                const items = JSON.parse(data_array_json);
+                const valid = include_items ? items : items.map((_: unknown, index: number) => index);
+                const stats = { total_items: items.length, valid_items: items.length, invalid_items: 0, total_errors: 0, validation_time_ms: 0, throughput: 0 };
+                return JSON.stringify({ valid, invalid: [], stats });
+              }
+              partition_dataset(data_array_json: string, workers: number): string {
+                return JSON.stringify([{ offset: 0, data: JSON.parse(data_array_json) }]);
+              }
+              validate_partition(data_array_json: string, offset: number): string {
+                return this.validate_dataset(data_array_json);
+              }
+              begin_array(): void {}
+              feed(items_json: string): string { return "[]"; }
+              finish(): string { return "{}"; }
               get_batch_stats(): string { return "{}"; }
+              reset_batch_stats(): void {}
+              set_frame_budget(target_ms?: number): void {}
+              get_batch_size(): number { return this.batch_size; }
             },
             UltraFastValidator: class {
               constructor(validator_type: string, config: string) {}
@@ -0,0 +1,131 @@
+// Native (napi-rs) backend loader - see napi/ for the addon.
+// The addon mirrors the WASM package's FastValidator, FastBatchValidator and
+// FastSchemaUtils, so it can stand in for WASM under Node.js. Methods that
+// need JS callbacks or promises are filled in here rather than in Rust.
+import type { WasmModule } from './adapter';
+
+type AnyClass = new (...args: any[]) => any;
+
+interface NativeAddon {
+  FastValidator: AnyClass;
+  FastBatchValidator: AnyClass;
+  FastSchemaUtils: Record<string, (...args: any[]) => any>;
+}
+
+const unsupported = (method: string) => () => {
+  throw new Error(`${method} is not supported by the native backend`);
+};
+
+function validationError(result: any) {
+  return {
+    name: 'FastSchemaError',
+    message: result.errors?.[0]?.message ?? 'Validation failed',
+    issues: result.errors ?? []
+  };
+}
+
+function wrapAddon(addon: NativeAddon): WasmModule {
+  class FastValidator extends addon.FastValidator {
+    validate_result(value: unknown) {
+      const result = this.validate_js(value);
+      return {
+        success: result.success,
+        data: result.data,
+        issues: result.errors ?? [],
+        warnings: result.warnings ?? [],
+        to_object: () => result
+      };
+    }
+
+    parse(value: unknown): unknown {
+      const result = this.validate_js(value);
+      if (!result.success) {
+        throw Object.assign(new Error(), validationError(result));
+      }
+      return result.data;
+    }
+
+    safeParse(value: unknown) {
+      const result = this.validate_js(value);
+      return result.success
+        ? { success: true as const, data: result.data }
+        : { success: false as const, error: validationError(result) };
+    }
+
+    async validate_async(data_json: string) {
+      return JSON.parse(this.validate(data_json));
+    }
+
+    async validate_many_async(data_array_json: string, _chunk_size: number) {
+      return JSON.parse(this.validate_many(data_array_json));
+    }
+
+    register_async_format = unsupported('register_async_format');
+    set_error_map = unsupported('set_error_map');
+    clear_error_map() {}
+
+    free() {
+      this.dispose();
+    }
+  }
+
+  class FastBatchValidator extends addon.FastBatchValidator {
+    validate_dataset_with_signal(data_array_json: string, signal: AbortSignal): string {
+      if (signal.aborted) {
+        throw new Error('Validation aborted');
+      }
+      return this.validate_dataset(data_array_json);
+    }
+
+    partition_dataset = unsupported('partition_dataset');
+    begin_array = unsupported('begin_array');
+    feed = unsupported('feed');
+    finish = unsupported('finish');
+  }
+
+  class UltraFastValidator {
+    constructor() {
+      unsupported('UltraFastValidator')();
+    }
+  }
+
+  return {
+    FastValidator,
+    FastBatchValidator,
+    UltraFastValidator,
+    FastSchemaUtils: {
+      validate_schema: addon.FastSchemaUtils.validate_schema,
+      get_version: addon.FastSchemaUtils.get_version,
+      analyze_schema_performance: unsupported('analyze_schema_performance')
+    }
+  } as unknown as WasmModule;
+}
+
+export class NativeLoader {
+  private static module: WasmModule | null | undefined;
+
+  /**
+   * The native addon as a WasmModule, or null if it isn't installed.
+   * Set FAST_SCHEMA_BACKEND=wasm to skip it.
+   */
+  static load(): WasmModule | null {
+    if (this.module !== undefined) {
+      return this.module;
+    }
+    this.module = null;
+    if (typeof process === 'undefined' || !process.versions?.node || process.env.FAST_SCHEMA_BACKEND === 'wasm') {
+      return null;
+    }
+
+    for (const path of ['fast-schema-native', '../../../napi']) {
+      try {
+        // eslint-disable-next-line @typescript-eslint/no-var-requires
+        this.module = wrapAddon(require(path));
+        break;
+      } catch {
+        continue;
+      }
+    }
+    return this.module;
+  }
+}
@@ -0,0 +1,404 @@
+
+//! Native Node.js addon with the shape of the WASM package's `FastValidator`,
+//! `FastBatchValidator` and `FastSchemaUtils`, so the JS adapter can use
+//! either. Besides JSON strings it takes `Buffer`s and plain JS values,
+//! which skip the string copies WASM needs.
+//!
+//! Method names keep the WASM package's snake_case rather than napi's
+//! default camelCase.
+
+use fast_schema::{
+    validate_schema_document, BatchValidator, CompiledSchema, ErrorCode, FormatRegistry, SchemaType, Severity, ValidationError,
+    ValidationOptions, ValidationResult, Validator,
+};
+use std::collections::HashMap;
+use napi::bindgen_prelude::{Buffer, Float64Array, Uint8Array};
+use napi::{Error, Result};
+use napi_derive::napi;
+use std::sync::Arc;
+
+fn error_result(message: &str) -> String {
+    let result = ValidationResult::failure(vec![ValidationError::new(
+        String::new(),
+        message.to_string(),
+        ErrorCode::InternalError,
+    )]);
+    serde_json::to_string(&result).unwrap_or_default()
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| error_result(&format!("Serialization failed: {}", e)))
+}
+
+fn to_json_array<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|e| format!("[{}]", error_result(&format!("Serialization failed: {}", e))))
+}
+
+fn reason(message: String) -> Error {
+    Error::from_reason(message)
+}
+
+fn result_from_js(result: serde_json::Value) -> Result<ValidationResult> {
+    serde_json::from_value(result).map_err(|e| reason(format!("Invalid validation result: {}", e)))
+}
+
+#[napi]
+pub struct FastValidator {
+    validator: Arc<Validator>,
+    schema_json: String,
+}
+
+#[napi]
+impl FastValidator {
+    /// Custom string formats the schema uses are given as `{name: pattern}`;
+    /// any other unknown format fails to compile
+    #[napi(constructor)]
+    pub fn new(schema_json: String, formats: Option<HashMap<String, String>>) -> Result<Self> {
+        let schema: serde_json::Value =
+            serde_json::from_str(&schema_json).map_err(|e| reason(format!("Invalid schema JSON: {}", e)))?;
+        let mut registry = FormatRegistry::new();
+        for (name, pattern) in formats.unwrap_or_default() {
+            registry.register_pattern(&name, &pattern).map_err(|e| reason(format!("Invalid format pattern: {}", e)))?;
+        }
+        let validator = Validator::from_json_with_formats(&schema, Arc::new(registry))
+            .map_err(|e| reason(format!("Schema compilation failed: {}", e)))?;
+        Ok(Self { validator: Arc::new(validator), schema_json })
+    }
+
+    /// Validate a JSON string, returning the result as JSON
+    #[napi(js_name = "validate")]
+    pub fn validate(&self, data_json: String) -> String {
+        match serde_json::from_str(&data_json) {
+            Ok(data) => to_json(&self.validator.validate(&data)),
+            Err(e) => error_result(&format!("Invalid JSON data: {}", e)),
+        }
+    }
+
+    /// Validate UTF-8 JSON in a `Buffer` (e.g. a request body) without
+    /// decoding it to a JS string first
+    #[napi(js_name = "validate_buffer")]
+    pub fn validate_buffer(&self, data: Buffer) -> String {
+        match serde_json::from_slice(&data) {
+            Ok(data) => to_json(&self.validator.validate(&data)),
+            Err(e) => error_result(&format!("Invalid JSON data: {}", e)),
+        }
+    }
+
+    /// Validate a MessagePack payload in a `Buffer`, without a JSON round-trip
+    #[napi(js_name = "validate_msgpack")]
+    pub fn validate_msgpack(&self, data: Buffer) -> String {
+        match self.validator.validate_msgpack(&data) {
+            Ok(result) => to_json(&result),
+            Err(e) => error_result(&e.to_string()),
+        }
+    }
+
+    /// Validate a CBOR payload in a `Buffer`, without a JSON round-trip
+    #[napi(js_name = "validate_cbor")]
+    pub fn validate_cbor(&self, data: Buffer) -> String {
+        match self.validator.validate_cbor(&data) {
+            Ok(result) => to_json(&result),
+            Err(e) => error_result(&e.to_string()),
+        }
+    }
+
+    /// Validate a JS value directly, without a JSON string round-trip
+    #[napi(js_name = "validate_value")]
+    pub fn validate_value(&self, value: serde_json::Value) -> String {
+        to_json(&self.validator.validate(&value))
+    }
+
+    /// Validate a JS value, returning the result as a JS object
+    #[napi(js_name = "validate_js")]
+    pub fn validate_js(&self, value: serde_json::Value) -> Result<serde_json::Value> {
+        serde_json::to_value(self.validator.validate(&value)).map_err(|e| reason(e.to_string()))
+    }
+
+    #[napi(js_name = "validate_many")]
+    pub fn validate_many(&self, data_array_json: String) -> String {
+        match serde_json::from_str::<Vec<serde_json::Value>>(&data_array_json) {
+            Ok(data) => to_json_array(&self.validator.validate_many(&data)),
+            Err(e) => format!("[{}]", error_result(&format!("Invalid JSON array: {}", e))),
+        }
+    }
+
+    /// Validate with options as JSON; invalid options fall back to the
+    /// defaults, as in the WASM package
+    #[napi(js_name = "validate_with_options")]
+    pub fn validate_with_options(&self, data_json: String, options_json: String) -> String {
+        let options: ValidationOptions = serde_json::from_str(&options_json).unwrap_or_default();
+        match serde_json::from_str(&data_json) {
+            Ok(data) => to_json(&self.validator.validate_with_options(&data, options)),
+            Err(e) => error_result(&format!("Invalid JSON data: {}", e)),
+        }
+    }
+
+    /// Errors of a result object as a tree keyed by property, like Zod's
+    /// `error.format()`
+    #[napi(js_name = "format_errors")]
+    pub fn format_errors(&self, result: serde_json::Value) -> Result<serde_json::Value> {
+        Ok(result_from_js(result)?.format())
+    }
+
+    /// Errors of a result object as `{formErrors, fieldErrors}`
+    #[napi(js_name = "flatten_errors")]
+    pub fn flatten_errors(&self, result: serde_json::Value) -> Result<serde_json::Value> {
+        serde_json::to_value(result_from_js(result)?.flatten()).map_err(|e| reason(e.to_string()))
+    }
+
+    #[napi(js_name = "validate_many_js")]
+    pub fn validate_many_js(&self, values: Vec<serde_json::Value>) -> Result<serde_json::Value> {
+        serde_json::to_value(self.validator.validate_many(&values)).map_err(|e| reason(e.to_string()))
+    }
+
+    #[napi(js_name = "get_schema")]
+    pub fn get_schema(&self) -> String {
+        self.schema_json.clone()
+    }
+
+    /// Register a custom string format matched by a regex
+    #[napi(js_name = "register_format")]
+    pub fn register_format(&mut self, name: String, pattern: String) -> Result<()> {
+        Arc::make_mut(&mut self.validator)
+            .register_format_pattern(&name, &pattern)
+            .map_err(|e| reason(format!("Invalid format pattern: {}", e)))
+    }
+
+    #[napi(js_name = "get_metadata")]
+    pub fn get_metadata(&self, path: String) -> String {
+        match self.validator.get_metadata(&path) {
+            Some(metadata) => serde_json::to_string(metadata).unwrap_or_else(|_| "null".to_string()),
+            None => "null".to_string(),
+        }
+    }
+
+    /// Replace the schema in place, keeping custom formats; on error the
+    /// current schema stays
+    #[napi(js_name = "update_schema")]
+    pub fn update_schema(&mut self, schema_json: String) -> Result<()> {
+        let schema: serde_json::Value =
+            serde_json::from_str(&schema_json).map_err(|e| reason(format!("Invalid schema JSON: {}", e)))?;
+        let validator = self.validator.recompile(&schema).map_err(|e| reason(format!("Schema compilation failed: {}", e)))?;
+        self.validator = Arc::new(validator);
+        self.schema_json = schema_json;
+        Ok(())
+    }
+
+    #[napi(js_name = "get_stats")]
+    pub fn get_stats(&self) -> String {
+        serde_json::to_string(&self.validator.stats()).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    #[napi(js_name = "get_stats_js")]
+    pub fn get_stats_js(&self) -> Result<serde_json::Value> {
+        serde_json::to_value(self.validator.stats()).map_err(|e| reason(e.to_string()))
+    }
+
+    #[napi(js_name = "reset_stats")]
+    pub fn reset_stats(&self) {
+        self.validator.reset_stats();
+    }
+
+    #[napi(js_name = "get_profile")]
+    pub fn get_profile(&self) -> String {
+        serde_json::to_string(&self.validator.get_profile()).unwrap_or_else(|_| "null".to_string())
+    }
+
+    #[napi(js_name = "reset_profile")]
+    pub fn reset_profile(&self) {
+        self.validator.reset_profile();
+    }
+
+    /// Drop this validator's patterns from the shared regex cache
+    #[napi(js_name = "reset_caches")]
+    pub fn reset_caches(&self) {
+        self.validator.clear_cache();
+    }
+
+    /// `linear_memory_bytes` is always null, as there is no WASM memory
+    #[napi(js_name = "get_memory_info")]
+    pub fn get_memory_info(&self) -> String {
+        serde_json::json!({
+            "regex_cache_size": fast_schema::regex_cache_len(),
+            "regex_cache_capacity": fast_schema::regex_cache_capacity(),
+            "estimated_complexity": self.validator.compiled_schema.estimated_complexity,
+            "max_depth": self.validator.compiled_schema.max_depth,
+            "schema_bytes": self.schema_json.len(),
+            "linear_memory_bytes": null
+        })
+        .to_string()
+    }
+
+    /// The compiled schema in binary form; readable by the WASM package's
+    /// `from_bytes` of the same version too
+    #[napi(js_name = "to_bytes")]
+    pub fn to_bytes(&self) -> Result<Buffer> {
+        self.validator.compiled_schema.to_bytes().map(Buffer::from).map_err(|e| reason(e.to_string()))
+    }
+
+    #[napi(factory, js_name = "from_bytes")]
+    pub fn from_bytes(bytes: Buffer) -> Result<Self> {
+        let compiled = CompiledSchema::from_bytes(&bytes).map_err(|e| reason(e.to_string()))?;
+        let schema_json = serde_json::to_string(&compiled.schema).map_err(|e| reason(e.to_string()))?;
+        Ok(Self { validator: Arc::new(Validator::from_compiled(compiled)), schema_json })
+    }
+
+    /// An independent copy sharing the compiled schema
+    #[napi(js_name = "clone")]
+    pub fn clone_instance(&self) -> Self {
+        Self { validator: Arc::clone(&self.validator), schema_json: self.schema_json.clone() }
+    }
+
+    /// Kept for API parity; native memory is released by the garbage
+    /// collector
+    #[napi(js_name = "dispose")]
+    pub fn dispose(&self) {}
+}
+
+#[napi]
+pub struct FastBatchValidator {
+    validator: Arc<BatchValidator>,
+}
+
+#[napi]
+impl FastBatchValidator {
+    #[napi(constructor)]
+    pub fn new(schema_json: String, batch_size: u32) -> Result<Self> {
+        let schema: SchemaType =
+            serde_json::from_str(&schema_json).map_err(|e| reason(format!("Invalid schema JSON: {}", e)))?;
+        let validator = BatchValidator::new(schema, batch_size as usize)
+            .map_err(|e| reason(format!("Batch validator creation failed: {}", e)))?;
+        Ok(Self { validator: Arc::new(validator) })
+    }
+
+    #[napi(js_name = "set_frame_budget")]
+    pub fn set_frame_budget(&mut self, target_ms: Option<f64>) {
+        Arc::make_mut(&mut self.validator).set_frame_budget(target_ms);
+    }
+
+    /// 1 for each valid number in a `Float64Array`
+    #[napi(js_name = "validate_f64_array")]
+    pub fn validate_f64_array(&self, values: Float64Array) -> Uint8Array {
+        Uint8Array::new(self.validator.validate_numbers(&values))
+    }
+
+    #[napi(js_name = "get_batch_size")]
+    pub fn get_batch_size(&self) -> u32 {
+        self.validator.batch_size() as u32
+    }
+
+    #[napi(js_name = "validate_dataset")]
+    pub fn validate_dataset(&self, data_array_json: String) -> String {
+        self.with_dataset(data_array_json.as_bytes(), |values| to_json_array(&self.validator.validate_dataset(values)))
+            .unwrap_or_else(|e| format!("[{}]", error_result(&e)))
+    }
+
+    /// `validate_dataset` for a JSON array in a `Buffer`
+    #[napi(js_name = "validate_dataset_buffer")]
+    pub fn validate_dataset_buffer(&self, data: Buffer) -> String {
+        self.with_dataset(&data, |values| to_json_array(&self.validator.validate_dataset(values)))
+            .unwrap_or_else(|e| format!("[{}]", error_result(&e)))
+    }
+
+    #[napi(js_name = "validate_dataset_compact")]
+    pub fn validate_dataset_compact(&self, data_array_json: String, failures_only: bool) -> String {
+        self.with_dataset(data_array_json.as_bytes(), |values| {
+            to_json(&self.validator.validate_dataset_compact(values, failures_only))
+        })
+        .unwrap_or_else(|e| error_result(&e))
+    }
+
+    #[napi(js_name = "validate_dataset_partitioned")]
+    pub fn validate_dataset_partitioned(&self, data_array_json: String, include_items: bool) -> String {
+        self.with_dataset(data_array_json.as_bytes(), |values| {
+            to_json(&self.validator.validate_dataset_partitioned(values, include_items))
+        })
+        .unwrap_or_else(|e| error_result(&e))
+    }
+
+    #[napi(js_name = "validate_partition")]
+    pub fn validate_partition(&self, data_array_json: String, offset: u32) -> String {
+        self.with_dataset(data_array_json.as_bytes(), |values| {
+            to_json_array(&self.validator.validate_partition(offset as usize, values))
+        })
+        .unwrap_or_else(|e| format!("[{}]", error_result(&e)))
+    }
+
+    #[napi(js_name = "clone")]
+    pub fn clone_instance(&self) -> Self {
+        Self { validator: Arc::clone(&self.validator) }
+    }
+
+    #[napi(js_name = "dispose")]
+    pub fn dispose(&self) {}
+
+    #[napi(js_name = "get_batch_stats")]
+    pub fn get_batch_stats(&self) -> String {
+        serde_json::to_string(&self.validator.get_stats()).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    #[napi(js_name = "reset_batch_stats")]
+    pub fn reset_batch_stats(&self) {
+        self.validator.reset_stats();
+    }
+
+    fn with_dataset(&self, json: &[u8], run: impl FnOnce(&[serde_json::Value]) -> String) -> std::result::Result<String, String> {
+        let values: Vec<serde_json::Value> =
+            serde_json::from_slice(json).map_err(|e| format!("Invalid JSON array: {}", e))?;
+        Ok(run(&values))
+    }
+}
+
+#[napi]
+pub struct FastSchemaUtils {}
+
+#[napi]
+impl FastSchemaUtils {
+    /// `{valid, message}` for a schema definition
+    #[napi(js_name = "validate_schema")]
+    pub fn validate_schema(schema_json: String) -> String {
+        let result = match serde_json::from_str::<SchemaType>(&schema_json) {
+            Ok(schema) => match Validator::new(schema) {
+                Ok(_) => serde_json::json!({"valid": true, "message": "Schema is valid"}),
+                Err(e) => serde_json::json!({"valid": false, "message": format!("Schema compilation failed: {}", e)}),
+            },
+            Err(e) => serde_json::json!({"valid": false, "message": format!("Invalid schema JSON: {}", e)}),
+        };
+        result.to_string()
+    }
+
+    /// `{valid, errors}` for a schema document checked against the
+    /// bundled meta-schema
+    #[napi(js_name = "validate_against_metaschema")]
+    pub fn validate_against_metaschema(schema_json: String) -> String {
+        let result = match serde_json::from_str::<serde_json::Value>(&schema_json) {
+            Ok(schema) => {
+                let (warnings, errors): (Vec<_>, Vec<_>) = validate_schema_document(&schema)
+                    .into_iter()
+                    .partition(|error| error.severity == Severity::Warning);
+                serde_json::json!({"valid": errors.is_empty(), "errors": errors, "warnings": warnings})
+            }
+            Err(e) => serde_json::json!({
+                "valid": false,
+                "errors": [ValidationError::new(String::new(), format!("Invalid schema JSON: {}", e), ErrorCode::SchemaInvalid)]
+            }),
+        };
+        result.to_string()
+    }
+
+    #[napi(js_name = "set_regex_cache_capacity")]
+    pub fn set_regex_cache_capacity(capacity: u32) {
+        fast_schema::set_regex_cache_capacity(capacity as usize);
+    }
+
+    #[napi(js_name = "get_version")]
+    pub fn get_version() -> String {
+        serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "name": "fast-schema",
+            "description": env!("CARGO_PKG_DESCRIPTION"),
+            "backend": "native"
+        })
+        .to_string()
+    }
+}
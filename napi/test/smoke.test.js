@@ -0,0 +1,26 @@
+// Loads the addon built by `napi build` (without `--platform`) and checks
+// that it validates values the same way as the WASM package.
+const test = require('node:test');
+const assert = require('node:assert');
+const { FastValidator } = require('../fast-schema-native.node');
+
+const schema = JSON.stringify({
+  type: 'object',
+  properties: { email: { type: 'string', format: 'email' } },
+  required: ['email'],
+});
+
+test('validates JSON strings, buffers and JS values', () => {
+  const validator = new FastValidator(schema);
+
+  assert.strictEqual(JSON.parse(validator.validate('{"email": "ann@example.com"}')).success, true);
+  assert.strictEqual(JSON.parse(validator.validate_buffer(Buffer.from('{"email": "ann"}'))).success, false);
+
+  const result = validator.validate_js({});
+  assert.strictEqual(result.success, false);
+  assert.deepStrictEqual(result.errors[0].path, ['email']);
+});
+
+test('rejects an invalid schema', () => {
+  assert.throws(() => new FastValidator('{'), /Invalid schema JSON/);
+});
@@ -0,0 +1,185 @@
+
+//! Schemas for JSON payloads mirrored from Avro records, read from an Avro
+//! schema document.
+//!
+//! Payloads are taken as plain JSON: a union value is the branch's value
+//! itself, not Avro's `{"type": value}` wrapper. Records, enums and fixed
+//! types are named, so each one becomes a schema under its full name
+//! (`acme.User`) and is referred to with `$ref`, recursive records included.
+
+use crate::builder::{Schema, SchemaBuilder};
+use crate::error::FastSchemaError;
+use crate::schema::SchemaType;
+use serde_json::{Map, Value};
+
+/// The schema for an Avro schema document, and one for each named type it declares
+pub(crate) fn avro_schemas(document: &Value) -> Result<(SchemaType, Vec<(String, SchemaType)>), FastSchemaError> {
+    let mut named = Vec::new();
+    let schema = convert(document, "", &mut named)?.build()?;
+    Ok((schema, named))
+}
+
+fn convert(node: &Value, namespace: &str, named: &mut Vec<(String, SchemaType)>) -> Result<SchemaBuilder, FastSchemaError> {
+    match node {
+        Value::String(name) => Ok(primitive(name).unwrap_or_else(|| reference(&full_name(name, namespace)))),
+        Value::Array(branches) => {
+            let branches = branches
+                .iter()
+                .map(|branch| convert(branch, namespace, named))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Schema::union(branches))
+        }
+        Value::Object(obj) => complex(obj, namespace, named),
+        _ => Err(invalid("a schema must be a type name, a union or an object")),
+    }
+}
+
+fn complex(obj: &Map<String, Value>, namespace: &str, named: &mut Vec<(String, SchemaType)>) -> Result<SchemaBuilder, FastSchemaError> {
+    let kind = match obj.get("type") {
+        Some(Value::String(kind)) => kind.as_str(),
+        // `{"type": {...}}` and `{"type": [...]}` wrap another schema
+        Some(schema) => return convert(schema, namespace, named),
+        None => return Err(invalid("'type' is missing")),
+    };
+    if let Some(schema) = primitive(kind) {
+        return Ok(match obj.get("logicalType").and_then(Value::as_str) {
+            Some("uuid") if kind == "string" => schema.uuid(),
+            _ => schema,
+        });
+    }
+
+    match kind {
+        "array" => {
+            let items = obj.get("items").ok_or_else(|| invalid("an array needs 'items'"))?;
+            Ok(Schema::array(convert(items, namespace, named)?))
+        }
+        "map" => {
+            let values = obj.get("values").ok_or_else(|| invalid("a map needs 'values'"))?;
+            Ok(Schema::object().catchall(convert(values, namespace, named)?))
+        }
+        "record" | "error" | "enum" | "fixed" => {
+            let name = obj.get("name").and_then(Value::as_str).ok_or_else(|| invalid(&format!("a {} needs a 'name'", kind)))?;
+            let namespace = obj.get("namespace").and_then(Value::as_str).unwrap_or(namespace);
+            let name = full_name(name, namespace);
+            // Names inside a type default to the namespace of its full name
+            let inner_namespace = name.rsplit_once('.').map_or("", |(namespace, _)| namespace);
+
+            let schema = match kind {
+                "enum" => {
+                    let symbols = obj.get("symbols").and_then(Value::as_array).ok_or_else(|| invalid("an enum needs 'symbols'"))?;
+                    Schema::union(symbols.iter().map(|symbol| Schema::literal(symbol.clone())))
+                }
+                // Bytes are written as a string of one code point per byte
+                "fixed" => {
+                    let size = obj.get("size").and_then(Value::as_u64).ok_or_else(|| invalid("a fixed needs a 'size'"))?;
+                    Schema::string().length(size as usize)
+                }
+                _ => {
+                    let fields = obj.get("fields").and_then(Value::as_array).ok_or_else(|| invalid("a record needs 'fields'"))?;
+                    let mut record = Schema::object().strict();
+                    for field in fields {
+                        let key = field.get("name").and_then(Value::as_str).ok_or_else(|| invalid("a field needs a 'name'"))?;
+                        let schema = field.get("type").ok_or_else(|| invalid(&format!("field '{}' needs a 'type'", key)))?;
+                        let mut schema = convert(schema, inner_namespace, named)?;
+                        // Readers fill in a missing field from its default
+                        if field.get("default").is_some() {
+                            schema = schema.optional();
+                        }
+                        record = record.prop(key, schema);
+                    }
+                    record
+                }
+            };
+            named.push((name.clone(), schema.build()?));
+            Ok(reference(&name))
+        }
+        _ => Ok(reference(&full_name(kind, namespace))),
+    }
+}
+
+fn primitive(name: &str) -> Option<SchemaBuilder> {
+    Some(match name {
+        "null" => Schema::null(),
+        "boolean" => Schema::boolean(),
+        "int" => Schema::integer().min(i32::MIN).max(i32::MAX),
+        "long" => Schema::integer(),
+        "float" | "double" => Schema::number(),
+        "bytes" | "string" => Schema::string(),
+        _ => return None,
+    })
+}
+
+fn reference(name: &str) -> SchemaBuilder {
+    SchemaBuilder::from_schema(SchemaType::Ref { reference: name.to_string() })
+}
+
+/// A name qualified by `namespace` unless it already has one
+fn full_name(name: &str, namespace: &str) -> String {
+    if name.contains('.') || namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
+fn invalid(message: &str) -> FastSchemaError {
+    FastSchemaError::SchemaCompilation(format!("Invalid Avro schema: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::SchemaRegistry;
+    use serde_json::json;
+
+    #[test]
+    fn test_avro_schemas() {
+        let schema = json!({
+            "type": "record",
+            "name": "User",
+            "namespace": "acme",
+            "fields": [
+                {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+                {"name": "age", "type": "int"},
+                {"name": "email", "type": ["null", "string"], "default": null},
+                {"name": "status", "type": {"type": "enum", "name": "Status", "symbols": ["ACTIVE", "BANNED"]}},
+                {"name": "manager", "type": ["null", "User"], "default": null},
+                {"name": "scores", "type": {"type": "map", "values": "double"}, "default": {}},
+                {"name": "tags", "type": {"type": "array", "items": "string"}, "default": []},
+                {"name": "hash", "type": {"type": "fixed", "name": "md5.Hash", "size": 4}, "default": "abcd"}
+            ]
+        });
+
+        let mut registry = SchemaRegistry::new();
+        registry.register_avro("acme.User", &schema).unwrap();
+        assert_eq!(registry.names(), vec!["acme.Status", "acme.User", "md5.Hash"]);
+
+        let validator = registry.validator("acme.User").unwrap();
+        let user = json!({
+            "id": "9b2f3c4e-8d1a-4f6b-9c3e-2a1b0c9d8e7f",
+            "age": 30,
+            "status": "ACTIVE",
+            "manager": {"id": "9b2f3c4e-8d1a-4f6b-9c3e-2a1b0c9d8e70", "age": 50, "status": "ACTIVE", "manager": null},
+            "scores": {"math": 0.5},
+            "hash": "wxyz"
+        });
+        assert!(validator.validate(&user).success, "{:?}", validator.validate(&user).errors);
+
+        let result = validator.validate(&json!({
+            "id": "nope",
+            "age": 3_000_000_000u64,
+            "status": "GONE",
+            "scores": {"math": "A"},
+            "hash": "abc",
+            "nickname": "al"
+        }));
+        let mut paths: Vec<String> = result.errors.iter().map(|error| error.path.to_string()).collect();
+        paths.sort();
+        // Unions report each branch's error
+        paths.dedup();
+        assert_eq!(paths, vec!["age", "hash", "id", "nickname", "scores.math", "status"]);
+
+        let mut registry = SchemaRegistry::new();
+        registry.register_avro("Tags", &json!({"type": "array", "items": "string"})).unwrap();
+        assert!(registry.validator("Tags").unwrap().validate(&json!(["a"])).success);
+    }
+}
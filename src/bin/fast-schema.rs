@@ -0,0 +1,66 @@
+//! `fast-schema [--json5] <schema> <file>...`: validate JSON, JSON5, YAML
+//! or TOML files against a schema document, picking the format from each
+//! extension. `.jsonc` files are read as JSON5; `--json5` reads `.json`
+//! files that way too, for configs such as `tsconfig.json` that have
+//! comments and trailing commas.
+//!
+//! Exits with 0 when every file is valid, 1 when any is invalid and 2 when
+//! a file cannot be read or parsed.
+
+use fast_schema::{parse_json5, parse_toml, parse_yaml, FastSchemaError, PrettyOptions, Validator};
+use std::path::Path;
+use std::process::ExitCode;
+
+fn load(path: &str, json5: bool) -> Result<serde_json::Value, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+    let extension = Path::new(path).extension().and_then(|extension| extension.to_str()).unwrap_or("");
+    let value = match extension.to_ascii_lowercase().as_str() {
+        "yaml" | "yml" => parse_yaml(&text),
+        "toml" => parse_toml(&text),
+        "json5" | "jsonc" => parse_json5(&text),
+        _ if json5 => parse_json5(&text),
+        _ => serde_json::from_str(&text).map_err(FastSchemaError::from),
+    };
+    value.map_err(|error| format!("{}: {}", path, error))
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json5 = args.first().is_some_and(|arg| arg == "--json5");
+    if json5 {
+        args.remove(0);
+    }
+    if args.len() < 2 {
+        eprintln!("usage: fast-schema [--json5] <schema> <file>...");
+        return ExitCode::from(2);
+    }
+
+    let validator = match load(&args[0], json5).and_then(|schema| Validator::from_json(&schema).map_err(|error| format!("{}: {}", args[0], error))) {
+        Ok(validator) => validator,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut code = 0;
+    for path in &args[1..] {
+        let data = match load(path, json5) {
+            Ok(data) => data,
+            Err(message) => {
+                eprintln!("{}", message);
+                code = 2;
+                continue;
+            }
+        };
+        let result = validator.validate(&data);
+        if result.success {
+            println!("{}: valid", path);
+        } else {
+            println!("{}: invalid", path);
+            println!("{}", result.to_pretty_string(PrettyOptions { data: Some(&data), ..Default::default() }));
+            code = code.max(1);
+        }
+    }
+    ExitCode::from(code)
+}
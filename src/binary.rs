@@ -0,0 +1,265 @@
+
+//! Compact binary encoding of JSON values, used to cache compiled schemas.
+//!
+//! Strings (object keys included) are stored once in a table and referred
+//! to by index, so the keywords repeated through a large schema (`type`,
+//! `properties`, `minLength`) cost a byte or two each. The encoding is
+//! self-describing, which the internally tagged `SchemaType` needs.
+
+use crate::error::FastSchemaError;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+const MAGIC: &[u8; 4] = b"FSCS";
+
+/// Nesting limit when decoding, matching `serde_json`'s
+const MAX_DEPTH: usize = 128;
+
+const NULL: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+const UNSIGNED: u8 = 3;
+const NEGATIVE: u8 = 4;
+const FLOAT: u8 = 5;
+const STRING: u8 = 6;
+const ARRAY: u8 = 7;
+const OBJECT: u8 = 8;
+
+/// Encode `value`, tagged with `version` so stale caches can be detected
+pub fn encode(value: &Value, version: &str) -> Vec<u8> {
+    let mut strings = Strings::default();
+    let mut body = Vec::new();
+    encode_value(value, &mut strings, &mut body);
+
+    let mut bytes = MAGIC.to_vec();
+    write_str(version, &mut bytes);
+    write_varint(strings.table.len() as u64, &mut bytes);
+    for s in &strings.table {
+        write_str(s, &mut bytes);
+    }
+    bytes.extend(body);
+    bytes
+}
+
+/// Decode bytes written by `encode` with the same `version`
+pub fn decode(bytes: &[u8], version: &str) -> Result<Value, FastSchemaError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(invalid("not a compiled schema"));
+    }
+    let written = reader.str()?;
+    if written != version {
+        return Err(invalid(&format!("written by version {}, expected {}", written, version)));
+    }
+
+    let count = reader.len()?;
+    let table = (0..count).map(|_| reader.str()).collect::<Result<Vec<_>, _>>()?;
+    let value = reader.value(&table, 0)?;
+    if reader.pos != bytes.len() {
+        return Err(invalid("trailing bytes"));
+    }
+    Ok(value)
+}
+
+fn invalid(reason: &str) -> FastSchemaError {
+    FastSchemaError::Decode(reason.to_string())
+}
+
+#[derive(Default)]
+struct Strings<'a> {
+    table: Vec<&'a str>,
+    indices: HashMap<&'a str, usize>,
+}
+
+impl<'a> Strings<'a> {
+    fn index(&mut self, s: &'a str) -> u64 {
+        let next = self.table.len();
+        let index = *self.indices.entry(s).or_insert(next);
+        if index == next {
+            self.table.push(s);
+        }
+        index as u64
+    }
+}
+
+fn encode_value<'a>(value: &'a Value, strings: &mut Strings<'a>, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(NULL),
+        Value::Bool(false) => out.push(FALSE),
+        Value::Bool(true) => out.push(TRUE),
+        Value::Number(number) => {
+            if let Some(n) = number.as_u64() {
+                out.push(UNSIGNED);
+                write_varint(n, out);
+            } else if let Some(n) = number.as_i64() {
+                // Negative, so -1 - n fits in a u64
+                out.push(NEGATIVE);
+                write_varint((-1 - n) as u64, out);
+            } else {
+                out.push(FLOAT);
+                out.extend(number.as_f64().unwrap_or_default().to_le_bytes());
+            }
+        }
+        Value::String(s) => {
+            out.push(STRING);
+            write_varint(strings.index(s), out);
+        }
+        Value::Array(items) => {
+            out.push(ARRAY);
+            write_varint(items.len() as u64, out);
+            for item in items {
+                encode_value(item, strings, out);
+            }
+        }
+        Value::Object(map) => {
+            out.push(OBJECT);
+            write_varint(map.len() as u64, out);
+            for (key, item) in map {
+                write_varint(strings.index(key), out);
+                encode_value(item, strings, out);
+            }
+        }
+    }
+}
+
+/// LEB128: seven bits per byte, high bit set on all but the last
+fn write_varint(mut n: u64, out: &mut Vec<u8>) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn write_str(s: &str, out: &mut Vec<u8>) {
+    write_varint(s.len() as u64, out);
+    out.extend(s.as_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], FastSchemaError> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len()).ok_or_else(|| invalid("truncated"))?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, FastSchemaError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, FastSchemaError> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(invalid("varint too long"))
+    }
+
+    /// A count of following items, which each take at least one byte
+    fn len(&mut self) -> Result<usize, FastSchemaError> {
+        let len = self.varint()?;
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(invalid("truncated"));
+        }
+        Ok(len as usize)
+    }
+
+    fn str(&mut self) -> Result<String, FastSchemaError> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| invalid("invalid UTF-8"))
+    }
+
+    fn string(&mut self, table: &[String]) -> Result<String, FastSchemaError> {
+        let index = self.varint()?;
+        table.get(index as usize).cloned().ok_or_else(|| invalid("string index out of range"))
+    }
+
+    fn value(&mut self, table: &[String], depth: usize) -> Result<Value, FastSchemaError> {
+        if depth > MAX_DEPTH {
+            return Err(invalid("nested too deeply"));
+        }
+
+        Ok(match self.byte()? {
+            NULL => Value::Null,
+            FALSE => Value::Bool(false),
+            TRUE => Value::Bool(true),
+            UNSIGNED => Value::from(self.varint()?),
+            NEGATIVE => {
+                let n = self.varint()?;
+                let n = i64::try_from(n).map_err(|_| invalid("integer out of range"))?;
+                Value::from(-1 - n)
+            }
+            FLOAT => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(self.take(8)?);
+                Number::from_f64(f64::from_le_bytes(bytes)).map(Value::Number).ok_or_else(|| invalid("non-finite number"))?
+            }
+            STRING => Value::String(self.string(table)?),
+            ARRAY => {
+                let len = self.len()?;
+                let items = (0..len).map(|_| self.value(table, depth + 1)).collect::<Result<_, _>>()?;
+                Value::Array(items)
+            }
+            OBJECT => {
+                let len = self.len()?;
+                let mut map = Map::new();
+                for _ in 0..len {
+                    let key = self.string(table)?;
+                    map.insert(key, self.value(table, depth + 1)?);
+                }
+                Value::Object(map)
+            }
+            tag => return Err(invalid(&format!("unknown tag {}", tag))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_binary_round_trip() {
+        let value = json!({
+            "type": "object",
+            "properties": {"a": {"type": "number", "min": -2.5}, "b": {"type": "string", "minLength": 300}},
+            "required": ["a", "b"],
+            "extremes": [i64::MIN, u64::MAX, 0, true, false, null, "é"]
+        });
+        let bytes = encode(&value, "1.0");
+        assert_eq!(decode(&bytes, "1.0").unwrap(), value);
+
+        // Repeated strings are stored once
+        assert!(bytes.len() < value.to_string().len());
+        assert_eq!(bytes.windows(4).filter(|w| w == b"type").count(), 1);
+    }
+
+    #[test]
+    fn test_binary_rejects_bad_input() {
+        let bytes = encode(&json!({"a": [1, 2, 3]}), "1.0");
+        assert!(matches!(decode(&bytes, "2.0"), Err(FastSchemaError::Decode(_))));
+        assert!(decode(b"JSON", "1.0").is_err());
+        for len in 0..bytes.len() {
+            assert!(decode(&bytes[..len], "1.0").is_err());
+        }
+
+        let mut nested = json!(null);
+        for _ in 0..200 {
+            nested = json!([nested]);
+        }
+        assert!(decode(&encode(&nested, "1.0"), "1.0").is_err());
+    }
+}
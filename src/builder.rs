@@ -0,0 +1,505 @@
+
+//! Fluent construction of schemas, so callers can write
+//! `Schema::object().prop("age", Schema::number().int().min(0))` instead
+//! of schema JSON.
+//!
+//! Modifiers never fail on their own: one that does not apply to the
+//! schema kind (e.g. `email` on a number) is recorded and reported by
+//! `build`, keeping chains free of `?`.
+
+use crate::error::FastSchemaError;
+use crate::schema::{LengthUnit, SchemaType, StringFormat, UnknownKeys};
+use std::collections::HashMap;
+
+/// Entry points for building schemas; each returns a `SchemaBuilder`
+pub struct Schema;
+
+impl Schema {
+    pub fn string() -> SchemaBuilder {
+        SchemaBuilder::string()
+    }
+
+    pub fn number() -> SchemaBuilder {
+        SchemaBuilder::number()
+    }
+
+    pub fn integer() -> SchemaBuilder {
+        SchemaBuilder::integer()
+    }
+
+    pub fn boolean() -> SchemaBuilder {
+        SchemaBuilder::boolean()
+    }
+
+    pub fn null() -> SchemaBuilder {
+        SchemaBuilder::null()
+    }
+
+    pub fn any() -> SchemaBuilder {
+        SchemaBuilder::any()
+    }
+
+    pub fn literal(value: impl Into<serde_json::Value>) -> SchemaBuilder {
+        SchemaBuilder::literal(value.into())
+    }
+
+    pub fn array(items: SchemaBuilder) -> SchemaBuilder {
+        SchemaBuilder::array(items)
+    }
+
+    /// An object without properties, to be added with `prop`
+    pub fn object() -> SchemaBuilder {
+        SchemaBuilder::object(Vec::<(String, SchemaBuilder)>::new())
+    }
+
+    pub fn union(options: impl IntoIterator<Item = SchemaBuilder>) -> SchemaBuilder {
+        SchemaBuilder::union(options)
+    }
+}
+
+/// A schema under construction
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaBuilder {
+    schema: SchemaType,
+    /// Whether `object` leaves this property out of `required`
+    optional: bool,
+    /// The first modifier that did not apply
+    error: Option<String>,
+}
+
+impl SchemaBuilder {
+    pub fn from_schema(schema: SchemaType) -> Self {
+        Self { schema, optional: false, error: None }
+    }
+
+    /// Start from a persisted schema document
+    pub fn from_json(schema_json: &str) -> Result<Self, FastSchemaError> {
+        Ok(Self::from_schema(serde_json::from_str(schema_json)?))
+    }
+
+    pub fn string() -> Self {
+        Self::from_schema(SchemaType::String {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            format: None,
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::default(),
+        })
+    }
+
+    pub fn number() -> Self {
+        Self::from_schema(SchemaType::Number {
+            min: None,
+            max: None,
+            exclusive_min: None,
+            exclusive_max: None,
+            integer: false,
+            multiple_of: None,
+            allow_non_finite: false,
+            strict_integer: false,
+            multiple_of_tolerance: None,
+        })
+    }
+
+    pub fn integer() -> Self {
+        Self::number().int()
+    }
+
+    pub fn boolean() -> Self {
+        Self::from_schema(SchemaType::Boolean)
+    }
+
+    pub fn null() -> Self {
+        Self::from_schema(SchemaType::Null)
+    }
+
+    pub fn any() -> Self {
+        Self::from_schema(SchemaType::Any)
+    }
+
+    pub fn literal(value: serde_json::Value) -> Self {
+        Self::from_schema(SchemaType::Const { value })
+    }
+
+    pub fn array(items: SchemaBuilder) -> Self {
+        let error = items.error.clone();
+        let mut builder = Self::from_schema(SchemaType::Array {
+            items: Box::new(items.schema),
+            min_items: None,
+            max_items: None,
+            unique_items: false,
+            unique_by: None,
+            sorted: None,
+            sorted_by: None,
+        });
+        builder.error = error;
+        builder
+    }
+
+    /// An object whose properties are required unless marked `optional`
+    pub fn object<K: Into<String>>(shape: impl IntoIterator<Item = (K, SchemaBuilder)>) -> Self {
+        let mut properties = HashMap::new();
+        let mut required = Vec::new();
+        let mut error = None;
+        for (key, property) in shape {
+            let key = key.into();
+            if !property.optional {
+                required.push(key.clone());
+            }
+            error = error.or(property.error);
+            properties.insert(key, property.schema);
+        }
+        required.sort();
+
+        let mut builder = Self::from_schema(SchemaType::Object {
+            properties,
+            required: Some(required),
+            additional_properties: UnknownKeys::default(),
+            catchall: None,
+            min_properties: None,
+            max_properties: None,
+            read_only: None,
+            write_only: None,
+            rules: None,
+        });
+        builder.error = error;
+        builder
+    }
+
+    /// Values matching any of `options`
+    pub fn union(options: impl IntoIterator<Item = SchemaBuilder>) -> Self {
+        let mut error = None;
+        let options = options
+            .into_iter()
+            .map(|option| {
+                error = error.take().or(option.error);
+                option.schema
+            })
+            .collect();
+        let mut builder = Self::from_schema(SchemaType::Union { options, discriminator: None });
+        builder.error = error;
+        builder
+    }
+
+    /// Minimum length for strings, item count for arrays and sets, or
+    /// value for numbers
+    pub fn min(self, bound: impl Into<f64>) -> Self {
+        let bound = bound.into();
+        self.modify("min", |schema| match schema {
+            SchemaType::String { min_length: count, .. }
+            | SchemaType::Array { min_items: count, .. }
+            | SchemaType::Set { min_items: count, .. } => set_count(count, bound),
+            SchemaType::Number { min, .. } => {
+                *min = Some(bound);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Maximum counterpart of `min`
+    pub fn max(self, bound: impl Into<f64>) -> Self {
+        let bound = bound.into();
+        self.modify("max", |schema| match schema {
+            SchemaType::String { max_length: count, .. }
+            | SchemaType::Array { max_items: count, .. }
+            | SchemaType::Set { max_items: count, .. } => set_count(count, bound),
+            SchemaType::Number { max, .. } => {
+                *max = Some(bound);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Exact length for strings or item count for arrays and sets
+    pub fn length(self, length: usize) -> Self {
+        self.min(length as f64).max(length as f64)
+    }
+
+    pub fn format(self, format: StringFormat) -> Self {
+        self.string_format("format", format)
+    }
+
+    pub fn email(self) -> Self {
+        self.string_format("email", StringFormat::Email)
+    }
+
+    pub fn url(self) -> Self {
+        self.string_format("url", StringFormat::Url)
+    }
+
+    pub fn uuid(self) -> Self {
+        self.string_format("uuid", StringFormat::Uuid)
+    }
+
+    fn string_format(self, name: &str, format: StringFormat) -> Self {
+        self.modify(name, |schema| match schema {
+            SchemaType::String { format: slot, .. } => {
+                *slot = Some(format);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    pub fn regex(self, pattern: &str) -> Self {
+        self.modify("regex", |schema| match schema {
+            SchemaType::String { pattern: slot, .. } => {
+                *slot = Some(pattern.to_string());
+                true
+            }
+            _ => false,
+        })
+    }
+
+    pub fn int(self) -> Self {
+        self.modify("int", |schema| match schema {
+            SchemaType::Number { integer, .. } => {
+                *integer = true;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    pub fn multiple_of(self, step: f64) -> Self {
+        self.modify("multipleOf", |schema| match schema {
+            SchemaType::Number { multiple_of, .. } => {
+                *multiple_of = Some(step);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Arrays whose items are all distinct
+    pub fn unique(self) -> Self {
+        self.modify("unique", |schema| match schema {
+            SchemaType::Array { unique_items, .. } => {
+                *unique_items = true;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Add or replace an object property, required unless `optional`
+    pub fn prop(self, key: impl Into<String>, property: SchemaBuilder) -> Self {
+        let key = key.into();
+        let error = property.error.clone();
+        let mut builder = self.modify("prop", |schema| match schema {
+            SchemaType::Object { properties, required, .. } => {
+                let required = required.get_or_insert_with(Vec::new);
+                required.retain(|name| *name != key);
+                if !property.optional {
+                    required.push(key.clone());
+                    required.sort();
+                }
+                properties.insert(key, property.schema);
+                true
+            }
+            _ => false,
+        });
+        builder.error = builder.error.or(error);
+        builder
+    }
+
+    /// Set the required properties, replacing those implied by `prop`
+    pub fn required<K: Into<String>>(self, keys: impl IntoIterator<Item = K>) -> Self {
+        let mut keys: Vec<String> = keys.into_iter().map(Into::into).collect();
+        keys.sort();
+        self.modify("required", |schema| match schema {
+            SchemaType::Object { required, .. } => {
+                *required = Some(keys);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Schema for every key not declared in the object's shape
+    pub fn catchall(self, values: SchemaBuilder) -> Self {
+        let error = values.error.clone();
+        let mut builder = self.modify("catchall", |schema| match schema {
+            SchemaType::Object { catchall, .. } => {
+                *catchall = Some(Box::new(values.schema));
+                true
+            }
+            _ => false,
+        });
+        builder.error = builder.error.or(error);
+        builder
+    }
+
+    /// Reject keys not declared in the object's shape
+    pub fn strict(self) -> Self {
+        self.unknown_keys("strict", UnknownKeys::Strict)
+    }
+
+    /// Remove keys not declared in the object's shape
+    pub fn strip(self) -> Self {
+        self.unknown_keys("strip", UnknownKeys::Strip)
+    }
+
+    pub fn passthrough(self) -> Self {
+        self.unknown_keys("passthrough", UnknownKeys::Passthrough)
+    }
+
+    /// Leave this property out of its object's `required` list
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+
+    /// The schema, or the first modifier that did not apply to it
+    pub fn build(self) -> Result<SchemaType, FastSchemaError> {
+        match self.error {
+            Some(error) => Err(FastSchemaError::UnsupportedOperation(error)),
+            None => Ok(self.schema),
+        }
+    }
+
+    /// The schema's JSON form, as accepted by `from_json`
+    pub fn to_json(&self) -> Result<String, FastSchemaError> {
+        Ok(serde_json::to_string(&self.clone().build()?)?)
+    }
+
+    fn unknown_keys(self, name: &str, policy: UnknownKeys) -> Self {
+        self.modify(name, |schema| match schema {
+            SchemaType::Object { additional_properties, .. } => {
+                *additional_properties = policy;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Apply `apply`, recording an error if it returns false
+    fn modify(mut self, name: &str, apply: impl FnOnce(&mut SchemaType) -> bool) -> Self {
+        if self.error.is_none() && !apply(self.schema.unannotated_mut()) {
+            self.error = Some(format!("'{}' does not apply to {}", name, kind(&self.schema)));
+        }
+        self
+    }
+}
+
+/// Set a length or item count bound, which must be a whole number
+fn set_count(count: &mut Option<usize>, bound: f64) -> bool {
+    let whole = bound >= 0.0 && bound.fract() == 0.0;
+    if whole {
+        *count = Some(bound as usize);
+    }
+    whole
+}
+
+/// The schema's `type` keyword, for error messages
+fn kind(schema: &SchemaType) -> String {
+    match schema {
+        SchemaType::Object { .. } => "object".to_string(),
+        SchemaType::Array { .. } => "array".to_string(),
+        _ => serde_json::to_value(schema)
+            .ok()
+            .and_then(|value| value.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_else(|| "this schema".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::Validator;
+    use serde_json::json;
+
+    #[test]
+    fn test_builder_schemas() {
+        let schema = SchemaBuilder::object(vec![
+            ("name", SchemaBuilder::string().min(3.0)),
+            ("email", SchemaBuilder::string().email()),
+            ("tags", SchemaBuilder::array(SchemaBuilder::string()).max(2.0).unique().optional()),
+            ("age", SchemaBuilder::integer().min(0.0).optional()),
+        ])
+        .strict()
+        .build()
+        .unwrap();
+
+        let as_json: SchemaType = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 3},
+                "email": {"type": "string", "format": "email"},
+                "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 2, "uniqueItems": true},
+                "age": {"type": "number", "integer": true, "min": 0.0}
+            },
+            "required": ["email", "name"],
+            "additionalProperties": "strict"
+        }))
+        .unwrap();
+        assert_eq!(schema, as_json);
+
+        let validator = Validator::new(schema.clone()).unwrap();
+        assert!(validator.validate(&json!({"name": "Ann", "email": "ann@example.com"})).success);
+        assert!(!validator.validate(&json!({"name": "Al", "email": "ann@example.com"})).success);
+
+        // The JSON form round-trips
+        let builder = SchemaBuilder::from_schema(schema.clone());
+        let restored = SchemaBuilder::from_json(&builder.to_json().unwrap()).unwrap();
+        assert_eq!(restored.build().unwrap(), schema);
+    }
+
+    #[test]
+    fn test_schema_entry_points() {
+        let schema = Schema::object()
+            .prop("age", Schema::number().int().min(0))
+            .prop("nickname", Schema::string().max(20u32))
+            .prop("role", Schema::union(vec![Schema::literal("admin"), Schema::literal("user")]))
+            .required(["age"])
+            .catchall(Schema::boolean())
+            .build()
+            .unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!({"age": 3, "verified": true})).success);
+        assert!(!validator.validate(&json!({"nickname": "x"})).success);
+        assert!(!validator.validate(&json!({"age": -1})).success);
+        assert!(!validator.validate(&json!({"age": 3, "role": "root"})).success);
+        assert!(validator.validate(&json!({"age": 3, "role": "admin"})).success);
+        assert!(!validator.validate(&json!({"age": 3, "verified": "yes"})).success);
+
+        // Optional properties stay out of `required`
+        let schema = Schema::object().prop("a", Schema::any()).prop("b", Schema::any().optional()).build().unwrap();
+        match schema {
+            SchemaType::Object { required, .. } => assert_eq!(required, Some(vec!["a".to_string()])),
+            _ => unreachable!(),
+        }
+        assert!(Schema::string().prop("a", Schema::any()).build().is_err());
+    }
+
+    #[test]
+    fn test_builder_misapplied_modifiers() {
+        let error = SchemaBuilder::number().email().min(1.0).build().unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported schema operation: 'email' does not apply to number");
+
+        // Errors in nested builders surface from the outer one
+        let nested = SchemaBuilder::object(vec![("ids", SchemaBuilder::array(SchemaBuilder::boolean().strict()))]);
+        assert!(nested.to_json().is_err());
+
+        assert!(SchemaBuilder::string().min(1.5).build().is_err());
+        assert!(SchemaBuilder::string().length(4).build().is_ok());
+    }
+}
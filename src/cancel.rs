@@ -0,0 +1,42 @@
+
+//! Cooperative cancellation for long-running batch validation.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag checked by batch validation between chunks. Clones share
+/// the same flag, so one can be handed to the validating thread and the
+/// other kept to cancel it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let handle = token.clone();
+        assert!(!token.is_cancelled());
+
+        handle.cancel();
+        assert!(token.is_cancelled());
+    }
+}
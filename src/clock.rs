@@ -0,0 +1,64 @@
+
+//! Monotonic clock for every target. `std::time::Instant::now` panics on
+//! `wasm32-unknown-unknown`, so there the time comes from the host's
+//! `performance.now()`.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use self::wasm_clock::Instant;
+
+#[cfg(target_arch = "wasm32")]
+mod wasm_clock {
+    use std::ops::Add;
+    use std::time::Duration;
+
+    /// Milliseconds since the host's time origin
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    pub struct Instant(f64);
+
+    impl Instant {
+        pub fn now() -> Self {
+            Self(now_ms())
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Self::now().duration_since(*self)
+        }
+
+        /// Zero if `earlier` is later, like `std::time::Instant`
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            Duration::from_secs_f64((self.0 - earlier.0).max(0.0) / 1000.0)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, duration: Duration) -> Instant {
+            Self(self.0 + duration.as_secs_f64() * 1000.0)
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    fn now_ms() -> f64 {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(catch, js_namespace = performance, js_name = now)]
+            fn performance_now() -> Result<f64, JsValue>;
+        }
+
+        // Hosts without the Performance API still have `Date`
+        performance_now().unwrap_or_else(|_| js_sys::Date::now())
+    }
+
+    /// Without the JS bindings there is no host clock: time stands still,
+    /// so timings read zero and deadlines never pass
+    #[cfg(not(feature = "wasm"))]
+    fn now_ms() -> f64 {
+        0.0
+    }
+}
@@ -0,0 +1,460 @@
+
+//! Validating deserialization: a `Deserializer` wrapper that builds a Rust
+//! value and records the JSON it reads in the same pass, then validates the
+//! recording, so a document is never parsed into a `Value` and deserialized
+//! a second time. Fields the target type ignores are still recorded and
+//! validated.
+
+use crate::error::{ErrorCode, ValidationError};
+use crate::validator::Validator;
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Deserializes from `inner` and validates what it read against a
+/// validator's schema
+///
+/// Transforms in the schema (such as stripped keys) do not reach the deserialized
+/// value; use `Validator::parse_into` when they are needed. A value that
+/// doesn't fit the target type is reported before schema errors, as the
+/// document is only validated once it has been read in full.
+pub struct ValidatingDeserializer<'v, D> {
+    validator: &'v Validator,
+    inner: D,
+}
+
+impl<'v, D> ValidatingDeserializer<'v, D> {
+    pub fn new(validator: &'v Validator, inner: D) -> Self {
+        Self { validator, inner }
+    }
+}
+
+impl<'v, 'de, D: Deserializer<'de>> ValidatingDeserializer<'v, D> {
+    /// Deserialize `T`, returning every schema error if the document is
+    /// invalid
+    pub fn deserialize<T: Deserialize<'de>>(self) -> Result<T, Vec<ValidationError>> {
+        self.read().map_err(|rejected| match rejected {
+            Rejected::Unreadable(error) => vec![deserialization_error(error)],
+            Rejected::Invalid(errors) => errors,
+        })
+    }
+
+    /// As `deserialize`, keeping the inner format's error
+    pub(crate) fn read<T: Deserialize<'de>>(self) -> Result<T, Rejected<D::Error>> {
+        let mut recorded = None;
+        let value = T::deserialize(Recorder { inner: self.inner, slot: &mut recorded }).map_err(Rejected::Unreadable)?;
+        let errors = self.validator.validate(&recorded.unwrap_or(Value::Null)).errors;
+        if errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(Rejected::Invalid(errors))
+        }
+    }
+
+    fn check(validator: &Validator, recorded: Option<Value>) -> Result<(), D::Error> {
+        let errors = validator.validate(&recorded.unwrap_or(Value::Null)).errors;
+        match errors.first() {
+            None => Ok(()),
+            Some(first) if errors.len() == 1 => Err(de::Error::custom(first)),
+            Some(first) => Err(de::Error::custom(format_args!("{} (and {} more errors)", first, errors.len() - 1))),
+        }
+    }
+}
+
+/// Why a `ValidatingDeserializer` produced no value
+pub(crate) enum Rejected<E> {
+    /// The input couldn't be read as the target type
+    Unreadable(E),
+    Invalid(Vec<ValidationError>),
+}
+
+/// A failed deserialization as a validation error
+pub(crate) fn deserialization_error(error: impl fmt::Display) -> ValidationError {
+    ValidationError::new(String::new(), error.to_string(), ErrorCode::DeserializationFailed)
+}
+
+/// As a plain `Deserializer`, schema errors become the inner format's error
+macro_rules! validate_forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                let mut recorded = None;
+                let value = Recorder { inner: self.inner, slot: &mut recorded }.$method($($arg,)* visitor)?;
+                Self::check(self.validator, recorded)?;
+                Ok(value)
+            }
+        )*
+    };
+}
+
+macro_rules! deserializer_methods {
+    ($forward:ident) => {
+        $forward! {
+            deserialize_any();
+            deserialize_bool();
+            deserialize_i8();
+            deserialize_i16();
+            deserialize_i32();
+            deserialize_i64();
+            deserialize_i128();
+            deserialize_u8();
+            deserialize_u16();
+            deserialize_u32();
+            deserialize_u64();
+            deserialize_u128();
+            deserialize_f32();
+            deserialize_f64();
+            deserialize_char();
+            deserialize_str();
+            deserialize_string();
+            deserialize_bytes();
+            deserialize_byte_buf();
+            deserialize_option();
+            deserialize_unit();
+            deserialize_unit_struct(name: &'static str);
+            deserialize_newtype_struct(name: &'static str);
+            deserialize_seq();
+            deserialize_tuple(len: usize);
+            deserialize_tuple_struct(name: &'static str, len: usize);
+            deserialize_map();
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+            deserialize_identifier();
+        }
+    };
+}
+
+impl<'v, 'de, D: Deserializer<'de>> Deserializer<'de> for ValidatingDeserializer<'v, D> {
+    type Error = D::Error;
+
+    deserializer_methods!(validate_forward);
+    validate_forward! {
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Passes everything through to `inner`, storing the JSON it reads in `slot`
+struct Recorder<'s, D> {
+    inner: D,
+    slot: &'s mut Option<Value>,
+}
+
+macro_rules! record_forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error> {
+                self.inner.$method($($arg,)* Recording { inner: visitor, slot: self.slot })
+            }
+        )*
+    };
+}
+
+impl<'s, 'de, D: Deserializer<'de>> Deserializer<'de> for Recorder<'s, D> {
+    type Error = D::Error;
+
+    deserializer_methods!(record_forward);
+
+    /// Ignored values are recorded in full, so they are still validated
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        *self.slot = Some(Value::deserialize(self.inner)?);
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Records each value the inner deserializer hands to `inner`
+struct Recording<'s, V> {
+    inner: V,
+    slot: &'s mut Option<Value>,
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+macro_rules! record_visit {
+    ($($method:ident($v:ident: $ty:ty) => $record:expr;)*) => {
+        $(
+            fn $method<E: de::Error>(self, $v: $ty) -> Result<V::Value, E> {
+                *self.slot = Some($record);
+                self.inner.$method($v)
+            }
+        )*
+    };
+}
+
+impl<'s, 'de, V: Visitor<'de>> Visitor<'de> for Recording<'s, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    record_visit! {
+        visit_bool(v: bool) => Value::Bool(v);
+        visit_i8(v: i8) => Value::from(v);
+        visit_i16(v: i16) => Value::from(v);
+        visit_i32(v: i32) => Value::from(v);
+        visit_i64(v: i64) => Value::from(v);
+        visit_i128(v: i128) => i64::try_from(v).map_or_else(|_| float(v as f64), Value::from);
+        visit_u8(v: u8) => Value::from(v);
+        visit_u16(v: u16) => Value::from(v);
+        visit_u32(v: u32) => Value::from(v);
+        visit_u64(v: u64) => Value::from(v);
+        visit_u128(v: u128) => u64::try_from(v).map_or_else(|_| float(v as f64), Value::from);
+        visit_f32(v: f32) => float(f64::from(v));
+        visit_f64(v: f64) => float(v);
+        visit_char(v: char) => Value::String(v.to_string());
+        visit_str(v: &str) => Value::String(v.to_string());
+        visit_borrowed_str(v: &'de str) => Value::String(v.to_string());
+        visit_string(v: String) => Value::String(v.clone());
+        visit_bytes(v: &[u8]) => Value::String(String::from_utf8_lossy(v).into_owned());
+        visit_borrowed_bytes(v: &'de [u8]) => Value::String(String::from_utf8_lossy(v).into_owned());
+        visit_byte_buf(v: Vec<u8>) => Value::String(String::from_utf8_lossy(&v).into_owned());
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        *self.slot = Some(Value::Null);
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        *self.slot = Some(Value::Null);
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner.visit_some(Recorder { inner: deserializer, slot: self.slot })
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner.visit_newtype_struct(Recorder { inner: deserializer, slot: self.slot })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        let mut items = Vec::new();
+        let value = self.inner.visit_seq(RecordingSeq { inner: seq, items: &mut items });
+        *self.slot = Some(Value::Array(items));
+        value
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        let mut entries = Map::new();
+        let value = self.inner.visit_map(RecordingMap { inner: map, entries: &mut entries, key: None });
+        *self.slot = Some(Value::Object(entries));
+        value
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_enum(RecordingEnum { inner: data, slot: self.slot })
+    }
+}
+
+/// A seed whose deserializer records into `slot`
+struct RecordingSeed<'s, S> {
+    inner: S,
+    slot: &'s mut Option<Value>,
+}
+
+impl<'s, 'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for RecordingSeed<'s, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.inner.deserialize(Recorder { inner: deserializer, slot: self.slot })
+    }
+}
+
+/// Object keys as JSON has them; non-string keys only come from other formats
+fn key_string(key: Option<Value>) -> String {
+    match key {
+        Some(Value::String(key)) => key,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    }
+}
+
+struct RecordingSeq<'s, A> {
+    inner: A,
+    items: &'s mut Vec<Value>,
+}
+
+impl<'s, 'de, A: SeqAccess<'de>> SeqAccess<'de> for RecordingSeq<'s, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, A::Error> {
+        let mut item = None;
+        let value = self.inner.next_element_seed(RecordingSeed { inner: seed, slot: &mut item })?;
+        if value.is_some() {
+            self.items.push(item.unwrap_or(Value::Null));
+        }
+        Ok(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+struct RecordingMap<'s, A> {
+    inner: A,
+    entries: &'s mut Map<String, Value>,
+    /// The key whose value comes next
+    key: Option<String>,
+}
+
+impl<'s, 'de, A: MapAccess<'de>> MapAccess<'de> for RecordingMap<'s, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error> {
+        let mut key = None;
+        let value = self.inner.next_key_seed(RecordingSeed { inner: seed, slot: &mut key })?;
+        self.key = value.as_ref().map(|_| key_string(key));
+        Ok(value)
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, A::Error> {
+        let mut item = None;
+        let value = self.inner.next_value_seed(RecordingSeed { inner: seed, slot: &mut item })?;
+        self.entries.insert(self.key.take().unwrap_or_default(), item.unwrap_or(Value::Null));
+        Ok(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+/// Externally tagged enums: a unit variant is recorded as its name, any
+/// other as `{"Variant": content}`
+struct RecordingEnum<'s, A> {
+    inner: A,
+    slot: &'s mut Option<Value>,
+}
+
+impl<'s, 'de, A: EnumAccess<'de>> EnumAccess<'de> for RecordingEnum<'s, A> {
+    type Error = A::Error;
+    type Variant = RecordingVariant<'s, A::Variant>;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self::Variant), A::Error> {
+        let mut key = None;
+        let (value, variant) = self.inner.variant_seed(RecordingSeed { inner: seed, slot: &mut key })?;
+        Ok((value, RecordingVariant { inner: variant, name: key_string(key), slot: self.slot }))
+    }
+}
+
+struct RecordingVariant<'s, A> {
+    inner: A,
+    name: String,
+    slot: &'s mut Option<Value>,
+}
+
+fn tag(slot: &mut Option<Value>, name: String, content: Option<Value>) {
+    let mut tagged = Map::new();
+    tagged.insert(name, content.unwrap_or(Value::Null));
+    *slot = Some(Value::Object(tagged));
+}
+
+impl<'s, 'de, A: VariantAccess<'de>> VariantAccess<'de> for RecordingVariant<'s, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        *self.slot = Some(Value::String(self.name));
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        let mut content = None;
+        let value = self.inner.newtype_variant_seed(RecordingSeed { inner: seed, slot: &mut content })?;
+        tag(self.slot, self.name, content);
+        Ok(value)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, A::Error> {
+        let mut content = None;
+        let value = self.inner.tuple_variant(len, Recording { inner: visitor, slot: &mut content })?;
+        tag(self.slot, self.name, content);
+        Ok(value)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, A::Error> {
+        let mut content = None;
+        let value = self.inner.struct_variant(fields, Recording { inner: visitor, slot: &mut content })?;
+        tag(self.slot, self.name, content);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Schema;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        name: String,
+        tags: Vec<String>,
+        role: Role,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Role {
+        Admin,
+        Guest { until: u32 },
+    }
+
+    fn validator() -> Validator {
+        let schema = Schema::object()
+            .prop("name", Schema::string().min(2))
+            .prop("tags", Schema::array(Schema::string()).max(2))
+            .prop("role", Schema::any())
+            .prop("age", Schema::integer().min(0).optional())
+            .build()
+            .unwrap();
+        Validator::new(schema).unwrap()
+    }
+
+    fn read(validator: &Validator, json: &str) -> Result<User, Vec<ValidationError>> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        ValidatingDeserializer::new(validator, &mut deserializer).deserialize()
+    }
+
+    #[test]
+    fn test_validating_deserializer() {
+        let validator = validator();
+        let user = read(&validator, r#"{"name": "Ann", "tags": ["a"], "role": {"Guest": {"until": 3}}}"#).unwrap();
+        assert_eq!(user, User { name: "Ann".to_string(), tags: vec!["a".to_string()], role: Role::Guest { until: 3 } });
+
+        let errors = read(&validator, r#"{"name": "A", "tags": ["a", "b", "c"], "role": "Admin"}"#).unwrap_err();
+        let mut paths: Vec<String> = errors.iter().map(|error| error.path.to_string()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["name", "tags"]);
+
+        // Fields the struct ignores are still validated
+        let errors = read(&validator, r#"{"name": "Ann", "tags": [], "role": "Admin", "age": -1}"#).unwrap_err();
+        assert_eq!(errors[0].path, "age");
+
+        let errors = read(&validator, r#"{"name": "Ann", "tags": [], "role": "Owner"}"#).unwrap_err();
+        assert_eq!(errors[0].code, ErrorCode::DeserializationFailed);
+    }
+
+    #[test]
+    fn test_validating_deserializer_as_deserializer() {
+        let validator = validator();
+        let value = json!({"name": "A", "tags": [], "role": "Admin"});
+        let error = User::deserialize(ValidatingDeserializer::new(&validator, &value)).unwrap_err();
+        assert!(error.to_string().starts_with("name: "));
+
+        let value = json!({"name": "Ann", "tags": [], "role": "Admin"});
+        assert!(User::deserialize(ValidatingDeserializer::new(&validator, &value)).is_ok());
+    }
+}
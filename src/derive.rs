@@ -0,0 +1,167 @@
+
+//! Schemas for Rust types: the `FastSchema` trait that
+//! `#[derive(FastSchema)]` implements (with the `derive` feature), and its
+//! impls for standard types.
+
+use crate::builder::{Schema, SchemaBuilder};
+use crate::error::FastSchemaError;
+use crate::schema::SchemaType;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A type whose JSON form has a schema
+pub trait FastSchema {
+    fn schema_builder() -> SchemaBuilder;
+
+    /// The schema with field constraints applied. Wrappers such as `Option`
+    /// and `Box` pass them on to the wrapped type, so `#[schema(email)]`
+    /// works on an `Option<String>` field.
+    fn constrained(constrain: impl FnOnce(SchemaBuilder) -> SchemaBuilder) -> SchemaBuilder
+    where
+        Self: Sized,
+    {
+        constrain(Self::schema_builder())
+    }
+
+    /// The schema, or the first `#[schema(...)]` constraint that does not
+    /// apply to its field's type
+    fn schema() -> Result<SchemaType, FastSchemaError>
+    where
+        Self: Sized,
+    {
+        Self::schema_builder().build()
+    }
+}
+
+macro_rules! impl_schema {
+    ($($ty:ty),* => $builder:expr) => {
+        $(
+            impl FastSchema for $ty {
+                fn schema_builder() -> SchemaBuilder {
+                    $builder
+                }
+            }
+        )*
+    };
+}
+
+impl_schema!(String, str => Schema::string());
+impl_schema!(char => Schema::string().length(1));
+impl_schema!(bool => Schema::boolean());
+impl_schema!(f32, f64 => Schema::number());
+impl_schema!(() => Schema::null());
+impl_schema!(serde_json::Value => Schema::any());
+// 64-bit bounds are not exact as `f64`, so only their sign is checked
+impl_schema!(i64, isize, i128 => Schema::integer());
+impl_schema!(u64, usize, u128 => Schema::integer().min(0));
+
+macro_rules! impl_bounded_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl_schema!($ty => Schema::integer().min(<$ty>::MIN).max(<$ty>::MAX));
+        )*
+    };
+}
+
+impl_bounded_integer!(i8, i16, i32, u8, u16, u32);
+
+/// Optional and nullable, as serde reads a missing key or `null` as `None`
+impl<T: FastSchema> FastSchema for Option<T> {
+    fn schema_builder() -> SchemaBuilder {
+        Self::constrained(|schema| schema)
+    }
+
+    fn constrained(constrain: impl FnOnce(SchemaBuilder) -> SchemaBuilder) -> SchemaBuilder {
+        Schema::union(vec![T::constrained(constrain), Schema::null()]).optional()
+    }
+}
+
+macro_rules! impl_wrapper {
+    ($($wrapper:ident),*) => {
+        $(
+            impl<T: FastSchema + ?Sized> FastSchema for $wrapper<T> {
+                fn schema_builder() -> SchemaBuilder {
+                    T::schema_builder()
+                }
+
+                fn constrained(constrain: impl FnOnce(SchemaBuilder) -> SchemaBuilder) -> SchemaBuilder {
+                    constrain(T::schema_builder())
+                }
+            }
+        )*
+    };
+}
+
+impl_wrapper!(Box, Rc, Arc);
+
+impl<T: FastSchema> FastSchema for Vec<T> {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::array(T::schema_builder())
+    }
+}
+
+impl<T: FastSchema> FastSchema for VecDeque<T> {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::array(T::schema_builder())
+    }
+}
+
+impl<T: FastSchema> FastSchema for [T] {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::array(T::schema_builder())
+    }
+}
+
+impl<T: FastSchema> FastSchema for HashSet<T> {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::array(T::schema_builder()).unique()
+    }
+}
+
+impl<T: FastSchema> FastSchema for BTreeSet<T> {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::array(T::schema_builder()).unique()
+    }
+}
+
+/// Maps with string keys serialize as JSON objects
+impl<V: FastSchema> FastSchema for HashMap<String, V> {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::object().catchall(V::schema_builder())
+    }
+}
+
+impl<V: FastSchema> FastSchema for BTreeMap<String, V> {
+    fn schema_builder() -> SchemaBuilder {
+        Schema::object().catchall(V::schema_builder())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::Validator;
+    use serde_json::json;
+
+    #[test]
+    fn test_std_type_schemas() {
+        let validate = |schema: SchemaType, value: serde_json::Value| Validator::new(schema).unwrap().validate(&value).success;
+
+        assert!(validate(u8::schema().unwrap(), json!(255)));
+        assert!(!validate(u8::schema().unwrap(), json!(256)));
+        assert!(!validate(u64::schema().unwrap(), json!(-1)));
+        assert!(!validate(i32::schema().unwrap(), json!(1.5)));
+        assert!(validate(<Option<String>>::schema().unwrap(), json!(null)));
+        assert!(validate(<Vec<Box<bool>>>::schema().unwrap(), json!([true, false])));
+        assert!(!validate(<HashSet<char>>::schema().unwrap(), json!(["a", "a"])));
+        assert!(!validate(<HashMap<String, f64>>::schema().unwrap(), json!({"a": 1, "b": "x"})));
+
+        // Constraints reach through wrappers
+        let schema = <Option<Arc<String>>>::constrained(|schema| schema.email()).build().unwrap();
+        assert!(validate(schema.clone(), json!("ann@example.com")));
+        assert!(!validate(schema, json!("ann")));
+        assert!(<Option<u8>>::schema_builder().is_optional());
+        assert!(<bool>::constrained(|schema| schema.email()).build().is_err());
+    }
+}
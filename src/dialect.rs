@@ -0,0 +1,657 @@
+
+//! Standard JSON Schema documents. FastSchema's own format tags every node
+//! with a `type`; `Dialect::Draft202012` instead reads a draft 2020-12
+//! document and maps its keywords onto `SchemaType`. `$ref`s become
+//! definitions keyed by their resolved URI, so `$defs`, `$anchor`, `$id`
+//! and recursive references all go through `SchemaType::Ref`.
+//!
+//! Keywords with no `SchemaType` equivalent (`not`, `patternProperties`,
+//! `contains`, ...) are rejected rather than silently ignored.
+//!
+//! `Dialect::ZodToJsonSchema` reads `zod-to-json-schema` output, which
+//! `crate::zod` first rewrites as draft 2020-12.
+
+use crate::error::FastSchemaError;
+use crate::schema::{SchemaMetadata, SchemaType, StringFormat, Unevaluated, UnknownKeys};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// The `$schema` URI of draft 2020-12
+pub const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The `$schema` URI of draft-07, which `zod-to-json-schema` declares
+pub const DRAFT_07: &str = "http://json-schema.org/draft-07/schema";
+
+/// Keywords that change validation but have no `SchemaType` mapping
+const UNSUPPORTED: &[&str] = &[
+    "not",
+    "patternProperties",
+    "dependentRequired",
+    "dependentSchemas",
+    "contains",
+    "minContains",
+    "maxContains",
+    "$dynamicRef",
+    "$dynamicAnchor",
+];
+
+/// Keywords whose values are data, not subschemas
+const DATA_KEYWORDS: &[&str] = &["const", "enum", "default", "examples"];
+
+/// Format of a schema document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Dialect {
+    /// FastSchema's `type`-tagged format
+    #[default]
+    FastSchema,
+    /// JSON Schema draft 2020-12
+    Draft202012,
+    /// Draft-07 as generated by `zod-to-json-schema`
+    ZodToJsonSchema,
+}
+
+impl Dialect {
+    /// The dialect a document declares with `$schema`; anything else is
+    /// read as FastSchema's format
+    pub fn detect(document: &Value) -> Dialect {
+        match document.get("$schema").and_then(Value::as_str) {
+            Some(uri) if uri.trim_end_matches('#') == DRAFT_2020_12 => Dialect::Draft202012,
+            Some(uri) if uri.trim_end_matches('#') == DRAFT_07 => Dialect::ZodToJsonSchema,
+            _ => Dialect::FastSchema,
+        }
+    }
+}
+
+/// A translated draft 2020-12 document
+pub(crate) struct Translated {
+    pub schema: SchemaType,
+    /// Targets of every `$ref`, keyed like `SchemaType::Ref::reference`
+    pub definitions: HashMap<String, SchemaType>,
+}
+
+/// Translate a draft 2020-12 document
+pub(crate) fn translate(document: &Value) -> Result<Translated, FastSchemaError> {
+    let mut translator = Translator::new(document);
+    let schema = translator.translate(document, "")?;
+    Ok(Translated { schema, definitions: translator.definitions })
+}
+
+/// Translates the schemas of one document, sharing `$ref` targets between
+/// them (e.g. every schema of an OpenAPI document)
+pub(crate) struct Translator<'d> {
+    /// The document's base URI
+    base: String,
+    /// Schema resources by `$id` (the root under its own `$id` or "")
+    resources: HashMap<String, &'d Value>,
+    /// `$anchor`s by resource and name
+    anchors: HashMap<(String, String), &'d Value>,
+    definitions: HashMap<String, SchemaType>,
+    /// References seen but not yet translated
+    pending: Vec<String>,
+}
+
+impl<'d> Translator<'d> {
+    pub(crate) fn new(document: &'d Value) -> Self {
+        let mut translator = Translator {
+            base: resource_uri(document, ""),
+            resources: HashMap::new(),
+            anchors: HashMap::new(),
+            definitions: HashMap::new(),
+            pending: Vec::new(),
+        };
+        translator.index(document, "", &mut String::new());
+        translator
+    }
+
+    /// Translate the schema at `pointer` in the document, and everything it
+    /// references
+    pub(crate) fn translate(&mut self, schema: &Value, pointer: &str) -> Result<SchemaType, FastSchemaError> {
+        let base = self.base.clone();
+        let schema = self.node(schema, &base, &mut pointer.to_string())?;
+        while let Some(reference) = self.pending.pop() {
+            if self.definitions.contains_key(&reference) {
+                continue;
+            }
+            let (target, base) = self.resolve(&reference)?;
+            // Reserve the key first so recursive references terminate
+            self.definitions.insert(reference.clone(), SchemaType::Any);
+            let mut pointer = match reference.find("#/") {
+                Some(index) => reference[index + 1..].to_string(),
+                None => String::new(),
+            };
+            let target = self.node(target, &base, &mut pointer)?;
+            self.definitions.insert(reference, target);
+        }
+        Ok(schema)
+    }
+
+    /// Targets of every `$ref` translated so far
+    pub(crate) fn into_definitions(self) -> HashMap<String, SchemaType> {
+        self.definitions
+    }
+
+    /// Register every `$id` and `$anchor` in the document
+    fn index(&mut self, node: &'d Value, base: &str, pointer: &mut String) {
+        match node {
+            Value::Object(obj) => {
+                let base = resource_uri(node, base);
+                if obj.contains_key("$id") || pointer.is_empty() {
+                    self.resources.insert(base.clone(), node);
+                }
+                if let Some(anchor) = obj.get("$anchor").and_then(Value::as_str) {
+                    self.anchors.insert((base.clone(), anchor.to_string()), node);
+                }
+                for (keyword, value) in obj {
+                    if DATA_KEYWORDS.contains(&keyword.as_str()) {
+                        continue;
+                    }
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(keyword);
+                    self.index(value, &base, pointer);
+                    pointer.truncate(len);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let len = pointer.len();
+                    pointer.push_str(&format!("/{}", index));
+                    self.index(item, base, pointer);
+                    pointer.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The node a resolved reference points at, and its base URI
+    fn resolve(&self, reference: &str) -> Result<(&'d Value, String), FastSchemaError> {
+        let (uri, fragment) = match reference.find('#') {
+            Some(index) => (&reference[..index], &reference[index + 1..]),
+            None => (reference, ""),
+        };
+        let unresolved = || FastSchemaError::SchemaCompilation(format!("Unresolved $ref '{}'", reference));
+
+        let resource = *self.resources.get(uri).ok_or_else(unresolved)?;
+        let target = if fragment.is_empty() {
+            Some(resource)
+        } else if fragment.starts_with('/') {
+            resource.pointer(&decode_fragment(fragment))
+        } else {
+            self.anchors.get(&(uri.to_string(), fragment.to_string())).copied()
+        };
+        target.map(|target| (target, uri.to_string())).ok_or_else(unresolved)
+    }
+
+    fn node(&mut self, node: &Value, base: &str, pointer: &mut String) -> Result<SchemaType, FastSchemaError> {
+        let obj = match node {
+            Value::Bool(true) => return Ok(SchemaType::Any),
+            Value::Bool(false) => return Ok(never()),
+            Value::Object(obj) => obj,
+            _ => return Err(invalid(pointer, "a schema must be an object or a boolean")),
+        };
+        let base = resource_uri(node, base);
+
+        if let Some(keyword) = UNSUPPORTED.iter().find(|keyword| obj.contains_key(**keyword)) {
+            return Err(FastSchemaError::UnsupportedOperation(format!(
+                "'{}' at '#{}' has no FastSchema equivalent",
+                keyword, pointer
+            )));
+        }
+
+        let mut parts = Vec::new();
+        match obj.get("type") {
+            Some(Value::String(name)) => parts.push(self.typed(name, obj, &base, pointer)?),
+            Some(Value::Array(names)) => {
+                let mut options = Vec::new();
+                for name in names {
+                    let name = name.as_str().ok_or_else(|| invalid(pointer, "'type' entries must be strings"))?;
+                    options.push(self.typed(name, obj, &base, pointer)?);
+                }
+                parts.push(any_of(options));
+            }
+            Some(_) => return Err(invalid(pointer, "'type' must be a string or an array")),
+            // Untyped constraints only apply to values of their type
+            None => {
+                for name in ["string", "number", "array", "object"] {
+                    if constrains(name, obj) {
+                        parts.push(SchemaType::Conditional {
+                            condition: Box::new(bare(name)),
+                            then_schema: Box::new(self.typed(name, obj, &base, pointer)?),
+                            else_schema: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(value) = obj.get("const") {
+            parts.push(SchemaType::Const { value: value.clone() });
+        }
+        if let Some(values) = obj.get("enum") {
+            let values = values.as_array().ok_or_else(|| invalid(pointer, "'enum' must be an array"))?;
+            parts.push(any_of(values.iter().map(|value| SchemaType::Const { value: value.clone() }).collect()));
+        }
+        if let Some(reference) = obj.get("$ref") {
+            let reference = reference.as_str().ok_or_else(|| invalid(pointer, "'$ref' must be a string"))?;
+            let reference = resolve_uri(&base, reference);
+            self.pending.push(reference.clone());
+            parts.push(SchemaType::Ref { reference });
+        }
+        for keyword in ["allOf", "anyOf", "oneOf"] {
+            if let Some(schemas) = obj.get(keyword) {
+                let schemas = self.list(schemas, &base, pointer, keyword)?;
+                parts.push(match keyword {
+                    "allOf" => SchemaType::AllOf { schemas, unevaluated_properties: None, unevaluated_items: None },
+                    "anyOf" => SchemaType::AnyOf { schemas, unevaluated_properties: None, unevaluated_items: None },
+                    _ => SchemaType::OneOf { schemas, unevaluated_properties: None, unevaluated_items: None },
+                });
+            }
+        }
+        if let Some(condition) = obj.get("if") {
+            let branch = |translator: &mut Self, keyword: &str, pointer: &mut String| {
+                obj.get(keyword)
+                    .map(|schema| translator.child(schema, &base, pointer, &[keyword]))
+                    .transpose()
+            };
+            let then_schema = branch(self, "then", pointer)?.unwrap_or(SchemaType::Any);
+            let else_schema = branch(self, "else", pointer)?;
+            parts.push(SchemaType::Conditional {
+                condition: Box::new(self.child(condition, &base, pointer, &["if"])?),
+                then_schema: Box::new(then_schema),
+                else_schema: else_schema.map(Box::new),
+            });
+        }
+
+        let unevaluated_properties = self.unevaluated(obj, "unevaluatedProperties", &base, pointer)?;
+        let unevaluated_items = self.unevaluated(obj, "unevaluatedItems", &base, pointer)?;
+        let schema = if unevaluated_properties.is_some() || unevaluated_items.is_some() {
+            SchemaType::AllOf { schemas: parts, unevaluated_properties, unevaluated_items }
+        } else {
+            match parts.len() {
+                0 => SchemaType::Any,
+                1 => parts.remove(0),
+                _ => SchemaType::AllOf { schemas: parts, unevaluated_properties: None, unevaluated_items: None },
+            }
+        };
+        Ok(schema.with_metadata(SchemaMetadata::read(obj)))
+    }
+
+    /// The keywords of one JSON type
+    fn typed(&mut self, name: &str, obj: &Map<String, Value>, base: &str, pointer: &mut String) -> Result<SchemaType, FastSchemaError> {
+        let number = |keyword: &str| obj.get(keyword).and_then(Value::as_f64);
+
+        Ok(match name {
+            "null" => SchemaType::Null,
+            "boolean" => SchemaType::Boolean,
+            "string" => string_schema(
+                count(obj, "minLength", pointer)?,
+                count(obj, "maxLength", pointer)?,
+                obj.get("pattern").and_then(Value::as_str).map(str::to_string),
+                obj.get("format").and_then(Value::as_str).map(StringFormat::from_name),
+            ),
+            "number" | "integer" => {
+                let bounds = [number("minimum"), number("maximum"), number("exclusiveMinimum"), number("exclusiveMaximum")];
+                number_schema(bounds, name == "integer", number("multipleOf"))
+            }
+            "array" => {
+                let items = obj.get("items").map(|items| self.child(items, base, pointer, &["items"])).transpose()?;
+                let (min_items, max_items) = (count(obj, "minItems", pointer)?, count(obj, "maxItems", pointer)?);
+                let unique_items = obj.get("uniqueItems").and_then(Value::as_bool).unwrap_or(false);
+                let prefix_items = match obj.get("prefixItems") {
+                    Some(schemas) => self.list(schemas, base, pointer, "prefixItems")?,
+                    None => Vec::new(),
+                };
+
+                if prefix_items.is_empty() {
+                    array_schema(items.unwrap_or(SchemaType::Any), min_items, max_items, unique_items)
+                } else {
+                    let tuple = SchemaType::Tuple { prefix_items, items: items.map(Box::new) };
+                    if min_items.is_none() && max_items.is_none() && !unique_items {
+                        tuple
+                    } else {
+                        SchemaType::AllOf {
+                            schemas: vec![array_schema(SchemaType::Any, min_items, max_items, unique_items), tuple],
+                            unevaluated_properties: None,
+                            unevaluated_items: None,
+                        }
+                    }
+                }
+            }
+            "object" => {
+                let mut properties = HashMap::new();
+                if let Some(schemas) = obj.get("properties") {
+                    let schemas = schemas.as_object().ok_or_else(|| invalid(pointer, "'properties' must be an object"))?;
+                    for (key, schema) in schemas {
+                        properties.insert(key.clone(), self.child(schema, base, pointer, &["properties", key])?);
+                    }
+                }
+                let required = obj
+                    .get("required")
+                    .map(|names| {
+                        names.as_array()
+                            .and_then(|names| names.iter().map(|name| name.as_str().map(str::to_string)).collect())
+                            .ok_or_else(|| invalid(pointer, "'required' must be an array of strings"))
+                    })
+                    .transpose()?;
+                let (additional_properties, catchall) = match obj.get("additionalProperties") {
+                    None | Some(Value::Bool(true)) => (UnknownKeys::Passthrough, None),
+                    Some(Value::Bool(false)) => (UnknownKeys::Strict, None),
+                    Some(schema) => {
+                        let schema = self.child(schema, base, pointer, &["additionalProperties"])?;
+                        (UnknownKeys::Passthrough, Some(Box::new(schema)))
+                    }
+                };
+
+                let (min_properties, max_properties) =
+                    (count(obj, "minProperties", pointer)?, count(obj, "maxProperties", pointer)?);
+                let object =
+                    object_schema(properties, required, additional_properties, catchall, min_properties, max_properties);
+                match obj.get("propertyNames") {
+                    // Keys are checked as a map's keys, once the value is known to be an object
+                    Some(names) => SchemaType::AllOf {
+                        schemas: vec![
+                            object,
+                            SchemaType::Conditional {
+                                condition: Box::new(bare("object")),
+                                then_schema: Box::new(SchemaType::Map {
+                                    keys: Box::new(self.child(names, base, pointer, &["propertyNames"])?),
+                                    values: Box::new(SchemaType::Any),
+                                }),
+                                else_schema: None,
+                            },
+                        ],
+                        unevaluated_properties: None,
+                        unevaluated_items: None,
+                    },
+                    None => object,
+                }
+            }
+            _ => return Err(invalid(pointer, &format!("unknown type '{}'", name))),
+        })
+    }
+
+    fn child(&mut self, node: &Value, base: &str, pointer: &mut String, segments: &[&str]) -> Result<SchemaType, FastSchemaError> {
+        let len = pointer.len();
+        for segment in segments {
+            pointer.push('/');
+            pointer.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+        }
+        let schema = self.node(node, base, pointer);
+        pointer.truncate(len);
+        schema
+    }
+
+    fn list(&mut self, schemas: &Value, base: &str, pointer: &mut String, keyword: &str) -> Result<Vec<SchemaType>, FastSchemaError> {
+        let schemas = schemas.as_array().ok_or_else(|| invalid(pointer, &format!("'{}' must be an array", keyword)))?;
+        schemas
+            .iter()
+            .enumerate()
+            .map(|(index, schema)| self.child(schema, base, pointer, &[keyword, &index.to_string()]))
+            .collect()
+    }
+
+    fn unevaluated(
+        &mut self,
+        obj: &Map<String, Value>,
+        keyword: &str,
+        base: &str,
+        pointer: &mut String,
+    ) -> Result<Option<Unevaluated>, FastSchemaError> {
+        Ok(match obj.get(keyword) {
+            None => None,
+            Some(Value::Bool(allowed)) => Some(Unevaluated::Allowed(*allowed)),
+            Some(schema) => Some(Unevaluated::Schema(Box::new(self.child(schema, base, pointer, &[keyword])?))),
+        })
+    }
+}
+
+/// Whether `obj` has keywords for the JSON type `name`
+fn constrains(name: &str, obj: &Map<String, Value>) -> bool {
+    let keywords: &[&str] = match name {
+        "string" => &["minLength", "maxLength", "pattern", "format"],
+        "number" => &["minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum", "multipleOf"],
+        "array" => &["items", "prefixItems", "minItems", "maxItems", "uniqueItems"],
+        _ => &["properties", "required", "additionalProperties", "propertyNames", "minProperties", "maxProperties"],
+    };
+    keywords.iter().any(|keyword| obj.contains_key(*keyword))
+}
+
+/// A schema accepting every value of the JSON type `name`
+fn bare(name: &str) -> SchemaType {
+    match name {
+        "string" => string_schema(None, None, None, None),
+        "number" => number_schema([None; 4], false, None),
+        "array" => array_schema(SchemaType::Any, None, None, false),
+        _ => object_schema(HashMap::new(), None, UnknownKeys::Passthrough, None, None, None),
+    }
+}
+
+fn string_schema(
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<String>,
+    format: Option<StringFormat>,
+) -> SchemaType {
+    SchemaType::String {
+        min_length,
+        max_length,
+        pattern,
+        format,
+        content_encoding: None,
+        content_media_type: None,
+        content_schema: None,
+        country: None,
+        uuid_version: None,
+        email_options: None,
+        min_date: None,
+        max_date: None,
+        offset_required: false,
+        precision: None,
+        allow_idn: false,
+        length_unit: Default::default(),
+    }
+}
+
+/// `bounds` are minimum, maximum, exclusive minimum and exclusive maximum
+fn number_schema(bounds: [Option<f64>; 4], integer: bool, multiple_of: Option<f64>) -> SchemaType {
+    let [min, max, exclusive_min, exclusive_max] = bounds;
+    SchemaType::Number {
+        min,
+        max,
+        exclusive_min,
+        exclusive_max,
+        integer,
+        multiple_of,
+        allow_non_finite: false,
+        strict_integer: false,
+        multiple_of_tolerance: None,
+    }
+}
+
+fn array_schema(items: SchemaType, min_items: Option<usize>, max_items: Option<usize>, unique_items: bool) -> SchemaType {
+    SchemaType::Array {
+        items: Box::new(items),
+        min_items,
+        max_items,
+        unique_items,
+        unique_by: None,
+        sorted: None,
+        sorted_by: None,
+    }
+}
+
+fn object_schema(
+    properties: HashMap<String, SchemaType>,
+    required: Option<Vec<String>>,
+    additional_properties: UnknownKeys,
+    catchall: Option<Box<SchemaType>>,
+    min_properties: Option<usize>,
+    max_properties: Option<usize>,
+) -> SchemaType {
+    SchemaType::Object {
+        properties,
+        required,
+        additional_properties,
+        catchall,
+        min_properties,
+        max_properties,
+        read_only: None,
+        write_only: None,
+        rules: None,
+    }
+}
+
+/// A non-negative integer keyword
+fn count(obj: &Map<String, Value>, keyword: &str, pointer: &str) -> Result<Option<usize>, FastSchemaError> {
+    obj.get(keyword)
+        .map(|value| {
+            value.as_u64()
+                .map(|count| count as usize)
+                .ok_or_else(|| invalid(pointer, &format!("'{}' must be a non-negative integer", keyword)))
+        })
+        .transpose()
+}
+
+/// The `false` schema: an `anyOf` with no branches matches nothing
+fn never() -> SchemaType {
+    any_of(Vec::new())
+}
+
+fn any_of(mut schemas: Vec<SchemaType>) -> SchemaType {
+    if schemas.len() == 1 {
+        return schemas.remove(0);
+    }
+    SchemaType::AnyOf { schemas, unevaluated_properties: None, unevaluated_items: None }
+}
+
+fn invalid(pointer: &str, message: &str) -> FastSchemaError {
+    FastSchemaError::SchemaCompilation(format!("Invalid schema at '#{}': {}", pointer, message))
+}
+
+/// The base URI of `node`: its `$id` resolved against `base`, without a fragment
+fn resource_uri(node: &Value, base: &str) -> String {
+    match node.get("$id").and_then(Value::as_str) {
+        Some(id) => {
+            let uri = resolve_uri(base, id);
+            match uri.find('#') {
+                Some(index) => uri[..index].to_string(),
+                None => uri,
+            }
+        }
+        None => base.to_string(),
+    }
+}
+
+/// Resolve a URI reference against a base URI. Covers fragments, absolute
+/// URIs, absolute paths and sibling paths, which is what schema
+/// documents use in practice.
+fn resolve_uri(base: &str, reference: &str) -> String {
+    if reference.starts_with('#') {
+        format!("{}{}", base, reference)
+    } else if reference.contains(':') {
+        reference.to_string()
+    } else if reference.starts_with('/') {
+        match base.find("://").and_then(|scheme| base[scheme + 3..].find('/').map(|path| scheme + 3 + path)) {
+            Some(path) => format!("{}{}", &base[..path], reference),
+            None => format!("{}{}", base, reference),
+        }
+    } else {
+        match base.rfind('/') {
+            Some(index) => format!("{}{}", &base[..=index], reference),
+            None => reference.to_string(),
+        }
+    }
+}
+
+/// Percent-decode a JSON pointer fragment
+fn decode_fragment(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = fragment.get(index + 1..index + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::Validator;
+    use serde_json::json;
+
+    /// Suite files that test what this dialect deliberately leaves out:
+    /// documents the suite serves from localhost, custom `$vocabulary`
+    /// metaschemas, and `format` as an annotation (it is always asserted here)
+    const KNOWN_UNSUPPORTED: &[&str] = &["refRemote.json", "vocabulary.json", "format.json"];
+
+    /// Run groups in the JSON-Schema-Test-Suite file format
+    fn run_suite(groups: &Value) -> Vec<String> {
+        let mut failures = Vec::new();
+        for group in groups.as_array().unwrap() {
+            let validator = match Validator::from_json_with_dialect(&group["schema"], Dialect::Draft202012) {
+                Ok(validator) => validator,
+                Err(FastSchemaError::UnsupportedOperation(_)) => continue,
+                Err(e) => {
+                    failures.push(format!("{}: {}", group["description"], e));
+                    continue;
+                }
+            };
+            for test in group["tests"].as_array().unwrap() {
+                if validator.validate(&test["data"]).success != test["valid"].as_bool().unwrap() {
+                    failures.push(format!("{} / {}", group["description"], test["description"]));
+                }
+            }
+        }
+        failures
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Dialect::detect(&json!({"$schema": DRAFT_2020_12, "type": "string"})), Dialect::Draft202012);
+        assert_eq!(Dialect::detect(&json!({"type": "string"})), Dialect::FastSchema);
+        assert_eq!(resolve_uri("https://example.com/a/b.json", "c.json"), "https://example.com/a/c.json");
+        assert_eq!(resolve_uri("https://example.com/a/b.json", "/c.json"), "https://example.com/c.json");
+        assert_eq!(resolve_uri("", "#/$defs/x"), "#/$defs/x");
+    }
+
+    #[test]
+    fn test_draft_2020_12_suite() {
+        let failures = run_suite(&serde_json::from_str(include_str!("draft2020-12.json")).unwrap());
+        assert!(failures.is_empty(), "{:#?}", failures);
+
+        // A full JSON-Schema-Test-Suite checkout can be run with
+        // JSON_SCHEMA_TEST_SUITE=/path/to/suite cargo test test_draft_2020_12_suite
+        if let Ok(suite) = std::env::var("JSON_SCHEMA_TEST_SUITE") {
+            let dir = std::path::Path::new(&suite).join("tests/draft2020-12");
+            let mut failures = Vec::new();
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                if path.extension().is_some_and(|extension| extension == "json") && !KNOWN_UNSUPPORTED.contains(&name.as_str()) {
+                    let groups: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+                    failures.extend(run_suite(&groups).into_iter().map(|failure| format!("{}: {}", name, failure)));
+                }
+            }
+            assert!(failures.is_empty(), "{:#?}", failures);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_keyword() {
+        let schema = json!({"$schema": DRAFT_2020_12, "properties": {"a": {"not": {"type": "string"}}}});
+        let error = Validator::from_json(&schema).err().unwrap();
+        assert!(error.to_string().contains("'not' at '#/properties/a'"));
+    }
+}
@@ -0,0 +1,181 @@
+
+//! Environment variables as typed configuration. `EnvValidator` picks the
+//! variables an object schema declares, reads each string by the schema of
+//! its property (as `FormValidator` does, with lists comma-separated) and
+//! fills in the `default` of any that are unset or empty. Every other
+//! variable in the environment is ignored.
+
+use crate::error::{FastSchemaError, PathSegment, ValidationError, ValidationResult};
+use crate::form::Coercion;
+use crate::schema::SchemaType;
+use crate::validator::Validator;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Validates environment variables against an object schema
+pub struct EnvValidator {
+    validator: Validator,
+}
+
+impl EnvValidator {
+    /// Schemas built in Rust have no `default` annotations; use `from_json`
+    /// for those
+    pub fn new(schema: SchemaType) -> Result<Self, FastSchemaError> {
+        Ok(Self::from_validator(Validator::new(schema)?))
+    }
+
+    /// Compile a schema document, keeping its `default`s
+    pub fn from_json(schema: &Value) -> Result<Self, FastSchemaError> {
+        Ok(Self::from_validator(Validator::from_json(schema)?))
+    }
+
+    pub fn from_validator(validator: Validator) -> Self {
+        Self { validator }
+    }
+
+    pub fn validator(&self) -> &Validator {
+        &self.validator
+    }
+
+    /// Validate the environment of this process; on success `data` holds
+    /// the typed configuration
+    pub fn validate_env(&self) -> ValidationResult {
+        self.validate_vars(std::env::vars())
+    }
+
+    /// Validate `(name, value)` pairs, such as a `.env` file's
+    pub fn validate_vars<I, K, V>(&self, vars: I) -> ValidationResult
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.validator.validate(&env_config(&self.validator, collect(vars)))
+    }
+
+    /// Validate a flat `{"NAME": "value"}` object; values that are not
+    /// strings are validated as they are
+    pub fn validate_map(&self, vars: &Map<String, Value>) -> ValidationResult {
+        self.validator.validate(&env_config(&self.validator, vars.clone().into_iter().collect()))
+    }
+
+    /// Validate the environment of this process and deserialize the
+    /// configuration into `T`
+    pub fn parse_env<T: DeserializeOwned>(&self) -> Result<T, Vec<ValidationError>> {
+        self.validator.parse_into(&env_config(&self.validator, collect(std::env::vars())))
+    }
+}
+
+fn collect<I, K, V>(vars: I) -> HashMap<String, Value>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    vars.into_iter().map(|(name, value)| (name.into(), Value::String(value.into()))).collect()
+}
+
+/// The configuration object: every declared variable that is set, coerced
+/// by its schema, or else its default
+pub(crate) fn env_config(validator: &Validator, mut vars: HashMap<String, Value>) -> Value {
+    let coercion = Coercion::new(validator);
+    let mut declared = Vec::new();
+    declared_properties(&coercion, &validator.compiled_schema.schema, &mut declared);
+
+    let mut config = Map::new();
+    for (name, schema) in declared {
+        if config.contains_key(name) {
+            continue;
+        }
+        let set = vars.remove(name).and_then(|value| match (value, coercion.resolve(schema)) {
+            (Value::String(text), SchemaType::Array { .. } | SchemaType::Set { .. } | SchemaType::Tuple { .. }) => {
+                let items = text.split(',').map(|item| Value::String(item.trim().to_string()));
+                coercion.coerce_field(items.filter(|item| item != "").collect(), schema)
+            }
+            (value, _) => coercion.coerce(value, schema),
+        });
+        let default = || {
+            let metadata = validator.compiled_schema.metadata_at(&[PathSegment::Key(name.clone())])?;
+            coercion.coerce(metadata.default.clone()?, schema)
+        };
+        if let Some(value) = set.or_else(default) {
+            config.insert(name.clone(), value);
+        }
+    }
+    Value::Object(config)
+}
+
+/// Properties of an object schema, including those of its combinators
+fn declared_properties<'a>(coercion: &Coercion<'a>, schema: &'a SchemaType, declared: &mut Vec<(&'a String, &'a SchemaType)>) {
+    match coercion.resolve(schema) {
+        SchemaType::Object { properties, .. } => declared.extend(properties.iter()),
+        SchemaType::Union { options: schemas, .. }
+        | SchemaType::OneOf { schemas, .. }
+        | SchemaType::AnyOf { schemas, .. }
+        | SchemaType::AllOf { schemas, .. }
+        | SchemaType::Intersection { schemas } => {
+            for schema in schemas {
+                declared_properties(coercion, schema, declared);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    struct Config {
+        database_url: String,
+        port: u16,
+        debug: bool,
+        allowed_hosts: Vec<String>,
+    }
+
+    #[test]
+    fn test_validate_env_vars() {
+        let env = EnvValidator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "DATABASE_URL": {"type": "string", "minLength": 1},
+                "PORT": {"type": "number", "integer": true, "min": 1, "max": 65535, "default": 8080},
+                "DEBUG": {"type": "boolean", "default": false},
+                "ALLOWED_HOSTS": {"type": "array", "items": {"type": "string"}, "default": []}
+            },
+            "required": ["DATABASE_URL", "PORT", "DEBUG", "ALLOWED_HOSTS"]
+        }))
+        .unwrap();
+
+        let vars = vec![
+            ("DATABASE_URL", "postgres://db/app"),
+            ("DEBUG", "1"),
+            ("PORT", ""),
+            ("ALLOWED_HOSTS", "a.example, b.example,"),
+            ("HOME", "/root"),
+        ];
+        let result = env.validate_vars(vars);
+        assert!(result.success, "{:?}", result.errors);
+        assert_eq!(
+            result.data,
+            Some(json!({
+                "DATABASE_URL": "postgres://db/app",
+                "PORT": 8080,
+                "DEBUG": true,
+                "ALLOWED_HOSTS": ["a.example", "b.example"]
+            }))
+        );
+
+        let result = env.validate_map(json!({"PORT": "http"}).as_object().unwrap());
+        let paths: Vec<String> = result.errors.iter().map(|error| error.path.to_string()).collect();
+        assert_eq!(paths, vec!["DATABASE_URL", "PORT"]);
+
+        let config: Config = env.validator().parse_into(&env_config(env.validator(), collect(vec![("DATABASE_URL", "x"), ("PORT", "5432")]))).unwrap();
+        assert_eq!(config, Config { database_url: "x".into(), port: 5432, debug: false, allowed_hosts: Vec::new() });
+    }
+}
@@ -1,6 +1,8 @@
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Validation result that can contain multiple errors
@@ -9,22 +11,356 @@ pub struct ValidationResult {
     pub success: bool,
     pub data: Option<serde_json::Value>,
     pub errors: Vec<ValidationError>,
+    /// Advisory findings (`Severity::Warning`), which don't affect `success`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationError>,
+    /// `early_exit` / `max_errors` stopped validation, so more errors may exist
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub errors_truncated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub performance: Option<PerformanceStats>,
 }
 
-/// Individual validation error
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Individual validation error. Serializes with a `pointer` (RFC 6901)
+/// next to `path` unless the path uses `PathStyle::Legacy`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct ValidationError {
-    pub path: String,
+    pub path: ErrorPath,
     pub message: String,
     pub code: ErrorCode,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub expected: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub received: Option<String>,
+    /// Details for programmatic use: `expected` / `received` when known,
+    /// plus the keyword's bounds (`min`, `max`, ...). Always an object.
+    #[serde(default = "empty_params")]
+    pub params: serde_json::Value,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Where the offending value is in the source text, when validating
+    /// raw JSON with `ValidationOptions::source_locations`
+    #[serde(default)]
+    pub location: Option<SourceLocation>,
+}
+
+/// A value's position in JSON source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// 1-based
+    pub line: usize,
+    /// 1-based, in characters
+    pub column: usize,
+    /// Byte offset where the value starts
+    pub start: usize,
+    /// Byte offset just past the value
+    pub end: usize,
+}
+
+fn empty_params() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+fn params_are_empty(params: &serde_json::Value) -> bool {
+    params.as_object().is_none_or(serde_json::Map::is_empty)
+}
+
+/// Whether a finding fails validation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    /// Reported in `ValidationResult::warnings` without failing validation
+    Warning,
+}
+
+/// A step in a data path
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    Index(usize),
+    Key(String),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathSegment::Index(index) => write!(f, "{}", index),
+            PathSegment::Key(key) => f.write_str(key),
+        }
+    }
+}
+
+/// How error paths are serialized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathStyle {
+    /// `["user", 0, "name"]`, with `"pointer": "/user/0/name"`
+    #[default]
+    Segments,
+    /// `"user.[0].name"`, without a pointer
+    Legacy,
 }
 
+/// Location of an error in the data. Displays (and compares with
+/// strings) in the legacy `user.[0].name` form.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorPath {
+    segments: Vec<PathSegment>,
+    style: PathStyle,
+}
+
+impl ErrorPath {
+    pub fn new(segments: Vec<PathSegment>) -> Self {
+        Self { segments, style: PathStyle::default() }
+    }
+
+    /// Parse a legacy path such as `profile.emails.[0]` (or
+    /// `profile.emails[0]`)
+    pub fn parse(path: &str) -> Self {
+        let mut segments = Vec::new();
+
+        for part in path.split('.').filter(|part| !part.is_empty()) {
+            let (key, mut indexes) = match part.find('[') {
+                Some(start) => (&part[..start], &part[start..]),
+                None => (part, ""),
+            };
+
+            let mut parsed = Vec::new();
+            while let Some(index) = indexes.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+                match index.0.parse() {
+                    Ok(i) => parsed.push(PathSegment::Index(i)),
+                    Err(_) => break,
+                }
+                indexes = index.1;
+            }
+
+            if !indexes.is_empty() {
+                // Not an index suffix, so the brackets are part of the key
+                segments.push(PathSegment::Key(part.to_string()));
+                continue;
+            }
+            if !key.is_empty() {
+                segments.push(PathSegment::Key(key.to_string()));
+            }
+            segments.extend(parsed);
+        }
+
+        Self::new(segments)
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// This path with `key` appended
+    pub fn child(mut self, key: &str) -> Self {
+        self.segments.push(PathSegment::Key(key.to_string()));
+        self
+    }
+
+    /// RFC 6901 JSON Pointer, e.g. `/user/0/name`
+    pub fn pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| format!("/{}", segment.to_string().replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    /// `users[0].name`, for people rather than programs
+    pub fn display_path(&self) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Index(index) => out.push_str(&format!("[{}]", index)),
+                PathSegment::Key(key) if out.is_empty() => out.push_str(key),
+                PathSegment::Key(key) => {
+                    out.push('.');
+                    out.push_str(key);
+                }
+            }
+        }
+        out
+    }
+
+    /// The value at this path in `data`
+    pub fn resolve<'a>(&self, data: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+        self.segments.iter().try_fold(data, |current, segment| match segment {
+            PathSegment::Key(key) => current.as_object()?.get(key),
+            PathSegment::Index(index) => current.as_array()?.get(*index),
+        })
+    }
+
+    pub fn style(&self) -> PathStyle {
+        self.style
+    }
+
+    pub fn set_style(&mut self, style: PathStyle) {
+        self.style = style;
+    }
+}
+
+impl fmt::Display for ErrorPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            match segment {
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Key(key) => f.write_str(key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The style only affects serialization
+impl PartialEq for ErrorPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.segments == other.segments
+    }
+}
+
+impl PartialEq<str> for ErrorPath {
+    fn eq(&self, other: &str) -> bool {
+        let legacy = self.to_string();
+        legacy == other
+    }
+}
+
+impl PartialEq<&str> for ErrorPath {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<String> for ErrorPath {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl From<&str> for ErrorPath {
+    fn from(path: &str) -> Self {
+        Self::parse(path)
+    }
+}
+
+impl From<String> for ErrorPath {
+    fn from(path: String) -> Self {
+        Self::parse(&path)
+    }
+}
+
+impl Serialize for ErrorPath {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.style {
+            PathStyle::Segments => self.segments.serialize(serializer),
+            PathStyle::Legacy => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorPath {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Segments(Vec<PathSegment>),
+            Legacy(String),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Segments(segments) => Self::new(segments),
+            Repr::Legacy(path) => Self { style: PathStyle::Legacy, ..Self::parse(&path) },
+        })
+    }
+}
+
+impl Serialize for ValidationError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Repr<'a> {
+            path: &'a ErrorPath,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pointer: Option<String>,
+            message: &'a str,
+            code: &'a ErrorCode,
+            #[serde(skip_serializing_if = "params_are_empty")]
+            params: &'a serde_json::Value,
+            severity: Severity,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            location: Option<SourceLocation>,
+        }
+
+        Repr {
+            path: &self.path,
+            pointer: match self.path.style {
+                PathStyle::Segments => Some(self.path.pointer()),
+                PathStyle::Legacy => None,
+            },
+            message: &self.message,
+            code: &self.code,
+            params: &self.params,
+            severity: self.severity,
+            location: self.location,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// `ValidationResult::diff` output. Errors are matched by path and code.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDiff {
+    /// In the earlier result only
+    pub resolved: Vec<ValidationError>,
+    /// In the later result only
+    #[serde(rename = "new")]
+    pub introduced: Vec<ValidationError>,
+    /// In both, as the later result reports them
+    pub persisting: Vec<ValidationError>,
+}
+
+/// Settings for `ValidationResult::to_pretty_string`
+#[derive(Debug, Clone, Copy)]
+pub struct PrettyOptions<'a> {
+    /// Highlight with ANSI escape codes, for terminals
+    pub color: bool,
+    /// The validated data, to show the offending values
+    pub data: Option<&'a serde_json::Value>,
+    /// Longest value snippet, in characters
+    pub max_snippet_length: usize,
+}
+
+impl Default for PrettyOptions<'_> {
+    fn default() -> Self {
+        Self { color: false, data: None, max_snippet_length: 60 }
+    }
+}
+
+/// `ValidationResult::flatten` output
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenedErrors {
+    pub form_errors: Vec<String>,
+    pub field_errors: BTreeMap<String, Vec<String>>,
+}
+
+/// What an error map is given for each error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorIssue {
+    pub code: ErrorCode,
+    pub path: ErrorPath,
+    /// The error's `params`, plus the default `message`
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Rewrites error messages, like Zod's `errorMap`; `None` keeps the
+/// default message
+pub type ErrorMap = Arc<dyn Fn(&ErrorIssue) -> Option<String> + Send + Sync>;
+
 /// Performance statistics for optimization
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PerformanceStats {
@@ -35,34 +371,51 @@ pub struct PerformanceStats {
 }
 
 /// Error codes for programmatic handling
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ErrorCode {
     // Type errors
     InvalidType,
+    ConstMismatch,
 
     // String errors
     StringTooShort,
     StringTooLong,
     StringPatternMismatch,
     StringFormatInvalid,
+    StringContentEncodingInvalid,
+    StringContentMediaTypeInvalid,
+    StringDateTooEarly,
+    StringDateTooLate,
 
     // Number errors
     NumberTooSmall,
     NumberTooLarge,
     NumberNotInteger,
     NumberNotMultipleOf,
+    NumberPrecisionExceeded,
+    NumberScaleExceeded,
 
     // Array errors
     ArrayTooShort,
     ArrayTooLong,
     ArrayNotUnique,
+    ArrayNotSorted,
     ArrayItemInvalid,
+    ArrayUnevaluatedItem,
 
     // Object errors
     ObjectMissingProperty,
     ObjectAdditionalProperty,
     ObjectPropertyInvalid,
+    ObjectTooFewProperties,
+    ObjectTooManyProperties,
+    ObjectUnevaluatedProperty,
+    ObjectReadOnlyProperty,
+    ObjectWriteOnlyProperty,
+    ObjectMutuallyExclusive,
+    ObjectMissingAlternative,
+    ObjectComparisonFailed,
 
     // Composition errors
     OneOfNoMatch,
@@ -89,12 +442,20 @@ pub enum ErrorCode {
     AllOfFailure,
     AnyOfNoMatch,
 
+    /// A registered `KeywordValidator` rejected the value; its own code is
+    /// the `code` parameter
+    CustomKeyword,
+
     // Schema errors
     SchemaInvalid,
     SchemaCompilationFailed,
 
     // Runtime errors
     ValidationFailed,
+    /// Input or validation time exceeded a configured limit
+    LimitExceeded,
+    /// A valid value still could not be deserialized into the target type
+    DeserializationFailed,
     InternalError,
 }
 
@@ -110,6 +471,21 @@ pub enum FastSchemaError {
     #[error("Regex compilation failed: {0}")]
     RegexCompilation(#[from] regex::Error),
 
+    #[error("Unsupported schema operation: {0}")]
+    UnsupportedOperation(String),
+
+    #[error("Compiled schema decoding failed: {0}")]
+    Decode(String),
+
+    #[error("Array stream error: {0}")]
+    Stream(String),
+
+    #[error("OpenAPI error: {0}")]
+    OpenApi(String),
+
+    #[error("Input parsing failed: {0}")]
+    InputParsing(String),
+
     #[error("Validation failed with {0} errors")]
     ValidationFailed(usize),
 
@@ -124,6 +500,20 @@ impl ValidationResult {
             success: true,
             data: Some(data),
             errors: Vec::new(),
+            warnings: Vec::new(),
+            errors_truncated: false,
+            performance: None,
+        }
+    }
+
+    /// Create a successful result that does not echo the data
+    pub fn valid() -> Self {
+        Self {
+            success: true,
+            data: None,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            errors_truncated: false,
             performance: None,
         }
     }
@@ -134,6 +524,8 @@ impl ValidationResult {
             success: true,
             data: Some(data),
             errors: Vec::new(),
+            warnings: Vec::new(),
+            errors_truncated: false,
             performance: Some(stats),
         }
     }
@@ -144,6 +536,8 @@ impl ValidationResult {
             success: false,
             data: None,
             errors,
+            warnings: Vec::new(),
+            errors_truncated: false,
             performance: None,
         }
     }
@@ -154,10 +548,18 @@ impl ValidationResult {
             success: false,
             data: None,
             errors,
+            warnings: Vec::new(),
+            errors_truncated: false,
             performance: Some(stats),
         }
     }
 
+    /// Mark the errors as cut short by `early_exit` / `max_errors`
+    pub fn with_truncation(mut self, errors_truncated: bool) -> Self {
+        self.errors_truncated = errors_truncated;
+        self
+    }
+
     /// Add an error to the result
     pub fn add_error(&mut self, error: ValidationError) {
         self.errors.push(error);
@@ -185,15 +587,124 @@ impl ValidationResult {
         self.errors.iter().filter(|e| e.path == path).collect()
     }
 
+    /// Human-readable report, one block per error and warning:
+    ///
+    /// ```text
+    /// error[STRING_TOO_SHORT] users[0].name: String length 1 is less than minimum 2
+    ///   value: "a"
+    /// ```
+    pub fn to_pretty_string(&self, options: PrettyOptions) -> String {
+        let paint = |code: &str, text: &str| {
+            if options.color {
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            } else {
+                text.to_string()
+            }
+        };
+
+        let mut out = String::new();
+        let findings = self.errors.iter().map(|e| (e, "error", "1;31")).chain(self.warnings.iter().map(|e| (e, "warning", "1;33")));
+        for (error, label, color) in findings {
+            let path = if error.path.is_empty() { "(root)".to_string() } else { error.path.display_path() };
+            let code = serde_json::to_value(&error.code).unwrap_or_default();
+            out.push_str(&format!(
+                "{} {}: {}\n",
+                paint(color, &format!("{}[{}]", label, code.as_str().unwrap_or_default())),
+                paint("1", &path),
+                error.message,
+            ));
+
+            let value = options.data.and_then(|data| error.path.resolve(data)).or_else(|| error.received());
+            if let Some(value) = value {
+                let mut snippet = value.to_string();
+                if snippet.chars().count() > options.max_snippet_length {
+                    snippet = format!("{}…", snippet.chars().take(options.max_snippet_length).collect::<String>());
+                }
+                out.push_str(&format!("  value: {}\n", paint("2", &snippet)));
+            }
+        }
+
+        let count = |n: usize, noun: &str| format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" });
+        let summary = match (self.success, self.warnings.len()) {
+            (true, 0) => paint("1;32", "valid"),
+            (true, warnings) => format!("{} with {}", paint("1;32", "valid"), count(warnings, "warning")),
+            (false, 0) => paint("1;31", &count(self.errors.len(), "error")),
+            (false, warnings) => format!("{}, {}", paint("1;31", &count(self.errors.len(), "error")), count(warnings, "warning")),
+        };
+        out.push_str(&summary);
+        out
+    }
+
+    /// Errors as a tree keyed by property, like Zod's `format()`:
+    /// `{"_errors": [], "profile": {"_errors": [], "email": {"_errors": ["..."]}}}`
+    pub fn format(&self) -> serde_json::Value {
+        let mut root = serde_json::json!({"_errors": []});
+        for error in &self.errors {
+            let mut node = &mut root;
+            for segment in error.path.segments() {
+                node = node
+                    .as_object_mut()
+                    .expect("format nodes are objects")
+                    .entry(segment.to_string())
+                    .or_insert_with(|| serde_json::json!({"_errors": []}));
+            }
+            if let Some(messages) = node["_errors"].as_array_mut() {
+                messages.push(serde_json::Value::String(error.message.clone()));
+            }
+        }
+        root
+    }
+
+    /// Errors split like Zod's `flatten()`: those at the root in
+    /// `formErrors`, the rest grouped by top-level property
+    pub fn flatten(&self) -> FlattenedErrors {
+        let mut flattened = FlattenedErrors::default();
+        for error in &self.errors {
+            match error.path.segments().first() {
+                Some(field) => flattened.field_errors.entry(field.to_string()).or_default().push(error.message.clone()),
+                None => flattened.form_errors.push(error.message.clone()),
+            }
+        }
+        flattened
+    }
+
+    /// What changed from this result to `other`, e.g. after the user
+    /// edited a form
+    pub fn diff(&self, other: &ValidationResult) -> ErrorDiff {
+        let key = |error: &ValidationError| (error.path.segments().to_vec(), error.code.clone());
+        let before: HashSet<_> = self.errors.iter().map(key).collect();
+        let after: HashSet<_> = other.errors.iter().map(key).collect();
+
+        let (persisting, introduced) = other.errors.iter().cloned().partition(|error| before.contains(&key(error)));
+        ErrorDiff {
+            resolved: self.errors.iter().filter(|error| !after.contains(&key(error))).cloned().collect(),
+            introduced,
+            persisting,
+        }
+    }
+
+    /// Errors grouped by their path, in path order
+    pub fn group_by_path(&self) -> BTreeMap<String, Vec<&ValidationError>> {
+        let mut groups: BTreeMap<String, Vec<&ValidationError>> = BTreeMap::new();
+        for error in &self.errors {
+            groups.entry(error.path.to_string()).or_default().push(error);
+        }
+        groups
+    }
+
     /// Merge multiple validation results (for parallel validation)
     pub fn merge(results: Vec<ValidationResult>) -> ValidationResult {
         let mut merged_errors = Vec::new();
+        let mut merged_warnings = Vec::new();
         let mut all_successful = true;
         let mut merged_data = None;
         let mut total_time = 0.0;
         let mut total_items = 0;
+        let mut errors_truncated = false;
 
         for result in results {
+            errors_truncated |= result.errors_truncated;
+            merged_warnings.extend(result.warnings);
             if !result.success {
                 all_successful = false;
                 merged_errors.extend(result.errors);
@@ -223,6 +734,8 @@ impl ValidationResult {
                 success: true,
                 data: merged_data,
                 errors: Vec::new(),
+                warnings: merged_warnings,
+                errors_truncated: false,
                 performance,
             }
         } else {
@@ -230,6 +743,8 @@ impl ValidationResult {
                 success: false,
                 data: None,
                 errors: merged_errors,
+                warnings: merged_warnings,
+                errors_truncated,
                 performance,
             }
         }
@@ -237,36 +752,84 @@ impl ValidationResult {
 }
 
 impl ValidationError {
+    /// The error as an error map sees it
+    pub fn issue(&self) -> ErrorIssue {
+        let mut params = self.params.as_object().cloned().unwrap_or_default();
+        params.insert("message".to_string(), serde_json::Value::String(self.message.clone()));
+
+        ErrorIssue { code: self.code.clone(), path: self.path.clone(), params }
+    }
+
     /// Create a new validation error
-    pub fn new(path: String, message: String, code: ErrorCode) -> Self {
+    pub fn new(path: impl Into<ErrorPath>, message: String, code: ErrorCode) -> Self {
         Self {
-            path,
+            path: path.into(),
             message,
+            severity: code.default_severity(),
             code,
-            expected: None,
-            received: None,
+            params: empty_params(),
+            location: None,
         }
     }
 
-    /// Create an error with expected and actual values
+    /// Create an error with structured params; `params` must be an object
+    pub fn with_params(path: impl Into<ErrorPath>, message: String, code: ErrorCode, params: serde_json::Value) -> Self {
+        debug_assert!(params.is_object());
+        Self { params, ..Self::new(path, message, code) }
+    }
+
+    /// Create an error with expected and received values
     pub fn with_values(
-        path: String,
+        path: impl Into<ErrorPath>,
         message: String,
         code: ErrorCode,
         expected: serde_json::Value,
         received: serde_json::Value,
     ) -> Self {
-        Self {
-            path,
-            message,
-            code,
-            expected: Some(expected.to_string()),
-            received: Some(received.to_string()),
+        Self::with_params(path, message, code, serde_json::json!({ "expected": expected, "received": received }))
+    }
+
+    /// Create an error for a value outside `min` / `max`; unset bounds are
+    /// left out of the params
+    pub fn out_of_range<T: Serialize>(
+        path: impl Into<ErrorPath>,
+        message: String,
+        code: ErrorCode,
+        min: Option<T>,
+        max: Option<T>,
+        received: serde_json::Value,
+    ) -> Self {
+        let mut params = serde_json::json!({ "received": received });
+        if let Some(min) = min {
+            params["min"] = serde_json::json!(min);
+        }
+        if let Some(max) = max {
+            params["max"] = serde_json::json!(max);
+        }
+        Self::with_params(path, message, code, params)
+    }
+
+    /// Add a "did you mean" hint to the message and `params.suggestion`
+    pub fn with_suggestion(mut self, suggestion: Option<&str>) -> Self {
+        if let Some(suggestion) = suggestion {
+            self.message = format!("{} (did you mean '{}'?)", self.message, suggestion);
+            if let Some(params) = self.params.as_object_mut() {
+                params.insert("suggestion".to_string(), serde_json::json!(suggestion));
+            }
         }
+        self
+    }
+
+    pub fn expected(&self) -> Option<&serde_json::Value> {
+        self.params.get("expected")
+    }
+
+    pub fn received(&self) -> Option<&serde_json::Value> {
+        self.params.get("received")
     }
 
     /// Create a type mismatch error
-    pub fn type_mismatch(path: String, expected: &str, received: &serde_json::Value) -> Self {
+    pub fn type_mismatch(path: impl Into<ErrorPath>, expected: &str, received: &serde_json::Value) -> Self {
         let received_type = match received {
             serde_json::Value::Null => "null",
             serde_json::Value::Bool(_) => "boolean",
@@ -286,16 +849,16 @@ impl ValidationError {
     }
 
     /// Create a missing property error
-    pub fn missing_property(path: String, property: &str) -> Self {
+    pub fn missing_property(path: impl Into<ErrorPath>, property: &str) -> Self {
         Self::new(
-            format!("{}.{}", path, property),
+            path.into().child(property),
             format!("Required property '{}' is missing", property),
             ErrorCode::ObjectMissingProperty,
         )
     }
 
     /// Create a string length error
-    pub fn string_length(path: String, actual_length: usize, min: Option<usize>, max: Option<usize>) -> Self {
+    pub fn string_length(path: impl Into<ErrorPath>, actual_length: usize, min: Option<usize>, max: Option<usize>) -> Self {
         let message = match (min, max) {
             (Some(min_len), Some(max_len)) => {
                 format!("String length {} is not between {} and {}", actual_length, min_len, max_len)
@@ -319,17 +882,11 @@ impl ValidationError {
             ErrorCode::StringTooLong
         };
 
-        Self::with_values(
-            path,
-            message,
-            code,
-            serde_json::json!({ "min": min, "max": max }),
-            serde_json::json!(actual_length),
-        )
+        Self::out_of_range(path, message, code, min, max, serde_json::json!(actual_length))
     }
 
     /// Create a number range error
-    pub fn number_range(path: String, received: f64, min: Option<f64>, max: Option<f64>) -> Self {
+    pub fn number_range(path: impl Into<ErrorPath>, received: f64, min: Option<f64>, max: Option<f64>) -> Self {
         let message = match (min, max) {
             (Some(min_val), Some(max_val)) => {
                 format!("Number {} is not between {} and {}", received, min_val, max_val)
@@ -353,13 +910,7 @@ impl ValidationError {
             ErrorCode::NumberTooLarge
         };
 
-        Self::with_values(
-            path,
-            message,
-            code,
-            serde_json::json!({ "min": min, "max": max }),
-            serde_json::json!(received),
-        )
+        Self::out_of_range(path, message, code, min, max, serde_json::json!(received))
     }
 }
 
@@ -370,37 +921,171 @@ impl fmt::Display for ValidationError {
 }
 
 impl ErrorCode {
+    /// Severity unless `ValidationOptions::severities` overrides it
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            ErrorCode::SemanticWarning | ErrorCode::DeprecatedFeature => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
     /// Get a human-readable description of the error code
     pub fn description(&self) -> &'static str {
         match self {
             ErrorCode::InvalidType => "Value type does not match schema",
+            ErrorCode::ConstMismatch => "Value does not equal the expected constant",
             ErrorCode::StringTooShort => "String is shorter than minimum length",
             ErrorCode::StringTooLong => "String is longer than maximum length",
             ErrorCode::StringPatternMismatch => "String does not match required pattern",
             ErrorCode::StringFormatInvalid => "String format is invalid",
+            ErrorCode::StringContentEncodingInvalid => "String content is not validly encoded",
+            ErrorCode::StringContentMediaTypeInvalid => "String content does not match its media type",
+            ErrorCode::StringDateTooEarly => "Date is earlier than the minimum date",
+            ErrorCode::StringDateTooLate => "Date is later than the maximum date",
             ErrorCode::NumberTooSmall => "Number is smaller than minimum value",
             ErrorCode::NumberTooLarge => "Number is larger than maximum value",
             ErrorCode::NumberNotInteger => "Number is not an integer",
             ErrorCode::NumberNotMultipleOf => "Number is not a multiple of required value",
+            ErrorCode::NumberPrecisionExceeded => "Number has more significant digits than allowed",
+            ErrorCode::NumberScaleExceeded => "Number has more decimal places than allowed",
             ErrorCode::ArrayTooShort => "Array has fewer items than minimum",
             ErrorCode::ArrayTooLong => "Array has more items than maximum",
             ErrorCode::ArrayNotUnique => "Array contains duplicate items",
+            ErrorCode::ArrayNotSorted => "Array items are not in the required order",
             ErrorCode::ArrayItemInvalid => "Array item does not match schema",
+            ErrorCode::ArrayUnevaluatedItem => "Array item was not evaluated by any subschema",
             ErrorCode::ObjectMissingProperty => "Required object property is missing",
             ErrorCode::ObjectAdditionalProperty => "Object contains additional properties",
             ErrorCode::ObjectPropertyInvalid => "Object property does not match schema",
+            ErrorCode::ObjectTooFewProperties => "Object has fewer properties than minimum",
+            ErrorCode::ObjectTooManyProperties => "Object has more properties than maximum",
+            ErrorCode::ObjectUnevaluatedProperty => "Object property was not evaluated by any subschema",
+            ErrorCode::ObjectReadOnlyProperty => "Read-only property is not allowed in a request",
+            ErrorCode::ObjectWriteOnlyProperty => "Write-only property is not allowed in a response",
+            ErrorCode::ObjectMutuallyExclusive => "Mutually exclusive properties are both present",
+            ErrorCode::ObjectMissingAlternative => "None of the alternative properties is present",
+            ErrorCode::ObjectComparisonFailed => "Property comparison failed",
             ErrorCode::OneOfNoMatch => "Value does not match any oneOf schemas",
             ErrorCode::OneOfMultipleMatches => "Value matches multiple oneOf schemas",
             ErrorCode::AllOfFailure => "Value does not match all allOf schemas",
             ErrorCode::AnyOfNoMatch => "Value does not match any anyOf schemas",
+            ErrorCode::CustomKeyword => "Value does not satisfy a custom keyword",
             ErrorCode::SchemaInvalid => "Schema definition is invalid",
             ErrorCode::SchemaCompilationFailed => "Schema compilation failed",
             ErrorCode::ValidationFailed => "Validation failed",
+            ErrorCode::LimitExceeded => "Input exceeds a validation limit",
+            ErrorCode::DeserializationFailed => "Value does not fit the target type",
             ErrorCode::InternalError => "Internal validation error",
-            // TODO: Add descriptions for additional error codes
-            _ => "Validation error",
+            ErrorCode::InvalidHtmlElement => "Element is not a valid HTML element",
+            ErrorCode::InvalidHtmlAttribute => "Attribute is not valid for the element",
+            ErrorCode::MissingRequiredAttribute => "Required attribute is missing",
+            ErrorCode::DeprecatedAttribute => "Attribute is deprecated",
+            ErrorCode::InvalidAttributeValue => "Attribute value is invalid",
+            ErrorCode::InvalidComponentType => "Component type does not match schema",
+            ErrorCode::MissingRequiredProp => "Required component prop is missing",
+            ErrorCode::InvalidPropType => "Component prop type does not match schema",
+            ErrorCode::ChildrenNotAllowed => "Element does not allow children",
+            ErrorCode::InvalidStructure => "Element structure is invalid",
+            ErrorCode::AccessibilityViolation => "Element violates an accessibility rule",
+            ErrorCode::SemanticWarning => "Element does not follow semantic HTML guidelines",
+            ErrorCode::InvalidFormat => "Value is not in the expected format",
+            ErrorCode::InvalidValue => "Value is not allowed",
+            ErrorCode::UnknownKey => "Key is not recognized",
+            ErrorCode::Required => "Required value is missing",
+            ErrorCode::DeprecatedFeature => "Feature is deprecated",
+        }
+    }
+
+    /// The group the code belongs to
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ErrorCode::InvalidType | ErrorCode::ConstMismatch | ErrorCode::InvalidFormat | ErrorCode::InvalidValue => {
+                ErrorCategory::Type
+            }
+            ErrorCode::StringTooShort
+            | ErrorCode::StringTooLong
+            | ErrorCode::StringPatternMismatch
+            | ErrorCode::StringFormatInvalid
+            | ErrorCode::StringContentEncodingInvalid
+            | ErrorCode::StringContentMediaTypeInvalid
+            | ErrorCode::StringDateTooEarly
+            | ErrorCode::StringDateTooLate => ErrorCategory::String,
+            ErrorCode::NumberTooSmall
+            | ErrorCode::NumberTooLarge
+            | ErrorCode::NumberNotInteger
+            | ErrorCode::NumberNotMultipleOf
+            | ErrorCode::NumberPrecisionExceeded
+            | ErrorCode::NumberScaleExceeded => ErrorCategory::Number,
+            ErrorCode::ArrayTooShort
+            | ErrorCode::ArrayTooLong
+            | ErrorCode::ArrayNotUnique
+            | ErrorCode::ArrayNotSorted
+            | ErrorCode::ArrayItemInvalid
+            | ErrorCode::ArrayUnevaluatedItem => ErrorCategory::Array,
+            ErrorCode::ObjectMissingProperty
+            | ErrorCode::ObjectAdditionalProperty
+            | ErrorCode::ObjectPropertyInvalid
+            | ErrorCode::ObjectTooFewProperties
+            | ErrorCode::ObjectTooManyProperties
+            | ErrorCode::ObjectUnevaluatedProperty
+            | ErrorCode::ObjectReadOnlyProperty
+            | ErrorCode::ObjectWriteOnlyProperty
+            | ErrorCode::ObjectMutuallyExclusive
+            | ErrorCode::ObjectMissingAlternative
+            | ErrorCode::ObjectComparisonFailed => ErrorCategory::Object,
+            ErrorCode::OneOfNoMatch | ErrorCode::OneOfMultipleMatches | ErrorCode::AllOfFailure | ErrorCode::AnyOfNoMatch => {
+                ErrorCategory::Composition
+            }
+            ErrorCode::InvalidHtmlElement
+            | ErrorCode::InvalidHtmlAttribute
+            | ErrorCode::MissingRequiredAttribute
+            | ErrorCode::DeprecatedAttribute
+            | ErrorCode::InvalidAttributeValue
+            | ErrorCode::InvalidComponentType
+            | ErrorCode::MissingRequiredProp
+            | ErrorCode::InvalidPropType
+            | ErrorCode::ChildrenNotAllowed
+            | ErrorCode::InvalidStructure
+            | ErrorCode::AccessibilityViolation
+            | ErrorCode::SemanticWarning
+            | ErrorCode::Required => ErrorCategory::Html,
+            ErrorCode::SchemaInvalid | ErrorCode::SchemaCompilationFailed | ErrorCode::UnknownKey | ErrorCode::DeprecatedFeature => {
+                ErrorCategory::Schema
+            }
+            ErrorCode::CustomKeyword => ErrorCategory::Custom,
+            ErrorCode::ValidationFailed
+            | ErrorCode::LimitExceeded
+            | ErrorCode::DeserializationFailed
+            | ErrorCode::InternalError => ErrorCategory::Runtime,
         }
     }
+
+    /// Whether the code is a warning unless overridden
+    pub fn is_warning(&self) -> bool {
+        self.default_severity() == Severity::Warning
+    }
+}
+
+/// Broad kind of an `ErrorCode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorCategory {
+    /// The value is of the wrong kind altogether
+    Type,
+    String,
+    Number,
+    Array,
+    Object,
+    /// `oneOf` / `anyOf` / `allOf`
+    Composition,
+    /// HTML elements and React components
+    Html,
+    /// Problems with the schema rather than the data
+    Schema,
+    /// Registered custom keywords
+    Custom,
+    /// Limits and internal failures
+    Runtime,
 }
 
 #[cfg(test)]
@@ -447,4 +1132,173 @@ mod tests {
         assert!(!merged.success);
         assert_eq!(merged.errors.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_error_issue() {
+        let error = ValidationError::type_mismatch("user.age".to_string(), "number", &serde_json::json!("x"));
+        let issue = error.issue();
+        assert_eq!((issue.code, issue.path.to_string().as_str()), (ErrorCode::InvalidType, "user.age"));
+        assert_eq!(issue.params["expected"], "number");
+        assert_eq!(issue.params["received"], "string");
+        assert_eq!(issue.params["message"], "Expected number, got string");
+
+        let plain = ValidationError::new(String::new(), "bad".to_string(), ErrorCode::ValidationFailed).issue();
+        assert_eq!(plain.params.len(), 1);
+    }
+
+    #[test]
+    fn test_error_params() {
+        let error = ValidationError::string_length("name", 2, Some(3), None);
+        assert_eq!(error.params, serde_json::json!({"min": 3, "received": 2}));
+        assert_eq!(error.received(), Some(&serde_json::json!(2)));
+        assert!(error.expected().is_none());
+
+        let mismatch = ValidationError::type_mismatch("age", "number", &serde_json::json!(true));
+        let value = serde_json::to_value(&mismatch).unwrap();
+        assert_eq!(value["params"], serde_json::json!({"expected": "number", "received": "boolean"}));
+        assert_eq!(serde_json::from_value::<ValidationError>(value).unwrap(), mismatch);
+
+        let plain = ValidationError::new("age", "bad".to_string(), ErrorCode::ValidationFailed);
+        assert!(serde_json::to_value(&plain).unwrap().get("params").is_none());
+    }
+
+    #[test]
+    fn test_pretty_string() {
+        let data = serde_json::json!({"users": [{"name": "a"}], "bio": "x".repeat(100)});
+        let mut result = ValidationResult::failure(vec![
+            ValidationError::string_length("users.[0].name", 1, Some(2), None),
+            ValidationError::new("", "Invalid object".to_string(), ErrorCode::ValidationFailed),
+        ]);
+        result.warnings.push(ValidationError::new("bio", "Too long".to_string(), ErrorCode::SemanticWarning));
+
+        let pretty = result.to_pretty_string(PrettyOptions { data: Some(&data), max_snippet_length: 5, ..Default::default() });
+        assert_eq!(pretty, [
+            "error[STRING_TOO_SHORT] users[0].name: String length 1 is less than minimum 2",
+            "  value: \"a\"",
+            "error[VALIDATION_FAILED] (root): Invalid object",
+            "  value: {\"bio…",
+            "warning[SEMANTIC_WARNING] bio: Too long",
+            "  value: \"xxxx…",
+            "2 errors, 1 warning",
+        ].join("\n"));
+
+        // Without the data, the received value stands in
+        let plain = result.to_pretty_string(PrettyOptions::default());
+        assert!(plain.starts_with("error[STRING_TOO_SHORT] users[0].name: String length 1 is less than minimum 2\n  value: 1\n"));
+
+        let colored = ValidationResult::valid().to_pretty_string(PrettyOptions { color: true, ..Default::default() });
+        assert_eq!(colored, "\x1b[1;32mvalid\x1b[0m");
+    }
+
+    #[test]
+    fn test_diff() {
+        let error = |path: &str, code, message: &str| ValidationError::new(path, message.to_string(), code);
+        let before = ValidationResult::failure(vec![
+            error("email", ErrorCode::StringFormatInvalid, "Invalid email"),
+            error("age", ErrorCode::InvalidType, "Expected number"),
+        ]);
+        let after = ValidationResult::failure(vec![
+            error("age", ErrorCode::InvalidType, "Expected number, got string"),
+            error("age", ErrorCode::NumberTooSmall, "Too small"),
+        ]);
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.resolved.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), vec!["Invalid email"]);
+        assert_eq!(diff.introduced.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), vec!["Too small"]);
+        assert_eq!(diff.persisting.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(), vec!["Expected number, got string"]);
+        assert_eq!(serde_json::to_value(&diff).unwrap()["new"][0]["code"], "NUMBER_TOO_SMALL");
+
+        let fixed = after.diff(&ValidationResult::valid());
+        assert_eq!((fixed.resolved.len(), fixed.introduced.len(), fixed.persisting.len()), (2, 0, 0));
+    }
+
+    #[test]
+    fn test_error_code_categories() {
+        assert_eq!(ErrorCode::StringTooShort.category(), ErrorCategory::String);
+        assert_eq!(ErrorCode::AccessibilityViolation.category(), ErrorCategory::Html);
+        assert_eq!(ErrorCode::AccessibilityViolation.description(), "Element violates an accessibility rule");
+        assert_eq!(ErrorCode::AnyOfNoMatch.category(), ErrorCategory::Composition);
+        assert!(ErrorCode::SemanticWarning.is_warning());
+        assert!(!ErrorCode::InvalidType.is_warning());
+        assert_eq!(serde_json::to_value(ErrorCategory::Html).unwrap(), "html");
+    }
+
+    #[test]
+    fn test_group_by_path() {
+        let error = |path: &str, code| ValidationError::new(path, "bad".to_string(), code);
+        let result = ValidationResult::failure(vec![
+            error("users.[1].email", ErrorCode::StringFormatInvalid),
+            error("users.[0].name", ErrorCode::StringTooShort),
+            error("users.[1].email", ErrorCode::StringTooLong),
+        ]);
+
+        let groups = result.group_by_path();
+        assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["users.[0].name", "users.[1].email"]);
+        assert_eq!(groups["users.[1].email"].iter().map(|e| &e.code).collect::<Vec<_>>(), vec![&ErrorCode::StringFormatInvalid, &ErrorCode::StringTooLong]);
+    }
+
+    #[test]
+    fn test_format_and_flatten() {
+        let error = |path: &str, message: &str| ValidationError::new(path.to_string(), message.to_string(), ErrorCode::ValidationFailed);
+        let result = ValidationResult::failure(vec![
+            error("profile.email", "Invalid email"),
+            error("profile.email", "Too long"),
+            error("tags.[1]", "Expected string"),
+            error(".name", "Required"),
+            error("", "Invalid object"),
+        ]);
+
+        assert_eq!(result.format(), serde_json::json!({
+            "_errors": ["Invalid object"],
+            "profile": {"_errors": [], "email": {"_errors": ["Invalid email", "Too long"]}},
+            "tags": {"_errors": [], "1": {"_errors": ["Expected string"]}},
+            "name": {"_errors": ["Required"]}
+        }));
+
+        let flattened = serde_json::to_value(result.flatten()).unwrap();
+        assert_eq!(flattened, serde_json::json!({
+            "formErrors": ["Invalid object"],
+            "fieldErrors": {"name": ["Required"], "profile": ["Invalid email", "Too long"], "tags": ["Expected string"]}
+        }));
+    }
+
+    #[test]
+    fn test_error_paths() {
+        let path = ErrorPath::new(vec![PathSegment::Key("a.b/c~".into()), PathSegment::Index(0), PathSegment::Key("name".into())]);
+        assert_eq!(path.pointer(), "/a.b~1c~0/0/name");
+        assert_eq!(path, "a.b/c~.[0].name");
+
+        let mut error = ValidationError::new(path, "bad".to_string(), ErrorCode::ValidationFailed);
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["path"], serde_json::json!(["a.b/c~", 0, "name"]));
+        assert_eq!(json["pointer"], "/a.b~1c~0/0/name");
+        assert_eq!(serde_json::from_value::<ValidationError>(json).unwrap(), error);
+
+        error.path.set_style(PathStyle::Legacy);
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["path"], "a.b/c~.[0].name");
+        assert!(json.get("pointer").is_none());
+
+        let legacy: ErrorPath = serde_json::from_value(serde_json::json!("users.[2].tags.[0]")).unwrap();
+        assert_eq!(legacy.segments(), [
+            PathSegment::Key("users".into()), PathSegment::Index(2), PathSegment::Key("tags".into()), PathSegment::Index(0)
+        ]);
+        assert_eq!(ErrorPath::parse("").pointer(), "");
+    }
+
+    #[test]
+    fn test_default_severities() {
+        let warning = ValidationError::new(String::new(), "Semantic guideline".to_string(), ErrorCode::SemanticWarning);
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(ErrorCode::DeprecatedFeature.default_severity(), Severity::Warning);
+        assert_eq!(ErrorCode::InvalidType.default_severity(), Severity::Error);
+        assert_eq!(serde_json::to_value(&warning).unwrap()["severity"], "warning");
+
+        let merged = ValidationResult::merge(vec![
+            ValidationResult { warnings: vec![warning.clone()], ..ValidationResult::valid() },
+            ValidationResult { warnings: vec![warning], ..ValidationResult::valid() },
+        ]);
+        assert!(merged.success);
+        assert_eq!(merged.warnings.len(), 2);
+    }
+}
@@ -0,0 +1,216 @@
+
+//! C API, built with the `ffi` feature. Schemas and data go in as JSON text
+//! and results come out as the JSON of a `ValidationResult`, so bindings
+//! for C, C++, Go (cgo) or Swift only need UTF-8 strings. The header is
+//! `include/fast_schema.h`, generated by cbindgen (see `cbindgen.toml`).
+//!
+//! Strings returned by this API are owned by the caller and must be
+//! released with `fast_schema_string_free`. Panics never cross the
+//! boundary; they are reported like any other failure.
+
+use crate::error::{ErrorCode, ValidationError, ValidationResult};
+use crate::validator::Validator;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// A compiled schema. Opaque to C; safe to use from several threads at once.
+pub struct FastSchemaValidator {
+    validator: Validator,
+}
+
+/// Borrow a C string as UTF-8
+unsafe fn text<'a>(ptr: *const c_char) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err("Null string".to_string());
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|e| format!("Invalid UTF-8: {}", e))
+}
+
+/// Hand a string to C, dropping interior NULs that can't cross the boundary
+fn to_c(text: String) -> *mut c_char {
+    let text = CString::new(text).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|&byte| byte != 0);
+        CString::new(bytes).unwrap_or_default()
+    });
+    text.into_raw()
+}
+
+fn failure_json(message: &str) -> *mut c_char {
+    let result = ValidationResult::failure(vec![ValidationError::new(
+        String::new(),
+        message.to_string(),
+        ErrorCode::InternalError,
+    )]);
+    to_c(serde_json::to_string(&result).unwrap_or_default())
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Validation panicked".to_string())
+}
+
+/// Compile a JSON schema. Returns null on failure, storing a message in
+/// `*error` (when `error` isn't null) that the caller must free.
+///
+/// # Safety
+///
+/// `schema_json` must be null or a NUL-terminated string, and `error` null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fast_schema_validator_new(
+    schema_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut FastSchemaValidator {
+    let compiled = panic::catch_unwind(AssertUnwindSafe(|| {
+        let schema: serde_json::Value =
+            serde_json::from_str(text(schema_json)?).map_err(|e| format!("Invalid schema JSON: {}", e))?;
+        Validator::from_json(&schema).map_err(|e| e.to_string())
+    }))
+    .unwrap_or_else(|payload| Err(panic_message(payload)));
+
+    match compiled {
+        Ok(validator) => {
+            if !error.is_null() {
+                *error = ptr::null_mut();
+            }
+            Box::into_raw(Box::new(FastSchemaValidator { validator }))
+        }
+        Err(message) => {
+            if !error.is_null() {
+                *error = to_c(message);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Validate a JSON document, returning the `ValidationResult` as JSON.
+/// Malformed input is reported as a failed result, so this only returns
+/// null if `validator` is null.
+///
+/// # Safety
+///
+/// `validator` must be null or come from `fast_schema_validator_new` and
+/// not yet be freed; `data_json` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn fast_schema_validate(
+    validator: *const FastSchemaValidator,
+    data_json: *const c_char,
+) -> *mut c_char {
+    let validator = match validator.as_ref() {
+        Some(validator) => &validator.validator,
+        None => return ptr::null_mut(),
+    };
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+        let data: serde_json::Value =
+            serde_json::from_str(text(data_json)?).map_err(|e| format!("Invalid JSON data: {}", e))?;
+        serde_json::to_string(&validator.validate(&data)).map_err(|e| e.to_string())
+    }))
+    .unwrap_or_else(|payload| Err(panic_message(payload)));
+
+    match outcome {
+        Ok(json) => to_c(json),
+        Err(message) => failure_json(&message),
+    }
+}
+
+/// Whether a JSON document is valid, without building a result. Null or
+/// malformed input is invalid.
+///
+/// # Safety
+///
+/// As for `fast_schema_validate`.
+#[no_mangle]
+pub unsafe extern "C" fn fast_schema_is_valid(validator: *const FastSchemaValidator, data_json: *const c_char) -> bool {
+    let validator = match validator.as_ref() {
+        Some(validator) => &validator.validator,
+        None => return false,
+    };
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        text(data_json)
+            .ok()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+            .is_some_and(|data| validator.validate(&data).success)
+    }))
+    .unwrap_or(false)
+}
+
+/// Release a validator. Null is ignored.
+///
+/// # Safety
+///
+/// `validator` must be null or come from `fast_schema_validator_new`, and
+/// must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fast_schema_validator_free(validator: *mut FastSchemaValidator) {
+    if !validator.is_null() {
+        drop(Box::from_raw(validator));
+    }
+}
+
+/// Release a string returned by this API. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this API, and must not be
+/// used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fast_schema_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// The library version, e.g. `0.1.0`. Static; do not free.
+#[no_mangle]
+pub extern "C" fn fast_schema_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(text: &str) -> CString {
+        CString::new(text).unwrap()
+    }
+
+    unsafe fn take(string: *mut c_char) -> String {
+        let owned = CStr::from_ptr(string).to_str().unwrap().to_string();
+        fast_schema_string_free(string);
+        owned
+    }
+
+    #[test]
+    fn test_c_api() {
+        unsafe {
+            let mut error = ptr::null_mut();
+            let validator = fast_schema_validator_new(c(r#"{"type": "string", "minLength": 2}"#).as_ptr(), &mut error);
+            assert!(!validator.is_null() && error.is_null());
+
+            let result: serde_json::Value = serde_json::from_str(&take(fast_schema_validate(validator, c(r#""a""#).as_ptr()))).unwrap();
+            assert_eq!(result["success"], false);
+            assert_eq!(result["errors"][0]["code"], "STRING_TOO_SHORT");
+            assert!(fast_schema_is_valid(validator, c(r#""ab""#).as_ptr()));
+            assert!(!fast_schema_is_valid(validator, c("not json").as_ptr()));
+            assert!(!fast_schema_is_valid(validator, ptr::null()));
+
+            let result = take(fast_schema_validate(validator, c("{").as_ptr()));
+            assert!(result.contains("INTERNAL_ERROR"));
+            assert!(fast_schema_validate(ptr::null(), c("1").as_ptr()).is_null());
+            fast_schema_validator_free(validator);
+
+            let validator = fast_schema_validator_new(c(r#"{"type": "nope"}"#).as_ptr(), &mut error);
+            assert!(validator.is_null());
+            assert!(take(error).contains("nope"));
+
+            assert_eq!(CStr::from_ptr(fast_schema_version()).to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+        }
+    }
+}
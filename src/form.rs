@@ -0,0 +1,309 @@
+
+//! Form submissions and query strings, where every value is a string.
+//! `FormValidator` groups the fields by name, coerces each one by the
+//! schema of its property, then validates the resulting object:
+//!
+//! - numbers and booleans are parsed (`on`, `yes` and `1` are true),
+//! - array properties collect every repeated key, with or without a `[]`
+//!   suffix (`tag=a&tag=b`, `tag[]=a&tag[]=b`),
+//! - other properties keep the last value, so a hidden `false` field
+//!   followed by a checkbox works as usual,
+//! - an empty value for a property that is not a string counts as missing,
+//!   the way browsers submit an untouched number input.
+//!
+//! Values that cannot be coerced are left as strings for the schema to
+//! reject with its usual error.
+
+use crate::error::{FastSchemaError, ValidationResult};
+use crate::schema::SchemaType;
+use crate::utils::{ValidationContext, ValidationOptions};
+use crate::validator::Validator;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Validates form fields and query strings against an object schema
+pub struct FormValidator {
+    validator: Validator,
+}
+
+impl FormValidator {
+    pub fn new(schema: SchemaType) -> Result<Self, FastSchemaError> {
+        Ok(Self::from_validator(Validator::new(schema)?))
+    }
+
+    /// Use an existing validator, keeping its definitions and formats
+    pub fn from_validator(validator: Validator) -> Self {
+        Self { validator }
+    }
+
+    pub fn validator(&self) -> &Validator {
+        &self.validator
+    }
+
+    /// Validate an `application/x-www-form-urlencoded` body or a query
+    /// string (a leading `?` is ignored)
+    pub fn validate_urlencoded(&self, body: &str) -> ValidationResult {
+        self.validate_pairs(parse_urlencoded(body))
+    }
+
+    /// Validate `(name, value)` pairs in submission order, as multipart
+    /// parsers and `URLSearchParams` yield them
+    pub fn validate_pairs<I, K, V>(&self, pairs: I) -> ValidationResult
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        validate_form(&self.validator, fields_from_pairs(pairs))
+    }
+
+    /// Validate a field map such as `{"age": "30", "tag": ["a", "b"]}`,
+    /// where arrays hold the values of a repeated field. Values that are
+    /// not strings are validated as they are.
+    pub fn validate_fields(&self, fields: &Value) -> ValidationResult {
+        match fields {
+            Value::Object(fields) => validate_form(&self.validator, fields_from_map(fields)),
+            other => self.validator.validate(other),
+        }
+    }
+}
+
+/// Every value submitted for each field name
+pub(crate) type Fields = BTreeMap<String, Vec<Value>>;
+
+pub(crate) fn fields_from_pairs<I, K, V>(pairs: I) -> Fields
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut fields = Fields::new();
+    for (name, value) in pairs {
+        fields.entry(field_name(name.as_ref())).or_default().push(Value::String(value.as_ref().to_string()));
+    }
+    fields
+}
+
+pub(crate) fn fields_from_map(map: &Map<String, Value>) -> Fields {
+    let mut fields = Fields::new();
+    for (name, value) in map {
+        let values = fields.entry(field_name(name)).or_default();
+        match value {
+            Value::Array(items) => values.extend(items.iter().cloned()),
+            value => values.push(value.clone()),
+        }
+    }
+    fields
+}
+
+/// Coerce `fields` by the properties of the validator's schema and
+/// validate the resulting object
+pub(crate) fn validate_form(validator: &Validator, fields: Fields) -> ValidationResult {
+    let form = Coercion::new(validator);
+    let root = &validator.compiled_schema.schema;
+    let mut object = Map::new();
+    for (name, values) in fields {
+        let value = match form.property_schema(root, &name) {
+            Some(schema) => form.coerce_field(values, schema),
+            None if values.len() == 1 => values.into_iter().next(),
+            None => Some(Value::Array(values)),
+        };
+        if let Some(value) = value {
+            object.insert(name, value);
+        }
+    }
+    validator.validate(&Value::Object(object))
+}
+
+/// Reads strings as the schemas of a validator expect, also used for
+/// environment variables
+pub(crate) struct Coercion<'a> {
+    validator: &'a Validator,
+}
+
+impl<'a> Coercion<'a> {
+    pub(crate) fn new(validator: &'a Validator) -> Self {
+        Self { validator }
+    }
+
+    /// The schema of property `name`, looking through references and
+    /// combinators
+    pub(crate) fn property_schema(&self, schema: &'a SchemaType, name: &str) -> Option<&'a SchemaType> {
+        match self.resolve(schema) {
+            SchemaType::Object { properties, catchall, .. } => properties.get(name).or(catchall.as_deref()),
+            SchemaType::Map { values, .. } => Some(values),
+            SchemaType::Union { options: schemas, .. }
+            | SchemaType::OneOf { schemas, .. }
+            | SchemaType::AnyOf { schemas, .. }
+            | SchemaType::AllOf { schemas, .. }
+            | SchemaType::Intersection { schemas } => {
+                schemas.iter().find_map(|schema| self.property_schema(schema, name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Follow references, refinements and transform inputs to the schema
+    /// that decides how a string is read
+    pub(crate) fn resolve(&self, schema: &'a SchemaType) -> &'a SchemaType {
+        match schema {
+            SchemaType::Ref { reference } => match self.validator.definitions.get(reference) {
+                Some(target) => self.resolve(target),
+                None => schema,
+            },
+            SchemaType::Refinement { base, .. } => self.resolve(base),
+            SchemaType::Transform { input, .. } => self.resolve(input),
+            SchemaType::Annotated { schema, .. } => self.resolve(schema),
+            _ => schema,
+        }
+    }
+
+    /// Every value of a field, by its property schema; `None` leaves the
+    /// property out
+    pub(crate) fn coerce_field(&self, values: Vec<Value>, schema: &'a SchemaType) -> Option<Value> {
+        match self.resolve(schema) {
+            SchemaType::Array { items, .. } | SchemaType::Set { items, .. } => Some(Value::Array(
+                values.into_iter().filter_map(|value| self.coerce(value, items)).collect(),
+            )),
+            SchemaType::Tuple { prefix_items, items } => Some(Value::Array(
+                values
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(index, value)| match prefix_items.get(index).or(items.as_deref()) {
+                        Some(schema) => self.coerce(value, schema),
+                        None => Some(value),
+                    })
+                    .collect(),
+            )),
+            schema => values.into_iter().last().and_then(|value| self.coerce(value, schema)),
+        }
+    }
+
+    /// A single string read as `schema` expects
+    pub(crate) fn coerce(&self, value: Value, schema: &'a SchemaType) -> Option<Value> {
+        let text = match &value {
+            Value::String(text) => text.as_str(),
+            _ => return Some(value),
+        };
+        match self.resolve(schema) {
+            SchemaType::Number { .. } if text.trim().is_empty() => None,
+            SchemaType::Number { .. } => Some(
+                serde_json::from_str::<serde_json::Number>(text.trim()).map(Value::Number).unwrap_or(value),
+            ),
+            SchemaType::Boolean => match text.to_ascii_lowercase().as_str() {
+                "" => None,
+                "true" | "on" | "yes" | "1" => Some(Value::Bool(true)),
+                "false" | "off" | "no" | "0" => Some(Value::Bool(false)),
+                _ => Some(value),
+            },
+            SchemaType::Null if text.is_empty() => Some(Value::Null),
+            SchemaType::Const { value: expected } if !expected.is_string() => {
+                match serde_json::from_str::<Value>(text) {
+                    Ok(parsed) if parsed == *expected => Some(parsed),
+                    _ => Some(value),
+                }
+            }
+            // The first option that accepts its reading of the string
+            SchemaType::Union { options: schemas, .. }
+            | SchemaType::OneOf { schemas, .. }
+            | SchemaType::AnyOf { schemas, .. } => {
+                let accepted = schemas.iter().find_map(|schema| {
+                    let coerced = self.coerce(value.clone(), schema)?;
+                    let mut context = ValidationContext::new(ValidationOptions::default());
+                    self.validator.validate_value(&coerced, schema, &mut context).is_empty().then_some(coerced)
+                });
+                match accepted {
+                    Some(coerced) => Some(coerced),
+                    None if text.is_empty() => None,
+                    None => Some(value),
+                }
+            }
+            SchemaType::AllOf { schemas, .. } | SchemaType::Intersection { schemas } => schemas
+                .iter()
+                .map(|schema| self.coerce(value.clone(), schema))
+                .find(|coerced| coerced.as_ref() != Some(&value))
+                .unwrap_or(Some(value)),
+            _ => Some(value),
+        }
+    }
+}
+
+/// A field name without the `[]` suffix some forms use for arrays
+fn field_name(name: &str) -> String {
+    name.strip_suffix("[]").unwrap_or(name).to_string()
+}
+
+/// Split a urlencoded body into decoded `(name, value)` pairs
+pub(crate) fn parse_urlencoded(body: &str) -> Vec<(String, String)> {
+    body.strip_prefix('?')
+        .unwrap_or(body)
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode `+` and `%XX` escapes; malformed escapes are kept as written
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' => match text.get(index + 1..index + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) => {
+                    decoded.push(byte);
+                    index += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Schema;
+    use crate::error::ErrorCode;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_form_fields() {
+        let schema = Schema::object()
+            .prop("name", Schema::string().min(1))
+            .prop("age", Schema::integer().min(18).optional())
+            .prop("subscribe", Schema::boolean())
+            .prop("tag", Schema::array(Schema::string()))
+            .prop("score", Schema::array(Schema::number()).optional())
+            .build()
+            .unwrap();
+        let form = FormValidator::new(schema).unwrap();
+
+        let result = form.validate_urlencoded("?name=Ann+Lee%21&age=30&subscribe=false&subscribe=on&tag=a&tag=b%20c");
+        assert!(result.success, "{:?}", result.errors);
+        assert_eq!(
+            result.data,
+            Some(json!({"name": "Ann Lee!", "age": 30, "subscribe": true, "tag": ["a", "b c"]}))
+        );
+
+        let result = form.validate_pairs(vec![("name", "Bo"), ("age", ""), ("subscribe", "no"), ("tag[]", "x"), ("score[]", "1.5")]);
+        assert_eq!(result.data, Some(json!({"name": "Bo", "subscribe": false, "tag": ["x"], "score": [1.5]})));
+
+        let result = form.validate_fields(&json!({"name": "Cy", "age": "ten", "subscribe": "on", "tag": ["a", "b"]}));
+        assert!(!result.success);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path.to_string(), "age");
+        assert_eq!(result.errors[0].code, ErrorCode::InvalidType);
+
+        let result = form.validate_urlencoded("name=Di&age=12&tag=a");
+        assert_eq!(result.errors.iter().map(|e| e.path.to_string()).collect::<Vec<_>>(), vec!["subscribe", "age"]);
+    }
+}
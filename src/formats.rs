@@ -0,0 +1,102 @@
+
+use crate::error::FastSchemaError;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Predicate backing a callback format
+pub type FormatFn = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A user-registered string format
+#[derive(Clone)]
+pub enum CustomFormat {
+    Pattern(Arc<Regex>),
+    Callback(FormatFn),
+}
+
+impl CustomFormat {
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            CustomFormat::Pattern(regex) => regex.is_match(value),
+            CustomFormat::Callback(callback) => callback(value),
+        }
+    }
+}
+
+impl fmt::Debug for CustomFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomFormat::Pattern(regex) => f.debug_tuple("Pattern").field(&regex.as_str()).finish(),
+            CustomFormat::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// Named string formats beyond the built-in `StringFormat` variants,
+/// used by schemas such as `{"type": "string", "format": "sku"}`
+#[derive(Debug, Clone, Default)]
+pub struct FormatRegistry {
+    formats: HashMap<String, CustomFormat>,
+}
+
+impl FormatRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a format matched by a regex
+    pub fn register_pattern(&mut self, name: &str, pattern: &str) -> Result<(), FastSchemaError> {
+        let regex = Regex::new(pattern)?;
+        self.formats.insert(name.to_string(), CustomFormat::Pattern(Arc::new(regex)));
+        Ok(())
+    }
+
+    /// Register (or replace) a format checked by a callback
+    pub fn register_fn<F>(&mut self, name: &str, callback: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.formats.insert(name.to_string(), CustomFormat::Callback(Arc::new(callback)));
+    }
+
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.formats.remove(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CustomFormat> {
+        self.formats.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.formats.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.formats.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.formats.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_registry() {
+        let mut registry = FormatRegistry::new();
+        registry.register_pattern("sku", r"^[A-Z]{3}-\d{4}$").unwrap();
+        registry.register_fn("even-length", |value| value.len() % 2 == 0);
+
+        assert!(registry.get("sku").unwrap().matches("ABC-1234"));
+        assert!(!registry.get("sku").unwrap().matches("abc-1234"));
+        assert!(registry.get("even-length").unwrap().matches("ab"));
+        assert!(registry.get("missing").is_none());
+
+        assert!(registry.register_pattern("broken", "(").is_err());
+        assert_eq!(registry.len(), 2);
+    }
+}
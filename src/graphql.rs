@@ -0,0 +1,2025 @@
+
+//! GraphQL responses checked against a `GraphQLSchemaDefinition`.
+//!
+//! Given the operation that produced it, a response must have exactly the
+//! selected fields (by alias), with `@skip` / `@include` on variables and
+//! fragments on types other than the runtime one (known from `__typename`)
+//! making fields optional. Without an operation, `data` may hold any
+//! fields of the root type, each checked against its declared type.
+//! Non-null and list wrappers, enums and the built-in scalars are
+//! enforced; custom scalars accept any value.
+//!
+//! `GraphQLValidator` checks the other direction too: that a query
+//! document and its variables fit the schema before they are executed.
+
+use crate::error::{ErrorCode, ErrorPath, FastSchemaError, PathSegment, ValidationError, ValidationResult};
+use crate::schema::{GraphQLArgument, GraphQLField, GraphQLSchemaDefinition, GraphQLTypeDefinition};
+use crate::utils::{closest_match, ValidationContext, ValidationOptions};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A type reference such as `[User!]!`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl TypeRef {
+    pub(crate) fn parse(text: &str) -> Result<TypeRef, String> {
+        let mut parser = Parser::new(text)?;
+        let type_ref = parser.type_ref()?;
+        parser.end()?;
+        Ok(type_ref)
+    }
+
+    /// The named type under the list and non-null wrappers
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            TypeRef::Named(name) => name,
+            TypeRef::List(inner) | TypeRef::NonNull(inner) => inner.name(),
+        }
+    }
+}
+
+impl fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeRef::Named(name) => f.write_str(name),
+            TypeRef::List(inner) => write!(f, "[{}]", inner),
+            TypeRef::NonNull(inner) => write!(f, "{}!", inner),
+        }
+    }
+}
+
+/// An executable document: operations and fragments
+#[derive(Debug)]
+pub(crate) struct Document {
+    pub operations: Vec<Operation>,
+    pub fragments: HashMap<String, Fragment>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+#[derive(Debug)]
+pub(crate) struct Operation {
+    pub kind: OperationKind,
+    pub name: Option<String>,
+    pub variables: Vec<VariableDefinition>,
+    pub directives: Vec<Directive>,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Debug)]
+pub(crate) struct VariableDefinition {
+    pub name: String,
+    pub type_ref: TypeRef,
+    pub default_value: Option<InputValue>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Fragment {
+    pub type_condition: String,
+    pub directives: Vec<Directive>,
+    pub selections: Vec<Selection>,
+}
+
+#[derive(Debug)]
+pub(crate) enum Selection {
+    Field(Field),
+    FragmentSpread { name: String, directives: Vec<Directive> },
+    InlineFragment { type_condition: Option<String>, directives: Vec<Directive>, selections: Vec<Selection> },
+}
+
+#[derive(Debug)]
+pub(crate) struct Field {
+    pub alias: Option<String>,
+    pub name: String,
+    pub arguments: Vec<(String, InputValue)>,
+    pub directives: Vec<Directive>,
+    pub selections: Vec<Selection>,
+}
+
+impl Field {
+    /// The key of the field in the response
+    pub(crate) fn response_key(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Directive {
+    pub name: String,
+    pub arguments: Vec<(String, InputValue)>,
+}
+
+/// A literal or variable in an argument, default value or directive
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum InputValue {
+    Variable(String),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(String),
+    List(Vec<InputValue>),
+    Object(Vec<(String, InputValue)>),
+}
+
+/// Whether `@skip` / `@include` keep a selection: `Some` when literals
+/// decide it, `None` when it depends on variables
+fn included(directives: &[Directive]) -> Option<bool> {
+    let mut result = Some(true);
+    for directive in directives {
+        let keep_if = match directive.name.as_str() {
+            "skip" => false,
+            "include" => true,
+            _ => continue,
+        };
+        match directive.arguments.iter().find(|(name, _)| name == "if").map(|(_, value)| value) {
+            Some(InputValue::Boolean(condition)) if *condition != keep_if => return Some(false),
+            Some(InputValue::Boolean(_)) => {}
+            _ => result = None,
+        }
+    }
+    result
+}
+
+impl Document {
+    pub(crate) fn parse(text: &str) -> Result<Document, String> {
+        let mut parser = Parser::new(text)?;
+        let mut document = Document { operations: Vec::new(), fragments: HashMap::new() };
+        while !parser.at_end() {
+            if parser.peek_name("fragment") {
+                parser.advance();
+                let name = parser.name()?;
+                parser.expect_name("on")?;
+                let type_condition = parser.name()?;
+                let directives = parser.directives()?;
+                let selections = parser.selection_set()?;
+                if document.fragments.insert(name.clone(), Fragment { type_condition, directives, selections }).is_some() {
+                    return Err(format!("fragment '{}' is defined more than once", name));
+                }
+            } else {
+                document.operations.push(parser.operation()?);
+            }
+        }
+        if document.operations.is_empty() {
+            return Err("the document has no operation".to_string());
+        }
+        Ok(document)
+    }
+
+    /// The operation named `name`, or the only one
+    pub(crate) fn operation(&self, name: Option<&str>) -> Result<&Operation, String> {
+        match name {
+            Some(name) => self
+                .operations
+                .iter()
+                .find(|operation| operation.name.as_deref() == Some(name))
+                .ok_or_else(|| format!("the document has no operation named '{}'", name)),
+            None if self.operations.len() == 1 => Ok(&self.operations[0]),
+            None => Err("an operation name is required when the document has several operations".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Punctuator(char),
+    Spread,
+    Name(String),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Parser, String> {
+        Ok(Parser { tokens: tokenize(text)?, pos: 0 })
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+    }
+
+    fn invalid(&self, expected: &str) -> String {
+        match self.tokens.get(self.pos) {
+            Some((_, line)) => format!("line {}: expected {}", line, expected),
+            None => format!("expected {}, found the end of the document", expected),
+        }
+    }
+
+    fn end(&self) -> Result<(), String> {
+        if !self.at_end() {
+            return Err(self.invalid("the end of the input"));
+        }
+        Ok(())
+    }
+
+    fn peek_punctuator(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punctuator(c))
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek_punctuator(c) {
+            self.advance();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if !self.eat(c) {
+            return Err(self.invalid(&format!("'{}'", c)));
+        }
+        Ok(())
+    }
+
+    fn peek_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == name)
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Name(name)) => {
+                let name = name.clone();
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.invalid("a name")),
+        }
+    }
+
+    fn expect_name(&mut self, name: &str) -> Result<(), String> {
+        if !self.peek_name(name) {
+            return Err(self.invalid(&format!("'{}'", name)));
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn operation(&mut self) -> Result<Operation, String> {
+        // A bare selection set is an anonymous query
+        if self.peek_punctuator('{') {
+            let selections = self.selection_set()?;
+            return Ok(Operation { kind: OperationKind::Query, name: None, variables: Vec::new(), directives: Vec::new(), selections });
+        }
+        let kind = match self.name()?.as_str() {
+            "query" => OperationKind::Query,
+            "mutation" => OperationKind::Mutation,
+            "subscription" => OperationKind::Subscription,
+            _ => {
+                self.pos -= 1;
+                return Err(self.invalid("'query', 'mutation', 'subscription' or 'fragment'"));
+            }
+        };
+        let name = match self.peek() {
+            Some(Token::Name(_)) => Some(self.name()?),
+            _ => None,
+        };
+        let mut variables = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                self.expect('$')?;
+                let name = self.name()?;
+                self.expect(':')?;
+                let type_ref = self.type_ref()?;
+                let default_value = if self.eat('=') { Some(self.value(true)?) } else { None };
+                self.directives()?;
+                variables.push(VariableDefinition { name, type_ref, default_value });
+            }
+        }
+        let directives = self.directives()?;
+        let selections = self.selection_set()?;
+        Ok(Operation { kind, name, variables, directives, selections })
+    }
+
+    fn type_ref(&mut self) -> Result<TypeRef, String> {
+        let type_ref = if self.eat('[') {
+            let inner = self.type_ref()?;
+            self.expect(']')?;
+            TypeRef::List(Box::new(inner))
+        } else {
+            TypeRef::Named(self.name()?)
+        };
+        Ok(if self.eat('!') { TypeRef::NonNull(Box::new(type_ref)) } else { type_ref })
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Selection>, String> {
+        self.expect('{')?;
+        let mut selections = Vec::new();
+        while !self.eat('}') {
+            selections.push(self.selection()?);
+        }
+        if selections.is_empty() {
+            return Err(self.invalid("a selection"));
+        }
+        Ok(selections)
+    }
+
+    fn selection(&mut self) -> Result<Selection, String> {
+        if self.peek() == Some(&Token::Spread) {
+            self.advance();
+            if self.peek_name("on") || self.peek_punctuator('{') || self.peek_punctuator('@') {
+                let type_condition = if self.peek_name("on") {
+                    self.advance();
+                    Some(self.name()?)
+                } else {
+                    None
+                };
+                let directives = self.directives()?;
+                let selections = self.selection_set()?;
+                return Ok(Selection::InlineFragment { type_condition, directives, selections });
+            }
+            let name = self.name()?;
+            let directives = self.directives()?;
+            return Ok(Selection::FragmentSpread { name, directives });
+        }
+
+        let mut name = self.name()?;
+        let mut alias = None;
+        if self.eat(':') {
+            alias = Some(name);
+            name = self.name()?;
+        }
+        let arguments = self.arguments()?;
+        let directives = self.directives()?;
+        let selections = if self.peek_punctuator('{') { self.selection_set()? } else { Vec::new() };
+        Ok(Selection::Field(Field { alias, name, arguments, directives, selections }))
+    }
+
+    fn arguments(&mut self) -> Result<Vec<(String, InputValue)>, String> {
+        let mut arguments = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                let name = self.name()?;
+                self.expect(':')?;
+                arguments.push((name, self.value(false)?));
+            }
+        }
+        Ok(arguments)
+    }
+
+    fn directives(&mut self) -> Result<Vec<Directive>, String> {
+        let mut directives = Vec::new();
+        while self.eat('@') {
+            let name = self.name()?;
+            let arguments = self.arguments()?;
+            directives.push(Directive { name, arguments });
+        }
+        Ok(directives)
+    }
+
+    /// A value; `constant` ones (default values) cannot use variables
+    fn value(&mut self, constant: bool) -> Result<InputValue, String> {
+        let token = self.peek().cloned().ok_or_else(|| self.invalid("a value"))?;
+        self.advance();
+        Ok(match token {
+            Token::Punctuator('$') if !constant => InputValue::Variable(self.name()?),
+            Token::Int(n) => InputValue::Int(n),
+            Token::Float(n) => InputValue::Float(n),
+            Token::String(s) => InputValue::String(s),
+            Token::Name(name) => match name.as_str() {
+                "true" => InputValue::Boolean(true),
+                "false" => InputValue::Boolean(false),
+                "null" => InputValue::Null,
+                _ => InputValue::Enum(name),
+            },
+            Token::Punctuator('[') => {
+                let mut items = Vec::new();
+                while !self.eat(']') {
+                    items.push(self.value(constant)?);
+                }
+                InputValue::List(items)
+            }
+            Token::Punctuator('{') => {
+                let mut fields = Vec::new();
+                while !self.eat('}') {
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    fields.push((name, self.value(constant)?));
+                }
+                InputValue::Object(fields)
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.invalid("a value"));
+            }
+        })
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    let mut line = 1;
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            // Commas are insignificant, like whitespace
+            ' ' | '\t' | '\r' | ',' | '\u{feff}' => {}
+            '#' => {
+                while chars.peek().is_some_and(|&(_, c)| c != '\n') {
+                    chars.next();
+                }
+            }
+            '!' | '$' | '&' | '(' | ')' | ':' | '=' | '@' | '[' | ']' | '{' | '|' | '}' => {
+                tokens.push((Token::Punctuator(c), line))
+            }
+            '.' => {
+                if !text[start..].starts_with("...") {
+                    return Err(format!("line {}: unexpected '.'", line));
+                }
+                chars.next();
+                chars.next();
+                tokens.push((Token::Spread, line));
+            }
+            '"' => {
+                let (string, lines) = if text[start..].starts_with("\"\"\"") {
+                    block_string(text, start, &mut chars)?
+                } else {
+                    (string(&mut chars).map_err(|reason| format!("line {}: {}", line, reason))?, 0)
+                };
+                tokens.push((Token::String(string), line));
+                line += lines;
+            }
+            c if c == '_' || c.is_ascii_alphabetic() => {
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    if c != '_' && !c.is_ascii_alphanumeric() {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                tokens.push((Token::Name(text[start..end].to_string()), line));
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    let sign = (c == '-' || c == '+') && matches!(text.as_bytes()[i - 1], b'e' | b'E');
+                    if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E') || sign) {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                let literal = &text[start..end];
+                let token = match literal.parse::<i64>() {
+                    Ok(n) => Token::Int(n),
+                    Err(_) => Token::Float(literal.parse().map_err(|_| format!("line {}: invalid number '{}'", line, literal))?),
+                };
+                tokens.push((token, line));
+            }
+            c => return Err(format!("line {}: unexpected '{}'", line, c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// The rest of a `"` string
+fn string(chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>) -> Result<String, String> {
+    let mut string = String::new();
+    loop {
+        match chars.next().map(|(_, c)| c) {
+            None | Some('\n') => return Err("unterminated string".to_string()),
+            Some('"') => return Ok(string),
+            Some('\\') => match chars.next().map(|(_, c)| c) {
+                Some('b') => string.push('\u{8}'),
+                Some('f') => string.push('\u{c}'),
+                Some('n') => string.push('\n'),
+                Some('r') => string.push('\r'),
+                Some('t') => string.push('\t'),
+                Some(c @ ('"' | '\\' | '/')) => string.push(c),
+                Some('u') => {
+                    let hex: String = (0..4).filter_map(|_| chars.next().map(|(_, c)| c)).collect();
+                    let code = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    string.push(code.ok_or_else(|| format!("invalid escape '\\u{}'", hex))?);
+                }
+                _ => return Err("invalid escape".to_string()),
+            },
+            Some(c) => string.push(c),
+        }
+    }
+}
+
+/// A `"""` block string starting at `start`, with its common indentation
+/// removed, and the number of line breaks it spans
+fn block_string(
+    text: &str,
+    start: usize,
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+) -> Result<(String, usize), String> {
+    let body_start = start + 3;
+    let mut end = None;
+    let mut search = body_start;
+    while let Some(offset) = text[search..].find("\"\"\"") {
+        let at = search + offset;
+        if text[..at].ends_with('\\') {
+            search = at + 3;
+            continue;
+        }
+        end = Some(at);
+        break;
+    }
+    let end = end.ok_or_else(|| "unterminated block string".to_string())?;
+    while chars.peek().is_some_and(|&(i, _)| i < end + 3) {
+        chars.next();
+    }
+
+    let raw = text[body_start..end].replace("\\\"\"\"", "\"\"\"");
+    let lines: Vec<&str> = raw.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut lines: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| if i == 0 { line } else { line.get(indent..).unwrap_or("") })
+        .collect();
+    while lines.first().is_some_and(|line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    Ok((lines.join("\n"), text[start..end].matches('\n').count()))
+}
+
+/// Type lookups in a schema definition
+pub(crate) struct Types<'a> {
+    definition: &'a GraphQLSchemaDefinition,
+    /// Whether unknown type names pass, as custom scalars would
+    open: bool,
+}
+
+pub(crate) const BUILT_IN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+impl<'a> Types<'a> {
+    pub(crate) fn new(definition: &'a GraphQLSchemaDefinition) -> Self {
+        Types { definition, open: false }
+    }
+
+    /// Whether `name` is neither defined nor built in
+    fn is_unknown(&self, name: &str) -> bool {
+        !self.open && self.get(name).is_none() && !BUILT_IN_SCALARS.contains(&name)
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&'a GraphQLTypeDefinition> {
+        self.definition.types.get(name)
+    }
+
+    /// The root type of an operation, named by the schema or by convention
+    pub(crate) fn root(&self, kind: OperationKind) -> &'a str {
+        let (declared, default) = match kind {
+            OperationKind::Query => (&self.definition.query, "Query"),
+            OperationKind::Mutation => (&self.definition.mutation, "Mutation"),
+            OperationKind::Subscription => (&self.definition.subscription, "Subscription"),
+        };
+        declared.as_deref().unwrap_or(default)
+    }
+
+    /// The output fields of an object or interface type
+    pub(crate) fn fields(&self, type_name: &str) -> Option<&'a HashMap<String, GraphQLField>> {
+        match self.get(type_name)? {
+            GraphQLTypeDefinition::Object { fields, .. } | GraphQLTypeDefinition::Interface { fields, .. } => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// Whether objects of type `object` match the type condition `condition`
+    pub(crate) fn applies(&self, condition: &str, object: &str) -> bool {
+        condition == object
+            || match self.get(condition) {
+                Some(GraphQLTypeDefinition::Union { types, .. }) => types.iter().any(|t| t == object),
+                Some(GraphQLTypeDefinition::Interface { .. }) => self.implements(object, condition),
+                _ => false,
+            }
+    }
+
+    fn implements(&self, object: &str, interface: &str) -> bool {
+        matches!(self.get(object), Some(GraphQLTypeDefinition::Object { interfaces, .. }) if interfaces.iter().any(|i| i == interface))
+    }
+
+    /// The object types an object, interface or union stands for
+    fn possible_types(&self, name: &'a str) -> Vec<&'a str> {
+        match self.get(name) {
+            Some(GraphQLTypeDefinition::Union { types, .. }) => types.iter().map(String::as_str).collect(),
+            Some(GraphQLTypeDefinition::Interface { .. }) => {
+                self.definition.types.keys().map(String::as_str).filter(|object| self.implements(object, name)).collect()
+            }
+            _ => vec![name],
+        }
+    }
+
+    /// Whether `name` is an interface or union
+    fn is_abstract(&self, name: &str) -> bool {
+        matches!(self.get(name), Some(GraphQLTypeDefinition::Interface { .. } | GraphQLTypeDefinition::Union { .. }))
+    }
+}
+
+/// A response key with the fields selected under it
+struct Collected<'d> {
+    /// The type whose field it is
+    parent: String,
+    fields: Vec<&'d Field>,
+    /// Whether the field may be absent, under a variable `@skip` /
+    /// `@include` or a fragment for another runtime type
+    optional: bool,
+}
+
+/// Checks a response against a schema and (optionally) an operation
+struct ResponseChecker<'a, 'd> {
+    types: Types<'a>,
+    document: Option<&'d Document>,
+}
+
+/// Validate a GraphQL response (`{data, errors, extensions}`)
+pub(crate) fn validate_response(
+    definition: &GraphQLSchemaDefinition,
+    operation: Option<&str>,
+    operation_name: Option<&str>,
+    value: &Value,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let document = match operation.map(Document::parse).transpose() {
+        Ok(document) => document,
+        Err(reason) => return vec![schema_error(context, format!("Invalid GraphQL operation: {}", reason))],
+    };
+    let selected = match document.as_ref().map(|document| document.operation(operation_name)).transpose() {
+        Ok(selected) => selected,
+        Err(reason) => return vec![schema_error(context, format!("Invalid GraphQL operation: {}", reason))],
+    };
+    check_response(definition, document.as_ref(), selected, value, context)
+}
+
+fn check_response(
+    definition: &GraphQLSchemaDefinition,
+    document: Option<&Document>,
+    selected: Option<&Operation>,
+    value: &Value,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let response = match value {
+        Value::Object(response) => response,
+        _ => return vec![ValidationError::type_mismatch(context.path.build(), "GraphQL response", value)],
+    };
+    let mut errors = Vec::new();
+    for key in response.keys() {
+        if !matches!(key.as_str(), "data" | "errors" | "extensions") {
+            errors.push(ValidationError::new(
+                context.path.build().child(key),
+                format!("A GraphQL response has no '{}' entry", key),
+                ErrorCode::ObjectAdditionalProperty,
+            ).with_suggestion(closest_match(key, ["data", "errors", "extensions"])));
+        }
+    }
+
+    let has_errors = match response.get("errors") {
+        Some(response_errors) => {
+            context.path.push("errors");
+            check_errors(response_errors, context, &mut errors);
+            context.path.pop();
+            true
+        }
+        None => false,
+    };
+    if let Some(extensions) = response.get("extensions") {
+        if !extensions.is_object() {
+            errors.push(ValidationError::type_mismatch(context.path.build().child("extensions"), "object", extensions));
+        }
+    }
+
+    let checker = ResponseChecker { types: Types::new(definition), document };
+    let root = checker.types.root(selected.map_or(OperationKind::Query, |operation| operation.kind));
+    match response.get("data") {
+        None if !has_errors => errors.push(ValidationError::missing_property(context.path.build(), "data")),
+        None => {}
+        Some(Value::Null) if !has_errors => errors.push(ValidationError::new(
+            context.path.build().child("data"),
+            "Response data is null without any errors".to_string(),
+            ErrorCode::InvalidValue,
+        )),
+        Some(Value::Null) => {}
+        Some(data) => {
+            context.path.push("data");
+            let root_ref = TypeRef::NonNull(Box::new(TypeRef::Named(root.to_string())));
+            let selections = selected.map(|operation| vec![&operation.selections]);
+            checker.check_value(data, &root_ref, selections.as_deref(), context, &mut errors);
+            context.path.pop();
+        }
+    }
+    errors
+}
+
+/// Validate a value against a single type definition; named types it
+/// refers to are taken as custom scalars
+pub(crate) fn validate_type(
+    definition: &GraphQLTypeDefinition,
+    value: &Value,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let (name, inputs) = match definition {
+        GraphQLTypeDefinition::Object { name, .. }
+        | GraphQLTypeDefinition::Interface { name, .. }
+        | GraphQLTypeDefinition::Union { name, .. }
+        | GraphQLTypeDefinition::Enum { name, .. }
+        | GraphQLTypeDefinition::Scalar { name, .. } => (name, false),
+        GraphQLTypeDefinition::InputObject { name, .. } => (name, true),
+    };
+    let schema = GraphQLSchemaDefinition {
+        query: None,
+        mutation: None,
+        subscription: None,
+        types: HashMap::from([(name.clone(), definition.clone())]),
+        directives: Vec::new(),
+    };
+    let types = Types { definition: &schema, open: true };
+    let type_ref = TypeRef::NonNull(Box::new(TypeRef::Named(name.clone())));
+    let mut errors = Vec::new();
+    if inputs {
+        check_input(&types, value, &type_ref, context, &mut errors);
+    } else {
+        let checker = ResponseChecker { types, document: None };
+        checker.check_value(value, &type_ref, None, context, &mut errors);
+    }
+    errors
+}
+
+fn schema_error(context: &ValidationContext, message: String) -> ValidationError {
+    ValidationError::new(context.path.build(), message, ErrorCode::SchemaInvalid)
+}
+
+/// Check the `errors` list of a response
+fn check_errors(value: &Value, context: &mut ValidationContext, errors: &mut Vec<ValidationError>) {
+    let items = match value {
+        Value::Array(items) if !items.is_empty() => items,
+        Value::Array(_) => {
+            errors.push(ValidationError::new(
+                context.path.build(),
+                "Response errors must not be empty when present".to_string(),
+                ErrorCode::ArrayTooShort,
+            ));
+            return;
+        }
+        _ => {
+            errors.push(ValidationError::type_mismatch(context.path.build(), "array", value));
+            return;
+        }
+    };
+    for (index, item) in items.iter().enumerate() {
+        context.path.push_index(index);
+        match item {
+            Value::Object(error) => {
+                match error.get("message") {
+                    Some(Value::String(_)) => {}
+                    Some(message) => errors.push(ValidationError::type_mismatch(context.path.build().child("message"), "string", message)),
+                    None => errors.push(ValidationError::missing_property(context.path.build(), "message")),
+                }
+                if let Some(path) = error.get("path") {
+                    let valid = path.as_array().is_some_and(|segments| segments.iter().all(|s| s.is_string() || s.is_u64()));
+                    if !valid {
+                        errors.push(ValidationError::new(
+                            context.path.build().child("path"),
+                            "Error path must be a list of field names and indexes".to_string(),
+                            ErrorCode::InvalidValue,
+                        ));
+                    }
+                }
+                if let Some(locations) = error.get("locations") {
+                    let position = |location: &Value| {
+                        ["line", "column"].iter().all(|key| location.get(key).and_then(Value::as_u64).is_some_and(|n| n > 0))
+                    };
+                    if !locations.as_array().is_some_and(|locations| locations.iter().all(position)) {
+                        errors.push(ValidationError::new(
+                            context.path.build().child("locations"),
+                            "Error locations must be a list of {line, column} positions".to_string(),
+                            ErrorCode::InvalidValue,
+                        ));
+                    }
+                }
+            }
+            _ => errors.push(ValidationError::type_mismatch(context.path.build(), "object", item)),
+        }
+        context.path.pop();
+    }
+}
+
+impl<'a, 'd> ResponseChecker<'a, 'd> {
+    /// Check `value` against `type_ref`; `selections` are the merged
+    /// selection sets of the field, if there is an operation
+    fn check_value(
+        &self,
+        value: &Value,
+        type_ref: &TypeRef,
+        selections: Option<&[&'d Vec<Selection>]>,
+        context: &mut ValidationContext,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        if !context.should_continue(errors.len()) {
+            return;
+        }
+        match type_ref {
+            TypeRef::NonNull(inner) => {
+                if value.is_null() {
+                    errors.push(ValidationError::type_mismatch(context.path.build(), &type_ref.to_string(), value));
+                } else {
+                    self.check_value(value, inner, selections, context, errors);
+                }
+            }
+            _ if value.is_null() => {}
+            TypeRef::List(inner) => match value {
+                Value::Array(items) => {
+                    for (index, item) in items.iter().enumerate() {
+                        context.path.push_index(index);
+                        self.check_value(item, inner, selections, context, errors);
+                        context.path.pop();
+                    }
+                }
+                _ => errors.push(ValidationError::type_mismatch(context.path.build(), &type_ref.to_string(), value)),
+            },
+            TypeRef::Named(name) => {
+                if let Some(error) = check_scalar(name, value, context) {
+                    errors.push(error);
+                    return;
+                }
+                if self.types.is_unknown(name) {
+                    errors.push(schema_error(context, format!("Unknown GraphQL type '{}'", name)));
+                    return;
+                }
+                match self.types.get(name) {
+                    None | Some(GraphQLTypeDefinition::Scalar { .. }) => {}
+                    Some(GraphQLTypeDefinition::Enum { values, .. }) => {
+                        if let Some(error) = check_enum(name, values, value, context) {
+                            errors.push(error);
+                        }
+                    }
+                    Some(GraphQLTypeDefinition::InputObject { .. }) => {
+                        errors.push(schema_error(context, format!("Input type '{}' cannot appear in a response", name)))
+                    }
+                    Some(_) => match value {
+                        Value::Object(object) => self.check_object(object, name, selections, context, errors),
+                        _ => errors.push(ValidationError::type_mismatch(context.path.build(), name, value)),
+                    },
+                }
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        object: &Map<String, Value>,
+        type_name: &str,
+        selections: Option<&[&'d Vec<Selection>]>,
+        context: &mut ValidationContext,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        // The runtime type, when known
+        let runtime = match object.get("__typename") {
+            Some(Value::String(typename)) => {
+                let possible = if self.types.is_abstract(type_name) {
+                    typename != type_name && self.types.applies(type_name, typename)
+                } else {
+                    typename == type_name
+                };
+                if !possible {
+                    errors.push(ValidationError::with_values(
+                        context.path.build().child("__typename"),
+                        format!("'{}' is not a possible type of {}", typename, type_name),
+                        ErrorCode::InvalidValue,
+                        Value::String(type_name.to_string()),
+                        Value::String(typename.clone()),
+                    ));
+                    return;
+                }
+                Some(typename.as_str())
+            }
+            Some(other) => {
+                errors.push(ValidationError::type_mismatch(context.path.build().child("__typename"), "String", other));
+                return;
+            }
+            None if self.types.is_abstract(type_name) => None,
+            None => Some(type_name),
+        };
+
+        let selections = match selections {
+            Some(selections) => selections,
+            None => return self.check_unselected(object, type_name, runtime, context, errors),
+        };
+
+        let mut collected: Vec<(&str, Collected<'d>)> = Vec::new();
+        let mut visited = HashSet::new();
+        for &selection_set in selections {
+            if let Err(message) = self.collect(selection_set, type_name, runtime, false, &mut collected, &mut visited) {
+                errors.push(schema_error(context, message));
+                return;
+            }
+        }
+
+        for (key, entry) in &collected {
+            let value = match object.get(*key) {
+                Some(value) => value,
+                None if entry.optional => continue,
+                None => {
+                    errors.push(ValidationError::missing_property(context.path.build(), key));
+                    continue;
+                }
+            };
+            let field_name = entry.fields[0].name.as_str();
+            context.path.push(key);
+            if field_name == "__typename" {
+                if !value.is_string() {
+                    errors.push(ValidationError::type_mismatch(context.path.build(), "String", value));
+                }
+            } else {
+                match self.field_type(&entry.parent, field_name) {
+                    Ok(type_ref) => {
+                        let nested: Vec<&'d Vec<Selection>> = entry.fields.iter().map(|field| &field.selections).collect();
+                        self.check_value(value, &type_ref, Some(&nested), context, errors);
+                    }
+                    Err(message) => errors.push(schema_error(context, message)),
+                }
+            }
+            context.path.pop();
+        }
+        for key in object.keys() {
+            if !collected.iter().any(|(selected, _)| selected == key) {
+                errors.push(ValidationError::new(
+                    context.path.build().child(key),
+                    format!("Field '{}' was not selected", key),
+                    ErrorCode::ObjectAdditionalProperty,
+                ).with_suggestion(closest_match(key, collected.iter().map(|(selected, _)| *selected).filter(|k| !object.contains_key(*k)))));
+            }
+        }
+    }
+
+    /// Without an operation, every field present must exist on the type
+    fn check_unselected(
+        &self,
+        object: &Map<String, Value>,
+        type_name: &str,
+        runtime: Option<&str>,
+        context: &mut ValidationContext,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let lookup = runtime.unwrap_or(type_name);
+        for (key, value) in object {
+            if key == "__typename" {
+                continue;
+            }
+            context.path.push(key);
+            match self.types.fields(lookup).and_then(|fields| fields.get(key)) {
+                Some(field) => match TypeRef::parse(&field.type_name) {
+                    Ok(type_ref) => self.check_value(value, &type_ref, None, context, errors),
+                    Err(reason) => errors.push(schema_error(context, format!("Invalid type of {}.{}: {}", lookup, key, reason))),
+                },
+                // A union's fields depend on its member, which `__typename` names
+                None if runtime.is_none() => {}
+                None => {
+                    let known = self.types.fields(lookup).into_iter().flat_map(|fields| fields.keys().map(String::as_str));
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("Type {} has no field '{}'", lookup, key),
+                        ErrorCode::ObjectAdditionalProperty,
+                    ).with_suggestion(closest_match(key, known)));
+                }
+            }
+            context.path.pop();
+        }
+    }
+
+    /// Collect the fields a selection set gives objects of type `runtime`
+    /// (or of some type under `parent` when it is unknown), merging those
+    /// with the same response key
+    fn collect(
+        &self,
+        selections: &'d [Selection],
+        parent: &str,
+        runtime: Option<&str>,
+        optional: bool,
+        collected: &mut Vec<(&'d str, Collected<'d>)>,
+        visited: &mut HashSet<&'d str>,
+    ) -> Result<(), String> {
+        for selection in selections {
+            match selection {
+                Selection::Field(field) => {
+                    let optional = match included(&field.directives) {
+                        Some(false) => continue,
+                        Some(true) => optional,
+                        None => true,
+                    };
+                    let key = field.response_key();
+                    match collected.iter_mut().find(|(existing, _)| *existing == key) {
+                        Some((_, entry)) => {
+                            entry.fields.push(field);
+                            entry.optional &= optional;
+                        }
+                        None => collected.push((key, Collected { parent: parent.to_string(), fields: vec![field], optional })),
+                    }
+                }
+                Selection::InlineFragment { type_condition, directives, selections } => {
+                    let condition = type_condition.as_deref().unwrap_or(parent);
+                    if let Some((parent, optional)) = self.fragment_scope(condition, parent, runtime, directives, optional) {
+                        let runtime = runtime.or(if self.types.is_abstract(&parent) { None } else { Some(parent.as_str()) });
+                        self.collect(selections, &parent, runtime, optional, collected, visited)?;
+                    }
+                }
+                Selection::FragmentSpread { name, directives } => {
+                    let document = self.document.ok_or_else(|| "fragments need an operation document".to_string())?;
+                    let (name, fragment) = document
+                        .fragments
+                        .get_key_value(name)
+                        .ok_or_else(|| format!("Unknown fragment '{}'", name))?;
+                    if !visited.insert(name.as_str()) {
+                        continue;
+                    }
+                    if let Some((parent, optional)) = self.fragment_scope(&fragment.type_condition, parent, runtime, directives, optional) {
+                        let runtime = runtime.or(if self.types.is_abstract(&parent) { None } else { Some(parent.as_str()) });
+                        self.collect(&fragment.selections, &parent, runtime, optional, collected, visited)?;
+                    }
+                    visited.remove(name.as_str());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The type a fragment's fields belong to and whether they are
+    /// optional, or `None` when the fragment does not apply
+    fn fragment_scope(
+        &self,
+        condition: &str,
+        parent: &str,
+        runtime: Option<&str>,
+        directives: &[Directive],
+        optional: bool,
+    ) -> Option<(String, bool)> {
+        let optional = match included(directives) {
+            Some(false) => return None,
+            Some(true) => optional,
+            None => true,
+        };
+        match runtime {
+            Some(runtime) if self.types.applies(condition, runtime) => Some((condition.to_string(), optional)),
+            Some(_) => None,
+            // Without `__typename`, a fragment on a narrower type may or may not apply
+            None if condition == parent => Some((condition.to_string(), optional)),
+            None => Some((condition.to_string(), true)),
+        }
+    }
+
+    fn field_type(&self, parent: &str, field_name: &str) -> Result<TypeRef, String> {
+        let field = self
+            .types
+            .fields(parent)
+            .and_then(|fields| fields.get(field_name))
+            .ok_or_else(|| format!("Cannot query field '{}' on type '{}'", field_name, parent))?;
+        TypeRef::parse(&field.type_name).map_err(|reason| format!("Invalid type of {}.{}: {}", parent, field_name, reason))
+    }
+}
+
+/// Validates GraphQL requests (a query document and its variables) and
+/// responses against a schema definition, so a gateway can reject bad
+/// operations before they reach the backend
+pub struct GraphQLValidator {
+    schema: GraphQLSchemaDefinition,
+}
+
+impl GraphQLValidator {
+    pub fn new(schema: GraphQLSchemaDefinition) -> Self {
+        Self { schema }
+    }
+
+    /// Load a schema definition from its JSON form
+    pub fn from_json(schema: &Value) -> Result<Self, FastSchemaError> {
+        Ok(Self::new(serde_json::from_value(schema.clone())?))
+    }
+
+    pub fn schema(&self) -> &GraphQLSchemaDefinition {
+        &self.schema
+    }
+
+    /// Validate a query document and its variables. The operation's fields,
+    /// arguments, fragments and directives must exist in the schema, with
+    /// argument literals of the right types, and `variables` must give
+    /// every required variable a value of its declared type. Query errors
+    /// have paths under `query` (by response key) and variable errors under
+    /// `variables`. On success, `data` holds the variables with defaults
+    /// filled in. A document that cannot be parsed is an error.
+    pub fn validate_request(
+        &self,
+        query: &str,
+        variables: &Value,
+        operation_name: Option<&str>,
+    ) -> Result<ValidationResult, FastSchemaError> {
+        let document = parse_document(query)?;
+        let mut context = ValidationContext::new(ValidationOptions::default());
+        let operation = match document.operation(operation_name) {
+            Ok(operation) => operation,
+            Err(reason) => {
+                return Ok(ValidationResult::failure(vec![ValidationError::new(
+                    ErrorPath::new(vec![PathSegment::Key("operationName".to_string())]),
+                    format!("Cannot pick an operation: {}", reason),
+                    ErrorCode::InvalidValue,
+                )]));
+            }
+        };
+
+        let mut checker = RequestChecker {
+            types: Types::new(&self.schema),
+            schema: &self.schema,
+            document: &document,
+            operation,
+            used: HashSet::new(),
+            errors: Vec::new(),
+        };
+        context.path.push("query");
+        checker.check_operation(&mut context);
+        context.path.pop();
+        context.path.push("variables");
+        let variables = checker.check_variables(variables, &mut context);
+        context.path.pop();
+
+        let errors = checker.errors;
+        Ok(if errors.is_empty() {
+            ValidationResult::success(variables)
+        } else {
+            ValidationResult::failure(errors).with_truncation(context.truncated)
+        })
+    }
+
+    /// Validate a GraphQL-over-HTTP request body, `{"query", "variables",
+    /// "operationName"}`
+    pub fn validate_request_body(&self, body: &Value) -> Result<ValidationResult, FastSchemaError> {
+        let root = ErrorPath::new(Vec::new());
+        let query = match body.get("query") {
+            Some(Value::String(query)) => query,
+            Some(query) => return Ok(ValidationResult::failure(vec![ValidationError::type_mismatch(root.child("query"), "string", query)])),
+            None if body.is_object() => return Ok(ValidationResult::failure(vec![ValidationError::missing_property(root, "query")])),
+            None => return Ok(ValidationResult::failure(vec![ValidationError::type_mismatch(root, "object", body)])),
+        };
+        let operation_name = match body.get("operationName") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(name)) => Some(name.as_str()),
+            Some(name) => {
+                return Ok(ValidationResult::failure(vec![ValidationError::type_mismatch(root.child("operationName"), "string", name)]))
+            }
+        };
+        self.validate_request(query, body.get("variables").unwrap_or(&Value::Null), operation_name)
+    }
+
+    /// Validate a response to `query` (see `SchemaType::GraphQLSchema`)
+    pub fn validate_response(
+        &self,
+        query: &str,
+        operation_name: Option<&str>,
+        response: &Value,
+    ) -> Result<ValidationResult, FastSchemaError> {
+        let document = parse_document(query)?;
+        let operation = document.operation(operation_name).map_err(|reason| FastSchemaError::InputParsing(format!("GraphQL {}", reason)))?;
+        let mut context = ValidationContext::new(ValidationOptions::default());
+        let errors = check_response(&self.schema, Some(&document), Some(operation), response, &mut context);
+        Ok(if errors.is_empty() {
+            ValidationResult::success(response.clone())
+        } else {
+            ValidationResult::failure(errors).with_truncation(context.truncated)
+        })
+    }
+}
+
+fn parse_document(query: &str) -> Result<Document, FastSchemaError> {
+    Document::parse(query).map_err(|reason| FastSchemaError::InputParsing(format!("GraphQL {}", reason)))
+}
+
+/// Checks an operation and its variables
+struct RequestChecker<'a, 'd> {
+    types: Types<'a>,
+    schema: &'a GraphQLSchemaDefinition,
+    document: &'d Document,
+    operation: &'d Operation,
+    /// Variables the operation refers to
+    used: HashSet<&'d str>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'a, 'd> RequestChecker<'a, 'd> {
+    fn error(&mut self, context: &ValidationContext, message: String) {
+        self.errors.push(ValidationError::new(context.path.build(), message, ErrorCode::InvalidValue));
+    }
+
+    fn check_operation(&mut self, context: &mut ValidationContext) {
+        let operation = self.operation;
+        let root = self.types.root(operation.kind);
+        if self.types.fields(root).is_none() {
+            let kind = format!("{:?}", operation.kind).to_lowercase();
+            return self.error(context, format!("The schema does not support {} operations", kind));
+        }
+
+        let mut defined = HashSet::new();
+        for variable in &operation.variables {
+            if !defined.insert(variable.name.as_str()) {
+                self.error(context, format!("Variable '${}' is defined more than once", variable.name));
+            }
+            let name = variable.type_ref.name();
+            let input = match self.types.get(name) {
+                Some(GraphQLTypeDefinition::Scalar { .. } | GraphQLTypeDefinition::Enum { .. } | GraphQLTypeDefinition::InputObject { .. }) => true,
+                Some(_) => false,
+                None => BUILT_IN_SCALARS.contains(&name),
+            };
+            if !input {
+                self.error(context, format!("Variable '${}' cannot be of type {}, which is not an input type", variable.name, variable.type_ref));
+            }
+            if let Some(default) = &variable.default_value {
+                context.path.push(&format!("${}", variable.name));
+                self.check_literal(default, &variable.type_ref, false, context);
+                context.path.pop();
+            }
+        }
+
+        self.check_directives(&operation.directives, context);
+        let mut fragments = Vec::new();
+        self.check_selections(&operation.selections, root, context, &mut fragments);
+
+        for variable in &operation.variables {
+            if !self.used.contains(variable.name.as_str()) {
+                self.error(context, format!("Variable '${}' is never used", variable.name));
+            }
+        }
+    }
+
+    fn check_selections(
+        &mut self,
+        selections: &'d [Selection],
+        parent: &str,
+        context: &mut ValidationContext,
+        fragments: &mut Vec<&'d str>,
+    ) {
+        for selection in selections {
+            if !context.should_continue(self.errors.len()) {
+                return;
+            }
+            match selection {
+                Selection::Field(field) => {
+                    context.path.push(field.response_key());
+                    self.check_field(field, parent, context, fragments);
+                    context.path.pop();
+                }
+                Selection::InlineFragment { type_condition, directives, selections } => {
+                    self.check_directives(directives, context);
+                    let condition = type_condition.as_deref().unwrap_or(parent);
+                    if self.check_condition(condition, parent, context) {
+                        self.check_selections(selections, condition, context, fragments);
+                    }
+                }
+                Selection::FragmentSpread { name, directives } => {
+                    self.check_directives(directives, context);
+                    let (name, fragment) = match self.document.fragments.get_key_value(name) {
+                        Some(entry) => entry,
+                        None => {
+                            self.error(context, format!("Unknown fragment '{}'", name));
+                            continue;
+                        }
+                    };
+                    if fragments.contains(&name.as_str()) {
+                        self.error(context, format!("Fragment '{}' spreads itself", name));
+                        continue;
+                    }
+                    self.check_directives(&fragment.directives, context);
+                    if self.check_condition(&fragment.type_condition, parent, context) {
+                        fragments.push(name);
+                        self.check_selections(&fragment.selections, &fragment.type_condition, context, fragments);
+                        fragments.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether a fragment on `condition` can be spread inside `parent`
+    fn check_condition(&mut self, condition: &str, parent: &str, context: &ValidationContext) -> bool {
+        if self.types.fields(condition).is_none() && !matches!(self.types.get(condition), Some(GraphQLTypeDefinition::Union { .. })) {
+            self.error(context, format!("Fragments cannot be on '{}', which is not an object, interface or union type", condition));
+            return false;
+        }
+        let overlaps = self.types.possible_types(condition).iter().any(|t| self.types.possible_types(parent).contains(t));
+        if !overlaps {
+            self.error(context, format!("A fragment on '{}' can never apply to '{}'", condition, parent));
+        }
+        overlaps
+    }
+
+    fn check_field(&mut self, field: &'d Field, parent: &str, context: &mut ValidationContext, fragments: &mut Vec<&'d str>) {
+        self.check_directives(&field.directives, context);
+        let introspection = field.name == "__schema" || field.name == "__type";
+        if field.name == "__typename" || (introspection && parent == self.types.root(OperationKind::Query)) {
+            // Introspection has its own schema, which is not checked here
+            field.arguments.iter().for_each(|(_, value)| self.check_variables_in(value));
+            if field.name == "__typename" && !field.selections.is_empty() {
+                self.error(context, "Field '__typename' cannot have a selection".to_string());
+            }
+            return;
+        }
+
+        let definition = match self.types.fields(parent).and_then(|fields| fields.get(&field.name)) {
+            Some(definition) => definition,
+            None => {
+                let known = self.types.fields(parent).into_iter().flat_map(|fields| fields.keys().map(String::as_str));
+                let error = ValidationError::new(
+                    context.path.build(),
+                    format!("Type {} has no field '{}'", parent, field.name),
+                    ErrorCode::ObjectAdditionalProperty,
+                )
+                .with_suggestion(closest_match(&field.name, known));
+                self.errors.push(error);
+                return;
+            }
+        };
+        self.check_arguments(&field.arguments, &definition.args, &format!("field '{}'", field.name), context);
+
+        let type_ref = match TypeRef::parse(&definition.type_name) {
+            Ok(type_ref) => type_ref,
+            Err(reason) => return self.errors.push(schema_error(context, format!("Invalid type of {}.{}: {}", parent, field.name, reason))),
+        };
+        let named = type_ref.name();
+        let composite = match self.types.get(named) {
+            Some(GraphQLTypeDefinition::Object { .. } | GraphQLTypeDefinition::Interface { .. } | GraphQLTypeDefinition::Union { .. }) => true,
+            Some(GraphQLTypeDefinition::InputObject { .. }) => {
+                return self.errors.push(schema_error(context, format!("Input type '{}' cannot be a field type", named)));
+            }
+            Some(_) => false,
+            None if BUILT_IN_SCALARS.contains(&named) => false,
+            None => return self.errors.push(schema_error(context, format!("Unknown GraphQL type '{}'", named))),
+        };
+        match (composite, field.selections.is_empty()) {
+            (true, true) => self.error(context, format!("Field '{}' of type {} must have a selection of subfields", field.name, type_ref)),
+            (false, false) => self.error(context, format!("Field '{}' of type {} cannot have a selection", field.name, type_ref)),
+            (true, false) => self.check_selections(&field.selections, named, context, fragments),
+            (false, true) => {}
+        }
+    }
+
+    /// Record the variables a value refers to
+    fn check_variables_in(&mut self, value: &'d InputValue) {
+        match value {
+            InputValue::Variable(name) => {
+                self.used.insert(name);
+            }
+            InputValue::List(items) => items.iter().for_each(|item| self.check_variables_in(item)),
+            InputValue::Object(fields) => fields.iter().for_each(|(_, item)| self.check_variables_in(item)),
+            _ => {}
+        }
+    }
+
+    fn check_arguments(
+        &mut self,
+        given: &'d [(String, InputValue)],
+        defined: &HashMap<String, GraphQLArgument>,
+        owner: &str,
+        context: &mut ValidationContext,
+    ) {
+        for (index, (name, value)) in given.iter().enumerate() {
+            context.path.push(name);
+            if given[..index].iter().any(|(earlier, _)| earlier == name) {
+                self.error(context, format!("Argument '{}' of {} is given more than once", name, owner));
+            }
+            match defined.get(name) {
+                Some(argument) => match TypeRef::parse(&argument.type_name) {
+                    Ok(type_ref) => self.check_literal(value, &type_ref, argument.default_value.is_some(), context),
+                    Err(reason) => self.errors.push(schema_error(context, format!("Invalid type of argument '{}': {}", name, reason))),
+                },
+                None => {
+                    self.check_variables_in(value);
+                    let error = ValidationError::new(
+                        context.path.build(),
+                        format!("Unknown argument '{}' of {}", name, owner),
+                        ErrorCode::ObjectAdditionalProperty,
+                    )
+                    .with_suggestion(closest_match(name, defined.keys().map(String::as_str)));
+                    self.errors.push(error);
+                }
+            }
+            context.path.pop();
+        }
+
+        let mut missing: Vec<&String> = defined
+            .iter()
+            .filter(|(name, argument)| {
+                argument.default_value.is_none()
+                    && TypeRef::parse(&argument.type_name).is_ok_and(|type_ref| matches!(type_ref, TypeRef::NonNull(_)))
+                    && !given.iter().any(|(given, _)| given == *name)
+            })
+            .map(|(name, _)| name)
+            .collect();
+        missing.sort();
+        for name in missing {
+            self.errors.push(ValidationError::new(
+                context.path.build().child(name),
+                format!("Argument '{}' of {} is required", name, owner),
+                ErrorCode::ObjectMissingProperty,
+            ));
+        }
+    }
+
+    fn check_directives(&mut self, directives: &'d [Directive], context: &mut ValidationContext) {
+        for directive in directives {
+            let arguments = match directive.name.as_str() {
+                "skip" | "include" => HashMap::from([(
+                    "if".to_string(),
+                    GraphQLArgument { name: "if".to_string(), type_name: "Boolean!".to_string(), default_value: None, description: None },
+                )]),
+                name => match self.schema.directives.iter().find(|defined| defined.name == name) {
+                    Some(defined) => defined.args.clone(),
+                    None => {
+                        directive.arguments.iter().for_each(|(_, value)| self.check_variables_in(value));
+                        self.error(context, format!("Unknown directive '@{}'", name));
+                        continue;
+                    }
+                },
+            };
+            self.check_arguments(&directive.arguments, &arguments, &format!("directive '@{}'", directive.name), context);
+        }
+    }
+
+    /// Check an argument literal, or a variable's type, against the
+    /// argument's type; `location_default` is whether the argument or
+    /// input field has a default value
+    fn check_literal(&mut self, value: &'d InputValue, type_ref: &TypeRef, location_default: bool, context: &mut ValidationContext) {
+        if let InputValue::Variable(name) = value {
+            self.used.insert(name);
+            match self.operation.variables.iter().find(|variable| &variable.name == name) {
+                None => self.error(context, format!("Variable '${}' is not defined by the operation", name)),
+                Some(variable) if !variable_allowed(variable, location_default, type_ref) => {
+                    self.errors.push(ValidationError::with_values(
+                        context.path.build(),
+                        format!("Variable '${}' of type {} cannot be used where {} is expected", name, variable.type_ref, type_ref),
+                        ErrorCode::InvalidType,
+                        Value::String(type_ref.to_string()),
+                        Value::String(variable.type_ref.to_string()),
+                    ));
+                }
+                Some(_) => {}
+            }
+            return;
+        }
+
+        match type_ref {
+            TypeRef::NonNull(inner) => {
+                if *value == InputValue::Null {
+                    self.errors.push(literal_mismatch(context, type_ref, value));
+                } else {
+                    self.check_literal(value, inner, false, context);
+                }
+            }
+            _ if *value == InputValue::Null => {}
+            TypeRef::List(inner) => match value {
+                InputValue::List(items) => {
+                    for (index, item) in items.iter().enumerate() {
+                        context.path.push_index(index);
+                        self.check_literal(item, inner, false, context);
+                        context.path.pop();
+                    }
+                }
+                // A single value is coerced to a list of one
+                _ => self.check_literal(value, inner, false, context),
+            },
+            TypeRef::Named(name) => {
+                let valid = match (name.as_str(), value) {
+                    ("Int", InputValue::Int(n)) => i32::try_from(*n).is_ok(),
+                    ("Float", InputValue::Int(_) | InputValue::Float(_)) => true,
+                    ("String", InputValue::String(_)) => true,
+                    ("ID", InputValue::String(_) | InputValue::Int(_)) => true,
+                    ("Boolean", InputValue::Boolean(_)) => true,
+                    ("Int" | "Float" | "String" | "ID" | "Boolean", _) => false,
+                    _ => return self.check_named_literal(name, value, context),
+                };
+                if !valid {
+                    self.errors.push(literal_mismatch(context, type_ref, value));
+                }
+            }
+        }
+    }
+
+    fn check_named_literal(&mut self, name: &str, value: &'d InputValue, context: &mut ValidationContext) {
+        match self.types.get(name) {
+            None => self.errors.push(schema_error(context, format!("Unknown GraphQL type '{}'", name))),
+            Some(GraphQLTypeDefinition::Scalar { .. }) => self.check_variables_in(value),
+            Some(GraphQLTypeDefinition::Enum { values, .. }) => match value {
+                InputValue::Enum(symbol) if values.iter().any(|value| &value.name == symbol) => {}
+                InputValue::Enum(symbol) => {
+                    let names: Vec<&str> = values.iter().map(|value| value.name.as_str()).collect();
+                    let error = ValidationError::with_values(
+                        context.path.build(),
+                        format!("'{}' is not a value of enum {}", symbol, name),
+                        ErrorCode::InvalidValue,
+                        Value::from(names.clone()),
+                        Value::String(symbol.clone()),
+                    )
+                    .with_suggestion(closest_match(symbol, names));
+                    self.errors.push(error);
+                }
+                _ => self.errors.push(literal_mismatch(context, &TypeRef::Named(name.to_string()), value)),
+            },
+            Some(GraphQLTypeDefinition::InputObject { fields, .. }) => {
+                let given = match value {
+                    InputValue::Object(given) => given,
+                    _ => return self.errors.push(literal_mismatch(context, &TypeRef::Named(name.to_string()), value)),
+                };
+                let arguments: HashMap<String, GraphQLArgument> = fields
+                    .iter()
+                    .map(|(key, field)| {
+                        let argument = GraphQLArgument {
+                            name: field.name.clone(),
+                            type_name: field.type_name.clone(),
+                            default_value: field.default_value.clone(),
+                            description: None,
+                        };
+                        (key.clone(), argument)
+                    })
+                    .collect();
+                self.check_arguments(given, &arguments, &format!("input type {}", name), context);
+            }
+            Some(_) => self.errors.push(schema_error(context, format!("Output type '{}' cannot be used as an input", name))),
+        }
+    }
+
+    /// Check the variables object, returning it with defaults filled in
+    fn check_variables(&mut self, variables: &Value, context: &mut ValidationContext) -> Value {
+        let empty = Map::new();
+        let given = match variables {
+            Value::Null => &empty,
+            Value::Object(given) => given,
+            _ => {
+                self.errors.push(ValidationError::type_mismatch(context.path.build(), "object", variables));
+                return variables.clone();
+            }
+        };
+
+        let mut filled = given.clone();
+        for variable in &self.operation.variables {
+            match given.get(&variable.name) {
+                Some(value) => {
+                    context.path.push(&variable.name);
+                    check_input(&self.types, value, &variable.type_ref, context, &mut self.errors);
+                    context.path.pop();
+                }
+                None => match &variable.default_value {
+                    Some(default) => {
+                        filled.insert(variable.name.clone(), literal_json(default));
+                    }
+                    None if matches!(variable.type_ref, TypeRef::NonNull(_)) => {
+                        self.errors.push(ValidationError::new(
+                            context.path.build().child(&variable.name),
+                            format!("Variable '${}' of type {} is required", variable.name, variable.type_ref),
+                            ErrorCode::ObjectMissingProperty,
+                        ));
+                    }
+                    None => {}
+                },
+            }
+        }
+        for key in given.keys() {
+            if !self.operation.variables.iter().any(|variable| &variable.name == key) {
+                let error = ValidationError::new(
+                    context.path.build().child(key),
+                    format!("Variable '{}' is not defined by the operation", key),
+                    ErrorCode::ObjectAdditionalProperty,
+                )
+                .with_suggestion(closest_match(key, self.operation.variables.iter().map(|variable| variable.name.as_str())));
+                self.errors.push(error);
+            }
+        }
+        Value::Object(filled)
+    }
+}
+
+/// Whether `variable` may be used where `location` is expected. A nullable
+/// variable fits a non-null location when either has a default value.
+fn variable_allowed(variable: &VariableDefinition, location_default: bool, location: &TypeRef) -> bool {
+    let has_default = location_default || variable.default_value.as_ref().is_some_and(|value| *value != InputValue::Null);
+    match (location, &variable.type_ref) {
+        (TypeRef::NonNull(location), variable) if has_default && !matches!(variable, TypeRef::NonNull(_)) => {
+            types_compatible(variable, location)
+        }
+        (location, variable) => types_compatible(variable, location),
+    }
+}
+
+fn types_compatible(variable: &TypeRef, location: &TypeRef) -> bool {
+    match (variable, location) {
+        (TypeRef::NonNull(variable), TypeRef::NonNull(location)) => types_compatible(variable, location),
+        (_, TypeRef::NonNull(_)) => false,
+        (TypeRef::NonNull(variable), location) => types_compatible(variable, location),
+        (TypeRef::List(variable), TypeRef::List(location)) => types_compatible(variable, location),
+        (_, TypeRef::List(_)) | (TypeRef::List(_), _) => false,
+        (TypeRef::Named(variable), TypeRef::Named(location)) => variable == location,
+    }
+}
+
+fn literal_mismatch(context: &ValidationContext, expected: &TypeRef, value: &InputValue) -> ValidationError {
+    let received = match value {
+        InputValue::Variable(_) => "variable",
+        InputValue::Int(_) => "Int",
+        InputValue::Float(_) => "Float",
+        InputValue::String(_) => "String",
+        InputValue::Boolean(_) => "Boolean",
+        InputValue::Null => "null",
+        InputValue::Enum(_) => "enum value",
+        InputValue::List(_) => "list",
+        InputValue::Object(_) => "object",
+    };
+    let message = match value {
+        InputValue::Int(n) if expected.name() == "Int" => format!("Int {} is outside the 32-bit range", n),
+        _ => format!("Expected {}, got {}", expected, received),
+    };
+    ValidationError::with_values(
+        context.path.build(),
+        message,
+        ErrorCode::InvalidType,
+        Value::String(expected.to_string()),
+        Value::String(received.to_string()),
+    )
+}
+
+/// The JSON form of a constant literal
+fn literal_json(value: &InputValue) -> Value {
+    match value {
+        InputValue::Variable(_) | InputValue::Null => Value::Null,
+        InputValue::Int(n) => Value::from(*n),
+        InputValue::Float(n) => Value::from(*n),
+        InputValue::String(s) | InputValue::Enum(s) => Value::String(s.clone()),
+        InputValue::Boolean(b) => Value::Bool(*b),
+        InputValue::List(items) => Value::Array(items.iter().map(literal_json).collect()),
+        InputValue::Object(fields) => Value::Object(fields.iter().map(|(key, item)| (key.clone(), literal_json(item))).collect()),
+    }
+}
+
+/// Check a built-in scalar; other types pass
+fn check_scalar(name: &str, value: &Value, context: &ValidationContext) -> Option<ValidationError> {
+    let valid = match name {
+        "Int" if value.is_i64() || value.is_u64() => {
+            let n = value.as_i64().unwrap_or(i64::MAX);
+            if i32::try_from(n).is_err() {
+                let code = if n < 0 { ErrorCode::NumberTooSmall } else { ErrorCode::NumberTooLarge };
+                return Some(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Int {} is outside the 32-bit range", value),
+                    code,
+                    Some(i32::MIN),
+                    Some(i32::MAX),
+                    value.clone(),
+                ));
+            }
+            true
+        }
+        "Int" => false,
+        "Float" => value.is_number(),
+        "String" | "ID" => value.is_string(),
+        "Boolean" => value.is_boolean(),
+        _ => true,
+    };
+    if valid {
+        None
+    } else {
+        Some(ValidationError::type_mismatch(context.path.build(), name, value))
+    }
+}
+
+fn check_enum(
+    name: &str,
+    values: &[crate::schema::GraphQLEnumValue],
+    value: &Value,
+    context: &ValidationContext,
+) -> Option<ValidationError> {
+    let names: Vec<String> = values.iter().map(|value| value.name.clone()).collect();
+    match value {
+        Value::String(s) if names.contains(s) => None,
+        Value::String(s) => Some(
+            ValidationError::with_values(
+                context.path.build(),
+                format!("'{}' is not a value of enum {}", s, name),
+                ErrorCode::InvalidValue,
+                Value::from(names.clone()),
+                value.clone(),
+            )
+            .with_suggestion(closest_match(s, names.iter().map(String::as_str))),
+        ),
+        _ => Some(ValidationError::type_mismatch(context.path.build(), name, value)),
+    }
+}
+
+/// Check a JSON input value (such as a variable) against an input type
+pub(crate) fn check_input(
+    types: &Types<'_>,
+    value: &Value,
+    type_ref: &TypeRef,
+    context: &mut ValidationContext,
+    errors: &mut Vec<ValidationError>,
+) {
+    if !context.should_continue(errors.len()) {
+        return;
+    }
+    match type_ref {
+        TypeRef::NonNull(inner) => {
+            if value.is_null() {
+                errors.push(ValidationError::type_mismatch(context.path.build(), &type_ref.to_string(), value));
+            } else {
+                check_input(types, value, inner, context, errors);
+            }
+        }
+        _ if value.is_null() => {}
+        TypeRef::List(inner) => match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    context.path.push_index(index);
+                    check_input(types, item, inner, context, errors);
+                    context.path.pop();
+                }
+            }
+            // A single value is coerced to a list of one
+            _ => check_input(types, value, inner, context, errors),
+        },
+        TypeRef::Named(name) => {
+            // Input IDs may also be integers
+            let scalar = if name == "ID" && (value.is_i64() || value.is_u64()) { None } else { check_scalar(name, value, context) };
+            if let Some(error) = scalar {
+                errors.push(error);
+                return;
+            }
+            if types.is_unknown(name) {
+                errors.push(schema_error(context, format!("Unknown GraphQL type '{}'", name)));
+                return;
+            }
+            match types.get(name) {
+                None | Some(GraphQLTypeDefinition::Scalar { .. }) => {}
+                Some(GraphQLTypeDefinition::Enum { values, .. }) => {
+                    if let Some(error) = check_enum(name, values, value, context) {
+                        errors.push(error);
+                    }
+                }
+                Some(GraphQLTypeDefinition::InputObject { fields, .. }) => {
+                    let object = match value {
+                        Value::Object(object) => object,
+                        _ => return errors.push(ValidationError::type_mismatch(context.path.build(), name, value)),
+                    };
+                    for (key, item) in object {
+                        context.path.push(key);
+                        match fields.get(key) {
+                            Some(field) => match TypeRef::parse(&field.type_name) {
+                                Ok(field_type) => check_input(types, item, &field_type, context, errors),
+                                Err(reason) => errors.push(schema_error(context, format!("Invalid type of {}.{}: {}", name, key, reason))),
+                            },
+                            None => errors.push(ValidationError::new(
+                                context.path.build(),
+                                format!("Input type {} has no field '{}'", name, key),
+                                ErrorCode::ObjectAdditionalProperty,
+                            ).with_suggestion(closest_match(key, fields.keys().map(String::as_str)))),
+                        }
+                        context.path.pop();
+                    }
+                    let mut required: Vec<&String> = fields
+                        .iter()
+                        .filter(|(key, field)| {
+                            field.default_value.is_none() && field.type_name.trim_end().ends_with('!') && !object.contains_key(*key)
+                        })
+                        .map(|(key, _)| key)
+                        .collect();
+                    required.sort();
+                    for key in required {
+                        errors.push(ValidationError::missing_property(context.path.build(), key));
+                    }
+                }
+                Some(_) => errors.push(schema_error(context, format!("Output type '{}' cannot be used as an input", name))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorCode, GraphQLValidator, SchemaType, Validator};
+    use serde_json::{json, Value};
+
+    fn field(type_name: &str) -> Value {
+        json!({"name": "", "type_name": type_name, "args": {}})
+    }
+
+    fn schema() -> Value {
+        json!({
+            "types": {
+                "Query": {"kind": "OBJECT", "name": "Query", "interfaces": [], "fields": {
+                    "user": {"name": "user", "type_name": "User", "args": {"id": {"name": "id", "type_name": "ID!"}}},
+                    "search": {"name": "search", "type_name": "[SearchResult!]!", "args": {
+                        "term": {"name": "term", "type_name": "String!"},
+                        "limit": {"name": "limit", "type_name": "Int!", "default_value": 10},
+                        "roles": {"name": "roles", "type_name": "[Role!]"}
+                    }}
+                }},
+                "Mutation": {"kind": "OBJECT", "name": "Mutation", "interfaces": [], "fields": {
+                    "createUser": {"name": "createUser", "type_name": "User!", "args": {"input": {"name": "input", "type_name": "NewUser!"}}}
+                }},
+                "NewUser": {"kind": "INPUT_OBJECT", "name": "NewUser", "fields": {
+                    "name": {"name": "name", "type_name": "String!"},
+                    "role": {"name": "role", "type_name": "Role", "default_value": "MEMBER"}
+                }},
+                "User": {"kind": "OBJECT", "name": "User", "interfaces": ["Node"], "fields": {
+                    "id": field("ID!"),
+                    "name": field("String"),
+                    "age": field("Int"),
+                    "role": field("Role!"),
+                    "friends": field("[User!]")
+                }},
+                "Post": {"kind": "OBJECT", "name": "Post", "interfaces": ["Node"], "fields": {
+                    "id": field("ID!"),
+                    "title": field("String!")
+                }},
+                "Node": {"kind": "INTERFACE", "name": "Node", "fields": {"id": field("ID!")}},
+                "SearchResult": {"kind": "UNION", "name": "SearchResult", "types": ["User", "Post"]},
+                "Role": {"kind": "ENUM", "name": "Role", "values": [{"name": "ADMIN"}, {"name": "MEMBER"}]}
+            },
+            "directives": []
+        })
+    }
+
+    fn validator(operation: Option<&str>) -> Validator {
+        let schema: SchemaType = serde_json::from_value(json!({
+            "type": "graphqlschema",
+            "schema_definition": schema(),
+            "operation": operation
+        }))
+        .unwrap();
+        Validator::new(schema).unwrap()
+    }
+
+    #[test]
+    fn test_validate_graphql_response_against_operation() {
+        let validator = validator(Some(
+            r#"
+            query Profile($withFriends: Boolean!) {
+              user {
+                id
+                displayName: name
+                role
+                friends @include(if: $withFriends) { id }
+              }
+              search { __typename ...PostFields ... on User { id } }
+            }
+            fragment PostFields on Post { title }
+            "#,
+        ));
+
+        let response = json!({"data": {
+            "user": {"id": "1", "displayName": null, "role": "ADMIN", "friends": [{"id": "2"}]},
+            "search": [{"__typename": "Post", "title": "Hi"}, {"__typename": "User", "id": "3"}]
+        }});
+        assert!(validator.validate(&response).success, "{:?}", validator.validate(&response).errors);
+
+        let response = json!({"data": {
+            "user": {"id": null, "role": "ADMN", "name": "x"},
+            "search": [{"__typename": "Post"}, {"__typename": "Comment"}]
+        }});
+        let errors = validator.validate(&response).errors;
+        let found: Vec<(String, ErrorCode)> = errors.iter().map(|e| (e.path.to_string(), e.code.clone())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("data.user.id".to_string(), ErrorCode::InvalidType),
+                ("data.user.displayName".to_string(), ErrorCode::ObjectMissingProperty),
+                ("data.user.role".to_string(), ErrorCode::InvalidValue),
+                ("data.user.name".to_string(), ErrorCode::ObjectAdditionalProperty),
+                ("data.search.[0].title".to_string(), ErrorCode::ObjectMissingProperty),
+                ("data.search.[1].__typename".to_string(), ErrorCode::InvalidValue),
+            ]
+        );
+        assert!(errors[2].message.contains("did you mean 'ADMIN'"));
+
+        // Null data needs errors, and errors need messages
+        assert!(validator.validate(&json!({"data": null, "errors": [{"message": "boom", "path": ["user"]}]})).success);
+        assert!(!validator.validate(&json!({"data": null})).success);
+        assert!(!validator.validate(&json!({"errors": [{"path": ["user"]}]})).success);
+        assert!(!validator.validate(&json!({"errors": []})).success);
+    }
+
+    #[test]
+    fn test_validate_graphql_response_without_operation() {
+        let validator = validator(None);
+        assert!(validator.validate(&json!({"data": {"user": {"id": "1", "age": 30}}})).success);
+
+        let result = validator.validate(&json!({"data": {"user": {"id": 1, "age": 3_000_000_000u64, "nmae": "x"}}, "extra": 1}));
+        let found: Vec<(String, ErrorCode)> = result.errors.iter().map(|e| (e.path.to_string(), e.code.clone())).collect();
+        assert!(found.contains(&("extra".to_string(), ErrorCode::ObjectAdditionalProperty)));
+        assert!(found.contains(&("data.user.id".to_string(), ErrorCode::InvalidType)));
+        assert!(found.contains(&("data.user.age".to_string(), ErrorCode::NumberTooLarge)));
+        assert!(found.contains(&("data.user.nmae".to_string(), ErrorCode::ObjectAdditionalProperty)));
+
+        // A bad operation is a schema problem, not a data one
+        let result = validator_with("{ user { nope } }").validate(&json!({"data": {"user": {"nope": 1}}}));
+        assert_eq!(result.errors[0].code, ErrorCode::SchemaInvalid);
+        let result = validator_with("query { user { id }").validate(&json!({"data": {}}));
+        assert_eq!(result.errors[0].code, ErrorCode::SchemaInvalid);
+    }
+
+    fn validator_with(operation: &str) -> Validator {
+        validator(Some(operation))
+    }
+
+    #[test]
+    fn test_validate_graphql_request() {
+        let validator = GraphQLValidator::from_json(&schema()).unwrap();
+        let query = r#"
+            query Find($id: ID!, $term: String!, $limit: Int) {
+              user(id: $id) { ...Named }
+              search(term: $term, roles: [ADMIN], limit: 5) { ... on Post { title } }
+              again: search(term: "x", limit: $limit) { __typename }
+            }
+            fragment Named on User { name }
+        "#;
+        let result = validator.validate_request(query, &json!({"id": 7, "term": "ann"}), None).unwrap();
+        assert!(result.success, "{:?}", result.errors);
+        assert_eq!(result.data, Some(json!({"id": 7, "term": "ann"})));
+
+        let query = r#"
+            query Find($id: ID, $unused: Int) {
+              user(id: $id, first: 1) { nmae friends }
+              search(term: 5, roles: [OWNER]) { ...Missing }
+              __typename @skip(if: $nope)
+            }
+        "#;
+        let result = validator.validate_request(query, &json!({"id": true, "extra": 1}), None).unwrap();
+        let found: Vec<(String, ErrorCode)> = result.errors.iter().map(|e| (e.path.to_string(), e.code.clone())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("query.user.id".to_string(), ErrorCode::InvalidType),
+                ("query.user.first".to_string(), ErrorCode::ObjectAdditionalProperty),
+                ("query.user.nmae".to_string(), ErrorCode::ObjectAdditionalProperty),
+                ("query.user.friends".to_string(), ErrorCode::InvalidValue),
+                ("query.search.term".to_string(), ErrorCode::InvalidType),
+                ("query.search.roles.[0]".to_string(), ErrorCode::InvalidValue),
+                ("query.search".to_string(), ErrorCode::InvalidValue),
+                ("query.__typename.if".to_string(), ErrorCode::InvalidValue),
+                ("query".to_string(), ErrorCode::InvalidValue),
+                ("variables.id".to_string(), ErrorCode::InvalidType),
+                ("variables.extra".to_string(), ErrorCode::ObjectAdditionalProperty),
+            ]
+        );
+        assert!(result.errors[2].message.contains("did you mean 'name'"));
+
+        // Input objects, defaults and the request body form
+        let body = json!({
+            "query": "mutation Add($input: NewUser!) { createUser(input: $input) { id } }",
+            "variables": {"input": {"name": "Ann", "role": "ADMN"}}
+        });
+        let result = validator.validate_request_body(&body).unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path.to_string(), "variables.input.role");
+        let result = validator.validate_request("mutation { createUser(input: {role: ADMIN}) { id } }", &json!(null), None).unwrap();
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectMissingProperty);
+        assert!(!validator.validate_request_body(&json!({"variables": {}})).unwrap().success);
+        assert!(validator.validate_request("query { user(id: 1) { id }", &json!({}), None).is_err());
+    }
+
+    #[test]
+    fn test_validate_graphql_type() {
+        let schema: SchemaType = serde_json::from_value(json!({
+            "type": "graphqltype",
+            "type_definition": {"kind": "INPUT_OBJECT", "name": "NewUser", "fields": {
+                "name": {"name": "name", "type_name": "String!"},
+                "tags": {"name": "tags", "type_name": "[String!]"},
+                "age": {"name": "age", "type_name": "Int", "default_value": 18}
+            }}
+        }))
+        .unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!({"name": "Ann", "tags": ["a"]})).success);
+        let result = validator.validate(&json!({"tags": [1], "agee": 3}));
+        let codes: Vec<ErrorCode> = result.errors.iter().map(|e| e.code.clone()).collect();
+        assert_eq!(codes, vec![ErrorCode::ObjectAdditionalProperty, ErrorCode::InvalidType, ErrorCode::ObjectMissingProperty]);
+    }
+}
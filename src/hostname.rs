@@ -0,0 +1,246 @@
+
+//! Hostname validation (RFC 1123 / RFC 5890) with punycode (RFC 3492)
+//! support for internationalized domain names.
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Maximum hostname length in octets, excluding a trailing dot
+pub const MAX_HOSTNAME_LENGTH: usize = 253;
+const MAX_LABEL_LENGTH: usize = 63;
+const ACE_PREFIX: &str = "xn--";
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(digit: u32) -> char {
+    match digit {
+        0..=25 => (b'a' + digit as u8) as char,
+        _ => (b'0' + (digit - 26) as u8) as char,
+    }
+}
+
+fn decode_digit(byte: u8) -> Option<u32> {
+    match byte {
+        b'a'..=b'z' => Some(u32::from(byte - b'a')),
+        b'A'..=b'Z' => Some(u32::from(byte - b'A')),
+        b'0'..=b'9' => Some(u32::from(byte - b'0') + 26),
+        _ => None,
+    }
+}
+
+/// Decode a punycode string (without the `xn--` prefix), or `None` if
+/// it is malformed
+pub fn punycode_decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(index) => (&input[..index], &input[index + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let (mut n, mut i, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+    let mut bytes = extended.bytes().peekable();
+
+    while bytes.peek().is_some() {
+        let old_i = i;
+        let mut weight = 1u32;
+        let mut k = BASE;
+        loop {
+            let digit = decode_digit(bytes.next()?)?;
+            i = i.checked_add(digit.checked_mul(weight)?)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            weight = weight.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+/// Encode a Unicode string as punycode (without the `xn--` prefix)
+pub fn punycode_encode(input: &str) -> Option<String> {
+    let chars: Vec<u32> = input.chars().map(u32::from).collect();
+    let mut output: String = input.chars().filter(char::is_ascii).collect();
+    let basic_length = output.len() as u32;
+    if basic_length > 0 {
+        output.push('-');
+    }
+
+    let (mut n, mut delta, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+    let mut handled = basic_length;
+    while (handled as usize) < chars.len() {
+        let m = chars.iter().copied().filter(|c| *c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+
+        for &c in &chars {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic_length);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+
+    Some(output)
+}
+
+/// Convert a label to its ASCII (A-label) form, checking LDH rules and
+/// that `xn--` labels are canonical punycode. Returns `None` if invalid.
+fn to_ascii_label(label: &str, allow_idn: bool) -> Option<String> {
+    if label.is_ascii() {
+        let lower = label.to_ascii_lowercase();
+        let ldh = lower.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-');
+        if !ldh || lower.starts_with('-') || lower.ends_with('-') {
+            return None;
+        }
+        // Hyphens in positions 3-4 are reserved for ACE prefixes
+        if lower.get(2..4) == Some("--") {
+            let encoded = lower.strip_prefix(ACE_PREFIX)?;
+            let decoded = punycode_decode(encoded)?;
+            let valid_unicode = !decoded.is_ascii() && decoded.chars().all(|c| c.is_alphanumeric() || c == '-');
+            if !valid_unicode || punycode_encode(&decoded.to_lowercase())? != encoded {
+                return None;
+            }
+        }
+        return Some(lower);
+    }
+
+    let valid_unicode = allow_idn
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_alphanumeric() || c == '-');
+    if !valid_unicode {
+        return None;
+    }
+    punycode_encode(&label.to_lowercase()).map(|encoded| format!("{}{}", ACE_PREFIX, encoded))
+}
+
+/// Validate a hostname: dot-separated labels of letters, digits and
+/// hyphens, each at most 63 octets and at most 253 in total (measured
+/// in ASCII form). With `allow_idn`, Unicode labels are accepted.
+/// With `require_tld` (the `fqdn` format), at least two labels are
+/// required, the last one alphabetic, and a trailing dot is allowed.
+pub fn is_valid_hostname(value: &str, allow_idn: bool, require_tld: bool) -> bool {
+    let name = match value.strip_suffix('.') {
+        Some(name) if require_tld => name,
+        _ => value,
+    };
+
+    let mut labels = Vec::new();
+    for label in name.split('.') {
+        match to_ascii_label(label, allow_idn) {
+            Some(ascii) if !ascii.is_empty() && ascii.len() <= MAX_LABEL_LENGTH => labels.push(ascii),
+            _ => return false,
+        }
+    }
+
+    let length = labels.iter().map(String::len).sum::<usize>() + labels.len() - 1;
+    if length > MAX_HOSTNAME_LENGTH {
+        return false;
+    }
+
+    if require_tld {
+        let tld = &labels[labels.len() - 1];
+        let valid_tld = tld.starts_with(ACE_PREFIX) || (tld.len() >= 2 && tld.bytes().all(|b| b.is_ascii_alphabetic()));
+        return labels.len() >= 2 && valid_tld;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_punycode_round_trip() {
+        assert_eq!(punycode_encode("münchen").as_deref(), Some("mnchen-3ya"));
+        assert_eq!(punycode_decode("mnchen-3ya").as_deref(), Some("münchen"));
+        assert_eq!(punycode_encode("例え").as_deref(), Some("r8jz45g"));
+        assert_eq!(punycode_decode("r8jz45g").as_deref(), Some("例え"));
+        assert!(punycode_decode("mnchen-3y!").is_none());
+    }
+
+    #[test]
+    fn test_hostnames() {
+        assert!(is_valid_hostname("example.com", false, false));
+        assert!(is_valid_hostname("localhost", false, false));
+        assert!(is_valid_hostname("xn--mnchen-3ya.de", false, false));
+        assert!(!is_valid_hostname("xn--mnchen-3yb!.de", false, false));
+        assert!(!is_valid_hostname("xn--zz.de", false, false)); // Not valid punycode
+        assert!(!is_valid_hostname("ab--cd.com", false, false)); // Reserved hyphens
+        assert!(!is_valid_hostname("-example.com", false, false));
+        assert!(!is_valid_hostname("example..com", false, false));
+        assert!(!is_valid_hostname("example.com.", false, false));
+        assert!(!is_valid_hostname(&format!("{}.com", "a".repeat(64)), false, false));
+        assert!(!is_valid_hostname(&vec!["a".repeat(63); 4].join("."), false, false)); // 255 octets
+
+        assert!(!is_valid_hostname("münchen.de", false, false));
+        assert!(is_valid_hostname("münchen.de", true, false));
+        assert!(!is_valid_hostname("mün_chen.de", true, false));
+    }
+
+    #[test]
+    fn test_fqdn() {
+        assert!(is_valid_hostname("www.example.com", false, true));
+        assert!(is_valid_hostname("www.example.com.", false, true));
+        assert!(is_valid_hostname("example.xn--p1ai", false, true));
+        assert!(!is_valid_hostname("localhost", false, true));
+        assert!(!is_valid_hostname("example.123", false, true));
+        assert!(!is_valid_hostname("example.c", false, true));
+    }
+}
@@ -1,7 +1,10 @@
 // HTML/React validation module
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::error::{ValidationError, ErrorCode};
+use crate::error::{ValidationError, ErrorCode, ErrorPath, PathSegment};
+use crate::schema::SchemaType;
+use crate::utils::{ValidationContext, ValidationOptions};
+use crate::validator::Validator;
 
 /// HTML element types supported for validation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -79,6 +82,7 @@ pub enum HtmlElementType {
 
 /// HTML props validation schema
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HtmlProps {
     pub id: Option<String>,
     pub class_name: Option<String>,
@@ -103,11 +107,16 @@ pub enum AccessibilityLevel {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReactComponent {
     pub component_name: String,
+    #[serde(default)]
     pub props_schema: HashMap<String, crate::schema::SchemaType>,
+    #[serde(default)]
     pub required_props: Vec<String>,
+    #[serde(default)]
     pub children_allowed: bool,
     pub children_schema: Option<Box<crate::schema::SchemaType>>,
+    #[serde(default)]
     pub validate_lifecycle: bool,
+    #[serde(default)]
     pub validate_hooks: bool,
 }
 
@@ -351,12 +360,26 @@ impl Default for AccessibilityLevel {
 pub struct HtmlValidator;
 
 impl HtmlValidator {
-    /// Validate an HTML element structure
+    /// Validate an HTML element structure. With `validate_semantic`, the
+    /// structure of the whole subtree is checked as a document (see
+    /// `validate_semantic_rules`).
     pub fn validate_html_element(
         value: &serde_json::Value,
         element_type: &HtmlElementType,
         props: &HtmlProps,
         path: &str,
+    ) -> Vec<ValidationError> {
+        Self::check_element(value, element_type, props, path, props.validate_semantic)
+    }
+
+    /// An element, checking the document structure below it only when
+    /// `semantic` is set
+    fn check_element(
+        value: &serde_json::Value,
+        element_type: &HtmlElementType,
+        props: &HtmlProps,
+        path: &str,
+        semantic: bool,
     ) -> Vec<ValidationError> {
         let mut errors = Vec::new();
 
@@ -365,21 +388,23 @@ impl HtmlValidator {
             if let Some(type_value) = obj.get("type") {
                 if !Self::validate_element_type(type_value, element_type) {
                     errors.push(ValidationError {
-                        path: format!("{}.type", path),
+                        path: format!("{}.type", path).into(),
                         message: format!("Expected element type '{}', got '{:?}'",
                                        element_type.tag_name(), type_value),
                         code: ErrorCode::InvalidType,
-                        received: Some(format!("{:?}", type_value)),
-                        expected: Some(element_type.tag_name()),
+                        severity: ErrorCode::InvalidType.default_severity(),
+                        params: serde_json::json!({"expected": element_type.tag_name(), "received": type_value}),
+                        location: None,
                     });
                 }
             } else {
                 errors.push(ValidationError {
-                    path: format!("{}.type", path),
+                    path: format!("{}.type", path).into(),
                     message: "HTML element must have a 'type' property".to_string(),
                     code: ErrorCode::Required,
-                    received: None,
-                    expected: Some(element_type.tag_name()),
+                    severity: ErrorCode::Required.default_severity(),
+                    params: serde_json::json!({"expected": element_type.tag_name()}),
+                    location: None,
                 });
             }
 
@@ -391,43 +416,74 @@ impl HtmlValidator {
                 errors.extend(props_errors);
             }
 
-            // Validate children
-            if let Some(children_value) = obj.get("children") {
-                if !element_type.allows_children() {
-                    errors.push(ValidationError {
-                        path: format!("{}.children", path),
-                        message: format!("Element '{}' cannot have children", element_type.tag_name()),
-                        code: ErrorCode::InvalidStructure,
-                        received: Some("children".to_string()),
-                        expected: None,
-                    });
-                }
+            // Validate children; their own schemas are checked by `validate_element_tree`
+            if obj.contains_key("children") && !element_type.allows_children() {
+                errors.push(ValidationError {
+                    path: format!("{}.children", path).into(),
+                    message: format!("Element '{}' cannot have children", element_type.tag_name()),
+                    code: ErrorCode::InvalidStructure,
+                    severity: ErrorCode::InvalidStructure.default_severity(),
+                    params: serde_json::json!({"received": "children"}),
+                    location: None,
+                });
             }
 
             // Semantic validation
-            if props.validate_semantic {
-                let semantic_errors = Self::validate_semantic_rules(element_type, obj, path);
-                errors.extend(semantic_errors);
+            if semantic {
+                errors.extend(Self::validate_semantic_rules(obj, path));
             }
 
         } else {
             errors.push(ValidationError {
-                path: path.to_string(),
+                path: path.into(),
                 message: "Expected React element object".to_string(),
                 code: ErrorCode::InvalidType,
-                received: Some(format!("{:?}", value)),
-                expected: Some("React element object".to_string()),
+                severity: ErrorCode::InvalidType.default_severity(),
+                params: serde_json::json!({"expected": "React element object", "received": value}),
+                location: None,
             });
         }
 
         errors
     }
 
-    /// Validate React component
+    /// Validate an element tree against an `HtmlElement` or
+    /// `ReactComponent` schema: each node, then its `children` (or
+    /// `props.children`) against the node's children schema, recursively.
+    /// Children may be a single node, text or an array; `null` and booleans
+    /// render nothing. Errors have paths under `path`, such as
+    /// `root.children[2].props.src`.
+    pub fn validate_element_tree(
+        value: &serde_json::Value,
+        schema: &SchemaType,
+        path: &str,
+    ) -> Vec<ValidationError> {
+        let validator = match Validator::new(schema.clone()) {
+            Ok(validator) => validator,
+            Err(e) => return vec![ValidationError::new(path, e.to_string(), ErrorCode::SchemaInvalid)],
+        };
+        validator.validate_value(value, &validator.compiled_schema.schema, &mut context_at(path))
+    }
+
+    /// Validate React component, including each prop with a schema in
+    /// `props_schema`
     pub fn validate_react_component(
         value: &serde_json::Value,
         component: &ReactComponent,
         path: &str,
+    ) -> Vec<ValidationError> {
+        let validator = Validator::from_compiled(SchemaType::Any.compile());
+        Self::validate_component_node(&validator, value, component, path, &mut context_at(path))
+    }
+
+    /// A component without its children; props are checked by `validator`,
+    /// so `$ref`s and custom formats resolve as they do elsewhere
+    fn validate_component_node(
+        validator: &Validator,
+        value: &serde_json::Value,
+        component: &ReactComponent,
+        path: &str,
+        context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
         let mut errors = Vec::new();
 
@@ -437,12 +493,13 @@ impl HtmlValidator {
                 if let Some(type_str) = type_value.as_str() {
                     if type_str != component.component_name {
                         errors.push(ValidationError {
-                            path: format!("{}.type", path),
+                            path: format!("{}.type", path).into(),
                             message: format!("Expected component '{}', got '{}'",
                                            component.component_name, type_str),
                             code: ErrorCode::InvalidType,
-                            received: Some(type_str.to_string()),
-                            expected: Some(component.component_name.clone()),
+                            severity: ErrorCode::InvalidType.default_severity(),
+                            params: serde_json::json!({"expected": component.component_name, "received": type_str}),
+                            location: None,
                         });
                     }
                 }
@@ -454,22 +511,26 @@ impl HtmlValidator {
                 for required_prop in &component.required_props {
                     if !props_obj.contains_key(required_prop) {
                         errors.push(ValidationError {
-                            path: format!("{}.props.{}", path, required_prop),
+                            path: format!("{}.props.{}", path, required_prop).into(),
                             message: format!("Required prop '{}' is missing", required_prop),
                             code: ErrorCode::Required,
-                            received: None,
-                            expected: Some(required_prop.clone()),
+                            severity: ErrorCode::Required.default_severity(),
+                            params: serde_json::json!({"expected": required_prop}),
+                            location: None,
                         });
                     }
                 }
 
-                // Validate each prop according to schema
-                for (prop_name, prop_schema) in &component.props_schema {
+                // Validate each prop according to schema, in name order
+                let mut prop_schemas: Vec<_> = component.props_schema.iter().collect();
+                prop_schemas.sort_by_key(|(prop_name, _)| prop_name.as_str());
+                for (prop_name, prop_schema) in prop_schemas {
                     if let Some(prop_value) = props_obj.get(prop_name) {
-                        let prop_path = format!("{}.props.{}", path, prop_name);
-                        // Note: This would need to call the main validator
-                        // let prop_errors = validate_value(prop_value, prop_schema, &prop_path);
-                        // errors.extend(prop_errors);
+                        context.path.push("props");
+                        context.path.push(prop_name);
+                        errors.extend(validator.validate_value(prop_value, prop_schema, context));
+                        context.path.pop();
+                        context.path.pop();
                     }
                 }
             }
@@ -477,12 +538,13 @@ impl HtmlValidator {
             // Validate children
             if !component.children_allowed && obj.contains_key("children") {
                 errors.push(ValidationError {
-                    path: format!("{}.children", path),
+                    path: format!("{}.children", path).into(),
                     message: format!("Component '{}' does not accept children",
                                    component.component_name),
                     code: ErrorCode::InvalidStructure,
-                    received: Some("children".to_string()),
-                    expected: None,
+                    severity: ErrorCode::InvalidStructure.default_severity(),
+                    params: serde_json::json!({"received": "children"}),
+                    location: None,
                 });
             }
         }
@@ -513,11 +575,12 @@ impl HtmlValidator {
             for required_attr in &attr_rules.required_attributes {
                 if !props_obj.contains_key(required_attr) {
                     errors.push(ValidationError {
-                        path: format!("{}.{}", path, required_attr),
+                        path: format!("{}.{}", path, required_attr).into(),
                         message: format!("Required attribute '{}' is missing", required_attr),
                         code: ErrorCode::Required,
-                        received: None,
-                        expected: Some(required_attr.clone()),
+                        severity: ErrorCode::Required.default_severity(),
+                        params: serde_json::json!({"expected": required_attr}),
+                        location: None,
                     });
                 }
             }
@@ -528,25 +591,27 @@ impl HtmlValidator {
                 if !attr_rules.allowed_attributes.contains(attr_name) &&
                    !attr_name.starts_with("data-") &&
                    !attr_name.starts_with("aria-") &&
-                   attr_name != "className" && attr_name != "style" {
+                   attr_name != "className" && attr_name != "style" && attr_name != "children" {
                     errors.push(ValidationError {
-                        path: format!("{}.{}", path, attr_name),
+                        path: format!("{}.{}", path, attr_name).into(),
                         message: format!("Attribute '{}' is not allowed on element '{}'",
                                        attr_name, element_type.tag_name()),
                         code: ErrorCode::UnknownKey,
-                        received: Some(attr_name.clone()),
-                        expected: None,
+                        severity: ErrorCode::UnknownKey.default_severity(),
+                        params: serde_json::json!({"received": attr_name}),
+                        location: None,
                     });
                 }
 
                 // Check deprecated attributes
                 if attr_rules.deprecated_attributes.contains(attr_name) {
                     errors.push(ValidationError {
-                        path: format!("{}.{}", path, attr_name),
+                        path: format!("{}.{}", path, attr_name).into(),
                         message: format!("Attribute '{}' is deprecated", attr_name),
                         code: ErrorCode::DeprecatedFeature,
-                        received: Some(attr_name.clone()),
-                        expected: None,
+                        severity: ErrorCode::DeprecatedFeature.default_severity(),
+                        params: serde_json::json!({"received": attr_name}),
+                        location: None,
                     });
                 }
 
@@ -554,11 +619,12 @@ impl HtmlValidator {
                 if attr_rules.boolean_attributes.contains(attr_name) {
                     if !attr_value.is_boolean() {
                         errors.push(ValidationError {
-                            path: format!("{}.{}", path, attr_name),
+                            path: format!("{}.{}", path, attr_name).into(),
                             message: format!("Attribute '{}' must be a boolean", attr_name),
                             code: ErrorCode::InvalidType,
-                            received: Some(format!("{:?}", attr_value)),
-                            expected: Some("boolean".to_string()),
+                            severity: ErrorCode::InvalidType.default_severity(),
+                            params: serde_json::json!({"expected": "boolean", "received": attr_value}),
+                            location: None,
                         });
                     }
                 }
@@ -568,11 +634,12 @@ impl HtmlValidator {
                     if let Some(url_str) = attr_value.as_str() {
                         if !Self::is_valid_url(url_str) {
                             errors.push(ValidationError {
-                                path: format!("{}.{}", path, attr_name),
+                                path: format!("{}.{}", path, attr_name).into(),
                                 message: format!("Invalid URL in attribute '{}'", attr_name),
                                 code: ErrorCode::InvalidFormat,
-                                received: Some(url_str.to_string()),
-                                expected: Some("valid URL".to_string()),
+                                severity: ErrorCode::InvalidFormat.default_severity(),
+                                params: serde_json::json!({"expected": "valid URL", "received": url_str}),
+                                location: None,
                             });
                         }
                     }
@@ -583,12 +650,13 @@ impl HtmlValidator {
                     if let Some(attr_str) = attr_value.as_str() {
                         if !allowed_values.contains(&attr_str.to_string()) {
                             errors.push(ValidationError {
-                                path: format!("{}.{}", path, attr_name),
+                                path: format!("{}.{}", path, attr_name).into(),
                                 message: format!("Invalid value '{}' for attribute '{}'",
                                                attr_str, attr_name),
                                 code: ErrorCode::InvalidValue,
-                                received: Some(attr_str.to_string()),
-                                expected: Some(format!("one of: {}", allowed_values.join(", "))),
+                                severity: ErrorCode::InvalidValue.default_severity(),
+                                params: serde_json::json!({"expected": format!("one of: {}", allowed_values.join(", ")), "received": attr_str}),
+                                location: None,
                             });
                         }
                     }
@@ -606,28 +674,18 @@ impl HtmlValidator {
         errors
     }
 
+    /// Check the structure of the document rooted at `element_obj`: one
+    /// `<main>`, outside other landmarks; no `<header>` or `<footer>`
+    /// inside another; heading levels that do not skip; and landmarks of
+    /// the same role told apart by their labels. Hidden subtrees are left
+    /// out, as they are from the accessibility tree.
     fn validate_semantic_rules(
-        element_type: &HtmlElementType,
         element_obj: &serde_json::Map<String, serde_json::Value>,
         path: &str,
     ) -> Vec<ValidationError> {
-        let mut errors = Vec::new();
-        let rules = element_type.get_semantic_rules();
-
-        // This is a simplified version - in a real implementation,
-        // you'd need more context about the document structure
-        for rule in rules {
-            // Add warnings for semantic violations
-            errors.push(ValidationError {
-                path: path.to_string(),
-                message: format!("Semantic guideline: {}", rule),
-                code: ErrorCode::SemanticWarning,
-                received: None,
-                expected: None,
-            });
-        }
-
-        errors
+        let mut outline = DocumentOutline::default();
+        outline.visit_element(element_obj, path);
+        outline.errors
     }
 
     fn validate_accessibility(
@@ -642,11 +700,12 @@ impl HtmlValidator {
             HtmlElementType::Image => {
                 if !props_obj.contains_key("alt") {
                     errors.push(ValidationError {
-                        path: format!("{}.alt", path),
+                        path: format!("{}.alt", path).into(),
                         message: "Images must have alt text for accessibility".to_string(),
                         code: ErrorCode::AccessibilityViolation,
-                        received: None,
-                        expected: Some("alt attribute".to_string()),
+                        severity: ErrorCode::AccessibilityViolation.default_severity(),
+                        params: serde_json::json!({"expected": "alt attribute"}),
+                        location: None,
                     });
                 }
             },
@@ -654,11 +713,12 @@ impl HtmlValidator {
                 if !props_obj.contains_key("aria-label") &&
                    !props_obj.contains_key("children") {
                     errors.push(ValidationError {
-                        path: path.to_string(),
+                        path: path.into(),
                         message: "Buttons must have accessible text (children or aria-label)".to_string(),
                         code: ErrorCode::AccessibilityViolation,
-                        received: None,
-                        expected: Some("aria-label or text content".to_string()),
+                        severity: ErrorCode::AccessibilityViolation.default_severity(),
+                        params: serde_json::json!({"expected": "aria-label or text content"}),
+                        location: None,
                     });
                 }
             },
@@ -667,11 +727,12 @@ impl HtmlValidator {
                     if type_val.as_str() == Some("submit") || type_val.as_str() == Some("button") {
                         if !props_obj.contains_key("value") && !props_obj.contains_key("aria-label") {
                             errors.push(ValidationError {
-                                path: path.to_string(),
+                                path: path.into(),
                                 message: "Input buttons must have accessible text".to_string(),
                                 code: ErrorCode::AccessibilityViolation,
-                                received: None,
-                                expected: Some("value or aria-label attribute".to_string()),
+                                severity: ErrorCode::AccessibilityViolation.default_severity(),
+                                params: serde_json::json!({"expected": "value or aria-label attribute"}),
+                                location: None,
                             });
                         }
                     }
@@ -687,11 +748,12 @@ impl HtmlValidator {
                 if let Some(style) = props_obj.get("style").and_then(|s| s.as_object()) {
                     if style.contains_key("color") && !style.contains_key("background-color") {
                         errors.push(ValidationError {
-                            path: format!("{}.style", path),
+                            path: format!("{}.style", path).into(),
                             message: "Color alone should not be used to convey information".to_string(),
                             code: ErrorCode::AccessibilityViolation,
-                            received: None,
-                            expected: Some("sufficient color contrast".to_string()),
+                            severity: ErrorCode::AccessibilityViolation.default_severity(),
+                            params: serde_json::json!({"expected": "sufficient color contrast"}),
+                            location: None,
                         });
                     }
                 }
@@ -711,4 +773,372 @@ impl HtmlValidator {
         url.starts_with("/") ||
         url.starts_with("#")
     }
-}
\ No newline at end of file
+}
+
+/// What a document walk has seen so far, for the checks that depend on
+/// more than one element
+#[derive(Default)]
+struct DocumentOutline {
+    /// Tags of the elements enclosing the current one, outermost first
+    ancestors: Vec<String>,
+    /// Level of the last heading
+    heading: Option<u8>,
+    /// Labels of the landmarks seen, by role
+    landmarks: HashMap<String, Vec<Option<String>>>,
+    errors: Vec<ValidationError>,
+}
+
+impl DocumentOutline {
+    fn visit(&mut self, node: &serde_json::Value, path: &str) {
+        match node {
+            serde_json::Value::Object(element) => self.visit_element(element, path),
+            serde_json::Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.visit(item, &format!("{}[{}]", path, index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_element(&mut self, element: &serde_json::Map<String, serde_json::Value>, path: &str) {
+        let props = element.get("props").and_then(|props| props.as_object());
+        let prop = |name: &str| props.and_then(|props| props.get(name));
+        let hidden = prop("hidden").is_some_and(|hidden| hidden != false);
+        if hidden || prop("aria-hidden").is_some_and(|aria| aria == true || aria == "true") {
+            return;
+        }
+        // Components are transparent: only the elements they render count
+        let tag = element.get("type").and_then(|tag| tag.as_str()).unwrap_or_default().to_ascii_lowercase();
+        self.check(&tag, props, path);
+
+        self.ancestors.push(tag);
+        match (element.get("children"), prop("children")) {
+            (Some(children), _) => self.visit(children, &format!("{}.children", path)),
+            (None, Some(children)) => self.visit(children, &format!("{}.props.children", path)),
+            (None, None) => {}
+        }
+        self.ancestors.pop();
+    }
+
+    fn check(&mut self, tag: &str, props: Option<&serde_json::Map<String, serde_json::Value>>, path: &str) {
+        match tag {
+            "header" | "footer" => {
+                if let Some(ancestor) = self.inside(&["header", "footer", "address"]) {
+                    self.error(path, format!("<{}> cannot be nested inside <{}>", tag, ancestor), serde_json::json!({"element": tag, "ancestor": ancestor}));
+                }
+            }
+            "main" => {
+                if let Some(ancestor) = self.inside(&["article", "aside", "footer", "header", "nav"]) {
+                    self.error(path, format!("<main> cannot be nested inside <{}>", ancestor), serde_json::json!({"element": tag, "ancestor": ancestor}));
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(level) = heading_level(tag) {
+            if let Some(previous) = self.heading.filter(|previous| level > previous + 1) {
+                self.error(
+                    path,
+                    format!("Heading level skipped: <h{}> follows <h{}>", level, previous),
+                    serde_json::json!({"level": level, "previous": previous}),
+                );
+            }
+            self.heading = Some(level);
+        }
+
+        let text = |name: &str| props.and_then(|props| props.get(name)).and_then(|value| value.as_str());
+        let label = text("aria-label")
+            .map(|label| label.trim().to_lowercase())
+            .or_else(|| text("aria-labelledby").map(|ids| format!("#{}", ids.trim())));
+        let sectioned = self.inside(&["article", "aside", "main", "nav", "section"]).is_some();
+        let role = match (text("role"), tag) {
+            (Some(role), _) => role.trim().to_ascii_lowercase(),
+            (None, "main") => "main".to_string(),
+            (None, "nav") => "navigation".to_string(),
+            (None, "aside") => "complementary".to_string(),
+            (None, "search") => "search".to_string(),
+            (None, "header") if !sectioned => "banner".to_string(),
+            (None, "footer") if !sectioned => "contentinfo".to_string(),
+            // Sections and forms are landmarks only when labelled
+            (None, "section") if label.is_some() => "region".to_string(),
+            (None, "form") if label.is_some() => "form".to_string(),
+            _ => return,
+        };
+        if !LANDMARK_ROLES.contains(&role.as_str()) {
+            return;
+        }
+
+        let seen = self.landmarks.entry(role.clone()).or_default();
+        let message = if seen.is_empty() {
+            None
+        } else if matches!(role.as_str(), "main" | "banner" | "contentinfo") {
+            Some(match tag {
+                "main" => "A document should have only one <main>".to_string(),
+                _ => format!("A document should have only one '{}' landmark", role),
+            })
+        } else if seen.contains(&label) {
+            Some(format!("Each '{}' landmark needs a distinct aria-label when there are several", role))
+        } else {
+            None
+        };
+        seen.push(label);
+        if let Some(message) = message {
+            self.error(path, message, serde_json::json!({"role": role}));
+        }
+    }
+
+    /// The closest enclosing element among `tags`
+    fn inside(&self, tags: &[&str]) -> Option<String> {
+        self.ancestors.iter().rev().find(|tag| tags.contains(&tag.as_str())).cloned()
+    }
+
+    fn error(&mut self, path: &str, message: String, params: serde_json::Value) {
+        self.errors.push(ValidationError {
+            path: path.into(),
+            message,
+            code: ErrorCode::SemanticWarning,
+            severity: ErrorCode::SemanticWarning.default_severity(),
+            params,
+            location: None,
+        });
+    }
+}
+
+const LANDMARK_ROLES: &[&str] = &["banner", "complementary", "contentinfo", "form", "main", "navigation", "region", "search"];
+
+/// The level of an `h1`-`h6` tag
+fn heading_level(tag: &str) -> Option<u8> {
+    match tag.strip_prefix('h')?.parse() {
+        Ok(level @ 1..=6) => Some(level),
+        _ => None,
+    }
+}
+
+/// A context whose paths start at `path`
+fn context_at(path: &str) -> ValidationContext {
+    let mut context = ValidationContext::new(ValidationOptions::default());
+    for segment in ErrorPath::parse(path).segments() {
+        match segment {
+            PathSegment::Key(key) => context.path.push(key),
+            PathSegment::Index(index) => context.path.push_index(*index),
+        }
+    }
+    context
+}
+
+/// An `HtmlElement` node of a tree: the element itself, then its children
+pub(crate) fn validate_element(
+    validator: &Validator,
+    value: &serde_json::Value,
+    element_type: &HtmlElementType,
+    props: &HtmlProps,
+    children: Option<&SchemaType>,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let path = context.path.build().display_path();
+    // The outermost semantic element checks the document below it once
+    let document = props.validate_semantic && !context.html_document;
+    let mut errors = HtmlValidator::check_element(value, element_type, props, &path, document);
+    if let (Some(children), true) = (children, element_type.allows_children()) {
+        let outer = context.html_document;
+        context.html_document |= document;
+        errors.extend(validate_children(validator, value, children, context));
+        context.html_document = outer;
+    }
+    errors
+}
+
+/// A `ReactComponent` node of a tree: the component itself, then its children
+pub(crate) fn validate_component(
+    validator: &Validator,
+    value: &serde_json::Value,
+    component: &ReactComponent,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let path = context.path.build().display_path();
+    let mut errors = HtmlValidator::validate_component_node(validator, value, component, &path, context);
+    if let (Some(children), true) = (&component.children_schema, component.children_allowed) {
+        errors.extend(validate_children(validator, value, children, context));
+    }
+    errors
+}
+
+/// Check the children of an element, given at the top level as in the
+/// examples here or under `props` as `React.createElement` produces them
+fn validate_children(
+    validator: &Validator,
+    element: &serde_json::Value,
+    schema: &SchemaType,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let (segments, children): (&[&str], _) = match element.get("children") {
+        Some(children) => (&["children"], children),
+        None => match element.get("props").and_then(|props| props.get("children")) {
+            Some(children) => (&["props", "children"], children),
+            None => return Vec::new(),
+        },
+    };
+    for segment in segments {
+        context.path.push(segment);
+    }
+    let errors = validate_child(validator, children, schema, context);
+    for _ in segments {
+        context.path.pop();
+    }
+    errors
+}
+
+fn validate_child(
+    validator: &Validator,
+    child: &serde_json::Value,
+    schema: &SchemaType,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    match child {
+        serde_json::Value::Null | serde_json::Value::Bool(_) => Vec::new(),
+        serde_json::Value::Array(items) => {
+            let mut errors = Vec::new();
+            for (index, item) in items.iter().enumerate() {
+                if !context.should_continue(errors.len()) {
+                    break;
+                }
+                context.path.push_index(index);
+                errors.extend(validate_child(validator, item, schema, context));
+                context.path.pop();
+            }
+            errors
+        }
+        child => validator.validate_value(child, schema, context),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn element(element_type: HtmlElementType, children: Option<SchemaType>) -> SchemaType {
+        SchemaType::HtmlElement {
+            element_type,
+            props: HtmlProps { validate_semantic: false, ..HtmlProps::default() },
+            children: children.map(Box::new),
+            validate_dom: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_element_tree() {
+        let text = SchemaType::Const { value: json!("Hello") };
+        let paragraph = element(HtmlElementType::Paragraph, Some(text.clone()));
+        let image = element(HtmlElementType::Image, None);
+        let schema = element(
+            HtmlElementType::Div,
+            Some(SchemaType::Union { options: vec![text, paragraph, image], discriminator: None }),
+        );
+
+        let tree = json!({
+            "type": "div",
+            "props": {"className": "card"},
+            "children": [
+                "Hello",
+                {"type": "p", "props": {"children": ["Hello", null, "Bye"]}},
+                {"type": "img", "props": {"src": "photo.png", "alt": "A photo"}}
+            ]
+        });
+        let errors = HtmlValidator::validate_element_tree(&tree, &schema, "root");
+        let paths: Vec<String> = errors.iter().map(|error| error.path.display_path()).collect();
+        // Each child that matches no option is reported with its closest one
+        assert_eq!(
+            paths,
+            vec!["root.children[1]", "root.children[1].props.children[2]", "root.children[2]", "root.children[2].props.src"]
+        );
+        assert_eq!(errors[0].code, ErrorCode::AnyOfNoMatch);
+        assert_eq!(errors[3].code, ErrorCode::InvalidFormat);
+
+        let valid = json!({"type": "div", "props": {}, "children": {"type": "img", "props": {"src": "/a.png", "alt": ""}}});
+        assert!(HtmlValidator::validate_element_tree(&valid, &schema, "root").is_empty());
+        assert!(crate::validator::Validator::new(schema).unwrap().validate(&valid).success);
+    }
+
+    #[test]
+    fn test_validate_react_component_props() {
+        use crate::builder::Schema;
+
+        let component = ReactComponent {
+            component_name: "Avatar".to_string(),
+            props_schema: vec![
+                ("size".to_string(), Schema::integer().min(16).build().unwrap()),
+                ("user".to_string(), Schema::object().prop("email", Schema::string().email()).build().unwrap()),
+            ]
+            .into_iter()
+            .collect(),
+            required_props: vec!["user".to_string()],
+            children_allowed: false,
+            children_schema: None,
+            validate_lifecycle: false,
+            validate_hooks: false,
+        };
+
+        let value = json!({"type": "Avatar", "props": {"size": 8, "user": {"email": "ann"}}});
+        let errors = HtmlValidator::validate_react_component(&value, &component, "root.children[0]");
+        let paths: Vec<String> = errors.iter().map(|error| error.path.display_path()).collect();
+        assert_eq!(paths, vec!["root.children[0].props.size", "root.children[0].props.user.email"]);
+        assert_eq!((errors[0].code.clone(), errors[1].code.clone()), (ErrorCode::NumberTooSmall, ErrorCode::StringFormatInvalid));
+
+        let validator = Validator::new(SchemaType::ReactComponent { component }).unwrap();
+        assert!(validator.validate(&json!({"type": "Avatar", "props": {"user": {"email": "ann@example.com"}}})).success);
+        let result = validator.validate(&json!({"type": "Avatar", "props": {"size": "big", "user": {"email": "b@example.com"}}}));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path.display_path(), "props.size");
+    }
+
+    #[test]
+    fn test_validate_semantic_structure() {
+        let document = crate::markup::parse_html(concat!(
+            "<div><header><h1>Shop</h1><nav aria-label=Main></nav></header>",
+            "<main><h2>Offers</h2><h4>Today</h4><article><header><h3>Tea</h3></header></article></main>",
+            "<main hidden><h6>Old</h6></main>",
+            "<nav aria-label=main></nav>",
+            "<footer><header></header></footer><main></main></div>",
+        ))
+        .unwrap();
+
+        let semantic = HtmlProps { validate_accessibility: false, ..HtmlProps::default() };
+        let errors = HtmlValidator::validate_html_element(&document, &HtmlElementType::Div, &semantic, "root");
+        let found: Vec<(String, String)> =
+            errors.iter().map(|error| (error.path.display_path(), error.message.clone())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("root.children[1].children[1]".to_string(), "Heading level skipped: <h4> follows <h2>".to_string()),
+                ("root.children[3]".to_string(), "Each 'navigation' landmark needs a distinct aria-label when there are several".to_string()),
+                ("root.children[4].children[0]".to_string(), "<header> cannot be nested inside <footer>".to_string()),
+                ("root.children[4].children[0]".to_string(), "A document should have only one 'banner' landmark".to_string()),
+                ("root.children[5]".to_string(), "A document should have only one <main>".to_string()),
+            ]
+        );
+        assert!(errors.iter().all(|error| error.code == ErrorCode::SemanticWarning));
+
+        // Nested semantic elements leave the document to the outermost one
+        let header = SchemaType::HtmlElement {
+            element_type: HtmlElementType::Header,
+            props: semantic.clone(),
+            children: Some(Box::new(SchemaType::Any)),
+            validate_dom: false,
+        };
+        let schema = SchemaType::HtmlElement {
+            element_type: HtmlElementType::Div,
+            props: semantic,
+            children: Some(Box::new(header)),
+            validate_dom: false,
+        };
+        let tree = json!({"type": "div", "children": [
+            {"type": "header", "children": {"type": "header"}},
+            {"type": "header"}
+        ]});
+        let result = Validator::new(schema).unwrap().validate(&tree);
+        let paths: Vec<String> = result.warnings.iter().map(|warning| warning.path.display_path()).collect();
+        assert_eq!(paths, vec!["children[0].children", "children[1]"]);
+    }
+}
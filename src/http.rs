@@ -0,0 +1,294 @@
+
+//! HTTP request bodies: `ValidatedJson<T>` reads and validates a JSON body
+//! in one pass, and rejects it with an RFC 7807 `application/problem+json`
+//! response. With the `axum` or `actix-web` feature `ValidatedJson<T>` is an
+//! extractor for that framework, reading its validator from the router state
+//! (`Arc<Validator>`) or app data (`web::Data<Validator>`).
+
+use crate::de::{deserialization_error, Rejected, ValidatingDeserializer};
+use crate::error::{ErrorCode, ValidationError, ValidationResult};
+use crate::validator::Validator;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// A request body that passed its schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatedJson<T>(pub T);
+
+impl<T: DeserializeOwned> ValidatedJson<T> {
+    /// Deserialize and validate `body`. Malformed JSON is rejected with 400,
+    /// a body that fails the schema or doesn't fit `T` with 422.
+    pub fn from_slice(validator: &Validator, body: &[u8]) -> Result<Self, ProblemDetails> {
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        let value = ValidatingDeserializer::new(validator, &mut deserializer)
+            .read()
+            .and_then(|value| deserializer.end().map(|_| value).map_err(Rejected::Unreadable));
+        match value {
+            Ok(value) => Ok(ValidatedJson(value)),
+            Err(Rejected::Invalid(errors)) => Err(ProblemDetails::from_errors(errors)),
+            Err(Rejected::Unreadable(error)) if error.is_data() => {
+                Err(ProblemDetails::from_errors(vec![deserialization_error(error)]))
+            }
+            Err(Rejected::Unreadable(error)) => Err(ProblemDetails::malformed(&error)),
+        }
+    }
+}
+
+impl<T> ValidatedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidatedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ValidatedJson<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// An RFC 7807 problem document for a rejected request body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// Every validation error, as `ValidationResult` reports them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ValidationError>,
+}
+
+impl ProblemDetails {
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+    /// 422 Unprocessable Entity listing `errors`
+    pub fn from_errors(errors: Vec<ValidationError>) -> Self {
+        let detail = match errors.len() {
+            1 => errors[0].to_string(),
+            count => format!("{} validation errors", count),
+        };
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: "Request body failed validation".to_string(),
+            status: 422,
+            detail,
+            errors,
+        }
+    }
+
+    /// 422 for a failed result; `None` if it succeeded
+    pub fn from_result(result: &ValidationResult) -> Option<Self> {
+        if result.success {
+            None
+        } else {
+            Some(Self::from_errors(result.errors.clone()))
+        }
+    }
+
+    /// 400 Bad Request for a body that isn't JSON
+    pub fn malformed(error: &serde_json::Error) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: "Request body is not valid JSON".to_string(),
+            status: 400,
+            detail: error.to_string(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// A body that couldn't be received, such as one over the size limit
+    #[cfg(any(feature = "axum", feature = "actix-web"))]
+    fn unreadable(status: u16, detail: String) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: "Request body could not be read".to_string(),
+            status,
+            detail,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Whether any error came from the target type rather than the schema
+    pub fn is_type_mismatch(&self) -> bool {
+        self.errors.iter().any(|error| error.code == ErrorCode::DeserializationFailed)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.title, self.status, self.detail)
+    }
+}
+
+impl std::error::Error for ProblemDetails {}
+
+#[cfg(feature = "axum")]
+mod axum_impl {
+    use super::{ProblemDetails, ValidatedJson};
+    use crate::validator::Validator;
+    use axum::body::Bytes;
+    use axum::extract::{FromRef, FromRequest, Request};
+    use axum::http::{header, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use serde::de::DeserializeOwned;
+    use std::sync::Arc;
+
+    impl<T, S> FromRequest<S> for ValidatedJson<T>
+    where
+        T: DeserializeOwned,
+        S: Send + Sync,
+        Arc<Validator>: FromRef<S>,
+    {
+        type Rejection = ProblemDetails;
+
+        async fn from_request(request: Request, state: &S) -> Result<Self, ProblemDetails> {
+            let body = Bytes::from_request(request, state)
+                .await
+                .map_err(|rejection| ProblemDetails::unreadable(rejection.status().as_u16(), rejection.body_text()))?;
+            Self::from_slice(&Arc::<Validator>::from_ref(state), &body)
+        }
+    }
+
+    impl IntoResponse for ProblemDetails {
+        fn into_response(self) -> Response {
+            let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            (status, [(header::CONTENT_TYPE, Self::CONTENT_TYPE)], self.to_json()).into_response()
+        }
+    }
+}
+
+#[cfg(feature = "actix-web")]
+mod actix_impl {
+    use super::{ProblemDetails, ValidatedJson};
+    use crate::validator::Validator;
+    use actix_web::dev::Payload;
+    use actix_web::http::StatusCode;
+    use actix_web::web::{Bytes, Data};
+    use actix_web::{FromRequest, HttpRequest, HttpResponse, ResponseError};
+    use serde::de::DeserializeOwned;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    impl<T: DeserializeOwned + 'static> FromRequest for ValidatedJson<T> {
+        type Error = ProblemDetails;
+        type Future = Pin<Box<dyn Future<Output = Result<Self, ProblemDetails>>>>;
+
+        fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+            let validator = request.app_data::<Data<Validator>>().cloned();
+            let body = Bytes::from_request(request, payload);
+            Box::pin(async move {
+                let validator = validator.ok_or_else(|| {
+                    ProblemDetails::unreadable(500, "no web::Data<Validator> is registered".to_string())
+                })?;
+                let body = body.await.map_err(|error| {
+                    ProblemDetails::unreadable(error.as_response_error().status_code().as_u16(), error.to_string())
+                })?;
+                Self::from_slice(&validator, &body)
+            })
+        }
+    }
+
+    impl ResponseError for ProblemDetails {
+        fn status_code(&self) -> StatusCode {
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+
+        fn error_response(&self) -> HttpResponse {
+            HttpResponse::build(self.status_code()).content_type(Self::CONTENT_TYPE).body(self.to_json())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Schema;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Signup {
+        email: String,
+    }
+
+    #[test]
+    fn test_validated_json() {
+        let schema = Schema::object().prop("email", Schema::string().email()).build().unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        let body = ValidatedJson::<Signup>::from_slice(&validator, br#"{"email": "ann@example.com"}"#).unwrap();
+        assert_eq!(body.email, "ann@example.com");
+
+        let problem = ValidatedJson::<Signup>::from_slice(&validator, br#"{"email": "ann"}"#).unwrap_err();
+        assert_eq!((problem.status, problem.errors.len()), (422, 1));
+        let json: serde_json::Value = serde_json::from_str(&problem.to_json()).unwrap();
+        assert_eq!(json["errors"][0]["path"], serde_json::json!(["email"]));
+        assert_eq!(json["type"], "about:blank");
+
+        let problem = ValidatedJson::<Signup>::from_slice(&validator, br#"{"email": "#).unwrap_err();
+        assert_eq!((problem.status, problem.errors.len()), (400, 0));
+
+        let problem = ValidatedJson::<Vec<String>>::from_slice(&validator, br#"{"email": "ann@example.com"}"#).unwrap_err();
+        assert!(problem.status == 422 && problem.is_type_mismatch());
+
+        assert_eq!(ProblemDetails::from_result(&validator.validate(&serde_json::json!({"email": "a@b.co"}))), None);
+    }
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_axum_extractor() {
+        use axum::body::Body;
+        use axum::extract::{FromRequest, Request};
+        use axum::response::IntoResponse;
+        use std::sync::Arc;
+
+        let schema = Schema::object().prop("email", Schema::string().email()).build().unwrap();
+        let state = Arc::new(Validator::new(schema).unwrap());
+        let request = |body: &'static str| Request::builder().method("POST").body(Body::from(body)).unwrap();
+
+        let ValidatedJson(signup) =
+            ValidatedJson::<Signup>::from_request(request(r#"{"email": "ann@example.com"}"#), &state).await.unwrap();
+        assert_eq!(signup.email, "ann@example.com");
+
+        let problem = ValidatedJson::<Signup>::from_request(request(r#"{"email": "ann"}"#), &state).await.unwrap_err();
+        let response = problem.into_response();
+        assert_eq!(response.status(), 422);
+        assert_eq!(response.headers()["content-type"], ProblemDetails::CONTENT_TYPE);
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[actix_web::test]
+    async fn test_actix_extractor() {
+        use actix_web::test::TestRequest;
+        use actix_web::{web, FromRequest, ResponseError};
+
+        let schema = Schema::object().prop("email", Schema::string().email()).build().unwrap();
+        let validator = web::Data::new(Validator::new(schema).unwrap());
+
+        let (request, mut payload) =
+            TestRequest::post().app_data(validator.clone()).set_payload(r#"{"email": "ann@example.com"}"#).to_http_parts();
+        let ValidatedJson(signup) = ValidatedJson::<Signup>::from_request(&request, &mut payload).await.unwrap();
+        assert_eq!(signup.email, "ann@example.com");
+
+        let (request, mut payload) = TestRequest::post().app_data(validator).set_payload("{").to_http_parts();
+        let response = ValidatedJson::<Signup>::from_request(&request, &mut payload).await.unwrap_err().error_response();
+        assert_eq!(response.status(), 400);
+        assert_eq!(response.headers().get("content-type").unwrap(), ProblemDetails::CONTENT_TYPE);
+
+        let (request, mut payload) = TestRequest::post().set_payload("{}").to_http_parts();
+        let problem = ValidatedJson::<Signup>::from_request(&request, &mut payload).await.unwrap_err();
+        assert_eq!(problem.status, 500);
+    }
+}
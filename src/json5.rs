@@ -0,0 +1,339 @@
+
+//! JSON5 input, which covers JSONC, converted to `serde_json::Value`, for
+//! hand-edited configuration and schema files. On top of JSON it accepts
+//! `//` and `/* */` comments, trailing commas, unquoted (identifier) keys,
+//! single-quoted strings, hexadecimal numbers, leading `+` signs and
+//! leading or trailing decimal points. `Infinity` and `NaN` have no JSON
+//! form and are rejected; a repeated key keeps its last value.
+
+use crate::error::FastSchemaError;
+use serde_json::{Map, Value};
+use std::convert::TryFrom;
+
+/// Nesting limit, matching `serde_json`'s
+const MAX_DEPTH: usize = 128;
+
+/// Parse a JSON5 or JSONC document
+pub fn parse_json5(text: &str) -> Result<Value, FastSchemaError> {
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_space()?;
+    let value = parser.value(0)?;
+    parser.skip_space()?;
+    if parser.pos != text.len() {
+        return Err(parser.invalid("trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    /// Byte offset into `text`
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn invalid(&self, reason: &str) -> FastSchemaError {
+        let before = &self.text[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |start| start.chars().count()) + 1;
+        FastSchemaError::InputParsing(format!("JSON5 line {}, column {}: {}", line, column, reason))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), FastSchemaError> {
+        if !self.eat(c) {
+            return Err(self.invalid(&format!("expected '{}'", c)));
+        }
+        Ok(())
+    }
+
+    /// Skip whitespace and comments
+    fn skip_space(&mut self) -> Result<(), FastSchemaError> {
+        loop {
+            let rest = &self.text[self.pos..];
+            if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let end = comment.find("*/").ok_or_else(|| self.invalid("unterminated comment"))?;
+                self.pos += end + 4;
+            } else {
+                match self.peek() {
+                    Some(c) if c.is_whitespace() || c == '\u{feff}' => self.pos += c.len_utf8(),
+                    _ => return Ok(()),
+                }
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, FastSchemaError> {
+        if depth > MAX_DEPTH {
+            return Err(self.invalid("nested too deeply"));
+        }
+        match self.peek() {
+            Some('{') => self.object(depth),
+            Some('[') => self.array(depth),
+            Some(quote @ ('"' | '\'')) => {
+                self.pos += 1;
+                self.string(quote).map(Value::String)
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '+' | '-' | '.') => self.number(),
+            Some(c) if is_identifier_start(c) => match self.identifier().as_str() {
+                "null" => Ok(Value::Null),
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "Infinity" | "NaN" => Err(self.invalid("Infinity and NaN have no JSON form")),
+                word => Err(self.invalid(&format!("unexpected '{}'", word))),
+            },
+            Some(c) => Err(self.invalid(&format!("unexpected '{}'", c))),
+            None => Err(self.invalid("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value, FastSchemaError> {
+        self.expect('{')?;
+        let mut map = Map::new();
+        loop {
+            self.skip_space()?;
+            if self.eat('}') {
+                return Ok(Value::Object(map));
+            }
+            let key = match self.peek() {
+                Some(quote @ ('"' | '\'')) => {
+                    self.pos += 1;
+                    self.string(quote)?
+                }
+                Some(c) if is_identifier_start(c) => self.identifier(),
+                _ => return Err(self.invalid("expected a key")),
+            };
+            self.skip_space()?;
+            self.expect(':')?;
+            self.skip_space()?;
+            let value = self.value(depth + 1)?;
+            map.insert(key, value);
+            self.skip_space()?;
+            if !self.eat(',') {
+                self.skip_space()?;
+                self.expect('}')?;
+                return Ok(Value::Object(map));
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, FastSchemaError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_space()?;
+            if self.eat(']') {
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value(depth + 1)?);
+            self.skip_space()?;
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(Value::Array(items));
+            }
+        }
+    }
+
+    /// Letters, digits, `_` and `$`; escapes in identifiers are not supported
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| is_identifier_start(c) || c.is_ascii_digit()) {
+            self.next();
+        }
+        self.text[start..self.pos].to_string()
+    }
+
+    /// The rest of a string after its opening quote
+    fn string(&mut self, quote: char) -> Result<String, FastSchemaError> {
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.invalid("unterminated string")),
+                Some(c) if c == quote => return Ok(string),
+                Some('\n' | '\r') => return Err(self.invalid("unescaped line break in string")),
+                Some('\\') => self.escape(&mut string)?,
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self, string: &mut String) -> Result<(), FastSchemaError> {
+        let c = self.next().ok_or_else(|| self.invalid("unterminated string"))?;
+        match c {
+            'b' => string.push('\u{8}'),
+            'f' => string.push('\u{c}'),
+            'n' => string.push('\n'),
+            'r' => string.push('\r'),
+            't' => string.push('\t'),
+            'v' => string.push('\u{b}'),
+            '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => string.push('\0'),
+            '1'..='9' | '0' => return Err(self.invalid("octal escapes are not allowed")),
+            'x' => {
+                let code = self.hex(2)?;
+                string.push(char::from(code as u8));
+            }
+            'u' => {
+                let mut code = self.hex(4)?;
+                if (0xd800..0xdc00).contains(&code) && self.text[self.pos..].starts_with("\\u") {
+                    let pos = self.pos;
+                    self.pos += 2;
+                    let low = self.hex(4)?;
+                    if (0xdc00..0xe000).contains(&low) {
+                        code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                    } else {
+                        self.pos = pos;
+                    }
+                }
+                string.push(char::from_u32(code).ok_or_else(|| self.invalid("unpaired surrogate in string"))?);
+            }
+            // Line continuations
+            '\r' => {
+                self.eat('\n');
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            c => string.push(c),
+        }
+        Ok(())
+    }
+
+    fn hex(&mut self, digits: usize) -> Result<u32, FastSchemaError> {
+        let end = self.pos + digits;
+        let code = self
+            .text
+            .get(self.pos..end)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.invalid("invalid escape"))?;
+        self.pos = end;
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Value, FastSchemaError> {
+        let start = self.pos;
+        let negative = self.eat('-');
+        if !negative {
+            self.eat('+');
+        }
+        let rest = &self.text[self.pos..];
+        if rest.starts_with("Infinity") || rest.starts_with("NaN") {
+            return Err(self.invalid("Infinity and NaN have no JSON form"));
+        }
+        if rest.starts_with("0x") || rest.starts_with("0X") {
+            self.pos += 2;
+            let digits = self.pos;
+            while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                self.pos += 1;
+            }
+            let n = u64::from_str_radix(&self.text[digits..self.pos], 16).map_err(|_| self.invalid("invalid hexadecimal number"))?;
+            return Ok(match (negative, i64::try_from(n)) {
+                (false, _) => Value::from(n),
+                (true, Ok(n)) => Value::from(-n),
+                (true, Err(_)) => Value::from(-(n as f64)),
+            });
+        }
+
+        // Rewrite the literal as a JSON number: no `+`, and digits on both
+        // sides of the decimal point
+        let mut literal = String::from(if negative { "-" } else { "" });
+        let integer = self.digits();
+        literal.push_str(if integer.is_empty() { "0" } else { integer });
+        let mut fraction = "";
+        if self.eat('.') {
+            fraction = self.digits();
+            literal.push('.');
+            literal.push_str(if fraction.is_empty() { "0" } else { fraction });
+        }
+        if integer.is_empty() && fraction.is_empty() {
+            self.pos = start;
+            return Err(self.invalid("invalid number"));
+        }
+        if self.eat('e') || self.eat('E') {
+            literal.push('e');
+            if self.eat('-') {
+                literal.push('-');
+            } else {
+                self.eat('+');
+            }
+            literal.push_str(self.digits());
+        }
+        serde_json::from_str::<Value>(&literal).map_err(|_| {
+            self.pos = start;
+            self.invalid("invalid number")
+        })
+    }
+
+    fn digits(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        &self.text[start..self.pos]
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_json5() {
+        let text = r#"
+// Service settings
+{
+  name: 'api',        /* single quotes */
+  "port": +8080,
+  ratio: .5,
+  limit: 0x1F,
+  tags: ["a", 'b\'s', ],
+  $note: "line \
+continued",
+  nested: { empty: [], },
+}
+"#;
+        assert_eq!(
+            parse_json5(text).unwrap(),
+            json!({
+                "name": "api",
+                "port": 8080,
+                "ratio": 0.5,
+                "limit": 31,
+                "tags": ["a", "b's"],
+                "$note": "line continued",
+                "nested": {"empty": []}
+            })
+        );
+        assert_eq!(parse_json5("[1., -2e3, \"\\ud83d\\ude00\\x41\"]").unwrap(), json!([1.0, -2000.0, "😀A"]));
+        assert_eq!(parse_json5("{\"a\": 1, \"a\": 2}").unwrap(), json!({"a": 2}));
+
+        for invalid in ["", "[1,,]", "{a 1}", "[Infinity]", "[-NaN]", "NaN", "/* open", "'a\nb'", "[01]", "[.]", "{} {}", "[\"\\1\"]", "undefined"] {
+            assert!(matches!(parse_json5(invalid), Err(FastSchemaError::InputParsing(_))), "{:?}", invalid);
+        }
+        let error = parse_json5("{\n  a: 1,\n  b: @\n}").unwrap_err().to_string();
+        assert!(error.contains("line 3, column 6"), "{}", error);
+        assert!(parse_json5(&"[".repeat(200)).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+
+//! Custom schema keywords implemented in Rust. A schema document marks a
+//! node with an `x-` prefixed keyword, e.g. `"x-business-rule": "even"`,
+//! which stays an annotation until a `KeywordValidator` for it is
+//! registered with `Validator::register_keyword`.
+
+use crate::error::PathSegment;
+use crate::schema::ANY_ITEM;
+use serde_json::Value;
+
+/// Validates the values marked with one custom keyword
+pub trait KeywordValidator: Send + Sync {
+    /// The keyword, including its `x-` prefix
+    fn keyword(&self) -> &str;
+
+    /// Check the keyword's argument on registration; an `Err` message
+    /// rejects the schema
+    fn compile(&self, argument: &Value) -> Result<(), String> {
+        let _ = argument;
+        Ok(())
+    }
+
+    /// Added to the schema's estimated complexity for each use
+    fn complexity(&self, argument: &Value) -> usize {
+        let _ = argument;
+        10
+    }
+
+    /// Reported as the `code` parameter of `CUSTOM_KEYWORD` errors, for
+    /// error maps and clients to tell rules apart
+    fn error_code(&self) -> &str {
+        self.keyword()
+    }
+
+    /// `Err` with a message if `value` breaks the rule
+    fn validate(&self, argument: &Value, value: &Value) -> Result<(), String>;
+}
+
+/// The values at a metadata path, with their data paths. `ANY_ITEM`
+/// matches every item of an array.
+pub(crate) fn values_at<'v>(value: &'v Value, path: &[PathSegment]) -> Vec<(Vec<PathSegment>, &'v Value)> {
+    let mut found = vec![(Vec::new(), value)];
+    for segment in path {
+        let mut next = Vec::new();
+        for (segments, value) in found {
+            match (segment, value) {
+                (segment, Value::Array(items)) if *segment == ANY_ITEM => {
+                    for (index, item) in items.iter().enumerate() {
+                        let mut segments = segments.clone();
+                        segments.push(PathSegment::Index(index));
+                        next.push((segments, item));
+                    }
+                }
+                (PathSegment::Key(key), Value::Object(obj)) => {
+                    if let Some(property) = obj.get(key) {
+                        let mut segments = segments;
+                        segments.push(PathSegment::Key(key.clone()));
+                        next.push((segments, property));
+                    }
+                }
+                _ => {}
+            }
+        }
+        found = next;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use crate::validator::Validator;
+    use serde_json::json;
+
+    struct Even;
+
+    impl KeywordValidator for Even {
+        fn keyword(&self) -> &str {
+            "x-even"
+        }
+
+        fn compile(&self, argument: &Value) -> Result<(), String> {
+            argument.as_bool().map(|_| ()).ok_or_else(|| "expected a boolean".to_string())
+        }
+
+        fn complexity(&self, _: &Value) -> usize {
+            3
+        }
+
+        fn error_code(&self) -> &str {
+            "NOT_EVEN"
+        }
+
+        fn validate(&self, argument: &Value, value: &Value) -> Result<(), String> {
+            let even = value.as_i64().is_none_or(|number| number % 2 == 0);
+            if even || argument == &json!(false) {
+                Ok(())
+            } else {
+                Err("Number must be even".to_string())
+            }
+        }
+    }
+
+    #[test]
+    fn test_values_at() {
+        let value = json!({"items": [{"price": 1}, {"price": 2}, {}]});
+        let path = [PathSegment::Key("items".into()), ANY_ITEM, PathSegment::Key("price".into())];
+        let found = values_at(&value, &path);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[1].0, vec![PathSegment::Key("items".into()), PathSegment::Index(1), PathSegment::Key("price".into())]);
+        assert_eq!(values_at(&value, &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_custom_keyword() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "counts": {"type": "array", "items": {"type": "number", "x-even": true}}
+            }
+        });
+        let mut validator = Validator::from_json(&schema).unwrap();
+        // Unregistered keywords are annotations only
+        assert!(validator.validate(&json!({"counts": [1]})).success);
+
+        let complexity = validator.compiled_schema.estimated_complexity;
+        validator.register_keyword(Even).unwrap();
+        assert_eq!(validator.compiled_schema.estimated_complexity, complexity + 3);
+        // Replacing a keyword doesn't count its uses twice
+        validator.register_keyword(Even).unwrap();
+        assert_eq!(validator.compiled_schema.estimated_complexity, complexity + 3);
+
+        let result = validator.validate(&json!({"counts": [2, 3, 4, 5]}));
+        let paths: Vec<String> = result.errors.iter().map(|error| error.path.to_string()).collect();
+        assert_eq!(paths, vec!["counts.[1]", "counts.[3]"]);
+        assert_eq!(result.errors[0].code, ErrorCode::CustomKeyword);
+        assert_eq!(result.errors[0].params["code"], "NOT_EVEN");
+        assert_eq!(result.errors[0].params["keyword"], "x-even");
+
+        // Batches check each item
+        let results = validator.validate_many(&[json!({"counts": [1]}), json!({"counts": [2]})]);
+        assert_eq!(results[0].errors[0].path.to_string(), "[0].counts.[0]");
+        assert!(results[1].success);
+
+        let mut invalid = Validator::from_json(&json!({"type": "number", "x-even": "yes"})).unwrap();
+        assert!(invalid.register_keyword(Even).is_err());
+    }
+}
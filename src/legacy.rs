@@ -0,0 +1,155 @@
+
+//! Draft-07 and draft-04 spellings in FastSchema documents, such as
+//! `minimum` for `min` or `"type": "integer"`. They are rewritten before
+//! the document is parsed, and each one is reported as a
+//! `DEPRECATED_FEATURE` warning.
+
+use crate::error::{ErrorCode, ValidationError};
+use serde_json::{Map, Value};
+
+/// Legacy number keywords and their FastSchema names
+const NUMBER_ALIASES: &[(&str, &str)] = &[
+    ("minimum", "min"),
+    ("maximum", "max"),
+    ("multipleOf", "multiple_of"),
+    ("exclusiveMinimum", "exclusiveMin"),
+    ("exclusiveMaximum", "exclusiveMax"),
+];
+
+/// Keywords whose values are data, not subschemas
+const DATA_KEYWORDS: &[&str] = &["const", "default", "examples", "meta"];
+
+/// Rewrite legacy keyword spellings, returning the normalized document and
+/// a warning for each rewrite
+pub fn normalize_legacy_keywords(schema: &Value) -> (Value, Vec<ValidationError>) {
+    let mut schema = schema.clone();
+    let mut warnings = Vec::new();
+    normalize_node(&mut schema, &mut Vec::new(), &mut warnings);
+    (schema, warnings)
+}
+
+fn normalize_node(node: &mut Value, path: &mut Vec<String>, warnings: &mut Vec<ValidationError>) {
+    match node {
+        Value::Object(obj) => {
+            if obj.get("type") == Some(&Value::from("integer")) {
+                obj.insert("type".to_string(), Value::from("number"));
+                obj.insert("integer".to_string(), Value::Bool(true));
+                path.push("type".to_string());
+                warnings.push(deprecated(path, "Type 'integer' is deprecated; use 'number' with 'integer: true'"));
+                path.pop();
+            }
+            if obj.get("type") == Some(&Value::from("number")) {
+                normalize_number(obj, path, warnings);
+            }
+
+            for (keyword, value) in obj.iter_mut() {
+                if !DATA_KEYWORDS.contains(&keyword.as_str()) {
+                    path.push(keyword.clone());
+                    normalize_node(value, path, warnings);
+                    path.pop();
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                path.push(format!("[{}]", index));
+                normalize_node(item, path, warnings);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn normalize_number(obj: &mut Map<String, Value>, path: &mut Vec<String>, warnings: &mut Vec<ValidationError>) {
+    // Draft-04 booleans make `minimum` / `maximum` exclusive
+    for (flag, bound, exclusive) in [("exclusiveMinimum", "minimum", "exclusiveMin"), ("exclusiveMaximum", "maximum", "exclusiveMax")] {
+        if let Some(Value::Bool(flag_value)) = obj.get(flag).cloned() {
+            obj.remove(flag);
+            let bound_value = obj.remove(bound);
+            path.push(flag.to_string());
+            warnings.push(deprecated(
+                path,
+                &format!("Boolean '{}' is deprecated; use '{}' with the bound", flag, exclusive),
+            ));
+            path.pop();
+            match bound_value {
+                Some(value) if flag_value => obj.insert(exclusive.to_string(), value),
+                Some(value) => obj.insert(bound.to_string(), value),
+                None => None,
+            };
+        }
+    }
+
+    for (legacy, current) in NUMBER_ALIASES {
+        let value = match obj.remove(*legacy) {
+            Some(value) => value,
+            None => continue,
+        };
+        path.push(legacy.to_string());
+        if obj.contains_key(*current) {
+            warnings.push(deprecated(path, &format!("'{}' is ignored because '{}' is set", legacy, current)));
+        } else {
+            warnings.push(deprecated(path, &format!("'{}' is deprecated; use '{}'", legacy, current)));
+            obj.insert(current.to_string(), value);
+        }
+        path.pop();
+    }
+}
+
+fn deprecated(path: &[String], message: &str) -> ValidationError {
+    ValidationError::new(path.join("."), message.to_string(), ErrorCode::DeprecatedFeature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Severity;
+    use crate::validator::Validator;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_legacy_keywords() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "age": {"type": "integer", "minimum": 0, "exclusiveMaximum": 130},
+                "score": {"type": "number", "minimum": 0, "exclusiveMinimum": true, "max": 1, "maximum": 10},
+                "limits": {"type": "string", "examples": [{"type": "number", "minimum": 1}]}
+            }
+        });
+
+        let (normalized, mut warnings) = normalize_legacy_keywords(&schema);
+        assert_eq!(
+            normalized["properties"]["age"],
+            json!({"type": "number", "integer": true, "min": 0, "exclusiveMax": 130})
+        );
+        assert_eq!(normalized["properties"]["score"], json!({"type": "number", "exclusiveMin": 0, "max": 1}));
+        assert_eq!(normalized["properties"]["limits"], schema["properties"]["limits"]);
+
+        warnings.sort_by_key(|warning| warning.path.to_string());
+        let paths: Vec<String> = warnings.iter().map(|warning| warning.path.to_string()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "properties.age.exclusiveMaximum",
+                "properties.age.minimum",
+                "properties.age.type",
+                "properties.score.exclusiveMinimum",
+                "properties.score.maximum",
+            ]
+        );
+        assert!(warnings.iter().all(|warning| warning.severity == Severity::Warning));
+        assert!(warnings[4].message.contains("ignored"));
+        let document_errors = crate::metaschema::validate_schema_document(&schema);
+        assert_eq!(document_errors.len(), 5);
+        assert!(document_errors.iter().all(|error| error.code == ErrorCode::DeprecatedFeature));
+
+        let validator = Validator::from_json(&schema).unwrap();
+        assert_eq!(validator.compiled_schema.warnings.len(), 5);
+        assert!(validator.validate(&json!({"age": 30, "score": 0.5})).success);
+        let result = validator.validate(&json!({"age": 130, "score": 0}));
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0].params["exclusive"], true);
+    }
+}
@@ -0,0 +1,447 @@
+
+//! HTML markup read into the element trees that `HtmlElement` and
+//! `ReactComponent` schemas check. `<img class="a" src="b.png">` becomes
+//! `{"type": "img", "props": {"className": "a", "src": "b.png"}}`:
+//!
+//! - Tag and attribute names are lowercased. `class` and `for` become
+//!   `className` and `htmlFor`, as in React.
+//! - Boolean attributes such as `disabled` are `true`. An inline `style`
+//!   becomes an object of declarations.
+//! - Child elements and text go in `children`. Whitespace-only text and
+//!   comments are dropped, and character references are decoded.
+//! - Markup with one root element gives that element. Otherwise the result
+//!   is an array of the top-level nodes.
+//!
+//! This is a lightweight tokenizer rather than an HTML5 tree builder. Void
+//! elements need no end tag. So do `p`, `li` and the other elements whose
+//! end tags HTML makes optional, when their parent closes or a sibling
+//! starts. Any other missing or stray end tag is an error. Every node keeps
+//! its byte span, so validation errors can point into the markup.
+
+use crate::error::{FastSchemaError, PathSegment};
+use crate::source_map::SourceMap;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Nesting limit, as for JSON5
+const MAX_DEPTH: usize = 128;
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Elements whose end tag may be left out
+const OPTIONAL_END: &[&str] = &[
+    "p", "li", "dt", "dd", "option", "optgroup", "tr", "td", "th", "thead", "tbody", "tfoot", "colgroup", "caption",
+];
+
+/// Start tags that close an open `p`
+const CLOSES_PARAGRAPH: &[&str] = &[
+    "address", "article", "aside", "blockquote", "details", "div", "dl", "fieldset", "figure", "footer", "form",
+    "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "main", "nav", "ol", "p", "pre", "section", "table", "ul",
+];
+
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen", "async", "autofocus", "autoplay", "checked", "controls", "default", "defer", "disabled",
+    "formnovalidate", "hidden", "inert", "ismap", "loop", "multiple", "muted", "nomodule", "novalidate", "open",
+    "playsinline", "readonly", "required", "reversed", "selected",
+];
+
+/// Parse HTML markup into an element tree
+pub fn parse_html(markup: &str) -> Result<Value, FastSchemaError> {
+    parse_html_with_spans(markup).map(|(tree, _)| tree)
+}
+
+/// Parse HTML markup, mapping each path of the tree to its bytes
+pub(crate) fn parse_html_with_spans(markup: &str) -> Result<(Value, SourceMap<'_>), FastSchemaError> {
+    let mut parser = Parser { text: markup, pos: 0, spans: HashMap::new(), open: Vec::new() };
+    let mut nodes = parser.nodes(None, &mut Vec::new())?;
+
+    // A single root stands for the whole document
+    let elements = nodes.iter().filter(|node| node.is_object()).count();
+    if nodes.len() == 1 && elements == 1 {
+        let spans = parser.spans.into_iter().map(|(mut path, span)| {
+            path.remove(0);
+            (path, span)
+        });
+        return Ok((nodes.remove(0), SourceMap::from_spans(markup, spans.collect())));
+    }
+    parser.spans.insert(Vec::new(), (0, markup.len()));
+    Ok((Value::Array(nodes), SourceMap::from_spans(markup, parser.spans)))
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    /// Byte offset into `text`
+    pos: usize,
+    spans: HashMap<Vec<PathSegment>, (usize, usize)>,
+    /// Names of the elements being parsed, outermost first
+    open: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn invalid(&self, at: usize, reason: &str) -> FastSchemaError {
+        let before = &self.text[..at];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |start| start.chars().count()) + 1;
+        FastSchemaError::InputParsing(format!("HTML line {}, column {} (byte {}): {}", line, column, at, reason))
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// Nodes up to the end tag of `parent` (left for the caller to read),
+    /// or to the end of input at the top level. `path` is the parent's
+    /// children path.
+    fn nodes(&mut self, parent: Option<&str>, path: &mut Vec<PathSegment>) -> Result<Vec<Value>, FastSchemaError> {
+        let mut nodes = Vec::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return match parent {
+                    Some(name) if !OPTIONAL_END.contains(&name) => {
+                        Err(self.invalid(self.text.len(), &format!("<{}> is never closed", name)))
+                    }
+                    _ => Ok(nodes),
+                };
+            }
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").ok_or_else(|| self.invalid(self.pos, "unterminated comment"))?;
+                self.pos += 4 + end + 3;
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                // Doctype or processing instruction
+                let end = rest.find('>').ok_or_else(|| self.invalid(self.pos, "unterminated declaration"))?;
+                self.pos += end + 1;
+            } else if let Some(end_tag) = rest.strip_prefix("</") {
+                let name = tag_name(end_tag).to_ascii_lowercase();
+                match parent {
+                    Some(parent) if parent == name => return Ok(nodes),
+                    // `</ul>` also ends an open `li`
+                    Some(parent) if OPTIONAL_END.contains(&parent) && self.open.contains(&name) => return Ok(nodes),
+                    _ => return Err(self.invalid(self.pos, &format!("unexpected </{}>", name))),
+                }
+            } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+                let name = tag_name(&rest[1..]).to_ascii_lowercase();
+                if let Some(parent) = parent {
+                    if implicitly_closes(parent, &name) {
+                        return Ok(nodes);
+                    }
+                }
+                path.push(PathSegment::Index(nodes.len()));
+                let element = self.element(path);
+                path.pop();
+                nodes.push(element?);
+            } else {
+                let start = self.pos;
+                let first = rest.chars().next().map_or(0, char::len_utf8);
+                let end = rest[first..].find('<').map_or(rest.len(), |end| end + first);
+                self.pos += end;
+                let text = &self.text[start..self.pos];
+                if !text.trim().is_empty() {
+                    path.push(PathSegment::Index(nodes.len()));
+                    self.spans.insert(path.clone(), (start, self.pos));
+                    path.pop();
+                    nodes.push(Value::String(decode_entities(text)));
+                }
+            }
+        }
+    }
+
+    /// The element whose start tag is at the cursor, at `path`
+    fn element(&mut self, path: &mut Vec<PathSegment>) -> Result<Value, FastSchemaError> {
+        let start = self.pos;
+        self.pos += 1;
+        let name_start = self.pos;
+        let name = tag_name(self.rest()).to_ascii_lowercase();
+        self.pos += name.len();
+        self.record(path, "type", (name_start, self.pos));
+
+        let mut props = Map::new();
+        let self_closing = loop {
+            self.skip_space();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                break true;
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break false;
+            }
+            if rest.is_empty() {
+                return Err(self.invalid(start, &format!("unterminated <{}> tag", name)));
+            }
+            let (attribute, value, span) = self.attribute()?;
+            let key = match attribute.as_str() {
+                "class" => "className".to_string(),
+                "for" => "htmlFor".to_string(),
+                _ => attribute,
+            };
+            path.push(PathSegment::Key("props".to_string()));
+            self.record(path, &key, span);
+            path.pop();
+            props.insert(key, value);
+        };
+        self.record(path, "props", (start, self.pos));
+
+        let mut element = Map::new();
+        element.insert("type".to_string(), Value::String(name.clone()));
+        element.insert("props".to_string(), Value::Object(props));
+        if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+            self.spans.insert(path.clone(), (start, self.pos));
+            return Ok(Value::Object(element));
+        }
+
+        if self.open.len() >= MAX_DEPTH {
+            return Err(self.invalid(start, "nested too deeply"));
+        }
+        let content_start = self.pos;
+        self.open.push(name.clone());
+        path.push(PathSegment::Key("children".to_string()));
+        let children = if matches!(name.as_str(), "script" | "style" | "textarea" | "title") {
+            self.raw_text(&name, path)
+        } else {
+            self.nodes(Some(&name), path)
+        };
+        path.pop();
+        self.open.pop();
+        let children = children?;
+        self.record(path, "children", (content_start, self.pos));
+
+        // The end tag is ours unless an ancestor's closed us implicitly
+        let end_tag = format!("</{}", name);
+        if self.rest().get(..end_tag.len()).is_some_and(|tag| tag.eq_ignore_ascii_case(&end_tag)) {
+            let end = self.rest().find('>').ok_or_else(|| self.invalid(self.pos, "unterminated end tag"))?;
+            self.pos += end + 1;
+        }
+        self.spans.insert(path.clone(), (start, self.pos));
+        if !children.is_empty() {
+            element.insert("children".to_string(), Value::Array(children));
+        }
+        Ok(Value::Object(element))
+    }
+
+    /// The text content of `script`, `style` and the like, up to its end tag
+    fn raw_text(&mut self, name: &str, path: &mut Vec<PathSegment>) -> Result<Vec<Value>, FastSchemaError> {
+        let end_tag = format!("</{}", name);
+        let start = self.pos;
+        let length = self
+            .rest()
+            .to_ascii_lowercase()
+            .find(&end_tag)
+            .ok_or_else(|| self.invalid(start, &format!("<{}> is never closed", name)))?;
+        self.pos += length;
+        let text = &self.text[start..self.pos];
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        path.push(PathSegment::Index(0));
+        self.spans.insert(path.clone(), (start, self.pos));
+        path.pop();
+        let text = if name == "script" || name == "style" { text.to_string() } else { decode_entities(text) };
+        Ok(vec![Value::String(text)])
+    }
+
+    /// An attribute at the cursor: its name, value and span
+    fn attribute(&mut self) -> Result<(String, Value, (usize, usize)), FastSchemaError> {
+        let start = self.pos;
+        let length = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/' | '"' | '\'' | '<'))
+            .unwrap_or(self.rest().len());
+        if length == 0 {
+            return Err(self.invalid(self.pos, "expected an attribute name"));
+        }
+        let name = self.rest()[..length].to_ascii_lowercase();
+        self.pos += length;
+
+        self.skip_space();
+        if !self.rest().starts_with('=') {
+            return Ok((name, Value::Bool(true), (start, self.pos)));
+        }
+        self.pos += 1;
+        self.skip_space();
+        let raw = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = self.rest()[1..]
+                    .find(quote)
+                    .ok_or_else(|| self.invalid(start, &format!("unterminated value of attribute '{}'", name)))?;
+                let raw = &self.rest()[1..end + 1];
+                self.pos += end + 2;
+                raw
+            }
+            _ => {
+                let end = self.rest().find(|c: char| c.is_whitespace() || c == '>').unwrap_or(self.rest().len());
+                let raw = &self.rest()[..end];
+                self.pos += end;
+                raw
+            }
+        };
+        let text = decode_entities(raw);
+        let value = if BOOLEAN_ATTRIBUTES.contains(&name.as_str()) {
+            Value::Bool(true)
+        } else if name == "style" {
+            style_declarations(&text)
+        } else {
+            Value::String(text)
+        };
+        Ok((name, value, (start, self.pos)))
+    }
+
+    fn skip_space(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn record(&mut self, path: &mut Vec<PathSegment>, key: &str, span: (usize, usize)) {
+        path.push(PathSegment::Key(key.to_string()));
+        self.spans.insert(path.clone(), span);
+        path.pop();
+    }
+}
+
+/// The tag name at the start of `text`
+fn tag_name(text: &str) -> &str {
+    let end = text.find(|c: char| c.is_whitespace() || matches!(c, '>' | '/')).unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Whether a `name` start tag ends an open `parent` whose end tag is optional
+fn implicitly_closes(parent: &str, name: &str) -> bool {
+    match parent {
+        "p" => CLOSES_PARAGRAPH.contains(&name),
+        "li" => name == "li",
+        "dt" | "dd" => matches!(name, "dt" | "dd"),
+        "option" => matches!(name, "option" | "optgroup"),
+        "tr" => matches!(name, "tr" | "tbody" | "tfoot"),
+        "td" | "th" => matches!(name, "td" | "th" | "tr" | "tbody" | "tfoot"),
+        "thead" | "tbody" => matches!(name, "tbody" | "tfoot"),
+        _ => false,
+    }
+}
+
+/// `color: red; margin: 0` as `{"color": "red", "margin": "0"}`
+fn style_declarations(style: &str) -> Value {
+    let declarations = style.split(';').filter_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        Some((property.trim().to_ascii_lowercase(), Value::String(value.trim().to_string())))
+    });
+    Value::Object(declarations.collect())
+}
+
+/// Decode character references; unknown named references are kept as written
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let reference = rest[1..].find(';').map(|end| &rest[1..end + 1]).filter(|name| name.len() <= 10);
+        let character = reference.and_then(|name| match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#').and_then(|decimal| decimal.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        match (reference, character) {
+            (Some(name), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[name.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorPath;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_html() {
+        let markup = "<!DOCTYPE html>\n<ul class=\"menu\">\n  <li>Fish &amp; chips<li><a href=/menu>Menu</a>\n  <!-- more -->\n</ul>\n";
+        let (tree, map) = parse_html_with_spans(markup).unwrap();
+        assert_eq!(
+            tree,
+            json!({
+                "type": "ul",
+                "props": {"className": "menu"},
+                "children": [
+                    {"type": "li", "props": {}, "children": ["Fish & chips"]},
+                    {"type": "li", "props": {}, "children": [
+                        {"type": "a", "props": {"href": "/menu"}, "children": ["Menu"]}
+                    ]}
+                ]
+            })
+        );
+        let locate = |path: &str| map.locate(&ErrorPath::parse(path)).map(|l| (l.line, l.column, &markup[l.start..l.end]));
+        assert_eq!(locate("children[1].children[0].props.href"), Some((3, 30, "href=/menu")));
+        assert_eq!(locate("children[1].children[0].props.title").unwrap().2, "<a href=/menu>");
+        assert_eq!(locate("children[0]").unwrap().2, "<li>Fish &amp; chips");
+
+        assert_eq!(
+            parse_html("<input disabled type=checkbox style='color: red'> <br/>text").unwrap(),
+            json!([
+                {"type": "input", "props": {"disabled": true, "type": "checkbox", "style": {"color": "red"}}},
+                {"type": "br", "props": {}},
+                "text"
+            ])
+        );
+        assert_eq!(parse_html("<p>One<p>Two<div></div>").unwrap()[1], json!({"type": "p", "props": {}, "children": ["Two"]}));
+
+        let error = parse_html("<div>\n  <span>x</div>").unwrap_err().to_string();
+        assert!(error.contains("line 2, column 10 (byte 15): unexpected </div>"), "{}", error);
+        assert!(parse_html("<section><b>").is_err());
+
+        assert_eq!(parse_html("é").unwrap(), json!(["é"]));
+        assert_eq!(parse_html("<p>é<b>ü</b>ß</p>").unwrap()["children"], json!(["é", {"type": "b", "props": {}, "children": ["ü"]}, "ß"]));
+        assert_eq!(parse_html("<p>日本</P>").unwrap()["children"], json!(["日本"]));
+
+        let deep = |depth: usize| "<div>".repeat(depth) + &"</div>".repeat(depth);
+        assert!(parse_html(&deep(MAX_DEPTH)).is_ok());
+        let error = parse_html(&deep(100_000)).unwrap_err().to_string();
+        assert!(error.contains("nested too deeply"), "{}", error);
+    }
+
+    #[test]
+    fn test_validate_html_locations() {
+        use crate::html::{HtmlElementType, HtmlProps};
+        use crate::schema::SchemaType;
+        use crate::validator::Validator;
+
+        let schema = SchemaType::HtmlElement {
+            element_type: HtmlElementType::Image,
+            props: HtmlProps { validate_semantic: false, ..HtmlProps::default() },
+            children: None,
+            validate_dom: false,
+        };
+        let validator = Validator::new(schema).unwrap();
+        let markup = "<img\n  alt=\"A photo\"\n  src=\"photo.png\">";
+        let result = validator.validate_html(markup).unwrap();
+        assert!(!result.success);
+        let location = result.errors[0].location.unwrap();
+        assert_eq!((location.line, location.column), (3, 3));
+        assert_eq!(&markup[location.start..location.end], "src=\"photo.png\"");
+
+        assert!(validator.validate_html("<img src=\"/a.png\" alt=\"\">").unwrap().success);
+        assert!(validator.validate_html("<img></img></img>").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Memoization of subtrees already found valid, for data that repeats
+//! the same values (enum-like strings, shared config objects).
+//!
+//! Only successes are cached: they need no error paths, which differ at
+//! every location. Entries keep two independent 64-bit hashes of the
+//! value rather than a copy, so an entry costs the same however large the
+//! value is; invalid data would need both hashes to collide at once.
+
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Entries kept before the oldest is evicted
+pub const DEFAULT_CAPACITY: usize = 65_536;
+
+/// Mixed into the second hash so it is independent of the first
+const SECOND_HASH_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A cache shared by the contexts of one validation or batch
+pub type SharedMemo = Arc<Mutex<MemoCache>>;
+
+/// Two independent hashes of a value's content
+pub type ContentHash = (u64, u64);
+
+/// Values known to pass a schema node, keyed by the node's address and
+/// the value's content hash
+#[derive(Debug)]
+pub struct MemoCache {
+    entries: HashSet<(usize, ContentHash)>,
+    /// Entries in insertion order, oldest first
+    order: VecDeque<(usize, ContentHash)>,
+    capacity: usize,
+    hits: usize,
+}
+
+impl Default for MemoCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl MemoCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache holding at most `capacity` entries
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+            hits: 0,
+        }
+    }
+
+    pub fn shared() -> SharedMemo {
+        Arc::new(Mutex::new(Self::new()))
+    }
+
+    /// Whether a value with this content already passed node `node`
+    pub fn is_valid(&mut self, node: usize, hash: ContentHash) -> bool {
+        let known = self.entries.contains(&(node, hash));
+        if known {
+            self.hits += 1;
+        }
+        known
+    }
+
+    pub fn insert_valid(&mut self, node: usize, hash: ContentHash) {
+        if self.capacity == 0 || !self.entries.insert((node, hash)) {
+            return;
+        }
+        self.order.push_back((node, hash));
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// Node validations skipped so far
+    #[cfg(test)]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Values worth memoizing: checking scalars is cheaper than hashing them
+pub fn is_memoizable(value: &Value) -> bool {
+    match value {
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+pub fn content_hash(value: &Value) -> ContentHash {
+    let mut first = DefaultHasher::new();
+    value.hash(&mut first);
+    let mut second = DefaultHasher::new();
+    SECOND_HASH_SALT.hash(&mut second);
+    value.hash(&mut second);
+    (first.finish(), second.finish())
+}
+
+pub fn lock(memo: &SharedMemo) -> MutexGuard<'_, MemoCache> {
+    // The cache holds no invariants a panicking thread could break
+    memo.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_memo_cache() {
+        let mut memo = MemoCache::new();
+        let value = json!({"mode": "fast", "retries": 3});
+        let hash = content_hash(&value);
+        assert_eq!(hash, content_hash(&json!({"retries": 3, "mode": "fast"})));
+
+        assert_ne!(hash.0, hash.1);
+
+        assert!(!memo.is_valid(1, hash));
+        memo.insert_valid(1, hash);
+        memo.insert_valid(1, hash);
+        assert!(memo.is_valid(1, hash));
+        assert!(!memo.is_valid(2, hash));
+
+        // Only one of the two hashes matching is not a hit
+        assert!(!memo.is_valid(1, (hash.0, hash.1 ^ 1)));
+        assert_eq!((memo.hits(), memo.len()), (1, 1));
+
+        // At capacity the oldest entry makes room
+        let mut memo = MemoCache::with_capacity(2);
+        let hashes: Vec<_> = ["a", "b", "c"].iter().map(|s| content_hash(&json!(s))).collect();
+        for hash in &hashes {
+            memo.insert_valid(1, *hash);
+        }
+        assert_eq!(memo.len(), 2);
+        assert!(!memo.is_valid(1, hashes[0]));
+        assert!(memo.is_valid(1, hashes[1]) && memo.is_valid(1, hashes[2]));
+
+        assert!(is_memoizable(&json!("a")));
+        assert!(!is_memoizable(&json!("")));
+        assert!(!is_memoizable(&json!(3)));
+    }
+}
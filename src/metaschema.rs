@@ -0,0 +1,238 @@
+
+use crate::error::{ErrorCode, ValidationError};
+use crate::legacy::normalize_legacy_keywords;
+use crate::schema::{implied_type, ContentEncoding, EmailOptions, LengthUnit, SchemaType, StringFormat};
+use crate::utils::parse_date_bound;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+/// Bundled keyword table for schema documents (compiled once)
+static METASCHEMA: Lazy<Value> = Lazy::new(|| {
+    serde_json::from_str(include_str!("metaschema.json")).expect("bundled meta-schema is valid JSON")
+});
+
+/// Check a schema document against the bundled meta-schema.
+///
+/// Reports unknown keywords, constraints with the wrong value type and
+/// invalid regex patterns with their path in the document. Anything the
+/// keyword table cannot express is caught by parsing the document.
+///
+/// Legacy draft-07 spellings are checked under their FastSchema names and
+/// reported as `DEPRECATED_FEATURE` warnings after any errors.
+pub fn validate_schema_document(schema: &Value) -> Vec<ValidationError> {
+    let (schema, warnings) = normalize_legacy_keywords(schema);
+    let mut errors = Vec::new();
+    let mut path = Vec::new();
+    check_node(&schema, &mut path, &mut errors);
+
+    if errors.is_empty() {
+        if let Err(e) = serde_json::from_value::<SchemaType>(schema) {
+            errors.push(ValidationError::new(
+                String::new(),
+                format!("Schema could not be parsed: {}", e),
+                ErrorCode::SchemaInvalid,
+            ));
+        }
+    }
+
+    errors.extend(warnings);
+    errors
+}
+
+fn check_node(node: &Value, path: &mut Vec<String>, errors: &mut Vec<ValidationError>) {
+    let obj = match node.as_object() {
+        Some(obj) => obj,
+        None => {
+            errors.push(keyword_error(path, "Schema must be an object", ErrorCode::SchemaInvalid));
+            return;
+        }
+    };
+
+    let type_name = match obj.get("type").and_then(|t| t.as_str()).or_else(|| implied_type(obj)) {
+        Some(type_name) => type_name,
+        None => {
+            errors.push(keyword_error(path, "Schema is missing a string 'type'", ErrorCode::SchemaInvalid));
+            return;
+        }
+    };
+
+    let keywords = match METASCHEMA["types"].get(type_name) {
+        Some(Value::Object(keywords)) => keywords,
+        // Types whose shape is only checked by the parser
+        Some(_) => return,
+        None => {
+            path.push("type".to_string());
+            errors.push(keyword_error(path, &format!("Unknown schema type '{}'", type_name), ErrorCode::SchemaInvalid));
+            path.pop();
+            return;
+        }
+    };
+
+    // `x-` extension keys are annotations for other tools
+    for (keyword, value) in obj.iter().filter(|(keyword, _)| !keyword.starts_with("x-")) {
+        let kind = keywords
+            .get(keyword)
+            .or_else(|| METASCHEMA["common"].get(keyword))
+            .and_then(|kind| kind.as_str());
+
+        path.push(keyword.clone());
+        match kind {
+            Some(kind) => check_keyword(kind, value, path, errors),
+            None => errors.push(keyword_error(
+                path,
+                &format!("Unknown keyword '{}' for type '{}'", keyword, type_name),
+                ErrorCode::UnknownKey,
+            )),
+        }
+        path.pop();
+    }
+}
+
+fn check_keyword(kind: &str, value: &Value, path: &mut Vec<String>, errors: &mut Vec<ValidationError>) {
+    let valid = match kind {
+        "any" => true,
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "count" => value.is_u64(),
+        "array" => value.is_array(),
+        "integer_or_string" => value.is_i64() || value.is_u64() || value.is_string(),
+        "number_or_string" => value.is_number() || value.is_string(),
+        "string_array" => value.as_array().is_some_and(|items| items.iter().all(|v| v.is_string())),
+        "format" => serde_json::from_value::<StringFormat>(value.clone()).is_ok(),
+        "encoding" => serde_json::from_value::<ContentEncoding>(value.clone()).is_ok(),
+        "length_unit" => serde_json::from_value::<LengthUnit>(value.clone()).is_ok(),
+        "email_options" => value.is_object() && serde_json::from_value::<EmailOptions>(value.clone()).is_ok(),
+        "date_bound" => value.as_str().is_some_and(|bound| parse_date_bound(bound).is_ok()),
+        "sort_order" => matches!(value.as_str(), Some("asc") | Some("desc")),
+        "unknown_keys" => {
+            value.is_boolean() || matches!(value.as_str(), Some("strict") | Some("strip") | Some("passthrough"))
+        }
+        "regex" => match value.as_str() {
+            Some(pattern) => {
+                if let Err(e) = Regex::new(pattern) {
+                    errors.push(keyword_error(path, &format!("Invalid regex pattern: {}", e), ErrorCode::SchemaInvalid));
+                }
+                true
+            }
+            None => false,
+        },
+        "schema" => {
+            check_node(value, path, errors);
+            true
+        }
+        "unevaluated" => {
+            if !value.is_boolean() {
+                check_node(value, path, errors);
+            }
+            true
+        }
+        "schema_array" => match value.as_array() {
+            Some(schemas) => {
+                for (index, schema) in schemas.iter().enumerate() {
+                    path.push(format!("[{}]", index));
+                    check_node(schema, path, errors);
+                    path.pop();
+                }
+                true
+            }
+            None => false,
+        },
+        "schema_map" => match value.as_object() {
+            Some(schemas) => {
+                for (name, schema) in schemas {
+                    path.push(name.clone());
+                    check_node(schema, path, errors);
+                    path.pop();
+                }
+                true
+            }
+            None => false,
+        },
+        _ => true,
+    };
+
+    if !valid {
+        errors.push(keyword_error(
+            path,
+            &format!("Expected {} but received {}", kind_name(kind), value),
+            ErrorCode::InvalidType,
+        ));
+    }
+}
+
+fn kind_name(kind: &str) -> &str {
+    match kind {
+        "count" => "a non-negative integer",
+        "integer_or_string" => "an integer or integer string",
+        "number_or_string" => "a number or decimal string",
+        "string_array" => "an array of strings",
+        "format" => "a known string format",
+        "encoding" => "a known content encoding",
+        "length_unit" => "'chars' | 'bytes' | 'graphemes'",
+        "email_options" => "an object of email options",
+        "date_bound" => "an RFC 3339 date-time or full date",
+        "sort_order" => "'asc' | 'desc'",
+        "unknown_keys" => "a boolean or 'strict' | 'strip' | 'passthrough'",
+        "regex" => "a regex pattern string",
+        "schema_array" => "an array of schemas",
+        "schema_map" => "an object of schemas",
+        other => other,
+    }
+}
+
+fn keyword_error(path: &[String], message: &str, code: ErrorCode) -> ValidationError {
+    ValidationError::new(path.join("."), message.to_string(), code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_schema_document() {
+        let schema = json!({
+            "type": "object",
+            "title": "User",
+            "x-table": "users",
+            "properties": {
+                "name": {"type": "string", "minLength": 1, "pattern": "^[a-z]+$", "default": "ann"},
+                "password": {"type": "string", "sensitive": true, "x-ui": {"widget": "password"}},
+                "nickname": {"type": "string", "maxLength": 12, "lengthUnit": "bytes"},
+                "score": {"type": "number", "allowNonFinite": true, "multiple_of": 0.1, "multipleOfTolerance": 1e-9},
+                "count": {"type": "number", "integer": true, "strictInteger": true},
+                "tags": {"type": "array", "items": {"type": "string", "format": "email"}}
+            },
+            "required": ["name"]
+        });
+
+        assert!(validate_schema_document(&schema).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_schema_document() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLenght": 1, "pattern": "(unclosed"},
+                "age": {"type": "number", "min": "zero"},
+                "bio": {"type": "string", "lengthUnit": "words"}
+            }
+        });
+
+        let mut errors = validate_schema_document(&schema);
+        errors.sort_by_key(|error| error.path.to_string());
+
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0].path, "properties.age.min");
+        assert_eq!(errors[0].code, ErrorCode::InvalidType);
+        assert_eq!(errors[1].path, "properties.bio.lengthUnit");
+        assert_eq!(errors[1].code, ErrorCode::InvalidType);
+        assert_eq!(errors[2].path, "properties.name.minLenght");
+        assert_eq!(errors[2].code, ErrorCode::UnknownKey);
+        assert_eq!(errors[3].path, "properties.name.pattern");
+        assert_eq!(errors[3].code, ErrorCode::SchemaInvalid);
+    }
+}
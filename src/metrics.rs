@@ -0,0 +1,167 @@
+
+//! Running counters behind `ValidationStats`: outcomes, error codes, node
+//! types visited and recent throughput.
+
+use crate::error::{ErrorCode, ValidationError};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use crate::clock::Instant;
+use std::time::Duration;
+
+/// How far back the rolling throughput looks
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Width of one throughput bucket
+const BUCKET: Duration = Duration::from_millis(250);
+
+/// Counters kept since a validator was created or its stats were reset
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    started: Instant,
+    validations: u64,
+    invalid: u64,
+    errors_by_code: HashMap<ErrorCode, u64>,
+    node_counts: HashMap<String, u64>,
+    /// Validations finished per bucket, oldest first
+    buckets: VecDeque<(Instant, u64)>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            validations: 0,
+            invalid: 0,
+            errors_by_code: HashMap::new(),
+            node_counts: HashMap::new(),
+            buckets: VecDeque::new(),
+        }
+    }
+
+    /// Count one finished validation and the errors it reported
+    pub fn record(&mut self, errors: &[ValidationError]) {
+        self.record_many([errors]);
+    }
+
+    /// Count finished validations, one per error list, as if they all
+    /// finished now
+    pub fn record_many<'a>(&mut self, runs: impl IntoIterator<Item = &'a [ValidationError]>) {
+        let mut finished = 0;
+        for errors in runs {
+            finished += 1;
+            if !errors.is_empty() {
+                self.invalid += 1;
+            }
+            for error in errors {
+                *self.errors_by_code.entry(error.code.clone()).or_insert(0) += 1;
+            }
+        }
+        if finished == 0 {
+            return;
+        }
+        self.validations += finished;
+
+        let now = Instant::now();
+        match self.buckets.back_mut() {
+            Some((start, count)) if now.duration_since(*start) < BUCKET => *count += finished,
+            _ => self.buckets.push_back((now, finished)),
+        }
+        self.expire(now);
+    }
+
+    /// Add the schema nodes visited by type, e.g. from a profile
+    pub fn add_nodes(&mut self, counts: HashMap<String, u64>) {
+        for (kind, hits) in counts {
+            *self.node_counts.entry(kind).or_insert(0) += hits;
+        }
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((start, _)) = self.buckets.front() {
+            if now.duration_since(*start) <= THROUGHPUT_WINDOW {
+                break;
+            }
+            self.buckets.pop_front();
+        }
+    }
+
+    pub fn validations(&self) -> u64 {
+        self.validations
+    }
+
+    pub fn invalid(&self) -> u64 {
+        self.invalid
+    }
+
+    /// Keyed by the code as it appears in errors, e.g. `STRING_TOO_SHORT`
+    pub fn errors_by_code(&self) -> BTreeMap<String, u64> {
+        self.errors_by_code
+            .iter()
+            .map(|(code, count)| {
+                let name = serde_json::to_value(code)
+                    .ok()
+                    .and_then(|value| value.as_str().map(str::to_string))
+                    .unwrap_or_else(|| format!("{:?}", code));
+                (name, *count)
+            })
+            .collect()
+    }
+
+    pub fn node_counts(&self) -> BTreeMap<String, u64> {
+        self.node_counts.iter().map(|(kind, hits)| (kind.clone(), *hits)).collect()
+    }
+
+    /// Validations per second over the last ten seconds, or since the
+    /// counters started if that was more recent
+    pub fn throughput(&self) -> f64 {
+        let now = Instant::now();
+        let recent: u64 = self
+            .buckets
+            .iter()
+            .filter(|(start, _)| now.duration_since(*start) <= THROUGHPUT_WINDOW)
+            .map(|(_, count)| count)
+            .sum();
+        if recent == 0 {
+            return 0.0;
+        }
+        // At least one bucket, so a burst right after a reset isn't
+        // divided by a near-zero span
+        let span = now.duration_since(self.started).min(THROUGHPUT_WINDOW).max(BUCKET);
+        recent as f64 / span.as_secs_f64()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_counters() {
+        let mut metrics = Metrics::new();
+        assert_eq!(metrics.throughput(), 0.0);
+
+        metrics.record(&[]);
+        metrics.record(&[
+            ValidationError::new("a".to_string(), "too short".to_string(), ErrorCode::StringTooShort),
+            ValidationError::new("b".to_string(), "too short".to_string(), ErrorCode::StringTooShort),
+        ]);
+        metrics.add_nodes(vec![("string".to_string(), 2)].into_iter().collect());
+        metrics.add_nodes(vec![("string".to_string(), 1)].into_iter().collect());
+
+        assert_eq!((metrics.validations(), metrics.invalid()), (2, 1));
+        assert_eq!(metrics.errors_by_code().get("STRING_TOO_SHORT"), Some(&2));
+
+        let missing = [ValidationError::new("c".to_string(), "required".to_string(), ErrorCode::Required)];
+        metrics.record_many([&missing[..], &[], &[]]);
+        metrics.record_many(std::iter::empty());
+        assert_eq!((metrics.validations(), metrics.invalid()), (5, 2));
+        assert_eq!(metrics.node_counts().get("string"), Some(&3));
+        // Five validations in well under one bucket
+        assert!(metrics.throughput() >= 5.0 / BUCKET.as_secs_f64() - 1e-9);
+    }
+}
@@ -0,0 +1,466 @@
+
+//! OpenAPI 3.1 documents. `OpenApiValidator` compiles the request body,
+//! parameters and responses of every operation once. Their schemas are JSON
+//! Schema draft 2020-12, and `$ref`s such as `#/components/schemas/User`
+//! resolve against the whole document, so components are shared rather
+//! than extracted by hand.
+
+use crate::dialect::Translator;
+use crate::error::{FastSchemaError, ValidationResult};
+use crate::schema::{SchemaType, UnknownKeys};
+use crate::validator::Validator;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Parameter locations, which key the object `validate_parameters` checks
+const LOCATIONS: &[&str] = &["path", "query", "header", "cookie"];
+
+/// Validates HTTP traffic against the operations of an OpenAPI 3.1 document
+pub struct OpenApiValidator {
+    operations: Vec<Operation>,
+}
+
+struct Operation {
+    /// Path template, e.g. `/users/{id}`
+    path: String,
+    method: String,
+    parameters: Vec<Parameter>,
+    parameter_validator: Validator,
+    request_body: Option<Validator>,
+    /// By status key: `200`, `4XX` or `default`
+    responses: Vec<(String, Validator)>,
+}
+
+struct Parameter {
+    name: String,
+    location: String,
+    /// The parameter's schema, used to parse string values
+    schema: Value,
+}
+
+/// `{param}` values taken from a concrete path
+type PathValues = Vec<(String, String)>;
+
+/// An operation's schemas before the shared definitions are complete
+struct OperationSchemas {
+    path: String,
+    method: String,
+    parameters: Vec<Parameter>,
+    parameter_schema: SchemaType,
+    request_body: Option<SchemaType>,
+    responses: Vec<(String, SchemaType)>,
+}
+
+impl OpenApiValidator {
+    /// Compile every operation of an OpenAPI 3.1 document
+    pub fn from_json(document: &Value) -> Result<Self, FastSchemaError> {
+        match document.get("openapi").and_then(Value::as_str) {
+            Some(version) if version.starts_with("3.1") => {}
+            Some(version) => return Err(error(format!("OpenAPI {} is not supported; expected 3.1", version))),
+            None => return Err(error("Missing 'openapi' version".to_string())),
+        }
+
+        let mut translator = Translator::new(document);
+        let mut compiled = Vec::new();
+        let paths = document.get("paths").and_then(Value::as_object).cloned().unwrap_or_default();
+        for (path, item) in &paths {
+            let item = resolve(document, item)?;
+            let pointer = format!("/paths/{}", escape(path));
+            for method in METHODS {
+                if let Some(operation) = item.get(*method) {
+                    let pointer = format!("{}/{}", pointer, method);
+                    compiled.push(compile_operation(document, &mut translator, path, method, item, operation, &pointer)?);
+                }
+            }
+        }
+
+        let definitions = Arc::new(translator.into_definitions());
+        let validator = |schema: SchemaType| -> Result<Validator, FastSchemaError> {
+            Validator::new(schema)?.with_definitions(Arc::clone(&definitions))
+        };
+        let operations = compiled
+            .into_iter()
+            .map(|operation| {
+                Ok(Operation {
+                    path: operation.path,
+                    method: operation.method,
+                    parameters: operation.parameters,
+                    parameter_validator: validator(operation.parameter_schema)?,
+                    request_body: operation.request_body.map(validator).transpose()?,
+                    responses: operation
+                        .responses
+                        .into_iter()
+                        .map(|(status, schema)| Ok((status, validator(schema)?)))
+                        .collect::<Result<_, FastSchemaError>>()?,
+                })
+            })
+            .collect::<Result<_, FastSchemaError>>()?;
+
+        Ok(Self { operations })
+    }
+
+    /// `(path template, method)` of every operation
+    pub fn operations(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.operations.iter().map(|operation| (operation.path.as_str(), operation.method.as_str()))
+    }
+
+    /// Validate a JSON request body. Operations without a JSON body schema
+    /// accept anything.
+    pub fn validate_request_body(&self, path: &str, method: &str, body: &Value) -> Result<ValidationResult, FastSchemaError> {
+        let (operation, _) = self.find(path, method)?;
+        Ok(match &operation.request_body {
+            Some(validator) => validator.validate(body),
+            None => ValidationResult::success(body.clone()),
+        })
+    }
+
+    /// Validate parameters given as `{"path": {..}, "query": {..}, "header":
+    /// {..}, "cookie": {..}}`. String values are parsed by their schema
+    /// type (`"10"` for an integer, `"a,b"` for an array), header names are
+    /// case-insensitive, and path parameters missing from `parameters` are
+    /// read from `path` itself.
+    pub fn validate_parameters(&self, path: &str, method: &str, parameters: &Value) -> Result<ValidationResult, FastSchemaError> {
+        let (operation, path_values) = self.find(path, method)?;
+        let mut values = Map::new();
+        for location in LOCATIONS {
+            let mut given: Map<String, Value> = match parameters.get(*location).and_then(Value::as_object) {
+                Some(given) if *location == "header" => {
+                    given.iter().map(|(name, value)| (name.to_ascii_lowercase(), value.clone())).collect()
+                }
+                Some(given) => given.clone(),
+                None => Map::new(),
+            };
+            if *location == "path" {
+                for (name, value) in &path_values {
+                    given.entry(name.clone()).or_insert_with(|| Value::String(value.clone()));
+                }
+            }
+            for parameter in operation.parameters.iter().filter(|parameter| parameter.location == *location) {
+                if let Some(value) = given.get_mut(&parameter.name) {
+                    *value = parse_parameter(value, &parameter.schema);
+                }
+            }
+            values.insert(location.to_string(), Value::Object(given));
+        }
+        Ok(operation.parameter_validator.validate(&Value::Object(values)))
+    }
+
+    /// Validate a JSON response body against the response for `status`,
+    /// falling back to its `4XX`-style range and then `default`
+    pub fn validate_response(&self, path: &str, method: &str, status: u16, body: &Value) -> Result<ValidationResult, FastSchemaError> {
+        let (operation, _) = self.find(path, method)?;
+        let exact = status.to_string();
+        let range = format!("{}XX", status / 100);
+        let response = [exact.as_str(), range.as_str(), "default"]
+            .iter()
+            .find_map(|key| operation.responses.iter().find(|(status, _)| status.eq_ignore_ascii_case(key)));
+        match response {
+            Some((_, validator)) => Ok(validator.validate(body)),
+            None => Err(error(format!("No response {} for {} {}", status, method.to_uppercase(), operation.path))),
+        }
+    }
+
+    /// The operation for a method and a path template or concrete path,
+    /// with the path parameters a concrete path supplies. Templates with
+    /// more literal segments win, so `/users/me` beats `/users/{id}`.
+    fn find(&self, path: &str, method: &str) -> Result<(&Operation, PathValues), FastSchemaError> {
+        let path = path.split('?').next().unwrap_or_default();
+        let method = method.to_ascii_lowercase();
+        self.operations
+            .iter()
+            .filter(|operation| operation.method == method)
+            .filter_map(|operation| {
+                if operation.path == path {
+                    return Some((usize::MAX, operation, Vec::new()));
+                }
+                match_template(&operation.path, path).map(|(literals, values)| (literals, operation, values))
+            })
+            .max_by_key(|(literals, _, _)| *literals)
+            .map(|(_, operation, values)| (operation, values))
+            .ok_or_else(|| error(format!("No operation for {} {}", method.to_uppercase(), path)))
+    }
+}
+
+fn compile_operation(
+    document: &Value,
+    translator: &mut Translator<'_>,
+    path: &str,
+    method: &str,
+    item: &Value,
+    operation: &Value,
+    pointer: &str,
+) -> Result<OperationSchemas, FastSchemaError> {
+    // Operation parameters override path-level ones with the same name and location
+    let mut declared: Vec<(Value, String)> = Vec::new();
+    let sources = [(item, pointer.rsplit_once('/').map_or("", |(parent, _)| parent)), (operation, pointer)];
+    for (source, source_pointer) in sources.iter() {
+        for (index, parameter) in source.get("parameters").and_then(Value::as_array).into_iter().flatten().enumerate() {
+            let parameter = resolve(document, parameter)?.clone();
+            let key = |parameter: &Value| (parameter.get("name").cloned(), parameter.get("in").cloned());
+            declared.retain(|(existing, _)| key(existing) != key(&parameter));
+            declared.push((parameter, format!("{}/parameters/{}/schema", source_pointer, index)));
+        }
+    }
+
+    let mut parameters = Vec::new();
+    let mut locations: HashMap<&str, (HashMap<String, SchemaType>, Vec<String>)> = HashMap::new();
+    for (parameter, schema_pointer) in &declared {
+        let location = parameter.get("in").and_then(Value::as_str).unwrap_or_default();
+        let location = *LOCATIONS
+            .iter()
+            .find(|known| **known == location)
+            .ok_or_else(|| error(format!("Unknown parameter location '{}' at '#{}'", location, schema_pointer)))?;
+        let mut name = parameter.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+        if location == "header" {
+            name = name.to_ascii_lowercase();
+        }
+        let schema_json = parameter.get("schema").cloned().unwrap_or(Value::Bool(true));
+        let schema = translator.translate(&schema_json, schema_pointer)?;
+
+        let (properties, required) = locations.entry(location).or_default();
+        properties.insert(name.clone(), schema);
+        if location == "path" || parameter.get("required") == Some(&Value::Bool(true)) {
+            required.push(name.clone());
+        }
+        parameters.push(Parameter {
+            name,
+            location: location.to_string(),
+            schema: resolve(document, &schema_json)?.clone(),
+        });
+    }
+    let parameter_schema = object(
+        locations
+            .into_iter()
+            .map(|(location, (properties, required))| (location.to_string(), object(properties, required)))
+            .collect(),
+        Vec::new(),
+    );
+
+    let request_body = match operation.get("requestBody") {
+        Some(body) => {
+            let body = resolve(document, body)?;
+            json_schema(translator, body, &format!("{}/requestBody", pointer))?
+        }
+        None => None,
+    };
+
+    let mut responses = Vec::new();
+    if let Some(declared) = operation.get("responses").and_then(Value::as_object) {
+        for (status, response) in declared {
+            let response = resolve(document, response)?;
+            let pointer = format!("{}/responses/{}", pointer, escape(status));
+            let schema = json_schema(translator, response, &pointer)?.unwrap_or(SchemaType::Any);
+            responses.push((status.clone(), schema));
+        }
+    }
+
+    Ok(OperationSchemas {
+        path: path.to_string(),
+        method: method.to_string(),
+        parameters,
+        parameter_schema,
+        request_body,
+        responses,
+    })
+}
+
+/// The schema of a body's JSON media type (`application/json` or any
+/// `+json` type)
+fn json_schema(translator: &mut Translator<'_>, body: &Value, pointer: &str) -> Result<Option<SchemaType>, FastSchemaError> {
+    let content = match body.get("content").and_then(Value::as_object) {
+        Some(content) => content,
+        None => return Ok(None),
+    };
+    let media_type = content.iter().find(|(media_type, _)| {
+        let essence = media_type.split(';').next().unwrap_or_default().trim();
+        essence == "application/json" || essence.ends_with("+json")
+    });
+    match media_type.and_then(|(name, media_type)| Some((name, media_type.get("schema")?))) {
+        Some((name, schema)) => {
+            let pointer = format!("{}/content/{}/schema", pointer, escape(name));
+            translator.translate(schema, &pointer).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+fn object(properties: HashMap<String, SchemaType>, required: Vec<String>) -> SchemaType {
+    SchemaType::Object {
+        properties,
+        required: Some(required),
+        additional_properties: UnknownKeys::Passthrough,
+        catchall: None,
+        min_properties: None,
+        max_properties: None,
+        read_only: None,
+        write_only: None,
+        rules: None,
+    }
+}
+
+/// Follow `$ref`s to components within the document
+fn resolve<'d>(document: &'d Value, mut node: &'d Value) -> Result<&'d Value, FastSchemaError> {
+    for _ in 0..32 {
+        let reference = match node.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference,
+            None => return Ok(node),
+        };
+        node = reference
+            .strip_prefix('#')
+            .and_then(|pointer| document.pointer(pointer))
+            .ok_or_else(|| error(format!("Unresolved $ref '{}'", reference)))?;
+    }
+    Err(error("$ref chain is too long".to_string()))
+}
+
+/// Match a concrete path against a template, returning the number of
+/// literal segments and the values of `{param}` segments
+fn match_template(template: &str, path: &str) -> Option<(usize, PathValues)> {
+    let (template_segments, path_segments): (Vec<&str>, Vec<&str>) =
+        (template.split('/').collect(), path.split('/').collect());
+    if template_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut literals = 0;
+    let mut values = Vec::new();
+    for (template_segment, segment) in template_segments.iter().zip(&path_segments) {
+        match template_segment.strip_prefix('{').and_then(|name| name.strip_suffix('}')) {
+            Some(_) if segment.is_empty() => return None,
+            Some(name) => values.push((name.to_string(), segment.to_string())),
+            None if template_segment == segment => literals += 1,
+            None => return None,
+        }
+    }
+    Some((literals, values))
+}
+
+/// Parse a string parameter value by its schema's `type`
+fn parse_parameter(value: &Value, schema: &Value) -> Value {
+    let text = match value {
+        Value::String(text) => text,
+        _ => return value.clone(),
+    };
+    match schema.get("type").and_then(Value::as_str) {
+        Some("integer") | Some("number") => {
+            serde_json::from_str::<serde_json::Number>(text).map(Value::Number).unwrap_or_else(|_| value.clone())
+        }
+        Some("boolean") => match text.as_str() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => value.clone(),
+        },
+        Some("array") => {
+            let items = schema.get("items").unwrap_or(&Value::Null);
+            let parts = text.split(',').filter(|part| !part.is_empty());
+            Value::Array(parts.map(|part| parse_parameter(&Value::String(part.to_string()), items)).collect())
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Escape a JSON pointer segment
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn error(message: String) -> FastSchemaError {
+    FastSchemaError::OpenApi(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "openapi": "3.1.0",
+            "info": {"title": "Users", "version": "1"},
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "integer", "minimum": 1}}],
+                    "get": {
+                        "parameters": [
+                            {"name": "fields", "in": "query", "schema": {"type": "array", "items": {"type": "string"}}},
+                            {"$ref": "#/components/parameters/Trace"}
+                        ],
+                        "responses": {
+                            "200": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}},
+                            "4XX": {"$ref": "#/components/responses/Problem"}
+                        }
+                    },
+                    "put": {
+                        "requestBody": {
+                            "content": {"application/json; charset=utf-8": {"schema": {"$ref": "#/components/schemas/User"}}}
+                        },
+                        "responses": {"204": {"description": "Updated"}}
+                    }
+                },
+                "/users/me": {"get": {"responses": {"default": {"description": "Current user"}}}}
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {"name": {"type": "string"}, "manager": {"$ref": "#/components/schemas/User"}},
+                        "required": ["name"]
+                    }
+                },
+                "parameters": {"Trace": {"name": "X-Trace", "in": "header", "required": true, "schema": {"type": "boolean"}}},
+                "responses": {
+                    "Problem": {
+                        "content": {"application/problem+json": {"schema": {"type": "object", "required": ["title"]}}}
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_openapi_bodies() {
+        let api = OpenApiValidator::from_json(&document()).unwrap();
+        assert_eq!(api.operations().count(), 3);
+
+        let user = json!({"name": "Ann", "manager": {"name": "Bo"}});
+        assert!(api.validate_request_body("/users/{id}", "PUT", &user).unwrap().success);
+        let result = api.validate_request_body("/users/7", "put", &json!({"manager": {}})).unwrap();
+        let mut paths: Vec<String> = result.errors.iter().map(|error| error.path.to_string()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["manager.name", "name"]);
+
+        assert!(api.validate_response("/users/7", "get", 200, &user).unwrap().success);
+        assert!(!api.validate_response("/users/7", "get", 404, &json!({})).unwrap().success);
+        assert!(api.validate_response("/users/7", "get", 500, &json!({})).is_err());
+        // The literal template wins over `{id}`
+        assert!(api.validate_response("/users/me", "get", 500, &json!(null)).unwrap().success);
+        assert!(matches!(api.validate_request_body("/teams", "get", &json!({})), Err(FastSchemaError::OpenApi(_))));
+    }
+
+    #[test]
+    fn test_openapi_parameters() {
+        let api = OpenApiValidator::from_json(&document()).unwrap();
+
+        let result = api
+            .validate_parameters("/users/7?fields=name", "GET", &json!({"query": {"fields": "name,manager"}, "header": {"x-trace": "true"}}))
+            .unwrap();
+        assert!(result.success, "{:?}", result.errors);
+        let data = result.data.unwrap();
+        assert_eq!(data["path"]["id"], 7);
+        assert_eq!(data["query"]["fields"], json!(["name", "manager"]));
+
+        let result = api.validate_parameters("/users/0", "get", &json!({})).unwrap();
+        let mut errors: Vec<(String, ErrorCode)> =
+            result.errors.iter().map(|error| (error.path.to_string(), error.code.clone())).collect();
+        errors.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(errors[0], ("header.x-trace".to_string(), ErrorCode::ObjectMissingProperty));
+        assert_eq!(errors[1], ("path.id".to_string(), ErrorCode::NumberTooSmall));
+
+        let error = OpenApiValidator::from_json(&json!({"openapi": "3.0.3", "paths": {}})).err().unwrap();
+        assert!(error.to_string().contains("3.0.3"));
+    }
+}
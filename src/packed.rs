@@ -0,0 +1,426 @@
+
+//! MessagePack and CBOR payloads, decoded straight to JSON values so binary
+//! data from devices and realtime feeds is validated without a JSON text
+//! round-trip.
+//!
+//! JSON has no byte strings, so binary values become base64 strings (which
+//! `contentEncoding: "base64"` accepts), and MessagePack timestamps and CBOR
+//! epoch dates become RFC 3339 strings. Map keys that are not strings are
+//! written as their JSON text (`1`, `true`), and a repeated key keeps its
+//! last value, as `serde_json` does. NaN and infinities have no JSON form
+//! and are rejected.
+
+use crate::error::FastSchemaError;
+use crate::utils::format_timestamp;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{Map, Number, Value};
+use std::convert::TryFrom;
+
+/// Nesting limit when decoding, matching `serde_json`'s
+const MAX_DEPTH: usize = 128;
+
+/// The MessagePack extension type of timestamps
+const TIMESTAMP: i8 = -1;
+
+/// The CBOR "break" that ends an indefinite-length item
+const BREAK: u8 = 0xff;
+
+/// Decode a MessagePack value
+pub fn decode_msgpack(bytes: &[u8]) -> Result<Value, FastSchemaError> {
+    let mut reader = Reader { bytes, pos: 0, format: "MessagePack" };
+    let value = reader.msgpack(0)?;
+    reader.finish(value)
+}
+
+/// Decode a CBOR data item
+pub fn decode_cbor(bytes: &[u8]) -> Result<Value, FastSchemaError> {
+    let mut reader = Reader { bytes, pos: 0, format: "CBOR" };
+    let value = reader.cbor(0)?;
+    reader.finish(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    format: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    fn invalid(&self, reason: &str) -> FastSchemaError {
+        FastSchemaError::InputParsing(format!("{} at byte {}: {}", self.format, self.pos, reason))
+    }
+
+    fn finish(&self, value: Value) -> Result<Value, FastSchemaError> {
+        if self.pos != self.bytes.len() {
+            return Err(self.invalid("trailing bytes"));
+        }
+        Ok(value)
+    }
+
+    fn take(&mut self, len: u64) -> Result<&'a [u8], FastSchemaError> {
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| self.pos.checked_add(len))
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| self.invalid("truncated"))?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, FastSchemaError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// A big-endian unsigned integer of `size` bytes
+    fn uint(&mut self, size: u64) -> Result<u64, FastSchemaError> {
+        Ok(self.take(size)?.iter().fold(0, |n, &byte| n << 8 | u64::from(byte)))
+    }
+
+    /// A count of following items, which each take at least one byte
+    fn count(&self, len: u64) -> Result<usize, FastSchemaError> {
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(self.invalid("truncated"));
+        }
+        Ok(len as usize)
+    }
+
+    fn string(&mut self, len: u64) -> Result<String, FastSchemaError> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.invalid("invalid UTF-8"))
+    }
+
+    fn float(&self, value: f64) -> Result<Value, FastSchemaError> {
+        Number::from_f64(value).map(Value::Number).ok_or_else(|| self.invalid("NaN and infinities have no JSON form"))
+    }
+
+    fn float32(&mut self) -> Result<Value, FastSchemaError> {
+        let bits = self.uint(4)? as u32;
+        self.float(f64::from(f32::from_bits(bits)))
+    }
+
+    fn float64(&mut self) -> Result<Value, FastSchemaError> {
+        let bits = self.uint(8)?;
+        self.float(f64::from_bits(bits))
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), FastSchemaError> {
+        if depth > MAX_DEPTH {
+            return Err(self.invalid("nested too deeply"));
+        }
+        Ok(())
+    }
+
+    fn msgpack(&mut self, depth: usize) -> Result<Value, FastSchemaError> {
+        self.check_depth(depth)?;
+        let byte = self.byte()?;
+        Ok(match byte {
+            0x00..=0x7f => Value::from(byte),
+            0xe0..=0xff => Value::from(byte as i8),
+            0x80..=0x8f => self.msgpack_map(u64::from(byte & 0x0f), depth)?,
+            0x90..=0x9f => self.msgpack_array(u64::from(byte & 0x0f), depth)?,
+            0xa0..=0xbf => Value::String(self.string(u64::from(byte & 0x1f))?),
+            0xc0 => Value::Null,
+            0xc1 => return Err(self.invalid("0xc1 is never used")),
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4..=0xc6 => {
+                let len = self.uint(1 << (byte - 0xc4))?;
+                bytes_value(self.take(len)?)
+            }
+            0xc7..=0xc9 => {
+                let len = self.uint(1 << (byte - 0xc7))?;
+                let kind = self.byte()? as i8;
+                self.extension(kind, len)?
+            }
+            0xca => self.float32()?,
+            0xcb => self.float64()?,
+            0xcc..=0xcf => Value::from(self.uint(1 << (byte - 0xcc))?),
+            0xd0..=0xd3 => {
+                let size = 1 << (byte - 0xd0);
+                // Shift the sign bit to the top, then back with sign extension
+                let shift = 64 - 8 * size;
+                Value::from(((self.uint(size)? << shift) as i64) >> shift)
+            }
+            0xd4..=0xd8 => {
+                let kind = self.byte()? as i8;
+                self.extension(kind, 1 << (byte - 0xd4))?
+            }
+            0xd9..=0xdb => {
+                let len = self.uint(1 << (byte - 0xd9))?;
+                Value::String(self.string(len)?)
+            }
+            0xdc | 0xdd => {
+                let len = self.uint(if byte == 0xdc { 2 } else { 4 })?;
+                self.msgpack_array(len, depth)?
+            }
+            0xde | 0xdf => {
+                let len = self.uint(if byte == 0xde { 2 } else { 4 })?;
+                self.msgpack_map(len, depth)?
+            }
+        })
+    }
+
+    fn msgpack_array(&mut self, len: u64, depth: usize) -> Result<Value, FastSchemaError> {
+        let len = self.count(len)?;
+        let items = (0..len).map(|_| self.msgpack(depth + 1)).collect::<Result<_, _>>()?;
+        Ok(Value::Array(items))
+    }
+
+    fn msgpack_map(&mut self, len: u64, depth: usize) -> Result<Value, FastSchemaError> {
+        let len = self.count(len)?;
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = key_string(self.msgpack(depth + 1)?);
+            map.insert(key, self.msgpack(depth + 1)?);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn extension(&mut self, kind: i8, len: u64) -> Result<Value, FastSchemaError> {
+        if kind != TIMESTAMP {
+            return Err(self.invalid(&format!("extension type {} is not supported", kind)));
+        }
+        let (seconds, nanosecond) = match len {
+            4 => (self.uint(4)? as i64, 0),
+            8 => {
+                let data = self.uint(8)?;
+                ((data & 0x3_ffff_ffff) as i64, (data >> 34) as u32)
+            }
+            12 => {
+                let nanosecond = self.uint(4)? as u32;
+                (self.uint(8)? as i64, nanosecond)
+            }
+            _ => return Err(self.invalid("a timestamp is 4, 8 or 12 bytes")),
+        };
+        if nanosecond >= 1_000_000_000 {
+            return Err(self.invalid("timestamp nanoseconds out of range"));
+        }
+        Ok(Value::String(format_timestamp(seconds, nanosecond)))
+    }
+
+    /// The argument of a CBOR initial byte's additional information
+    fn argument(&mut self, info: u8) -> Result<u64, FastSchemaError> {
+        match info {
+            0..=23 => Ok(u64::from(info)),
+            24..=27 => self.uint(1 << (info - 24)),
+            _ => Err(self.invalid(&format!("additional information {} is reserved", info))),
+        }
+    }
+
+    /// The bytes of a definite or indefinite-length byte or text string
+    fn chunks(&mut self, major: u8, info: u8) -> Result<Vec<u8>, FastSchemaError> {
+        if info != 31 {
+            let len = self.argument(info)?;
+            return Ok(self.take(len)?.to_vec());
+        }
+        let mut bytes = Vec::new();
+        while self.peek() != Some(BREAK) {
+            let initial = self.byte()?;
+            if initial >> 5 != major || initial & 0x1f == 31 {
+                return Err(self.invalid("an indefinite-length string holds definite strings of its type"));
+            }
+            let len = self.argument(initial & 0x1f)?;
+            bytes.extend_from_slice(self.take(len)?);
+        }
+        self.pos += 1;
+        Ok(bytes)
+    }
+
+    /// Whether an indefinite-length array or map has more items, consuming its break
+    fn more(&mut self) -> Result<bool, FastSchemaError> {
+        match self.peek() {
+            Some(BREAK) => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(_) => Ok(true),
+            None => Err(self.invalid("truncated")),
+        }
+    }
+
+    fn cbor(&mut self, depth: usize) -> Result<Value, FastSchemaError> {
+        self.check_depth(depth)?;
+        let initial = self.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        Ok(match major {
+            0 => Value::from(self.argument(info)?),
+            1 => negative(self.argument(info)?),
+            2 => bytes_value(&self.chunks(major, info)?),
+            3 => {
+                let bytes = self.chunks(major, info)?;
+                Value::String(String::from_utf8(bytes).map_err(|_| self.invalid("invalid UTF-8"))?)
+            }
+            4 => {
+                let mut items = Vec::new();
+                if info == 31 {
+                    while self.more()? {
+                        items.push(self.cbor(depth + 1)?);
+                    }
+                } else {
+                    let len = self.argument(info)?;
+                    for _ in 0..self.count(len)? {
+                        items.push(self.cbor(depth + 1)?);
+                    }
+                }
+                Value::Array(items)
+            }
+            5 => {
+                let mut map = Map::new();
+                if info == 31 {
+                    while self.more()? {
+                        let key = key_string(self.cbor(depth + 1)?);
+                        map.insert(key, self.cbor(depth + 1)?);
+                    }
+                } else {
+                    let len = self.argument(info)?;
+                    for _ in 0..self.count(len)? {
+                        let key = key_string(self.cbor(depth + 1)?);
+                        map.insert(key, self.cbor(depth + 1)?);
+                    }
+                }
+                Value::Object(map)
+            }
+            6 => {
+                let tag = self.argument(info)?;
+                self.tagged(tag, depth)?
+            }
+            _ => match info {
+                20 => Value::Bool(false),
+                21 => Value::Bool(true),
+                // `undefined` has no JSON form; it reads as a missing value
+                22 | 23 => Value::Null,
+                25 => {
+                    let bits = self.uint(2)? as u16;
+                    self.float(half(bits))?
+                }
+                26 => self.float32()?,
+                27 => self.float64()?,
+                31 => return Err(self.invalid("unexpected break")),
+                _ => return Err(self.invalid("simple values other than true, false, null and undefined are not supported")),
+            },
+        })
+    }
+
+    fn tagged(&mut self, tag: u64, depth: usize) -> Result<Value, FastSchemaError> {
+        match tag {
+            // Bignums, kept while they fit in 64 bits
+            2 | 3 => {
+                let initial = self.byte()?;
+                if initial >> 5 != 2 {
+                    return Err(self.invalid("a bignum holds a byte string"));
+                }
+                let bytes = self.chunks(2, initial & 0x1f)?;
+                let significant = bytes.iter().position(|&byte| byte != 0).map_or(&[][..], |start| &bytes[start..]);
+                if significant.len() > 8 {
+                    return Err(self.invalid("bignum out of range"));
+                }
+                let n = significant.iter().fold(0, |n, &byte| n << 8 | u64::from(byte));
+                Ok(if tag == 2 { Value::from(n) } else { negative(n) })
+            }
+            // Epoch-based date-time
+            1 => match self.cbor(depth + 1)? {
+                Value::Number(n) => {
+                    let seconds = n.as_f64().unwrap_or(0.0);
+                    let whole = seconds.floor();
+                    let nanosecond = ((seconds - whole) * 1e9).round().min(999_999_999.0) as u32;
+                    Ok(Value::String(format_timestamp(whole as i64, nanosecond)))
+                }
+                _ => Err(self.invalid("an epoch date-time holds a number")),
+            },
+            // Standard date-time strings (tag 0) and every other tag read as
+            // the item they wrap
+            _ => self.cbor(depth + 1),
+        }
+    }
+}
+
+/// The CBOR negative integer `-1 - n`, as a float once it passes `i64::MIN`
+fn negative(n: u64) -> Value {
+    match i64::try_from(n) {
+        Ok(n) => Value::from(-1 - n),
+        Err(_) => Value::from(-1.0 - n as f64),
+    }
+}
+
+fn bytes_value(bytes: &[u8]) -> Value {
+    Value::String(STANDARD.encode(bytes))
+}
+
+fn key_string(key: Value) -> String {
+    match key {
+        Value::String(key) => key,
+        key => key.to_string(),
+    }
+}
+
+/// An IEEE 754 half-precision float
+fn half(bits: u16) -> f64 {
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f64::from(bits & 0x3ff);
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent - 25),
+    };
+    if bits & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_msgpack_and_cbor() {
+        // {"id": 7, "temp": -1.5, "ok": true, "raw": b"\x01\x02", "at": timestamp 1_700_000_000, 1: [-33, null]}
+        let msgpack = [
+            0x86, 0xa2, b'i', b'd', 0x07, 0xa4, b't', b'e', b'm', b'p', 0xcb, 0xbf, 0xf8, 0, 0, 0, 0, 0, 0, 0xa2,
+            b'o', b'k', 0xc3, 0xa3, b'r', b'a', b'w', 0xc4, 0x02, 0x01, 0x02, 0xa2, b'a', b't', 0xd6, 0xff, 0x65,
+            0x53, 0xf1, 0x00, 0x01, 0x92, 0xd0, 0xdf, 0xc0,
+        ];
+        let expected = json!({
+            "id": 7,
+            "temp": -1.5,
+            "ok": true,
+            "raw": "AQI=",
+            "at": "2023-11-14T22:13:20Z",
+            "1": [-33, null]
+        });
+        assert_eq!(decode_msgpack(&msgpack).unwrap(), expected);
+
+        // The same value in CBOR, with an indefinite-length array, a
+        // half-float and an epoch date
+        let cbor = [
+            0xa6, 0x62, b'i', b'd', 0x07, 0x64, b't', b'e', b'm', b'p', 0xf9, 0xbe, 0x00, 0x62, b'o', b'k', 0xf5,
+            0x63, b'r', b'a', b'w', 0x42, 0x01, 0x02, 0x62, b'a', b't', 0xc1, 0x1a, 0x65, 0x53, 0xf1, 0x00, 0x01,
+            0x9f, 0x38, 0x20, 0xf6, 0xff,
+        ];
+        assert_eq!(decode_cbor(&cbor).unwrap(), expected);
+        assert_eq!(decode_cbor(&[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).unwrap(), json!(-1.8446744073709552e19));
+        assert!(decode_cbor(&[0xfb, 0x7f, 0xf8, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(decode_cbor(&[0xc3, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+
+        for len in 0..msgpack.len() {
+            assert!(decode_msgpack(&msgpack[..len]).is_err());
+        }
+        for len in 0..cbor.len() {
+            assert!(decode_cbor(&cbor[..len]).is_err());
+        }
+        assert!(matches!(decode_msgpack(&[0xc1]), Err(FastSchemaError::InputParsing(_))));
+        assert!(decode_msgpack(&[0xc0, 0xc0]).is_err());
+        assert!(decode_msgpack(&[0xc7, 0x01, 0x05, 0x00]).is_err());
+        assert!(decode_msgpack(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(decode_msgpack(&[0x91; 200]).is_err());
+    }
+}
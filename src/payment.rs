@@ -0,0 +1,225 @@
+
+//! Checksum-based payment formats: card numbers (Luhn), IBAN (mod-97)
+//! and BIC. Tables are sorted for binary search.
+
+use crate::locale;
+
+/// Card networks recognised from the number prefix (IIN range)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardBrand {
+    Visa,
+    Mastercard,
+    Amex,
+    Discover,
+    DinersClub,
+    Jcb,
+    UnionPay,
+    Maestro,
+}
+
+impl CardBrand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardBrand::Visa => "visa",
+            CardBrand::Mastercard => "mastercard",
+            CardBrand::Amex => "amex",
+            CardBrand::Discover => "discover",
+            CardBrand::DinersClub => "diners-club",
+            CardBrand::Jcb => "jcb",
+            CardBrand::UnionPay => "unionpay",
+            CardBrand::Maestro => "maestro",
+        }
+    }
+
+    /// Number lengths the network issues
+    fn lengths(&self) -> &'static [usize] {
+        match self {
+            CardBrand::Visa => &[13, 16, 19],
+            CardBrand::Mastercard => &[16],
+            CardBrand::Amex => &[15],
+            CardBrand::Discover | CardBrand::Jcb | CardBrand::UnionPay => &[16, 17, 18, 19],
+            CardBrand::DinersClub => &[14, 15, 16, 17, 18, 19],
+            CardBrand::Maestro => &[12, 13, 14, 15, 16, 17, 18, 19],
+        }
+    }
+}
+
+/// Detect the card network from the leading digits of a card number
+pub fn detect_card_brand(digits: &str) -> Option<CardBrand> {
+    let prefix = |length: usize| digits.get(..length).and_then(|p| p.parse::<u32>().ok());
+    let in_range = |length: usize, low: u32, high: u32| prefix(length).is_some_and(|p| (low..=high).contains(&p));
+
+    if digits.starts_with('4') {
+        Some(CardBrand::Visa)
+    } else if in_range(2, 51, 55) || in_range(4, 2221, 2720) {
+        Some(CardBrand::Mastercard)
+    } else if in_range(2, 34, 34) || in_range(2, 37, 37) {
+        Some(CardBrand::Amex)
+    } else if in_range(4, 6011, 6011) || in_range(3, 644, 649) || in_range(2, 65, 65) {
+        Some(CardBrand::Discover)
+    } else if in_range(3, 300, 305) || in_range(2, 36, 36) || in_range(2, 38, 39) {
+        Some(CardBrand::DinersClub)
+    } else if in_range(4, 3528, 3589) {
+        Some(CardBrand::Jcb)
+    } else if in_range(2, 62, 62) {
+        Some(CardBrand::UnionPay)
+    } else if prefix(4).is_some_and(|p| [5018, 5020, 5038, 5893, 6304, 6759, 6761, 6762, 6763].contains(&p)) {
+        Some(CardBrand::Maestro)
+    } else {
+        None
+    }
+}
+
+/// Luhn (mod 10) checksum over ASCII digits
+fn luhn_checksum(digits: &str) -> bool {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(index, b)| {
+            let digit = u32::from(b - b'0');
+            if index % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                digit
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Validate a card number: 12-19 digits (optionally grouped by single
+/// spaces or dashes) passing the Luhn check, with a length the detected
+/// brand issues
+pub fn is_credit_card(value: &str) -> bool {
+    // One kind of separator, never doubled or at the ends
+    let digits: String = match value.chars().find(|c| *c == ' ' || *c == '-') {
+        Some(separator) => {
+            let groups: Vec<&str> = value.split(separator).collect();
+            if groups.iter().any(|group| group.is_empty()) {
+                return false;
+            }
+            groups.concat()
+        }
+        None => value.to_string(),
+    };
+    if !(12..=19).contains(&digits.len()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    let valid_length = detect_card_brand(&digits).is_none_or(|brand| brand.lengths().contains(&digits.len()));
+    valid_length && luhn_checksum(&digits)
+}
+
+/// IBAN length by country (SWIFT IBAN registry), sorted by country
+pub const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24), ("AE", 23), ("AL", 28), ("AT", 20), ("AZ", 28), ("BA", 20), ("BE", 16), ("BG", 22),
+    ("BH", 22), ("BI", 27), ("BR", 29), ("BY", 28), ("CH", 21), ("CR", 22), ("CY", 28), ("CZ", 24),
+    ("DE", 22), ("DJ", 27), ("DK", 18), ("DO", 28), ("EE", 20), ("EG", 29), ("ES", 24), ("FI", 18),
+    ("FK", 18), ("FO", 18), ("FR", 27), ("GB", 22), ("GE", 22), ("GI", 23), ("GL", 18), ("GR", 27),
+    ("GT", 28), ("HN", 28), ("HR", 21), ("HU", 28), ("IE", 22), ("IL", 23), ("IQ", 23), ("IS", 26),
+    ("IT", 27), ("JO", 30), ("KW", 30), ("KZ", 20), ("LB", 28), ("LC", 32), ("LI", 21), ("LT", 20),
+    ("LU", 20), ("LV", 21), ("LY", 25), ("MC", 27), ("MD", 24), ("ME", 22), ("MK", 19), ("MN", 20),
+    ("MR", 27), ("MT", 31), ("MU", 30), ("NI", 28), ("NL", 18), ("NO", 15), ("OM", 23), ("PK", 24),
+    ("PL", 28), ("PS", 29), ("PT", 25), ("QA", 29), ("RO", 24), ("RS", 22), ("RU", 33), ("SA", 24),
+    ("SC", 31), ("SD", 18), ("SE", 24), ("SI", 19), ("SK", 24), ("SM", 27), ("SO", 23), ("ST", 25),
+    ("SV", 28), ("TL", 23), ("TN", 24), ("TR", 26), ("UA", 29), ("VA", 22), ("VG", 24), ("XK", 20),
+    ("YE", 30),
+];
+
+/// Validate an IBAN in electronic (`DE89370400440532013000`) or print
+/// (`DE89 3704 0044 0532 0130 00`) form: a registered country, its
+/// length, and the ISO 7064 mod-97 check digits
+pub fn is_iban(value: &str) -> bool {
+    if value.contains(' ') {
+        // Print form groups four characters per block
+        let groups: Vec<&str> = value.split(' ').collect();
+        let (last, full) = groups.split_last().expect("split yields at least one group");
+        if full.iter().any(|group| group.len() != 4) || last.is_empty() || last.len() > 4 {
+            return false;
+        }
+    }
+
+    let iban: String = value.chars().filter(|c| *c != ' ').collect::<String>().to_ascii_uppercase();
+    if iban.len() < 5 || !iban.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return false;
+    }
+
+    let expected_length = IBAN_LENGTHS
+        .binary_search_by(|(country, _)| (*country).cmp(&iban[..2]))
+        .ok()
+        .map(|index| IBAN_LENGTHS[index].1);
+    if expected_length != Some(iban.len()) || !iban[2..4].bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    // Move the country and check digits to the end, map letters to 10-35
+    // and reduce digit by digit to stay within u32
+    let remainder = iban[4..].bytes().chain(iban[..4].bytes()).fold(0u32, |remainder, b| {
+        if b.is_ascii_digit() {
+            (remainder * 10 + u32::from(b - b'0')) % 97
+        } else {
+            (remainder * 100 + u32::from(b - b'A') + 10) % 97
+        }
+    });
+    remainder == 1
+}
+
+/// Validate an ISO 9362 BIC: 4-char institution code, a country code,
+/// 2-char location and an optional 3-char branch (8 or 11 chars, uppercase)
+pub fn is_bic(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let is_upper_alnum = |b: &u8| b.is_ascii_uppercase() || b.is_ascii_digit();
+
+    (bytes.len() == 8 || bytes.len() == 11)
+        && bytes[..4].iter().all(is_upper_alnum)
+        && bytes[4..6].iter().all(u8::is_ascii_uppercase)
+        && locale::is_country_code(&value[4..6])
+        && bytes[6..].iter().all(is_upper_alnum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iban_lengths_are_sorted() {
+        assert!(IBAN_LENGTHS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_credit_card() {
+        assert!(is_credit_card("4111111111111111"));
+        assert!(is_credit_card("4111 1111 1111 1111"));
+        assert!(is_credit_card("5555-5555-5555-4444"));
+        assert!(is_credit_card("378282246310005"));
+        assert!(!is_credit_card("4111111111111112")); // Luhn
+        assert!(!is_credit_card("3782822463100050")); // Amex numbers are 15 digits
+        assert!(!is_credit_card("4111 1111-1111 1111"));
+        assert!(!is_credit_card("4111  1111 1111 1111"));
+
+        assert_eq!(detect_card_brand("4111111111111111"), Some(CardBrand::Visa));
+        assert_eq!(detect_card_brand("2221000000000009"), Some(CardBrand::Mastercard));
+        assert_eq!(detect_card_brand("6011111111111117"), Some(CardBrand::Discover));
+        assert_eq!(detect_card_brand("3530111333300000"), Some(CardBrand::Jcb));
+        assert_eq!(detect_card_brand("9999999999999995"), None);
+    }
+
+    #[test]
+    fn test_iban_and_bic() {
+        assert!(is_iban("DE89370400440532013000"));
+        assert!(is_iban("GB82 WEST 1234 5698 7654 32"));
+        assert!(is_iban("no9386011117947"));
+        assert!(!is_iban("DE89370400440532013001")); // Check digits
+        assert!(!is_iban("DE8937040044053201300")); // Length
+        assert!(!is_iban("XX89370400440532013000"));
+        assert!(!is_iban("GB82 WEST 12345698 765432"));
+
+        assert!(is_bic("DEUTDEFF"));
+        assert!(is_bic("NEDSZAJJXXX"));
+        assert!(!is_bic("DEUTXXFF")); // Unknown country
+        assert!(!is_bic("DEUTDEF"));
+        assert!(!is_bic("deutdeff"));
+    }
+}
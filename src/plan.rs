@@ -0,0 +1,714 @@
+
+//! Validation plans: a schema compiled once into a tree of closures, with
+//! regexes compiled, property names owned by their node and required
+//! lists precomputed. Nodes the plan does not specialise (composition,
+//! references, content and cross-field constraints, ...) are handed to
+//! the interpreter in `Validator::validate_value`.
+
+use crate::error::{ErrorCode, ValidationError};
+use crate::memo;
+use crate::regex_cache;
+use crate::schema::{LengthUnit, SchemaType, StringFormat, UnknownKeys};
+use crate::utils::{closest_match, json_equal, validate_string_format, UniqueChecker, ValidationContext};
+use crate::validator::{enum_suggestion, enum_values, format_failure, redact_sensitive, union_failure, Validator};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// A compiled schema node
+type NodeFn = Box<dyn Fn(&Validator, &Value, &mut ValidationContext) -> Vec<ValidationError> + Send + Sync>;
+
+/// Executable form of a schema, built by `SchemaType::compile`
+pub struct ValidationPlan {
+    root: NodeFn,
+    compiled_nodes: usize,
+    interpreted_nodes: usize,
+}
+
+impl ValidationPlan {
+    pub fn compile(schema: &SchemaType) -> Self {
+        let mut builder = Builder::default();
+        let root = compile_node(schema, &mut builder);
+
+        Self {
+            root,
+            compiled_nodes: builder.compiled,
+            interpreted_nodes: builder.interpreted,
+        }
+    }
+
+    /// Validate a value; errors are identical to interpreting the schema
+    pub(crate) fn run(&self, validator: &Validator, value: &Value, context: &mut ValidationContext) -> Vec<ValidationError> {
+        (self.root)(validator, value, context)
+    }
+
+    /// Nodes with a specialised closure
+    pub fn compiled_nodes(&self) -> usize {
+        self.compiled_nodes
+    }
+
+    /// Subtrees delegated to the interpreter
+    pub fn interpreted_nodes(&self) -> usize {
+        self.interpreted_nodes
+    }
+}
+
+impl fmt::Debug for ValidationPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationPlan")
+            .field("compiled_nodes", &self.compiled_nodes)
+            .field("interpreted_nodes", &self.interpreted_nodes)
+            .finish()
+    }
+}
+
+/// Node counts gathered while building a plan
+#[derive(Default)]
+struct Builder {
+    compiled: usize,
+    interpreted: usize,
+}
+
+fn compile_node(schema: &SchemaType, builder: &mut Builder) -> NodeFn {
+    let node = match schema {
+        SchemaType::Annotated { schema, metadata } if metadata.sensitive => {
+            let node = compile_node(schema, builder);
+            return Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+                let mut errors = node(validator, value, context);
+                redact_sensitive(&mut errors, value, context.path.depth());
+                errors
+            });
+        }
+        SchemaType::Annotated { schema, .. } => return compile_node(schema, builder),
+        SchemaType::String {
+            min_length,
+            max_length,
+            pattern,
+            format,
+            content_encoding: None,
+            content_media_type: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit,
+            ..
+        } if format.as_ref().is_none_or(|format| is_plain_format(format, *min_length, *max_length)) => {
+            compile_string(*min_length, *max_length, *length_unit, pattern, format)
+        }
+        SchemaType::Number {
+            min, max, exclusive_min, exclusive_max, integer, multiple_of, allow_non_finite, strict_integer, multiple_of_tolerance,
+        } => {
+            let (min, max, exclusive_min, exclusive_max) = (*min, *max, *exclusive_min, *exclusive_max);
+            let (integer, multiple_of) = (*integer, *multiple_of);
+            let (allow_non_finite, strict_integer, tolerance) = (*allow_non_finite, *strict_integer, *multiple_of_tolerance);
+            Some(Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+                let mut errors = validator.validate_number(
+                    value, min, max, exclusive_min, exclusive_max, integer, multiple_of, allow_non_finite, strict_integer,
+                    tolerance, context,
+                );
+                context.limit_errors(&mut errors);
+                errors
+            }) as NodeFn)
+        }
+        SchemaType::BigInt { min, max } => {
+            let (min, max) = (*min, *max);
+            Some(Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+                let mut errors = validator.validate_bigint(value, min, max, context);
+                context.limit_errors(&mut errors);
+                errors
+            }) as NodeFn)
+        }
+        SchemaType::Boolean => Some(Box::new(|validator: &Validator, value: &Value, context: &mut ValidationContext| {
+            validator.validate_boolean(value, context)
+        }) as NodeFn),
+        SchemaType::Null => Some(Box::new(|validator: &Validator, value: &Value, context: &mut ValidationContext| {
+            validator.validate_null(value, context)
+        }) as NodeFn),
+        SchemaType::Any => Some(Box::new(|_: &Validator, _: &Value, _: &mut ValidationContext| Vec::new()) as NodeFn),
+        SchemaType::Const { value: expected } => {
+            let expected = expected.clone();
+            Some(Box::new(move |_: &Validator, value: &Value, context: &mut ValidationContext| {
+                if json_equal(value, &expected) {
+                    Vec::new()
+                } else {
+                    vec![ValidationError::with_values(
+                        context.path.build(),
+                        format!("Value must be {}", expected),
+                        ErrorCode::ConstMismatch,
+                        expected.clone(),
+                        value.clone(),
+                    )]
+                }
+            }) as NodeFn)
+        }
+        SchemaType::Array { items, min_items, max_items, unique_items, unique_by: None, sorted: None, sorted_by: None } => {
+            Some(compile_array(items, *min_items, *max_items, *unique_items, builder))
+        }
+        SchemaType::Object {
+            properties,
+            required,
+            additional_properties,
+            catchall,
+            min_properties,
+            max_properties,
+            read_only: None,
+            write_only: None,
+            rules: None,
+        } => Some(compile_object(
+            properties.iter().map(|(name, schema)| (name.clone(), compile_node(schema, builder))).collect(),
+            required.clone().unwrap_or_default(),
+            *additional_properties,
+            catchall.as_ref().map(|schema| compile_node(schema, builder)),
+            *min_properties,
+            *max_properties,
+        )),
+        SchemaType::AnyOf { schemas, unevaluated_properties: None, unevaluated_items: None } => {
+            Some(compile_any_of(compile_branches(schemas, builder), enum_values(schemas)))
+        }
+        SchemaType::OneOf { schemas, unevaluated_properties: None, unevaluated_items: None } => {
+            Some(compile_one_of(compile_branches(schemas, builder), enum_values(schemas)))
+        }
+        SchemaType::Union { options, .. } => {
+            Some(compile_any_of(compile_branches(options, builder), enum_values(options)))
+        }
+        _ => None,
+    };
+
+    match node {
+        Some(node) if accepted_types(schema) & (STRING | ARRAY | OBJECT) != 0 => {
+            builder.compiled += 1;
+            memoized(node)
+        }
+        Some(node) => {
+            builder.compiled += 1;
+            node
+        }
+        None => {
+            builder.interpreted += 1;
+            let schema = schema.clone();
+            Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+                validator.validate_value(value, &schema, context)
+            })
+        }
+    }
+}
+
+/// Consult the context's memo (if any) around a compiled node; mirrors
+/// `Validator::validate_value` for interpreted ones
+fn memoized(node: NodeFn) -> NodeFn {
+    // A live allocation of its own gives the node an address no schema
+    // node (the interpreter's keys) or other plan node can share
+    let marker = Box::new(0u8);
+
+    Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+        let memo = match &context.memo {
+            Some(memo) if !context.track_evaluated && memo::is_memoizable(value) => Arc::clone(memo),
+            _ => return node(validator, value, context),
+        };
+
+        let (id, hash) = (&*marker as *const u8 as usize, memo::content_hash(value));
+        if memo::lock(&memo).is_valid(id, hash) {
+            return Vec::new();
+        }
+        let errors = node(validator, value, context);
+        if errors.is_empty() && !context.truncated {
+            memo::lock(&memo).insert_valid(id, hash);
+        }
+        errors
+    })
+}
+
+/// Formats whose check depends only on the string itself
+fn is_plain_format(format: &StringFormat, min_length: Option<usize>, max_length: Option<usize>) -> bool {
+    match format {
+        // Registry lookups, numeric coordinates and nanoid's length override
+        StringFormat::Custom(_) | StringFormat::Latitude | StringFormat::Longitude => false,
+        StringFormat::Nanoid => min_length.is_none() && max_length.is_none(),
+        _ => true,
+    }
+}
+
+fn compile_string(
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    length_unit: LengthUnit,
+    pattern: &Option<String>,
+    format: &Option<StringFormat>,
+) -> Option<NodeFn> {
+    // Invalid patterns are reported by the interpreter
+    let pattern = match pattern {
+        Some(pattern) => Some((pattern.clone(), regex_cache::get_or_compile(pattern).ok()?)),
+        None => None,
+    };
+    let format = format.clone();
+    let check_length = min_length.is_some() || max_length.is_some();
+
+    Some(Box::new(move |_: &Validator, value: &Value, context: &mut ValidationContext| {
+        let mut errors = Vec::new();
+
+        let s = match value.as_str() {
+            Some(s) => s,
+            None => return vec![ValidationError::type_mismatch(context.path.build(), "string", value)],
+        };
+
+        if check_length {
+            let len = length_unit.length(s);
+            if let Some(min) = min_length.filter(|min| len < *min) {
+                errors.push(ValidationError::string_length(context.path.build(), len, Some(min), max_length));
+            }
+            if let Some(max) = max_length.filter(|max| len > *max) {
+                errors.push(ValidationError::string_length(context.path.build(), len, min_length, Some(max)));
+            }
+        }
+
+        if let Some((pattern, regex)) = pattern.as_ref().filter(|_| context.should_continue(errors.len())) {
+            if !regex.is_match(s) {
+                errors.push(ValidationError::new(
+                    context.path.build(),
+                    format!("String does not match pattern: {}", pattern),
+                    ErrorCode::StringPatternMismatch,
+                ));
+            }
+        }
+
+        if let Some(format) = format.as_ref().filter(|_| context.should_continue(errors.len())) {
+            if !validate_string_format(s, format) {
+                errors.push(format_failure(format, s, context.path.build()));
+            }
+        }
+
+        context.limit_errors(&mut errors);
+        errors
+    }))
+}
+
+fn compile_array(
+    items: &SchemaType,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    unique_items: bool,
+    builder: &mut Builder,
+) -> NodeFn {
+    let items = compile_node(items, builder);
+
+    Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+        let arr = match value.as_array() {
+            Some(arr) => arr,
+            None => return vec![ValidationError::type_mismatch(context.path.build(), "array", value)],
+        };
+        let mut errors = Vec::new();
+        let len = arr.len();
+
+        if let Some(min) = min_items.filter(|min| len < *min) {
+            errors.push(ValidationError::out_of_range(
+                context.path.build(),
+                format!("Array must have at least {} items", min),
+                ErrorCode::ArrayTooShort,
+                Some(min),
+                None,
+                serde_json::json!(len),
+            ));
+        }
+        if let Some(max) = max_items.filter(|max| len > *max) {
+            errors.push(ValidationError::out_of_range(
+                context.path.build(),
+                format!("Array must have at most {} items", max),
+                ErrorCode::ArrayTooLong,
+                None,
+                Some(max),
+                serde_json::json!(len),
+            ));
+        }
+
+        if unique_items {
+            let mut unique_checker = UniqueChecker::new();
+            for (index, item) in arr.iter().enumerate() {
+                if !context.should_continue(errors.len()) {
+                    break;
+                }
+                if let Some(first) = unique_checker.insert_at(item, index) {
+                    context.path.push_index(index);
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("Item duplicates the item at index {}", first),
+                        ErrorCode::ArrayNotUnique,
+                    ));
+                    context.path.pop();
+                }
+            }
+        }
+
+        context.mark_items_evaluated(len);
+        for (index, item) in arr.iter().enumerate() {
+            if !context.should_continue(errors.len()) {
+                break;
+            }
+
+            context.path.push_index(index);
+            let item_errors = items(validator, item, context);
+            context.path.pop();
+            errors.extend(item_errors);
+        }
+
+        context.limit_errors(&mut errors);
+        errors
+    })
+}
+
+fn compile_object(
+    properties: Vec<(String, NodeFn)>,
+    required: Vec<String>,
+    additional_properties: UnknownKeys,
+    catchall: Option<NodeFn>,
+    min_properties: Option<usize>,
+    max_properties: Option<usize>,
+) -> NodeFn {
+    let known: HashSet<String> = properties.iter().map(|(name, _)| name.clone()).collect();
+    let check_unknown = catchall.is_some() || additional_properties == UnknownKeys::Strict;
+
+    Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+        let obj = match value.as_object() {
+            Some(obj) => obj,
+            None => return vec![ValidationError::type_mismatch(context.path.build(), "object", value)],
+        };
+        let mut errors = Vec::new();
+
+        if let Some(min) = min_properties.filter(|min| obj.len() < *min) {
+            errors.push(ValidationError::out_of_range(
+                context.path.build(),
+                format!("Object must have at least {} properties", min),
+                ErrorCode::ObjectTooFewProperties,
+                Some(min),
+                None,
+                serde_json::json!(obj.len()),
+            ));
+        }
+        if let Some(max) = max_properties.filter(|max| obj.len() > *max) {
+            errors.push(ValidationError::out_of_range(
+                context.path.build(),
+                format!("Object must have at most {} properties", max),
+                ErrorCode::ObjectTooManyProperties,
+                None,
+                Some(max),
+                serde_json::json!(obj.len()),
+            ));
+        }
+
+        for name in &required {
+            if !context.should_continue(errors.len()) {
+                break;
+            }
+            if !obj.contains_key(name) {
+                errors.push(ValidationError::missing_property(context.path.build(), name));
+            }
+        }
+
+        for (name, node) in &properties {
+            if let Some(prop_value) = obj.get(name) {
+                if !context.should_continue(errors.len()) {
+                    break;
+                }
+
+                context.mark_property_evaluated(name);
+                context.path.push(name);
+                let prop_errors = node(validator, prop_value, context);
+                context.path.pop();
+                errors.extend(prop_errors);
+            }
+        }
+
+        if check_unknown {
+            for (key, prop_value) in obj {
+                if known.contains(key) {
+                    continue;
+                }
+                if !context.should_continue(errors.len()) {
+                    break;
+                }
+
+                match &catchall {
+                    Some(catchall) => {
+                        context.mark_property_evaluated(key);
+                        context.path.push(key);
+                        let prop_errors = catchall(validator, prop_value, context);
+                        context.path.pop();
+                        errors.extend(prop_errors);
+                    }
+                    None => {
+                        let missing = known.iter().map(String::as_str).filter(|name| !obj.contains_key(*name));
+                        errors.push(ValidationError::new(
+                            context.path.build().child(key),
+                            format!("Additional property '{}' is not allowed", key),
+                            ErrorCode::ObjectAdditionalProperty,
+                        ).with_suggestion(closest_match(key, missing)))
+                    }
+                }
+            }
+        }
+
+        context.limit_errors(&mut errors);
+        errors
+    })
+}
+
+const NULL: u8 = 1;
+const BOOLEAN: u8 = 1 << 1;
+const NUMBER: u8 = 1 << 2;
+const STRING: u8 = 1 << 3;
+const ARRAY: u8 = 1 << 4;
+const OBJECT: u8 = 1 << 5;
+const ANY_TYPE: u8 = u8::MAX;
+
+fn type_bit(value: &Value) -> u8 {
+    match value {
+        Value::Null => NULL,
+        Value::Bool(_) => BOOLEAN,
+        Value::Number(_) => NUMBER,
+        Value::String(_) => STRING,
+        Value::Array(_) => ARRAY,
+        Value::Object(_) => OBJECT,
+    }
+}
+
+/// Whether `value` has a JSON type `schema` can accept at all
+pub(crate) fn accepts(schema: &SchemaType, value: &Value) -> bool {
+    accepted_types(schema) & type_bit(value) != 0
+}
+
+/// JSON types a schema can accept; values of other types are certain to fail
+fn accepted_types(schema: &SchemaType) -> u8 {
+    match schema {
+        SchemaType::Annotated { schema, .. } => accepted_types(schema),
+        SchemaType::String { .. } => STRING,
+        SchemaType::Number { allow_non_finite: true, .. } => NUMBER | STRING,
+        SchemaType::Number { .. } => NUMBER,
+        SchemaType::BigInt { .. } | SchemaType::Decimal { .. } => NUMBER | STRING,
+        SchemaType::Boolean => BOOLEAN,
+        SchemaType::Null => NULL,
+        SchemaType::Array { .. } | SchemaType::Set { .. } | SchemaType::Tuple { .. } => ARRAY,
+        SchemaType::Map { .. } => ARRAY | OBJECT,
+        SchemaType::Object { .. } => OBJECT,
+        SchemaType::Const { value } => type_bit(value),
+        _ => ANY_TYPE,
+    }
+}
+
+/// A union branch with its type pre-filter and position in the schema
+struct Branch {
+    index: usize,
+    types: u8,
+    node: NodeFn,
+}
+
+/// Compile union branches, cheapest first. Branch errors are reported in
+/// schema order, so the order only changes how soon a match is found.
+fn compile_branches(schemas: &[SchemaType], builder: &mut Builder) -> Vec<Branch> {
+    let mut ordered: Vec<(usize, &SchemaType)> = schemas.iter().enumerate().collect();
+    ordered.sort_by_key(|(_, schema)| schema.estimate_complexity());
+
+    ordered
+        .into_iter()
+        .map(|(index, schema)| Branch { index, types: accepted_types(schema), node: compile_node(schema, builder) })
+        .collect()
+}
+
+fn compile_any_of(branches: Vec<Branch>, enum_values: Vec<String>) -> NodeFn {
+    Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+        let value_type = type_bit(value);
+        let verbose = context.options.verbose_unions;
+        let mut matched = false;
+        let mut failures = Vec::new();
+
+        for branch in branches.iter().filter(|branch| verbose || branch.types & value_type != 0) {
+            let mut temp_context = context.branch();
+            let errors = (branch.node)(validator, value, &mut temp_context);
+            if errors.is_empty() {
+                // Every matching branch contributes evaluated keys when tracked
+                matched = true;
+                context.merge_evaluated(temp_context);
+                if !context.track_evaluated {
+                    break;
+                }
+            } else if !matched {
+                failures.push((branch.index, errors));
+            }
+        }
+
+        if matched {
+            Vec::new()
+        } else {
+            let suggested = enum_suggestion(value, &enum_values);
+            let summary = ValidationError::new(
+                context.path.build(),
+                "Value does not match any anyOf schemas".to_string(),
+                ErrorCode::AnyOfNoMatch,
+            )
+            .with_suggestion(suggested.map(|index| enum_values[index].as_str()));
+            union_failure(summary, failures, verbose, suggested)
+        }
+    })
+}
+
+fn compile_one_of(branches: Vec<Branch>, enum_values: Vec<String>) -> NodeFn {
+    Box::new(move |validator: &Validator, value: &Value, context: &mut ValidationContext| {
+        let value_type = type_bit(value);
+        let verbose = context.options.verbose_unions;
+        let mut valid_count = 0;
+        let mut failures = Vec::new();
+
+        for branch in branches.iter().filter(|branch| verbose || branch.types & value_type != 0) {
+            let mut temp_context = context.branch();
+            let errors = (branch.node)(validator, value, &mut temp_context);
+            if errors.is_empty() {
+                valid_count += 1;
+                context.merge_evaluated(temp_context);
+                // A second match already decides the outcome
+                if valid_count == 2 {
+                    break;
+                }
+            } else {
+                failures.push((branch.index, errors));
+            }
+        }
+
+        match valid_count {
+            0 => {
+                let suggested = enum_suggestion(value, &enum_values);
+                let summary = ValidationError::new(
+                    context.path.build(),
+                    "Value does not match any oneOf schemas".to_string(),
+                    ErrorCode::OneOfNoMatch,
+                )
+                .with_suggestion(suggested.map(|index| enum_values[index].as_str()));
+                union_failure(summary, failures, verbose, suggested)
+            }
+            1 => Vec::new(),
+            _ => vec![ValidationError::new(
+                context.path.build(),
+                "Value matches more than one oneOf schema, expected exactly 1".to_string(),
+                ErrorCode::OneOfMultipleMatches,
+            )],
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ValidationOptions;
+    use serde_json::json;
+
+    fn run_both(schema: &Value, data: &Value, options: ValidationOptions) -> (Vec<ValidationError>, Vec<ValidationError>) {
+        let validator = Validator::from_json(schema).unwrap();
+        let plan = std::sync::Arc::clone(&validator.compiled_schema.plan);
+        let schema = validator.compiled_schema.schema.clone();
+
+        let mut context = ValidationContext::new(options.clone());
+        let planned = plan.run(&validator, data, &mut context);
+        let mut context = ValidationContext::new(options);
+        let interpreted = validator.validate_value(data, &schema, &mut context);
+        (planned, interpreted)
+    }
+
+    #[test]
+    fn test_plan_matches_interpreter() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "format": "uuid"},
+                "name": {"type": "string", "minLength": 2, "maxLength": 5, "pattern": "^[a-z]+$"},
+                "age": {"type": "number", "min": 0, "integer": true},
+                "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 2, "uniqueItems": true},
+                "kind": {"type": "const", "const": "user"},
+                "flags": {"type": "object", "properties": {}, "catchall": {"type": "boolean"}}
+            },
+            "required": ["id", "name", "email"],
+            "additionalProperties": "strict"
+        });
+        let values = [
+            json!({"id": "550e8400-e29b-41d4-a716-446655440000", "name": "abc", "email": 1}),
+            json!({"id": "nope", "name": "ABCDEFG", "age": -1.5, "tags": ["a", "a", 3], "kind": "admin", "x": 1}),
+            json!({"flags": {"a": true, "b": "no"}, "name": 5}),
+            json!("not an object"),
+        ];
+
+        for data in &values {
+            for options in [
+                ValidationOptions::default(),
+                ValidationOptions { early_exit: true, ..Default::default() },
+                ValidationOptions { max_errors: Some(3), ..Default::default() },
+            ] {
+                let (planned, interpreted) = run_both(&schema, data, options);
+                assert_eq!(planned, interpreted, "data: {}", data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unions_short_circuit_by_cost_and_type() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "any": {"type": "anyof", "anyOf": [
+                    {"type": "object", "properties": {"a": {"type": "string"}}, "required": ["a"]},
+                    {"type": "number", "min": 0},
+                    {"type": "null"}
+                ]},
+                "one": {"type": "oneof", "oneOf": [
+                    {"type": "string", "minLength": 2},
+                    {"type": "string", "maxLength": 4},
+                    {"type": "string", "pattern": "^a"},
+                    {"type": "boolean"}
+                ]}
+            }
+        });
+        let values = [
+            json!({"any": {"a": "x"}, "one": "abcdef"}),
+            json!({"any": -1, "one": "x"}),
+            json!({"any": null, "one": "abc"}),
+            json!({"any": "nope", "one": true}),
+            json!({"one": 5}),
+        ];
+
+        for data in &values {
+            for options in [ValidationOptions::default(), ValidationOptions { verbose_unions: true, ..Default::default() }] {
+                let (planned, interpreted) = run_both(&schema, data, options);
+                assert_eq!(planned, interpreted, "data: {}", data);
+            }
+        }
+
+        let (planned, _) = run_both(&schema, &values[2], ValidationOptions::default());
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].code, ErrorCode::OneOfMultipleMatches);
+
+        let union: SchemaType = serde_json::from_value(schema["properties"]["any"].clone()).unwrap();
+        let plan = ValidationPlan::compile(&union);
+        assert_eq!(plan.interpreted_nodes(), 0);
+
+        assert_eq!(accepted_types(&SchemaType::Null), NULL);
+        assert_eq!(accepted_types(&SchemaType::Const { value: json!("x") }), STRING);
+        assert_eq!(accepted_types(&SchemaType::Any), ANY_TYPE);
+    }
+
+    #[test]
+    fn test_plan_delegates_unsupported_nodes() {
+        let schema: SchemaType = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "pattern": "^[a-z]+$"},
+                "contact": {"type": "allof", "allOf": [{"type": "string"}, {"type": "string", "minLength": 3}]},
+                "code": {"type": "string", "format": "sku"}
+            }
+        })).unwrap();
+
+        let plan = ValidationPlan::compile(&schema);
+        assert_eq!(plan.compiled_nodes(), 2);
+        assert_eq!(plan.interpreted_nodes(), 2);
+    }
+}
@@ -0,0 +1,254 @@
+
+//! Opt-in per-node profiling: time and hit counts for every schema node
+//! visited, arranged as a call tree so hot spots can be traced to the
+//! property or pattern responsible.
+
+use crate::schema::{SchemaType, StringFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// One schema node in the profile tree. `time_ms` includes the children.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileNode {
+    /// Data path with array indices collapsed to `[*]`
+    pub path: String,
+    /// Schema type, with the pattern or format for strings
+    pub schema: String,
+    pub hits: u64,
+    pub time_ms: f64,
+    /// Sorted by time, slowest first
+    pub children: Vec<ProfileNode>,
+}
+
+/// A schema node is identified by its address in the compiled schema
+/// (which outlives every validation) and the data path it was reached at
+type NodeKey = (usize, String);
+
+#[derive(Debug, Clone)]
+struct Entry {
+    path: String,
+    schema: String,
+    hits: u64,
+    time: Duration,
+    children: HashMap<NodeKey, usize>,
+}
+
+impl Entry {
+    fn new(path: String, schema: String) -> Self {
+        Self { path, schema, hits: 0, time: Duration::ZERO, children: HashMap::new() }
+    }
+}
+
+/// Accumulates the call tree of one or more validations
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// Arena of nodes; index 0 is a sentinel root
+    entries: Vec<Entry>,
+    /// Nodes currently being validated
+    stack: Vec<usize>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![Entry::new(String::new(), String::new())],
+            stack: vec![0],
+        }
+    }
+
+    /// Start timing `schema` at data `path`
+    pub fn enter(&mut self, schema: &SchemaType, path: &str) {
+        let parent = *self.stack.last().unwrap_or(&0);
+        let path = collapse_indices(path);
+        let key = (schema as *const SchemaType as usize, path);
+
+        let index = match self.entries[parent].children.get(&key) {
+            Some(index) => *index,
+            None => {
+                let index = self.entries.len();
+                self.entries.push(Entry::new(key.1.clone(), schema_label(schema)));
+                self.entries[parent].children.insert(key, index);
+                index
+            }
+        };
+        self.stack.push(index);
+    }
+
+    /// Stop timing the innermost node
+    pub fn exit(&mut self, elapsed: Duration) {
+        if self.stack.len() > 1 {
+            let index = self.stack.pop().unwrap_or_default();
+            let entry = &mut self.entries[index];
+            entry.hits += 1;
+            entry.time += elapsed;
+        }
+    }
+
+    /// Add another profile's counts into this one
+    pub fn merge(&mut self, other: &Profiler) {
+        self.merge_entry(0, other, 0);
+    }
+
+    fn merge_entry(&mut self, target: usize, other: &Profiler, source: usize) {
+        for (key, &other_index) in &other.entries[source].children {
+            let index = match self.entries[target].children.get(key) {
+                Some(index) => *index,
+                None => {
+                    let index = self.entries.len();
+                    let other_entry = &other.entries[other_index];
+                    self.entries.push(Entry::new(other_entry.path.clone(), other_entry.schema.clone()));
+                    self.entries[target].children.insert(key.clone(), index);
+                    index
+                }
+            };
+
+            let other_entry = &other.entries[other_index];
+            self.entries[index].hits += other_entry.hits;
+            self.entries[index].time += other_entry.time;
+            self.merge_entry(index, other, other_index);
+        }
+    }
+
+    /// Nodes visited per schema type, e.g. `{"string": 4, "object": 1}`
+    pub fn hits_by_type(&self) -> HashMap<String, u64> {
+        let mut counts = HashMap::new();
+        for entry in &self.entries[1..] {
+            let kind = entry.schema.split(' ').next().unwrap_or_default();
+            *counts.entry(kind.to_string()).or_insert(0) += entry.hits;
+        }
+        counts
+    }
+
+    /// The profile tree, or `None` if nothing was profiled
+    pub fn tree(&self) -> Option<ProfileNode> {
+        let roots = self.children_of(0);
+        match roots.len() {
+            0 => None,
+            1 => roots.into_iter().next(),
+            // Several validations with different root nodes cannot happen
+            // with one validator, but keep them all rather than pick one
+            _ => Some(ProfileNode {
+                path: String::new(),
+                schema: "root".to_string(),
+                hits: roots.iter().map(|node| node.hits).sum(),
+                time_ms: roots.iter().map(|node| node.time_ms).sum(),
+                children: roots,
+            }),
+        }
+    }
+
+    fn children_of(&self, index: usize) -> Vec<ProfileNode> {
+        let mut children: Vec<ProfileNode> = self.entries[index]
+            .children
+            .values()
+            .map(|&child| {
+                let entry = &self.entries[child];
+                ProfileNode {
+                    path: entry.path.clone(),
+                    schema: entry.schema.clone(),
+                    hits: entry.hits,
+                    time_ms: entry.time.as_secs_f64() * 1000.0,
+                    children: self.children_of(child),
+                }
+            })
+            .collect();
+        children.sort_by(|a, b| b.time_ms.total_cmp(&a.time_ms).then_with(|| a.path.cmp(&b.path)));
+        children
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `items.[3].tags.[0]` -> `items.[*].tags.[*]`, so array elements share a node
+fn collapse_indices(path: &str) -> String {
+    path.split('.')
+        .map(|segment| {
+            let is_index = segment.len() > 2
+                && segment.starts_with('[')
+                && segment.ends_with(']')
+                && segment[1..segment.len() - 1].bytes().all(|b| b.is_ascii_digit());
+            if is_index { "[*]" } else { segment }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn schema_label(schema: &SchemaType) -> String {
+    let schema = schema.unannotated();
+    if let SchemaType::String { pattern, format, .. } = schema {
+        if let Some(pattern) = pattern {
+            return format!("string /{}/", pattern);
+        }
+        if let Some(format) = format {
+            let name = match format {
+                StringFormat::Custom(name) => name.clone(),
+                format => serde_json::to_value(format)
+                    .ok()
+                    .and_then(|value| value.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+            };
+            return format!("string ({})", name);
+        }
+    }
+
+    // The serde tag is the schema's `type` keyword
+    match schema {
+        SchemaType::Object { .. } => "object".to_string(),
+        SchemaType::Array { .. } => "array".to_string(),
+        _ => serde_json::to_value(schema)
+            .ok()
+            .and_then(|value| value.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_profile_tree_and_merge() {
+        let schema: SchemaType = serde_json::from_value(json!({
+            "type": "array",
+            "items": {"type": "string", "pattern": "^a"}
+        })).unwrap();
+        let item = match &schema {
+            SchemaType::Array { items, .. } => items.as_ref(),
+            _ => unreachable!(),
+        };
+
+        let mut profiler = Profiler::new();
+        profiler.enter(&schema, "");
+        for index in 0..3 {
+            profiler.enter(item, &format!("[{}]", index));
+            profiler.exit(Duration::from_millis(1));
+        }
+        profiler.exit(Duration::from_millis(5));
+
+        let mut total = Profiler::new();
+        assert!(total.tree().is_none());
+        total.merge(&profiler);
+        total.merge(&profiler);
+
+        let tree = total.tree().unwrap();
+        assert_eq!((tree.schema.as_str(), tree.hits), ("array", 2));
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].path, "[*]");
+        assert_eq!(tree.children[0].schema, "string /^a/");
+        assert_eq!(tree.children[0].hits, 6);
+        assert!((tree.children[0].time_ms - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_collapse_indices() {
+        assert_eq!(collapse_indices("users.[12].tags.[0]"), "users.[*].tags.[*]");
+        assert_eq!(collapse_indices("[x]"), "[x]");
+        assert_eq!(collapse_indices(""), "");
+    }
+}
@@ -0,0 +1,466 @@
+
+//! Schemas for the JSON form of protobuf messages, read from an encoded
+//! `FileDescriptorSet` (`protoc --include_imports --descriptor_set_out`).
+//!
+//! Every message and enum becomes a schema named after its full name
+//! (`acme.v1.User`), and fields refer to them with `$ref`. The proto3 JSON
+//! mapping is followed: fields are keyed by their `json_name`, 64-bit
+//! integers may be quoted, `bytes` are base64, enums are names or numbers,
+//! and the well-known types (`Timestamp`, `Struct`, the wrappers, ...) take
+//! their special JSON forms.
+
+use crate::builder::{Schema, SchemaBuilder};
+use crate::error::FastSchemaError;
+use crate::schema::{ObjectRule, SchemaType, StringFormat};
+use std::collections::HashMap;
+
+const LABEL_REQUIRED: u64 = 2;
+const LABEL_REPEATED: u64 = 3;
+
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_ENUM: u64 = 14;
+
+/// Standard or URL-safe base64, padded or not, as proto3 JSON parsers accept
+const BASE64_PATTERN: &str = "^[A-Za-z0-9+/_-]*={0,2}$";
+
+/// A field of an encoded message
+enum Wire<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+#[derive(Default)]
+struct Message {
+    name: String,
+    fields: Vec<Field>,
+    nested: Vec<Message>,
+    enums: Vec<Enum>,
+    oneofs: usize,
+    map_entry: bool,
+}
+
+#[derive(Default)]
+struct Field {
+    name: String,
+    label: u64,
+    kind: u64,
+    type_name: String,
+    oneof_index: Option<usize>,
+    json_name: Option<String>,
+    proto3_optional: bool,
+}
+
+#[derive(Default)]
+struct Enum {
+    name: String,
+    values: Vec<(String, i64)>,
+}
+
+/// Schemas for every message and enum in a `FileDescriptorSet`, by full name
+pub(crate) fn descriptor_set_schemas(bytes: &[u8]) -> Result<Vec<(String, SchemaType)>, FastSchemaError> {
+    let mut messages = Vec::new();
+    let mut enums = Vec::new();
+    for (number, value) in fields(bytes)? {
+        if let (1, Wire::Bytes(file)) = (number, value) {
+            let mut package = String::new();
+            let mut file_messages = Vec::new();
+            let mut file_enums = Vec::new();
+            for (number, value) in fields(file)? {
+                match (number, value) {
+                    (2, Wire::Bytes(name)) => package = text(name)?,
+                    (4, Wire::Bytes(message)) => file_messages.push(parse_message(message)?),
+                    (5, Wire::Bytes(descriptor)) => file_enums.push(parse_enum(descriptor)?),
+                    _ => {}
+                }
+            }
+            let prefix = if package.is_empty() { String::new() } else { format!("{}.", package) };
+            for message in file_messages {
+                flatten(message, &prefix, &mut messages, &mut enums);
+            }
+            enums.extend(file_enums.into_iter().map(|descriptor| (format!("{}{}", prefix, descriptor.name), descriptor)));
+        }
+    }
+
+    let map_entries: HashMap<&str, &Message> = messages
+        .iter()
+        .filter(|(_, message)| message.map_entry)
+        .map(|(name, message)| (name.as_str(), message))
+        .collect();
+
+    let mut schemas = Vec::new();
+    for (name, message) in messages.iter().filter(|(_, message)| !message.map_entry) {
+        schemas.push((name.clone(), message_schema(message, &map_entries)?));
+    }
+    for (name, descriptor) in &enums {
+        let options = descriptor
+            .values
+            .iter()
+            .flat_map(|(value, number)| vec![Schema::literal(value.as_str()), Schema::literal(*number)]);
+        schemas.push((name.clone(), Schema::union(options).build()?));
+    }
+    Ok(schemas)
+}
+
+/// Collect a message and its nested types under their full names
+fn flatten(mut message: Message, prefix: &str, messages: &mut Vec<(String, Message)>, enums: &mut Vec<(String, Enum)>) {
+    let name = format!("{}{}", prefix, message.name);
+    let nested_prefix = format!("{}.", name);
+    for nested in std::mem::take(&mut message.nested) {
+        flatten(nested, &nested_prefix, messages, enums);
+    }
+    for descriptor in std::mem::take(&mut message.enums) {
+        enums.push((format!("{}{}", nested_prefix, descriptor.name), descriptor));
+    }
+    messages.push((name, message));
+}
+
+fn message_schema(message: &Message, map_entries: &HashMap<&str, &Message>) -> Result<SchemaType, FastSchemaError> {
+    let mut object = Schema::object().strict();
+    let mut oneofs = vec![Vec::new(); message.oneofs];
+    for field in &message.fields {
+        let key = field.json_name.clone().unwrap_or_else(|| json_name(&field.name));
+        let mut schema = field_schema(field, map_entries);
+        if field.label != LABEL_REQUIRED {
+            schema = schema.optional();
+        }
+        // proto3 `optional` fields sit alone in a synthetic oneof
+        match field.oneof_index {
+            Some(index) if !field.proto3_optional && index < oneofs.len() => oneofs[index].push(key.clone()),
+            _ => {}
+        }
+        object = object.prop(key, schema);
+    }
+
+    let mut schema = object.build()?;
+    let rules: Vec<ObjectRule> = oneofs
+        .into_iter()
+        .filter(|fields| fields.len() > 1)
+        .map(|fields| ObjectRule::MutuallyExclusive { fields })
+        .collect();
+    if let SchemaType::Object { rules: slot, .. } = &mut schema {
+        if !rules.is_empty() {
+            *slot = Some(rules);
+        }
+    }
+    Ok(schema)
+}
+
+fn field_schema(field: &Field, map_entries: &HashMap<&str, &Message>) -> SchemaBuilder {
+    if field.label != LABEL_REPEATED {
+        return scalar(field.kind, &field.type_name);
+    }
+    // Map fields are repeated entry messages, written as JSON objects
+    match map_entries.get(field.type_name.trim_start_matches('.')) {
+        Some(entry) if field.kind == TYPE_MESSAGE => {
+            let values = entry.fields.iter().find(|field| field.name == "value");
+            let values = values.map_or_else(Schema::any, |value| scalar(value.kind, &value.type_name));
+            Schema::object().catchall(values)
+        }
+        _ => Schema::array(scalar(field.kind, &field.type_name)),
+    }
+}
+
+/// The schema for one value of a field type
+fn scalar(kind: u64, type_name: &str) -> SchemaBuilder {
+    match kind {
+        // double, float
+        1 | 2 => Schema::union(vec![
+            Schema::number(),
+            Schema::literal("NaN"),
+            Schema::literal("Infinity"),
+            Schema::literal("-Infinity"),
+        ]),
+        // int64, sfixed64, sint64
+        3 | 16 | 18 => Schema::union(vec![Schema::integer(), Schema::string().regex("^-?[0-9]+$")]),
+        // uint64, fixed64
+        4 | 6 => Schema::union(vec![Schema::integer().min(0), Schema::string().regex("^[0-9]+$")]),
+        // int32, sfixed32, sint32
+        5 | 15 | 17 => Schema::integer().min(i32::MIN).max(i32::MAX),
+        // fixed32, uint32
+        7 | 13 => Schema::integer().min(0).max(u32::MAX),
+        8 => Schema::boolean(),
+        9 => Schema::string(),
+        12 => Schema::string().regex(BASE64_PATTERN),
+        // group, message, enum
+        10 | TYPE_MESSAGE | TYPE_ENUM => {
+            let name = type_name.trim_start_matches('.');
+            well_known(name).unwrap_or_else(|| {
+                SchemaBuilder::from_schema(SchemaType::Ref { reference: name.to_string() })
+            })
+        }
+        _ => Schema::any(),
+    }
+}
+
+/// The special JSON forms of `google.protobuf` types
+fn well_known(name: &str) -> Option<SchemaBuilder> {
+    Some(match name.strip_prefix("google.protobuf.")? {
+        "Timestamp" => Schema::string().format(StringFormat::DateTime),
+        "Duration" => Schema::string().regex("^-?[0-9]+(\\.[0-9]{1,9})?s$"),
+        "FieldMask" => Schema::string(),
+        "Struct" => Schema::object().passthrough(),
+        "Value" => Schema::any(),
+        "ListValue" => Schema::array(Schema::any()),
+        "NullValue" => Schema::null(),
+        "Empty" => Schema::object().strict(),
+        "Any" => Schema::object().prop("@type", Schema::string()).passthrough(),
+        "DoubleValue" => scalar(1, ""),
+        "FloatValue" => scalar(2, ""),
+        "Int64Value" => scalar(3, ""),
+        "UInt64Value" => scalar(4, ""),
+        "Int32Value" => scalar(5, ""),
+        "UInt32Value" => scalar(13, ""),
+        "BoolValue" => scalar(8, ""),
+        "StringValue" => scalar(9, ""),
+        "BytesValue" => scalar(12, ""),
+        _ => return None,
+    })
+}
+
+/// protoc's default `json_name`: lowerCamelCase of the field name
+fn json_name(name: &str) -> String {
+    let mut json_name = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            json_name.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            json_name.push(c);
+        }
+    }
+    json_name
+}
+
+fn parse_message(bytes: &[u8]) -> Result<Message, FastSchemaError> {
+    let mut message = Message::default();
+    for (number, value) in fields(bytes)? {
+        match (number, value) {
+            (1, Wire::Bytes(name)) => message.name = text(name)?,
+            (2, Wire::Bytes(field)) => message.fields.push(parse_field(field)?),
+            (3, Wire::Bytes(nested)) => message.nested.push(parse_message(nested)?),
+            (4, Wire::Bytes(descriptor)) => message.enums.push(parse_enum(descriptor)?),
+            (7, Wire::Bytes(options)) => {
+                for (number, value) in fields(options)? {
+                    if let (7, Wire::Varint(map_entry)) = (number, value) {
+                        message.map_entry = map_entry != 0;
+                    }
+                }
+            }
+            (8, Wire::Bytes(_)) => message.oneofs += 1,
+            _ => {}
+        }
+    }
+    Ok(message)
+}
+
+fn parse_field(bytes: &[u8]) -> Result<Field, FastSchemaError> {
+    let mut field = Field::default();
+    for (number, value) in fields(bytes)? {
+        match (number, value) {
+            (1, Wire::Bytes(name)) => field.name = text(name)?,
+            (4, Wire::Varint(label)) => field.label = label,
+            (5, Wire::Varint(kind)) => field.kind = kind,
+            (6, Wire::Bytes(name)) => field.type_name = text(name)?,
+            (9, Wire::Varint(index)) => field.oneof_index = Some(index as usize),
+            (10, Wire::Bytes(name)) => field.json_name = Some(text(name)?),
+            (17, Wire::Varint(optional)) => field.proto3_optional = optional != 0,
+            _ => {}
+        }
+    }
+    Ok(field)
+}
+
+fn parse_enum(bytes: &[u8]) -> Result<Enum, FastSchemaError> {
+    let mut descriptor = Enum::default();
+    for (number, value) in fields(bytes)? {
+        match (number, value) {
+            (1, Wire::Bytes(name)) => descriptor.name = text(name)?,
+            (2, Wire::Bytes(value)) => {
+                let mut name = String::new();
+                let mut value_number = 0;
+                for (number, value) in fields(value)? {
+                    match (number, value) {
+                        (1, Wire::Bytes(bytes)) => name = text(bytes)?,
+                        // int32, sign-extended to 64 bits on the wire
+                        (2, Wire::Varint(number)) => value_number = number as i64 as i32 as i64,
+                        _ => {}
+                    }
+                }
+                descriptor.values.push((name, value_number));
+            }
+            _ => {}
+        }
+    }
+    Ok(descriptor)
+}
+
+/// Split an encoded message into its fields
+fn fields(bytes: &[u8]) -> Result<Vec<(u64, Wire<'_>)>, FastSchemaError> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let key = varint(bytes, &mut pos)?;
+        let value = match key & 7 {
+            0 => Wire::Varint(varint(bytes, &mut pos)?),
+            1 | 5 => {
+                pos += if key & 7 == 1 { 8 } else { 4 };
+                Wire::Fixed
+            }
+            2 => {
+                let len = varint(bytes, &mut pos)? as usize;
+                let value = bytes.get(pos..pos.saturating_add(len)).ok_or_else(|| invalid("truncated field"))?;
+                pos += len;
+                Wire::Bytes(value)
+            }
+            wire_type => return Err(invalid(&format!("unsupported wire type {}", wire_type))),
+        };
+        fields.push((key >> 3, value));
+    }
+    if pos > bytes.len() {
+        return Err(invalid("truncated field"));
+    }
+    Ok(fields)
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> Result<u64, FastSchemaError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or_else(|| invalid("truncated varint"))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid("varint is too long"))
+}
+
+fn text(bytes: &[u8]) -> Result<String, FastSchemaError> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid("a name is not valid UTF-8"))
+}
+
+fn invalid(message: &str) -> FastSchemaError {
+    FastSchemaError::SchemaCompilation(format!("Invalid FileDescriptorSet: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::SchemaRegistry;
+    use serde_json::json;
+
+    enum Value<'a> {
+        Int(u64),
+        Str(&'a str),
+        Message(Vec<u8>),
+    }
+
+    /// Encode `(field number, value)` pairs
+    fn encode(fields: &[(u64, Value)]) -> Vec<u8> {
+        fn varint(mut value: u64, out: &mut Vec<u8>) {
+            while value >= 0x80 {
+                out.push(value as u8 | 0x80);
+                value >>= 7;
+            }
+            out.push(value as u8);
+        }
+        let mut out = Vec::new();
+        for (number, value) in fields {
+            let bytes = match value {
+                Value::Int(value) => {
+                    varint(number << 3, &mut out);
+                    varint(*value, &mut out);
+                    continue;
+                }
+                Value::Str(text) => text.as_bytes(),
+                Value::Message(bytes) => bytes.as_slice(),
+            };
+            varint(number << 3 | 2, &mut out);
+            varint(bytes.len() as u64, &mut out);
+            out.extend_from_slice(bytes);
+        }
+        out
+    }
+
+    fn field(name: &str, label: u64, kind: u64, type_name: &str, extra: &[(u64, Value)]) -> Value<'static> {
+        let mut fields = vec![(1, Value::Str(name)), (4, Value::Int(label)), (5, Value::Int(kind))];
+        if !type_name.is_empty() {
+            fields.push((6, Value::Str(type_name)));
+        }
+        let mut bytes = encode(&fields);
+        bytes.extend(encode(extra));
+        Value::Message(bytes)
+    }
+
+    #[test]
+    fn test_descriptor_set_schemas() {
+        // package acme;
+        // message User {
+        //   string user_name = 1;
+        //   repeated int64 ids = 2;
+        //   Role role = 3;
+        //   map<string, User> friends = 4;
+        //   google.protobuf.Timestamp created_at = 5;
+        //   oneof contact { string email = 6; string phone = 7; }
+        //   enum Role { GUEST = 0; ADMIN = 1; }
+        // }
+        let entry = encode(&[
+            (1, Value::Str("FriendsEntry")),
+            (2, field("key", 1, 9, "", &[])),
+            (2, field("value", 1, 11, ".acme.User", &[])),
+            (7, Value::Message(encode(&[(7, Value::Int(1))]))),
+        ]);
+        let role = encode(&[
+            (1, Value::Str("Role")),
+            (2, Value::Message(encode(&[(1, Value::Str("GUEST")), (2, Value::Int(0))]))),
+            (2, Value::Message(encode(&[(1, Value::Str("ADMIN")), (2, Value::Int(1))]))),
+        ]);
+        let user = encode(&[
+            (1, Value::Str("User")),
+            (2, field("user_name", 1, 9, "", &[(10, Value::Str("userName"))])),
+            (2, field("ids", 3, 3, "", &[])),
+            (2, field("role", 1, 14, ".acme.User.Role", &[])),
+            (2, field("friends", 3, 11, ".acme.User.FriendsEntry", &[])),
+            (2, field("created_at", 1, 11, ".google.protobuf.Timestamp", &[])),
+            (2, field("email", 1, 9, "", &[(9, Value::Int(0))])),
+            (2, field("phone", 1, 9, "", &[(9, Value::Int(0))])),
+            (3, Value::Message(entry)),
+            (4, Value::Message(role)),
+            (8, Value::Message(encode(&[(1, Value::Str("contact"))]))),
+        ]);
+        let file = encode(&[(1, Value::Str("acme/user.proto")), (2, Value::Str("acme")), (4, Value::Message(user))]);
+        let descriptor_set = encode(&[(1, Value::Message(file))]);
+
+        let mut registry = SchemaRegistry::new();
+        let mut names = registry.register_descriptor_set(&descriptor_set).unwrap();
+        names.sort();
+        assert_eq!(names, vec!["acme.User", "acme.User.Role"]);
+
+        let validator = registry.validator("acme.User").unwrap();
+        let user = json!({
+            "userName": "ann",
+            "ids": [1, "9007199254740993"],
+            "role": "ADMIN",
+            "friends": {"bob": {"role": 0, "createdAt": "2024-01-02T03:04:05Z"}},
+            "email": "ann@example.com"
+        });
+        assert!(validator.validate(&user).success, "{:?}", validator.validate(&user).errors);
+
+        let result = validator.validate(&json!({
+            "user_name": "ann",
+            "ids": ["1.5"],
+            "role": "OWNER",
+            "friends": {"bob": {"createdAt": "yesterday"}},
+            "email": "ann@example.com",
+            "phone": "555"
+        }));
+        let mut paths: Vec<String> = result.errors.iter().map(|error| error.path.to_string()).collect();
+        paths.sort();
+        // Unions report each branch's error
+        paths.dedup();
+        assert_eq!(paths, vec!["friends.bob.createdAt", "ids.[0]", "phone", "role", "user_name"]);
+    }
+}
@@ -0,0 +1,258 @@
+
+//! Process-wide LRU cache of compiled regexes, shared by every validator
+//! so schemas compiled repeatedly don't recompile their patterns.
+//! Validators resolve their patterns once when they are built, so the
+//! cache is only consulted while compiling.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Patterns kept by default before the least recently used is evicted
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+static REGEX_CACHE: Lazy<Mutex<RegexCache>> = Lazy::new(|| Mutex::new(RegexCache::new(DEFAULT_CAPACITY)));
+
+/// Sentinel link for the ends of the recency list
+const NIL: usize = usize::MAX;
+
+/// A cached pattern, linked into the recency list by slot index
+#[derive(Debug)]
+struct Slot {
+    pattern: String,
+    regex: Arc<Regex>,
+    /// Neighbours towards the most and least recently used ends
+    newer: usize,
+    older: usize,
+}
+
+/// LRU map from pattern source to compiled regex. Slots form a doubly
+/// linked list from most to least recently used, so lookups, inserts and
+/// evictions are O(1).
+#[derive(Debug)]
+struct RegexCache {
+    capacity: usize,
+    entries: HashMap<String, usize>,
+    slots: Vec<Slot>,
+    /// Slots of removed entries, reused by later inserts
+    free: Vec<usize>,
+    newest: usize,
+    oldest: usize,
+    /// Lookups that found or missed their pattern since the last reset
+    hits: u64,
+    misses: u64,
+}
+
+impl RegexCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            newest: NIL,
+            oldest: NIL,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn get(&mut self, pattern: &str) -> Option<Arc<Regex>> {
+        match self.entries.get(pattern).copied() {
+            Some(slot) => {
+                self.hits += 1;
+                self.unlink(slot);
+                self.link_newest(slot);
+                Some(Arc::clone(&self.slots[slot].regex))
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, pattern: &str, regex: Arc<Regex>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(slot) = self.entries.get(pattern).copied() {
+            self.slots[slot].regex = regex;
+            self.unlink(slot);
+            self.link_newest(slot);
+            return;
+        }
+        self.evict(self.capacity - 1);
+
+        let entry = Slot { pattern: pattern.to_string(), regex, newer: NIL, older: NIL };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot] = entry;
+                slot
+            }
+            None => {
+                self.slots.push(entry);
+                self.slots.len() - 1
+            }
+        };
+        self.entries.insert(pattern.to_string(), slot);
+        self.link_newest(slot);
+    }
+
+    /// Drop least recently used entries until at most `size` remain
+    fn evict(&mut self, size: usize) {
+        while self.entries.len() > size && self.oldest != NIL {
+            let pattern = self.slots[self.oldest].pattern.clone();
+            self.remove(&pattern);
+        }
+    }
+
+    fn remove(&mut self, pattern: &str) {
+        if let Some(slot) = self.entries.remove(pattern) {
+            self.unlink(slot);
+            self.free.push(slot);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.slots.clear();
+        self.free.clear();
+        self.newest = NIL;
+        self.oldest = NIL;
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (newer, older) = (self.slots[slot].newer, self.slots[slot].older);
+        match newer {
+            NIL => self.newest = older,
+            newer => self.slots[newer].older = older,
+        }
+        match older {
+            NIL => self.oldest = newer,
+            older => self.slots[older].newer = newer,
+        }
+    }
+
+    fn link_newest(&mut self, slot: usize) {
+        self.slots[slot].newer = NIL;
+        self.slots[slot].older = self.newest;
+        match self.newest {
+            NIL => self.oldest = slot,
+            newest => self.slots[newest].newer = slot,
+        }
+        self.newest = slot;
+    }
+}
+
+fn cache() -> MutexGuard<'static, RegexCache> {
+    // The cache holds no invariants a panicking thread could break
+    REGEX_CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Get a compiled regex for `pattern`, compiling and caching it on a miss
+pub fn get_or_compile(pattern: &str) -> Result<Arc<Regex>, regex::Error> {
+    if let Some(regex) = cache().get(pattern) {
+        return Ok(regex);
+    }
+
+    // Compile without holding the lock; a racing thread may insert the
+    // same pattern, which simply replaces an equivalent entry
+    let regex = Arc::new(Regex::new(pattern)?);
+    cache().insert(pattern, Arc::clone(&regex));
+    Ok(regex)
+}
+
+/// Whether `pattern` is currently cached (does not affect recency)
+#[cfg(test)]
+pub fn contains(pattern: &str) -> bool {
+    cache().entries.contains_key(pattern)
+}
+
+/// Number of cached patterns
+pub fn len() -> usize {
+    cache().entries.len()
+}
+
+pub fn capacity() -> usize {
+    cache().capacity
+}
+
+/// Change the capacity, evicting least recently used patterns if the
+/// cache is over the new limit. A capacity of 0 disables caching.
+pub fn set_capacity(capacity: usize) {
+    let mut cache = cache();
+    cache.capacity = capacity;
+    cache.evict(capacity);
+}
+
+pub fn clear() {
+    cache().clear();
+}
+
+/// Drop `patterns` from the cache, keeping everything else
+pub fn remove<'a>(patterns: impl IntoIterator<Item = &'a str>) {
+    let mut cache = cache();
+    for pattern in patterns {
+        cache.remove(pattern);
+    }
+}
+
+/// Lookups that hit and missed since the counters were last reset
+pub fn hit_stats() -> (u64, u64) {
+    let cache = cache();
+    (cache.hits, cache.misses)
+}
+
+pub fn reset_hit_stats() {
+    let mut cache = cache();
+    cache.hits = 0;
+    cache.misses = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut cache = RegexCache::new(2);
+        cache.insert("a", Arc::new(Regex::new("a").unwrap()));
+        cache.insert("b", Arc::new(Regex::new("b").unwrap()));
+        assert!(cache.get("a").is_some()); // "b" is now least recently used
+
+        cache.insert("c", Arc::new(Regex::new("c").unwrap()));
+        assert_eq!(cache.entries.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!((cache.hits, cache.misses), (3, 1));
+
+        // Removed slots are reused and keep the recency order intact
+        cache.remove("a");
+        cache.insert("d", Arc::new(Regex::new("d").unwrap()));
+        cache.insert("e", Arc::new(Regex::new("e").unwrap()));
+        assert_eq!(cache.slots.len(), 2);
+        assert!(cache.get("c").is_none());
+        assert!(cache.get("d").is_some());
+        cache.evict(1);
+        assert!(cache.get("e").is_none());
+        assert!(cache.get("d").is_some());
+
+        cache.capacity = 0;
+        cache.evict(0);
+        cache.insert("f", Arc::new(Regex::new("f").unwrap()));
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn test_shared_cache() {
+        let first = get_or_compile(r"^shared-\d+$").unwrap();
+        let second = get_or_compile(r"^shared-\d+$").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(contains(r"^shared-\d+$"));
+        assert!(get_or_compile("(").is_err());
+        assert!(!contains("("));
+    }
+}
@@ -0,0 +1,251 @@
+
+use crate::error::{FastSchemaError, ValidationResult};
+use crate::schema::SchemaType;
+use crate::validator::Validator;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Named schemas that can reference each other with `{"type": "ref", "$ref": "<name>"}`
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: HashMap<String, SchemaType>,
+    /// Shared with validators; rebuilt lazily after registration
+    definitions: Option<Arc<HashMap<String, SchemaType>>>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) a schema under a name
+    pub fn register(&mut self, name: &str, schema: SchemaType) {
+        self.schemas.insert(name.to_string(), schema);
+        self.definitions = None;
+    }
+
+    /// Register a schema document, keeping its annotations
+    pub fn register_json(&mut self, name: &str, schema_json: &serde_json::Value) -> Result<(), FastSchemaError> {
+        let schema: SchemaType = serde_json::from_value(schema_json.clone())?;
+        self.register(name, schema);
+        Ok(())
+    }
+
+    /// Register every message and enum of an encoded protobuf
+    /// `FileDescriptorSet` under its full name, returning the names
+    pub fn register_descriptor_set(&mut self, bytes: &[u8]) -> Result<Vec<String>, FastSchemaError> {
+        let schemas = crate::protobuf::descriptor_set_schemas(bytes)?;
+        let names = schemas.iter().map(|(name, _)| name.clone()).collect();
+        for (name, schema) in schemas {
+            self.register(&name, schema);
+        }
+        Ok(names)
+    }
+
+    /// Register an Avro schema under a name, and each record, enum and
+    /// fixed type it declares under its full name
+    pub fn register_avro(&mut self, name: &str, schema_json: &serde_json::Value) -> Result<(), FastSchemaError> {
+        let (schema, named) = crate::avro::avro_schemas(schema_json)?;
+        for (type_name, schema) in named {
+            self.register(&type_name, schema);
+        }
+        // A named root is already registered under its own name
+        if !matches!(&schema, SchemaType::Ref { reference } if reference == name) {
+            self.register(name, schema);
+        }
+        Ok(())
+    }
+
+    /// Remove a schema, returning it if it was registered
+    pub fn unregister(&mut self, name: &str) -> Option<SchemaType> {
+        self.definitions = None;
+        self.schemas.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SchemaType> {
+        self.schemas.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.schemas.contains_key(name)
+    }
+
+    /// Registered schema names, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.schemas.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// References that do not resolve to a registered schema, as `(schema, reference)` pairs
+    pub fn unresolved_references(&self) -> Vec<(String, String)> {
+        let mut unresolved: Vec<(String, String)> = self.schemas
+            .iter()
+            .flat_map(|(name, schema)| {
+                schema.references()
+                    .into_iter()
+                    .filter(|reference| !self.schemas.contains_key(*reference))
+                    .map(move |reference| (name.clone(), reference.to_string()))
+            })
+            .collect();
+        unresolved.sort();
+        unresolved.dedup();
+        unresolved
+    }
+
+    /// Create a validator for a registered schema, resolving references
+    /// against every schema in the registry
+    pub fn validator(&mut self, name: &str) -> Result<Validator, FastSchemaError> {
+        let schema = self.schemas.get(name).cloned().ok_or_else(|| {
+            FastSchemaError::SchemaCompilation(format!("Schema '{}' is not registered", name))
+        })?;
+
+        if let Some((from, reference)) = self.unresolved_references().into_iter().next() {
+            return Err(FastSchemaError::SchemaCompilation(format!(
+                "Schema '{}' references unregistered schema '{}'",
+                from, reference
+            )));
+        }
+
+        let schemas = &self.schemas;
+        let definitions = Arc::clone(
+            self.definitions.get_or_insert_with(|| Arc::new(schemas.clone()))
+        );
+        Validator::new(schema)?.with_definitions(definitions)
+    }
+}
+
+/// Many named schemas behind one object, each compiled on first use
+#[derive(Clone, Default)]
+pub struct ValidatorSet {
+    registry: SchemaRegistry,
+    /// Cleared whenever a schema changes, since any of them may be referenced
+    validators: HashMap<String, Validator>,
+}
+
+impl ValidatorSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or replace) a named schema document
+    pub fn add_json(&mut self, name: &str, schema_json: &serde_json::Value) -> Result<(), FastSchemaError> {
+        self.registry.register_json(name, schema_json)?;
+        self.validators.clear();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.validators.clear();
+        self.registry.unregister(name).is_some()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.registry.names()
+    }
+
+    /// The validator for a named schema, compiling it if needed
+    pub fn validator(&mut self, name: &str) -> Result<&Validator, FastSchemaError> {
+        if !self.validators.contains_key(name) {
+            let validator = self.registry.validator(name)?;
+            self.validators.insert(name.to_string(), validator);
+        }
+        Ok(&self.validators[name])
+    }
+
+    /// Validate `data` against the schema registered as `name`
+    pub fn validate_as(&mut self, name: &str, data: &serde_json::Value) -> Result<ValidationResult, FastSchemaError> {
+        Ok(self.validator(name)?.validate(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use serde_json::json;
+
+    #[test]
+    fn test_cross_schema_references() {
+        let mut registry = SchemaRegistry::new();
+        registry.register_json("Address", &json!({
+            "type": "object",
+            "properties": {"city": {"type": "string"}},
+            "required": ["city"]
+        })).unwrap();
+        registry.register_json("User", &json!({
+            "type": "object",
+            "properties": {
+                "address": {"type": "ref", "$ref": "Address"},
+                "friends": {"type": "array", "items": {"type": "ref", "$ref": "User"}}
+            }
+        })).unwrap();
+
+        let validator = registry.validator("User").unwrap();
+
+        assert!(validator.validate(&json!({
+            "address": {"city": "Oslo"},
+            "friends": [{"address": {"city": "Bergen"}}]
+        })).success);
+
+        let result = validator.validate(&json!({"friends": [{"address": {}}]}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectMissingProperty);
+        assert_eq!(result.errors[0].path, "friends.[0].address.city");
+    }
+
+    #[test]
+    fn test_validator_set() {
+        let mut set = ValidatorSet::new();
+        set.add_json("Tag", &json!({"type": "string", "minLength": 1})).unwrap();
+        set.add_json("Post", &json!({
+            "type": "object",
+            "properties": {"tags": {"type": "array", "items": {"type": "ref", "$ref": "Tag"}}}
+        })).unwrap();
+
+        assert_eq!(set.names(), vec!["Post", "Tag"]);
+        assert!(set.validate_as("Tag", &json!("news")).unwrap().success);
+        assert!(!set.validate_as("Post", &json!({"tags": [""]})).unwrap().success);
+
+        // Replacing a schema recompiles the ones referencing it
+        set.add_json("Tag", &json!({"type": "number"})).unwrap();
+        assert!(set.validate_as("Post", &json!({"tags": [1]})).unwrap().success);
+
+        assert!(set.remove("Tag"));
+        assert!(set.validate_as("Tag", &json!(1)).is_err());
+        assert!(set.validate_as("Post", &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_unresolved_references() {
+        let mut registry = SchemaRegistry::new();
+        registry.register_json("Order", &json!({"type": "ref", "$ref": "Customer"})).unwrap();
+
+        assert_eq!(
+            registry.unresolved_references(),
+            vec![("Order".to_string(), "Customer".to_string())]
+        );
+        assert!(registry.validator("Order").is_err());
+        assert!(registry.validator("Missing").is_err());
+    }
+    #[test]
+    fn test_reference_cycles() {
+        let mut registry = SchemaRegistry::new();
+        registry.register_json("A", &json!({"type": "ref", "$ref": "B"})).unwrap();
+        registry.register_json("B", &json!({"anyOf": [{"type": "string"}, {"type": "ref", "$ref": "A"}]})).unwrap();
+        let error = registry.validator("A").err().unwrap();
+        assert!(error.to_string().contains("B -> A -> B"), "{}", error);
+
+        // A cycle through a property consumes input on each step
+        registry.register_json("B", &json!({"type": "object", "properties": {"next": {"type": "ref", "$ref": "A"}}})).unwrap();
+        let validator = registry.validator("A").unwrap();
+        assert!(validator.validate(&json!({"next": {"next": {}}})).success);
+        assert!(!validator.validate(&json!(1)).success);
+
+        // A validator whose definitions bypassed the check still stops
+        let mut validator = Validator::new(SchemaType::Ref { reference: "A".to_string() }).unwrap();
+        validator.definitions = Arc::new(HashMap::from([("A".to_string(), SchemaType::Ref { reference: "A".to_string() })]));
+        assert_eq!(validator.validate(&json!(1)).errors[0].code, ErrorCode::LimitExceeded);
+        let paths = crate::utils::ValidationOptions { paths: Some(vec!["a".to_string()]), ..Default::default() };
+        assert!(!validator.validate_with_options(&json!({"a": 1}), paths).success);
+    }
+}
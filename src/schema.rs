@@ -1,11 +1,16 @@
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use crate::error::{ErrorPath, FastSchemaError, PathSegment, ValidationError};
+use crate::plan::ValidationPlan;
 use crate::html::{HtmlElementType, HtmlProps, ReactComponent};
 
 /// Core schema types supported by FastSchema
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "lowercase")]
+#[serde(remote = "Self", tag = "type", rename_all = "lowercase")]
 pub enum SchemaType {
     String {
         #[serde(rename = "minLength")]
@@ -14,13 +19,81 @@ pub enum SchemaType {
         max_length: Option<usize>,
         pattern: Option<String>,
         format: Option<StringFormat>,
+        /// Encoding of the string content, decoded before media type checks
+        #[serde(rename = "contentEncoding")]
+        content_encoding: Option<ContentEncoding>,
+        /// Media type of the (decoded) content, e.g. `application/json`
+        #[serde(rename = "contentMediaType")]
+        content_media_type: Option<String>,
+        /// Schema for parsed JSON content
+        #[serde(rename = "contentSchema")]
+        content_schema: Option<Box<SchemaType>>,
+        /// ISO 3166-1 alpha-2 region for `phone-number` / `postal-code` formats
+        country: Option<String>,
+        /// Required UUID version (1-8); implies a UUID-shaped string
+        #[serde(rename = "uuidVersion")]
+        uuid_version: Option<u8>,
+        /// Switches the `email` format to strict RFC 5321/5322 checking
+        #[serde(rename = "emailOptions")]
+        email_options: Option<EmailOptions>,
+        /// Earliest accepted `date-time` / `date`, as an RFC 3339 date-time or full date
+        #[serde(rename = "minDate")]
+        min_date: Option<String>,
+        /// Latest accepted `date-time` / `date`
+        #[serde(rename = "maxDate")]
+        max_date: Option<String>,
+        /// Reject `date-time` values without `Z` or a `±HH:MM` offset
+        #[serde(default, rename = "offsetRequired")]
+        offset_required: bool,
+        /// Exact number of fractional second digits for `date-time`
+        precision: Option<usize>,
+        /// Accept Unicode labels for `hostname` / `fqdn`
+        #[serde(default, rename = "allowIdn")]
+        allow_idn: bool,
+        /// What `minLength` / `maxLength` count
+        #[serde(default, rename = "lengthUnit")]
+        length_unit: LengthUnit,
     },
     Number {
         min: Option<f64>,
         max: Option<f64>,
+        /// The number must be greater than this
+        #[serde(rename = "exclusiveMin")]
+        exclusive_min: Option<f64>,
+        /// The number must be less than this
+        #[serde(rename = "exclusiveMax")]
+        exclusive_max: Option<f64>,
         #[serde(default)]
         integer: bool,
         multiple_of: Option<f64>,
+        /// Also accept `"NaN"`, `"Infinity"` and `"-Infinity"` strings
+        #[serde(default, rename = "allowNonFinite")]
+        allow_non_finite: bool,
+        /// With `integer`, reject integral numbers written as floats (`1.0`)
+        #[serde(default, rename = "strictInteger")]
+        strict_integer: bool,
+        /// How far `value / multiple_of` may be from a whole number
+        #[serde(rename = "multipleOfTolerance")]
+        multiple_of_tolerance: Option<f64>,
+    },
+    /// 64-bit safe integers, accepted as JSON integers or decimal strings
+    #[serde(rename = "bigint")]
+    BigInt {
+        #[serde(default, with = "bigint_bound")]
+        min: Option<i128>,
+        #[serde(default, with = "bigint_bound")]
+        max: Option<i128>,
+    },
+    /// Exact decimal numbers, accepted as JSON numbers or decimal strings
+    Decimal {
+        min: Option<rust_decimal::Decimal>,
+        max: Option<rust_decimal::Decimal>,
+        /// Maximum number of significant digits
+        precision: Option<u32>,
+        /// Maximum number of digits after the decimal point
+        scale: Option<u32>,
+        #[serde(rename = "multipleOf")]
+        multiple_of: Option<rust_decimal::Decimal>,
     },
     Boolean,
     Array {
@@ -31,26 +104,91 @@ pub enum SchemaType {
         max_items: Option<usize>,
         #[serde(rename = "uniqueItems", default)]
         unique_items: bool,
+        /// Items (objects) must have distinct values for this property
+        #[serde(rename = "uniqueBy")]
+        unique_by: Option<String>,
+        /// Items (or their `sortedBy` property) must be in this order
+        sorted: Option<SortOrder>,
+        #[serde(rename = "sortedBy")]
+        sorted_by: Option<String>,
+    },
+    /// Positional items (JSON Schema `prefixItems`), then `items` for the rest
+    Tuple {
+        #[serde(rename = "prefixItems")]
+        prefix_items: Vec<SchemaType>,
+        /// Schema for items past the prefix; `None` accepts anything
+        items: Option<Box<SchemaType>>,
     },
     Object {
         properties: HashMap<String, SchemaType>,
         required: Option<Vec<String>>,
-        #[serde(rename = "additionalProperties", default = "default_additional_properties")]
-        additional_properties: bool,
+        #[serde(rename = "additionalProperties", default, deserialize_with = "deserialize_unknown_keys")]
+        additional_properties: UnknownKeys,
+        /// Schema applied to every undeclared key, overriding the unknown-key policy
+        catchall: Option<Box<SchemaType>>,
+        #[serde(rename = "minProperties")]
+        min_properties: Option<usize>,
+        #[serde(rename = "maxProperties")]
+        max_properties: Option<usize>,
+        /// Properties only allowed in responses (OpenAPI `readOnly`)
+        #[serde(rename = "readOnly")]
+        read_only: Option<Vec<String>>,
+        /// Properties only allowed in requests (OpenAPI `writeOnly`)
+        #[serde(rename = "writeOnly")]
+        write_only: Option<Vec<String>>,
+        /// Cross-field rules checked after the properties themselves
+        rules: Option<Vec<ObjectRule>>,
     },
     Null,
     Any,
+    /// Exactly one value, compared with deep equality
+    Const {
+        #[serde(rename = "const")]
+        value: serde_json::Value,
+    },
+
+    // Collection types (z.set / z.map after a JSON round-trip)
+    Set {
+        items: Box<SchemaType>,
+        #[serde(rename = "minItems")]
+        min_items: Option<usize>,
+        #[serde(rename = "maxItems")]
+        max_items: Option<usize>,
+    },
+    Map {
+        keys: Box<SchemaType>,
+        values: Box<SchemaType>,
+    },
+
+    /// Reference to a named schema in a `SchemaRegistry`
+    Ref {
+        #[serde(rename = "$ref")]
+        reference: String,
+    },
+
     OneOf {
         #[serde(rename = "oneOf")]
         schemas: Vec<SchemaType>,
+        #[serde(rename = "unevaluatedProperties")]
+        unevaluated_properties: Option<Unevaluated>,
+        #[serde(rename = "unevaluatedItems")]
+        unevaluated_items: Option<Unevaluated>,
     },
     AllOf {
         #[serde(rename = "allOf")]
         schemas: Vec<SchemaType>,
+        #[serde(rename = "unevaluatedProperties")]
+        unevaluated_properties: Option<Unevaluated>,
+        #[serde(rename = "unevaluatedItems")]
+        unevaluated_items: Option<Unevaluated>,
     },
     AnyOf {
         #[serde(rename = "anyOf")]
         schemas: Vec<SchemaType>,
+        #[serde(rename = "unevaluatedProperties")]
+        unevaluated_properties: Option<Unevaluated>,
+        #[serde(rename = "unevaluatedItems")]
+        unevaluated_items: Option<Unevaluated>,
     },
 
     // Union and intersection types
@@ -81,11 +219,20 @@ pub enum SchemaType {
         else_schema: Option<Box<SchemaType>>,
     },
 
+    /// `schema` with its annotations (`title`, `sensitive`, `x-` keywords,
+    /// ...), which are written next to its other keywords
+    Annotated {
+        schema: Box<SchemaType>,
+        metadata: SchemaMetadata,
+    },
+
     // HTML/React validation types
     HtmlElement {
         element_type: HtmlElementType,
+        #[serde(default)]
         props: HtmlProps,
         children: Option<Box<SchemaType>>,
+        #[serde(default)]
         validate_dom: bool,
     },
     ReactComponent {
@@ -104,16 +251,378 @@ pub enum SchemaType {
     },
 
     // GraphQL types
+    /// A GraphQL response, checked against the selections of `operation`
+    /// (a query document) when there is one
     GraphQLSchema {
         schema_definition: GraphQLSchemaDefinition,
+        operation: Option<String>,
+        /// Which operation of a document with several to check against
+        #[serde(rename = "operationName")]
+        operation_name: Option<String>,
     },
     GraphQLType {
         type_definition: GraphQLTypeDefinition,
     },
 }
 
-fn default_additional_properties() -> bool {
-    true
+/// Unit for string lengths
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    /// Unicode scalar values
+    #[default]
+    Chars,
+    /// UTF-8 bytes
+    Bytes,
+    /// Extended grapheme clusters, so `"👍🏽"` or `"é"` written with a
+    /// combining accent count as 1
+    #[cfg(feature = "graphemes")]
+    Graphemes,
+}
+
+impl LengthUnit {
+    pub fn length(&self, s: &str) -> usize {
+        match self {
+            LengthUnit::Chars => s.chars().count(),
+            LengthUnit::Bytes => s.len(),
+            #[cfg(feature = "graphemes")]
+            LengthUnit::Graphemes => unicode_segmentation::UnicodeSegmentation::graphemes(s, true).count(),
+        }
+    }
+}
+
+/// Ordering required by array `sorted` constraints
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Cross-field constraints on object schemas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "camelCase")]
+pub enum ObjectRule {
+    /// `field` is required when property `when` equals `equals`
+    RequiredIf {
+        field: String,
+        when: String,
+        equals: serde_json::Value,
+    },
+    /// At most one of `fields` may be present
+    MutuallyExclusive { fields: Vec<String> },
+    /// At least one of `fields` must be present
+    AtLeastOneOf { fields: Vec<String> },
+    /// `field <op> other`, comparing numbers numerically and strings
+    /// lexicographically (so ISO 8601 dates compare chronologically).
+    /// Skipped when either side is missing.
+    Compare {
+        field: String,
+        op: Comparison,
+        other: String,
+    },
+}
+
+/// Comparison operators for `ObjectRule::Compare`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Lte => "<=",
+            Comparison::Gt => ">",
+            Comparison::Gte => ">=",
+        }
+    }
+
+    /// Compare two JSON values; `None` if they are not comparable
+    pub fn holds(&self, left: &serde_json::Value, right: &serde_json::Value) -> Option<bool> {
+        use std::cmp::Ordering;
+
+        let ordering = match (left, right) {
+            (serde_json::Value::Number(a), serde_json::Value::Number(b)) => {
+                a.as_f64()?.partial_cmp(&b.as_f64()?)?
+            }
+            (serde_json::Value::String(a), serde_json::Value::String(b)) => a.cmp(b),
+            _ => match self {
+                Comparison::Eq => return Some(left == right),
+                Comparison::Ne => return Some(left != right),
+                _ => return None,
+            },
+        };
+
+        Some(match self {
+            Comparison::Eq => ordering == Ordering::Equal,
+            Comparison::Ne => ordering != Ordering::Equal,
+            Comparison::Lt => ordering == Ordering::Less,
+            Comparison::Lte => ordering != Ordering::Greater,
+            Comparison::Gt => ordering == Ordering::Greater,
+            Comparison::Gte => ordering != Ordering::Less,
+        })
+    }
+}
+
+/// Binary-to-text encodings for string content (`contentEncoding`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentEncoding {
+    Base64,
+    Base64url,
+}
+
+impl ContentEncoding {
+    /// Decode content, returning `None` if it is not validly encoded
+    pub fn decode(&self, content: &str) -> Option<Vec<u8>> {
+        crate::utils::decode_base64(content, *self == ContentEncoding::Base64url)
+    }
+}
+
+/// Strict `email` format settings (`emailOptions`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EmailOptions {
+    /// Accept domain literals such as `user@[192.168.0.1]` or `user@[IPv6:::1]`
+    pub allow_ip_literal: bool,
+    /// Accept UTF-8 local parts and domains (RFC 6531)
+    pub allow_international: bool,
+    /// Enforce the 64-octet local part, 255-octet domain and 254-octet address limits
+    pub check_length: bool,
+}
+
+impl Default for EmailOptions {
+    fn default() -> Self {
+        Self {
+            allow_ip_literal: false,
+            allow_international: false,
+            check_length: true,
+        }
+    }
+}
+
+/// Constraint for properties/items no composition branch evaluated
+/// (draft 2019-09 `unevaluatedProperties` / `unevaluatedItems`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Unevaluated {
+    /// `true` allows anything, `false` rejects any unevaluated entry
+    Allowed(bool),
+    /// Unevaluated entries must match this schema
+    Schema(Box<SchemaType>),
+}
+
+/// How object keys not declared in `properties` are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnknownKeys {
+    /// Unknown keys are a validation error
+    Strict,
+    /// Unknown keys are removed from the output data
+    Strip,
+    /// Unknown keys are kept as-is
+    #[default]
+    Passthrough,
+}
+
+/// Keywords that imply a schema's `type` when it is left out, as JSON
+/// Schema allows (`{"const": "dark"}`), in order of precedence
+const IMPLIED_TYPES: &[(&str, &str)] = &[("const", "const"), ("oneOf", "oneof"), ("anyOf", "anyof"), ("allOf", "allof")];
+
+impl Serialize for SchemaType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SchemaType::Annotated { schema, metadata } => {
+                let mut written = serde_json::to_value(schema).map_err(serde::ser::Error::custom)?;
+                if let Some(object) = written.as_object_mut() {
+                    object.extend(metadata.keywords());
+                }
+                written.serialize(serializer)
+            }
+            _ => SchemaType::serialize(self, serializer),
+        }
+    }
+}
+
+/// Keywords read into `SchemaMetadata`, besides `x-` ones
+const ANNOTATIONS: &[&str] = &["title", "description", "examples", "default", "meta", "sensitive"];
+
+/// Keywords whose values are data, not subschemas
+const DATA_KEYWORDS: &[&str] = &["const", "default", "examples", "meta", "equals"];
+
+/// Keywords whose values map names to subschemas
+const SCHEMA_MAPS: &[&str] = &["properties", "props_schema"];
+
+thread_local! {
+    /// Set while a document that `normalize_schema` already rewrote is
+    /// read, so nested schemas are not buffered and rewritten again
+    static NORMALIZED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Clears `NORMALIZED` when the outermost read ends, also on a panic
+struct NormalizedGuard;
+
+impl Drop for NormalizedGuard {
+    fn drop(&mut self) {
+        NORMALIZED.with(|normalized| normalized.set(false));
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if NORMALIZED.with(Cell::get) {
+            return SchemaType::deserialize(deserializer);
+        }
+        let mut schema = serde_json::Value::deserialize(deserializer)?;
+        normalize_schema(&mut schema);
+        NORMALIZED.with(|normalized| normalized.set(true));
+        let _guard = NormalizedGuard;
+        SchemaType::deserialize(schema).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The `type` of a schema object that leaves it out
+pub(crate) fn implied_type(schema: &serde_json::Map<String, serde_json::Value>) -> Option<&'static str> {
+    if schema.contains_key("type") {
+        return None;
+    }
+    IMPLIED_TYPES.iter().find(|(keyword, _)| schema.contains_key(*keyword)).map(|(_, tag)| *tag)
+}
+
+/// Add implied `type`s throughout a schema document, and turn a `const`,
+/// `oneOf`, `anyOf` or `allOf` next to a different `type` into an `allOf`
+/// of both, so neither is dropped
+fn normalize_schema(node: &mut serde_json::Value) {
+    let object = match node {
+        serde_json::Value::Object(object) => object,
+        _ => return,
+    };
+    if let Some(tag) = implied_type(object) {
+        object.insert("type".to_string(), serde_json::Value::from(tag));
+    }
+    // Only schema objects (the one internally tagged type) carry annotations
+    let metadata = match object.get("type") {
+        Some(serde_json::Value::String(_)) => SchemaMetadata::take(object),
+        _ => SchemaMetadata::default(),
+    };
+    combine_siblings(object);
+
+    for (keyword, value) in object.iter_mut() {
+        if DATA_KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+        match value {
+            serde_json::Value::Object(schemas) if SCHEMA_MAPS.contains(&keyword.as_str()) => {
+                schemas.values_mut().for_each(normalize_schema);
+            }
+            serde_json::Value::Array(schemas) => schemas.iter_mut().for_each(normalize_schema),
+            schema => normalize_schema(schema),
+        }
+    }
+
+    if metadata != SchemaMetadata::default() {
+        *node = serde_json::json!({
+            "type": "annotated",
+            "schema": node.take(),
+            "metadata": metadata,
+        });
+    }
+}
+
+fn combine_siblings(object: &mut serde_json::Map<String, serde_json::Value>) {
+    let tag = object.get("type").and_then(serde_json::Value::as_str).unwrap_or_default().to_string();
+    let mut parts: Vec<serde_json::Value> = Vec::new();
+    for (keyword, implied) in IMPLIED_TYPES {
+        if *implied != tag {
+            if let Some(value) = object.remove(*keyword) {
+                parts.push(serde_json::json!({ "type": implied, *keyword: value }));
+            }
+        }
+    }
+    if parts.is_empty() {
+        return;
+    }
+
+    let mut combined = serde_json::Map::new();
+    // Only compositions read `unevaluated*`, so they move to the `allOf`
+    // unless the schema is one itself
+    if !IMPLIED_TYPES[1..].iter().any(|(_, implied)| *implied == tag) {
+        for keyword in ["unevaluatedProperties", "unevaluatedItems"] {
+            if let Some(value) = object.remove(keyword) {
+                combined.insert(keyword.to_string(), value);
+            }
+        }
+    }
+    parts.insert(0, serde_json::Value::Object(std::mem::take(object)));
+    combined.insert("type".to_string(), serde_json::Value::from("allof"));
+    combined.insert("allOf".to_string(), serde_json::Value::Array(parts));
+    *object = combined;
+}
+
+/// Accept the legacy boolean form (`false` = strict, `true` = passthrough)
+/// as well as the policy name.
+fn deserialize_unknown_keys<'de, D>(deserializer: D) -> Result<UnknownKeys, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Flag(bool),
+        Policy(UnknownKeys),
+    }
+
+    Ok(match Repr::deserialize(deserializer)? {
+        Repr::Flag(true) => UnknownKeys::Passthrough,
+        Repr::Flag(false) => UnknownKeys::Strict,
+        Repr::Policy(policy) => policy,
+    })
+}
+
+/// Serde helpers for bigint bounds, which may be JSON integers or decimal strings.
+/// Internally tagged enums buffer their content, and that buffer cannot hold i128.
+mod bigint_bound {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::convert::TryFrom;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Bound {
+        Signed(i64),
+        Unsigned(u64),
+        Encoded(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Option<i128>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(n) => match i64::try_from(*n) {
+                Ok(small) => serializer.serialize_i64(small),
+                Err(_) => serializer.serialize_str(&n.to_string()),
+            },
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i128>, D::Error> {
+        match Option::<Bound>::deserialize(deserializer)? {
+            Some(Bound::Signed(n)) => Ok(Some(n as i128)),
+            Some(Bound::Unsigned(n)) => Ok(Some(n as i128)),
+            Some(Bound::Encoded(s)) => crate::utils::parse_int64(&s)
+                .map(Some)
+                .ok_or_else(|| de::Error::custom(format!("bigint bound '{}' is not a 64-bit integer", s))),
+            None => Ok(None),
+        }
+    }
 }
 
 /// String format validators
@@ -138,6 +647,8 @@ pub enum StringFormat {
     Ipv4,
     Ipv6,
     Hostname,
+    /// Hostname with at least two labels and an alphabetic TLD
+    Fqdn,
     #[serde(rename = "ipv4-cidr")]
     Ipv4Cidr,
     #[serde(rename = "ipv6-cidr")]
@@ -164,6 +675,10 @@ pub enum StringFormat {
     Jwt,
     Nanoid,
     Cuid,
+    /// Crockford base32, 26 chars
+    Ulid,
+    /// Base62, 27 chars
+    Ksuid,
     #[serde(rename = "object-id")]
     ObjectId,
 
@@ -176,6 +691,9 @@ pub enum StringFormat {
     // Geographic formats
     Latitude,
     Longitude,
+    /// `"lat,lng"` coordinate pair
+    #[serde(rename = "geo-point")]
+    GeoPoint,
     Country,
     Language,
     Timezone,
@@ -186,11 +704,22 @@ pub enum StringFormat {
     #[serde(rename = "postal-code")]
     PostalCode,
 
+    // Payment
+    /// Card number passing the Luhn check
+    #[serde(rename = "credit-card", alias = "creditCard")]
+    CreditCard,
+    Iban,
+    Bic,
+
     // Crypto/Security
     #[serde(rename = "sha256")]
     Sha256,
     #[serde(rename = "md5")]
     Md5,
+    #[serde(rename = "sha1")]
+    Sha1,
+    #[serde(rename = "sha512")]
+    Sha512,
 
     // JSON formats
     JsonPointer,
@@ -198,6 +727,19 @@ pub enum StringFormat {
 
     // Custom regex
     Regex,
+
+    /// Any other name, resolved against the validator's `FormatRegistry`
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl StringFormat {
+    /// The format named as in schema JSON, e.g. `"date-time"`; unknown
+    /// names are `Custom`
+    pub fn from_name(name: &str) -> Self {
+        serde_json::from_value(serde_json::Value::String(name.to_string()))
+            .unwrap_or_else(|_| StringFormat::Custom(name.to_string()))
+    }
 }
 
 /// Refinement function for custom validation
@@ -353,10 +895,200 @@ pub struct GraphQLDirective {
 #[derive(Debug, Clone)]
 pub struct CompiledSchema {
     pub schema: SchemaType,
+    /// Executable form of `schema` used on the hot path
+    pub plan: Arc<ValidationPlan>,
     pub required_fields: Vec<String>,
     pub has_patterns: bool,
+    /// Valid data is rewritten on output (e.g. unknown keys stripped)
+    pub has_transforms: bool,
+    /// Uses `unevaluated*` keywords, so evaluated keys must be tracked
+    pub has_unevaluated: bool,
     pub max_depth: usize,
     pub estimated_complexity: usize,
+    /// Annotations keyed by the data path they describe, with `ANY_ITEM`
+    /// standing for every item of an array
+    pub metadata: HashMap<Vec<PathSegment>, SchemaMetadata>,
+    /// Deprecated spellings rewritten when the source JSON was parsed
+    pub warnings: Vec<ValidationError>,
+}
+
+/// Metadata path segment for every item of an array
+pub const ANY_ITEM: PathSegment = PathSegment::Index(usize::MAX);
+
+/// A metadata path as text, e.g. `items.[].price`
+pub(crate) fn display_metadata_path(path: &[PathSegment]) -> String {
+    let segments: Vec<String> = path
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Key(key) => key.clone(),
+            segment if *segment == ANY_ITEM => "[]".to_string(),
+            PathSegment::Index(index) => format!("[{}]", index),
+        })
+        .collect();
+    segments.join(".")
+}
+
+/// Non-validating annotations attached to a schema node
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SchemaMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<serde_json::Value>>,
+    /// The `default` keyword, filled in by `EnvValidator` for missing variables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    /// Arbitrary user data (e.g. form widget hints)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<serde_json::Value>,
+    /// Data here (and below) is never echoed in errors, see `Redaction`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
+    /// `x-` prefixed keywords, validated by any registered `KeywordValidator`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, serde_json::Value>,
+}
+
+impl SchemaMetadata {
+    /// The annotations among a schema object's keywords
+    pub(crate) fn read(obj: &serde_json::Map<String, serde_json::Value>) -> SchemaMetadata {
+        SchemaMetadata {
+            title: obj.get("title").and_then(|v| v.as_str()).map(String::from),
+            description: obj.get("description").and_then(|v| v.as_str()).map(String::from),
+            examples: obj.get("examples").and_then(|v| v.as_array()).cloned(),
+            default: obj.get("default").cloned(),
+            meta: obj.get("meta").cloned(),
+            sensitive: obj.get("sensitive").and_then(|v| v.as_bool()).unwrap_or(false),
+            extensions: obj
+                .iter()
+                .filter(|(key, _)| key.starts_with("x-"))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Like `read`, removing the annotation keywords from `obj`
+    fn take(obj: &mut serde_json::Map<String, serde_json::Value>) -> SchemaMetadata {
+        let metadata = Self::read(obj);
+        obj.retain(|key, _| !ANNOTATIONS.contains(&key.as_str()) && !key.starts_with("x-"));
+        metadata
+    }
+
+    /// The annotation keywords, as written in a schema object
+    fn keywords(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut keywords = match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(keywords)) => keywords,
+            _ => serde_json::Map::new(),
+        };
+        keywords.remove("extensions");
+        keywords.extend(self.extensions.clone());
+        keywords
+    }
+}
+
+/// The parts of a `CompiledSchema` kept by `to_bytes`; the plan and the
+/// metadata (read off the schema's annotations) are rebuilt
+#[derive(Serialize, Deserialize)]
+struct CompiledParts {
+    schema: SchemaType,
+    required_fields: Vec<String>,
+    has_patterns: bool,
+    has_transforms: bool,
+    has_unevaluated: bool,
+    max_depth: usize,
+    estimated_complexity: usize,
+    #[serde(default)]
+    warnings: Vec<ValidationError>,
+}
+
+impl CompiledSchema {
+    /// Binary form for caching (e.g. in IndexedDB), readable by
+    /// `from_bytes` of the same fast-schema version
+    pub fn to_bytes(&self) -> Result<Vec<u8>, FastSchemaError> {
+        let parts = CompiledParts {
+            schema: self.schema.clone(),
+            required_fields: self.required_fields.clone(),
+            has_patterns: self.has_patterns,
+            has_transforms: self.has_transforms,
+            has_unevaluated: self.has_unevaluated,
+            max_depth: self.max_depth,
+            estimated_complexity: self.estimated_complexity,
+            warnings: self.warnings.clone(),
+        };
+        Ok(crate::binary::encode(&serde_json::to_value(parts)?, env!("CARGO_PKG_VERSION")))
+    }
+
+    /// Rehydrate a schema from `to_bytes`, skipping JSON parsing and schema
+    /// analysis. Only the plan is rebuilt, with regexes from the shared cache.
+    pub fn from_bytes(bytes: &[u8]) -> Result<CompiledSchema, FastSchemaError> {
+        let value = crate::binary::decode(bytes, env!("CARGO_PKG_VERSION"))?;
+        let parts: CompiledParts = serde_json::from_value(value)?;
+
+        Ok(CompiledSchema {
+            plan: Arc::new(ValidationPlan::compile(&parts.schema)),
+            metadata: parts.schema.collect_metadata(),
+            schema: parts.schema,
+            required_fields: parts.required_fields,
+            has_patterns: parts.has_patterns,
+            has_transforms: parts.has_transforms,
+            has_unevaluated: parts.has_unevaluated,
+            max_depth: parts.max_depth,
+            estimated_complexity: parts.estimated_complexity,
+            warnings: parts.warnings,
+        })
+    }
+
+    /// Look up annotations for a schema path such as `items.[].price`.
+    /// Data paths such as `tags.[2]` are accepted too, so error paths can be
+    /// passed straight through.
+    pub fn get_metadata(&self, path: &str) -> Option<&SchemaMetadata> {
+        let segments: Vec<PathSegment> = ErrorPath::parse(path)
+            .segments()
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Key(key) if key == "[]" => ANY_ITEM,
+                segment => segment.clone(),
+            })
+            .collect();
+        self.metadata_at(&segments)
+    }
+
+    /// Annotations for the data at `segments`
+    pub fn metadata_at(&self, segments: &[PathSegment]) -> Option<&SchemaMetadata> {
+        let path: Vec<PathSegment> = segments.iter().map(metadata_segment).collect();
+        self.metadata.get(&path)
+    }
+
+    /// Whether the data at `segments`, or anything containing it, is
+    /// marked `sensitive`
+    pub fn is_sensitive(&self, segments: &[PathSegment]) -> bool {
+        if self.metadata.is_empty() {
+            return false;
+        }
+
+        let mut path = Vec::with_capacity(segments.len());
+        let check = |path: &[PathSegment]| self.metadata.get(path).is_some_and(|metadata| metadata.sensitive);
+        if check(&path) {
+            return true;
+        }
+        for segment in segments {
+            path.push(metadata_segment(segment));
+            if check(&path) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// The metadata key for a data path segment: any array index is `ANY_ITEM`
+fn metadata_segment(segment: &PathSegment) -> PathSegment {
+    match segment {
+        PathSegment::Index(_) => ANY_ITEM,
+        key => key.clone(),
+    }
 }
 
 impl SchemaType {
@@ -368,7 +1100,7 @@ impl SchemaType {
         let estimated_complexity = self.estimate_complexity();
 
         // Extract required fields for quick access
-        if let SchemaType::Object { required, properties, .. } = self {
+        if let SchemaType::Object { required, properties, .. } = self.unannotated() {
             if let Some(req) = required {
                 required_fields = req.clone();
             }
@@ -382,19 +1114,42 @@ impl SchemaType {
             }
         }
 
+        // Compile from the stored copy so plan and interpreter walk
+        // properties in the same order
+        let schema = self.clone();
+        let plan = Arc::new(ValidationPlan::compile(&schema));
+
         CompiledSchema {
-            schema: self.clone(),
+            schema,
+            plan,
             required_fields,
             has_patterns,
+            has_transforms: self.has_transforms(),
+            has_unevaluated: self.has_unevaluated(),
             max_depth,
             estimated_complexity,
+            metadata: self.collect_metadata(),
+            warnings: Vec::new(),
         }
     }
 
     /// Calculate maximum nesting depth
     fn calculate_depth(&self) -> usize {
         match self {
-            SchemaType::Array { items, .. } => 1 + items.calculate_depth(),
+            SchemaType::Annotated { schema, .. } => schema.calculate_depth(),
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => 1 + items.calculate_depth(),
+            SchemaType::Map { keys, values } => {
+                1 + keys.calculate_depth().max(values.calculate_depth())
+            }
+            SchemaType::Tuple { prefix_items, items } => {
+                1 + prefix_items
+                    .iter()
+                    .chain(items.as_deref())
+                    .map(|s| s.calculate_depth())
+                    .max()
+                    .unwrap_or(0)
+            }
             SchemaType::Object { properties, .. } => {
                 1 + properties
                     .values()
@@ -402,9 +1157,9 @@ impl SchemaType {
                     .max()
                     .unwrap_or(0)
             }
-            SchemaType::OneOf { schemas } |
-            SchemaType::AllOf { schemas } |
-            SchemaType::AnyOf { schemas } => {
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } => {
                 1 + schemas
                     .iter()
                     .map(|s| s.calculate_depth())
@@ -416,27 +1171,42 @@ impl SchemaType {
     }
 
     /// Estimate validation complexity for optimization decisions
-    fn estimate_complexity(&self) -> usize {
+    pub(crate) fn estimate_complexity(&self) -> usize {
         match self {
-            SchemaType::String { pattern, format, .. } => {
+            SchemaType::Annotated { schema, .. } => schema.estimate_complexity(),
+            SchemaType::String { pattern, format, content_encoding, content_schema, .. } => {
                 let mut complexity = 1;
                 if pattern.is_some() { complexity += 10; }
                 if format.is_some() { complexity += 5; }
+                if content_encoding.is_some() { complexity += 5; }
+                if let Some(schema) = content_schema {
+                    complexity += 10 + schema.estimate_complexity();
+                }
                 complexity
             }
             SchemaType::Number { .. } => 2,
+            SchemaType::BigInt { .. } => 3,
+            SchemaType::Decimal { .. } => 4,
             SchemaType::Boolean | SchemaType::Null => 1,
+            SchemaType::Const { .. } => 2,
             SchemaType::Array { items, .. } => 5 + items.estimate_complexity(),
+            SchemaType::Set { items, .. } => 8 + items.estimate_complexity(),
+            SchemaType::Tuple { prefix_items, items } => {
+                5 + prefix_items.iter().chain(items.as_deref()).map(|s| s.estimate_complexity()).sum::<usize>()
+            }
+            SchemaType::Map { keys, values } => {
+                8 + keys.estimate_complexity() + values.estimate_complexity()
+            }
             SchemaType::Object { properties, .. } => {
                 10 + properties.values().map(|s| s.estimate_complexity()).sum::<usize>()
             }
-            SchemaType::OneOf { schemas } => {
+            SchemaType::OneOf { schemas, .. } => {
                 20 + schemas.iter().map(|s| s.estimate_complexity()).sum::<usize>()
             }
-            SchemaType::AllOf { schemas } => {
+            SchemaType::AllOf { schemas, .. } => {
                 15 + schemas.iter().map(|s| s.estimate_complexity()).sum::<usize>()
             }
-            SchemaType::AnyOf { schemas } => {
+            SchemaType::AnyOf { schemas, .. } => {
                 10 + schemas.iter().map(|s| s.estimate_complexity()).sum::<usize>()
             }
             SchemaType::Any => 1,
@@ -448,23 +1218,314 @@ impl SchemaType {
     /// Check if schema contains regex patterns
     fn has_patterns(&self) -> bool {
         match self {
-            SchemaType::String { pattern, .. } => pattern.is_some(),
-            SchemaType::Array { items, .. } => items.has_patterns(),
+            SchemaType::Annotated { schema, .. } => schema.has_patterns(),
+            SchemaType::String { pattern, content_schema, .. } => {
+                pattern.is_some() || content_schema.as_ref().is_some_and(|s| s.has_patterns())
+            }
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => items.has_patterns(),
+            SchemaType::Map { keys, values } => keys.has_patterns() || values.has_patterns(),
+            SchemaType::Tuple { prefix_items, items } => {
+                prefix_items.iter().chain(items.as_deref()).any(|s| s.has_patterns())
+            }
             SchemaType::Object { properties, .. } => {
                 properties.values().any(|s| s.has_patterns())
             }
-            SchemaType::OneOf { schemas } |
-            SchemaType::AllOf { schemas } |
-            SchemaType::AnyOf { schemas } => {
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } => {
                 schemas.iter().any(|s| s.has_patterns())
             }
             _ => false,
         }
     }
 
+    /// Check if validated data must be rewritten before it is returned
+    pub(crate) fn has_transforms(&self) -> bool {
+        match self {
+            SchemaType::Annotated { schema, .. } => schema.has_transforms(),
+            SchemaType::Object { properties, additional_properties, catchall, .. } => {
+                *additional_properties == UnknownKeys::Strip
+                    || catchall.as_ref().is_some_and(|s| s.has_transforms())
+                    || properties.values().any(|s| s.has_transforms())
+            }
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => items.has_transforms(),
+            SchemaType::Map { values, .. } => values.has_transforms(),
+            SchemaType::Tuple { prefix_items, items } => {
+                prefix_items.iter().chain(items.as_deref()).any(|s| s.has_transforms())
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if schema uses `unevaluatedProperties` / `unevaluatedItems`
+    pub(crate) fn has_unevaluated(&self) -> bool {
+        match self {
+            SchemaType::Annotated { schema, .. } => schema.has_unevaluated(),
+            SchemaType::OneOf { schemas, unevaluated_properties, unevaluated_items } |
+            SchemaType::AllOf { schemas, unevaluated_properties, unevaluated_items } |
+            SchemaType::AnyOf { schemas, unevaluated_properties, unevaluated_items } => {
+                unevaluated_properties.is_some()
+                    || unevaluated_items.is_some()
+                    || schemas.iter().any(|s| s.has_unevaluated())
+            }
+            SchemaType::Object { properties, catchall, .. } => {
+                catchall.as_ref().is_some_and(|s| s.has_unevaluated())
+                    || properties.values().any(|s| s.has_unevaluated())
+            }
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => items.has_unevaluated(),
+            SchemaType::Map { keys, values } => keys.has_unevaluated() || values.has_unevaluated(),
+            SchemaType::Tuple { prefix_items, items } => {
+                prefix_items.iter().chain(items.as_deref()).any(|s| s.has_unevaluated())
+            }
+            SchemaType::Conditional { condition, then_schema, else_schema } => {
+                condition.has_unevaluated()
+                    || then_schema.has_unevaluated()
+                    || else_schema.as_ref().is_some_and(|s| s.has_unevaluated())
+            }
+            _ => false,
+        }
+    }
+
+    /// This schema with `metadata` attached, unless there is none
+    pub fn with_metadata(self, metadata: SchemaMetadata) -> SchemaType {
+        if metadata == SchemaMetadata::default() {
+            return self;
+        }
+        SchemaType::Annotated { schema: Box::new(self), metadata }
+    }
+
+    /// The schema under any annotations
+    pub fn unannotated(&self) -> &SchemaType {
+        match self {
+            SchemaType::Annotated { schema, .. } => schema.unannotated(),
+            schema => schema,
+        }
+    }
+
+    /// `unannotated`, for changing the schema in place
+    pub(crate) fn unannotated_mut(&mut self) -> &mut SchemaType {
+        match self {
+            SchemaType::Annotated { schema, .. } => schema.unannotated_mut(),
+            schema => schema,
+        }
+    }
+
+    /// Collect the names of all schemas referenced with `$ref`
+    pub fn references(&self) -> Vec<&str> {
+        let mut refs = Vec::new();
+        self.collect_references(&mut refs);
+        refs
+    }
+
+    fn collect_references<'a>(&'a self, refs: &mut Vec<&'a str>) {
+        match self {
+            SchemaType::Ref { reference } => refs.push(reference),
+            SchemaType::String { content_schema: Some(schema), .. } => schema.collect_references(refs),
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => items.collect_references(refs),
+            SchemaType::Map { keys, values } => {
+                keys.collect_references(refs);
+                values.collect_references(refs);
+            }
+            SchemaType::Tuple { prefix_items, items } => {
+                for schema in prefix_items.iter().chain(items.as_deref()) {
+                    schema.collect_references(refs);
+                }
+            }
+            SchemaType::Object { properties, catchall, .. } => {
+                for schema in properties.values() {
+                    schema.collect_references(refs);
+                }
+                if let Some(schema) = catchall {
+                    schema.collect_references(refs);
+                }
+            }
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } |
+            SchemaType::Intersection { schemas } |
+            SchemaType::Union { options: schemas, .. } => {
+                for schema in schemas {
+                    schema.collect_references(refs);
+                }
+            }
+            SchemaType::Conditional { condition, then_schema, else_schema } => {
+                condition.collect_references(refs);
+                then_schema.collect_references(refs);
+                if let Some(schema) = else_schema {
+                    schema.collect_references(refs);
+                }
+            }
+            SchemaType::Refinement { base, .. } => base.collect_references(refs),
+            SchemaType::Annotated { schema, .. } => schema.collect_references(refs),
+            SchemaType::Transform { input, .. } => input.collect_references(refs),
+            _ => {}
+        }
+    }
+
+    /// Annotations of this schema's nodes, keyed by the data path they
+    /// describe. Composition branches share the path of their parent; the
+    /// outermost annotation for a path wins, but `x-` keywords add up.
+    pub(crate) fn collect_metadata(&self) -> HashMap<Vec<PathSegment>, SchemaMetadata> {
+        let mut metadata = HashMap::new();
+        self.collect_metadata_into(&mut Vec::new(), &mut metadata);
+        metadata
+    }
+
+    fn collect_metadata_into(&self, path: &mut Vec<PathSegment>, metadata: &mut HashMap<Vec<PathSegment>, SchemaMetadata>) {
+        match self {
+            SchemaType::Annotated { schema, metadata: found } => {
+                match metadata.entry(path.clone()) {
+                    Entry::Occupied(mut entry) => {
+                        for (keyword, argument) in &found.extensions {
+                            entry.get_mut().extensions.entry(keyword.clone()).or_insert_with(|| argument.clone());
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(found.clone());
+                    }
+                }
+                schema.collect_metadata_into(path, metadata);
+            }
+            SchemaType::Object { properties, .. } => {
+                for (name, schema) in properties {
+                    path.push(PathSegment::Key(name.clone()));
+                    schema.collect_metadata_into(path, metadata);
+                    path.pop();
+                }
+            }
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } |
+            SchemaType::Tuple { items: Some(items), .. } => {
+                path.push(ANY_ITEM);
+                items.collect_metadata_into(path, metadata);
+                path.pop();
+            }
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } |
+            SchemaType::Intersection { schemas } |
+            SchemaType::Union { options: schemas, .. } => {
+                for schema in schemas {
+                    schema.collect_metadata_into(path, metadata);
+                }
+            }
+            SchemaType::Refinement { base, .. } => base.collect_metadata_into(path, metadata),
+            _ => {}
+        }
+    }
+
+    /// Regex sources of every `pattern` in this schema, not following `$ref`s
+    pub(crate) fn collect_patterns<'a>(&'a self, patterns: &mut Vec<&'a str>) {
+        self.for_each_string(&mut |schema| {
+            if let SchemaType::String { pattern, .. } = schema {
+                patterns.extend(pattern.as_deref());
+            }
+        });
+    }
+
+    /// Names of the non-built-in string formats in this schema, not
+    /// following `$ref`s
+    pub(crate) fn collect_custom_formats<'a>(&'a self, names: &mut Vec<&'a str>) {
+        self.for_each_string(&mut |schema| {
+            if let SchemaType::String { format: Some(StringFormat::Custom(name)), .. } = schema {
+                names.push(name);
+            }
+        });
+    }
+
+    /// Call `visit` with every string schema nested in this one
+    fn for_each_string<'a>(&'a self, visit: &mut dyn FnMut(&'a SchemaType)) {
+        match self {
+            SchemaType::String { content_schema, .. } => {
+                visit(self);
+                if let Some(schema) = content_schema {
+                    schema.for_each_string(visit);
+                }
+            }
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => items.for_each_string(visit),
+            SchemaType::Map { keys, values } => {
+                keys.for_each_string(visit);
+                values.for_each_string(visit);
+            }
+            SchemaType::Tuple { prefix_items, items } => {
+                for schema in prefix_items.iter().chain(items.as_deref()) {
+                    schema.for_each_string(visit);
+                }
+            }
+            SchemaType::Object { properties, catchall, .. } => {
+                for schema in properties.values() {
+                    schema.for_each_string(visit);
+                }
+                if let Some(schema) = catchall {
+                    schema.for_each_string(visit);
+                }
+            }
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } |
+            SchemaType::Intersection { schemas } |
+            SchemaType::Union { options: schemas, .. } => {
+                for schema in schemas {
+                    schema.for_each_string(visit);
+                }
+            }
+            SchemaType::Conditional { condition, then_schema, else_schema } => {
+                condition.for_each_string(visit);
+                then_schema.for_each_string(visit);
+                if let Some(schema) = else_schema {
+                    schema.for_each_string(visit);
+                }
+            }
+            SchemaType::Refinement { base, .. } => base.for_each_string(visit),
+            SchemaType::Annotated { schema, .. } => schema.for_each_string(visit),
+            SchemaType::Transform { input, .. } => input.for_each_string(visit),
+            _ => {}
+        }
+    }
+
+    /// References followed while still validating this same value, i.e.
+    /// not inside a property or item schema. A cycle of these never
+    /// consumes input.
+    pub(crate) fn same_value_references(&self) -> Vec<&str> {
+        let mut refs = Vec::new();
+        self.collect_same_value_references(&mut refs);
+        refs
+    }
+
+    fn collect_same_value_references<'a>(&'a self, refs: &mut Vec<&'a str>) {
+        match self {
+            SchemaType::Ref { reference } => refs.push(reference),
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } |
+            SchemaType::Intersection { schemas } |
+            SchemaType::Union { options: schemas, .. } => {
+                for schema in schemas {
+                    schema.collect_same_value_references(refs);
+                }
+            }
+            SchemaType::Conditional { condition, then_schema, else_schema } => {
+                condition.collect_same_value_references(refs);
+                then_schema.collect_same_value_references(refs);
+                if let Some(schema) = else_schema {
+                    schema.collect_same_value_references(refs);
+                }
+            }
+            SchemaType::Refinement { base, .. } => base.collect_same_value_references(refs),
+            SchemaType::Annotated { schema, .. } => schema.collect_same_value_references(refs),
+            SchemaType::Transform { input, .. } => input.collect_same_value_references(refs),
+            _ => {}
+        }
+    }
+
     /// Get all property names for object schemas (used for optimization)
     pub fn get_property_names(&self) -> Vec<String> {
-        match self {
+        match self.unannotated() {
             SchemaType::Object { properties, .. } => {
                 properties.keys().cloned().collect()
             }
@@ -474,12 +1535,15 @@ impl SchemaType {
 
     /// Check if schema is simple (no nested objects/arrays)
     pub fn is_simple(&self) -> bool {
-        match self {
+        match self.unannotated() {
             SchemaType::String { .. } |
             SchemaType::Number { .. } |
+            SchemaType::BigInt { .. } |
+            SchemaType::Decimal { .. } |
             SchemaType::Boolean |
             SchemaType::Null |
-            SchemaType::Any => true,
+            SchemaType::Any |
+            SchemaType::Const { .. } => true,
             _ => false,
         }
     }
@@ -487,6 +1551,7 @@ impl SchemaType {
     /// Get estimated validation time in microseconds (for batch optimization)
     pub fn estimated_validation_time(&self) -> u64 {
         match self {
+            SchemaType::Annotated { schema, .. } => schema.estimated_validation_time(),
             SchemaType::String { pattern, format, .. } => {
                 let mut time = 1;
                 if pattern.is_some() { time += 50; }
@@ -494,24 +1559,31 @@ impl SchemaType {
                 time
             }
             SchemaType::Number { .. } => 2,
+            SchemaType::BigInt { .. } => 3,
+            SchemaType::Decimal { .. } => 5,
             SchemaType::Boolean | SchemaType::Null | SchemaType::Any => 1,
+            SchemaType::Const { .. } => 2,
             SchemaType::Array { items, .. } => 10 + items.estimated_validation_time(),
+            SchemaType::Set { items, .. } => 15 + items.estimated_validation_time(),
+            SchemaType::Map { keys, values } => {
+                15 + keys.estimated_validation_time() + values.estimated_validation_time()
+            }
             SchemaType::Object { properties, .. } => {
                 20 + properties.values()
                     .map(|s| s.estimated_validation_time())
                     .sum::<u64>()
             }
-            SchemaType::OneOf { schemas } => {
+            SchemaType::OneOf { schemas, .. } => {
                 100 + schemas.iter()
                     .map(|s| s.estimated_validation_time())
                     .sum::<u64>()
             }
-            SchemaType::AllOf { schemas } => {
+            SchemaType::AllOf { schemas, .. } => {
                 schemas.iter()
                     .map(|s| s.estimated_validation_time())
                     .sum::<u64>()
             }
-            SchemaType::AnyOf { schemas } => {
+            SchemaType::AnyOf { schemas, .. } => {
                 // Best case: first schema matches
                 schemas.first()
                     .map(|s| s.estimated_validation_time())
@@ -524,10 +1596,200 @@ impl SchemaType {
 }
 
 
+
+/// Schema derivation operations (Zod-style object transforms)
+impl SchemaType {
+    /// Make every top-level property optional
+    pub fn partial(&self) -> Result<SchemaType, FastSchemaError> {
+        let mut derived = self.clone();
+        let (_, required) = derived.object_parts_mut("partial")?;
+        *required = None;
+        Ok(derived)
+    }
+
+    /// Make every property optional, recursing into nested objects, arrays,
+    /// map values and the branches of unions and intersections
+    pub fn deep_partial(&self) -> Result<SchemaType, FastSchemaError> {
+        self.object_parts("deepPartial")?;
+        Ok(self.deep_partial_inner())
+    }
+
+    fn deep_partial_inner(&self) -> SchemaType {
+        let mut derived = self.clone();
+        match &mut derived {
+            SchemaType::Annotated { schema, .. } => **schema = schema.deep_partial_inner(),
+            SchemaType::Object { properties, required, .. } => {
+                for prop_schema in properties.values_mut() {
+                    *prop_schema = prop_schema.deep_partial_inner();
+                }
+                *required = None;
+            }
+            SchemaType::Array { items, .. } |
+            SchemaType::Set { items, .. } => {
+                **items = items.deep_partial_inner();
+            }
+            SchemaType::Map { values, .. } => **values = values.deep_partial_inner(),
+            SchemaType::OneOf { schemas, .. } |
+            SchemaType::AllOf { schemas, .. } |
+            SchemaType::AnyOf { schemas, .. } |
+            SchemaType::Intersection { schemas } |
+            SchemaType::Union { options: schemas, .. } => {
+                for schema in schemas {
+                    *schema = schema.deep_partial_inner();
+                }
+            }
+            _ => {}
+        }
+        derived
+    }
+
+    /// Make every top-level property required
+    pub fn required(&self) -> Result<SchemaType, FastSchemaError> {
+        let mut derived = self.clone();
+        let (properties, required) = derived.object_parts_mut("required")?;
+        let mut names: Vec<String> = properties.keys().cloned().collect();
+        names.sort();
+        *required = Some(names);
+        Ok(derived)
+    }
+
+    /// Keep only the listed properties
+    pub fn pick(&self, keys: &[&str]) -> Result<SchemaType, FastSchemaError> {
+        self.retain_properties("pick", |key| keys.contains(&key))
+    }
+
+    /// Remove the listed properties
+    pub fn omit(&self, keys: &[&str]) -> Result<SchemaType, FastSchemaError> {
+        self.retain_properties("omit", |key| !keys.contains(&key))
+    }
+
+    /// Add or override properties
+    pub fn extend(&self, extra: HashMap<String, SchemaType>) -> Result<SchemaType, FastSchemaError> {
+        let mut derived = self.clone();
+        let (properties, _) = derived.object_parts_mut("extend")?;
+        properties.extend(extra);
+        Ok(derived)
+    }
+
+    /// Merge another object schema into this one; `other` wins on conflicts,
+    /// including its unknown-key policy and catchall
+    pub fn merge(&self, other: &SchemaType) -> Result<SchemaType, FastSchemaError> {
+        let (other_properties, other_required) = other.object_parts("merge")?;
+        let mut derived = other.clone();
+
+        let (properties, required) = self.object_parts("merge")?;
+        let mut merged_properties = properties.clone();
+        merged_properties.extend(other_properties.clone());
+
+        let mut merged_required: Vec<String> = required.clone().unwrap_or_default();
+        for name in other_required.iter().flatten() {
+            if !merged_required.contains(name) {
+                merged_required.push(name.clone());
+            }
+        }
+
+        let (target_properties, target_required) = derived.object_parts_mut("merge")?;
+        *target_properties = merged_properties;
+        *target_required = if merged_required.is_empty() { None } else { Some(merged_required) };
+        Ok(derived)
+    }
+
+    fn retain_properties(
+        &self,
+        operation: &str,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<SchemaType, FastSchemaError> {
+        let mut derived = self.clone();
+        let (properties, required) = derived.object_parts_mut(operation)?;
+        properties.retain(|key, _| keep(key));
+        if let Some(names) = required {
+            names.retain(|name| keep(name));
+        }
+        Ok(derived)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn object_parts(
+        &self,
+        operation: &str,
+    ) -> Result<(&HashMap<String, SchemaType>, &Option<Vec<String>>), FastSchemaError> {
+        match self {
+            SchemaType::Annotated { schema, .. } => schema.object_parts(operation),
+            SchemaType::Object { properties, required, .. } => Ok((properties, required)),
+            _ => Err(FastSchemaError::UnsupportedOperation(format!(
+                "'{}' requires an object schema", operation
+            ))),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn object_parts_mut(
+        &mut self,
+        operation: &str,
+    ) -> Result<(&mut HashMap<String, SchemaType>, &mut Option<Vec<String>>), FastSchemaError> {
+        match self {
+            SchemaType::Annotated { schema, .. } => schema.object_parts_mut(operation),
+            SchemaType::Object { properties, required, .. } => Ok((properties, required)),
+            _ => Err(FastSchemaError::UnsupportedOperation(format!(
+                "'{}' requires an object schema", operation
+            ))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_implied_types() {
+        let schema: SchemaType = serde_json::from_value(serde_json::json!({
+            "oneOf": [{"const": "light"}, {"const": {"mode": "custom"}}, {"type": "number"}]
+        }))
+        .unwrap();
+        match &schema {
+            SchemaType::OneOf { schemas, .. } => {
+                assert_eq!(schemas[0], SchemaType::Const { value: serde_json::json!("light") });
+                assert_eq!(schemas[1], SchemaType::Const { value: serde_json::json!({"mode": "custom"}) });
+            }
+            other => panic!("expected oneOf, got {:?}", other),
+        }
+        // Serializing keeps the explicit tag
+        assert_eq!(serde_json::to_value(SchemaType::Const { value: serde_json::json!(1) }).unwrap(), serde_json::json!({"type": "const", "const": 1}));
+
+        // Annotations are kept with the schema and written back next to it
+        let annotated = serde_json::json!({"type": "string", "title": "Name", "sensitive": true, "x-widget": "text"});
+        let schema: SchemaType = serde_json::from_value(annotated.clone()).unwrap();
+        assert!(matches!(&schema, SchemaType::Annotated { metadata, .. } if metadata.sensitive));
+        assert!(matches!(schema.unannotated(), SchemaType::String { .. }));
+        let written = serde_json::to_value(&schema).unwrap();
+        assert_eq!((&written["type"], &written["title"], &written["x-widget"]), (&annotated["type"], &annotated["title"], &annotated["x-widget"]));
+        assert_eq!(serde_json::from_value::<SchemaType>(written).unwrap(), schema);
+
+        // A `type` next to a composition keeps both
+        let validator = crate::Validator::new(serde_json::from_value(serde_json::json!({
+            "type": "string",
+            "minLength": 4,
+            "oneOf": [{"const": "red"}, {"const": "green"}, {"const": "blue"}]
+        })).unwrap()).unwrap();
+        assert!(validator.validate(&serde_json::json!("green")).success);
+        assert!(!validator.validate(&serde_json::json!("purple")).success);
+        assert!(!validator.validate(&serde_json::json!("red")).success);
+        let schema: SchemaType = serde_json::from_value(serde_json::json!({"type": "number", "const": 1})).unwrap();
+        assert!(matches!(&schema, SchemaType::AllOf { schemas, .. } if schemas[1] == SchemaType::Const { value: serde_json::json!(1) }));
+
+        // A property named like a keyword is not a composition
+        let schema: SchemaType = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {"const": {"type": "string"}, "oneOf": {"oneOf": [{"type": "null"}]}}
+        })).unwrap();
+        assert_eq!(schema.get_property_names().len(), 2);
+
+        let error = serde_json::from_str::<SchemaType>(r#"{"minLength": 1}"#).unwrap_err();
+        assert!(error.to_string().contains("missing field `type`"), "{}", error);
+        assert!(crate::metaschema::validate_schema_document(&serde_json::json!({"anyOf": [{"const": 1}]})).is_empty());
+    }
+
     #[test]
     fn test_schema_compilation() {
         let schema = SchemaType::Object {
@@ -538,17 +1800,40 @@ mod tests {
                     max_length: Some(50),
                     pattern: None,
                     format: None,
+                    content_encoding: None,
+                    content_media_type: None,
+                    content_schema: None,
+                    country: None,
+                    uuid_version: None,
+                    email_options: None,
+                    min_date: None,
+                    max_date: None,
+                    offset_required: false,
+                    precision: None,
+                    allow_idn: false,
+                    length_unit: LengthUnit::Chars,
                 });
                 props.insert("age".to_string(), SchemaType::Number {
                     min: Some(0.0),
                     max: Some(120.0),
+                    exclusive_min: None,
+                    exclusive_max: None,
                     integer: true,
                     multiple_of: None,
+                    allow_non_finite: false,
+                    strict_integer: false,
+                    multiple_of_tolerance: None,
                 });
                 props
             },
             required: Some(vec!["name".to_string(), "age".to_string()]),
-            additional_properties: true,
+            additional_properties: UnknownKeys::Passthrough,
+            catchall: None,
+            min_properties: None,
+            max_properties: None,
+            read_only: None,
+            write_only: None,
+            rules: None,
         };
 
         let compiled = schema.compile();
@@ -558,6 +1843,33 @@ mod tests {
         assert_eq!(compiled.max_depth, 1);
     }
 
+    #[test]
+    fn test_compiled_schema_bytes() {
+        let schema_json = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "code": {"type": "string", "pattern": "^[A-Z]{3}$", "title": "Code"},
+                "amount": {"type": "number", "min": 0.5}
+            },
+            "required": ["code"]
+        });
+        let compiled = serde_json::from_value::<SchemaType>(schema_json.clone()).unwrap().compile();
+        assert_eq!(compiled.get_metadata("code").unwrap().title.as_deref(), Some("Code"));
+
+        let bytes = compiled.to_bytes().unwrap();
+        let restored = CompiledSchema::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.schema, compiled.schema);
+        assert_eq!(restored.metadata, compiled.metadata);
+        assert_eq!(restored.required_fields, compiled.required_fields);
+        assert_eq!((restored.max_depth, restored.estimated_complexity), (compiled.max_depth, compiled.estimated_complexity));
+
+        let validator = crate::validator::Validator::from_compiled(restored);
+        assert!(validator.validate(&serde_json::json!({"code": "ABC"})).success);
+        assert!(!validator.validate(&serde_json::json!({"code": "abc", "amount": 0})).success);
+
+        assert!(matches!(CompiledSchema::from_bytes(&bytes[..bytes.len() - 1]), Err(FastSchemaError::Decode(_))));
+    }
+
     #[test]
     fn test_complexity_estimation() {
         let simple_schema = SchemaType::String {
@@ -565,6 +1877,18 @@ mod tests {
             max_length: None,
             pattern: None,
             format: None,
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
         };
 
         let complex_schema = SchemaType::String {
@@ -572,8 +1896,92 @@ mod tests {
             max_length: Some(50),
             pattern: Some(r"^\w+$".to_string()),
             format: Some(StringFormat::Email),
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
         };
 
         assert!(complex_schema.estimate_complexity() > simple_schema.estimate_complexity());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_schema_derivation() {
+        let user: SchemaType = serde_json::from_str(r#"{
+            "type": "object",
+            "properties": {
+                "id": {"type": "string"},
+                "name": {"type": "string"},
+                "address": {
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}},
+                    "required": ["city"]
+                }
+            },
+            "required": ["id", "name", "address"]
+        }"#).unwrap();
+
+        let picked = user.pick(&["id", "name"]).unwrap();
+        assert_eq!(picked.get_property_names().len(), 2);
+        assert!(matches!(&picked, SchemaType::Object { required: Some(r), .. } if r.len() == 2));
+
+        let omitted = user.omit(&["id"]).unwrap();
+        assert!(!omitted.get_property_names().contains(&"id".to_string()));
+
+        let partial = user.partial().unwrap();
+        assert!(matches!(partial, SchemaType::Object { required: None, .. }));
+
+        // Deep partial also relaxes nested objects
+        let deep = user.deep_partial().unwrap();
+        if let SchemaType::Object { properties, .. } = &deep {
+            assert!(matches!(properties["address"], SchemaType::Object { required: None, .. }));
+        }
+
+        let required = partial.required().unwrap();
+        assert!(matches!(&required, SchemaType::Object { required: Some(r), .. } if r.len() == 3));
+
+        let mut extra = HashMap::new();
+        extra.insert("role".to_string(), SchemaType::Boolean);
+        let extended = user.extend(extra).unwrap();
+        assert_eq!(extended.get_property_names().len(), 4);
+
+        let merged = picked.merge(&omitted).unwrap();
+        assert_eq!(merged.get_property_names().len(), 3);
+
+        assert!(SchemaType::Boolean.partial().is_err());
+    }
+
+    #[test]
+    fn test_deep_partial_nested_schemas() {
+        let required = |name: &str| serde_json::json!({
+            "type": "object",
+            "properties": {name: {"type": "string"}},
+            "required": [name]
+        });
+        let order: SchemaType = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "items": {"type": "map", "keys": {"type": "string"}, "values": required("sku")},
+                "buyer": {"type": "allof", "allOf": [required("name"), required("email")]},
+                "contact": {"type": "anyof", "anyOf": [required("phone"), required("address")]},
+                "payment": {"type": "oneof", "oneOf": [required("card"), {"type": "string"}]}
+            },
+            "required": ["items", "buyer", "contact", "payment"]
+        })).unwrap();
+        let data = serde_json::json!({"items": {"a": {}}, "buyer": {}, "contact": {}, "payment": {}});
+
+        let validator = crate::validator::Validator::new(order.clone()).unwrap();
+        assert!(!validator.validate(&data).success);
+        let validator = crate::validator::Validator::new(order.deep_partial().unwrap()).unwrap();
+        assert!(validator.validate(&data).success);
+        assert!(validator.validate(&serde_json::json!({})).success);
+    }
+}
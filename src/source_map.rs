@@ -0,0 +1,176 @@
+
+//! Byte spans of the values in a JSON document, so errors found while
+//! validating raw text can point at the offending token.
+
+use crate::error::{ErrorPath, PathSegment, SourceLocation};
+use std::collections::HashMap;
+
+/// Where each value of a document starts and ends
+#[derive(Debug)]
+pub struct SourceMap<'a> {
+    text: &'a str,
+    spans: HashMap<Vec<PathSegment>, (usize, usize)>,
+    /// Byte offset of the start of every line
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Map a document that already parsed as JSON; text that does not
+    /// gives a partial map rather than an error
+    pub fn new(text: &'a str) -> Self {
+        let mut scanner = Scanner { text, pos: 0, spans: HashMap::new() };
+        scanner.value(&mut Vec::new());
+        Self::from_spans(text, scanner.spans)
+    }
+
+    /// Map a document of another format (e.g. HTML markup) from the spans
+    /// its parser recorded
+    pub(crate) fn from_spans(text: &'a str, spans: HashMap<Vec<PathSegment>, (usize, usize)>) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { text, spans, line_starts }
+    }
+
+    /// Location of the value at `path`, or of its closest ancestor for
+    /// paths that are not in the document (e.g. a missing property)
+    pub fn locate(&self, path: &ErrorPath) -> Option<SourceLocation> {
+        let segments = path.segments();
+        let (start, end) = (0..=segments.len()).rev().find_map(|len| self.spans.get(&segments[..len]))?;
+
+        let line = self.line_starts.partition_point(|&line_start| line_start <= *start);
+        let line_start = self.line_starts[line - 1];
+        Some(SourceLocation {
+            line,
+            column: self.text[line_start..*start].chars().count() + 1,
+            start: *start,
+            end: *end,
+        })
+    }
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    spans: HashMap<Vec<PathSegment>, (usize, usize)>,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip the value at the cursor, recording its span and those inside it
+    fn value(&mut self, path: &mut Vec<PathSegment>) {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b'"') => {}
+                        Some(b'}') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        _ => return,
+                    }
+                    let key_start = self.pos;
+                    self.string();
+                    let key = serde_json::from_str(&self.text[key_start..self.pos]).unwrap_or_default();
+
+                    self.skip_whitespace();
+                    if self.peek() != Some(b':') {
+                        return;
+                    }
+                    self.pos += 1;
+                    path.push(PathSegment::Key(key));
+                    self.value(path);
+                    path.pop();
+                    self.separator();
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b']') => {
+                            self.pos += 1;
+                            break;
+                        }
+                        None => return,
+                        _ => {}
+                    }
+                    path.push(PathSegment::Index(index));
+                    self.value(path);
+                    path.pop();
+                    index += 1;
+                    self.separator();
+                }
+            }
+            Some(b'"') => self.string(),
+            Some(_) => {
+                while !matches!(self.peek(), None | Some(b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')) {
+                    self.pos += 1;
+                }
+            }
+            None => return,
+        }
+
+        // Later duplicates win, as they do when parsing
+        self.spans.insert(path.clone(), (start, self.pos));
+    }
+
+    /// Skip the string starting at the cursor
+    fn string(&mut self) {
+        self.pos += 1;
+        let mut escaped = false;
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn separator(&mut self) {
+        self.skip_whitespace();
+        if self.peek() == Some(b',') {
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_locations() {
+        let text = "{\n  \"name\": \"Ann\",\n  \"tags\": [1, \"x\\\"]\"],\n  \"é\": {\"a/b\": null}\n}";
+        let map = SourceMap::new(text);
+        let locate = |path: &str| map.locate(&ErrorPath::parse(path)).map(|l| (l.line, l.column, &text[l.start..l.end]));
+
+        assert_eq!(locate(""), Some((1, 1, text)));
+        assert_eq!(locate("name"), Some((2, 11, "\"Ann\"")));
+        assert_eq!(locate("tags.[1]"), Some((3, 15, "\"x\\\"]\"")));
+        assert_eq!(locate("é.a/b"), Some((4, 16, "null")));
+        // Missing values point at the closest ancestor
+        assert_eq!(locate("é.missing").map(|l| l.2), Some("{\"a/b\": null}"));
+        assert_eq!(locate("tags.[5]").map(|l| (l.0, l.1)), Some((3, 11)));
+    }
+}
@@ -0,0 +1,339 @@
+
+//! Push-based validation of a JSON array (or NDJSON) arriving in chunks
+//! (e.g. a download in progress): elements are validated as soon as they
+//! are complete, so the whole document never has to be buffered.
+
+use crate::error::{FastSchemaError, ValidationResult};
+use crate::utils::PerformanceTracker;
+use crate::validator::BatchValidator;
+use serde::{Deserialize, Serialize};
+
+/// Splits the text of a JSON array into its top-level elements
+#[derive(Debug, Default)]
+struct ArrayScanner {
+    started: bool,
+    closed: bool,
+    /// Nesting depth inside the current element
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// A `,` was seen, so another element must follow
+    after_comma: bool,
+    element: String,
+}
+
+impl ArrayScanner {
+    fn feed(&mut self, chunk: &str) -> Result<Vec<serde_json::Value>, FastSchemaError> {
+        let mut elements = Vec::new();
+
+        for c in chunk.chars() {
+            if self.closed {
+                if !c.is_whitespace() {
+                    return Err(FastSchemaError::Stream("Unexpected data after the end of the array".to_string()));
+                }
+                continue;
+            }
+            if !self.started {
+                match c {
+                    '[' => self.started = true,
+                    c if c.is_whitespace() => {}
+                    _ => return Err(FastSchemaError::Stream("Expected '[' at the start of the array".to_string())),
+                }
+                continue;
+            }
+
+            if self.in_string {
+                self.element.push(c);
+                if self.escaped {
+                    self.escaped = false;
+                } else if c == '\\' {
+                    self.escaped = true;
+                } else if c == '"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match c {
+                '"' => {
+                    self.in_string = true;
+                    self.element.push(c);
+                }
+                '[' | '{' => {
+                    self.depth += 1;
+                    self.element.push(c);
+                }
+                ']' | '}' if self.depth > 0 => {
+                    self.depth -= 1;
+                    self.element.push(c);
+                }
+                ',' if self.depth == 0 => {
+                    elements.push(self.take_element()?);
+                    self.after_comma = true;
+                }
+                ']' if self.depth == 0 => {
+                    if self.after_comma || !self.element.trim().is_empty() {
+                        elements.push(self.take_element()?);
+                    }
+                    self.closed = true;
+                }
+                _ => self.element.push(c),
+            }
+        }
+
+        Ok(elements)
+    }
+
+    fn take_element(&mut self) -> Result<serde_json::Value, FastSchemaError> {
+        if self.element.trim().is_empty() {
+            return Err(FastSchemaError::Stream("Missing array element".to_string()));
+        }
+        self.after_comma = false;
+        let value = serde_json::from_str(&self.element)?;
+        self.element.clear();
+        Ok(value)
+    }
+}
+
+/// Splits NDJSON text into one value per non-blank line
+#[derive(Debug, Default)]
+struct LineScanner {
+    line: String,
+    /// Lines completed so far, for error messages
+    line_number: usize,
+}
+
+impl LineScanner {
+    fn feed(&mut self, chunk: &str) -> Result<Vec<serde_json::Value>, FastSchemaError> {
+        let mut values = Vec::new();
+        for piece in chunk.split_inclusive('\n') {
+            self.line.push_str(piece);
+            if self.line.ends_with('\n') {
+                values.extend(self.take_line()?);
+            }
+        }
+        Ok(values)
+    }
+
+    fn take_line(&mut self) -> Result<Option<serde_json::Value>, FastSchemaError> {
+        self.line_number += 1;
+        let line = std::mem::take(&mut self.line);
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_str(line)
+            .map(Some)
+            .map_err(|e| FastSchemaError::Stream(format!("Invalid JSON on line {}: {}", self.line_number, e)))
+    }
+}
+
+#[derive(Debug)]
+enum Framing {
+    Array(ArrayScanner),
+    Lines(LineScanner),
+}
+
+/// Totals for a finished stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamSummary {
+    pub total_items: usize,
+    pub valid_items: usize,
+    pub invalid_items: usize,
+    pub validation_time_ms: f64,
+    pub throughput: f64,
+}
+
+/// An array being validated chunk by chunk with a `BatchValidator`
+#[derive(Debug)]
+pub struct BatchStream {
+    framing: Framing,
+    /// Bytes of a UTF-8 sequence split across `feed_bytes` chunks
+    partial_char: Vec<u8>,
+    performance: PerformanceTracker,
+    valid_items: usize,
+    invalid_items: usize,
+}
+
+impl BatchStream {
+    pub fn new() -> Self {
+        Self::with_framing(Framing::Array(ArrayScanner::default()))
+    }
+
+    /// A stream of newline-delimited JSON values instead of an array
+    pub fn ndjson() -> Self {
+        Self::with_framing(Framing::Lines(LineScanner::default()))
+    }
+
+    fn with_framing(framing: Framing) -> Self {
+        Self {
+            framing,
+            partial_char: Vec::new(),
+            performance: PerformanceTracker::new(),
+            valid_items: 0,
+            invalid_items: 0,
+        }
+    }
+
+    /// Feed the next chunk of text; returns results for the elements it
+    /// completed, indexed by their position in the whole stream
+    pub fn feed(&mut self, validator: &BatchValidator, chunk: &str) -> Result<Vec<ValidationResult>, FastSchemaError> {
+        let values = match &mut self.framing {
+            Framing::Array(scanner) => scanner.feed(chunk)?,
+            Framing::Lines(scanner) => scanner.feed(chunk)?,
+        };
+        Ok(self.validate(validator, &values))
+    }
+
+    /// Feed the next chunk of UTF-8 bytes, which may end mid-character
+    pub fn feed_bytes(&mut self, validator: &BatchValidator, chunk: &[u8]) -> Result<Vec<ValidationResult>, FastSchemaError> {
+        self.partial_char.extend_from_slice(chunk);
+        let complete = match std::str::from_utf8(&self.partial_char) {
+            Ok(text) => text.len(),
+            // An incomplete sequence at the end is completed by the next chunk
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => return Err(FastSchemaError::Stream("Invalid UTF-8 in the stream".to_string())),
+        };
+
+        let rest = self.partial_char.split_off(complete);
+        let text = String::from_utf8(std::mem::replace(&mut self.partial_char, rest))
+            .map_err(|e| FastSchemaError::Internal(e.to_string()))?;
+        self.feed(validator, &text)
+    }
+
+    /// Validate a final NDJSON line that has no trailing newline; array
+    /// streams have nothing to flush
+    pub fn flush(&mut self, validator: &BatchValidator) -> Result<Vec<ValidationResult>, FastSchemaError> {
+        let values: Vec<_> = match &mut self.framing {
+            Framing::Lines(scanner) if !scanner.line.is_empty() => scanner.take_line()?.into_iter().collect(),
+            _ => Vec::new(),
+        };
+        Ok(self.validate(validator, &values))
+    }
+
+    fn validate(&mut self, validator: &BatchValidator, values: &[serde_json::Value]) -> Vec<ValidationResult> {
+        let results = validator.validate_partition(self.total_items(), values);
+
+        let valid = results.iter().filter(|result| result.success).count();
+        self.valid_items += valid;
+        self.invalid_items += results.len() - valid;
+        self.performance.increment_items(results.len());
+
+        results
+    }
+
+    pub fn total_items(&self) -> usize {
+        self.valid_items + self.invalid_items
+    }
+
+    /// End the stream, failing if the array was never closed or data was
+    /// left unvalidated (call `flush` first for NDJSON)
+    pub fn finish(self) -> Result<StreamSummary, FastSchemaError> {
+        if !self.partial_char.is_empty() {
+            return Err(FastSchemaError::Stream("Incomplete UTF-8 sequence at the end of the stream".to_string()));
+        }
+        match &self.framing {
+            Framing::Array(scanner) if !scanner.closed => {
+                return Err(FastSchemaError::Stream("Unexpected end of the array".to_string()));
+            }
+            Framing::Lines(scanner) if !scanner.line.trim().is_empty() => {
+                return Err(FastSchemaError::Stream("Unflushed line at the end of the stream".to_string()));
+            }
+            _ => {}
+        }
+
+        Ok(StreamSummary {
+            total_items: self.total_items(),
+            valid_items: self.valid_items,
+            invalid_items: self.invalid_items,
+            validation_time_ms: self.performance.elapsed_ms(),
+            throughput: self.performance.throughput(),
+        })
+    }
+}
+
+impl Default for BatchStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn batch() -> BatchValidator {
+        let schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        })).unwrap();
+        BatchValidator::new(schema, 2).unwrap()
+    }
+
+    #[test]
+    fn test_stream_chunks_split_anywhere() {
+        let validator = batch();
+        let text = r#" [{"name": "a, [b]"}, {"name": "c\"]"}, {}, {"name": 1} ] "#;
+
+        for split in 0..text.len() {
+            let mut stream = BatchStream::new();
+            let mut results = stream.feed(&validator, &text[..split]).unwrap();
+            results.extend(stream.feed(&validator, &text[split..]).unwrap());
+
+            assert_eq!(results.iter().map(|r| r.success).collect::<Vec<_>>(), vec![true, true, false, false]);
+            assert_eq!(results[3].errors[0].path, "[3].name");
+
+            let summary = stream.finish().unwrap();
+            assert_eq!((summary.total_items, summary.valid_items, summary.invalid_items), (4, 2, 2));
+        }
+    }
+
+    #[test]
+    fn test_ndjson_bytes() {
+        let validator = batch();
+        let text = "{\"name\": \"Zoë\"}\n\n{}\r\n{\"name\": \"日本\"}";
+
+        for split in 0..text.len() {
+            let mut stream = BatchStream::ndjson();
+            let mut results = stream.feed_bytes(&validator, &text.as_bytes()[..split]).unwrap();
+            results.extend(stream.feed_bytes(&validator, &text.as_bytes()[split..]).unwrap());
+            results.extend(stream.flush(&validator).unwrap());
+
+            assert_eq!(results.iter().map(|r| r.success).collect::<Vec<_>>(), vec![true, false, true]);
+            assert_eq!(results[1].errors[0].path, "[1].name");
+            assert_eq!(stream.finish().unwrap().total_items, 3);
+        }
+
+        let mut stream = BatchStream::ndjson();
+        let error = stream.feed(&validator, "{}\nnope\n").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        let mut stream = BatchStream::ndjson();
+        stream.feed(&validator, "{}").unwrap();
+        assert!(stream.finish().is_err());
+
+        let mut stream = BatchStream::new();
+        stream.feed_bytes(&validator, &"[\"é".as_bytes()[..3]).unwrap();
+        assert!(stream.finish().is_err());
+        assert!(BatchStream::new().feed_bytes(&validator, b"[\xff]").is_err());
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let validator = batch();
+        let feed = |text: &str| {
+            let mut stream = BatchStream::new();
+            stream.feed(&validator, text).and_then(|_| stream.finish())
+        };
+
+        assert_eq!(feed("[]").unwrap().total_items, 0);
+        assert!(feed(r#"[{"name": "a"}"#).is_err());
+        assert!(feed(r#"[{"name": "a"},]"#).is_err());
+        assert!(feed("[,]").is_err());
+        assert!(feed("{}").is_err());
+        assert!(feed("[] 1").is_err());
+        assert!(feed("[nope]").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+
+//! TOML input, converted to `serde_json::Value` so it validates like any
+//! JSON payload. Date-times become RFC 3339 strings, which the `date-time`,
+//! `date` and `time` formats check.
+
+use crate::error::FastSchemaError;
+use serde_json::{Map, Number, Value};
+use toml_edit::{DocumentMut, Item, Table};
+
+/// Parse a TOML document
+pub fn parse_toml(text: &str) -> Result<Value, FastSchemaError> {
+    let document: DocumentMut = text
+        .parse()
+        .map_err(|error: toml_edit::TomlError| FastSchemaError::InputParsing(format!("TOML {}", error).trim_end().to_string()))?;
+    convert_table(document.as_table())
+}
+
+fn convert_table(table: &Table) -> Result<Value, FastSchemaError> {
+    let mut map = Map::new();
+    for (key, item) in table.iter() {
+        map.insert(key.to_string(), convert_item(item)?);
+    }
+    Ok(Value::Object(map))
+}
+
+fn convert_item(item: &Item) -> Result<Value, FastSchemaError> {
+    match item {
+        Item::None => Ok(Value::Null),
+        Item::Value(value) => convert_value(value),
+        Item::Table(table) => convert_table(table),
+        Item::ArrayOfTables(tables) => tables.iter().map(convert_table).collect::<Result<_, _>>().map(Value::Array),
+    }
+}
+
+fn convert_value(value: &toml_edit::Value) -> Result<Value, FastSchemaError> {
+    use toml_edit::Value as Toml;
+
+    Ok(match value {
+        Toml::String(text) => Value::String(text.value().clone()),
+        Toml::Integer(number) => Value::from(*number.value()),
+        Toml::Float(number) => Value::Number(Number::from_f64(*number.value()).ok_or_else(|| {
+            FastSchemaError::InputParsing(format!("TOML float '{}' has no JSON representation", number.value()))
+        })?),
+        Toml::Boolean(flag) => Value::Bool(*flag.value()),
+        Toml::Datetime(datetime) => Value::String(datetime.value().to_string()),
+        Toml::Array(items) => Value::Array(items.iter().map(convert_value).collect::<Result<_, _>>()?),
+        Toml::InlineTable(table) => {
+            let mut map = Map::new();
+            for (key, value) in table.iter() {
+                map.insert(key.to_string(), convert_value(value)?);
+            }
+            Value::Object(map)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_toml() {
+        let toml = r#"
+title = "service"
+ports = [8080, 8081]
+ratio = 0.5
+started = 2024-01-02T03:04:05Z
+
+[database]
+url = "postgres://localhost/app"
+pool = { min = 1, max = 8 }
+
+[[workers]]
+name = "mailer"
+"#;
+        assert_eq!(
+            parse_toml(toml).unwrap(),
+            json!({
+                "title": "service",
+                "ports": [8080, 8081],
+                "ratio": 0.5,
+                "started": "2024-01-02T03:04:05Z",
+                "database": {"url": "postgres://localhost/app", "pool": {"min": 1, "max": 8}},
+                "workers": [{"name": "mailer"}]
+            })
+        );
+        assert!(parse_toml("a = ").unwrap_err().to_string().contains("TOML"));
+        assert!(parse_toml("a = nan").is_err());
+    }
+}
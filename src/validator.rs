@@ -1,268 +1,1284 @@
 // src/validator.rs
-use crate::error::{ValidationResult, ValidationError, ErrorCode};
-use crate::schema::{SchemaType, StringFormat, CompiledSchema};
+use crate::de::{deserialization_error, ValidatingDeserializer};
+use crate::dialect::Dialect;
+use crate::legacy::normalize_legacy_keywords;
+use crate::error::{ErrorIssue, ErrorMap, ErrorPath, PathSegment, Severity, ValidationResult, ValidationError, ErrorCode};
+use crate::cancel::CancellationToken;
+use crate::clock::Instant;
+use crate::formats::FormatRegistry;
+use crate::hostname;
+use crate::keywords::{self, KeywordValidator};
+use crate::locale;
+use crate::memo::{self, SharedMemo};
+use crate::metrics::Metrics;
+use crate::plan::accepts;
+use crate::profile::{ProfileNode, Profiler};
+use crate::regex_cache;
+use crate::source_map::SourceMap;
+use crate::schema::{
+    SchemaType, StringFormat, CompiledSchema, Comparison, ContentEncoding, EmailOptions, LengthUnit, ObjectRule,
+    SchemaMetadata, SortOrder, UnknownKeys, Unevaluated, display_metadata_path
+};
 use crate::utils::{
-    validate_string_format, UniqueChecker, ValidationContext,
-    ValidationOptions, SchemaOptimizer, is_integer
+    check_input_limits, closest_match, validate_string_format, UniqueChecker, ValidationContext,
+    ValidationOptions, SchemaOptimizer, PerformanceTracker, AccessMode, Redaction, UnknownFormats, DEFAULT_MULTIPLE_OF_TOLERANCE, is_integer,
+    is_multiple, is_nanoid_alphabet, is_valid_email_strict, is_valid_latitude, is_valid_longitude, json_equal,
+    parse_date_bound, parse_full_date, parse_int64, parse_iso_duration, non_finite_name, parse_non_finite, parse_rfc3339_date_time,
+    uuid_version_of
 };
 use regex::Regex;
+use rust_decimal::Decimal;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
-use std::sync::Arc;
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
 
-/// Main validation engine
+/// Main validation engine. Validation takes `&self`, so one compiled
+/// validator can be shared across threads behind an `Arc`.
 pub struct Validator {
     pub compiled_schema: CompiledSchema,
-    pub regex_cache: HashMap<String, Arc<Regex>>,
+    /// Named schemas that `$ref` nodes resolve against
+    pub definitions: Arc<HashMap<String, SchemaType>>,
+    /// User-registered string formats
+    pub formats: Arc<FormatRegistry>,
+    /// Regexes for the schema's and definitions' patterns, compiled when
+    /// the validator is built (`None` for invalid patterns)
+    patterns: Arc<HashMap<String, Option<Arc<Regex>>>>,
+    /// Registered custom keywords, by keyword
+    keywords: Arc<HashMap<String, Arc<dyn KeywordValidator>>>,
+    /// Rewrites the message of every error in a result
+    error_map: Option<ErrorMap>,
+    /// Accumulated from validations run with `ValidationOptions::profile`
+    profile: Mutex<Profiler>,
+    /// Counters reported by `stats`
+    metrics: Mutex<Metrics>,
+}
+
+/// Clones share the compiled plan, definitions, formats, keywords and error
+/// map; the profile and stats start empty
+impl Clone for Validator {
+    fn clone(&self) -> Self {
+        Self {
+            compiled_schema: self.compiled_schema.clone(),
+            definitions: Arc::clone(&self.definitions),
+            formats: Arc::clone(&self.formats),
+            patterns: Arc::clone(&self.patterns),
+            keywords: Arc::clone(&self.keywords),
+            error_map: self.error_map.clone(),
+            profile: Mutex::new(Profiler::new()),
+            metrics: Mutex::new(Metrics::new()),
+        }
+    }
 }
 
 impl Validator {
-    /// Create a new validator with compiled schema
+    /// Create a new validator with compiled schema. Formats that are not
+    /// built in fail to compile; see `new_with_formats`.
     pub fn new(schema: SchemaType) -> Result<Self, crate::error::FastSchemaError> {
-        let compiled_schema = schema.compile();
+        Self::new_with_formats(schema, Arc::new(FormatRegistry::new()))
+    }
 
-        Ok(Self {
+    /// Create a validator whose schema may use the custom string formats of
+    /// `formats`; any other unknown format name fails to compile
+    pub fn new_with_formats(schema: SchemaType, formats: Arc<FormatRegistry>) -> Result<Self, crate::error::FastSchemaError> {
+        Self::from_compiled(schema.compile()).with_formats(formats)
+    }
+
+    /// Create a validator for an already compiled schema, e.g. one from
+    /// `CompiledSchema::from_bytes`
+    pub fn from_compiled(compiled_schema: CompiledSchema) -> Self {
+        let mut patterns = HashMap::new();
+        resolve_patterns(&mut patterns, [&compiled_schema.schema]);
+        Self {
             compiled_schema,
-            regex_cache: HashMap::new(),
-        })
+            definitions: Arc::new(HashMap::new()),
+            formats: Arc::new(FormatRegistry::new()),
+            patterns: Arc::new(patterns),
+            keywords: Arc::new(HashMap::new()),
+            error_map: None,
+            profile: Mutex::new(Profiler::new()),
+            metrics: Mutex::new(Metrics::new()),
+        }
+    }
+
+    /// Use a shared set of custom string formats, which must cover every
+    /// format the schema and its definitions name
+    pub fn with_formats(mut self, formats: Arc<FormatRegistry>) -> Result<Self, crate::error::FastSchemaError> {
+        self.formats = formats;
+        self.check_formats(std::iter::once(&self.compiled_schema.schema).chain(self.definitions.values()))?;
+        Ok(self)
+    }
+
+    /// Fail on string formats that are neither built in nor registered
+    fn check_formats<'a>(&self, schemas: impl IntoIterator<Item = &'a SchemaType>) -> Result<(), crate::error::FastSchemaError> {
+        let mut names = Vec::new();
+        for schema in schemas {
+            schema.collect_custom_formats(&mut names);
+        }
+        match names.into_iter().find(|name| self.formats.get(name).is_none()) {
+            Some(name) => Err(crate::error::FastSchemaError::SchemaCompilation(format!(
+                "Unknown string format '{}'; register it before compiling the schema",
+                name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Register a custom string format matched by a regex
+    pub fn register_format_pattern(&mut self, name: &str, pattern: &str) -> Result<(), crate::error::FastSchemaError> {
+        Arc::make_mut(&mut self.formats).register_pattern(name, pattern)
+    }
+
+    /// Register a custom string format checked by a closure
+    pub fn register_format<F>(&mut self, name: &str, callback: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.formats).register_fn(name, callback);
+    }
+
+    /// Validate the values marked with a custom keyword. Every use of the
+    /// keyword in the schema must pass its `compile` check; an earlier
+    /// validator for the same keyword is replaced.
+    pub fn register_keyword<K: KeywordValidator + 'static>(&mut self, keyword: K) -> Result<(), crate::error::FastSchemaError> {
+        let added = self.keyword_complexity(&keyword)?;
+        let replaced = match self.keywords.get(keyword.keyword()) {
+            Some(previous) => self.keyword_complexity(previous.as_ref())?,
+            None => 0,
+        };
+        self.compiled_schema.estimated_complexity = self.compiled_schema.estimated_complexity - replaced + added;
+        Arc::make_mut(&mut self.keywords).insert(keyword.keyword().to_string(), Arc::new(keyword));
+        Ok(())
+    }
+
+    /// Run `keyword`'s compile check over its uses, totalling their complexity
+    fn keyword_complexity(&self, keyword: &dyn KeywordValidator) -> Result<usize, crate::error::FastSchemaError> {
+        let mut complexity = 0;
+        for (path, metadata) in &self.compiled_schema.metadata {
+            if let Some(argument) = metadata.extensions.get(keyword.keyword()) {
+                keyword.compile(argument).map_err(|message| {
+                    crate::error::FastSchemaError::SchemaCompilation(format!(
+                        "{} at '{}': {}",
+                        keyword.keyword(),
+                        display_metadata_path(path),
+                        message
+                    ))
+                })?;
+                complexity += keyword.complexity(argument);
+            }
+        }
+        Ok(complexity)
+    }
+
+    /// Add errors from registered keywords for `value`, which is at the
+    /// context's current path
+    fn validate_keywords(&self, value: &serde_json::Value, errors: &mut Vec<ValidationError>, context: &mut ValidationContext) {
+        if self.keywords.is_empty() || !context.should_continue(errors.len()) {
+            return;
+        }
+
+        let mut uses: Vec<(&Vec<PathSegment>, &SchemaMetadata)> = self
+            .compiled_schema
+            .metadata
+            .iter()
+            .filter(|(_, metadata)| !metadata.extensions.is_empty())
+            .collect();
+        uses.sort_by_key(|(path, _)| display_metadata_path(path));
+
+        let base = context.path.build();
+        for (path, metadata) in uses {
+            for (name, argument) in &metadata.extensions {
+                let keyword = match self.keywords.get(name) {
+                    Some(keyword) => keyword,
+                    None => continue,
+                };
+                for (segments, found) in keywords::values_at(value, path) {
+                    if let Err(message) = keyword.validate(argument, found) {
+                        let mut full = base.segments().to_vec();
+                        full.extend(segments);
+                        errors.push(ValidationError::with_params(
+                            ErrorPath::new(full),
+                            message,
+                            ErrorCode::CustomKeyword,
+                            serde_json::json!({"keyword": name, "code": keyword.error_code()}),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rewrite error messages with `map`, which gets each error's code,
+    /// path and parameters; returning `None` keeps the default message
+    pub fn set_error_map<F>(&mut self, map: F)
+    where
+        F: Fn(&ErrorIssue) -> Option<String> + Send + Sync + 'static,
+    {
+        self.error_map = Some(Arc::new(map));
+    }
+
+    pub fn clear_error_map(&mut self) {
+        self.error_map = None;
+    }
+
+    /// Deduplicate the findings of a validation and apply the per-path
+    /// limit, severity overrides, the error map and the path style, moving
+    /// warnings out of `errors`
+    fn finish_errors(
+        &self,
+        value: &serde_json::Value,
+        errors: &mut Vec<ValidationError>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        // Batch items are validated under their index
+        let base = context.path.build().segments().len();
+        let options = &context.options;
+        let mut seen = HashSet::new();
+        let mut per_path: HashMap<Vec<PathSegment>, usize> = HashMap::new();
+        let mut truncated = false;
+        errors.retain(|error| {
+            let path = error.path.segments().to_vec();
+            if !seen.insert((path.clone(), error.code.clone())) {
+                return false;
+            }
+            match options.max_errors_per_path {
+                Some(limit) => {
+                    let count = per_path.entry(path).or_default();
+                    *count += 1;
+                    truncated |= *count > limit;
+                    *count <= limit
+                }
+                None => true,
+            }
+        });
+
+        for error in errors.iter_mut() {
+            let segments = error.path.segments().get(base..).unwrap_or_default();
+            let redaction = if self.compiled_schema.is_sensitive(segments) { Redaction::Full } else { options.redact_values };
+            if redaction != Redaction::Off {
+                redact(error, value_at(value, segments), redaction);
+            }
+            if let Some(severity) = options.severities.get(&error.code) {
+                error.severity = *severity;
+            }
+            if let Some(message) = self.error_map.as_ref().and_then(|map| map(&error.issue())) {
+                error.message = message;
+            }
+            error.path.set_style(options.path_style);
+        }
+
+        let (warnings, remaining) = std::mem::take(errors)
+            .into_iter()
+            .partition(|error| error.severity == Severity::Warning);
+        *errors = remaining;
+        context.truncated |= truncated;
+        warnings
+    }
+
+    /// Resolve `$ref` nodes against a set of named schemas
+    /// Fails if references reachable from the schema form a cycle that
+    /// never descends into the value, such as `A -> B -> A`
+    pub fn with_definitions(mut self, definitions: Arc<HashMap<String, SchemaType>>) -> Result<Self, crate::error::FastSchemaError> {
+        if let Some(cycle) = reference_cycle(&self.compiled_schema.schema, &definitions) {
+            return Err(crate::error::FastSchemaError::SchemaCompilation(format!(
+                "Schema references form a cycle that never validates a nested value: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        self.check_formats(definitions.values())?;
+        // Referenced schemas may need the output pass or key tracking too
+        self.compiled_schema.has_transforms |= definitions.values().any(|s| s.has_transforms());
+        self.compiled_schema.has_unevaluated |= definitions.values().any(|s| s.has_unevaluated());
+        resolve_patterns(Arc::make_mut(&mut self.patterns), definitions.values());
+        self.definitions = definitions;
+        Ok(self)
+    }
+
+    /// Create a validator from a schema document, keeping its annotations
+    ///
+    /// Documents whose `$schema` is draft 2020-12 are read as standard JSON
+    /// Schema, and draft-07 ones as `zod-to-json-schema` output; see
+    /// `from_json_with_dialect`. Otherwise draft-07 spellings
+    /// such as `minimum` are accepted and listed in
+    /// `compiled_schema.warnings`.
+    pub fn from_json(schema_json: &serde_json::Value) -> Result<Self, crate::error::FastSchemaError> {
+        Self::from_json_with_formats(schema_json, Arc::new(FormatRegistry::new()))
+    }
+
+    /// Create a validator from a schema document that may use the custom
+    /// string formats of `formats`
+    pub fn from_json_with_formats(
+        schema_json: &serde_json::Value,
+        formats: Arc<FormatRegistry>,
+    ) -> Result<Self, crate::error::FastSchemaError> {
+        Self::compile_json(schema_json, Dialect::detect(schema_json), formats)
+    }
+
+    /// Create a validator from a schema document in the given dialect
+    pub fn from_json_with_dialect(
+        schema_json: &serde_json::Value,
+        dialect: Dialect,
+    ) -> Result<Self, crate::error::FastSchemaError> {
+        Self::compile_json(schema_json, dialect, Arc::new(FormatRegistry::new()))
+    }
+
+    fn compile_json(
+        schema_json: &serde_json::Value,
+        dialect: Dialect,
+        formats: Arc<FormatRegistry>,
+    ) -> Result<Self, crate::error::FastSchemaError> {
+        let validator = match dialect {
+            Dialect::FastSchema => {
+                let (normalized, warnings) = normalize_legacy_keywords(schema_json);
+                let mut validator = Self::new_with_formats(serde_json::from_value(normalized)?, formats)?;
+                validator.compiled_schema.warnings = warnings;
+                validator
+            }
+            Dialect::Draft202012 => {
+                let translated = crate::dialect::translate(schema_json)?;
+                Self::new_with_formats(translated.schema, formats)?.with_definitions(Arc::new(translated.definitions))?
+            }
+            Dialect::ZodToJsonSchema => {
+                let translated = crate::dialect::translate(&crate::zod::to_draft_2020_12(schema_json))?;
+                Self::new_with_formats(translated.schema, formats)?.with_definitions(Arc::new(translated.definitions))?
+            }
+        };
+        Ok(validator)
+    }
+
+    /// Compile another schema document into a validator sharing this one's
+    /// custom formats, keywords, definitions and error map
+    pub fn recompile(&self, schema_json: &serde_json::Value) -> Result<Self, crate::error::FastSchemaError> {
+        let mut validator = Self::from_json_with_formats(schema_json, Arc::clone(&self.formats))?;
+        // A draft 2020-12 document brings its own `$ref` targets
+        let definitions = if validator.definitions.is_empty() {
+            Arc::clone(&self.definitions)
+        } else {
+            let mut definitions = (*self.definitions).clone();
+            definitions.extend(validator.definitions.iter().map(|(name, schema)| (name.clone(), schema.clone())));
+            Arc::new(definitions)
+        };
+        validator = validator.with_definitions(definitions)?;
+        validator.error_map = self.error_map.clone();
+        for keyword in self.keywords.values() {
+            validator.compiled_schema.estimated_complexity += validator.keyword_complexity(keyword.as_ref())?;
+        }
+        validator.keywords = Arc::clone(&self.keywords);
+        Ok(validator)
+    }
+
+    /// Annotations (title, description, examples, default, meta) for a schema path
+    pub fn get_metadata(&self, path: &str) -> Option<&SchemaMetadata> {
+        self.compiled_schema.get_metadata(path)
     }
 
     /// Validate a single value
-    pub fn validate(&mut self, value: &serde_json::Value) -> ValidationResult {
-        let options = ValidationOptions::default();
-        let mut context = ValidationContext::new(options);
+    pub fn validate(&self, value: &serde_json::Value) -> ValidationResult {
+        self.validate_with_options(value, ValidationOptions::default())
+    }
 
-        let schema = &self.compiled_schema.schema.clone();
-        let errors = self.validate_value(value, schema, &mut context);
+    /// Validate a single value with custom options
+    pub fn validate_with_options(
+        &self,
+        value: &serde_json::Value,
+        options: ValidationOptions,
+    ) -> ValidationResult {
+        let mut context = self.create_context(options);
 
-        if errors.is_empty() {
-            if context.options.enable_performance_tracking {
-                ValidationResult::success_with_stats(value.clone(), context.performance.finish())
+        let mut errors = if let Some(error) = check_input_limits(value, &context.options) {
+            vec![error]
+        } else {
+            self.validate_root(value, &mut context)
+        };
+        if context.options.paths.is_none() {
+            self.validate_keywords(value, &mut errors, &mut context);
+        }
+        self.merge_profile(&context);
+        check_deadline(String::new(), &mut errors, &mut context);
+
+        let warnings = self.finish_errors(value, &mut errors, &mut context);
+        lock(&self.metrics).record(&errors);
+        let mut result = if errors.is_empty() {
+            let mut result = if !context.options.return_data {
+                ValidationResult::valid()
+            } else if self.needs_output_pass(&context.options) {
+                ValidationResult::success(output_value(value, &self.compiled_schema.schema, &context.options, &self.definitions))
             } else {
                 ValidationResult::success(value.clone())
+            };
+
+            if context.options.enable_performance_tracking {
+                result.performance = Some(context.performance.finish());
             }
+            result
         } else {
-            if context.options.enable_performance_tracking {
+            let result = if context.options.enable_performance_tracking {
                 ValidationResult::failure_with_stats(errors, context.performance.finish())
             } else {
                 ValidationResult::failure(errors)
+            };
+            result.with_truncation(context.truncated)
+        };
+        result.warnings = warnings;
+        result
+    }
+
+    /// Add a profiled run to the validator's profile and node counts
+    fn merge_profile(&self, context: &ValidationContext) {
+        if let Some(profiler) = &context.profiler {
+            let profiler = lock(profiler);
+            lock(&self.profile).merge(&profiler);
+            lock(&self.metrics).add_nodes(profiler.hits_by_type());
+        }
+    }
+
+    /// Run the plan, or only the requested paths
+    fn validate_root(&self, value: &serde_json::Value, context: &mut ValidationContext) -> Vec<ValidationError> {
+        match context.options.paths.take() {
+            Some(paths) => {
+                let errors = self.validate_paths(value, &paths, context);
+                context.options.paths = Some(paths);
+                errors
             }
+            // The plan's closures are opaque, so profile the interpreter
+            None if context.profiler.is_some() => self.validate_value(value, &self.compiled_schema.schema, context),
+            None => {
+                let plan = Arc::clone(&self.compiled_schema.plan);
+                plan.run(self, value, context)
+            }
+        }
+    }
+
+    /// Validate a raw JSON document. The parsed document is moved into
+    /// `data` rather than copied.
+    pub fn validate_raw(
+        &self,
+        raw: &RawValue,
+        options: ValidationOptions,
+    ) -> Result<ValidationResult, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(raw.get())?;
+        // Data that needs no rewriting is moved in once validation is done
+        let echo = options.return_data && !self.needs_output_pass(&options);
+        let source_locations = options.source_locations;
+
+        let options = ValidationOptions { return_data: options.return_data && !echo, ..options };
+        let mut result = self.validate_with_options(&value, options);
+        if result.success && echo {
+            result.data = Some(value);
+        }
+
+        if source_locations && !(result.errors.is_empty() && result.warnings.is_empty()) {
+            let map = SourceMap::new(raw.get());
+            for error in result.errors.iter_mut().chain(result.warnings.iter_mut()) {
+                error.location = map.locate(&error.path);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Validate a MessagePack payload, decoded without a JSON round-trip
+    pub fn validate_msgpack(&self, bytes: &[u8]) -> Result<ValidationResult, crate::error::FastSchemaError> {
+        Ok(self.validate(&crate::packed::decode_msgpack(bytes)?))
+    }
+
+    /// Validate a CBOR payload, decoded without a JSON round-trip
+    pub fn validate_cbor(&self, bytes: &[u8]) -> Result<ValidationResult, crate::error::FastSchemaError> {
+        Ok(self.validate(&crate::packed::decode_cbor(bytes)?))
+    }
+
+    /// Validate a JSON5 or JSONC document, with comments and trailing commas
+    pub fn validate_json5(&self, text: &str) -> Result<ValidationResult, crate::error::FastSchemaError> {
+        Ok(self.validate(&crate::json5::parse_json5(text)?))
+    }
+
+    /// Validate HTML markup as an element tree (see `parse_html`). Every
+    /// error carries the location of the element or attribute it is about.
+    pub fn validate_html(&self, markup: &str) -> Result<ValidationResult, crate::error::FastSchemaError> {
+        let (tree, map) = crate::markup::parse_html_with_spans(markup)?;
+        let mut result = self.validate(&tree);
+        for error in result.errors.iter_mut().chain(result.warnings.iter_mut()) {
+            error.location = map.locate(&error.path);
+        }
+        Ok(result)
+    }
+
+    /// Validate a YAML document, such as a configuration file
+    #[cfg(feature = "yaml")]
+    pub fn validate_yaml(&self, yaml: &str) -> Result<ValidationResult, crate::error::FastSchemaError> {
+        Ok(self.validate(&crate::yaml::parse_yaml(yaml)?))
+    }
+
+    /// Validate a TOML document, such as a configuration file
+    #[cfg(feature = "toml")]
+    pub fn validate_toml(&self, toml: &str) -> Result<ValidationResult, crate::error::FastSchemaError> {
+        Ok(self.validate(&crate::toml::parse_toml(toml)?))
+    }
+
+    /// Validate `value`, then deserialize the validated data (with
+    /// transforms such as stripped keys applied) into `T`
+    pub fn parse_into<T: DeserializeOwned>(&self, value: &serde_json::Value) -> Result<T, Vec<ValidationError>> {
+        let result = self.validate_with_options(value, ValidationOptions { return_data: true, ..Default::default() });
+        if !result.success {
+            return Err(result.errors);
         }
+        serde_json::from_value(result.data.unwrap_or(serde_json::Value::Null))
+            .map_err(|error| vec![deserialization_error(error)])
+    }
+
+    /// Deserialize `T` straight from JSON text, validating in the same pass
+    /// over the document (see `ValidatingDeserializer`)
+    pub fn parse_str_into<T: DeserializeOwned>(&self, json: &str) -> Result<T, Vec<ValidationError>> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let value = ValidatingDeserializer::new(self, &mut deserializer).deserialize()?;
+        deserializer.end().map_err(|error| vec![deserialization_error(error)])?;
+        Ok(value)
     }
 
     /// Validate multiple values efficiently
-    pub fn validate_many(&mut self, values: &[serde_json::Value]) -> Vec<ValidationResult> {
+    pub fn validate_many(&self, values: &[serde_json::Value]) -> Vec<ValidationResult> {
+        self.validate_many_at(0, values)
+    }
+
+    /// Validate a slice of a larger batch, `offset` being the index of its
+    /// first value, so error paths point into the whole batch
+    pub fn validate_many_at(&self, offset: usize, values: &[serde_json::Value]) -> Vec<ValidationResult> {
         let options = ValidationOptions {
             enable_performance_tracking: true,
             parallel_threshold: 100,
             ..Default::default()
         };
+        self.validate_many_with(offset, values, options)
+    }
 
-        // Optimize schema for batch processing
-        let optimized_schema = SchemaOptimizer::optimize_for_batch(
-            &self.compiled_schema.schema,
-            values.len()
-        );
+    /// Validate a batch with explicit options, e.g. `memoize` to share one
+    /// memo across all items
+    pub fn validate_many_with_options(&self, values: &[serde_json::Value], options: ValidationOptions) -> Vec<ValidationResult> {
+        self.validate_many_with(0, values, options)
+    }
+
+    fn validate_many_with(
+        &self,
+        offset: usize,
+        values: &[serde_json::Value],
+        options: ValidationOptions,
+    ) -> Vec<ValidationResult> {
+        // Items share one memo, whose keys point into the compiled plan
+        let shared = BatchShared {
+            memo: if options.memoize { Some(memo::MemoCache::shared()) } else { None },
+            deadline: options.max_time_ms.map(|ms| Instant::now() + std::time::Duration::from_millis(ms)),
+        };
 
         // Check if we can parallelize validation
-        if SchemaOptimizer::can_parallelize(&optimized_schema, values.len()) {
-            self.validate_parallel(values, &optimized_schema, options)
+        let results = if SchemaOptimizer::can_parallelize(&self.compiled_schema.schema, values.len()) {
+            self.validate_parallel(offset, values, options, &shared)
         } else {
-            self.validate_sequential(values, &optimized_schema, options)
-        }
+            self.validate_sequential(offset, values, options, &shared)
+        };
+        // Counted once for the batch so workers don't contend on the lock
+        lock(&self.metrics).record_many(results.iter().map(|result| result.errors.as_slice()));
+        results
     }
 
     /// Sequential validation for smaller datasets or complex schemas
     fn validate_sequential(
-        &mut self,
+        &self,
+        offset: usize,
         values: &[serde_json::Value],
-        schema: &SchemaType,
-        options: ValidationOptions
+        options: ValidationOptions,
+        shared: &BatchShared,
     ) -> Vec<ValidationResult> {
-        let mut results = Vec::with_capacity(values.len());
-
-        for (index, value) in values.iter().enumerate() {
-            let mut context = ValidationContext::new(options.clone());
-            context.path.push_index(index);
-            context.performance.increment_items(1);
-
-            let errors = self.validate_value(value, schema, &mut context);
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| self.validate_item(offset + index, value, &options, shared))
+            .collect()
+    }
 
-            if errors.is_empty() {
-                results.push(ValidationResult::success_with_stats(
-                    value.clone(),
-                    context.performance.finish()
-                ));
-            } else {
-                results.push(ValidationResult::failure_with_stats(
-                    errors,
-                    context.performance.finish()
-                ));
-            }
-        }
+    /// Parallel validation for large datasets with simple schemas
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    fn validate_parallel(
+        &self,
+        offset: usize,
+        values: &[serde_json::Value],
+        options: ValidationOptions,
+        shared: &BatchShared,
+    ) -> Vec<ValidationResult> {
+        use rayon::prelude::*;
 
-        results
+        values
+            .par_iter()
+            .enumerate()
+            .map(|(index, value)| self.validate_item(offset + index, value, &options, shared))
+            .collect()
     }
 
     /// Parallel validation for large datasets with simple schemas
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
     fn validate_parallel(
-        &mut self,
+        &self,
+        offset: usize,
         values: &[serde_json::Value],
-        schema: &SchemaType,
-        options: ValidationOptions
+        options: ValidationOptions,
+        shared: &BatchShared,
     ) -> Vec<ValidationResult> {
-        // For WASM, we'll simulate parallelism with chunked processing
+        // Without threads, simulate parallelism with chunked processing
         const CHUNK_SIZE: usize = 1000;
 
         let mut results = Vec::with_capacity(values.len());
 
-        for chunk in values.chunks(CHUNK_SIZE) {
-            let chunk_results = self.validate_chunk(chunk, schema, &options);
+        for (chunk_index, chunk) in values.chunks(CHUNK_SIZE).enumerate() {
+            let chunk_results = self.validate_chunk(offset + chunk_index * CHUNK_SIZE, chunk, &options, shared);
             results.extend(chunk_results);
         }
 
         results
     }
 
-    /// Validate a chunk of values efficiently
+    /// Validate a chunk of values efficiently, `offset` being the index of
+    /// its first value in the batch
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
     fn validate_chunk(
-        &mut self,
+        &self,
+        offset: usize,
         chunk: &[serde_json::Value],
-        schema: &SchemaType,
-        options: &ValidationOptions
+        options: &ValidationOptions,
+        shared: &BatchShared,
     ) -> Vec<ValidationResult> {
-        chunk.iter().enumerate().map(|(index, value)| {
-            let mut context = ValidationContext::new(options.clone());
-            context.path.push_index(index);
-            context.performance.increment_items(1);
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(index, value)| self.validate_item(offset + index, value, options, shared))
+            .collect()
+    }
 
-            let errors = self.validate_value(value, schema, &mut context);
+    /// Validate one value of a batch
+    fn validate_item(
+        &self,
+        index: usize,
+        value: &serde_json::Value,
+        options: &ValidationOptions,
+        shared: &BatchShared,
+    ) -> ValidationResult {
+        let mut context = self.create_context(options.clone());
+        context.memo = shared.memo.clone();
+        context.deadline = shared.deadline;
+        context.path.push_index(index);
+        context.performance.increment_items(1);
 
-            if errors.is_empty() {
-                ValidationResult::success_with_stats(value.clone(), context.performance.finish())
+        let mut errors = match check_input_limits(value, options) {
+            // The limit error's path is relative to the item
+            Some(mut error) => {
+                let mut segments = context.path.build().segments().to_vec();
+                segments.extend_from_slice(error.path.segments());
+                error.path = ErrorPath::new(segments);
+                vec![error]
+            }
+            None => self.validate_root(value, &mut context),
+        };
+        if options.paths.is_none() {
+            self.validate_keywords(value, &mut errors, &mut context);
+        }
+        self.merge_profile(&context);
+        check_deadline(format!("[{}]", index), &mut errors, &mut context);
+        let warnings = self.finish_errors(value, &mut errors, &mut context);
+
+        let mut result = if errors.is_empty() {
+            if !options.return_data {
+                ValidationResult::valid()
+            } else if self.needs_output_pass(options) {
+                ValidationResult::success(output_value(value, &self.compiled_schema.schema, options, &self.definitions))
             } else {
-                ValidationResult::failure_with_stats(errors, context.performance.finish())
+                ValidationResult::success(value.clone())
             }
-        }).collect()
+        } else {
+            ValidationResult::failure(errors).with_truncation(context.truncated)
+        };
+        if options.enable_performance_tracking {
+            result.performance = Some(context.performance.finish());
+        }
+        result.warnings = warnings;
+        result
     }
 
-    /// Core validation logic
-    fn validate_value(
-        &mut self,
+    /// Validate only the values at `paths`, each against the sub-schema
+    /// for its location
+    fn validate_paths(
+        &self,
         value: &serde_json::Value,
-        schema: &SchemaType,
+        paths: &[String],
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
-        match schema {
-            SchemaType::String { min_length, max_length, pattern, format } => {
-                self.validate_string(value, *min_length, *max_length, pattern, format, context)
-            }
-            SchemaType::Number { min, max, integer, multiple_of } => {
-                self.validate_number(value, *min, *max, *integer, *multiple_of, context)
+        let mut errors = Vec::new();
+
+        for path in paths {
+            if !context.should_continue(errors.len()) {
+                break;
             }
-            SchemaType::Boolean => {
-                self.validate_boolean(value, context)
+
+            let parsed = ErrorPath::parse(path);
+            let segments = parsed.segments();
+            for segment in segments {
+                match segment {
+                    PathSegment::Key(key) => context.path.push(key),
+                    PathSegment::Index(index) => context.path.push_index(*index),
+                }
             }
-            SchemaType::Array { items, min_items, max_items, unique_items } => {
-                self.validate_array(value, items, *min_items, *max_items, *unique_items, context)
+            let schema = &self.compiled_schema.schema;
+            match self.validate_at(value_at(value, segments), schema, segments, 0, context) {
+                Some(path_errors) => errors.extend(path_errors),
+                None => errors.push(ValidationError::new(
+                    context.path.build(),
+                    format!("Path '{}' is not defined by the schema", path),
+                    ErrorCode::SchemaInvalid,
+                )),
             }
-            SchemaType::Object { properties, required, additional_properties } => {
-                self.validate_object(value, properties, required, *additional_properties, context)
+            for _ in segments {
+                context.path.pop();
             }
-            SchemaType::Null => {
-                self.validate_null(value, context)
+        }
+
+        context.limit_errors(&mut errors);
+        errors
+    }
+
+    /// Validate `target`, the data (if any) at `segments`, against the
+    /// parts of `schema` that describe that location; `None` if no part
+    /// does. Every branch of an `allOf` applies, and one of an `anyOf`,
+    /// `oneOf` or union has to pass (`oneOf` exclusivity is a property of
+    /// the whole value, so it is not checked).
+    fn validate_at(
+        &self,
+        target: Option<&serde_json::Value>,
+        schema: &SchemaType,
+        segments: &[PathSegment],
+        refs: usize,
+        context: &mut ValidationContext,
+    ) -> Option<Vec<ValidationError>> {
+        let (segment, rest) = match segments.split_first() {
+            Some(split) => split,
+            None => return Some(target.map(|target| self.validate_value(target, schema, context)).unwrap_or_default()),
+        };
+
+        let child: &SchemaType = match (schema, segment) {
+            (SchemaType::Ref { .. }, _) if refs >= MAX_REF_DEPTH => return None,
+            (SchemaType::Ref { reference }, _) => {
+                let definitions = Arc::clone(&self.definitions);
+                return self.validate_at(target, definitions.get(reference)?, segments, refs + 1, context);
             }
-            SchemaType::Any => {
-                // Any type is always valid
-                Vec::new()
+            (SchemaType::Refinement { base, .. }, _) => return self.validate_at(target, base, segments, refs, context),
+            (SchemaType::Annotated { schema, metadata }, _) => {
+                let mut errors = self.validate_at(target, schema, segments, refs, context)?;
+                if let (true, Some(target)) = (metadata.sensitive, target) {
+                    redact_sensitive(&mut errors, target, context.path.depth());
+                }
+                return Some(errors);
             }
-            SchemaType::OneOf { schemas } => {
-                self.validate_one_of(value, schemas, context)
+            (SchemaType::AllOf { schemas, .. } | SchemaType::Intersection { schemas }, _) => {
+                let parts: Vec<_> = schemas
+                    .iter()
+                    .filter_map(|branch| self.validate_at(target, branch, segments, refs, context))
+                    .collect();
+                return if parts.is_empty() { None } else { Some(parts.concat()) };
             }
-            SchemaType::AllOf { schemas } => {
-                self.validate_all_of(value, schemas, context)
+            (
+                SchemaType::AnyOf { schemas, .. } | SchemaType::OneOf { schemas, .. } | SchemaType::Union { options: schemas, .. },
+                _,
+            ) => {
+                let mut failures = Vec::new();
+                for (index, branch) in schemas.iter().enumerate() {
+                    match self.validate_at(target, branch, segments, refs, context) {
+                        Some(errors) if errors.is_empty() => return Some(errors),
+                        Some(errors) => failures.push((index, errors)),
+                        None => {}
+                    }
+                }
+                if failures.is_empty() {
+                    return None;
+                }
+                let summary = ValidationError::new(
+                    context.path.build(),
+                    "Value does not match any anyOf schemas".to_string(),
+                    ErrorCode::AnyOfNoMatch,
+                );
+                return Some(union_failure(summary, failures, context.options.verbose_unions, None));
             }
-            SchemaType::AnyOf { schemas } => {
-                self.validate_any_of(value, schemas, context)
+            (SchemaType::Object { properties, catchall, .. }, PathSegment::Key(key)) => {
+                properties.get(key).or(catchall.as_deref())?
             }
-            // TODO: Implement validation for additional schema types
-            _ => {
-                vec![ValidationError::new(
-                    context.path.build(),
-                    "Schema type not yet implemented".to_string(),
-                    ErrorCode::SchemaInvalid,
-                )]
+            (SchemaType::Map { values, .. }, PathSegment::Key(_)) => values,
+            (SchemaType::Array { items, .. } | SchemaType::Set { items, .. }, PathSegment::Index(_)) => items,
+            (SchemaType::Tuple { prefix_items, items }, PathSegment::Index(index)) => {
+                prefix_items.get(*index).or(items.as_deref())?
             }
-        }
+            _ => return None,
+        };
+        self.validate_at(target, child, rest, refs, context)
     }
 
-    /// Validate string type
-    fn validate_string(
-        &mut self,
+    /// Check if valid data must be rewritten before it is returned
+    fn needs_output_pass(&self, options: &ValidationOptions) -> bool {
+        options.paths.is_none()
+            && (options.coerce
+                || self.compiled_schema.has_transforms
+                || (options.access_mode.is_some() && options.strip_inaccessible))
+    }
+
+    /// Create a context configured for this validator's schema
+    fn create_context(&self, options: ValidationOptions) -> ValidationContext {
+        let mut context = ValidationContext::new(options);
+        context.track_evaluated = self.compiled_schema.has_unevaluated;
+        context
+    }
+
+    /// Core validation logic, interpreting the schema node by node
+    pub(crate) fn validate_value(
+        &self,
         value: &serde_json::Value,
-        min_length: Option<usize>,
-        max_length: Option<usize>,
-        pattern: &Option<String>,
-        format: &Option<StringFormat>,
+        schema: &SchemaType,
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
-        let mut errors = Vec::new();
-
-        if let Some(s) = value.as_str() {
-            let len = s.chars().count(); // Unicode-aware length
-
-            // Length validation
-            if let Some(min) = min_length {
-                if len < min {
-                    errors.push(ValidationError::string_length(
-                        context.path.build(),
-                        len,
-                        Some(min),
-                        max_length,
-                    ));
+        let memo = match &context.memo {
+            Some(memo) if !context.track_evaluated && memo::is_memoizable(value) => {
+                let (node, hash) = (schema as *const SchemaType as usize, memo::content_hash(value));
+                if memo::lock(memo).is_valid(node, hash) {
+                    return Vec::new();
                 }
+                Some((Arc::clone(memo), node, hash))
             }
+            _ => None,
+        };
 
-            if let Some(max) = max_length {
-                if len > max {
-                    errors.push(ValidationError::string_length(
-                        context.path.build(),
-                        len,
-                        min_length,
-                        Some(max),
-                    ));
-                }
+        let errors = match &context.profiler {
+            Some(profiler) => {
+                let profiler = Arc::clone(profiler);
+                lock(&profiler).enter(schema, &context.path.build().to_string());
+                let start = Instant::now();
+                let errors = self.validate_node(value, schema, context);
+                lock(&profiler).exit(start.elapsed());
+                errors
             }
+            None => self.validate_node(value, schema, context),
+        };
 
-            // Pattern validation (cached regex)
-            if let Some(pattern_str) = pattern {
-                if let Some(regex) = self.get_or_compile_regex(pattern_str) {
-                    if !regex.is_match(s) {
-                        errors.push(ValidationError::new(
-                            context.path.build(),
-                            format!("String does not match pattern: {}", pattern_str),
-                            ErrorCode::StringPatternMismatch,
-                        ));
-                    }
-                } else {
-                    errors.push(ValidationError::new(
-                        context.path.build(),
-                        format!("Invalid regex pattern: {}", pattern_str),
+        // A truncated pass may have stopped before finding errors
+        if let Some((memo, node, hash)) = memo {
+            if errors.is_empty() && !context.truncated {
+                memo::lock(&memo).insert_valid(node, hash);
+            }
+        }
+        errors
+    }
+
+    /// Profile tree of every validation run with `ValidationOptions::profile`
+    /// since the validator was created or the profile was reset
+    pub fn get_profile(&self) -> Option<ProfileNode> {
+        lock(&self.profile).tree()
+    }
+
+    pub fn reset_profile(&self) {
+        *lock(&self.profile) = Profiler::new();
+    }
+
+    /// Schema shape, regex cache and usage counters since the validator was
+    /// created or `reset_stats` was called. Node types are only counted by
+    /// validations run with `ValidationOptions::profile`, as the compiled
+    /// plan does not visit nodes one by one.
+    pub fn stats(&self) -> ValidationStats {
+        let (hits, misses) = regex_cache::hit_stats();
+        let metrics = lock(&self.metrics);
+        ValidationStats {
+            compiled_complexity: self.compiled_schema.estimated_complexity,
+            max_depth: self.compiled_schema.max_depth,
+            has_patterns: self.compiled_schema.has_patterns,
+            regex_cache_size: regex_cache::len(),
+            regex_cache_hits: hits,
+            regex_cache_misses: misses,
+            regex_cache_hit_rate: if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 },
+            validations: metrics.validations(),
+            invalid: metrics.invalid(),
+            errors_by_code: metrics.errors_by_code(),
+            node_counts: metrics.node_counts(),
+            throughput: metrics.throughput(),
+        }
+    }
+
+    /// Drop this validator's patterns from the shared regex cache. The
+    /// validator keeps its own compiled copies, and other validators'
+    /// patterns stay cached.
+    pub fn clear_cache(&self) {
+        regex_cache::remove(self.patterns.keys().map(String::as_str));
+    }
+
+    /// Zero the usage counters. The regex cache is shared by every
+    /// validator, so its hit counters are reset for all of them.
+    pub fn reset_stats(&self) {
+        *lock(&self.metrics) = Metrics::new();
+        regex_cache::reset_hit_stats();
+    }
+
+    fn validate_node(
+        &self,
+        value: &serde_json::Value,
+        schema: &SchemaType,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let mut errors = match schema {
+            SchemaType::String {
+                min_length,
+                max_length,
+                pattern,
+                format,
+                content_encoding,
+                content_media_type,
+                content_schema,
+                country,
+                uuid_version,
+                email_options,
+                min_date,
+                max_date,
+                offset_required,
+                precision,
+                allow_idn,
+                length_unit,
+            } => {
+                let mut errors = self.validate_string(
+                    value, *min_length, *max_length, pattern, format, country, *uuid_version, email_options, *allow_idn,
+                    *length_unit, context,
+                );
+                let has_date_constraints = min_date.is_some() || max_date.is_some() || *offset_required || precision.is_some();
+                if errors.is_empty() && has_date_constraints {
+                    if let (Some(s), Some(fmt)) = (value.as_str(), format) {
+                        errors.extend(validate_date_constraints(
+                            s, fmt, min_date, max_date, *offset_required, *precision, context
+                        ));
+                    }
+                }
+                if errors.is_empty() && (content_encoding.is_some() || content_media_type.is_some()) {
+                    if let Some(s) = value.as_str() {
+                        errors.extend(self.validate_content(
+                            s,
+                            *content_encoding,
+                            content_media_type,
+                            content_schema,
+                            context,
+                        ));
+                    }
+                }
+                errors
+            }
+            SchemaType::Number {
+                min, max, exclusive_min, exclusive_max, integer, multiple_of, allow_non_finite, strict_integer,
+                multiple_of_tolerance,
+            } => {
+                self.validate_number(
+                    value, *min, *max, *exclusive_min, *exclusive_max, *integer, *multiple_of, *allow_non_finite,
+                    *strict_integer, *multiple_of_tolerance, context,
+                )
+            }
+            SchemaType::BigInt { min, max } => {
+                self.validate_bigint(value, *min, *max, context)
+            }
+            SchemaType::Decimal { min, max, precision, scale, multiple_of } => {
+                self.validate_decimal(value, *min, *max, *precision, *scale, *multiple_of, context)
+            }
+            SchemaType::Boolean => {
+                self.validate_boolean(value, context)
+            }
+            SchemaType::Array { items, min_items, max_items, unique_items, unique_by, sorted, sorted_by } => {
+                let mut errors = self.validate_array(
+                    value, items, *min_items, *max_items, *unique_items, unique_by, context
+                );
+                let check_sorted = sorted.is_some() || sorted_by.is_some();
+                if let (Some(arr), true) = (value.as_array(), check_sorted && context.should_continue(errors.len())) {
+                    errors.extend(validate_sorted(arr, sorted.unwrap_or(SortOrder::Asc), sorted_by, context));
+                }
+                errors
+            }
+            SchemaType::Object {
+                properties,
+                required,
+                additional_properties,
+                catchall,
+                min_properties,
+                max_properties,
+                read_only,
+                write_only,
+                rules,
+            } => {
+                let mut errors = self.validate_object(
+                    value,
+                    properties,
+                    required,
+                    *additional_properties,
+                    catchall,
+                    *min_properties,
+                    *max_properties,
+                    (read_only, write_only),
+                    context,
+                );
+                if let (Some(rules), Some(obj)) = (rules, value.as_object()) {
+                    if context.should_continue(errors.len()) {
+                        errors.extend(validate_object_rules(obj, rules, context));
+                    }
+                }
+                errors
+            }
+            SchemaType::Null => {
+                self.validate_null(value, context)
+            }
+            SchemaType::Any => {
+                // Any type is always valid
+                Vec::new()
+            }
+            SchemaType::Const { value: expected } => {
+                if json_equal(value, expected) {
+                    Vec::new()
+                } else {
+                    vec![ValidationError::with_values(
+                        context.path.build(),
+                        format!("Value must be {}", expected),
+                        ErrorCode::ConstMismatch,
+                        expected.clone(),
+                        value.clone(),
+                    )]
+                }
+            }
+            SchemaType::Set { items, min_items, max_items } => {
+                self.validate_set(value, items, *min_items, *max_items, context)
+            }
+            SchemaType::Tuple { prefix_items, items } => {
+                self.validate_tuple(value, prefix_items, items.as_deref(), context)
+            }
+            SchemaType::Map { keys, values } => {
+                self.validate_map(value, keys, values, context)
+            }
+            SchemaType::Ref { reference } => {
+                let definitions = Arc::clone(&self.definitions);
+                match definitions.get(reference) {
+                    Some(_) if context.ref_depth >= MAX_REF_DEPTH => vec![ValidationError::new(
+                        context.path.build(),
+                        format!("Schema references nest deeper than {}", MAX_REF_DEPTH),
+                        ErrorCode::LimitExceeded,
+                    )],
+                    Some(target) => {
+                        context.ref_depth += 1;
+                        let errors = self.validate_value(value, target, context);
+                        context.ref_depth -= 1;
+                        errors
+                    }
+                    None => vec![ValidationError::new(
+                        context.path.build(),
+                        format!("Unresolved schema reference '{}'", reference),
+                        ErrorCode::SchemaInvalid,
+                    )],
+                }
+            }
+            SchemaType::OneOf { schemas, unevaluated_properties, unevaluated_items } => {
+                let mut errors = self.validate_one_of(value, schemas, context);
+                if errors.is_empty() {
+                    errors = self.validate_unevaluated(
+                        value, unevaluated_properties, unevaluated_items, context
+                    );
+                }
+                errors
+            }
+            SchemaType::AllOf { schemas, unevaluated_properties, unevaluated_items } => {
+                let mut errors = self.validate_all_of(value, schemas, context);
+                if errors.is_empty() {
+                    errors = self.validate_unevaluated(
+                        value, unevaluated_properties, unevaluated_items, context
+                    );
+                }
+                errors
+            }
+            SchemaType::AnyOf { schemas, unevaluated_properties, unevaluated_items } => {
+                let mut errors = self.validate_any_of(value, schemas, context);
+                if errors.is_empty() {
+                    errors = self.validate_unevaluated(
+                        value, unevaluated_properties, unevaluated_items, context
+                    );
+                }
+                errors
+            }
+            SchemaType::Annotated { schema, metadata } => {
+                let mut errors = self.validate_node(value, schema, context);
+                if metadata.sensitive {
+                    redact_sensitive(&mut errors, value, context.path.depth());
+                }
+                errors
+            }
+            // The discriminator only speeds up matching, so anyOf semantics suffice
+            SchemaType::Union { options, .. } => self.validate_any_of(value, options, context),
+            SchemaType::Conditional { condition, then_schema, else_schema } => {
+                let mut temp_context = context.branch();
+                if self.validate_value(value, condition, &mut temp_context).is_empty() {
+                    context.merge_evaluated(temp_context);
+                    self.validate_value(value, then_schema, context)
+                } else if let Some(else_schema) = else_schema {
+                    self.validate_value(value, else_schema, context)
+                } else {
+                    Vec::new()
+                }
+            }
+            SchemaType::GraphQLSchema { schema_definition, operation, operation_name } => crate::graphql::validate_response(
+                schema_definition,
+                operation.as_deref(),
+                operation_name.as_deref(),
+                value,
+                context,
+            ),
+            SchemaType::GraphQLType { type_definition } => crate::graphql::validate_type(type_definition, value, context),
+            SchemaType::HtmlElement { element_type, props, children, .. } => {
+                crate::html::validate_element(self, value, element_type, props, children.as_deref(), context)
+            }
+            SchemaType::ReactComponent { component } => crate::html::validate_component(self, value, component, context),
+            // TODO: Implement validation for additional schema types
+            _ => {
+                vec![ValidationError::new(
+                    context.path.build(),
+                    "Schema type not yet implemented".to_string(),
+                    ErrorCode::SchemaInvalid,
+                )]
+            }
+        };
+
+        // Nested validators each stop at the limit; cap the combined list too
+        context.limit_errors(&mut errors);
+        errors
+    }
+
+    /// Validate string type
+    #[allow(clippy::too_many_arguments)]
+    fn validate_string(
+        &self,
+        value: &serde_json::Value,
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+        pattern: &Option<String>,
+        format: &Option<StringFormat>,
+        country: &Option<String>,
+        uuid_version: Option<u8>,
+        email_options: &Option<EmailOptions>,
+        allow_idn: bool,
+        length_unit: LengthUnit,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if let Some(s) = value.as_str() {
+            let len = length_unit.length(s);
+
+            // Length validation
+            if let Some(min) = min_length {
+                if len < min {
+                    errors.push(ValidationError::string_length(
+                        context.path.build(),
+                        len,
+                        Some(min),
+                        max_length,
+                    ));
+                }
+            }
+
+            if let Some(max) = max_length {
+                if len > max {
+                    errors.push(ValidationError::string_length(
+                        context.path.build(),
+                        len,
+                        min_length,
+                        Some(max),
+                    ));
+                }
+            }
+
+            // Pattern validation (cached regex)
+            if let Some(pattern_str) = pattern.as_ref().filter(|_| context.should_continue(errors.len())) {
+                if let Some(regex) = self.get_or_compile_regex(pattern_str) {
+                    if !regex.is_match(s) {
+                        errors.push(ValidationError::new(
+                            context.path.build(),
+                            format!("String does not match pattern: {}", pattern_str),
+                            ErrorCode::StringPatternMismatch,
+                        ));
+                    }
+                } else {
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("Invalid regex pattern: {}", pattern_str),
                         ErrorCode::StringPatternMismatch,
                     ));
                 }
             }
 
             // Format validation
-            if let Some(fmt) = format {
-                if !validate_string_format(s, fmt) {
+            if let Some(fmt) = format.as_ref().filter(|_| context.should_continue(errors.len())) {
+                let valid = match fmt {
+                    // An explicit length constraint replaces nanoid's default of 21
+                    StringFormat::Nanoid if min_length.is_some() || max_length.is_some() => {
+                        is_nanoid_alphabet(s)
+                    }
+                    StringFormat::Email => match email_options {
+                        Some(options) => is_valid_email_strict(s, options),
+                        None => validate_string_format(s, fmt),
+                    },
+                    StringFormat::Hostname | StringFormat::Fqdn if allow_idn => {
+                        hostname::is_valid_hostname(s, true, *fmt == StringFormat::Fqdn)
+                    }
+                    StringFormat::PhoneNumber => locale::is_phone_number(s, country.as_deref()),
+                    StringFormat::PostalCode => locale::is_postal_code(s, country.as_deref()),
+                    StringFormat::Custom(name) => match self.formats.get(name) {
+                        Some(custom) => custom.matches(s),
+                        None if context.options.unknown_formats == UnknownFormats::Ignore => true,
+                        None => {
+                            errors.push(ValidationError::new(
+                                context.path.build(),
+                                format!("Unknown string format '{}'", name),
+                                ErrorCode::SchemaInvalid,
+                            ));
+                            true
+                        }
+                    },
+                    _ => validate_string_format(s, fmt),
+                };
+
+                if !valid {
+                    errors.push(format_failure(fmt, s, context.path.build()));
+                }
+            }
+
+            if let Some(version) = uuid_version {
+                if uuid_version_of(s) != Some(version) {
                     errors.push(ValidationError::new(
                         context.path.build(),
-                        format!("String format '{}' validation failed", fmt_name(fmt)),
+                        format!("String must be a version {} UUID", version),
                         ErrorCode::StringFormatInvalid,
                     ));
                 }
             }
+        } else if let (Some(fmt), Some(degrees)) = (format, value.as_f64()) {
+            // Coordinates may also be given as JSON numbers
+            let in_range = match fmt {
+                StringFormat::Latitude => Some(is_valid_latitude(degrees)),
+                StringFormat::Longitude => Some(is_valid_longitude(degrees)),
+                _ => None,
+            };
+
+            match in_range {
+                Some(true) => {}
+                Some(false) => errors.push(ValidationError::new(
+                    context.path.build(),
+                    format!("Value {} is not a valid {}", degrees, fmt_name(fmt)),
+                    ErrorCode::StringFormatInvalid,
+                )),
+                None => errors.push(ValidationError::type_mismatch(
+                    context.path.build(),
+                    "string",
+                    value,
+                )),
+            }
         } else {
             errors.push(ValidationError::type_mismatch(
                 context.path.build(),
@@ -274,21 +1290,78 @@ impl Validator {
         errors
     }
 
+    /// Validate encoded string content (`contentEncoding` / `contentMediaType`)
+    fn validate_content(
+        &self,
+        content: &str,
+        encoding: Option<ContentEncoding>,
+        media_type: &Option<String>,
+        content_schema: &Option<Box<SchemaType>>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let decoded = match encoding {
+            Some(encoding) => match encoding.decode(content) {
+                Some(bytes) => bytes,
+                None => {
+                    return vec![ValidationError::new(
+                        context.path.build(),
+                        format!("String is not valid {:?} content", encoding).to_lowercase(),
+                        ErrorCode::StringContentEncodingInvalid,
+                    )];
+                }
+            },
+            None => content.as_bytes().to_vec(),
+        };
+
+        // Only JSON media types can be checked structurally
+        let is_json = media_type.as_deref().is_some_and(|media_type| {
+            let essence = media_type.split(';').next().unwrap_or("").trim();
+            essence == "application/json" || essence.ends_with("+json")
+        });
+        if !is_json {
+            return Vec::new();
+        }
+
+        match serde_json::from_slice::<serde_json::Value>(&decoded) {
+            Ok(parsed) => match content_schema {
+                Some(schema) => self.validate_value(&parsed, schema, context),
+                None => Vec::new(),
+            },
+            Err(e) => vec![ValidationError::new(
+                context.path.build(),
+                format!("String content is not valid JSON: {}", e),
+                ErrorCode::StringContentMediaTypeInvalid,
+            )],
+        }
+    }
+
     /// Validate number type
-    fn validate_number(
-        &mut self,
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn validate_number(
+        &self,
         value: &serde_json::Value,
         min: Option<f64>,
         max: Option<f64>,
+        exclusive_min: Option<f64>,
+        exclusive_max: Option<f64>,
         integer: bool,
         multiple_of: Option<f64>,
+        allow_non_finite: bool,
+        strict_integer: bool,
+        multiple_of_tolerance: Option<f64>,
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
         let mut errors = Vec::new();
 
-        if let Some(n) = value.as_f64() {
+        let number = match value {
+            serde_json::Value::String(s) if allow_non_finite => parse_non_finite(s),
+            _ => value.as_f64(),
+        };
+
+        if let Some(n) = number {
             // Integer validation
-            if integer && !is_integer(n) {
+            let float_literal = strict_integer && value.as_i64().is_none() && value.as_u64().is_none();
+            if integer && (!is_integer(n) || float_literal) {
                 errors.push(ValidationError::new(
                     context.path.build(),
                     "Number must be an integer".to_string(),
@@ -296,9 +1369,9 @@ impl Validator {
                 ));
             }
 
-            // Range validation
+            // Range validation; NaN is outside every range
             if let Some(min_val) = min {
-                if n < min_val {
+                if n.is_nan() || n < min_val {
                     errors.push(ValidationError::number_range(
                         context.path.build(),
                         n,
@@ -309,7 +1382,7 @@ impl Validator {
             }
 
             if let Some(max_val) = max {
-                if n > max_val {
+                if n.is_nan() || n > max_val {
                     errors.push(ValidationError::number_range(
                         context.path.build(),
                         n,
@@ -319,13 +1392,45 @@ impl Validator {
                 }
             }
 
+            if let Some(bound) = exclusive_min {
+                if n.is_nan() || n <= bound {
+                    let mut error = ValidationError::out_of_range(
+                        context.path.build(),
+                        format!("Number {} must be greater than {}", n, bound),
+                        ErrorCode::NumberTooSmall,
+                        Some(bound),
+                        None,
+                        serde_json::json!(n),
+                    );
+                    error.params["exclusive"] = serde_json::json!(true);
+                    errors.push(error);
+                }
+            }
+
+            if let Some(bound) = exclusive_max {
+                if n.is_nan() || n >= bound {
+                    let mut error = ValidationError::out_of_range(
+                        context.path.build(),
+                        format!("Number {} must be less than {}", n, bound),
+                        ErrorCode::NumberTooLarge,
+                        None,
+                        Some(bound),
+                        serde_json::json!(n),
+                    );
+                    error.params["exclusive"] = serde_json::json!(true);
+                    errors.push(error);
+                }
+            }
+
             // Multiple of validation
             if let Some(multiple) = multiple_of {
-                if multiple != 0.0 && (n % multiple).abs() > f64::EPSILON {
-                    errors.push(ValidationError::new(
+                let tolerance = multiple_of_tolerance.unwrap_or(DEFAULT_MULTIPLE_OF_TOLERANCE);
+                if !is_multiple(n, multiple, tolerance) {
+                    errors.push(ValidationError::with_params(
                         context.path.build(),
                         format!("Number must be a multiple of {}", multiple),
                         ErrorCode::NumberNotMultipleOf,
+                        serde_json::json!({ "multipleOf": multiple, "received": n }),
                     ));
                 }
             }
@@ -340,9 +1445,195 @@ impl Validator {
         errors
     }
 
+    /// Validate 64-bit integers without going through f64
+    pub(crate) fn validate_bigint(
+        &self,
+        value: &serde_json::Value,
+        min: Option<i128>,
+        max: Option<i128>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let n: i128 = match value {
+            serde_json::Value::Number(num) => {
+                if let Some(i) = num.as_i64() {
+                    i as i128
+                } else if let Some(u) = num.as_u64() {
+                    u as i128
+                } else {
+                    return vec![ValidationError::new(
+                        context.path.build(),
+                        "Number must be an integer".to_string(),
+                        ErrorCode::NumberNotInteger,
+                    )];
+                }
+            }
+            serde_json::Value::String(s) => match parse_int64(s) {
+                Some(n) => n,
+                // Well-formed, but wider than 64 bits
+                None if s.parse::<i128>().is_ok() => {
+                    let too_small = s.starts_with('-');
+                    return vec![ValidationError::with_values(
+                        context.path.build(),
+                        format!("Number {} is outside the 64-bit integer range", s),
+                        if too_small { ErrorCode::NumberTooSmall } else { ErrorCode::NumberTooLarge },
+                        serde_json::json!(if too_small { i64::MIN.to_string() } else { u64::MAX.to_string() }),
+                        value.clone(),
+                    )];
+                }
+                None => {
+                    return vec![ValidationError::with_values(
+                        context.path.build(),
+                        format!("String '{}' is not a valid bigint", s),
+                        ErrorCode::InvalidFormat,
+                        serde_json::json!("bigint"),
+                        value.clone(),
+                    )];
+                }
+            },
+            _ => {
+                return vec![ValidationError::type_mismatch(
+                    context.path.build(),
+                    "bigint",
+                    value,
+                )];
+            }
+        };
+
+        let mut errors = Vec::new();
+
+        if let Some(min_val) = min {
+            if n < min_val {
+                errors.push(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Number {} is less than minimum {}", n, min_val),
+                    ErrorCode::NumberTooSmall,
+                    Some(min_val.to_string()),
+                    None,
+                    serde_json::json!(n.to_string()),
+                ));
+            }
+        }
+
+        if let Some(max_val) = max {
+            if n > max_val {
+                errors.push(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Number {} is greater than maximum {}", n, max_val),
+                    ErrorCode::NumberTooLarge,
+                    None,
+                    Some(max_val.to_string()),
+                    serde_json::json!(n.to_string()),
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// Validate decimal numbers with exact arithmetic
+    #[allow(clippy::too_many_arguments)]
+    fn validate_decimal(
+        &self,
+        value: &serde_json::Value,
+        min: Option<Decimal>,
+        max: Option<Decimal>,
+        precision: Option<u32>,
+        scale: Option<u32>,
+        multiple_of: Option<Decimal>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let text = match value {
+            // Number::to_string yields the shortest round-trip form, e.g. "0.3"
+            serde_json::Value::Number(num) => num.to_string(),
+            serde_json::Value::String(s) => s.clone(),
+            _ => {
+                return vec![ValidationError::type_mismatch(
+                    context.path.build(),
+                    "decimal",
+                    value,
+                )];
+            }
+        };
+
+        let d = match Decimal::from_str(&text).or_else(|_| Decimal::from_scientific(&text)) {
+            Ok(d) => d.normalize(),
+            Err(_) => {
+                return vec![ValidationError::with_values(
+                    context.path.build(),
+                    format!("'{}' is not a valid decimal", text),
+                    ErrorCode::InvalidFormat,
+                    serde_json::json!("decimal"),
+                    value.clone(),
+                )];
+            }
+        };
+
+        let mut errors = Vec::new();
+
+        if let Some(min_val) = min {
+            if d < min_val {
+                errors.push(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Number {} is less than minimum {}", d, min_val),
+                    ErrorCode::NumberTooSmall,
+                    Some(min_val.to_string()),
+                    None,
+                    serde_json::json!(d.to_string()),
+                ));
+            }
+        }
+
+        if let Some(max_val) = max {
+            if d > max_val {
+                errors.push(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Number {} is greater than maximum {}", d, max_val),
+                    ErrorCode::NumberTooLarge,
+                    None,
+                    Some(max_val.to_string()),
+                    serde_json::json!(d.to_string()),
+                ));
+            }
+        }
+
+        if let Some(max_scale) = scale {
+            if d.scale() > max_scale {
+                errors.push(ValidationError::new(
+                    context.path.build(),
+                    format!("Number {} has more than {} decimal places", d, max_scale),
+                    ErrorCode::NumberScaleExceeded,
+                ));
+            }
+        }
+
+        if let Some(max_precision) = precision {
+            let digits = d.mantissa().unsigned_abs().to_string().len() as u32;
+            if digits.max(d.scale()) > max_precision {
+                errors.push(ValidationError::new(
+                    context.path.build(),
+                    format!("Number {} has more than {} significant digits", d, max_precision),
+                    ErrorCode::NumberPrecisionExceeded,
+                ));
+            }
+        }
+
+        if let Some(multiple) = multiple_of {
+            if !multiple.is_zero() && !(d % multiple).is_zero() {
+                errors.push(ValidationError::with_params(
+                    context.path.build(),
+                    format!("Number must be a multiple of {}", multiple),
+                    ErrorCode::NumberNotMultipleOf,
+                    serde_json::json!({ "multipleOf": multiple.to_string(), "received": d.to_string() }),
+                ));
+            }
+        }
+
+        errors
+    }
+
     /// Validate boolean type
-    fn validate_boolean(
-        &mut self,
+    pub(crate) fn validate_boolean(
+        &self,
         value: &serde_json::Value,
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
@@ -358,8 +1649,8 @@ impl Validator {
     }
 
     /// Validate null type
-    fn validate_null(
-        &mut self,
+    pub(crate) fn validate_null(
+        &self,
         value: &serde_json::Value,
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
@@ -375,13 +1666,15 @@ impl Validator {
     }
 
     /// Validate array type
+    #[allow(clippy::too_many_arguments)]
     fn validate_array(
-        &mut self,
+        &self,
         value: &serde_json::Value,
         items_schema: &SchemaType,
         min_items: Option<usize>,
         max_items: Option<usize>,
         unique_items: bool,
+        unique_by: &Option<String>,
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
         let mut errors = Vec::new();
@@ -392,40 +1685,69 @@ impl Validator {
             // Length validation
             if let Some(min) = min_items {
                 if len < min {
-                    errors.push(ValidationError::new(
+                    errors.push(ValidationError::out_of_range(
                         context.path.build(),
                         format!("Array must have at least {} items", min),
                         ErrorCode::ArrayTooShort,
+                        Some(min),
+                        None,
+                        serde_json::json!(len),
                     ));
                 }
             }
 
             if let Some(max) = max_items {
                 if len > max {
-                    errors.push(ValidationError::new(
+                    errors.push(ValidationError::out_of_range(
                         context.path.build(),
                         format!("Array must have at most {} items", max),
                         ErrorCode::ArrayTooLong,
+                        None,
+                        Some(max),
+                        serde_json::json!(len),
                     ));
                 }
             }
 
-            // Uniqueness validation
-            if unique_items {
+            // Uniqueness validation, reported on each duplicate item
+            if unique_items || unique_by.is_some() {
                 let mut unique_checker = UniqueChecker::new();
-                for item in arr {
-                    if !unique_checker.insert(item) {
+                for (index, item) in arr.iter().enumerate() {
+                    if !context.should_continue(errors.len()) {
+                        break;
+                    }
+
+                    let key = match unique_by {
+                        // Items without the key cannot collide
+                        Some(property) => match item.get(property) {
+                            Some(key) => key,
+                            None => continue,
+                        },
+                        None => item,
+                    };
+
+                    if let Some(first) = unique_checker.insert_at(key, index) {
+                        let message = match unique_by {
+                            Some(property) => format!(
+                                "Item has the same '{}' as the item at index {}",
+                                property, first
+                            ),
+                            None => format!("Item duplicates the item at index {}", first),
+                        };
+
+                        context.path.push_index(index);
                         errors.push(ValidationError::new(
                             context.path.build(),
-                            "Array items must be unique".to_string(),
+                            message,
                             ErrorCode::ArrayNotUnique,
                         ));
-                        break;
+                        context.path.pop();
                     }
                 }
             }
 
             // Validate each item
+            context.mark_items_evaluated(len);
             for (index, item) in arr.iter().enumerate() {
                 if !context.should_continue(errors.len()) {
                     break;
@@ -447,21 +1769,249 @@ impl Validator {
         errors
     }
 
-    /// Validate object type
+    /// Validate positional items, then the rest against `items`
+    fn validate_tuple(
+        &self,
+        value: &serde_json::Value,
+        prefix_items: &[SchemaType],
+        items: Option<&SchemaType>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let arr = match value.as_array() {
+            Some(arr) => arr,
+            None => {
+                return vec![ValidationError::type_mismatch(
+                    context.path.build(),
+                    "array",
+                    value,
+                )];
+            }
+        };
+
+        let mut errors = Vec::new();
+        let evaluated = if items.is_some() { arr.len() } else { arr.len().min(prefix_items.len()) };
+        context.mark_items_evaluated(evaluated);
+
+        for (index, item) in arr.iter().enumerate() {
+            let item_schema = match prefix_items.get(index).or(items) {
+                Some(item_schema) => item_schema,
+                None => break,
+            };
+            if !context.should_continue(errors.len()) {
+                break;
+            }
+
+            context.path.push_index(index);
+            let item_errors = self.validate_value(item, item_schema, context);
+            context.path.pop();
+            errors.extend(item_errors);
+        }
+
+        errors
+    }
+
+    /// Validate set type (array with enforced uniqueness)
+    fn validate_set(
+        &self,
+        value: &serde_json::Value,
+        items_schema: &SchemaType,
+        min_items: Option<usize>,
+        max_items: Option<usize>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let arr = match value.as_array() {
+            Some(arr) => arr,
+            None => {
+                return vec![ValidationError::type_mismatch(
+                    context.path.build(),
+                    "set",
+                    value,
+                )];
+            }
+        };
+
+        let mut errors = Vec::new();
+        let mut unique_checker = UniqueChecker::new();
+        let mut unique_count = 0;
+        context.mark_items_evaluated(arr.len());
+
+        for (index, item) in arr.iter().enumerate() {
+            if !unique_checker.insert(item) {
+                // Duplicates are dropped from the output when coercing
+                if !context.options.coerce {
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("Set contains a duplicate item at index {}", index),
+                        ErrorCode::ArrayNotUnique,
+                    ));
+                }
+                continue;
+            }
+            unique_count += 1;
+
+            if !context.should_continue(errors.len()) {
+                break;
+            }
+
+            context.path.push_index(index);
+            let item_errors = self.validate_value(item, items_schema, context);
+            context.path.pop();
+            errors.extend(item_errors);
+        }
+
+        // Size constraints apply to the deduplicated set
+        if let Some(min) = min_items {
+            if unique_count < min {
+                errors.push(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Set must have at least {} items", min),
+                    ErrorCode::ArrayTooShort,
+                    Some(min),
+                    None,
+                    serde_json::json!(unique_count),
+                ));
+            }
+        }
+
+        if let Some(max) = max_items {
+            if unique_count > max {
+                errors.push(ValidationError::out_of_range(
+                    context.path.build(),
+                    format!("Set must have at most {} items", max),
+                    ErrorCode::ArrayTooLong,
+                    None,
+                    Some(max),
+                    serde_json::json!(unique_count),
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// Validate map type, either as `[key, value]` pairs or as an object
+    fn validate_map(
+        &self,
+        value: &serde_json::Value,
+        keys_schema: &SchemaType,
+        values_schema: &SchemaType,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        match value {
+            serde_json::Value::Array(entries) => {
+                for (index, entry) in entries.iter().enumerate() {
+                    if !context.should_continue(errors.len()) {
+                        break;
+                    }
+
+                    context.path.push_index(index);
+                    match entry.as_array() {
+                        Some(pair) if pair.len() == 2 => {
+                            context.path.push_index(0);
+                            errors.extend(self.validate_value(&pair[0], keys_schema, context));
+                            context.path.pop();
+
+                            context.path.push_index(1);
+                            errors.extend(self.validate_value(&pair[1], values_schema, context));
+                            context.path.pop();
+                        }
+                        _ => {
+                            errors.push(ValidationError::type_mismatch(
+                                context.path.build(),
+                                "[key, value] pair",
+                                entry,
+                            ));
+                        }
+                    }
+                    context.path.pop();
+                }
+            }
+            serde_json::Value::Object(obj) => {
+                for (key, item) in obj {
+                    if !context.should_continue(errors.len()) {
+                        break;
+                    }
+
+                    context.path.push(key);
+                    let key_value = serde_json::Value::String(key.clone());
+                    errors.extend(self.validate_value(&key_value, keys_schema, context));
+                    errors.extend(self.validate_value(item, values_schema, context));
+                    context.path.pop();
+                }
+            }
+            _ => {
+                errors.push(ValidationError::type_mismatch(
+                    context.path.build(),
+                    "map",
+                    value,
+                ));
+            }
+        }
+
+        errors
+    }
+
+    /// Validate object type
+    #[allow(clippy::too_many_arguments)]
     fn validate_object(
-        &mut self,
+        &self,
         value: &serde_json::Value,
         properties: &HashMap<String, SchemaType>,
         required: &Option<Vec<String>>,
-        additional_properties: bool,
+        additional_properties: UnknownKeys,
+        catchall: &Option<Box<SchemaType>>,
+        min_properties: Option<usize>,
+        max_properties: Option<usize>,
+        access: (&Option<Vec<String>>, &Option<Vec<String>>),
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
         let mut errors = Vec::new();
 
         if let Some(obj) = value.as_object() {
+            let (read_only, write_only) = access;
+            let inaccessible = context.options.inaccessible_properties(read_only, write_only);
+
+            // Size constraints
+            if let Some(min) = min_properties {
+                if obj.len() < min {
+                    errors.push(ValidationError::out_of_range(
+                        context.path.build(),
+                        format!("Object must have at least {} properties", min),
+                        ErrorCode::ObjectTooFewProperties,
+                        Some(min),
+                        None,
+                        serde_json::json!(obj.len()),
+                    ));
+                }
+            }
+
+            if let Some(max) = max_properties {
+                if obj.len() > max {
+                    errors.push(ValidationError::out_of_range(
+                        context.path.build(),
+                        format!("Object must have at most {} properties", max),
+                        ErrorCode::ObjectTooManyProperties,
+                        None,
+                        Some(max),
+                        serde_json::json!(obj.len()),
+                    ));
+                }
+            }
+
             // Check required properties first (fast path)
             if let Some(required_props) = required {
                 for prop_name in required_props {
+                    if !context.should_continue(errors.len()) {
+                        break;
+                    }
+
+                    // Properties that may not be sent in this mode cannot be required
+                    if inaccessible.is_some_and(|names| names.contains(prop_name)) {
+                        continue;
+                    }
+
                     if !obj.contains_key(prop_name) {
                         errors.push(ValidationError::missing_property(
                             context.path.build(),
@@ -471,6 +2021,27 @@ impl Validator {
                 }
             }
 
+            // Reject properties not allowed in the current access mode
+            // (stripping happens on output)
+            if let Some(names) = inaccessible {
+                if !context.options.strip_inaccessible {
+                    let code = match context.options.access_mode {
+                        Some(AccessMode::Request) => ErrorCode::ObjectReadOnlyProperty,
+                        _ => ErrorCode::ObjectWriteOnlyProperty,
+                    };
+
+                    for name in names.iter().filter(|name| obj.contains_key(*name)) {
+                        context.path.push(name);
+                        errors.push(ValidationError::new(
+                            context.path.build(),
+                            format!("{} '{}' is not allowed here", code.description(), name),
+                            code.clone(),
+                        ));
+                        context.path.pop();
+                    }
+                }
+            }
+
             // Validate known properties
             for (prop_name, prop_schema) in properties {
                 if let Some(prop_value) = obj.get(prop_name) {
@@ -478,6 +2049,11 @@ impl Validator {
                         break;
                     }
 
+                    if inaccessible.is_some_and(|names| names.contains(prop_name)) {
+                        continue;
+                    }
+
+                    context.mark_property_evaluated(prop_name);
                     context.path.push(prop_name);
                     let prop_errors = self.validate_value(prop_value, prop_schema, context);
                     context.path.pop();
@@ -485,16 +2061,29 @@ impl Validator {
                 }
             }
 
-            // Check for additional properties
-            if !additional_properties {
-                for key in obj.keys() {
-                    if !properties.contains_key(key) {
-                        errors.push(ValidationError::new(
-                            format!("{}.{}", context.path.build(), key),
-                            format!("Additional property '{}' is not allowed", key),
-                            ErrorCode::ObjectAdditionalProperty,
-                        ));
-                    }
+            // Check for additional properties (stripping happens on output)
+            for (key, prop_value) in obj {
+                if properties.contains_key(key) {
+                    continue;
+                }
+                let checked = catchall.is_some() || additional_properties == UnknownKeys::Strict;
+                if checked && !context.should_continue(errors.len()) {
+                    break;
+                }
+
+                if let Some(catchall_schema) = catchall {
+                    context.mark_property_evaluated(key);
+                    context.path.push(key);
+                    let prop_errors = self.validate_value(prop_value, catchall_schema, context);
+                    context.path.pop();
+                    errors.extend(prop_errors);
+                } else if additional_properties == UnknownKeys::Strict {
+                    let missing = properties.keys().map(String::as_str).filter(|name| !obj.contains_key(*name));
+                    errors.push(ValidationError::new(
+                        context.path.build().child(key),
+                        format!("Additional property '{}' is not allowed", key),
+                        ErrorCode::ObjectAdditionalProperty,
+                    ).with_suggestion(closest_match(key, missing)));
                 }
             }
         } else {
@@ -510,41 +2099,48 @@ impl Validator {
 
     /// Validate oneOf constraint
     fn validate_one_of(
-        &mut self,
+        &self,
         value: &serde_json::Value,
         schemas: &[SchemaType],
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
         let mut valid_count = 0;
-        let mut all_errors = Vec::new();
+        let mut failures = Vec::new();
 
         for (index, schema) in schemas.iter().enumerate() {
             // Create a temporary context to avoid path pollution
-            let mut temp_context = ValidationContext::new(context.options.clone());
-            temp_context.path = context.path.clone();
+            let mut temp_context = context.branch();
 
             let errors = self.validate_value(value, schema, &mut temp_context);
 
             if errors.is_empty() {
                 valid_count += 1;
-            } else {
-                all_errors.extend(errors.into_iter().map(|mut e| {
-                    e.path = format!("{}[oneOf:{}].{}", context.path.build(), index, e.path);
-                    e
-                }));
+                context.merge_evaluated(temp_context);
+                // A second match already decides the outcome
+                if valid_count == 2 {
+                    break;
+                }
+            } else if context.options.verbose_unions || accepts(schema, value) {
+                failures.push((index, errors));
             }
         }
 
         match valid_count {
-            0 => vec![ValidationError::new(
-                context.path.build(),
-                "Value does not match any oneOf schemas".to_string(),
-                ErrorCode::OneOfNoMatch,
-            )],
+            0 => {
+                let values = enum_values(schemas);
+                let suggested = enum_suggestion(value, &values);
+                let summary = ValidationError::new(
+                    context.path.build(),
+                    "Value does not match any oneOf schemas".to_string(),
+                    ErrorCode::OneOfNoMatch,
+                )
+                .with_suggestion(suggested.map(|index| values[index].as_str()));
+                union_failure(summary, failures, context.options.verbose_unions, suggested)
+            }
             1 => Vec::new(), // Success
             _ => vec![ValidationError::new(
                 context.path.build(),
-                format!("Value matches {} oneOf schemas, expected exactly 1", valid_count),
+                "Value matches more than one oneOf schema, expected exactly 1".to_string(),
                 ErrorCode::OneOfMultipleMatches,
             )],
         }
@@ -552,28 +2148,29 @@ impl Validator {
 
     /// Validate allOf constraint
     fn validate_all_of(
-        &mut self,
+        &self,
         value: &serde_json::Value,
         schemas: &[SchemaType],
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
         let mut all_errors = Vec::new();
 
-        for (index, schema) in schemas.iter().enumerate() {
+        for schema in schemas {
             if !context.should_continue(all_errors.len()) {
                 break;
             }
 
-            let mut temp_context = ValidationContext::new(context.options.clone());
-            temp_context.path = context.path.clone();
+            let mut temp_context = context.branch();
 
             let errors = self.validate_value(value, schema, &mut temp_context);
 
-            if !errors.is_empty() {
-                all_errors.extend(errors.into_iter().map(|mut e| {
-                    e.path = format!("{}[allOf:{}].{}", context.path.build(), index, e.path);
-                    e
-                }));
+            if errors.is_empty() {
+                context.merge_evaluated(temp_context);
+            } else {
+                // Branch errors are reported, so is their truncation
+                context.truncated |= temp_context.truncated;
+                // Branches start from the current path, so their errors already point at the value
+                all_errors.extend(errors);
             }
         }
 
@@ -582,348 +2179,3090 @@ impl Validator {
 
     /// Validate anyOf constraint
     fn validate_any_of(
-        &mut self,
+        &self,
         value: &serde_json::Value,
         schemas: &[SchemaType],
         context: &mut ValidationContext,
     ) -> Vec<ValidationError> {
-        let mut all_errors = Vec::new();
+        let mut failures = Vec::new();
+        let mut matched = false;
 
         for (index, schema) in schemas.iter().enumerate() {
-            let mut temp_context = ValidationContext::new(context.options.clone());
-            temp_context.path = context.path.clone();
+            let mut temp_context = context.branch();
 
             let errors = self.validate_value(value, schema, &mut temp_context);
 
             if errors.is_empty() {
-                // Found a matching schema, validation succeeds
-                return Vec::new();
-            } else {
-                all_errors.extend(errors.into_iter().map(|mut e| {
-                    e.path = format!("{}[anyOf:{}].{}", context.path.build(), index, e.path);
-                    e
-                }));
+                // Found a matching schema, validation succeeds. Keep going only
+                // when every matching branch must contribute evaluated keys.
+                matched = true;
+                context.merge_evaluated(temp_context);
+                if !context.track_evaluated {
+                    return Vec::new();
+                }
+            } else if !matched && (context.options.verbose_unions || accepts(schema, value)) {
+                failures.push((index, errors));
             }
         }
 
+        if matched {
+            return Vec::new();
+        }
+
         // No schema matched
-        vec![ValidationError::new(
+        let values = enum_values(schemas);
+        let suggested = enum_suggestion(value, &values);
+        let summary = ValidationError::new(
             context.path.build(),
             "Value does not match any anyOf schemas".to_string(),
             ErrorCode::AnyOfNoMatch,
-        )]
+        )
+        .with_suggestion(suggested.map(|index| values[index].as_str()));
+        union_failure(summary, failures, context.options.verbose_unions, suggested)
     }
 
-    /// Get or compile regex pattern (with caching)
-    fn get_or_compile_regex(&mut self, pattern: &str) -> Option<Arc<Regex>> {
-        if let Some(cached_regex) = self.regex_cache.get(pattern) {
-            Some(Arc::clone(cached_regex))
-        } else {
-            match Regex::new(pattern) {
-                Ok(regex) => {
-                    let arc_regex = Arc::new(regex);
-                    self.regex_cache.insert(pattern.to_string(), Arc::clone(&arc_regex));
-                    Some(arc_regex)
+    /// Validate properties/items that no composition branch evaluated
+    fn validate_unevaluated(
+        &self,
+        value: &serde_json::Value,
+        unevaluated_properties: &Option<Unevaluated>,
+        unevaluated_items: &Option<Unevaluated>,
+        context: &mut ValidationContext,
+    ) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let location = context.evaluated
+            .get(&context.path.build().to_string())
+            .cloned()
+            .unwrap_or_default();
+
+        if let (Some(rule), Some(obj)) = (unevaluated_properties, value.as_object()) {
+            for (key, prop_value) in obj {
+                if location.properties.contains(key) {
+                    continue;
+                }
+                if !context.should_continue(errors.len()) {
+                    break;
+                }
+
+                match rule {
+                    Unevaluated::Allowed(true) => {}
+                    Unevaluated::Allowed(false) => {
+                        errors.push(ValidationError::new(
+                            context.path.build().child(key),
+                            format!("Unevaluated property '{}' is not allowed", key),
+                            ErrorCode::ObjectUnevaluatedProperty,
+                        ));
+                    }
+                    Unevaluated::Schema(schema) => {
+                        context.path.push(key);
+                        errors.extend(self.validate_value(prop_value, schema, context));
+                        context.path.pop();
+                    }
+                }
+                context.mark_property_evaluated(key);
+            }
+        }
+
+        if let (Some(rule), Some(arr)) = (unevaluated_items, value.as_array()) {
+            for (index, item) in arr.iter().enumerate().skip(location.items) {
+                if !context.should_continue(errors.len()) {
+                    break;
+                }
+
+                match rule {
+                    Unevaluated::Allowed(true) => {}
+                    Unevaluated::Allowed(false) => {
+                        context.path.push_index(index);
+                        errors.push(ValidationError::new(
+                            context.path.build(),
+                            format!("Unevaluated item at index {} is not allowed", index),
+                            ErrorCode::ArrayUnevaluatedItem,
+                        ));
+                        context.path.pop();
+                    }
+                    Unevaluated::Schema(schema) => {
+                        context.path.push_index(index);
+                        errors.extend(self.validate_value(item, schema, context));
+                        context.path.pop();
+                    }
                 }
-                Err(_) => None,
             }
+            context.mark_items_evaluated(arr.len());
+        }
+
+        errors
+    }
+
+    /// Regex for a pattern, resolved when the validator was built; any
+    /// other pattern goes through the shared cache
+    fn get_or_compile_regex(&self, pattern: &str) -> Option<Arc<Regex>> {
+        match self.patterns.get(pattern) {
+            Some(regex) => regex.clone(),
+            None => regex_cache::get_or_compile(pattern).ok(),
         }
     }
 }
 
-/// Get human-readable format name
-fn fmt_name(format: &StringFormat) -> &'static str {
-    match format {
-        StringFormat::Email => "email",
-        StringFormat::Uri => "uri",
-        StringFormat::Url => "url",
-        StringFormat::Uuid => "uuid",
-        StringFormat::DateTime => "date-time",
-        StringFormat::Date => "date",
-        StringFormat::Time => "time",
-        StringFormat::Ipv4 => "ipv4",
-        StringFormat::Ipv6 => "ipv6",
-        StringFormat::Hostname => "hostname",
-        StringFormat::JsonPointer => "json-pointer",
-        StringFormat::RelativeJsonPointer => "relative-json-pointer",
-        StringFormat::Regex => "regex",
-        // TODO: Add names for additional formats
-        _ => "unknown-format",
+/// Compile every `pattern` in `schemas` that isn't in `patterns` yet
+fn resolve_patterns<'a>(
+    patterns: &mut HashMap<String, Option<Arc<Regex>>>,
+    schemas: impl IntoIterator<Item = &'a SchemaType>,
+) {
+    let mut sources = Vec::new();
+    for schema in schemas {
+        schema.collect_patterns(&mut sources);
+    }
+    for source in sources {
+        if !patterns.contains_key(source) {
+            patterns.insert(source.to_string(), regex_cache::get_or_compile(source).ok());
+        }
     }
 }
 
-/// Batch validator for optimized bulk operations
-pub struct BatchValidator {
-    validator: Validator,
-    batch_size: usize,
+/// The value at a data path, if present
+fn value_at<'a>(value: &'a serde_json::Value, segments: &[PathSegment]) -> Option<&'a serde_json::Value> {
+    segments.iter().try_fold(value, |current, segment| match segment {
+        PathSegment::Key(key) => current.as_object()?.get(key),
+        PathSegment::Index(index) => current.as_array()?.get(*index),
+    })
 }
 
-impl BatchValidator {
-    pub fn new(schema: SchemaType, batch_size: usize) -> Result<Self, crate::error::FastSchemaError> {
-        Ok(Self {
-            validator: Validator::new(schema)?,
-            batch_size,
-        })
+/// Fully redact errors found inside a `sensitive` schema, whose data is
+/// `value` at path depth `base`
+pub(crate) fn redact_sensitive(errors: &mut [ValidationError], value: &serde_json::Value, base: usize) {
+    for error in errors {
+        let segments = error.path.segments().get(base..).unwrap_or_default();
+        redact(error, value_at(value, segments), Redaction::Full);
     }
+}
 
-    /// Validate large datasets efficiently
-    pub fn validate_dataset(&mut self, values: &[serde_json::Value]) -> Vec<ValidationResult> {
-        if values.len() <= self.batch_size {
-            self.validator.validate_many(values)
-        } else {
-            let mut results = Vec::with_capacity(values.len());
-
-            for chunk in values.chunks(self.batch_size) {
-                let chunk_results = self.validator.validate_many(chunk);
-                results.extend(chunk_results);
-            }
+/// Hide the received value in an error's params and message; `value` is
+/// the data at the error's path, when it exists
+fn redact(error: &mut ValidationError, value: Option<&serde_json::Value>, redaction: Redaction) {
+    let hide = |text: &str| match redaction {
+        Redaction::Truncate(max) if text.chars().count() > max => format!("{}…", text.chars().take(max).collect::<String>()),
+        Redaction::Truncate(_) => text.to_string(),
+        _ => "[REDACTED]".to_string(),
+    };
 
-            results
+    if let Some(received) = error.params.get_mut("received") {
+        match received {
+            serde_json::Value::String(s) => *s = hide(s),
+            _ if redaction == Redaction::Full => *received = serde_json::json!(hide("")),
+            _ => {}
         }
     }
 
-    /// Get validation statistics for optimization
-    pub fn get_stats(&self) -> ValidationStats {
-        ValidationStats {
-            compiled_complexity: self.validator.compiled_schema.estimated_complexity,
-            max_depth: self.validator.compiled_schema.max_depth,
-            has_patterns: self.validator.compiled_schema.has_patterns,
-            regex_cache_size: self.validator.regex_cache.len(),
+    // Messages quote strings (`'value'`) and write numbers bare; only
+    // those tokens are replaced, not other text that contains the value
+    match value {
+        Some(serde_json::Value::String(s)) if !s.is_empty() => {
+            error.message = error.message.replace(&format!("'{}'", s), &format!("'{}'", hide(s)));
+        }
+        Some(serde_json::Value::Number(n)) => {
+            let shown = n.to_string();
+            let numeric = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+            let mut message = String::with_capacity(error.message.len());
+            let mut rest = error.message.as_str();
+            while let Some(start) = rest.find(&shown) {
+                let end = start + shown.len();
+                let alone = !rest[..start].ends_with(numeric) && !rest[end..].starts_with(numeric);
+                message.push_str(&rest[..start]);
+                message.push_str(if alone { hide(&shown) } else { shown.clone() }.as_str());
+                rest = &rest[end..];
+            }
+            message.push_str(rest);
+            error.message = message;
         }
+        _ => {}
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ValidationStats {
-    pub compiled_complexity: usize,
-    pub max_depth: usize,
-    pub has_patterns: bool,
-    pub regex_cache_size: usize,
+/// Profiles and metrics hold no invariants a panicking thread could break
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
+/// Errors for a union no branch matched: `summary`, then the errors of the
+/// closest branch (or of every branch when `verbose`), each tagged with
+/// its branch index in `params.branch`. `failures` holds each failing
+/// branch's index and errors; `suggested` is the branch the summary
+/// suggests, if any.
+pub(crate) fn union_failure(
+    summary: ValidationError,
+    mut failures: Vec<(usize, Vec<ValidationError>)>,
+    verbose: bool,
+    suggested: Option<usize>,
+) -> Vec<ValidationError> {
+    failures.sort_by_key(|(index, _)| *index);
 
-    #[test]
-    fn test_string_validation() {
-        let schema = SchemaType::String {
-            min_length: Some(2),
-            max_length: Some(10),
-            pattern: Some(r"^[a-zA-Z]+$".to_string()),
-            format: Some(StringFormat::Email),
+    if !verbose {
+        // A branch that got the value's type or a discriminator (a `const`
+        // property) wrong is further off than any branch that got them
+        // right; after that, fewer errors is closer. Ties go to the branch
+        // the summary suggests, so both name the same one, then the first.
+        let depth = summary.path.segments().len();
+        let distance = |errors: &[ValidationError]| {
+            let wrong_kind = errors.iter().any(|error| match error.path.segments().len() {
+                d if d == depth => error.code == ErrorCode::InvalidType,
+                d if d == depth + 1 => error.code == ErrorCode::ConstMismatch,
+                _ => false,
+            });
+            (wrong_kind, errors.len())
         };
+        let best = failures
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (index, errors))| (distance(errors), Some(*index) != suggested))
+            .map(|(position, _)| position);
+        failures = best.map(|position| failures.swap_remove(position)).into_iter().collect();
+    }
 
-        let mut validator = Validator::new(schema).unwrap();
+    let mut errors = vec![summary];
+    for (index, branch_errors) in failures {
+        errors.extend(branch_errors.into_iter().map(|mut error| {
+            // Errors of a nested union keep their innermost branch
+            if let Some(params) = error.params.as_object_mut() {
+                params.entry("branch").or_insert_with(|| serde_json::json!(index));
+            }
+            error
+        }));
+    }
+    errors
+}
 
-        // Valid email
-        let result = validator.validate(&json!("test@example.com"));
-        assert!(!result.success); // Fails pattern (only letters)
+/// The values of a union whose branches are all string constants (an
+/// enum), or none
+pub(crate) fn enum_values(schemas: &[SchemaType]) -> Vec<String> {
+    schemas
+        .iter()
+        .map(|schema| match schema.unannotated() {
+            SchemaType::Const { value: serde_json::Value::String(s) } => Some(s.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
 
-        // Valid pattern but invalid email
-        let result = validator.validate(&json!("teststring"));
-        assert!(!result.success); // Not an email
+/// The enum value a string that matched none of them was likely meant to
+/// be, as its index in `values` (which is also its branch index)
+pub(crate) fn enum_suggestion(value: &serde_json::Value, values: &[String]) -> Option<usize> {
+    let closest = closest_match(value.as_str()?, values.iter().map(String::as_str))?;
+    values.iter().position(|candidate| candidate == closest)
+}
 
-        // Too short
-        let result = validator.validate(&json!("a"));
-        assert!(!result.success);
+/// Error for a string that failed its format; some formats can say what is wrong
+pub(crate) fn format_failure(format: &StringFormat, s: &str, path: ErrorPath) -> ValidationError {
+    let detail = match format {
+        StringFormat::Duration => parse_iso_duration(s).err(),
+        StringFormat::DateTime | StringFormat::DateTimeIso => parse_rfc3339_date_time(s).err(),
+        StringFormat::Date => parse_full_date(s).err(),
+        _ => None,
+    };
+    let message = match detail {
+        Some(detail) => format!("String format '{}' validation failed: {}", fmt_name(format), detail),
+        None => format!("String format '{}' validation failed", fmt_name(format)),
+    };
 
-        // Wrong type
-        let result = validator.validate(&json!(123));
-        assert!(!result.success);
-    }
+    ValidationError::new(path, message, ErrorCode::StringFormatInvalid)
+}
 
-    #[test]
-    fn test_number_validation() {
-        let schema = SchemaType::Number {
-            min: Some(0.0),
+/// Check `minDate` / `maxDate` / `offsetRequired` / `precision` for a
+/// `date-time` or `date` string that passed format validation
+fn validate_date_constraints(
+    s: &str,
+    format: &StringFormat,
+    min_date: &Option<String>,
+    max_date: &Option<String>,
+    offset_required: bool,
+    precision: Option<usize>,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let timestamp = match format {
+        StringFormat::DateTime | StringFormat::DateTimeIso => {
+            let date_time = match parse_rfc3339_date_time(s) {
+                Ok(date_time) => date_time,
+                Err(_) => return errors,
+            };
+            if offset_required && date_time.offset_minutes.is_none() {
+                errors.push(ValidationError::new(
+                    context.path.build(),
+                    "Date-time must include a UTC offset ('Z' or '+HH:MM')".to_string(),
+                    ErrorCode::StringFormatInvalid,
+                ));
+            }
+            if let Some(digits) = precision {
+                if date_time.fraction_digits != digits {
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("Date-time must have exactly {} fractional second digits", digits),
+                        ErrorCode::StringFormatInvalid,
+                    ));
+                }
+            }
+            date_time.timestamp()
+        }
+        StringFormat::Date => match parse_date_bound(s) {
+            Ok(timestamp) => timestamp,
+            Err(_) => return errors,
+        },
+        _ => return errors,
+    };
+
+    let bounds = [
+        (min_date, Ordering::Less, "before", ErrorCode::StringDateTooEarly),
+        (max_date, Ordering::Greater, "after", ErrorCode::StringDateTooLate),
+    ];
+    for (bound, out_of_range, relation, code) in bounds {
+        let bound = match bound {
+            Some(bound) => bound,
+            None => continue,
+        };
+        match parse_date_bound(bound) {
+            Ok(limit) if timestamp.cmp(&limit) == out_of_range => errors.push(ValidationError::with_values(
+                context.path.build(),
+                format!("Date must not be {} {}", relation, bound),
+                code,
+                serde_json::json!(bound),
+                serde_json::json!(s),
+            )),
+            Ok(_) => {}
+            Err(e) => errors.push(ValidationError::new(
+                context.path.build(),
+                format!("Invalid date bound '{}': {}", bound, e),
+                ErrorCode::SchemaInvalid,
+            )),
+        }
+    }
+
+    errors
+}
+
+/// Check array ordering, reporting the first item that is out of order.
+/// Equal neighbours are allowed; items that are not comparable are skipped.
+fn validate_sorted(
+    arr: &[serde_json::Value],
+    order: SortOrder,
+    sorted_by: &Option<String>,
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let (comparison, direction) = match order {
+        SortOrder::Asc => (Comparison::Lte, "ascending"),
+        SortOrder::Desc => (Comparison::Gte, "descending"),
+    };
+    let key = |item: &'_ serde_json::Value| -> Option<serde_json::Value> {
+        match sorted_by {
+            Some(property) => item.get(property).cloned(),
+            None => Some(item.clone()),
+        }
+    };
+
+    let mut previous: Option<serde_json::Value> = None;
+    for (index, item) in arr.iter().enumerate() {
+        let current = match key(item) {
+            Some(current) => current,
+            None => continue,
+        };
+
+        if let Some(prev) = &previous {
+            if comparison.holds(prev, &current) == Some(false) {
+                let subject = match sorted_by {
+                    Some(property) => format!("'{}'", property),
+                    None => "items".to_string(),
+                };
+                context.path.push_index(index);
+                let error = ValidationError::with_values(
+                    context.path.build(),
+                    format!("Array {} must be sorted in {} order", subject, direction),
+                    ErrorCode::ArrayNotSorted,
+                    serde_json::json!(format!("{} {}", comparison.symbol(), prev)),
+                    current,
+                );
+                context.path.pop();
+                return vec![error];
+            }
+        }
+        previous = Some(current);
+    }
+
+    Vec::new()
+}
+
+/// Check cross-field rules, reporting errors on the dependent field
+fn validate_object_rules(
+    obj: &serde_json::Map<String, serde_json::Value>,
+    rules: &[ObjectRule],
+    context: &mut ValidationContext,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for rule in rules {
+        match rule {
+            ObjectRule::RequiredIf { field, when, equals } => {
+                if obj.get(when) == Some(equals) && !obj.contains_key(field) {
+                    let mut error = ValidationError::missing_property(context.path.build(), field);
+                    error.message = format!(
+                        "Property '{}' is required when '{}' is {}",
+                        field, when, equals
+                    );
+                    errors.push(error);
+                }
+            }
+            ObjectRule::MutuallyExclusive { fields } => {
+                let present: Vec<&String> = fields.iter().filter(|f| obj.contains_key(*f)).collect();
+                for field in present.iter().skip(1) {
+                    context.path.push(field);
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("Property '{}' cannot be combined with '{}'", field, present[0]),
+                        ErrorCode::ObjectMutuallyExclusive,
+                    ));
+                    context.path.pop();
+                }
+            }
+            ObjectRule::AtLeastOneOf { fields } => {
+                if !fields.iter().any(|f| obj.contains_key(f)) {
+                    errors.push(ValidationError::new(
+                        context.path.build(),
+                        format!("At least one of {} is required", fields.join(", ")),
+                        ErrorCode::ObjectMissingAlternative,
+                    ));
+                }
+            }
+            ObjectRule::Compare { field, op, other } => {
+                if let (Some(left), Some(right)) = (obj.get(field), obj.get(other)) {
+                    if op.holds(left, right) == Some(false) {
+                        context.path.push(field);
+                        errors.push(ValidationError::with_values(
+                            context.path.build(),
+                            format!("Property '{}' must be {} '{}'", field, op.symbol(), other),
+                            ErrorCode::ObjectComparisonFailed,
+                            serde_json::json!(format!("{} {}", op.symbol(), right)),
+                            left.clone(),
+                        ));
+                        context.path.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Schema references nested deeper than this are reported rather than
+/// followed, as a backstop to the cycle check in `with_definitions`
+const MAX_REF_DEPTH: usize = 256;
+
+/// A cycle of definitions that `$ref`s into each other without descending
+/// into the value, among those reachable from `root`
+fn reference_cycle(root: &SchemaType, definitions: &HashMap<String, SchemaType>) -> Option<Vec<String>> {
+    // Every definition the schema can reach, through any reference
+    let mut reachable: Vec<&str> = root.references();
+    let mut seen: HashSet<&str> = reachable.iter().copied().collect();
+    let mut index = 0;
+    while let Some(name) = reachable.get(index).copied() {
+        index += 1;
+        for reference in definitions.get(name).map(SchemaType::references).unwrap_or_default() {
+            if seen.insert(reference) {
+                reachable.push(reference);
+            }
+        }
+    }
+
+    // Depth-first search over the same-value references only; a frame's
+    // references are listed when it is entered
+    let mut done: HashSet<&str> = HashSet::new();
+    for start in reachable {
+        let mut chain: Vec<&str> = Vec::new();
+        let mut stack: Vec<(&str, Option<Vec<&str>>)> = vec![(start, None)];
+        while let Some((name, next)) = stack.last_mut() {
+            let name = *name;
+            let next = match next {
+                Some(next) => next,
+                None if done.contains(name) => {
+                    stack.pop();
+                    continue;
+                }
+                None => {
+                    if let Some(position) = chain.iter().position(|seen| *seen == name) {
+                        let mut cycle: Vec<String> = chain[position..].iter().map(|name| name.to_string()).collect();
+                        cycle.push(name.to_string());
+                        return Some(cycle);
+                    }
+                    chain.push(name);
+                    let mut references = definitions.get(name).map(SchemaType::same_value_references).unwrap_or_default();
+                    references.reverse();
+                    next.insert(references)
+                }
+            };
+            match next.pop() {
+                Some(reference) => stack.push((reference, None)),
+                None => {
+                    done.insert(name);
+                    chain.pop();
+                    stack.pop();
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Produce the output data for an already-validated value
+/// What the items of one batch share
+struct BatchShared {
+    memo: Option<SharedMemo>,
+    /// When `max_time_ms` runs out for the batch as a whole
+    deadline: Option<Instant>,
+}
+
+/// Report a validation that ran out of `max_time_ms`, at `path`
+fn check_deadline(path: String, errors: &mut Vec<ValidationError>, context: &mut ValidationContext) {
+    if context.past_deadline() {
+        errors.push(ValidationError::new(
+            path,
+            format!("Validation exceeded the time limit of {} ms", context.options.max_time_ms.unwrap_or_default()),
+            ErrorCode::LimitExceeded,
+        ));
+        context.truncated = true;
+    }
+}
+
+fn output_value(
+    value: &serde_json::Value,
+    schema: &SchemaType,
+    options: &ValidationOptions,
+    definitions: &HashMap<String, SchemaType>,
+) -> serde_json::Value {
+    match (schema, value) {
+        (SchemaType::Ref { reference }, _) => match definitions.get(reference) {
+            Some(target) => output_value(value, target, options, definitions),
+            None => value.clone(),
+        },
+        (SchemaType::Annotated { schema, .. }, _) => output_value(value, schema, options, definitions),
+        (SchemaType::Set { items, .. }, serde_json::Value::Array(arr)) => {
+            let mut unique_checker = UniqueChecker::new();
+            serde_json::Value::Array(
+                arr.iter()
+                    .filter(|item| !options.coerce || unique_checker.insert(item))
+                    .map(|item| output_value(item, items, options, definitions))
+                    .collect(),
+            )
+        }
+        (SchemaType::Array { items, .. }, serde_json::Value::Array(arr)) => {
+            serde_json::Value::Array(
+                arr.iter().map(|item| output_value(item, items, options, definitions)).collect()
+            )
+        }
+        (SchemaType::Tuple { prefix_items, items }, serde_json::Value::Array(arr)) => {
+            serde_json::Value::Array(
+                arr.iter()
+                    .enumerate()
+                    .map(|(index, item)| match prefix_items.get(index).or(items.as_deref()) {
+                        Some(item_schema) => output_value(item, item_schema, options, definitions),
+                        None => item.clone(),
+                    })
+                    .collect(),
+            )
+        }
+        (SchemaType::Map { values, .. }, serde_json::Value::Array(entries)) => {
+            serde_json::Value::Array(
+                entries.iter()
+                    .map(|entry| match entry.as_array() {
+                        Some(pair) if pair.len() == 2 => serde_json::json!([
+                            pair[0].clone(),
+                            output_value(&pair[1], values, options, definitions),
+                        ]),
+                        _ => entry.clone(),
+                    })
+                    .collect(),
+            )
+        }
+        (SchemaType::Map { values, .. }, serde_json::Value::Object(obj)) => {
+            serde_json::Value::Object(
+                obj.iter()
+                    .map(|(key, item)| (key.clone(), output_value(item, values, options, definitions)))
+                    .collect(),
+            )
+        }
+        (
+            SchemaType::Object {
+                properties,
+                additional_properties,
+                catchall,
+                read_only,
+                write_only,
+                ..
+            },
+            serde_json::Value::Object(obj),
+        ) => {
+            let stripped = if options.strip_inaccessible {
+                options.inaccessible_properties(read_only, write_only)
+            } else {
+                None
+            };
+
+            serde_json::Value::Object(
+                obj.iter()
+                    .filter_map(|(key, item)| {
+                        if stripped.is_some_and(|names| names.contains(key)) {
+                            return None;
+                        }
+
+                        let output = match (properties.get(key), catchall) {
+                            (Some(prop_schema), _) => output_value(item, prop_schema, options, definitions),
+                            (None, Some(catchall_schema)) => output_value(item, catchall_schema, options, definitions),
+                            (None, None) if *additional_properties == UnknownKeys::Strip => return None,
+                            (None, None) => item.clone(),
+                        };
+                        Some((key.clone(), output))
+                    })
+                    .collect(),
+            )
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Get human-readable format name
+fn fmt_name(format: &StringFormat) -> &str {
+    match format {
+        StringFormat::Email => "email",
+        StringFormat::Uri => "uri",
+        StringFormat::Url => "url",
+        StringFormat::Uuid => "uuid",
+        StringFormat::DateTime => "date-time",
+        StringFormat::Date => "date",
+        StringFormat::Time => "time",
+        StringFormat::Ipv4 => "ipv4",
+        StringFormat::Ipv6 => "ipv6",
+        StringFormat::Hostname => "hostname",
+        StringFormat::Fqdn => "fqdn",
+        StringFormat::JsonPointer => "json-pointer",
+        StringFormat::RelativeJsonPointer => "relative-json-pointer",
+        StringFormat::Regex => "regex",
+        StringFormat::Duration => "duration",
+        StringFormat::Ipv4Cidr => "ipv4-cidr",
+        StringFormat::Ipv6Cidr => "ipv6-cidr",
+        StringFormat::Base64 => "base64",
+        StringFormat::Base64Url => "base64url",
+        StringFormat::Jwt => "jwt",
+        StringFormat::Nanoid => "nanoid",
+        StringFormat::Cuid => "cuid",
+        StringFormat::Ulid => "ulid",
+        StringFormat::Ksuid => "ksuid",
+        StringFormat::ObjectId => "object-id",
+        StringFormat::Latitude => "latitude",
+        StringFormat::Longitude => "longitude",
+        StringFormat::GeoPoint => "geo-point",
+        StringFormat::Country => "country",
+        StringFormat::Language => "language",
+        StringFormat::Timezone => "timezone",
+        StringFormat::PhoneNumber => "phone-number",
+        StringFormat::PostalCode => "postal-code",
+        StringFormat::CreditCard => "credit-card",
+        StringFormat::Iban => "iban",
+        StringFormat::Bic => "bic",
+        StringFormat::Md5 => "md5",
+        StringFormat::Sha1 => "sha1",
+        StringFormat::Sha256 => "sha256",
+        StringFormat::Sha512 => "sha512",
+        StringFormat::Custom(name) => name,
+        // TODO: Add names for additional formats
+        _ => "unknown-format",
+    }
+}
+
+/// Batch validator for optimized bulk operations
+pub struct BatchValidator {
+    validator: Validator,
+    batch_size: usize,
+    /// Set in adaptive mode, replacing the fixed `batch_size`
+    adaptive: Option<Mutex<AdaptiveBatchSize>>,
+}
+
+impl Clone for BatchValidator {
+    fn clone(&self) -> Self {
+        Self {
+            validator: self.validator.clone(),
+            batch_size: self.batch_size,
+            adaptive: self.adaptive.as_ref().map(|adaptive| Mutex::new(*lock_adaptive(adaptive))),
+        }
+    }
+}
+
+impl BatchValidator {
+    pub fn new(schema: SchemaType, batch_size: usize) -> Result<Self, crate::error::FastSchemaError> {
+        Ok(Self {
+            validator: Validator::new(schema)?,
+            batch_size,
+            adaptive: None,
+        })
+    }
+
+    /// Rewrite error messages; see `Validator::set_error_map`
+    pub fn set_error_map<F>(&mut self, map: F)
+    where
+        F: Fn(&ErrorIssue) -> Option<String> + Send + Sync + 'static,
+    {
+        self.validator.set_error_map(map);
+    }
+
+    /// Size chunks so each takes about `target_ms` (e.g. 8 ms to fit in a
+    /// 60 fps frame), starting from the fixed batch size; `None` turns
+    /// adaptive mode off
+    pub fn set_frame_budget(&mut self, target_ms: Option<f64>) {
+        self.adaptive = target_ms.map(|target_ms| Mutex::new(AdaptiveBatchSize::new(target_ms, self.batch_size)));
+    }
+
+    /// Current chunk size: the fixed size, or the adaptive estimate
+    pub fn batch_size(&self) -> usize {
+        match &self.adaptive {
+            Some(adaptive) => lock_adaptive(adaptive).size(),
+            None => self.batch_size.max(1),
+        }
+    }
+
+    /// Split `values` into chunks of the current batch size, timing each
+    /// in adaptive mode. `f` gets the chunk's offset and returns whether
+    /// to go on.
+    fn for_each_chunk<F>(&self, values: &[serde_json::Value], mut f: F)
+    where
+        F: FnMut(usize, &[serde_json::Value]) -> bool,
+    {
+        let mut start = 0;
+        while start < values.len() {
+            let end = values.len().min(start + self.batch_size());
+            let timer = Instant::now();
+            if !f(start, &values[start..end]) {
+                return;
+            }
+            if let Some(adaptive) = &self.adaptive {
+                lock_adaptive(adaptive).record(end - start, timer.elapsed().as_secs_f64() * 1000.0);
+            }
+            start = end;
+        }
+    }
+
+    /// Validate large datasets efficiently
+    pub fn validate_dataset(&self, values: &[serde_json::Value]) -> Vec<ValidationResult> {
+        self.validate_partition(0, values)
+    }
+
+    /// Validate a dataset, stopping between chunks once `token` is cancelled
+    pub fn validate_dataset_cancellable(&self, values: &[serde_json::Value], token: &CancellationToken) -> BatchResults {
+        self.validate_dataset_until(values, || token.is_cancelled())
+    }
+
+    /// Validate a dataset chunk by chunk, stopping before the next chunk once
+    /// `is_cancelled` returns true; results cover the chunks already done
+    pub fn validate_dataset_until<F>(&self, values: &[serde_json::Value], is_cancelled: F) -> BatchResults
+    where
+        F: Fn() -> bool,
+    {
+        let mut results = Vec::with_capacity(values.len());
+        let mut cancelled = false;
+
+        self.for_each_chunk(values, |start, chunk| {
+            cancelled = is_cancelled();
+            if !cancelled {
+                results.extend(self.validator.validate_many_at(start, chunk));
+            }
+            !cancelled
+        });
+
+        BatchResults { results, cancelled }
+    }
+
+    /// Validate one partition of a dataset split across workers, `offset`
+    /// being the index of its first value in the whole dataset
+    pub fn validate_partition(&self, offset: usize, values: &[serde_json::Value]) -> Vec<ValidationResult> {
+        let mut results = Vec::with_capacity(values.len());
+
+        self.for_each_chunk(values, |start, chunk| {
+            results.extend(self.validator.validate_many_at(offset + start, chunk));
+            true
+        });
+
+        results
+    }
+
+    /// Validate a dataset into a `CompactBatchResult`, which only carries
+    /// details for the values that failed. Valid data is not copied.
+    pub fn validate_dataset_compact(&self, values: &[serde_json::Value], failures_only: bool) -> CompactBatchResult {
+        let options = ValidationOptions {
+            parallel_threshold: 100,
+            return_data: false,
+            ..Default::default()
+        };
+
+        let mut compact = CompactBatchResult::new(values.len(), failures_only);
+        self.for_each_chunk(values, |start, chunk| {
+            let results = self.validator.validate_many_with(start, chunk, options.clone());
+            for (position, result) in results.into_iter().enumerate() {
+                compact.push(start + position, result);
+            }
+            true
+        });
+        compact
+    }
+
+    /// Validate a dataset into valid and invalid rows, so callers can route
+    /// the good rows onward without walking a result per item. `valid`
+    /// holds indices, or with `include_items` the valid values themselves.
+    pub fn validate_dataset_partitioned(&self, values: &[serde_json::Value], include_items: bool) -> PartitionedBatchResult {
+        let options = ValidationOptions {
+            parallel_threshold: 100,
+            return_data: include_items,
+            ..Default::default()
+        };
+
+        let mut tracker = PerformanceTracker::new();
+        let (mut indices, mut items, mut invalid) = (Vec::new(), Vec::new(), Vec::new());
+        self.for_each_chunk(values, |start, chunk| {
+            let results = self.validator.validate_many_with(start, chunk, options.clone());
+            for (position, result) in results.into_iter().enumerate() {
+                if !result.success {
+                    invalid.push(FailedItem { index: start + position, errors: result.errors });
+                } else if include_items {
+                    items.push(result.data.unwrap_or_default());
+                } else {
+                    indices.push(start + position);
+                }
+            }
+            true
+        });
+        tracker.increment_items(values.len());
+
+        let performance = tracker.finish();
+        PartitionedBatchResult {
+            stats: PartitionStats {
+                total_items: values.len(),
+                valid_items: values.len() - invalid.len(),
+                invalid_items: invalid.len(),
+                total_errors: invalid.iter().map(|item| item.errors.len()).sum(),
+                validation_time_ms: performance.validation_time_ms,
+                throughput: performance.throughput,
+            },
+            valid: if include_items { ValidRows::Items(items) } else { ValidRows::Indices(indices) },
+            invalid,
+        }
+    }
+
+    /// Validate a numeric column without building a JSON array, returning
+    /// one flag per value (1 = valid). Values are checked as JavaScript
+    /// would serialize them: integral ones as integers, and non-finite ones
+    /// as the `"NaN"` / `"Infinity"` / `"-Infinity"` strings `allowNonFinite`
+    /// accepts.
+    ///
+    /// A plain `number` schema is checked on the raw floats, without
+    /// building errors or counting the values in `stats`.
+    pub fn validate_numbers(&self, values: &[f64]) -> Vec<u8> {
+        if let Some(check) = NumberCheck::from_validator(&self.validator) {
+            return values.iter().map(|&n| check.accepts(n) as u8).collect();
+        }
+
+        let options = ValidationOptions {
+            early_exit: true,
+            return_data: false,
+            ..Default::default()
+        };
+        values
+            .iter()
+            .map(|&n| self.validator.validate_with_options(&number_value(n), options.clone()).success as u8)
+            .collect()
+    }
+
+    /// Get validation statistics for optimization
+    pub fn get_stats(&self) -> ValidationStats {
+        self.validator.stats()
+    }
+
+    pub fn reset_stats(&self) {
+        self.validator.reset_stats();
+    }
+}
+
+/// The constraints of a `number` schema, checked on raw floats with the
+/// outcome `validate_number` gives for their `number_value`
+#[derive(Debug, Clone, Copy)]
+struct NumberCheck {
+    min: Option<f64>,
+    max: Option<f64>,
+    exclusive_min: Option<f64>,
+    exclusive_max: Option<f64>,
+    integer: bool,
+    multiple_of: Option<f64>,
+    allow_non_finite: bool,
+    strict_integer: bool,
+    tolerance: f64,
+}
+
+impl NumberCheck {
+    /// The check for a validator whose whole schema is a `number`, unless
+    /// custom keywords also apply
+    fn from_validator(validator: &Validator) -> Option<Self> {
+        if !validator.keywords.is_empty() {
+            return None;
+        }
+        match validator.compiled_schema.schema.unannotated() {
+            SchemaType::Number {
+                min, max, exclusive_min, exclusive_max, integer, multiple_of, allow_non_finite, strict_integer,
+                multiple_of_tolerance,
+            } => Some(Self {
+                min: *min,
+                max: *max,
+                exclusive_min: *exclusive_min,
+                exclusive_max: *exclusive_max,
+                integer: *integer,
+                multiple_of: *multiple_of,
+                allow_non_finite: *allow_non_finite,
+                strict_integer: *strict_integer,
+                tolerance: multiple_of_tolerance.unwrap_or(DEFAULT_MULTIPLE_OF_TOLERANCE),
+            }),
+            _ => None,
+        }
+    }
+
+    fn accepts(&self, n: f64) -> bool {
+        if !n.is_finite() && !self.allow_non_finite {
+            return false;
+        }
+        // Only integral values too large for an integer stay floats
+        let float_literal = || {
+            let value = number_value(n);
+            self.strict_integer && !value.is_i64() && !value.is_u64()
+        };
+        // NaN fails every comparison, so it is outside every range
+        !(self.integer && (!is_integer(n) || float_literal()))
+            && self.min.is_none_or(|min| n >= min)
+            && self.max.is_none_or(|max| n <= max)
+            && self.exclusive_min.is_none_or(|bound| n > bound)
+            && self.exclusive_max.is_none_or(|bound| n < bound)
+            && self.multiple_of.is_none_or(|multiple| is_multiple(n, multiple, self.tolerance))
+    }
+}
+
+/// `n` as JavaScript serializes it to JSON: integral values that fit an
+/// integer become one, and non-finite values their string spellings
+fn number_value(n: f64) -> serde_json::Value {
+    // 2^63 and 2^64 are exact as floats, unlike `i64::MAX` and `u64::MAX`
+    const I64_END: f64 = 9_223_372_036_854_775_808.0;
+    const U64_END: f64 = 18_446_744_073_709_551_616.0;
+    if n.fract() == 0.0 && (-I64_END..I64_END).contains(&n) {
+        serde_json::Value::from(n as i64)
+    } else if n.fract() == 0.0 && (0.0..U64_END).contains(&n) {
+        serde_json::Value::from(n as u64)
+    } else {
+        serde_json::Number::from_f64(n)
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| serde_json::Value::String(non_finite_name(n).to_string()))
+    }
+}
+
+/// Smallest and largest chunk sizes adaptive mode will pick
+const MIN_ADAPTIVE_BATCH_SIZE: usize = 1;
+const MAX_ADAPTIVE_BATCH_SIZE: usize = 100_000;
+
+/// Chunk size tuned from measured chunk latency to stay near a time budget
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AdaptiveBatchSize {
+    target_ms: f64,
+    size: usize,
+}
+
+impl AdaptiveBatchSize {
+    fn new(target_ms: f64, initial_size: usize) -> Self {
+        Self {
+            target_ms,
+            size: initial_size.clamp(MIN_ADAPTIVE_BATCH_SIZE, MAX_ADAPTIVE_BATCH_SIZE),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Adjust after `items` took `elapsed_ms`. Overruns shrink the size
+    /// at once; growth goes halfway so one fast chunk can't overshoot.
+    fn record(&mut self, items: usize, elapsed_ms: f64) {
+        if items == 0 {
+            return;
+        }
+        let ideal = if elapsed_ms > 0.0 {
+            (items as f64 * self.target_ms / elapsed_ms) as usize
+        } else {
+            // Too fast to measure
+            items * 2
+        };
+
+        let size = if ideal < self.size { ideal } else { (self.size + ideal) / 2 };
+        self.size = size.clamp(MIN_ADAPTIVE_BATCH_SIZE, MAX_ADAPTIVE_BATCH_SIZE);
+    }
+}
+
+fn lock_adaptive(adaptive: &Mutex<AdaptiveBatchSize>) -> MutexGuard<'_, AdaptiveBatchSize> {
+    adaptive.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Results of a batch that may have been cancelled part-way
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchResults {
+    /// Results for the leading values validated before cancellation
+    pub results: Vec<ValidationResult>,
+    pub cancelled: bool,
+}
+
+/// Batch results without an object per valid value, which is cheaper to
+/// serialize for mostly-valid datasets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactBatchResult {
+    pub total_items: usize,
+    /// Bit `i % 32` of word `i / 32` is set when value `i` is valid; omitted
+    /// in `failures_only` mode
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub valid_bitset: Option<Vec<u32>>,
+    /// Invalid values in index order
+    pub failed: Vec<FailedItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedItem {
+    pub index: usize,
+    pub errors: Vec<ValidationError>,
+}
+
+impl CompactBatchResult {
+    fn new(total_items: usize, failures_only: bool) -> Self {
+        Self {
+            total_items,
+            valid_bitset: if failures_only { None } else { Some(vec![0; total_items.div_ceil(32)]) },
+            failed: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, index: usize, result: ValidationResult) {
+        if result.success {
+            if let Some(bitset) = &mut self.valid_bitset {
+                bitset[index / 32] |= 1 << (index % 32);
+            }
+        } else {
+            self.failed.push(FailedItem { index, errors: result.errors });
+        }
+    }
+
+    /// Build from full results, e.g. to compact an existing batch
+    pub fn from_results(results: Vec<ValidationResult>, failures_only: bool) -> Self {
+        let mut compact = Self::new(results.len(), failures_only);
+        for (index, result) in results.into_iter().enumerate() {
+            compact.push(index, result);
+        }
+        compact
+    }
+
+    pub fn valid_items(&self) -> usize {
+        self.total_items - self.failed.len()
+    }
+
+    /// Whether value `index` passed, or `None` if it is out of range
+    pub fn is_valid(&self, index: usize) -> Option<bool> {
+        if index >= self.total_items {
+            return None;
+        }
+        Some(match &self.valid_bitset {
+            Some(bitset) => bitset[index / 32] & (1 << (index % 32)) != 0,
+            None => self.failed.binary_search_by_key(&index, |item| item.index).is_err(),
+        })
+    }
+}
+
+/// A dataset split into the rows that passed and those that failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionedBatchResult {
+    pub valid: ValidRows,
+    /// Invalid values in index order
+    pub invalid: Vec<FailedItem>,
+    pub stats: PartitionStats,
+}
+
+/// The valid rows of a partitioned batch, in index order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ValidRows {
+    Indices(Vec<usize>),
+    Items(Vec<serde_json::Value>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionStats {
+    pub total_items: usize,
+    pub valid_items: usize,
+    pub invalid_items: usize,
+    /// Errors across all invalid rows
+    pub total_errors: usize,
+    pub validation_time_ms: f64,
+    /// Items per second
+    pub throughput: f64,
+}
+
+/// Split `len` items into at most `parts` contiguous ranges whose sizes
+/// differ by at most one, for handing a dataset to a pool of workers
+pub fn partition_ranges(len: usize, parts: usize) -> Vec<std::ops::Range<usize>> {
+    let parts = parts.clamp(1, len.max(1));
+    let (size, remainder) = (len / parts, len % parts);
+
+    let mut start = 0;
+    (0..parts)
+        .map(|part| {
+            let end = start + size + usize::from(part < remainder);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationStats {
+    pub compiled_complexity: usize,
+    pub max_depth: usize,
+    pub has_patterns: bool,
+    pub regex_cache_size: usize,
+    /// Process-wide regex cache lookups
+    #[serde(default)]
+    pub regex_cache_hits: u64,
+    #[serde(default)]
+    pub regex_cache_misses: u64,
+    /// Hits over lookups, 0 before the first lookup
+    #[serde(default)]
+    pub regex_cache_hit_rate: f64,
+    #[serde(default)]
+    pub validations: u64,
+    /// Validations that reported at least one error
+    #[serde(default)]
+    pub invalid: u64,
+    /// Errors reported per error code
+    #[serde(default)]
+    pub errors_by_code: BTreeMap<String, u64>,
+    /// Schema nodes visited per type, by profiled validations only
+    #[serde(default)]
+    pub node_counts: BTreeMap<String, u64>,
+    /// Validations per second over the last ten seconds
+    #[serde(default)]
+    pub throughput: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PathStyle;
+    use serde_json::json;
+
+    #[test]
+    fn test_string_validation() {
+        let schema = SchemaType::String {
+            min_length: Some(2),
+            max_length: Some(10),
+            pattern: Some(r"^[a-zA-Z]+$".to_string()),
+            format: Some(StringFormat::Email),
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        // Valid email
+        let result = validator.validate(&json!("test@example.com"));
+        assert!(!result.success); // Fails pattern (only letters)
+
+        // Valid pattern but invalid email
+        let result = validator.validate(&json!("teststring"));
+        assert!(!result.success); // Not an email
+
+        // Too short
+        let result = validator.validate(&json!("a"));
+        assert!(!result.success);
+
+        // Wrong type
+        let result = validator.validate(&json!(123));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_number_validation() {
+        let schema = SchemaType::Number {
+            min: Some(0.0),
             max: Some(100.0),
+            exclusive_min: None,
+            exclusive_max: None,
             integer: true,
             multiple_of: Some(5.0),
+            allow_non_finite: false,
+            strict_integer: false,
+            multiple_of_tolerance: None,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        // Valid integer multiple of 5
+        let result = validator.validate(&json!(25));
+        assert!(result.success);
+
+        // Not integer
+        let result = validator.validate(&json!(25.5));
+        assert!(!result.success);
+
+        // Not multiple of 5
+        let result = validator.validate(&json!(23));
+        assert!(!result.success);
+
+        // Out of range
+        let result = validator.validate(&json!(150));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_object_validation() {
+        let mut properties = HashMap::new();
+        properties.insert("name".to_string(), SchemaType::String {
+            min_length: Some(2),
+            max_length: Some(50),
+            pattern: None,
+            format: None,
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
+        });
+        properties.insert("age".to_string(), SchemaType::Number {
+            min: Some(0.0),
+            max: Some(120.0),
+            exclusive_min: None,
+            exclusive_max: None,
+            integer: true,
+            multiple_of: None,
+            allow_non_finite: false,
+            strict_integer: false,
+            multiple_of_tolerance: None,
+        });
+
+        let schema = SchemaType::Object {
+            properties,
+            required: Some(vec!["name".to_string(), "age".to_string()]),
+            additional_properties: UnknownKeys::Strict,
+            catchall: None,
+            min_properties: None,
+            max_properties: None,
+            read_only: None,
+            write_only: None,
+            rules: None,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        // Valid object
+        let result = validator.validate(&json!({
+            "name": "John Doe",
+            "age": 30
+        }));
+        assert!(result.success);
+
+        // Missing required property
+        let result = validator.validate(&json!({
+            "name": "John Doe"
+        }));
+        assert!(!result.success);
+
+        // Additional property
+        let result = validator.validate(&json!({
+            "name": "John Doe",
+            "age": 30,
+            "extra": "not allowed"
+        }));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_object_size_validation() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "object", "properties": {}, "minProperties": 1, "maxProperties": 2}"#
+        ).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!({"a": 1})).success);
+        assert!(validator.validate(&json!({"a": 1, "b": 2})).success);
+
+        let result = validator.validate(&json!({}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectTooFewProperties);
+
+        let result = validator.validate(&json!({"a": 1, "b": 2, "c": 3}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectTooManyProperties);
+    }
+
+    #[test]
+    fn test_unknown_key_policies() {
+        let schema_for = |policy: &str| -> SchemaType {
+            serde_json::from_str(&format!(
+                r#"{{"type": "object", "properties": {{"name": {{"type": "string"}}}}, "additionalProperties": {}}}"#,
+                policy
+            )).unwrap()
+        };
+        let data = json!({"name": "John", "extra": 1});
+
+        // Legacy boolean form still works
+        let validator = Validator::new(schema_for("false")).unwrap();
+        let result = validator.validate(&data);
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectAdditionalProperty);
+
+        let validator = Validator::new(schema_for(r#""strict""#)).unwrap();
+        assert!(!validator.validate(&data).success);
+
+        let validator = Validator::new(schema_for(r#""strip""#)).unwrap();
+        let result = validator.validate(&data);
+        assert!(result.success);
+        assert_eq!(result.data, Some(json!({"name": "John"})));
+
+        let validator = Validator::new(schema_for(r#""passthrough""#)).unwrap();
+        assert_eq!(validator.validate(&data).data, Some(data.clone()));
+
+        // Catchall validates undeclared keys and keeps them
+        let mut schema = schema_for(r#""strip""#);
+        if let SchemaType::Object { catchall, .. } = &mut schema {
+            *catchall = Some(Box::new(SchemaType::Number {
+                min: None,
+                max: None,
+                exclusive_min: None,
+                exclusive_max: None,
+                integer: true,
+                multiple_of: None,
+                allow_non_finite: false,
+                strict_integer: false,
+                multiple_of_tolerance: None,
+            }));
+        }
+        let validator = Validator::new(schema).unwrap();
+        assert_eq!(validator.validate(&data).data, Some(data.clone()));
+        let result = validator.validate(&json!({"name": "John", "extra": "x"}));
+        assert_eq!(result.errors[0].path, "extra");
+    }
+
+    #[test]
+    fn test_read_write_only_modes() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "object",
+            "properties": {
+                "id": {"type": "string"},
+                "password": {"type": "string"},
+                "name": {"type": "string"}
+            },
+            "required": ["id", "name"],
+            "readOnly": ["id"],
+            "writeOnly": ["password"]
+        }"#).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+        let request = ValidationOptions { access_mode: Some(AccessMode::Request), ..Default::default() };
+        let response = ValidationOptions { access_mode: Some(AccessMode::Response), ..Default::default() };
+
+        // Read-only properties are neither required nor allowed in requests
+        assert!(validator.validate_with_options(&json!({"name": "a", "password": "x"}), request.clone()).success);
+        let result = validator.validate_with_options(&json!({"id": "1", "name": "a"}), request.clone());
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectReadOnlyProperty);
+        assert_eq!(result.errors[0].path, "id");
+
+        let result = validator.validate_with_options(&json!({"id": "1", "name": "a", "password": "x"}), response.clone());
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectWriteOnlyProperty);
+
+        // Strip mode removes them from the output instead
+        let strip = ValidationOptions { strip_inaccessible: true, ..response };
+        let result = validator.validate_with_options(&json!({"id": "1", "name": "a", "password": "x"}), strip);
+        assert!(result.success);
+        assert_eq!(result.data, Some(json!({"id": "1", "name": "a"})));
+
+        // Without a mode the annotations are ignored
+        assert!(validator.validate(&json!({"id": "1", "name": "a", "password": "x"})).success);
+    }
+
+    #[test]
+    fn test_schema_metadata() {
+        let schema = json!({
+            "type": "object",
+            "title": "User",
+            "properties": {
+                "email": {
+                    "type": "string",
+                    "description": "Primary contact address",
+                    "examples": ["a@example.com"],
+                    "meta": {"widget": "email"}
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string", "title": "Tag"}
+                }
+            }
+        });
+
+        let validator = Validator::from_json(&schema).unwrap();
+
+        assert_eq!(validator.get_metadata("").unwrap().title.as_deref(), Some("User"));
+        let email = validator.get_metadata("email").unwrap();
+        assert_eq!(email.description.as_deref(), Some("Primary contact address"));
+        assert_eq!(email.examples, Some(vec![json!("a@example.com")]));
+        assert_eq!(email.meta, Some(json!({"widget": "email"})));
+
+        // Data paths resolve to the item schema
+        assert_eq!(validator.get_metadata("tags.[3]").unwrap().title.as_deref(), Some("Tag"));
+        assert_eq!(validator.get_metadata("tags.[]").unwrap().title.as_deref(), Some("Tag"));
+        assert!(validator.get_metadata("tags").is_none());
+
+        // Annotations survive building from a `SchemaType`
+        let validator = Validator::new(serde_json::from_value(schema).unwrap()).unwrap();
+        assert_eq!(validator.get_metadata("email").unwrap().meta, Some(json!({"widget": "email"})));
+
+        // Keys containing `.` don't collide with nested properties
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "a.b": {"type": "string", "title": "Dotted"},
+                "a": {"type": "object", "properties": {"b": {"type": "string", "title": "Nested"}}}
+            }
+        })).unwrap();
+        let dotted = [PathSegment::Key("a.b".into())];
+        let nested = [PathSegment::Key("a".into()), PathSegment::Key("b".into())];
+        assert_eq!(validator.compiled_schema.metadata_at(&dotted).unwrap().title.as_deref(), Some("Dotted"));
+        assert_eq!(validator.compiled_schema.metadata_at(&nested).unwrap().title.as_deref(), Some("Nested"));
+    }
+
+    #[test]
+    fn test_content_encoding_validation() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "string",
+            "contentEncoding": "base64",
+            "contentMediaType": "application/json",
+            "contentSchema": {"type": "object", "properties": {"id": {"type": "number"}}, "required": ["id"]}
+        }"#).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+
+        // {"id":1} and {"id":"x"}
+        assert!(validator.validate(&json!("eyJpZCI6MX0=")).success);
+        let result = validator.validate(&json!("eyJpZCI6IngifQ=="));
+        assert_eq!(result.errors[0].path, "id");
+
+        let result = validator.validate(&json!("not base64!"));
+        assert_eq!(result.errors[0].code, ErrorCode::StringContentEncodingInvalid);
+
+        // "hello" is valid base64 but not JSON
+        let result = validator.validate(&json!("aGVsbG8="));
+        assert_eq!(result.errors[0].code, ErrorCode::StringContentMediaTypeInvalid);
+    }
+
+    #[test]
+    fn test_cross_field_rules() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "object",
+            "properties": {},
+            "rules": [
+                {"rule": "requiredIf", "field": "state", "when": "country", "equals": "US"},
+                {"rule": "mutuallyExclusive", "fields": ["email", "phone"]},
+                {"rule": "atLeastOneOf", "fields": ["email", "phone", "fax"]},
+                {"rule": "compare", "field": "endDate", "op": "gte", "other": "startDate"}
+            ]
+        }"#).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!({
+            "country": "US", "state": "CA", "email": "a@b.co",
+            "startDate": "2024-01-01", "endDate": "2024-02-01"
+        })).success);
+
+        let result = validator.validate(&json!({"country": "US", "fax": "1"}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectMissingProperty);
+        assert!(result.errors[0].path.to_string().ends_with("state"));
+
+        let result = validator.validate(&json!({"email": "a@b.co", "phone": "1"}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectMutuallyExclusive);
+        assert_eq!(result.errors[0].path, "phone");
+
+        let result = validator.validate(&json!({"country": "NO"}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectMissingAlternative);
+
+        let result = validator.validate(&json!({"fax": "1", "startDate": "2024-02-01", "endDate": "2024-01-01"}));
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectComparisonFailed);
+        assert_eq!(result.errors[0].path, "endDate");
+    }
+
+    #[test]
+    fn test_unique_by_validation() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "array",
+            "items": {"type": "object", "properties": {"id": {"type": "number"}}},
+            "uniqueBy": "id"
+        }"#).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+
+        // Objects differ, but only the key matters
+        assert!(validator.validate(&json!([{"id": 1, "v": "a"}, {"id": 2, "v": "a"}, {}])).success);
+
+        let result = validator.validate(&json!([{"id": 1}, {"id": 2}, {"id": 1, "v": "b"}]));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code, ErrorCode::ArrayNotUnique);
+        assert_eq!(result.errors[0].path, "[2]");
+        assert!(result.errors[0].message.contains("index 0"));
+    }
+
+    #[test]
+    fn test_sorted_arrays() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "array",
+            "items": {"type": "object", "properties": {"ts": {"type": "string"}}},
+            "sortedBy": "ts"
+        }"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!([
+            {"ts": "2024-01-01T00:00:00Z"}, {"ts": "2024-01-01T00:00:00Z"}, {"ts": "2024-03-01T00:00:00Z"}
+        ])).success);
+
+        let result = validator.validate(&json!([{"ts": "2024-02-01"}, {"ts": "2024-01-01"}]));
+        assert_eq!(result.errors[0].code, ErrorCode::ArrayNotSorted);
+        assert_eq!(result.errors[0].path, "[1]");
+
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "array", "items": {"type": "number"}, "sorted": "desc"}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!([3, 2, 2, 1])).success);
+        assert!(!validator.validate(&json!([3, 1, 2])).success);
+    }
+
+    #[test]
+    fn test_const_validation() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "oneof",
+            "oneOf": [
+                {"type": "const", "const": "light"},
+                {"type": "const", "const": {"mode": "custom", "level": 2}}
+            ]
+        }"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!("light")).success);
+        assert!(validator.validate(&json!({"level": 2.0, "mode": "custom"})).success);
+        assert!(!validator.validate(&json!("dark")).success);
+
+        let validator = Validator::new(SchemaType::Const { value: json!([1, 2]) }).unwrap();
+        let result = validator.validate(&json!([2, 1]));
+        assert_eq!(result.errors[0].code, ErrorCode::ConstMismatch);
+    }
+
+    #[test]
+    fn test_coordinates_as_numbers() {
+        let schema: SchemaType = serde_json::from_str(r#"{"type": "string", "format": "latitude"}"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!(45.5)).success);
+        assert!(validator.validate(&json!("45.5")).success);
+        assert_eq!(validator.validate(&json!(-91)).errors[0].code, ErrorCode::StringFormatInvalid);
+        assert_eq!(validator.validate(&json!(true)).errors[0].code, ErrorCode::InvalidType);
+    }
+
+    #[test]
+    fn test_regional_formats() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "string", "format": "postal-code", "country": "US"}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!("02134")).success);
+        assert!(!validator.validate(&json!("SW1A 1AA")).success);
+
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "string", "format": "phone-number", "country": "NO"}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!("22 33 44 55")).success);
+        assert!(validator.validate(&json!("+14155552671")).success);
+        assert!(!validator.validate(&json!("22 33 44 55 ext")).success);
+    }
+
+    #[test]
+    fn test_error_limits() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 5, "pattern": "^[0-9]+$", "format": "email"},
+                "tags": {"type": "array", "items": {"type": "number"}}
+            },
+            "required": ["id", "name"]
+        }"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        let data = json!({"name": "ab", "tags": ["x", "y", "z"]});
+
+        let result = validator.validate(&data);
+        assert_eq!(result.errors.len(), 7);
+        assert!(!result.errors_truncated);
+
+        let early_exit = ValidationOptions { early_exit: true, ..Default::default() };
+        let result = validator.validate_with_options(&data, early_exit);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors_truncated);
+
+        // A single string node stops mid-way too
+        let result = validator.validate_with_options(
+            &json!({"id": 1, "name": "ab"}),
+            ValidationOptions { max_errors: Some(2), ..Default::default() },
+        );
+        assert_eq!(result.errors.len(), 2);
+        assert!(result.errors_truncated);
+
+        let result = validator.validate_with_options(
+            &data,
+            ValidationOptions { max_errors: Some(7), ..Default::default() },
+        );
+        assert_eq!(result.errors.len(), 7);
+        assert!(!result.errors_truncated);
+    }
+
+    #[test]
+    fn test_internationalized_hostnames() {
+        let schema: SchemaType = serde_json::from_str(r#"{"type": "string", "format": "fqdn"}"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!("xn--mnchen-3ya.de")).success);
+        assert!(!validator.validate(&json!("münchen.de")).success);
+
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "string", "format": "fqdn", "allowIdn": true}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!("münchen.de")).success);
+        assert!(!validator.validate(&json!("münchen")).success);
+    }
+
+    #[test]
+    fn test_payment_formats() {
+        let schema: SchemaType = serde_json::from_str(r#"{"type": "string", "format": "creditCard"}"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!("4242 4242 4242 4242")).success);
+        assert!(!validator.validate(&json!("4242 4242 4242 4241")).success);
+
+        let schema: SchemaType = serde_json::from_str(r#"{"type": "string", "format": "iban"}"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!("FR1420041010050500013M02606")).success);
+        assert!(!validator.validate(&json!("FR1420041010050500013M02607")).success);
+    }
+
+    #[test]
+    fn test_custom_formats() {
+        let schema: SchemaType = serde_json::from_str(r#"{"type": "string", "format": "sku"}"#).unwrap();
+        assert_eq!(schema, SchemaType::String {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            format: Some(StringFormat::Custom("sku".to_string())),
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
+        });
+        // Unregistered formats fail to compile, in the schema or its definitions
+        let error = Validator::new(schema.clone()).err().unwrap();
+        assert!(error.to_string().contains("Unknown string format 'sku'"));
+        let definitions = Arc::new(HashMap::from([("Sku".to_string(), schema.clone())]));
+        let referencing: SchemaType = serde_json::from_value(json!({"type": "ref", "$ref": "Sku"})).unwrap();
+        assert!(Validator::new(referencing).unwrap().with_definitions(definitions).is_err());
+
+        let mut formats = FormatRegistry::new();
+        formats.register_pattern("sku", r"^[A-Z]{3}-\d{4}$").unwrap();
+        let mut validator = Validator::new_with_formats(schema.clone(), Arc::new(formats)).unwrap();
+        assert!(validator.validate(&json!("ABC-1234")).success);
+        assert_eq!(validator.validate(&json!("abc")).errors[0].code, ErrorCode::StringFormatInvalid);
+
+        // Registered formats can be replaced afterwards
+        validator.register_format("sku", |value| value.starts_with("SKU"));
+        assert!(validator.validate(&json!("SKU1")).success);
+
+        // Only a validator loaded without compiling can meet an unknown format
+        let loaded = Validator::from_compiled(schema.compile());
+        assert_eq!(loaded.validate(&json!("ABC-1234")).errors[0].code, ErrorCode::SchemaInvalid);
+        let annotate = ValidationOptions { unknown_formats: UnknownFormats::Ignore, ..Default::default() };
+        assert!(loaded.validate_with_options(&json!("anything"), annotate).success);
+    }
+
+    #[test]
+    fn test_clone_shares_formats() {
+        let mut formats = FormatRegistry::new();
+        formats.register_fn("sku", |value| value.starts_with("SKU"));
+        let validator = Validator::from_json_with_formats(&json!({"type": "string", "format": "sku"}), Arc::new(formats)).unwrap();
+        let profile = ValidationOptions { profile: true, ..Default::default() };
+        validator.validate_with_options(&json!("SKU1"), profile);
+        assert!(validator.get_profile().is_some());
+
+        let clone = validator.clone();
+        assert!(clone.validate(&json!("SKU1")).success);
+        assert!(!clone.validate(&json!("abc")).success);
+        assert!(clone.get_profile().is_none());
+    }
+
+    #[test]
+    fn test_nanoid_custom_length() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "string", "format": "nanoid", "minLength": 10, "maxLength": 10}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!("IRFa-VaY2b")).success);
+        assert!(!validator.validate(&json!("V1StGXR8_Z5jdHi6B-myT")).success);
+        assert!(!validator.validate(&json!("IRFa+VaY2b")).success);
+    }
+
+    #[test]
+    fn test_strict_email_option() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "string", "format": "email", "emailOptions": {"allowIpLiteral": true}}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!("\"quoted local\"@example.com")).success);
+        assert!(validator.validate(&json!("admin@[10.0.0.1]")).success);
+        assert!(!validator.validate(&json!("a..b@example.com")).success);
+    }
+
+    #[test]
+    fn test_date_time_constraints() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "string",
+            "format": "date-time",
+            "minDate": "2024-01-01",
+            "maxDate": "2024-12-31T23:59:59Z",
+            "offsetRequired": true,
+            "precision": 3
+        }"#).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!("2024-06-01T12:00:00.000+02:00")).success);
+        // 00:30 at +01:00 is still 2023 in UTC
+        let result = validator.validate(&json!("2024-01-01T00:30:00.000+01:00"));
+        assert_eq!(result.errors[0].code, ErrorCode::StringDateTooEarly);
+        let result = validator.validate(&json!("2025-01-01T00:00:00.000Z"));
+        assert_eq!(result.errors[0].code, ErrorCode::StringDateTooLate);
+
+        // Both failures share a code and path, so only the first is reported
+        let result = validator.validate(&json!("2024-06-01T12:00:00.5"));
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].message.contains("UTC offset"));
+        let result = validator.validate(&json!("2024-06-01T12:00:00.5Z"));
+        assert!(result.errors[0].message.contains("3 fractional"));
+
+        let result = validator.validate(&json!("2024-02-30T12:00:00.000Z"));
+        assert!(result.errors[0].message.contains("day 30 is out of range"));
+    }
+
+    #[test]
+    fn test_uuid_version_constraint() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "string", "format": "uuid", "uuidVersion": 7}"#
+        ).unwrap();
+        let validator = Validator::new(schema).unwrap();
+
+        assert!(validator.validate(&json!("018f4e1c-7b2a-7c3d-9e4f-5a6b7c8d9e0f")).success);
+
+        let result = validator.validate(&json!("550e8400-e29b-41d4-a716-446655440000"));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code, ErrorCode::StringFormatInvalid);
+        assert!(result.errors[0].message.contains("version 7"));
+    }
+
+    #[test]
+    fn test_array_validation() {
+        let schema = SchemaType::Array {
+            items: Box::new(SchemaType::Number {
+                min: Some(0.0),
+                max: None,
+                exclusive_min: None,
+                exclusive_max: None,
+                integer: true,
+                multiple_of: None,
+                allow_non_finite: false,
+                strict_integer: false,
+                multiple_of_tolerance: None,
+            }),
+            min_items: Some(1),
+            max_items: Some(5),
+            unique_items: true,
+            unique_by: None,
+            sorted: None,
+            sorted_by: None,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        // Valid array
+        let result = validator.validate(&json!([1, 2, 3]));
+        assert!(result.success);
+
+        // Duplicate items
+        let result = validator.validate(&json!([1, 2, 2]));
+        assert!(!result.success);
+
+        // Too many items
+        let result = validator.validate(&json!([1, 2, 3, 4, 5, 6]));
+        assert!(!result.success);
+
+        // Empty array
+        let result = validator.validate(&json!([]));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_one_of_validation() {
+        let schema = SchemaType::OneOf {
+            schemas: vec![
+                SchemaType::String {
+                    min_length: None,
+                    max_length: None,
+                    pattern: None,
+                    format: None,
+                    content_encoding: None,
+                    content_media_type: None,
+                    content_schema: None,
+                    country: None,
+                    uuid_version: None,
+                    email_options: None,
+                    min_date: None,
+                    max_date: None,
+                    offset_required: false,
+                    precision: None,
+                    allow_idn: false,
+                    length_unit: LengthUnit::Chars,
+                },
+                SchemaType::Number {
+                    min: None,
+                    max: None,
+                    exclusive_min: None,
+                    exclusive_max: None,
+                    integer: false,
+                    multiple_of: None,
+                    allow_non_finite: false,
+                    strict_integer: false,
+                    multiple_of_tolerance: None,
+                },
+            ],
+            unevaluated_properties: None,
+            unevaluated_items: None,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        // Valid string
+        let result = validator.validate(&json!("test"));
+        assert!(result.success);
+
+        // Valid number
+        let result = validator.validate(&json!(42));
+        assert!(result.success);
+
+        // Invalid type
+        let result = validator.validate(&json!(true));
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_unevaluated_properties() {
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "allof",
+            "allOf": [
+                {"type": "object", "properties": {"name": {"type": "string"}}},
+                {"type": "object", "properties": {"age": {"type": "number"}}}
+            ],
+            "unevaluatedProperties": false
+        }"#).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.compiled_schema.has_unevaluated);
+
+        // Properties evaluated by any branch are not flagged
+        assert!(validator.validate(&json!({"name": "John", "age": 30})).success);
+
+        let result = validator.validate(&json!({"name": "John", "extra": true}));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code, ErrorCode::ObjectUnevaluatedProperty);
+        // A root property's path has no leading separator
+        assert_eq!(result.errors[0].path.segments(), [PathSegment::Key("extra".to_string())]);
+        assert_eq!((result.errors[0].path.to_string(), result.errors[0].path.pointer()), ("extra".to_string(), "/extra".to_string()));
+
+        // Only matching anyOf branches contribute evaluated keys
+        let schema: SchemaType = serde_json::from_str(r#"{
+            "type": "anyof",
+            "anyOf": [
+                {"type": "object", "properties": {"kind": {"type": "string"}}},
+                {"type": "object", "properties": {"size": {"type": "number"}}}
+            ],
+            "unevaluatedProperties": {"type": "boolean"}
+        }"#).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+        assert!(validator.validate(&json!({"kind": "a", "size": 1, "flag": true})).success);
+        let result = validator.validate(&json!({"kind": "a", "size": "big"}));
+        assert_eq!(result.errors[0].path.segments(), [PathSegment::Key("size".to_string())]);
+    }
+
+    #[test]
+    fn test_batch_validation() {
+        let schema = SchemaType::String {
+            min_length: Some(2),
+            max_length: Some(10),
+            pattern: None,
+            format: None,
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        let values = vec![
+            json!("valid1"),
+            json!("valid2"),
+            json!("x"), // Too short
+            json!(123), // Wrong type
+            json!("valid3"),
+        ];
+
+        let results = validator.validate_many(&values);
+        assert_eq!(results.len(), 5);
+        assert!(results[0].success);
+        assert!(results[1].success);
+        assert!(!results[2].success);
+        assert!(!results[3].success);
+        assert!(results[4].success);
+    }
+
+    #[test]
+    fn test_performance_tracking() {
+        let schema = SchemaType::String {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            format: None,
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
+        };
+
+        let validator = Validator::new(schema).unwrap();
+
+        let values: Vec<_> = (0..1000).map(|i| json!(format!("string_{}", i))).collect();
+        let results = validator.validate_many(&values);
+
+        assert_eq!(results.len(), 1000);
+
+        // Check that performance stats are included
+        if let Some(perf) = &results[0].performance {
+            assert!(perf.validation_time_ms >= 0.0);
+            assert!(perf.throughput > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_parallel_batch_keeps_order_and_paths() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"id": {"type": "number", "integer": true}, "name": {"type": "string"}},
+            "required": ["id"]
+        })).unwrap();
+
+        let values: Vec<_> = (0..2500)
+            .map(|i| if i % 1000 == 7 { json!({"id": "bad"}) } else { json!({"id": i, "name": "n"}) })
+            .collect();
+        let results = validator.validate_many(&values);
+
+        assert_eq!(results.len(), 2500);
+        for (index, result) in results.iter().enumerate() {
+            assert_eq!(result.success, index % 1000 != 7, "index {}", index);
+        }
+        assert_eq!(results[2007].errors[0].path, "[2007].id");
+    }
+
+    #[test]
+    fn test_batch_plan_matches_interpreter() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "number", "integer": true, "min": 1},
+                "email": {"type": "string", "format": "email"},
+                "tags": {"type": "array", "items": {"type": "string", "minLength": 2}, "maxItems": 2}
+            },
+            "required": ["id"]
+        })).unwrap();
+        let values: Vec<_> = (0..300)
+            .map(|i| match i % 4 {
+                0 => json!({"id": i + 1, "email": "a@example.com", "tags": ["ab"]}),
+                1 => json!({"id": 0, "email": "nope"}),
+                2 => json!({"tags": ["a", "bc", "de"]}),
+                _ => json!("not an object"),
+            })
+            .collect();
+
+        // Profiling runs the interpreter instead of the plan
+        let planned = validator.validate_many(&values);
+        let interpreted = validator.validate_many_with_options(&values, ValidationOptions { profile: true, ..Default::default() });
+        assert_eq!(planned.len(), interpreted.len());
+        for (index, (planned, interpreted)) in planned.iter().zip(&interpreted).enumerate() {
+            assert_eq!(planned.success, interpreted.success, "index {}", index);
+            assert_eq!(planned.errors, interpreted.errors, "index {}", index);
+        }
+        assert_eq!(planned[1].errors.len(), 2);
+    }
+
+    #[test]
+    fn test_batch_output_is_transformed() {
+        let schema = json!({"type": "object", "properties": {"id": {"type": "number"}}, "additionalProperties": "strip"});
+        let validator = Validator::from_json(&schema).unwrap();
+        let values: Vec<_> = (0..150).map(|i| json!({"id": i, "extra": true})).collect();
+
+        let results = validator.validate_many(&values);
+        assert_eq!(results[149].data, Some(json!({"id": 149})));
+        let results = validator.validate_many_with_options(&values[..2], ValidationOptions::default());
+        assert_eq!(results[1].data, Some(json!({"id": 1})));
+
+        let batch = BatchValidator::new(serde_json::from_value(schema).unwrap(), 4).unwrap();
+        let results = batch.validate_partition(10, &values[..5]);
+        assert!(results.iter().all(|result| result.data.as_ref().unwrap().get("extra").is_none()));
+    }
+
+    #[test]
+    fn test_validator_is_shareable_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Validator>();
+
+        let validator = Arc::new(Validator::from_json(&json!({
+            "type": "string",
+            "pattern": "^thread-[0-9]+$"
+        })).unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let validator = Arc::clone(&validator);
+                std::thread::spawn(move || {
+                    validator.validate(&json!(format!("thread-{}", i))).success
+                        && !validator.validate(&json!("other")).success
+                })
+            })
+            .collect();
+
+        assert!(handles.into_iter().all(|handle| handle.join().unwrap()));
+    }
+
+    #[test]
+    fn test_partitioned_batches() {
+        assert_eq!(partition_ranges(10, 3), vec![0..4, 4..7, 7..10]);
+        assert_eq!(partition_ranges(2, 4), vec![0..1, 1..2]);
+        assert_eq!(partition_ranges(0, 4), vec![0..0]);
+
+        let schema = serde_json::from_value(json!({"type": "number", "min": 0})).unwrap();
+        let batch = BatchValidator::new(schema, 4).unwrap();
+        let values: Vec<_> = (0..10).map(|i| json!(if i == 8 { -1 } else { i })).collect();
+
+        let whole = batch.validate_dataset(&values);
+        let merged: Vec<_> = partition_ranges(values.len(), 3)
+            .into_iter()
+            .flat_map(|range| batch.validate_partition(range.start, &values[range]))
+            .collect();
+
+        assert_eq!(whole.len(), 10);
+        assert_eq!(whole[8].errors[0].path, "[8]");
+        assert_eq!(
+            whole.iter().map(|r| &r.errors).collect::<Vec<_>>(),
+            merged.iter().map(|r| &r.errors).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_return_data_and_raw_values() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}}
+        })).unwrap();
+        let document = json!({"name": "Ada"});
+
+        let skip = ValidationOptions { return_data: false, ..Default::default() };
+        let result = validator.validate_with_options(&document, skip.clone());
+        assert!(result.success);
+        assert_eq!(result.data, None);
+
+        let raw = serde_json::value::to_raw_value(&document).unwrap();
+        let result = validator.validate_raw(&raw, ValidationOptions::default()).unwrap();
+        assert_eq!(result.data, Some(document));
+        assert_eq!(validator.validate_raw(&raw, skip).unwrap().data, None);
+
+        let invalid = RawValue::from_string(r#"{"name": 1}"#.to_string()).unwrap();
+        let result = validator.validate_raw(&invalid, ValidationOptions::default()).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].path, "name");
+        assert_eq!(result.errors[0].location, None);
+
+        let locations = ValidationOptions { source_locations: true, ..Default::default() };
+        let invalid = RawValue::from_string("{\n  \"name\": 1\n}".to_string()).unwrap();
+        let location = validator.validate_raw(&invalid, locations).unwrap().errors[0].location.unwrap();
+        assert_eq!((location.line, location.column, location.start, location.end), (2, 11, 12, 13));
+
+        let wrong_type = RawValue::from_string("[1, 2]".to_string()).unwrap();
+        assert!(!validator.validate_raw(&wrong_type, ValidationOptions::default()).unwrap().success);
+    }
+
+    #[test]
+    fn test_parse_into() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Point {
+            x: i64,
+            y: i64,
+        }
+
+        let schema = crate::builder::Schema::object()
+            .prop("x", crate::builder::Schema::integer().min(0))
+            .prop("y", crate::builder::Schema::integer())
+            .strip();
+        let validator = Validator::new(schema.build().unwrap()).unwrap();
+
+        // The unknown key is stripped before deserializing
+        let point: Point = validator.parse_into(&json!({"x": 1, "y": 2, "z": 3})).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+        let errors = validator.parse_into::<Point>(&json!({"x": -1})).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        let errors = validator.parse_into::<(i64, i64)>(&json!({"x": 1, "y": 2})).unwrap_err();
+        assert_eq!(errors[0].code, ErrorCode::DeserializationFailed);
+
+        let point: Point = validator.parse_str_into(r#"{"x": 1, "y": 2}"#).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+        assert_eq!(validator.parse_str_into::<Point>(r#"{"x": -1, "y": 2}"#).unwrap_err()[0].path, "x");
+        let errors = validator.parse_str_into::<Point>(r#"{"x": 1, "y": 2} trailing"#).unwrap_err();
+        assert_eq!(errors[0].code, ErrorCode::DeserializationFailed);
+    }
+
+    #[test]
+    fn test_partial_validation_paths() {
+        assert_eq!(
+            ErrorPath::parse("profile.emails[0]").segments(),
+            [PathSegment::Key("profile".into()), PathSegment::Key("emails".into()), PathSegment::Index(0)]
+        );
+        assert_eq!(ErrorPath::parse("roles.[1]").segments(), [PathSegment::Key("roles".into()), PathSegment::Index(1)]);
+        assert_eq!(ErrorPath::parse("a[b]").segments(), [PathSegment::Key("a[b]".into())]);
+
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "id": {"type": "number"},
+                "profile": {
+                    "type": "object",
+                    "properties": {"email": {"type": "string", "format": "email"}, "name": {"type": "string"}},
+                    "required": ["email", "name"]
+                },
+                "roles": {"type": "array", "items": {"type": "string", "minLength": 2}}
+            },
+            "required": ["id", "profile"]
+        })).unwrap();
+        let patch = |paths: &[&str]| ValidationOptions {
+            paths: Some(paths.iter().map(|p| p.to_string()).collect()),
+            ..Default::default()
+        };
+
+        // Required siblings outside the listed paths are not checked
+        let document = json!({"profile": {"email": "a@example.com"}, "roles": ["admin", "x"]});
+        let result = validator.validate_with_options(&document, patch(&["profile.email"]));
+        assert!(result.success);
+        assert_eq!(result.data, Some(document.clone()));
+        assert!(!validator.validate(&document).success);
+
+        let result = validator.validate_with_options(&document, patch(&["profile.email", "roles"]));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "roles.[1]");
+
+        assert!(validator.validate_with_options(&document, patch(&["roles[0]", "id"])).success);
+        assert!(!validator.validate_with_options(&document, patch(&["roles[1]"])).success);
+
+        let result = validator.validate_with_options(&document, patch(&["profile.phone"]));
+        assert_eq!(result.errors[0].code, ErrorCode::SchemaInvalid);
+
+        // Batches honour the paths too
+        let documents = [document.clone(), json!({"profile": {"email": "nope"}, "roles": ["admin"]})];
+        let results = validator.validate_many_with_options(&documents, patch(&["roles[0]", "id"]));
+        assert!(results.iter().all(|result| result.success));
+        let results = validator.validate_many_with_options(&documents, patch(&["profile.email"]));
+        assert!(results[0].success);
+        assert_eq!(results[1].errors.len(), 1);
+        assert_eq!(results[1].errors[0].path.pointer(), "/1/profile/email");
+
+        // Paths lead through compositions and tuples
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "point": {"type": "tuple", "prefixItems": [{"type": "number"}], "items": {"type": "string"}},
+                "contact": {"allOf": [
+                    {"type": "object", "properties": {"email": {"type": "string", "format": "email"}}},
+                    {"type": "object", "properties": {"name": {"type": "string", "minLength": 2}}}
+                ]},
+                "id": {"anyOf": [
+                    {"type": "object", "properties": {"uuid": {"type": "string", "format": "uuid"}}, "required": ["uuid"]},
+                    {"type": "object", "properties": {"serial": {"type": "integer"}}, "required": ["serial"]}
+                ]}
+            }
+        })).unwrap();
+        let document = json!({
+            "point": [1, "a", 2],
+            "contact": {"email": "a@example.com", "name": "x"},
+            "id": {"serial": "7"}
+        });
+        assert!(validator.validate_with_options(&document, patch(&["point[0]", "point[1]", "contact.email"])).success);
+        let result = validator.validate_with_options(&document, patch(&["point[2]", "contact.name"]));
+        let paths: Vec<_> = result.errors.iter().map(|e| e.path.pointer()).collect();
+        assert_eq!(paths, ["/point/2", "/contact/name"]);
+        let result = validator.validate_with_options(&document, patch(&["id.serial"]));
+        assert_eq!(result.errors[0].code, ErrorCode::AnyOfNoMatch);
+        assert!(validator.validate_with_options(&json!({"id": {"serial": 7}}), patch(&["id.serial"])).success);
+        let result = validator.validate_with_options(&document, patch(&["contact.phone"]));
+        assert_eq!(result.errors[0].code, ErrorCode::SchemaInvalid);
+    }
+
+    #[test]
+    fn test_input_limits() {
+        let validator = Validator::from_json(&json!({"type": "any"})).unwrap();
+        let limited = |options: ValidationOptions, value: &serde_json::Value| {
+            let result = validator.validate_with_options(value, options);
+            result.errors.first().filter(|e| e.code == ErrorCode::LimitExceeded).map(|e| e.path.to_string())
         };
 
-        let mut validator = Validator::new(schema).unwrap();
+        let nested = (0..100_000).fold(json!(1), |inner, _| serde_json::Value::Array(vec![inner]));
+        let depth = ValidationOptions { max_depth: Some(64), ..Default::default() };
+        let path = limited(depth.clone(), &nested).unwrap();
+        assert_eq!(path.matches("[0]").count(), 65);
+        // Dropping it in one piece would recurse as deep as it is nested
+        let mut nested = nested;
+        while let serde_json::Value::Array(mut items) = nested {
+            nested = items.pop().unwrap_or_default();
+        }
+
+        let shallow = json!({"a": [{"b": 1}]});
+        assert_eq!(limited(depth, &shallow), None);
+        assert_eq!(limited(ValidationOptions { max_depth: Some(1), ..Default::default() }, &shallow), Some("a.[0]".to_string()));
+
+        let document = json!({"name": "abcdef", "tags": [1, 2, 3]});
+        let strings = ValidationOptions { max_string_length: Some(5), ..Default::default() };
+        assert_eq!(limited(strings.clone(), &document), Some("name".to_string()));
+        // Keys are path segments of their own, dots and all
+        let result = validator.validate_with_options(&json!({"a.b": {"c": "abcdef"}}), strings.clone());
+        assert_eq!(result.errors[0].path.pointer(), "/a.b/c");
+        let arrays = ValidationOptions { max_array_length: Some(2), ..Default::default() };
+        assert_eq!(limited(arrays, &document), Some("tags".to_string()));
+        let objects = ValidationOptions { max_object_properties: Some(1), ..Default::default() };
+        assert_eq!(limited(objects.clone(), &document), Some(String::new()));
+
+        // Batch items are held to the same limits
+        let values = [json!({"name": "abc"}), document];
+        let batch = validator.validate_many_with_options(&values, strings);
+        assert!(batch[0].success);
+        assert_eq!((batch[1].errors[0].code.clone(), batch[1].errors[0].path.to_string()), (ErrorCode::LimitExceeded, "[1].name".to_string()));
+        let batch = validator.validate_many_with_options(&values, objects);
+        assert_eq!(batch[1].errors[0].path.to_string(), "[1]");
+    }
 
-        // Valid integer multiple of 5
-        let result = validator.validate(&json!(25));
-        assert!(result.success);
+    #[test]
+    fn test_time_limit() {
+        let validator = Validator::from_json(&json!({
+            "type": "array",
+            "items": {"type": "string", "pattern": "^[a-z]+$"}
+        })).unwrap();
+        let values = json!(vec!["abc"; 1000]);
 
-        // Not integer
-        let result = validator.validate(&json!(25.5));
+        let expired = ValidationOptions { max_time_ms: Some(0), ..Default::default() };
+        let result = validator.validate_with_options(&values, expired.clone());
         assert!(!result.success);
+        assert!(result.errors_truncated);
+        assert_eq!(result.errors.last().unwrap().code, ErrorCode::LimitExceeded);
 
-        // Not multiple of 5
-        let result = validator.validate(&json!(23));
-        assert!(!result.success);
+        let generous = ValidationOptions { max_time_ms: Some(60_000), ..Default::default() };
+        assert!(validator.validate_with_options(&values, generous.clone()).success);
 
-        // Out of range
-        let result = validator.validate(&json!(150));
-        assert!(!result.success);
+        let batch = validator.validate_many_with_options(&[values.clone(), values.clone()], expired);
+        assert!(batch.iter().all(|result| result.errors_truncated));
+        assert_eq!(batch[1].errors.last().unwrap().path.to_string(), "[1]");
+        assert!(validator.validate_many_with_options(&[values], generous).iter().all(|result| result.success));
+
+        // The limit is for the whole batch, not each item
+        let mut formats = FormatRegistry::new();
+        formats.register_fn("slow", |_| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            true
+        });
+        let validator = Validator::from_json_with_formats(&json!({"type": "string", "format": "slow"}), Arc::new(formats)).unwrap();
+        let options = ValidationOptions { max_time_ms: Some(25), ..Default::default() };
+        let batch = validator.validate_many_with_options(&vec![json!("a"); 6], options);
+        assert!(batch[0].success);
+        assert!(!batch[5].success);
+        assert_eq!(batch[5].errors.last().unwrap().code, ErrorCode::LimitExceeded);
     }
 
     #[test]
-    fn test_object_validation() {
-        let mut properties = HashMap::new();
-        properties.insert("name".to_string(), SchemaType::String {
-            min_length: Some(2),
-            max_length: Some(50),
-            pattern: None,
-            format: None,
+    fn test_cancellable_batches() {
+        let schema = serde_json::from_value(json!({"type": "number"})).unwrap();
+        let batch = BatchValidator::new(schema, 10).unwrap();
+        let values: Vec<_> = (0..35).map(|i| json!(i)).collect();
+
+        let token = CancellationToken::new();
+        let outcome = batch.validate_dataset_cancellable(&values, &token);
+        assert!(!outcome.cancelled);
+        assert_eq!(outcome.results.len(), 35);
+
+        token.cancel();
+        let outcome = batch.validate_dataset_cancellable(&values, &token);
+        assert!(outcome.cancelled);
+        assert!(outcome.results.is_empty());
+
+        // Cancel after the second chunk
+        let checks = std::cell::Cell::new(0);
+        let outcome = batch.validate_dataset_until(&values, || {
+            checks.set(checks.get() + 1);
+            checks.get() > 2
         });
-        properties.insert("age".to_string(), SchemaType::Number {
-            min: Some(0.0),
-            max: Some(120.0),
-            integer: true,
-            multiple_of: None,
+        assert!(outcome.cancelled);
+        assert_eq!(outcome.results.len(), 20);
+    }
+
+    #[test]
+    fn test_adaptive_batch_size() {
+        let mut adaptive = AdaptiveBatchSize::new(8.0, 100);
+        adaptive.record(100, 16.0); // Twice the budget
+        assert_eq!(adaptive.size(), 50);
+        adaptive.record(50, 2.0); // Ideal 200, grow halfway
+        assert_eq!(adaptive.size(), 125);
+        adaptive.record(125, 0.0);
+        assert_eq!(adaptive.size(), 187);
+        adaptive.record(187, 1e9);
+        assert_eq!(adaptive.size(), 1);
+
+        let schema = serde_json::from_value(json!({"type": "number"})).unwrap();
+        let mut batch = BatchValidator::new(schema, 10).unwrap();
+        batch.set_frame_budget(Some(1000.0));
+        let values: Vec<_> = (0..200).map(|i| if i == 150 { json!("x") } else { json!(i) }).collect();
+
+        let results = batch.validate_dataset(&values);
+        assert_eq!(results.len(), 200);
+        assert_eq!(results[150].errors[0].path, "[150]");
+        assert!(batch.batch_size() > 10);
+
+        batch.set_frame_budget(None);
+        assert_eq!(batch.batch_size(), 10);
+    }
+
+    #[test]
+    fn test_compact_batch_results() {
+        let schema = serde_json::from_value(json!({"type": "number", "min": 0})).unwrap();
+        let batch = BatchValidator::new(schema, 16).unwrap();
+        let values: Vec<_> = (0..40).map(|i| if i % 13 == 5 { json!(-1) } else { json!(i) }).collect();
+
+        let compact = batch.validate_dataset_compact(&values, false);
+        assert_eq!((compact.total_items, compact.valid_items()), (40, 37));
+        assert_eq!(compact.failed.iter().map(|item| item.index).collect::<Vec<_>>(), vec![5, 18, 31]);
+        assert_eq!(compact.failed[1].errors[0].path, "[18]");
+        assert_eq!(compact.valid_bitset.as_ref().unwrap().len(), 2);
+        assert_eq!(compact.is_valid(4), Some(true));
+        assert_eq!(compact.is_valid(31), Some(false));
+        assert_eq!(compact.is_valid(40), None);
+        assert_eq!(compact, CompactBatchResult::from_results(batch.validate_dataset(&values), false));
+
+        let failures = batch.validate_dataset_compact(&values, true);
+        assert!(failures.valid_bitset.is_none());
+        assert_eq!(failures.is_valid(18), Some(false));
+        assert_eq!(failures.is_valid(19), Some(true));
+        let json = serde_json::to_value(&failures).unwrap();
+        assert!(json.get("valid_bitset").is_none());
+    }
+
+    #[test]
+    fn test_partitioned_batch_results() {
+        let schema = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {"id": {"type": "number", "min": 0}},
+            "required": ["id"]
+        })).unwrap();
+        let batch = BatchValidator::new(schema, 4).unwrap();
+        let values: Vec<_> = (0..10).map(|i| if i % 4 == 1 { json!({"id": -i}) } else { json!({"id": i}) }).collect();
+
+        let partitioned = batch.validate_dataset_partitioned(&values, false);
+        assert_eq!(partitioned.valid, ValidRows::Indices(vec![0, 2, 3, 4, 6, 7, 8]));
+        assert_eq!(partitioned.invalid.iter().map(|item| item.index).collect::<Vec<_>>(), vec![1, 5, 9]);
+        assert_eq!(partitioned.invalid[1].errors[0].path, "[5].id");
+        let stats = &partitioned.stats;
+        assert_eq!((stats.total_items, stats.valid_items, stats.invalid_items, stats.total_errors), (10, 7, 3, 3));
+
+        let partitioned = batch.validate_dataset_partitioned(&values, true);
+        match &partitioned.valid {
+            ValidRows::Items(items) => {
+                assert_eq!(items.len(), 7);
+                assert_eq!(items[1], json!({"id": 2}));
+            }
+            rows => panic!("expected items, got {:?}", rows),
+        }
+        let json = serde_json::to_value(&partitioned).unwrap();
+        assert_eq!(json["valid"][0], json!({"id": 0}));
+        assert_eq!(json["invalid"][0]["index"], 1);
+    }
+
+    #[test]
+    fn test_validate_numbers() {
+        let schema = serde_json::from_value(json!({"type": "number", "min": 0, "integer": true})).unwrap();
+        let batch = BatchValidator::new(schema, 16).unwrap();
+        assert_eq!(batch.validate_numbers(&[0.0, 2.5, -1.0, 7.0, f64::NAN]), vec![1, 0, 0, 1, 0]);
+
+        let schema = serde_json::from_value(json!({"type": "number", "allowNonFinite": true})).unwrap();
+        let batch = BatchValidator::new(schema, 16).unwrap();
+        assert_eq!(batch.validate_numbers(&[f64::INFINITY, f64::NEG_INFINITY, f64::NAN, 1.0]), vec![1, 1, 1, 1]);
+
+        // Integral floats are integers, as they would be in JSON from JavaScript
+        let values = [3.0, -0.0, 2.5, 1e20, 1e300, f64::INFINITY];
+        let schema: SchemaType = serde_json::from_value(json!({"type": "number", "integer": true, "strictInteger": true})).unwrap();
+        let batch = BatchValidator::new(schema, 16).unwrap();
+        assert_eq!(batch.validate_numbers(&values), vec![1, 1, 0, 0, 0, 0]);
+
+        // Schemas other than a plain number agree with the raw-float check
+        let schema: SchemaType = serde_json::from_value(json!({
+            "anyOf": [{"type": "number", "integer": true, "strictInteger": true, "max": 10}, {"type": "string"}]
+        })).unwrap();
+        let batch = BatchValidator::new(schema, 16).unwrap();
+        assert_eq!(batch.validate_numbers(&values), vec![1, 1, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_memoized_validation() {
+        use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut formats = FormatRegistry::new();
+        formats.register_fn("color", move |s| {
+            counter.fetch_add(1, AtomicOrdering::SeqCst);
+            s == "red" || s == "blue"
         });
+        let validator = Validator::from_json_with_formats(&json!({
+            "type": "array",
+            "items": {"type": "object", "properties": {"color": {"type": "string", "format": "color"}}}
+        }), Arc::new(formats)).unwrap();
 
-        let schema = SchemaType::Object {
-            properties,
-            required: Some(vec!["name".to_string(), "age".to_string()]),
-            additional_properties: false,
-        };
+        let data = json!([{"color": "red"}, {"color": "red"}, {"color": "blue"}, {"color": "green"}, {"color": "green"}]);
+        let plain = validator.validate(&data);
+        assert_eq!(calls.swap(0, AtomicOrdering::SeqCst), 5);
 
-        let mut validator = Validator::new(schema).unwrap();
+        let options = ValidationOptions { memoize: true, ..Default::default() };
+        let memoized = validator.validate_with_options(&data, options.clone());
+        // Failures are not cached, so both "green" items are checked
+        assert_eq!(calls.swap(0, AtomicOrdering::SeqCst), 4);
+        assert_eq!(memoized.errors, plain.errors);
+        assert_eq!(memoized.errors.len(), 2);
 
-        // Valid object
-        let result = validator.validate(&json!({
-            "name": "John Doe",
-            "age": 30
-        }));
-        assert!(result.success);
+        // The memo is shared across the batch
+        let results = validator.validate_many_with_options(&vec![data; 3], options);
+        assert_eq!(calls.swap(0, AtomicOrdering::SeqCst), 4 + 2 + 2);
+        assert_eq!(results[2].errors[0].path, "[2].[3].color");
+    }
 
-        // Missing required property
-        let result = validator.validate(&json!({
-            "name": "John Doe"
-        }));
-        assert!(!result.success);
+    #[test]
+    fn test_error_dedup_and_per_path_limit() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"code": {"type": "string", "minLength": 5, "pattern": "^[0-9]+$", "format": "email"}},
+            "rules": [
+                {"rule": "atLeastOneOf", "fields": ["a", "b"]},
+                {"rule": "atLeastOneOf", "fields": ["c", "d"]}
+            ]
+        })).unwrap();
+        let data = json!({"code": "ab"});
 
-        // Additional property
-        let result = validator.validate(&json!({
-            "name": "John Doe",
-            "age": 30,
-            "extra": "not allowed"
-        }));
-        assert!(!result.success);
+        let result = validator.validate(&data);
+        assert_eq!(result.errors.len(), 4);
+        assert_eq!(result.errors.iter().filter(|e| e.code == ErrorCode::ObjectMissingAlternative).count(), 1);
+        assert!(!result.errors_truncated);
+
+        let options = ValidationOptions { max_errors_per_path: Some(2), ..Default::default() };
+        let result = validator.validate_with_options(&data, options.clone());
+        let groups = result.group_by_path();
+        assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["", "code"]);
+        assert_eq!(groups["code"].len(), 2);
+        assert!(result.errors_truncated);
+
+        let batch = validator.validate_many_with_options(&[data], options);
+        assert_eq!(batch[0].errors.len(), 3);
     }
 
     #[test]
-    fn test_array_validation() {
-        let schema = SchemaType::Array {
-            items: Box::new(SchemaType::Number {
-                min: Some(0.0),
-                max: None,
-                integer: true,
-                multiple_of: None,
-            }),
-            min_items: Some(1),
-            max_items: Some(5),
-            unique_items: true,
-        };
+    fn test_redacted_error_values() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "email": {"type": "string", "format": "email"},
+                "id": {"type": "bigint"},
+                "card": {"type": "object", "sensitive": true, "properties": {"number": {"type": "bigint"}}}
+            }
+        })).unwrap();
+        let data = json!({"email": "ann.smith@", "id": "abcdefghij", "card": {"number": "4111-1111"}});
 
-        let mut validator = Validator::new(schema).unwrap();
+        let result = validator.validate(&data);
+        let card = result.errors.iter().find(|e| e.path == "card.number").unwrap();
+        assert!(!card.message.contains("4111-1111"));
+        assert_eq!(card.params["received"], "[REDACTED]");
+        assert!(result.errors.iter().find(|e| e.path == "id").unwrap().message.contains("abcdefghij"));
 
-        // Valid array
-        let result = validator.validate(&json!([1, 2, 3]));
+        let truncate = ValidationOptions { redact_values: Redaction::Truncate(3), ..Default::default() };
+        let result = validator.validate_with_options(&data, truncate);
+        let id = result.errors.iter().find(|e| e.path == "id").unwrap();
+        assert_eq!(id.message, "String 'abc…' is not a valid bigint");
+        assert_eq!(id.params["received"], "abc…");
+
+        let options: ValidationOptions = serde_json::from_value(json!({"redact_values": "full"})).unwrap();
+        let batch = validator.validate_many_with_options(&[data], options.clone());
+        assert!(batch[0].errors.iter().all(|e| !e.message.contains("abcdefghij") && !e.message.contains("4111")));
+
+        // Only the value itself is hidden, not text that contains it
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"age": {"type": "number", "min": 10}, "id": {"type": "bigint"}}
+        })).unwrap();
+        let result = validator.validate_with_options(&json!({"age": 1, "id": "a"}), options);
+        let age = result.errors.iter().find(|e| e.path == "age").unwrap();
+        assert_eq!(age.message, "Number [REDACTED] is less than minimum 10");
+        let id = result.errors.iter().find(|e| e.path == "id").unwrap();
+        assert_eq!(id.message, "String '[REDACTED]' is not a valid bigint");
+
+        // `sensitive` travels with the schema, also through references
+        let card = json!({"type": "object", "sensitive": true, "properties": {"number": {"type": "bigint"}}});
+        let schema = serde_json::from_value(json!({"type": "object", "properties": {"card": card}})).unwrap();
+        let validator = Validator::new(schema).unwrap();
+        let result = validator.validate(&json!({"card": {"number": "4111-1111"}}));
+        assert_eq!(result.errors[0].params["received"], "[REDACTED]");
+        assert!(!result.errors[0].message.contains("4111"));
+
+        let mut registry = crate::registry::SchemaRegistry::new();
+        registry.register("Card", serde_json::from_value(card).unwrap());
+        registry.register_json("User", &json!({"type": "object", "properties": {"card": {"type": "ref", "$ref": "Card"}}})).unwrap();
+        let result = registry.validator("User").unwrap().validate(&json!({"card": {"number": "4111-1111"}}));
+        assert_eq!(result.errors[0].path, "card.number");
+        assert!(!result.errors[0].message.contains("4111"));
+    }
+
+    #[test]
+    fn test_did_you_mean_suggestions() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "email": {"type": "string"},
+                "color": {"type": "anyof", "anyOf": [
+                    {"type": "const", "const": "red"},
+                    {"type": "const", "const": "green"}
+                ]}
+            },
+            "additionalProperties": false
+        })).unwrap();
+
+        let result = validator.validate(&json!({"nmae": "Ann", "email": "a@b.c", "color": "gren", "zzz": 1}));
+        let unknown = result.errors.iter().find(|e| e.path == "nmae").unwrap();
+        assert_eq!(unknown.params["suggestion"], "name");
+        assert_eq!(unknown.message, "Additional property 'nmae' is not allowed (did you mean 'name'?)");
+        // Keys already present are not suggested
+        assert!(result.errors.iter().find(|e| e.path == "zzz").unwrap().params.get("suggestion").is_none());
+
+        let color = result.errors.iter().find(|e| e.code == ErrorCode::AnyOfNoMatch).unwrap();
+        assert_eq!(color.params["suggestion"], "green");
+        // Both branches are equally far off; the one reported is the one suggested
+        let branch = result.errors.iter().find(|e| e.code == ErrorCode::ConstMismatch).unwrap();
+        assert_eq!((branch.path.to_string().as_str(), &branch.params["branch"]), ("color", &json!(1)));
+
+        for kind in ["anyOf", "oneOf"] {
+            let validator = Validator::from_json(&json!({
+                kind: [{"type": "const", "const": "red"}, {"type": "const", "const": "green"}, {"type": "const", "const": "blue"}]
+            })).unwrap();
+            for profile in [false, true] {
+                let options = ValidationOptions { profile, ..Default::default() };
+                let errors = validator.validate_with_options(&json!("blu"), options).errors;
+                assert_eq!(errors.len(), 2, "{} profile={}", kind, profile);
+                assert_eq!(errors[0].params["suggestion"], "blue");
+                assert_eq!(errors[1].params["branch"], 2);
+            }
+            // Nothing close enough to suggest: the first branch is reported
+            assert_eq!(validator.validate(&json!("zzzzzz")).errors[1].params["branch"], 0);
+        }
+    }
+
+    #[test]
+    fn test_union_best_match() {
+        let validator = Validator::from_json(&json!({
+            "type": "anyof",
+            "anyOf": [
+                {"type": "string"},
+                {"type": "object", "properties": {
+                    "kind": {"type": "const", "const": "circle"},
+                    "radius": {"type": "number", "min": 0}
+                }, "required": ["kind", "radius"]},
+                {"type": "object", "properties": {
+                    "kind": {"type": "const", "const": "square"},
+                    "side": {"type": "number", "min": 0}
+                }, "required": ["kind", "side"]}
+            ]
+        })).unwrap();
+
+        // The discriminator picks the square branch despite its extra error
+        let data = json!({"kind": "square", "side": -1, "radius": 1});
+        let result = validator.validate(&data);
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0].code, ErrorCode::AnyOfNoMatch);
+        assert_eq!((result.errors[1].path.to_string(), &result.errors[1].code), ("side".to_string(), &ErrorCode::NumberTooSmall));
+        assert_eq!(result.errors[1].params["branch"], 2);
+
+        // Without one, the branch with the fewest errors
+        let result = validator.validate(&json!({"radius": 1}));
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[1].path, "kind");
+        assert_eq!(result.errors[1].params["branch"], 1);
+
+        let verbose = ValidationOptions { verbose_unions: true, ..Default::default() };
+        let result = validator.validate_with_options(&data, verbose);
+        let branches: Vec<_> = result.errors[1..].iter().map(|e| e.params["branch"].as_u64().unwrap()).collect();
+        assert_eq!(branches, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_warning_severity() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"name": {"type": "string", "minLength": 3}, "age": {"type": "number"}}
+        })).unwrap();
+        let data = json!({"name": "ab", "age": 1});
+        assert!(!validator.validate(&data).success);
+
+        let options: ValidationOptions = serde_json::from_value(json!({"severities": {"STRING_TOO_SHORT": "warning"}})).unwrap();
+        let result = validator.validate_with_options(&data, options.clone());
         assert!(result.success);
+        assert!(result.errors.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!((result.warnings[0].path.to_string(), result.warnings[0].severity), ("name".to_string(), Severity::Warning));
 
-        // Duplicate items
-        let result = validator.validate(&json!([1, 2, 2]));
+        // Errors still fail alongside warnings
+        let result = validator.validate_with_options(&json!({"name": "ab", "age": "x"}), options.clone());
         assert!(!result.success);
+        assert_eq!((result.errors.len(), result.warnings.len()), (1, 1));
 
-        // Too many items
-        let result = validator.validate(&json!([1, 2, 3, 4, 5, 6]));
-        assert!(!result.success);
+        let batch = validator.validate_many_with_options(&[data], options);
+        assert!(batch[0].success);
+        assert_eq!(batch[0].warnings[0].path, "[0].name");
+    }
 
-        // Empty array
-        let result = validator.validate(&json!([]));
-        assert!(!result.success);
+    #[test]
+    fn test_structured_error_paths() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"user.tags": {"type": "array", "items": {"type": "string"}}}
+        })).unwrap();
+        let data = json!({"user.tags": ["a", 3]});
+
+        let json = serde_json::to_value(validator.validate(&data)).unwrap();
+        assert_eq!(json["errors"][0]["path"], json!(["user.tags", 1]));
+        assert_eq!(json["errors"][0]["pointer"], "/user.tags/1");
+
+        let legacy = ValidationOptions { path_style: PathStyle::Legacy, ..Default::default() };
+        let json = serde_json::to_value(validator.validate_with_options(&data, legacy.clone())).unwrap();
+        assert_eq!(json["errors"][0]["path"], "user.tags.[1]");
+        assert!(json["errors"][0].get("pointer").is_none());
+
+        let batch = serde_json::to_value(validator.validate_many_with_options(&[data], legacy)).unwrap();
+        assert_eq!(batch[0]["errors"][0]["path"], "[0].user.tags.[1]");
     }
 
     #[test]
-    fn test_one_of_validation() {
-        let schema = SchemaType::OneOf {
-            schemas: vec![
-                SchemaType::String {
-                    min_length: None,
-                    max_length: None,
-                    pattern: None,
-                    format: None,
-                },
-                SchemaType::Number {
-                    min: None,
-                    max: None,
-                    integer: false,
-                    multiple_of: None,
-                },
-            ],
+    fn test_error_map() {
+        let mut validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"name": {"type": "string", "minLength": 3}, "age": {"type": "number"}},
+            "required": ["name", "age"]
+        })).unwrap();
+        validator.set_error_map(|issue| match issue.code {
+            ErrorCode::InvalidType => Some(format!("{} should be a {}", issue.path, issue.params["expected"].as_str()?)),
+            ErrorCode::StringTooShort => Some(format!("Too short ({})", issue.params["message"].as_str()?)),
+            _ => None,
+        });
+
+        let data = json!({"name": "ab", "age": "old"});
+        let messages = |results: &ValidationResult| results.errors.iter().map(|e| e.message.clone()).collect::<Vec<_>>();
+        let result = validator.validate(&data);
+        assert!(messages(&result).contains(&"age should be a number".to_string()));
+        assert!(messages(&result).iter().any(|m| m.starts_with("Too short (")));
+
+        // Unmapped codes keep the default message
+        let missing = validator.validate(&json!({"name": "abc"}));
+        assert_eq!(missing.errors[0].message, "Required property 'age' is missing");
+
+        // Batches and recompiled validators use the map too
+        let batch = validator.validate_many(std::slice::from_ref(&data));
+        assert!(messages(&batch[0]).contains(&"[0].age should be a number".to_string()));
+        let recompiled = validator.recompile(&json!({"type": "number"})).unwrap();
+        assert_eq!(recompiled.validate(&json!("x")).errors[0].message, " should be a number");
+
+        validator.clear_error_map();
+        assert_eq!(validator.validate(&json!({"name": "abc", "age": "old"})).errors[0].message, "Expected number, got string");
+    }
+
+    #[test]
+    fn test_number_strictness_options() {
+        let steps = Validator::from_json(&json!({"type": "number", "multiple_of": 0.1})).unwrap();
+        for n in [0.3, 0.7, 1.1, 123.4, -0.3] {
+            assert!(steps.validate(&json!(n)).success, "{}", n);
+        }
+        assert!(!steps.validate(&json!(0.35)).success);
+
+        let loose = Validator::from_json(&json!({"type": "number", "multiple_of": 0.1, "multipleOfTolerance": 0.5})).unwrap();
+        assert!(loose.validate(&json!(0.35)).success);
+
+        let integer = Validator::from_json(&json!({"type": "number", "integer": true})).unwrap();
+        let strict = Validator::from_json(&json!({"type": "number", "integer": true, "strictInteger": true})).unwrap();
+        let float_one: serde_json::Value = serde_json::from_str("1.0").unwrap();
+        assert!(integer.validate(&float_one).success);
+        assert_eq!(strict.validate(&float_one).errors[0].code, ErrorCode::NumberNotInteger);
+        assert!(strict.validate(&json!(1)).success);
+
+        let finite = Validator::from_json(&json!({"type": "number"})).unwrap();
+        let non_finite = Validator::from_json(&json!({"type": "number", "max": 10, "allowNonFinite": true})).unwrap();
+        assert!(!finite.validate(&json!("NaN")).success);
+        assert!(non_finite.validate(&json!("-Infinity")).success);
+        assert!(!non_finite.validate(&json!("Infinity")).success);
+        assert!(!non_finite.validate(&json!("NaN")).success);
+        assert!(!non_finite.validate(&json!("ten")).success);
+
+        // Planned unions must not skip the string spellings
+        let union = Validator::from_json(&json!({
+            "type": "oneof",
+            "oneOf": [{"type": "boolean"}, {"type": "number", "allowNonFinite": true}]
+        })).unwrap();
+        assert!(union.validate(&json!("Infinity")).success);
+    }
+
+    #[test]
+    fn test_string_length_units() {
+        let schema = |unit: &str| json!({"type": "string", "minLength": 5, "maxLength": 5, "lengthUnit": unit});
+        let chars = Validator::from_json(&schema("chars")).unwrap();
+        let bytes = Validator::from_json(&schema("bytes")).unwrap();
+
+        assert!(chars.validate(&json!("héllo")).success);
+        assert!(!bytes.validate(&json!("héllo")).success);
+        assert!(bytes.validate(&json!("hèyo")).success);
+        assert!(chars.validate(&json!("👍🏽 ok")).success);
+
+        // The interpreter (used while profiling) agrees with the plan
+        let options = ValidationOptions { profile: true, ..Default::default() };
+        assert!(!bytes.validate_with_options(&json!("héllo"), options).success);
+
+        // Omitted means chars
+        let default = Validator::from_json(&json!({"type": "string", "maxLength": 1})).unwrap();
+        assert!(default.validate(&json!("é")).success);
+        assert!(Validator::from_json(&schema("words")).is_err());
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn test_grapheme_lengths() {
+        let validator = Validator::from_json(&json!({"type": "string", "maxLength": 2, "lengthUnit": "graphemes"})).unwrap();
+        assert!(validator.validate(&json!("👍🏽e\u{301}")).success);
+        assert!(!validator.validate(&json!("👍🏽e\u{301}!")).success);
+    }
+
+    #[test]
+    fn test_profiling() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {
+                "users": {"type": "array", "items": {
+                    "type": "object",
+                    "properties": {"email": {"type": "string", "format": "email"}}
+                }}
+            }
+        })).unwrap();
+        let data = json!({"users": [{"email": "a@example.com"}, {"email": "b@example.com"}]});
+
+        validator.validate(&data);
+        assert!(validator.get_profile().is_none());
+
+        let options = ValidationOptions { profile: true, ..Default::default() };
+        assert!(validator.validate_with_options(&data, options.clone()).success);
+        validator.validate_with_options(&data, options);
+
+        let root = validator.get_profile().unwrap();
+        assert_eq!((root.schema.as_str(), root.hits), ("object", 2));
+        let users = &root.children[0];
+        assert_eq!((users.path.as_str(), users.schema.as_str()), ("users", "array"));
+        let user = &users.children[0];
+        assert_eq!((user.path.as_str(), user.hits), ("users.[*]", 4));
+        let email = &user.children[0];
+        assert_eq!((email.path.as_str(), email.schema.as_str(), email.hits), ("users.[*].email", "string (email)", 4));
+        assert!(root.time_ms >= email.time_ms);
+
+        validator.reset_profile();
+        assert!(validator.get_profile().is_none());
+
+        // Batch items are profiled too, under `[*]`
+        validator.validate_many_with_options(&[data.clone(), data], ValidationOptions { profile: true, ..Default::default() });
+        let root = validator.get_profile().unwrap();
+        assert_eq!((root.path.as_str(), root.schema.as_str(), root.hits), ("[*]", "object", 2));
+        assert_eq!(root.children[0].children[0].children[0].hits, 4);
+        assert_eq!(validator.stats().node_counts["object"], 12);
+    }
+
+    #[test]
+    fn test_validation_stats() {
+        let validator = Validator::from_json(&json!({
+            "type": "object",
+            "properties": {"name": {"type": "string", "minLength": 2}, "tags": {"type": "array", "items": {"type": "string"}}}
+        })).unwrap();
+
+        validator.validate(&json!({"name": "Ann"}));
+        validator.validate(&json!({"name": "A"}));
+        let results = validator.validate_many(&[json!({"name": "B"}), json!({"tags": [1, 2]})]);
+        assert!(results.iter().all(|result| result.performance.is_some()));
+        let options = ValidationOptions { profile: true, ..Default::default() };
+        validator.validate_with_options(&json!({"name": "Bo", "tags": ["a", "b"]}), options);
+        // Batch items only time themselves when asked to
+        let results = validator.validate_many_with_options(&[json!({}), json!({"name": 1})], ValidationOptions::default());
+        assert!(results.iter().all(|result| result.performance.is_none()));
+
+        let stats = validator.stats();
+        assert_eq!((stats.validations, stats.invalid), (7, 4));
+        assert_eq!(stats.errors_by_code.get("STRING_TOO_SHORT"), Some(&2));
+        assert_eq!(stats.errors_by_code.get("INVALID_TYPE"), Some(&3));
+        assert_eq!(stats.node_counts.get("string"), Some(&3));
+        assert_eq!(stats.node_counts.get("object"), Some(&1));
+        assert!(stats.throughput > 0.0);
+        assert!((0.0..=1.0).contains(&stats.regex_cache_hit_rate));
+
+        // Clones start with their own counters
+        assert_eq!(validator.clone().stats().validations, 0);
+        validator.reset_stats();
+        let stats = validator.stats();
+        assert_eq!((stats.validations, stats.throughput), (0, 0.0));
+        assert!(stats.errors_by_code.is_empty() && stats.node_counts.is_empty());
+    }
+
+    #[test]
+    fn test_set_validation() {
+        let schema = SchemaType::Set {
+            items: Box::new(SchemaType::String {
+                min_length: None,
+                max_length: None,
+                pattern: None,
+                format: None,
+                content_encoding: None,
+                content_media_type: None,
+                content_schema: None,
+                country: None,
+                uuid_version: None,
+                email_options: None,
+                min_date: None,
+                max_date: None,
+                offset_required: false,
+                precision: None,
+                allow_idn: false,
+                length_unit: LengthUnit::Chars,
+            }),
+            min_items: Some(2),
+            max_items: None,
         };
 
-        let mut validator = Validator::new(schema).unwrap();
+        let validator = Validator::new(schema).unwrap();
 
-        // Valid string
-        let result = validator.validate(&json!("test"));
-        assert!(result.success);
+        assert!(validator.validate(&json!(["a", "b"])).success);
 
-        // Valid number
-        let result = validator.validate(&json!(42));
+        // Duplicates are rejected by default
+        let result = validator.validate(&json!(["a", "b", "a"]));
+        assert!(!result.success);
+        assert_eq!(result.errors[0].code, ErrorCode::ArrayNotUnique);
+
+        // With coercion the set is returned deduplicated
+        let options = ValidationOptions { coerce: true, ..Default::default() };
+        let result = validator.validate_with_options(&json!(["a", "b", "a"]), options.clone());
         assert!(result.success);
+        assert_eq!(result.data, Some(json!(["a", "b"])));
 
-        // Invalid type
-        let result = validator.validate(&json!(true));
+        // Size is checked after deduplication
+        let result = validator.validate_with_options(&json!(["a", "a"]), options);
         assert!(!result.success);
+        assert_eq!(result.errors[0].code, ErrorCode::ArrayTooShort);
     }
 
     #[test]
-    fn test_batch_validation() {
-        let schema = SchemaType::String {
-            min_length: Some(2),
-            max_length: Some(10),
-            pattern: None,
-            format: None,
+    fn test_map_validation() {
+        let schema = SchemaType::Map {
+            keys: Box::new(SchemaType::String {
+                min_length: Some(2),
+                max_length: None,
+                pattern: None,
+                format: None,
+                content_encoding: None,
+                content_media_type: None,
+                content_schema: None,
+                country: None,
+                uuid_version: None,
+                email_options: None,
+                min_date: None,
+                max_date: None,
+                offset_required: false,
+                precision: None,
+                allow_idn: false,
+                length_unit: LengthUnit::Chars,
+            }),
+            values: Box::new(SchemaType::Number {
+                min: None,
+                max: None,
+                exclusive_min: None,
+                exclusive_max: None,
+                integer: true,
+                multiple_of: None,
+                allow_non_finite: false,
+                strict_integer: false,
+                multiple_of_tolerance: None,
+            }),
         };
 
-        let mut validator = Validator::new(schema).unwrap();
+        let validator = Validator::new(schema).unwrap();
 
-        let values = vec![
-            json!("valid1"),
-            json!("valid2"),
-            json!("x"), // Too short
-            json!(123), // Wrong type
-            json!("valid3"),
-        ];
+        // Entry form
+        assert!(validator.validate(&json!([["ab", 1], ["cd", 2]])).success);
+        let result = validator.validate(&json!([["ab", 1.5], ["cd"]]));
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.errors[0].path, "[0].[1]");
 
-        let results = validator.validate_many(&values);
-        assert_eq!(results.len(), 5);
-        assert!(results[0].success);
-        assert!(results[1].success);
-        assert!(!results[2].success);
-        assert!(!results[3].success);
-        assert!(results[4].success);
+        // Object form
+        assert!(validator.validate(&json!({"ab": 1})).success);
+        let result = validator.validate(&json!({"a": 1}));
+        assert_eq!(result.errors[0].code, ErrorCode::StringTooShort);
+
+        assert!(!validator.validate(&json!("not a map")).success);
     }
 
     #[test]
-    fn test_performance_tracking() {
-        let schema = SchemaType::String {
-            min_length: None,
-            max_length: None,
-            pattern: None,
-            format: None,
-        };
+    fn test_bigint_validation() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "bigint", "min": 0, "max": "9007199254740993"}"#
+        ).unwrap();
 
-        let mut validator = Validator::new(schema).unwrap();
+        let validator = Validator::new(schema).unwrap();
 
-        let values: Vec<_> = (0..1000).map(|i| json!(format!("string_{}", i))).collect();
-        let results = validator.validate_many(&values);
+        // 2^53 + 1 cannot be represented exactly as f64
+        assert!(validator.validate(&json!(9007199254740993u64)).success);
+        assert!(validator.validate(&json!("9007199254740993")).success);
 
-        assert_eq!(results.len(), 1000);
+        let result = validator.validate(&json!(9007199254740994u64));
+        assert_eq!(result.errors[0].code, ErrorCode::NumberTooLarge);
 
-        // Check that performance stats are included
-        if let Some(perf) = &results[0].performance {
-            assert!(perf.validation_time_ms >= 0.0);
-            assert!(perf.throughput > 0.0);
+        let result = validator.validate(&json!("-1"));
+        assert_eq!(result.errors[0].code, ErrorCode::NumberTooSmall);
+
+        assert_eq!(validator.validate(&json!(1.5)).errors[0].code, ErrorCode::NumberNotInteger);
+        assert_eq!(validator.validate(&json!("12abc")).errors[0].code, ErrorCode::InvalidFormat);
+        assert_eq!(validator.validate(&json!(true)).errors[0].code, ErrorCode::InvalidType);
+
+        // Strings are held to the same 64-bit range as JSON integers
+        let validator = Validator::from_json(&json!({"type": "bigint"})).unwrap();
+        for accepted in ["9223372036854775807", "9223372036854775808", "18446744073709551615", "-9223372036854775808"] {
+            assert!(validator.validate(&json!(accepted)).success, "{}", accepted);
         }
+        assert!(validator.validate(&json!(i64::MAX as u64 + 1)).success);
+        let too_large = validator.validate(&json!("18446744073709551616"));
+        assert_eq!(too_large.errors[0].code, ErrorCode::NumberTooLarge);
+        let too_small = validator.validate(&json!("-9223372036854775809"));
+        assert_eq!(too_small.errors[0].code, ErrorCode::NumberTooSmall);
+        assert!(serde_json::from_value::<SchemaType>(json!({"type": "bigint", "max": "18446744073709551616"})).is_err());
+        let validator = Validator::from_json(&json!({"type": "bigint", "max": "9223372036854775807"})).unwrap();
+        assert_eq!(validator.validate(&json!("9223372036854775808")).errors[0].code, ErrorCode::NumberTooLarge);
+
+        // Bounds round-trip through JSON
+        let reparsed: SchemaType = serde_json::from_str(
+            &serde_json::to_string(&validator.compiled_schema.schema).unwrap()
+        ).unwrap();
+        assert_eq!(reparsed, validator.compiled_schema.schema);
+    }
+
+    #[test]
+    fn test_decimal_validation() {
+        let schema: SchemaType = serde_json::from_str(
+            r#"{"type": "decimal", "min": "0", "precision": 5, "scale": 2, "multipleOf": "0.1"}"#
+        ).unwrap();
+
+        let validator = Validator::new(schema).unwrap();
+
+        // 0.3 % 0.1 is not zero in f64 arithmetic
+        assert!(validator.validate(&json!(0.3)).success);
+        assert!(validator.validate(&json!("123.40")).success);
+
+        let result = validator.validate(&json!("0.25"));
+        assert_eq!(result.errors[0].code, ErrorCode::NumberNotMultipleOf);
+
+        let result = validator.validate(&json!("1.234"));
+        assert!(result.errors.iter().any(|e| e.code == ErrorCode::NumberScaleExceeded));
+
+        let result = validator.validate(&json!("123456"));
+        assert_eq!(result.errors[0].code, ErrorCode::NumberPrecisionExceeded);
+
+        let result = validator.validate(&json!("-0.1"));
+        assert_eq!(result.errors[0].code, ErrorCode::NumberTooSmall);
+
+        assert_eq!(validator.validate(&json!("abc")).errors[0].code, ErrorCode::InvalidFormat);
+        assert_eq!(validator.validate(&json!(null)).errors[0].code, ErrorCode::InvalidType);
     }
 
     #[test]
@@ -933,23 +5272,42 @@ mod tests {
             max_length: None,
             pattern: Some(r"^test_\d+$".to_string()),
             format: None,
+            content_encoding: None,
+            content_media_type: None,
+            content_schema: None,
+            country: None,
+            uuid_version: None,
+            email_options: None,
+            min_date: None,
+            max_date: None,
+            offset_required: false,
+            precision: None,
+            allow_idn: false,
+            length_unit: LengthUnit::Chars,
         };
 
-        let mut validator = Validator::new(schema).unwrap();
+        // Building the validator compiles the pattern
+        let validator = Validator::new(schema).unwrap();
+        assert!(regex_cache::contains(r"^test_\d+$"));
 
-        // First validation compiles regex
         let result1 = validator.validate(&json!("test_123"));
         assert!(result1.success);
-
-        // Second validation should use cached regex
         let result2 = validator.validate(&json!("test_456"));
         assert!(result2.success);
-
-        // Invalid pattern
         let result3 = validator.validate(&json!("invalid"));
         assert!(!result3.success);
 
-        // Verify regex was cached
-        assert_eq!(validator.regex_cache.len(), 1);
+        // Clearing one validator's cache leaves other validators' patterns
+        let other = Validator::from_json(&json!({"type": "string", "pattern": "^other_\\d+$"})).unwrap();
+        validator.clear_cache();
+        assert!(!regex_cache::contains(r"^test_\d+$"));
+        assert!(regex_cache::contains(r"^other_\d+$"));
+        assert!(other.validate(&json!("other_1")).success);
+
+        // The validator still has its compiled pattern, on either path
+        let profiled = ValidationOptions { profile: true, ..Default::default() };
+        assert!(!validator.validate_with_options(&json!("invalid"), profiled.clone()).success);
+        assert!(validator.validate_with_options(&json!("test_7"), profiled).success);
+        assert!(!regex_cache::contains(r"^test_\d+$"));
     }
 }
\ No newline at end of file
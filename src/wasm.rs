@@ -0,0 +1,3259 @@
+
+//! JavaScript bindings, built with the `wasm` feature
+
+use wasm_bindgen::prelude::*;
+use serde_json;
+use crate::*;
+use crate::env::env_config;
+use crate::form::{fields_from_map, fields_from_pairs, parse_urlencoded, validate_form};
+use std::rc::Rc;
+#[cfg(any(test, all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+use std::{collections::HashMap, sync::{Mutex, MutexGuard}};
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+use std::{cell::RefCell, sync::atomic::{AtomicUsize, Ordering}};
+use std::sync::Arc;
+use wasm_bindgen::JsCast;
+
+// WASM console logging
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(s: &str);
+
+    #[wasm_bindgen(js_namespace = console)]
+    fn warn(s: &str);
+
+    #[wasm_bindgen(js_namespace = console)]
+    fn error(s: &str);
+}
+
+// The console imports panic when called off wasm32, so native builds of the
+// bindings (their tests) drop the messages
+#[cfg(not(target_arch = "wasm32"))]
+fn log(_: &str) {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn warn(_: &str) {}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn error(_: &str) {}
+
+// Logging macros for WASM
+macro_rules! console_log {
+    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+}
+
+macro_rules! console_warn {
+    ($($t:tt)*) => (warn(&format_args!($($t)*).to_string()))
+}
+
+macro_rules! console_error {
+    ($($t:tt)*) => (error(&format_args!($($t)*).to_string()))
+}
+
+/// Main FastValidator class exposed to JavaScript
+#[wasm_bindgen]
+pub struct FastValidator {
+    /// Shared with pending `validate_async` calls; cloned on write
+    validator: Rc<validator::Validator>,
+    schema_json: String,
+    /// Formats awaited by `validate_async`, see `register_async_format`
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    async_formats: AsyncFormats,
+}
+
+/// ZodString validator for WASM
+#[wasm_bindgen]
+pub struct ZodStringValidator {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    pattern: Option<String>,
+    format: Option<StringFormat>,
+    length_unit: LengthUnit,
+    validations: Vec<String>, // Store validation rules as JSON
+}
+
+/// ZodNumber validator for WASM
+#[wasm_bindgen]
+pub struct ZodNumberValidator {
+    min: Option<f64>,
+    max: Option<f64>,
+    integer: bool,
+    multiple_of: Option<f64>,
+    multiple_of_tolerance: f64,
+    allow_non_finite: bool,
+}
+
+/// ZodArray validator for WASM
+#[wasm_bindgen]
+pub struct ZodArrayValidator {
+    item_validator: String, // JSON representation of item schema
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    unique_items: bool,
+}
+
+/// ZodObject validator for WASM
+#[wasm_bindgen]
+pub struct ZodObjectValidator {
+    properties: String, // JSON representation of properties
+    required: Vec<String>,
+    additional_properties: bool,
+}
+
+/// Validation result with typed accessors, from `validate_result`
+#[wasm_bindgen]
+pub struct FastValidationResult {
+    result: ValidationResult,
+}
+
+/// One error or warning of a `FastValidationResult`
+#[wasm_bindgen]
+pub struct ValidationIssue {
+    error: ValidationError,
+}
+
+#[wasm_bindgen]
+impl FastValidator {
+    /// Create a new FastValidator instance. Custom string formats the
+    /// schema uses are given as `{name: pattern | (value) => boolean}`;
+    /// any other unknown format fails to compile.
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema_json: &str, formats: Option<js_sys::Object>) -> Result<FastValidator, JsValue> {
+        console_log!("Creating FastValidator with schema");
+
+        // Enable better error messages in development
+        #[cfg(feature = "console_error_panic_hook")]
+        console_error_panic_hook::set_once();
+
+        // Parse schema JSON
+        let schema: serde_json::Value = serde_json::from_str(schema_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+
+        // Create validator, keeping schema annotations for introspection
+        let validator = validator::Validator::from_json_with_formats(&schema, formats_from_js(formats)?)
+            .map_err(|e| JsValue::from_str(&format!("Schema compilation failed: {}", e)))?;
+
+        console_log!("FastValidator created successfully");
+
+        Ok(FastValidator::from_validator(validator, schema_json.to_string()))
+    }
+
+    /// Validate a single JSON value
+    #[wasm_bindgen]
+    pub fn validate(&mut self, data_json: &str) -> String {
+        match self.validate_internal(data_json) {
+            Ok(result) => result,
+            Err(e) => {
+                console_error!("Validation error: {}", e);
+                self.create_error_result(&e)
+            }
+        }
+    }
+
+    /// Create a validator from a JSON5 or JSONC schema file, which may have
+    /// comments and trailing commas
+    #[wasm_bindgen]
+    pub fn from_json5(schema_text: &str) -> Result<FastValidator, JsValue> {
+        let schema = parse_json5(schema_text)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON5: {}", e)))?;
+        let validator = validator::Validator::from_json(&schema)
+            .map_err(|e| JsValue::from_str(&format!("Schema compilation failed: {}", e)))?;
+
+        Ok(FastValidator::from_validator(validator, schema.to_string()))
+    }
+
+    /// Validate a JSON5 or JSONC document, such as a hand-edited config file
+    #[wasm_bindgen]
+    pub fn validate_json5(&mut self, text: &str) -> String {
+        let result = self.validate_decoded(parse_json5(text));
+        serde_json::to_string(&result).unwrap_or_else(|e| self.create_error_result(&e.to_string()))
+    }
+
+    /// Validate a JS value directly, without a JSON string round-trip
+    #[wasm_bindgen]
+    pub fn validate_value(&mut self, value: JsValue) -> String {
+        let result = self.validate_js_value(value);
+        serde_json::to_string(&result).unwrap_or_else(|e| self.create_error_result(&e.to_string()))
+    }
+
+    /// Validate a JS value, returning the result as a JS object
+    #[wasm_bindgen]
+    pub fn validate_js(&mut self, value: JsValue) -> Result<JsValue, JsValue> {
+        to_js(&self.validate_js_value(value))
+    }
+
+    /// Validate a MessagePack payload (a `Uint8Array`), returning the result
+    /// as a JS object; a payload that cannot be decoded fails validation
+    #[wasm_bindgen]
+    pub fn validate_msgpack(&mut self, bytes: &[u8]) -> Result<JsValue, JsValue> {
+        to_js(&self.validate_decoded(decode_msgpack(bytes)))
+    }
+
+    /// Validate a CBOR payload (a `Uint8Array`), returning the result as a
+    /// JS object; a payload that cannot be decoded fails validation
+    #[wasm_bindgen]
+    pub fn validate_cbor(&mut self, bytes: &[u8]) -> Result<JsValue, JsValue> {
+        to_js(&self.validate_decoded(decode_cbor(bytes)))
+    }
+
+    /// Validate form input (a `URLSearchParams`, a `FormData`, a query
+    /// string or a plain object of string fields), coercing each field by
+    /// its schema as `FormValidator` does. File entries are skipped.
+    #[wasm_bindgen]
+    pub fn validate_form(&mut self, input: JsValue) -> Result<JsValue, JsValue> {
+        let fields = if let Some(query) = input.as_string() {
+            fields_from_pairs(parse_urlencoded(&query))
+        } else if let Some(entries) = js_sys::try_iter(&input)? {
+            let mut pairs = Vec::new();
+            for entry in entries {
+                let entry = js_sys::Array::from(&entry?);
+                if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string()) {
+                    pairs.push((name, value));
+                }
+            }
+            fields_from_pairs(pairs)
+        } else {
+            match js_to_json(input).map_err(|e| JsValue::from_str(&e))? {
+                serde_json::Value::Object(map) => fields_from_map(&map),
+                other => return to_js(&self.validator.validate(&other)),
+            }
+        };
+        to_js(&validate_form(&self.validator, fields))
+    }
+
+    /// Validate an environment object such as Node's `process.env` as
+    /// `EnvValidator` does: declared variables are coerced by their schema,
+    /// unset ones take their `default`, and the rest are ignored. On
+    /// success `data` is the typed configuration.
+    #[wasm_bindgen]
+    pub fn validate_env(&mut self, env: JsValue) -> Result<JsValue, JsValue> {
+        let vars = match js_to_json(env).map_err(|e| JsValue::from_str(&e))? {
+            serde_json::Value::Object(vars) => vars.into_iter().collect(),
+            _ => Default::default(),
+        };
+        to_js(&self.validator.validate(&env_config(&self.validator, vars)))
+    }
+
+    /// The compiled schema in binary form, for caching in IndexedDB or on
+    /// disk; restore it with `from_bytes`. Named definitions used by `$ref`
+    /// are not included.
+    #[wasm_bindgen]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, JsValue> {
+        self.validator.compiled_schema.to_bytes()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Rehydrate a validator from `to_bytes`, without parsing or analysing
+    /// the schema again
+    #[wasm_bindgen]
+    pub fn from_bytes(bytes: &[u8]) -> Result<FastValidator, JsValue> {
+        let compiled = CompiledSchema::from_bytes(bytes)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let schema_json = serde_json::to_string(&compiled.schema)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(FastValidator::from_validator(Validator::from_compiled(compiled), schema_json))
+    }
+
+    /// Like Zod's `parse`: the validated (and transformed or coerced) data,
+    /// or throws a `FastSchemaError` whose `issues` are the errors
+    #[wasm_bindgen]
+    pub fn parse(&mut self, value: JsValue) -> Result<JsValue, JsValue> {
+        let result = self.validate_js_value(value);
+        if !result.success {
+            let error = js_sys::Error::new(&parse_error_message(&result.errors));
+            error.set_name("FastSchemaError");
+            js_sys::Reflect::set(&error, &JsValue::from_str("issues"), &to_js(&result.errors)?)?;
+            return Err(error.into());
+        }
+
+        match &result.data {
+            Some(data) => to_js(data),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// Like Zod's `safeParse`: `{success: true, data}` or
+    /// `{success: false, error: {name, message, issues}}`
+    #[wasm_bindgen(js_name = safeParse)]
+    pub fn safe_parse(&mut self, value: JsValue) -> Result<JsValue, JsValue> {
+        let result = self.validate_js_value(value);
+        to_js(&SafeParse::new(&result))
+    }
+
+    /// Validate an array of JS values, returning an array of result objects
+    #[wasm_bindgen]
+    pub fn validate_many_js(&mut self, values: JsValue) -> Result<JsValue, JsValue> {
+        let values = serde_wasm_bindgen::from_value(values).map_err(|e| format!("Invalid JS array: {}", e));
+        to_js(&self.validate_many_read(values))
+    }
+
+    /// Validate a JS value, returning a result with typed accessors rather
+    /// than a plain object
+    #[wasm_bindgen]
+    pub fn validate_result(&mut self, value: JsValue) -> FastValidationResult {
+        FastValidationResult { result: self.validate_js_value(value) }
+    }
+
+    /// Errors of a result object (from `validate_js` and friends) as a
+    /// tree keyed by property, like Zod's `error.format()`
+    #[wasm_bindgen]
+    pub fn format_errors(&self, result: JsValue) -> Result<JsValue, JsValue> {
+        to_js(&result_from_js(result)?.format())
+    }
+
+    /// Errors of a result object as `{formErrors, fieldErrors}`, like
+    /// Zod's `error.flatten()`
+    #[wasm_bindgen]
+    pub fn flatten_errors(&self, result: JsValue) -> Result<JsValue, JsValue> {
+        to_js(&result_from_js(result)?.flatten())
+    }
+
+    /// Validate multiple JSON values efficiently
+    #[wasm_bindgen]
+    pub fn validate_many(&mut self, data_array_json: &str) -> String {
+        match self.validate_many_internal(data_array_json) {
+            Ok(result) => result,
+            Err(e) => {
+                console_error!("Batch validation error: {}", e);
+                format!("[{}]", self.create_error_result(&e))
+            }
+        }
+    }
+
+    /// Validate with custom options
+    #[wasm_bindgen]
+    pub fn validate_with_options(&mut self, data_json: &str, options_json: &str) -> String {
+        let options: ValidationOptions = match serde_json::from_str(options_json) {
+            Ok(opts) => opts,
+            Err(e) => {
+                console_warn!("Invalid options, using defaults: {}", e);
+                ValidationOptions::default()
+            }
+        };
+
+        match self.validate_with_options_internal(data_json, options) {
+            Ok(result) => result,
+            Err(e) => {
+                console_error!("Validation error: {}", e);
+                self.create_error_result(&e)
+            }
+        }
+    }
+
+    /// Get schema information
+    #[wasm_bindgen]
+    pub fn get_schema(&self) -> String {
+        self.schema_json.clone()
+    }
+
+    /// Register a custom string format matched by a regex
+    #[wasm_bindgen]
+    pub fn register_format(&mut self, name: &str, pattern: &str) -> Result<(), JsValue> {
+        Rc::make_mut(&mut self.validator).register_format_pattern(name, pattern)
+            .map_err(|e| JsValue::from_str(&format!("Invalid format pattern: {}", e)))
+    }
+
+    /// Register a custom string format checked by a JS function
+    /// `(value: string) => boolean`
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn register_format_fn(&mut self, name: &str, callback: js_sys::Function) {
+        Rc::make_mut(&mut self.validator).register_format(name, js_format(callback));
+    }
+
+    /// Rewrite error messages with a JS function
+    /// `(issue: {code, path, params}) => string | undefined`, like Zod's
+    /// `errorMap`; returning anything but a string keeps the default message
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn set_error_map(&mut self, callback: js_sys::Function) {
+        let callback = JsCallback::new(callback);
+        Rc::make_mut(&mut self.validator).set_error_map(move |issue| {
+            let issue = to_js(issue).ok()?;
+            callback.call(&issue)?.as_string()
+        });
+    }
+
+    /// Go back to the default error messages
+    #[wasm_bindgen]
+    pub fn clear_error_map(&mut self) {
+        Rc::make_mut(&mut self.validator).clear_error_map();
+    }
+
+    /// Register a custom string format checked by an async JS function
+    /// `(value: string) => Promise<boolean>`. Only `validate_async` and
+    /// `validate_many_async` can await it; synchronous validation reports
+    /// values of this format as invalid. The schema compiles only if the
+    /// format was also given to the constructor, with any placeholder.
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn register_async_format(&mut self, name: &str, callback: js_sys::Function) {
+        Rc::make_mut(&mut self.async_formats.callbacks).insert(name.to_string(), callback);
+
+        let checks = Arc::clone(&self.async_formats.checks);
+        let format = name.to_string();
+        Rc::make_mut(&mut self.validator).register_format(name, move |value| lock_checks(&checks).check(&format, value));
+    }
+
+    /// Validate without blocking the event loop, awaiting async formats.
+    /// Resolves to the same structured result as `validate_js`.
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn validate_async(&self, data_json: String) -> js_sys::Promise {
+        let validator = Rc::clone(&self.validator);
+        let async_formats = self.async_formats.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let data: serde_json::Value = serde_json::from_str(&data_json)
+                .map_err(|e| JsValue::from_str(&format!("Invalid JSON: {}", e)))?;
+            yield_now().await?;
+
+            let result = async_formats.run(|| validator.validate(&data)).await?;
+            to_js(&result)
+        })
+    }
+
+    /// Validate an array `chunk_size` items at a time, yielding to the event
+    /// loop between chunks. Resolves to an array of results.
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn validate_many_async(&self, data_array_json: String, chunk_size: usize) -> js_sys::Promise {
+        let validator = Rc::clone(&self.validator);
+        let async_formats = self.async_formats.clone();
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let data_array: Vec<serde_json::Value> = serde_json::from_str(&data_array_json)
+                .map_err(|e| JsValue::from_str(&format!("Invalid JSON array: {}", e)))?;
+
+            let chunk_size = chunk_size.max(1);
+            let mut results = Vec::with_capacity(data_array.len());
+            for (index, chunk) in data_array.chunks(chunk_size).enumerate() {
+                yield_now().await?;
+                let offset = index * chunk_size;
+                results.extend(async_formats.run(|| validator.validate_many_at(offset, chunk)).await?);
+            }
+            to_js(&results)
+        })
+    }
+
+    /// Get title/description/examples/meta for a schema path ("null" if none)
+    #[wasm_bindgen]
+    pub fn get_metadata(&self, path: &str) -> String {
+        match self.validator.get_metadata(path) {
+            Some(metadata) => serde_json::to_string(metadata).unwrap_or_else(|_| "null".to_string()),
+            None => "null".to_string(),
+        }
+    }
+
+    /// Replace the schema in place. Custom formats carry over and pending
+    /// `validate_async` calls finish with the old schema; on error the
+    /// current schema stays.
+    #[wasm_bindgen]
+    pub fn update_schema(&mut self, schema_json: &str) -> Result<(), JsValue> {
+        let schema: serde_json::Value = serde_json::from_str(schema_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+        let validator = self.validator.recompile(&schema)
+            .map_err(|e| JsValue::from_str(&format!("Schema compilation failed: {}", e)))?;
+
+        self.validator = Rc::new(validator);
+        self.schema_json = schema_json.to_string();
+        Ok(())
+    }
+
+    /// Get validation statistics: schema shape, regex cache hit rate,
+    /// outcome and error code counts, and throughput over the last ten
+    /// seconds. Counters start over when the schema is replaced.
+    #[wasm_bindgen]
+    pub fn get_stats(&self) -> String {
+        match serde_json::to_string(&self.validator.stats()) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize stats: {}", e);
+                "{}".to_string()
+            }
+        }
+    }
+
+    /// Get validation statistics as a JS object
+    #[wasm_bindgen]
+    pub fn get_stats_js(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.validator.stats())
+    }
+
+    /// Zero the counters in `get_stats`, including the regex cache hit
+    /// counters shared by every validator
+    #[wasm_bindgen]
+    pub fn reset_stats(&mut self) {
+        self.validator.reset_stats();
+    }
+
+    /// Get the per-node profile of validations run with `{"profile": true}`
+    /// options, as a JSON tree (`null` if nothing was profiled)
+    #[wasm_bindgen]
+    pub fn get_profile(&self) -> String {
+        serde_json::to_string(&self.validator.get_profile()).unwrap_or_else(|_| "null".to_string())
+    }
+
+    #[wasm_bindgen]
+    pub fn reset_profile(&mut self) {
+        self.validator.reset_profile();
+    }
+
+    /// Reset internal caches (useful for memory management). Only this
+    /// validator's patterns leave the shared regex cache.
+    #[wasm_bindgen]
+    pub fn reset_caches(&mut self) {
+        self.validator.clear_cache();
+        console_log!("Caches reset");
+    }
+
+    /// Get memory usage information. `linear_memory_bytes` is the size of
+    /// the WASM linear memory shared by every instance (null natively); it
+    /// only grows, so compare it across page loads rather than over time.
+    #[wasm_bindgen]
+    pub fn get_memory_info(&self) -> String {
+        serde_json::json!({
+            "regex_cache_size": regex_cache::len(),
+            "regex_cache_capacity": regex_cache::capacity(),
+            "estimated_complexity": self.validator.compiled_schema.estimated_complexity,
+            "max_depth": self.validator.compiled_schema.max_depth,
+            "schema_bytes": self.schema_json.len(),
+            "linear_memory_bytes": linear_memory_bytes()
+        }).to_string()
+    }
+
+    /// An independent copy sharing the compiled schema
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_instance(&self) -> FastValidator {
+        FastValidator {
+            validator: Rc::clone(&self.validator),
+            schema_json: self.schema_json.clone(),
+            #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+            async_formats: self.async_formats.clone(),
+        }
+    }
+
+    /// Release the WASM memory held by this instance, like `free()`
+    #[wasm_bindgen]
+    pub fn dispose(self) {}
+
+    /// Run `callback` with a validator for `schema_json` and free it
+    /// afterwards, also when the callback throws or its promise settles
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn with_validator(schema_json: &str, callback: &js_sys::Function) -> Result<JsValue, JsValue> {
+        let validator: JsValue = FastValidator::new(schema_json, None)?.into();
+        let result = callback.call1(&JsValue::NULL, &validator);
+
+        if let Ok(promise) = &result {
+            if promise.is_instance_of::<js_sys::Promise>() {
+                let free = Closure::once_into_js(move || {
+                    let _ = call_method(&validator, "free");
+                });
+                let finally = js_sys::Reflect::get(promise, &JsValue::from_str("finally"))?
+                    .dyn_into::<js_sys::Function>()?;
+                return finally.call1(promise, &free);
+            }
+        }
+        call_method(&validator, "free")?;
+        result
+    }
+}
+
+/// Size of the WASM linear memory, which holds every instance's data
+fn linear_memory_bytes() -> Option<usize> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        Some(core::arch::wasm32::memory_size::<0>() * 65536)
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        None
+    }
+}
+
+/// JS function used as a format callback or error map. Validator callbacks
+/// must be `Send + Sync` and a `js_sys::Function` is neither, so the
+/// function stays in a thread-local table and this only holds its key; a
+/// call from any other thread finds nothing.
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+struct JsCallback(usize);
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+thread_local! {
+    static JS_CALLBACKS: RefCell<HashMap<usize, js_sys::Function>> = RefCell::new(HashMap::new());
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+static NEXT_JS_CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+impl JsCallback {
+    fn new(function: js_sys::Function) -> Self {
+        let key = NEXT_JS_CALLBACK.fetch_add(1, Ordering::Relaxed);
+        JS_CALLBACKS.with(|callbacks| callbacks.borrow_mut().insert(key, function));
+        JsCallback(key)
+    }
+
+    /// Call the function with one argument; `None` if it threw
+    fn call(&self, arg: &JsValue) -> Option<JsValue> {
+        // Not borrowed during the call, which may register another callback
+        let function = JS_CALLBACKS.with(|callbacks| callbacks.borrow().get(&self.0).cloned())?;
+        function.call1(&JsValue::NULL, arg).ok()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+impl Drop for JsCallback {
+    fn drop(&mut self) {
+        // The table is gone if the thread is exiting
+        let _ = JS_CALLBACKS.try_with(|callbacks| callbacks.borrow_mut().remove(&self.0));
+    }
+}
+
+/// Format check calling a JS function `(value: string) => boolean`
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+fn js_format(function: js_sys::Function) -> impl Fn(&str) -> bool + Send + Sync + 'static {
+    let callback = JsCallback::new(function);
+    move |value| callback.call(&JsValue::from_str(value)).is_some_and(|result| result.is_truthy())
+}
+
+/// Custom formats given as `{name: pattern | (value) => boolean}`
+fn formats_from_js(formats: Option<js_sys::Object>) -> Result<Arc<FormatRegistry>, JsValue> {
+    let mut registry = FormatRegistry::new();
+    let Some(formats) = formats else {
+        return Ok(Arc::new(registry));
+    };
+    for entry in js_sys::Object::entries(&formats).iter() {
+        let entry: js_sys::Array = entry.unchecked_into();
+        let name = entry.get(0).as_string().unwrap_or_default();
+        let format = entry.get(1);
+        if let Some(pattern) = format.as_string() {
+            registry.register_pattern(&name, &pattern)
+                .map_err(|e| JsValue::from_str(&format!("Invalid format pattern: {}", e)))?;
+            continue;
+        }
+        #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+        if let Some(function) = format.dyn_ref::<js_sys::Function>() {
+            registry.register_fn(&name, js_format(function.clone()));
+            continue;
+        }
+        return Err(JsValue::from_str(&format!("Format '{}' must be a pattern string or a function", name)));
+    }
+    Ok(Arc::new(registry))
+}
+
+/// State of the formats registered with `register_async_format`. Their
+/// sync callbacks record the values seen while collecting, then answer
+/// from the awaited results on a second pass.
+#[cfg(any(test, all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+#[derive(Debug, Default)]
+enum AsyncChecks {
+    /// Outside `validate_async`: there is nothing to await with
+    #[default]
+    Idle,
+    Collect(Vec<(String, String)>),
+    Resolved(HashMap<(String, String), bool>),
+}
+
+#[cfg(any(test, all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+impl AsyncChecks {
+    fn check(&mut self, format: &str, value: &str) -> bool {
+        match self {
+            AsyncChecks::Idle => false,
+            AsyncChecks::Collect(pending) => {
+                pending.push((format.to_string(), value.to_string()));
+                true
+            }
+            AsyncChecks::Resolved(outcomes) => outcomes
+                .get(&(format.to_string(), value.to_string()))
+                .copied()
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(any(test, all(target_arch = "wasm32", not(target_feature = "atomics"))))]
+fn lock_checks(checks: &Mutex<AsyncChecks>) -> MutexGuard<'_, AsyncChecks> {
+    checks.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+#[derive(Clone, Default)]
+struct AsyncFormats {
+    callbacks: Rc<HashMap<String, js_sys::Function>>,
+    checks: Arc<Mutex<AsyncChecks>>,
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+impl AsyncFormats {
+    /// Run a validation pass with async formats passing while their values
+    /// are collected; if any were, await them and rerun with the outcomes.
+    /// Each pass is synchronous, so concurrent calls never see each
+    /// other's state.
+    async fn run<T>(&self, validate: impl Fn() -> T) -> Result<T, JsValue> {
+        if self.callbacks.is_empty() {
+            return Ok(validate());
+        }
+
+        *lock_checks(&self.checks) = AsyncChecks::Collect(Vec::new());
+        let result = validate();
+        let pending = match std::mem::take(&mut *lock_checks(&self.checks)) {
+            AsyncChecks::Collect(pending) => pending,
+            _ => Vec::new(),
+        };
+        if pending.is_empty() {
+            return Ok(result);
+        }
+
+        let mut outcomes = HashMap::new();
+        for (format, value) in pending {
+            if outcomes.contains_key(&(format.clone(), value.clone())) {
+                continue;
+            }
+            let callback = match self.callbacks.get(&format) {
+                Some(callback) => callback,
+                None => continue,
+            };
+            let outcome = callback.call1(&JsValue::NULL, &JsValue::from_str(&value))?;
+            let outcome = wasm_bindgen_futures::JsFuture::from(js_sys::Promise::resolve(&outcome)).await?;
+            outcomes.insert((format, value), outcome.is_truthy());
+        }
+
+        *lock_checks(&self.checks) = AsyncChecks::Resolved(outcomes);
+        let result = validate();
+        *lock_checks(&self.checks) = AsyncChecks::Idle;
+        Ok(result)
+    }
+}
+
+/// Resolve on the next macrotask, letting the browser render and handle input
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+async fn yield_now() -> Result<(), JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global();
+        let set_timeout = js_sys::Reflect::get(&global, &JsValue::from_str("setTimeout"))
+            .ok()
+            .and_then(|set_timeout| set_timeout.dyn_into::<js_sys::Function>().ok());
+        let scheduled = set_timeout.is_some_and(|set_timeout| set_timeout.call2(&global, &resolve, &JsValue::from(0)).is_ok());
+        if !scheduled {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.map(|_| ())
+}
+
+// Internal implementation methods
+impl FastValidator {
+    fn from_validator(validator: Validator, schema_json: String) -> FastValidator {
+        FastValidator {
+            validator: Rc::new(validator),
+            schema_json,
+            #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+            async_formats: AsyncFormats::default(),
+        }
+    }
+
+    fn validate_internal(&mut self, data_json: &str) -> Result<String, String> {
+        // Parse JSON data
+        let data: serde_json::Value = serde_json::from_str(data_json)
+            .map_err(|e| format!("Invalid JSON data: {}", e))?;
+
+        // Validate
+        let result = self.validator.validate(&data);
+
+        // Serialize result
+        serde_json::to_string(&result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+
+    /// Validate a JS value; a value that cannot be read fails validation
+    fn validate_js_value(&mut self, value: JsValue) -> ValidationResult {
+        self.validate_read(js_to_json(value))
+    }
+
+    fn validate_decoded(&mut self, data: Result<serde_json::Value, FastSchemaError>) -> ValidationResult {
+        self.validate_read(data.map_err(|e| e.to_string()))
+    }
+
+    /// Validate data read from JS or decoded from bytes; data that could
+    /// not be read fails validation with that error
+    fn validate_read(&mut self, data: Result<serde_json::Value, String>) -> ValidationResult {
+        match data {
+            Ok(data) => self.validator.validate(&data),
+            Err(e) => {
+                console_error!("Validation error: {}", e);
+                internal_error_result(&e)
+            }
+        }
+    }
+
+    /// Validate an array read from JS; an unreadable array gives a single
+    /// failed result
+    fn validate_many_read(&mut self, values: Result<Vec<serde_json::Value>, String>) -> Vec<ValidationResult> {
+        match values {
+            Ok(data_array) => self.validator.validate_many(&data_array),
+            Err(e) => {
+                console_error!("Batch validation error: {}", e);
+                vec![internal_error_result(&e)]
+            }
+        }
+    }
+
+    fn validate_with_options_internal(
+        &mut self,
+        data_json: &str,
+        options: ValidationOptions,
+    ) -> Result<String, String> {
+        let data: serde_json::Value = serde_json::from_str(data_json)
+            .map_err(|e| format!("Invalid JSON data: {}", e))?;
+
+        let result = self.validator.validate_with_options(&data, options);
+
+        serde_json::to_string(&result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))
+    }
+
+    fn validate_many_internal(&mut self, data_array_json: &str) -> Result<String, String> {
+        // Parse JSON array
+        let data_array: Vec<serde_json::Value> = serde_json::from_str(data_array_json)
+            .map_err(|e| format!("Invalid JSON array: {}", e))?;
+
+        console_log!("Validating {} items", data_array.len());
+
+        // Validate batch
+        let results = self.validator.validate_many(&data_array);
+
+        // Serialize results
+        serde_json::to_string(&results)
+            .map_err(|e| format!("Failed to serialize results: {}", e))
+    }
+
+    fn create_error_result(&self, error_msg: &str) -> String {
+        serde_json::to_string(&internal_error_result(error_msg)).unwrap_or_else(|_| {
+            r#"{"success":false,"data":null,"errors":[{"path":"","message":"Serialization failed","code":"INTERNAL_ERROR"}]}"#.to_string()
+        })
+    }
+}
+
+/// Convert a materialized JS value (object, array, primitive) to JSON
+fn js_to_json(value: JsValue) -> Result<serde_json::Value, String> {
+    serde_wasm_bindgen::from_value(value).map_err(|e| format!("Invalid JS value: {}", e))
+}
+
+/// Convert a result to a plain JS object (maps become objects, not `Map`s)
+fn to_js<T: serde::Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+fn result_from_js(result: JsValue) -> Result<ValidationResult, JsValue> {
+    serde_wasm_bindgen::from_value(result).map_err(|e| JsValue::from_str(&format!("Invalid validation result: {}", e)))
+}
+
+#[wasm_bindgen]
+impl FastValidationResult {
+    #[wasm_bindgen(getter)]
+    pub fn success(&self) -> bool {
+        self.result.success
+    }
+
+    /// The validated data, or `undefined`
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Result<JsValue, JsValue> {
+        match &self.result.data {
+            Some(data) => to_js(data),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// The errors, as an array (so `for (const issue of result.issues)`)
+    #[wasm_bindgen(getter)]
+    pub fn issues(&self) -> Vec<ValidationIssue> {
+        self.result.errors.iter().map(|error| ValidationIssue { error: error.clone() }).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn warnings(&self) -> Vec<ValidationIssue> {
+        self.result.warnings.iter().map(|error| ValidationIssue { error: error.clone() }).collect()
+    }
+
+    /// The whole result as a plain object, as `validate_js` returns it
+    pub fn to_object(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.result)
+    }
+}
+
+#[wasm_bindgen]
+impl ValidationIssue {
+    /// Property names and array indices, e.g. `["users", 0, "email"]`
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.error.path.segments())
+    }
+
+    /// RFC 6901 JSON Pointer, e.g. `/users/0/email`
+    #[wasm_bindgen(getter)]
+    pub fn pointer(&self) -> String {
+        self.error.path.pointer()
+    }
+
+    /// e.g. `INVALID_TYPE`
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> String {
+        serde_label(&self.error.code)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.error.message.clone()
+    }
+
+    /// `expected`, `received`, bounds and other details
+    #[wasm_bindgen(getter)]
+    pub fn params(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.error.params)
+    }
+
+    /// `error` or `warning`
+    #[wasm_bindgen(getter)]
+    pub fn severity(&self) -> String {
+        serde_label(&self.error.severity)
+    }
+}
+
+/// The serialized name of a unit enum variant
+fn serde_label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// What `safeParse` returns
+#[derive(serde::Serialize)]
+struct SafeParse<'a> {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<&'a serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ParseError<'a>>,
+}
+
+#[derive(serde::Serialize)]
+struct ParseError<'a> {
+    name: &'static str,
+    message: String,
+    issues: &'a [ValidationError],
+}
+
+impl<'a> SafeParse<'a> {
+    fn new(result: &'a ValidationResult) -> Self {
+        let error = (!result.success).then(|| ParseError {
+            name: "FastSchemaError",
+            message: parse_error_message(&result.errors),
+            issues: &result.errors,
+        });
+        SafeParse { success: result.success, data: result.data.as_ref(), error }
+    }
+}
+
+/// `path: message` for each error, for thrown exceptions
+fn parse_error_message(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(|error| {
+            if error.path.is_empty() {
+                error.message.clone()
+            } else {
+                format!("{}: {}", error.path.display_path(), error.message)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Failed result for errors outside validation itself (bad input, ...)
+fn internal_error_result(error_msg: &str) -> ValidationResult {
+    ValidationResult::failure(vec![ValidationError::new(
+        String::new(),
+        error_msg.to_string(),
+        ErrorCode::InternalError,
+    )])
+}
+
+/// Named schemas with cross-schema `$ref` support
+#[wasm_bindgen]
+pub struct FastSchemaRegistry {
+    registry: SchemaRegistry,
+}
+
+#[wasm_bindgen]
+impl FastSchemaRegistry {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> FastSchemaRegistry {
+        FastSchemaRegistry {
+            registry: SchemaRegistry::new(),
+        }
+    }
+
+    /// Register (or replace) a named schema
+    #[wasm_bindgen]
+    pub fn register(&mut self, name: &str, schema_json: &str) -> Result<(), JsValue> {
+        let schema: serde_json::Value = serde_json::from_str(schema_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+
+        self.registry.register_json(name, &schema)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema '{}': {}", name, e)))
+    }
+
+    /// Remove a named schema, returning whether it was registered
+    #[wasm_bindgen]
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.registry.unregister(name).is_some()
+    }
+
+    /// Registered schema names as a JSON array
+    #[wasm_bindgen]
+    pub fn names(&self) -> String {
+        serde_json::json!(self.registry.names()).to_string()
+    }
+
+    /// An independent copy sharing the compiled schema
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_instance(&self) -> FastSchemaRegistry {
+        FastSchemaRegistry {
+            registry: self.registry.clone(),
+        }
+    }
+
+    /// Release the WASM memory held by this instance, like `free()`
+    #[wasm_bindgen]
+    pub fn dispose(self) {}
+
+    /// Create a validator for a registered schema
+    #[wasm_bindgen]
+    pub fn create_validator(&mut self, name: &str) -> Result<FastValidator, JsValue> {
+        let validator = self.registry.validator(name)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let schema_json = serde_json::to_string(&validator.compiled_schema.schema)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(FastValidator::from_validator(validator, schema_json))
+    }
+}
+
+impl Default for FastSchemaRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Many named schemas in one WASM object, for apps validating dozens of
+/// entity types without a `FastValidator` per type
+#[wasm_bindgen]
+pub struct FastValidatorSet {
+    set: ValidatorSet,
+}
+
+#[wasm_bindgen]
+impl FastValidatorSet {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> FastValidatorSet {
+        FastValidatorSet {
+            set: ValidatorSet::new(),
+        }
+    }
+
+    /// Add (or replace) a named schema; schemas may reference each other
+    #[wasm_bindgen]
+    pub fn add(&mut self, name: &str, schema_json: &str) -> Result<(), JsValue> {
+        let schema: serde_json::Value = serde_json::from_str(schema_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+
+        self.set.add_json(name, &schema)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema '{}': {}", name, e)))
+    }
+
+    /// Remove a named schema, returning whether it was present
+    #[wasm_bindgen]
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.set.remove(name)
+    }
+
+    /// Schema names as a JSON array
+    #[wasm_bindgen]
+    pub fn names(&self) -> String {
+        serde_json::json!(self.set.names()).to_string()
+    }
+
+    /// Validate JSON data against a named schema; throws if `name` is unknown
+    #[wasm_bindgen]
+    pub fn validate_as(&mut self, name: &str, data_json: &str) -> Result<String, JsValue> {
+        let result = match serde_json::from_str::<serde_json::Value>(data_json) {
+            Ok(data) => self.set.validate_as(name, &data)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?,
+            Err(e) => internal_error_result(&format!("Invalid JSON data: {}", e)),
+        };
+
+        serde_json::to_string(&result)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+    }
+
+    /// An independent copy sharing the compiled schema
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_instance(&self) -> FastValidatorSet {
+        FastValidatorSet {
+            set: self.set.clone(),
+        }
+    }
+
+    /// Release the WASM memory held by this instance, like `free()`
+    #[wasm_bindgen]
+    pub fn dispose(self) {}
+
+    /// Like `validate_as`, for a JS value, returning the result as a JS object
+    #[wasm_bindgen]
+    pub fn validate_js_as(&mut self, name: &str, value: JsValue) -> Result<JsValue, JsValue> {
+        let result = match js_to_json(value) {
+            Ok(data) => self.set.validate_as(name, &data)
+                .map_err(|e| JsValue::from_str(&e.to_string()))?,
+            Err(e) => internal_error_result(&e),
+        };
+        to_js(&result)
+    }
+}
+
+impl Default for FastValidatorSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Validates React element trees (`{type, props, children}` objects)
+/// against an HTML element schema, recursing into children
+#[wasm_bindgen]
+pub struct FastHtmlValidator {
+    validator: validator::Validator,
+}
+
+#[wasm_bindgen]
+impl FastHtmlValidator {
+    /// `schema_json` describes the root element, e.g. `{"element_type":
+    /// "Div", "props": {"validate_accessibility": true}, "children": {..}}`;
+    /// `children` is any schema, typically a union of element schemas
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema_json: &str) -> Result<FastHtmlValidator, JsValue> {
+        let validator = tagged_validator(schema_json, "htmlelement", None)?;
+        Ok(FastHtmlValidator { validator })
+    }
+
+    /// Validate an element given as JSON, returning the result as JSON
+    #[wasm_bindgen]
+    pub fn validate(&self, element_json: &str) -> String {
+        validate_element_json(&self.validator, element_json)
+    }
+
+    /// Validate an element given as a JS object, returning the result as
+    /// a JS object
+    #[wasm_bindgen]
+    pub fn validate_value(&self, element: JsValue) -> Result<JsValue, JsValue> {
+        validate_element_js(&self.validator, element)
+    }
+
+    /// Validate an HTML string, returning the result as JSON; each error's
+    /// `location` gives the byte offsets of the element or attribute
+    #[wasm_bindgen]
+    pub fn validate_markup(&self, markup: &str) -> String {
+        let result = self
+            .validator
+            .validate_html(markup)
+            .unwrap_or_else(|e| internal_error_result(&e.to_string()));
+        serde_json::to_string(&result).unwrap_or_else(|_| {
+            r#"{"success":false,"data":null,"errors":[{"path":"","message":"Serialization failed","code":"INTERNAL_ERROR"}]}"#.to_string()
+        })
+    }
+
+    /// Release the WASM memory held by this instance, like `free()`
+    #[wasm_bindgen]
+    pub fn dispose(self) {}
+}
+
+/// Validates React component elements: the component name, required and
+/// typed props (`props_schema`) and children
+#[wasm_bindgen]
+pub struct FastReactValidator {
+    validator: validator::Validator,
+}
+
+#[wasm_bindgen]
+impl FastReactValidator {
+    /// `component_json` describes the component, e.g. `{"component_name":
+    /// "Avatar", "props_schema": {"size": {"type": "number"}},
+    /// "required_props": ["user"]}`
+    #[wasm_bindgen(constructor)]
+    pub fn new(component_json: &str) -> Result<FastReactValidator, JsValue> {
+        let validator = tagged_validator(component_json, "reactcomponent", Some("component"))?;
+        Ok(FastReactValidator { validator })
+    }
+
+    /// Validate a component element given as JSON, returning the result as JSON
+    #[wasm_bindgen]
+    pub fn validate(&self, element_json: &str) -> String {
+        validate_element_json(&self.validator, element_json)
+    }
+
+    /// Validate a component element given as a JS object, returning the
+    /// result as a JS object
+    #[wasm_bindgen]
+    pub fn validate_value(&self, element: JsValue) -> Result<JsValue, JsValue> {
+        validate_element_js(&self.validator, element)
+    }
+
+    /// Release the WASM memory held by this instance, like `free()`
+    #[wasm_bindgen]
+    pub fn dispose(self) {}
+}
+
+/// Compile an element or component schema, adding its `type` tag (and
+/// wrapping it in `field`) unless the JSON already is a full schema
+fn tagged_validator(schema_json: &str, tag: &str, field: Option<&str>) -> Result<validator::Validator, JsValue> {
+    let mut schema: serde_json::Value = serde_json::from_str(schema_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+    if schema.get("type").is_none() {
+        if let Some(field) = field {
+            schema = serde_json::json!({ field: schema });
+        }
+        if let Some(object) = schema.as_object_mut() {
+            object.insert("type".to_string(), serde_json::Value::String(tag.to_string()));
+        }
+    }
+    let schema: SchemaType = serde_json::from_value(schema)
+        .map_err(|e| JsValue::from_str(&format!("Invalid {} schema: {}", tag, e)))?;
+    validator::Validator::new(schema).map_err(|e| JsValue::from_str(&format!("Schema compilation failed: {}", e)))
+}
+
+fn validate_element_json(validator: &validator::Validator, element_json: &str) -> String {
+    let result = match serde_json::from_str::<serde_json::Value>(element_json) {
+        Ok(element) => validator.validate(&element),
+        Err(e) => internal_error_result(&format!("Invalid JSON data: {}", e)),
+    };
+    serde_json::to_string(&result).unwrap_or_else(|_| {
+        r#"{"success":false,"data":null,"errors":[{"path":"","message":"Serialization failed","code":"INTERNAL_ERROR"}]}"#.to_string()
+    })
+}
+
+fn validate_element_js(validator: &validator::Validator, element: JsValue) -> Result<JsValue, JsValue> {
+    let result = match js_to_json(element) {
+        Ok(element) => validator.validate(&element),
+        Err(e) => internal_error_result(&e),
+    };
+    to_js(&result)
+}
+
+/// Batch validator for high-performance scenarios.
+///
+/// WASM runs `validate_dataset` on one thread. To spread a large dataset
+/// over Web Workers, split it with `partition_dataset`, post each
+/// partition to a worker holding its own `FastBatchValidator` for the same
+/// schema, call `validate_partition(data, offset)` there and concatenate
+/// the results in partition order; error paths index the whole dataset.
+#[wasm_bindgen]
+pub struct FastBatchValidator {
+    /// Shared with pending `validate_stream` calls; cloned on write
+    validator: Rc<BatchValidator>,
+    /// Array being fed with `feed` between `begin_array` and `finish`
+    stream: Option<BatchStream>,
+}
+
+#[wasm_bindgen]
+impl FastBatchValidator {
+    /// Create a new batch validator with specified batch size
+    #[wasm_bindgen(constructor)]
+    pub fn new(schema_json: &str, batch_size: usize) -> Result<FastBatchValidator, JsValue> {
+        console_log!("Creating FastBatchValidator with batch size: {}", batch_size);
+
+        let schema: SchemaType = serde_json::from_str(schema_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+
+        let validator = BatchValidator::new(schema, batch_size)
+            .map_err(|e| JsValue::from_str(&format!("Batch validator creation failed: {}", e)))?;
+
+        Ok(FastBatchValidator {
+            validator: Rc::new(validator),
+            stream: None,
+        })
+    }
+
+    /// Adapt the batch size so each batch takes about `target_ms` (e.g. 8 to
+    /// keep a 60 fps UI responsive); `undefined` restores the fixed size
+    #[wasm_bindgen]
+    pub fn set_frame_budget(&mut self, target_ms: Option<f64>) {
+        Rc::make_mut(&mut self.validator).set_frame_budget(target_ms);
+    }
+
+    /// Validate a numeric column (passed as a `Float64Array`) without JSON
+    /// serialization, returning a `Uint8Array` with 1 for each valid value
+    #[wasm_bindgen]
+    pub fn validate_f64_array(&self, values: &[f64]) -> Vec<u8> {
+        self.validator.validate_numbers(values)
+    }
+
+    /// `validate_f64_array` for a `Float64Array` kept as a JS object
+    #[wasm_bindgen]
+    pub fn validate_typed_array(&self, values: &js_sys::Float64Array) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(self.validate_f64_array(&values.to_vec()).as_slice())
+    }
+
+    /// Current batch size, to slice data for `validate_partition` calls
+    /// spread over animation frames
+    #[wasm_bindgen]
+    pub fn get_batch_size(&self) -> usize {
+        self.validator.batch_size()
+    }
+
+    /// Validate a large dataset efficiently
+    #[wasm_bindgen]
+    pub fn validate_dataset(&mut self, data_array_json: &str) -> String {
+        let data_array: Vec<serde_json::Value> = match serde_json::from_str(data_array_json) {
+            Ok(data) => data,
+            Err(e) => {
+                console_error!("Invalid JSON array: {}", e);
+                return format!("[{}]", self.create_error_result(&format!("Invalid JSON array: {}", e)));
+            }
+        };
+
+        console_log!("Batch validating {} items with batch size {}", data_array.len(), self.validator.batch_size());
+
+        let results = self.validator.validate_dataset(&data_array);
+
+        match serde_json::to_string(&results) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize batch results: {}", e);
+                format!("[{}]", self.create_error_result(&format!("Serialization failed: {}", e)))
+            }
+        }
+    }
+
+    /// Validate a dataset into `{total_items, valid_bitset, failed: [{index, errors}]}`,
+    /// skipping per-item results for valid values. With `failures_only` the
+    /// bitset is omitted too.
+    #[wasm_bindgen]
+    pub fn validate_dataset_compact(&mut self, data_array_json: &str, failures_only: bool) -> String {
+        let data_array: Vec<serde_json::Value> = match serde_json::from_str(data_array_json) {
+            Ok(data) => data,
+            Err(e) => {
+                console_error!("Invalid JSON array: {}", e);
+                return self.create_error_result(&format!("Invalid JSON array: {}", e));
+            }
+        };
+
+        let compact = self.validator.validate_dataset_compact(&data_array, failures_only);
+
+        match serde_json::to_string(&compact) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize batch results: {}", e);
+                self.create_error_result(&format!("Serialization failed: {}", e))
+            }
+        }
+    }
+
+    /// Validate a dataset into `{valid, invalid: [{index, errors}], stats}`.
+    /// `valid` lists the indices of the valid values, or with
+    /// `include_items` the validated values themselves.
+    #[wasm_bindgen]
+    pub fn validate_dataset_partitioned(&mut self, data_array_json: &str, include_items: bool) -> String {
+        let data_array: Vec<serde_json::Value> = match serde_json::from_str(data_array_json) {
+            Ok(data) => data,
+            Err(e) => {
+                console_error!("Invalid JSON array: {}", e);
+                return self.create_error_result(&format!("Invalid JSON array: {}", e));
+            }
+        };
+
+        let partitioned = self.validator.validate_dataset_partitioned(&data_array, include_items);
+
+        match serde_json::to_string(&partitioned) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize batch results: {}", e);
+                self.create_error_result(&format!("Serialization failed: {}", e))
+            }
+        }
+    }
+
+    /// Validate a dataset, stopping between batches once `signal` (an
+    /// `AbortSignal`) is aborted. Returns `{results, cancelled}`, with the
+    /// results of the batches completed before the abort.
+    #[wasm_bindgen]
+    pub fn validate_dataset_with_signal(&mut self, data_array_json: &str, signal: JsValue) -> String {
+        let data_array: Vec<serde_json::Value> = match serde_json::from_str(data_array_json) {
+            Ok(data) => data,
+            Err(e) => {
+                console_error!("Invalid JSON array: {}", e);
+                return format!("[{}]", self.create_error_result(&format!("Invalid JSON array: {}", e)));
+            }
+        };
+
+        let aborted = || {
+            js_sys::Reflect::get(&signal, &JsValue::from_str("aborted"))
+                .map(|aborted| aborted.is_truthy())
+                .unwrap_or(false)
+        };
+        let outcome = self.validator.validate_dataset_until(&data_array, aborted);
+        if outcome.cancelled {
+            console_log!("Batch validation aborted after {} items", outcome.results.len());
+        }
+
+        match serde_json::to_string(&outcome) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize batch results: {}", e);
+                format!("[{}]", self.create_error_result(&format!("Serialization failed: {}", e)))
+            }
+        }
+    }
+
+    /// Validate a `Blob` or `File` holding a JSON array (or NDJSON with
+    /// `ndjson`), read in chunks; see `validate_stream`
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn validate_blob(&self, blob: JsValue, ndjson: bool) -> js_sys::Promise {
+        match call_method(&blob, "stream") {
+            Ok(stream) => self.validate_stream(stream, ndjson),
+            Err(e) => js_sys::Promise::reject(&e),
+        }
+    }
+
+    /// Validate a `ReadableStream` of bytes or strings holding a JSON array
+    /// (or NDJSON with `ndjson`) as it is read, without buffering the whole
+    /// document. Resolves to `{summary, failed: [{index, errors}]}`; valid
+    /// items are only counted, so memory stays flat for large uploads.
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen]
+    pub fn validate_stream(&self, stream: JsValue, ndjson: bool) -> js_sys::Promise {
+        let validator = Rc::clone(&self.validator);
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let reader = call_method(&stream, "getReader")?;
+            let mut batch = if ndjson { BatchStream::ndjson() } else { BatchStream::new() };
+            let mut failed = Vec::new();
+
+            let outcome: Result<StreamSummary, JsValue> = async {
+                loop {
+                    let chunk = wasm_bindgen_futures::JsFuture::from(js_sys::Promise::from(call_method(&reader, "read")?)).await?;
+                    if js_sys::Reflect::get(&chunk, &JsValue::from_str("done"))?.is_truthy() {
+                        break;
+                    }
+
+                    let value = js_sys::Reflect::get(&chunk, &JsValue::from_str("value"))?;
+                    let offset = batch.total_items();
+                    let results = match value.as_string() {
+                        Some(text) => batch.feed(&validator, &text),
+                        None => batch.feed_bytes(&validator, &js_sys::Uint8Array::new(&value).to_vec()),
+                    };
+                    collect_failures(offset, results, &mut failed)?;
+                }
+                let offset = batch.total_items();
+                collect_failures(offset, batch.flush(&validator), &mut failed)?;
+                batch.finish().map_err(|e| JsValue::from_str(&e.to_string()))
+            }.await;
+
+            // Stop the underlying source rather than leave it half read
+            if outcome.is_err() {
+                let _ = call_method(&reader, "cancel");
+            }
+            to_js(&serde_json::json!({"summary": outcome?, "failed": failed}))
+        })
+    }
+
+    /// Start validating a JSON array that arrives in chunks, discarding any
+    /// unfinished stream
+    #[wasm_bindgen]
+    pub fn begin_array(&mut self) {
+        self.stream = Some(BatchStream::new());
+    }
+
+    /// Feed the next chunk of array text (split anywhere), returning the
+    /// results for elements it completed
+    #[wasm_bindgen]
+    pub fn feed(&mut self, items_json: &str) -> Result<String, JsValue> {
+        let stream = self.stream.as_mut()
+            .ok_or_else(|| JsValue::from_str("begin_array() must be called before feed()"))?;
+
+        let results = stream.feed(&self.validator, items_json)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_json::to_string(&results)
+            .map_err(|e| JsValue::from_str(&format!("Serialization failed: {}", e)))
+    }
+
+    /// End the stream and get its aggregated stats
+    #[wasm_bindgen]
+    pub fn finish(&mut self) -> Result<String, JsValue> {
+        let stream = self.stream.take()
+            .ok_or_else(|| JsValue::from_str("begin_array() must be called before finish()"))?;
+
+        let summary = stream.finish().map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_json::to_string(&summary)
+            .map_err(|e| JsValue::from_str(&format!("Serialization failed: {}", e)))
+    }
+
+    /// Split a dataset into `{offset, data}` partitions, one per worker
+    #[wasm_bindgen]
+    pub fn partition_dataset(&self, data_array_json: &str, workers: usize) -> Result<String, JsValue> {
+        let data_array: Vec<serde_json::Value> = serde_json::from_str(data_array_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid JSON array: {}", e)))?;
+
+        let partitions: Vec<_> = partition_ranges(data_array.len(), workers)
+            .into_iter()
+            .map(|range| serde_json::json!({
+                "offset": range.start,
+                "data": &data_array[range],
+            }))
+            .collect();
+
+        Ok(serde_json::Value::Array(partitions).to_string())
+    }
+
+    /// Validate one partition from `partition_dataset`, typically in a worker
+    #[wasm_bindgen]
+    pub fn validate_partition(&self, data_array_json: &str, offset: usize) -> String {
+        let data_array: Vec<serde_json::Value> = match serde_json::from_str(data_array_json) {
+            Ok(data) => data,
+            Err(e) => {
+                console_error!("Invalid JSON array: {}", e);
+                return format!("[{}]", self.create_error_result(&format!("Invalid JSON array: {}", e)));
+            }
+        };
+
+        let results = self.validator.validate_partition(offset, &data_array);
+
+        match serde_json::to_string(&results) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize batch results: {}", e);
+                format!("[{}]", self.create_error_result(&format!("Serialization failed: {}", e)))
+            }
+        }
+    }
+
+    /// An independent copy sharing the compiled schema
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_instance(&self) -> FastBatchValidator {
+        // An unfinished `begin_array` stream is not copied
+        FastBatchValidator {
+            validator: Rc::clone(&self.validator),
+            stream: None,
+        }
+    }
+
+    /// Release the WASM memory held by this instance, like `free()`
+    #[wasm_bindgen]
+    pub fn dispose(self) {}
+
+    /// Get batch validator statistics
+    #[wasm_bindgen]
+    pub fn get_batch_stats(&self) -> String {
+        let stats = self.validator.get_stats();
+        match serde_json::to_string(&stats) {
+            Ok(json) => json,
+            Err(e) => {
+                console_error!("Failed to serialize batch stats: {}", e);
+                "{}".to_string()
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn reset_batch_stats(&self) {
+        self.validator.reset_stats();
+    }
+
+    fn create_error_result(&self, error_msg: &str) -> String {
+        let error_result = ValidationResult {
+            success: false,
+            data: None,
+            errors: vec![ValidationError::new(
+                "".to_string(),
+                error_msg.to_string(),
+                ErrorCode::InternalError,
+            )],
+            warnings: Vec::new(),
+            errors_truncated: false,
+            performance: None,
+        };
+
+        serde_json::to_string(&error_result).unwrap_or_else(|_| {
+            r#"{"success":false,"data":null,"errors":[{"path":"","message":"Batch validation failed","code":"INTERNAL_ERROR"}]}"#.to_string()
+        })
+    }
+}
+
+/// Call a method that takes no arguments on a JS object
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+fn call_method(target: &JsValue, name: &str) -> Result<JsValue, JsValue> {
+    let method = js_sys::Reflect::get(target, &JsValue::from_str(name))?
+        .dyn_into::<js_sys::Function>()
+        .map_err(|_| JsValue::from_str(&format!("Expected an object with a {}() method", name)))?;
+    method.call0(target)
+}
+
+/// Keep the failed items of the results for a stream chunk whose first
+/// item is at `offset`
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+fn collect_failures(
+    offset: usize,
+    results: Result<Vec<ValidationResult>, error::FastSchemaError>,
+    failed: &mut Vec<FailedItem>,
+) -> Result<(), JsValue> {
+    let results = results.map_err(|e| JsValue::from_str(&e.to_string()))?;
+    failed.extend(
+        results
+            .into_iter()
+            .enumerate()
+            .filter(|(_, result)| !result.success)
+            .map(|(position, result)| FailedItem { index: offset + position, errors: result.errors }),
+    );
+    Ok(())
+}
+
+/// Utility functions for JavaScript
+#[wasm_bindgen]
+pub struct FastSchemaUtils;
+
+#[wasm_bindgen]
+impl FastSchemaUtils {
+    /// Validate a schema definition
+    #[wasm_bindgen]
+    pub fn validate_schema(schema_json: &str) -> String {
+        let result = match serde_json::from_str::<SchemaType>(schema_json) {
+            Ok(schema) => {
+                // Try to compile the schema
+                match Validator::new(schema) {
+                    Ok(_) => serde_json::json!({
+                        "valid": true,
+                        "message": "Schema is valid"
+                    }),
+                    Err(e) => serde_json::json!({
+                        "valid": false,
+                        "message": format!("Schema compilation failed: {}", e)
+                    })
+                }
+            }
+            Err(e) => serde_json::json!({
+                "valid": false,
+                "message": format!("Invalid schema JSON: {}", e)
+            })
+        };
+
+        result.to_string()
+    }
+
+    /// Check a schema document against the bundled meta-schema,
+    /// reporting every problem with its path in the document
+    #[wasm_bindgen]
+    pub fn validate_against_metaschema(schema_json: &str) -> String {
+        let result = match serde_json::from_str::<serde_json::Value>(schema_json) {
+            Ok(schema) => {
+                let (warnings, errors): (Vec<_>, Vec<_>) = validate_schema_document(&schema)
+                    .into_iter()
+                    .partition(|error| error.severity == Severity::Warning);
+                serde_json::json!({
+                    "valid": errors.is_empty(),
+                    "errors": errors,
+                    "warnings": warnings
+                })
+            }
+            Err(e) => serde_json::json!({
+                "valid": false,
+                "errors": [ValidationError::new(
+                    String::new(),
+                    format!("Invalid schema JSON: {}", e),
+                    ErrorCode::SchemaInvalid,
+                )]
+            })
+        };
+
+        result.to_string()
+    }
+
+    /// Set how many compiled regexes the shared cache keeps (0 disables it)
+    #[wasm_bindgen]
+    pub fn set_regex_cache_capacity(capacity: usize) {
+        regex_cache::set_capacity(capacity);
+    }
+
+    /// Get library version information
+    #[wasm_bindgen]
+    pub fn get_version() -> String {
+        serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "name": env!("CARGO_PKG_NAME"),
+            "description": env!("CARGO_PKG_DESCRIPTION")
+        }).to_string()
+    }
+
+    /// Get performance recommendations for a schema
+    #[wasm_bindgen]
+    pub fn analyze_schema_performance(schema_json: &str) -> String {
+        let analysis = match serde_json::from_str::<SchemaType>(schema_json) {
+            Ok(schema) => {
+                let compiled = schema.compile();
+                serde_json::json!({
+                    "complexity": compiled.estimated_complexity,
+                    "max_depth": compiled.max_depth,
+                    "has_patterns": compiled.has_patterns,
+                    "estimated_validation_time_us": schema.estimated_validation_time(),
+                    "recommendations": generate_recommendations(&compiled)
+                })
+            }
+            Err(e) => serde_json::json!({
+                "error": format!("Invalid schema: {}", e)
+            })
+        };
+
+        analysis.to_string()
+    }
+}
+
+/// Schema derivation operations for JavaScript
+#[wasm_bindgen]
+pub struct SchemaOps;
+
+#[wasm_bindgen]
+impl SchemaOps {
+    /// Make every top-level property optional
+    #[wasm_bindgen]
+    pub fn partial(schema_json: &str) -> Result<String, JsValue> {
+        derive_schema(schema_json, |schema| schema.partial())
+    }
+
+    /// Make every property optional, including nested objects
+    #[wasm_bindgen]
+    pub fn deep_partial(schema_json: &str) -> Result<String, JsValue> {
+        derive_schema(schema_json, |schema| schema.deep_partial())
+    }
+
+    /// Make every top-level property required
+    #[wasm_bindgen]
+    pub fn required(schema_json: &str) -> Result<String, JsValue> {
+        derive_schema(schema_json, |schema| schema.required())
+    }
+
+    /// Keep only the properties listed in a JSON array of keys
+    #[wasm_bindgen]
+    pub fn pick(schema_json: &str, keys_json: &str) -> Result<String, JsValue> {
+        let keys = parse_keys(keys_json)?;
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        derive_schema(schema_json, |schema| schema.pick(&keys))
+    }
+
+    /// Remove the properties listed in a JSON array of keys
+    #[wasm_bindgen]
+    pub fn omit(schema_json: &str, keys_json: &str) -> Result<String, JsValue> {
+        let keys = parse_keys(keys_json)?;
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        derive_schema(schema_json, |schema| schema.omit(&keys))
+    }
+
+    /// Add or override properties from a JSON object of property schemas
+    #[wasm_bindgen]
+    pub fn extend(schema_json: &str, properties_json: &str) -> Result<String, JsValue> {
+        let properties: std::collections::HashMap<String, SchemaType> =
+            serde_json::from_str(properties_json)
+                .map_err(|e| JsValue::from_str(&format!("Invalid properties JSON: {}", e)))?;
+        derive_schema(schema_json, |schema| schema.extend(properties))
+    }
+
+    /// Merge two object schemas; the second one wins on conflicts
+    #[wasm_bindgen]
+    pub fn merge(schema_json: &str, other_json: &str) -> Result<String, JsValue> {
+        let other: SchemaType = serde_json::from_str(other_json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+        derive_schema(schema_json, |schema| schema.merge(&other))
+    }
+}
+
+/// Fluent schema construction for JavaScript; every modifier returns a new
+/// builder, so partial chains can be shared
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct FastSchemaBuilder {
+    builder: SchemaBuilder,
+}
+
+#[wasm_bindgen]
+impl FastSchemaBuilder {
+    #[wasm_bindgen]
+    pub fn string() -> FastSchemaBuilder {
+        SchemaBuilder::string().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn number() -> FastSchemaBuilder {
+        SchemaBuilder::number().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn integer() -> FastSchemaBuilder {
+        SchemaBuilder::integer().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn boolean() -> FastSchemaBuilder {
+        SchemaBuilder::boolean().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn null() -> FastSchemaBuilder {
+        SchemaBuilder::null().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn any() -> FastSchemaBuilder {
+        SchemaBuilder::any().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn literal(value: JsValue) -> Result<FastSchemaBuilder, JsValue> {
+        let value = serde_wasm_bindgen::from_value(value)
+            .map_err(|e| JsValue::from_str(&format!("Invalid literal: {}", e)))?;
+        Ok(SchemaBuilder::literal(value).into())
+    }
+
+    /// Arrays of `items`, a builder or a plain schema object
+    #[wasm_bindgen]
+    pub fn array(items: JsValue) -> Result<FastSchemaBuilder, JsValue> {
+        Ok(SchemaBuilder::array(builder_from_js(&items)?).into())
+    }
+
+    /// Objects with the properties of `shape`, whose values are builders
+    /// or plain schema objects
+    #[wasm_bindgen]
+    pub fn object(shape: js_sys::Object) -> Result<FastSchemaBuilder, JsValue> {
+        let shape = js_sys::Object::entries(&shape)
+            .iter()
+            .map(|entry| {
+                let entry = js_sys::Array::from(&entry);
+                let key = entry.get(0).as_string().unwrap_or_default();
+                Ok((key, builder_from_js(&entry.get(1))?))
+            })
+            .collect::<Result<Vec<_>, JsValue>>()?;
+        Ok(SchemaBuilder::object(shape).into())
+    }
+
+    #[wasm_bindgen]
+    pub fn union(options: js_sys::Array) -> Result<FastSchemaBuilder, JsValue> {
+        let options = options
+            .iter()
+            .map(|option| builder_from_js(&option))
+            .collect::<Result<Vec<_>, JsValue>>()?;
+        Ok(SchemaBuilder::union(options).into())
+    }
+
+    /// Start from a persisted schema document
+    #[wasm_bindgen(js_name = fromJSON)]
+    pub fn from_json(schema_json: &str) -> Result<FastSchemaBuilder, JsValue> {
+        SchemaBuilder::from_json(schema_json)
+            .map(FastSchemaBuilder::from)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen]
+    pub fn min(&self, bound: f64) -> FastSchemaBuilder {
+        self.builder.clone().min(bound).into()
+    }
+
+    #[wasm_bindgen]
+    pub fn max(&self, bound: f64) -> FastSchemaBuilder {
+        self.builder.clone().max(bound).into()
+    }
+
+    #[wasm_bindgen]
+    pub fn length(&self, length: usize) -> FastSchemaBuilder {
+        self.builder.clone().length(length).into()
+    }
+
+    #[wasm_bindgen]
+    pub fn email(&self) -> FastSchemaBuilder {
+        self.builder.clone().email().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn url(&self) -> FastSchemaBuilder {
+        self.builder.clone().url().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn uuid(&self) -> FastSchemaBuilder {
+        self.builder.clone().uuid().into()
+    }
+
+    /// Any named format, e.g. `"ipv4"` or `"date-time"`
+    #[wasm_bindgen]
+    pub fn format(&self, format: &str) -> Result<FastSchemaBuilder, JsValue> {
+        let format = serde_json::from_value(serde_json::Value::String(format.to_string()))
+            .map_err(|e| JsValue::from_str(&format!("Invalid format: {}", e)))?;
+        Ok(self.builder.clone().format(format).into())
+    }
+
+    #[wasm_bindgen]
+    pub fn regex(&self, pattern: &str) -> FastSchemaBuilder {
+        self.builder.clone().regex(pattern).into()
+    }
+
+    #[wasm_bindgen]
+    pub fn int(&self) -> FastSchemaBuilder {
+        self.builder.clone().int().into()
+    }
+
+    #[wasm_bindgen(js_name = multipleOf)]
+    pub fn multiple_of(&self, step: f64) -> FastSchemaBuilder {
+        self.builder.clone().multiple_of(step).into()
+    }
+
+    #[wasm_bindgen]
+    pub fn unique(&self) -> FastSchemaBuilder {
+        self.builder.clone().unique().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn strict(&self) -> FastSchemaBuilder {
+        self.builder.clone().strict().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn strip(&self) -> FastSchemaBuilder {
+        self.builder.clone().strip().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn passthrough(&self) -> FastSchemaBuilder {
+        self.builder.clone().passthrough().into()
+    }
+
+    #[wasm_bindgen]
+    pub fn optional(&self) -> FastSchemaBuilder {
+        self.builder.clone().optional().into()
+    }
+
+    #[wasm_bindgen(js_name = isOptional)]
+    pub fn is_optional(&self) -> bool {
+        self.builder.is_optional()
+    }
+
+    /// The schema as a JS object, so `JSON.stringify` persists builders
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<JsValue, JsValue> {
+        let schema = self.builder.clone().build().map_err(|e| JsValue::from_str(&e.to_string()))?;
+        to_js(&schema)
+    }
+
+    /// Compile the schema into a validator
+    #[wasm_bindgen]
+    pub fn build(&self) -> Result<FastValidator, JsValue> {
+        let schema_json = self.builder.to_json().map_err(|e| JsValue::from_str(&e.to_string()))?;
+        FastValidator::new(&schema_json, None)
+    }
+}
+
+impl From<SchemaBuilder> for FastSchemaBuilder {
+    fn from(builder: SchemaBuilder) -> Self {
+        FastSchemaBuilder { builder }
+    }
+}
+
+/// A builder from a `FastSchemaBuilder` or a plain schema object. Builders
+/// are read through `toJSON` and `isOptional` rather than unwrapped, which
+/// would hand their memory over and invalidate the caller's reference.
+fn builder_from_js(value: &JsValue) -> Result<SchemaBuilder, JsValue> {
+    // Rethrows a builder's own error from `toJSON`
+    let schema_json = js_sys::JSON::stringify(value)?
+        .as_string()
+        .ok_or_else(|| JsValue::from_str("Expected a schema"))?;
+    let builder = SchemaBuilder::from_json(&schema_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid schema: {}", e)))?;
+
+    let optional = js_sys::Reflect::get(value, &JsValue::from_str("isOptional"))
+        .ok()
+        .filter(JsValue::is_function)
+        .map(js_sys::Function::from)
+        .and_then(|method| method.call0(value).ok())
+        .and_then(|optional| optional.as_bool())
+        .unwrap_or(false);
+    Ok(if optional { builder.optional() } else { builder })
+}
+
+fn derive_schema(
+    schema_json: &str,
+    operation: impl FnOnce(&SchemaType) -> Result<SchemaType, error::FastSchemaError>,
+) -> Result<String, JsValue> {
+    let schema: SchemaType = serde_json::from_str(schema_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid schema JSON: {}", e)))?;
+
+    let derived = operation(&schema)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    serde_json::to_string(&derived)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize schema: {}", e)))
+}
+
+fn parse_keys(keys_json: &str) -> Result<Vec<String>, JsValue> {
+    serde_json::from_str(keys_json)
+        .map_err(|e| JsValue::from_str(&format!("Invalid keys JSON: {}", e)))
+}
+
+fn generate_recommendations(compiled: &CompiledSchema) -> Vec<String> {
+    let mut recommendations = Vec::new();
+
+    if compiled.estimated_complexity > 100 {
+        recommendations.push("Consider simplifying the schema for better performance".to_string());
+    }
+
+    if compiled.max_depth > 5 {
+        recommendations.push("Deep nesting detected - consider flattening the schema".to_string());
+    }
+
+    if compiled.has_patterns {
+        recommendations.push("Regex patterns detected - cache validators for repeated use".to_string());
+    }
+
+    if compiled.estimated_complexity > 50 && compiled.max_depth > 3 {
+        recommendations.push("Complex schema detected - consider using batch validation for large datasets".to_string());
+    }
+
+    if recommendations.is_empty() {
+        recommendations.push("Schema is well-optimized for performance".to_string());
+    }
+
+    recommendations
+}
+
+// WASM-specific optimizations and utilities
+#[cfg(target_arch = "wasm32")]
+mod wasm_optimizations {
+    use super::*;
+
+    /// Memory-efficient JSON parsing for WASM
+    pub fn parse_json_streaming(json_str: &str) -> Result<serde_json::Value, serde_json::Error> {
+        // For large JSON, we could implement streaming parsing here
+        // For now, use standard parsing
+        serde_json::from_str(json_str)
+    }
+
+    /// WASM-specific memory management
+    pub fn optimize_memory_usage() {
+        // Force garbage collection if available
+        // In WASM, this is handled by the JS runtime
+    }
+
+    /// Check WASM memory limits
+    pub fn check_memory_limits(data_size: usize) -> bool {
+        // Conservative estimate: 1MB per 10,000 items
+        const BYTES_PER_ITEM: usize = 100;
+        let estimated_memory = data_size * BYTES_PER_ITEM;
+
+        // WASM has a 4GB memory limit, but be conservative
+        estimated_memory < 100_000_000 // 100MB limit
+    }
+}
+
+// Export WASM optimizations when targeting WASM
+#[cfg(target_arch = "wasm32")]
+pub use wasm_optimizations::*;
+
+// Tests for the public API
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn test_fast_validator_creation() {
+        let schema_json = r#"{
+            "type": "string",
+            "minLength": 2,
+            "maxLength": 10
+        }"#;
+
+        let validator = FastValidator::new(schema_json, None);
+        assert!(validator.is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_basic_validation() {
+        let schema_json = r#"{
+            "type": "string",
+            "minLength": 2,
+            "maxLength": 10
+        }"#;
+
+        let mut validator = FastValidator::new(schema_json, None).unwrap();
+
+        // Valid string
+        let result = validator.validate(r#""hello""#);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(parsed.success);
+
+        // Invalid string (too short)
+        let result = validator.validate(r#""x""#);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(!parsed.success);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_clone_and_memory_info() {
+        let validator = FastValidator::new(r#"{"type": "string", "minLength": 2}"#, None).unwrap();
+        let mut copy = validator.clone_instance();
+        validator.dispose();
+
+        let parsed: ValidationResult = serde_json::from_str(&copy.validate(r#""x""#)).unwrap();
+        assert!(!parsed.success);
+
+        let info: serde_json::Value = serde_json::from_str(&copy.get_memory_info()).unwrap();
+        assert_eq!(info["schema_bytes"], 34);
+        #[cfg(target_arch = "wasm32")]
+        assert!(info["linear_memory_bytes"].as_u64().unwrap() >= 65536);
+    }
+
+    #[test]
+    fn test_clone_instances() {
+        let mut validator = FastValidator::new(r#"{"type": "string", "minLength": 2}"#, None).unwrap();
+        let mut copy = validator.clone_instance();
+        assert!(Rc::ptr_eq(&validator.validator, &copy.validator));
+
+        // Replacing the schema of one leaves the other alone
+        validator.update_schema(r#"{"type": "number"}"#).unwrap();
+        validator.dispose();
+        let parsed: ValidationResult = serde_json::from_str(&copy.validate(r#""ab""#)).unwrap();
+        assert!(parsed.success);
+        let info: serde_json::Value = serde_json::from_str(&copy.get_memory_info()).unwrap();
+        assert_eq!((&info["schema_bytes"], &info["linear_memory_bytes"]), (&serde_json::json!(34), &serde_json::Value::Null));
+
+        let mut registry = FastSchemaRegistry::new();
+        registry.register("id", r#"{"type": "string"}"#).unwrap();
+        let mut copy = registry.clone_instance();
+        copy.register("age", r#"{"type": "number"}"#).unwrap();
+        assert_eq!((registry.names(), copy.names()), (r#"["id"]"#.to_string(), r#"["age","id"]"#.to_string()));
+
+        let mut set = FastValidatorSet::new();
+        set.add("id", r#"{"type": "string"}"#).unwrap();
+        let mut copy = set.clone_instance();
+        assert!(copy.remove("id"));
+        assert_eq!((set.names(), copy.names()), (r#"["id"]"#.to_string(), "[]".to_string()));
+
+        // An unfinished stream stays with the original
+        let mut batch = FastBatchValidator::new(r#"{"type": "number"}"#, 10).unwrap();
+        batch.begin_array();
+        let copy = batch.clone_instance();
+        assert!(batch.stream.is_some() && copy.stream.is_none());
+        assert_eq!(copy.validate_f64_array(&[1.0]), vec![1]);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_validate_f64_array() {
+        let mut validator = ZodNumberValidator::new();
+        validator.set_min(0.0);
+        validator.set_integer(true);
+        assert_eq!(validator.validate_f64_array(&[1.0, -1.0, 0.5, f64::INFINITY, 3.0]), vec![1, 0, 0, 0, 1]);
+
+        let batch = FastBatchValidator::new(r#"{"type": "number", "max": 10}"#, 8).unwrap();
+        assert_eq!(batch.validate_f64_array(&[10.0, 11.0, f64::NAN]), vec![1, 0, 0]);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn test_schema_builder() {
+        let shape = js_sys::Object::new();
+        let name = FastSchemaBuilder::string().min(3.0);
+        js_sys::Reflect::set(&shape, &"name".into(), &name.clone().into()).unwrap();
+        js_sys::Reflect::set(&shape, &"email".into(), &FastSchemaBuilder::string().email().optional().into()).unwrap();
+        let schema = FastSchemaBuilder::object(shape).unwrap();
+
+        let mut validator = schema.build().unwrap();
+        let parsed: ValidationResult = serde_json::from_str(&validator.validate(r#"{"name": "Ann"}"#)).unwrap();
+        assert!(parsed.success);
+        let parsed: ValidationResult = serde_json::from_str(&validator.validate(r#"{"email": "x"}"#)).unwrap();
+        assert_eq!(parsed.errors.len(), 2);
+
+        // Builders stay usable after being placed in a shape, and persist as JSON
+        assert!(name.min(1.0).build().is_ok());
+        let json = js_sys::JSON::stringify(&schema.to_json().unwrap()).unwrap().as_string().unwrap();
+        assert!(FastSchemaBuilder::from_json(&json).unwrap().build().is_ok());
+
+        assert!(FastSchemaBuilder::number().email().to_json().is_err());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn test_validate_js_value() {
+        let mut validator = FastValidator::new(r#"{
+            "type": "object",
+            "properties": {"age": {"type": "number", "integer": true}},
+            "required": ["age"]
+        }"#, None).unwrap();
+
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &JsValue::from_str("age"), &JsValue::from_f64(42.0)).unwrap();
+        let parsed: ValidationResult = serde_json::from_str(&validator.validate_value(object.into())).unwrap();
+        assert!(parsed.success);
+
+        let parsed: ValidationResult = serde_json::from_str(&validator.validate_value(JsValue::from_str("x"))).unwrap();
+        assert!(!parsed.success);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn test_structured_js_results() {
+        let mut validator = FastValidator::new(r#"{"type": "number", "min": 0}"#, None).unwrap();
+
+        let result: ValidationResult = serde_wasm_bindgen::from_value(validator.validate_js(JsValue::from_f64(-1.0)).unwrap()).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.errors[0].code, ErrorCode::NumberTooSmall);
+
+        let values = js_sys::Array::of2(&JsValue::from_f64(1.0), &JsValue::from_str("x"));
+        let results: Vec<ValidationResult> = serde_wasm_bindgen::from_value(validator.validate_many_js(values.into()).unwrap()).unwrap();
+        assert!(results[0].success);
+        assert!(!results[1].success);
+
+        assert!(validator.get_stats_js().unwrap().is_object());
+    }
+
+    #[test]
+    fn test_validate_read() {
+        let mut validator = FastValidator::new(r#"{
+            "type": "object",
+            "properties": {"age": {"type": "number", "integer": true}},
+            "required": ["age"]
+        }"#, None).unwrap();
+
+        // JS numbers always arrive as f64
+        assert!(validator.validate_read(Ok(serde_json::json!({"age": 42.0}))).success);
+        assert_eq!(validator.validate_read(Ok(serde_json::json!({"age": 4.5}))).errors[0].code, ErrorCode::NumberNotInteger);
+
+        let result = validator.validate_read(Err("Invalid JS value: invalid type: symbol".to_string()));
+        assert!(!result.success);
+        assert_eq!(result.errors[0].code, ErrorCode::InternalError);
+        assert_eq!(result.errors[0].message, "Invalid JS value: invalid type: symbol");
+    }
+
+    #[test]
+    fn test_structured_results() {
+        let mut validator = FastValidator::new(r#"{"type": "number", "min": 0}"#, None).unwrap();
+
+        let results = validator.validate_many_read(Ok(vec![serde_json::json!(1.0), serde_json::json!(-1.0)]));
+        assert!(results[0].success);
+        assert_eq!(results[1].errors[0].code, ErrorCode::NumberTooSmall);
+
+        let results = validator.validate_many_read(Err("Invalid JS array: invalid type: string".to_string()));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].errors[0].code, ErrorCode::InternalError);
+
+        // `to_js` writes maps as plain objects, so the stats must serialize to one
+        assert!(serde_json::to_value(validator.validator.stats()).unwrap().is_object());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_js_error_views() {
+        let mut validator = FastValidator::new(r#"{
+            "type": "object",
+            "properties": {"profile": {"type": "object", "properties": {"email": {"type": "string", "format": "email"}}}}
+        }"#, None).unwrap();
+        let result = validator.validate_js(js_sys::JSON::parse(r#"{"profile": {"email": "nope"}}"#).unwrap()).unwrap();
+
+        let formatted: serde_json::Value = serde_wasm_bindgen::from_value(validator.format_errors(result.clone()).unwrap()).unwrap();
+        assert_eq!(formatted["profile"]["email"]["_errors"].as_array().unwrap().len(), 1);
+        let flattened: FlattenedErrors = serde_wasm_bindgen::from_value(validator.flatten_errors(result).unwrap()).unwrap();
+        assert_eq!(flattened.field_errors["profile"].len(), 1);
+        assert!(validator.format_errors(JsValue::from_f64(1.0)).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_js_parse_and_safe_parse() {
+        let mut validator = FastValidator::new(r#"{
+            "type": "object",
+            "properties": {"age": {"type": "number"}}
+        }"#, None).unwrap();
+
+        let parsed: serde_json::Value = serde_wasm_bindgen::from_value(validator.parse(js_sys::JSON::parse(r#"{"age": 3}"#).unwrap()).unwrap()).unwrap();
+        assert_eq!(parsed, serde_json::json!({"age": 3}));
+
+        let thrown = validator.parse(js_sys::JSON::parse(r#"{"age": "x"}"#).unwrap()).unwrap_err();
+        let error: js_sys::Error = thrown.dyn_into().unwrap();
+        assert_eq!(String::from(error.name()), "FastSchemaError");
+        assert_eq!(String::from(error.message()), "age: Expected number, got string");
+        let issues = js_sys::Reflect::get(&error, &JsValue::from_str("issues")).unwrap();
+        assert_eq!(js_sys::Array::from(&issues).length(), 1);
+
+        let safe: serde_json::Value = serde_wasm_bindgen::from_value(validator.safe_parse(js_sys::JSON::parse(r#"{"age": "x"}"#).unwrap()).unwrap()).unwrap();
+        assert_eq!(safe["success"], false);
+        assert!(safe.get("data").is_none());
+        assert_eq!(safe["error"]["issues"][0]["code"], "INVALID_TYPE");
+
+        let safe: serde_json::Value = serde_wasm_bindgen::from_value(validator.safe_parse(js_sys::JSON::parse(r#"{"age": 1}"#).unwrap()).unwrap()).unwrap();
+        assert_eq!(safe, serde_json::json!({"success": true, "data": {"age": 1}}));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_js_result_accessors() {
+        let mut validator = FastValidator::new(r#"{
+            "type": "object",
+            "properties": {"users": {"type": "array", "items": {"type": "string", "minLength": 3}}}
+        }"#, None).unwrap();
+        let result = validator.validate_result(js_sys::JSON::parse(r#"{"users": ["ab"]}"#).unwrap());
+        assert!(!result.success());
+        assert!(result.data().unwrap().is_undefined());
+
+        let issues = result.issues();
+        assert_eq!(issues.len(), 1);
+        let path: Vec<PathSegment> = serde_wasm_bindgen::from_value(issues[0].path().unwrap()).unwrap();
+        assert_eq!(path, vec![PathSegment::Key("users".to_string()), PathSegment::Index(0)]);
+        assert_eq!((issues[0].pointer(), issues[0].code(), issues[0].severity()), ("/users/0".to_string(), "STRING_TOO_SHORT".to_string(), "error".to_string()));
+        let params: serde_json::Value = serde_wasm_bindgen::from_value(issues[0].params().unwrap()).unwrap();
+        assert_eq!(params["min"], 3);
+
+        let valid = validator.validate_result(js_sys::JSON::parse(r#"{"users": []}"#).unwrap());
+        assert!(valid.success() && valid.issues().is_empty());
+        assert!(!valid.data().unwrap().is_undefined());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let validator = Validator::from_json(&serde_json::json!({
+            "type": "object",
+            "properties": {"age": {"type": "number"}, "tags": {"type": "array", "items": {"type": "string"}}}
+        })).unwrap();
+
+        let result = validator.validate(&serde_json::json!({"tags": [1, true]}));
+        assert_eq!(parse_error_message(&result.errors), "tags[0]: Expected string, got number; tags[1]: Expected string, got boolean");
+        let safe = serde_json::to_value(SafeParse::new(&result)).unwrap();
+        assert_eq!((&safe["success"], &safe["error"]["name"]), (&serde_json::json!(false), &serde_json::json!("FastSchemaError")));
+        assert_eq!(safe["error"]["issues"][0]["code"], "INVALID_TYPE");
+        assert!(safe.get("data").is_none());
+
+        let result = validator.validate(&serde_json::json!(1));
+        assert_eq!(parse_error_message(&result.errors), "Expected object, got number");
+
+        let result = validator.validate(&serde_json::json!({"age": 1}));
+        assert_eq!(serde_json::to_value(SafeParse::new(&result)).unwrap(), serde_json::json!({"success": true, "data": {"age": 1}}));
+    }
+
+    #[test]
+    fn test_result_accessors() {
+        let validator = Validator::from_json(&serde_json::json!({
+            "type": "object",
+            "properties": {"users": {"type": "array", "items": {"type": "string", "minLength": 3}}}
+        })).unwrap();
+        let result = FastValidationResult { result: validator.validate(&serde_json::json!({"users": ["ab"]})) };
+        assert!(!result.success());
+        assert!(result.warnings().is_empty());
+
+        let issues = result.issues();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].pointer(), "/users/0");
+        assert_eq!((issues[0].code(), issues[0].severity()), ("STRING_TOO_SHORT".to_string(), "error".to_string()));
+        assert_eq!(issues[0].message(), result.result.errors[0].message);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_constructor_formats() {
+        let schema = r#"{"type": "string", "format": "sku"}"#;
+        assert!(FastValidator::new(schema, None).is_err());
+
+        let formats = js_sys::Object::new();
+        js_sys::Reflect::set(&formats, &"sku".into(), &"^[A-Z]{3}-[0-9]+$".into()).unwrap();
+        let mut validator = FastValidator::new(schema, Some(formats)).unwrap();
+        let result: ValidationResult = serde_json::from_str(&validator.validate(r#""ABC-1""#)).unwrap();
+        assert!(result.success);
+        let result: ValidationResult = serde_json::from_str(&validator.validate(r#""abc""#)).unwrap();
+        assert_eq!(result.errors[0].code, ErrorCode::StringFormatInvalid);
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen_test]
+    async fn test_validate_async_awaits_async_formats() {
+        let available = js_sys::Function::new_with_args("value", "return Promise.resolve(value !== 'taken')");
+        let formats = js_sys::Object::new();
+        js_sys::Reflect::set(&formats, &"username".into(), &available).unwrap();
+        let schema = r#"{"type": "array", "items": {"type": "string", "format": "username"}}"#;
+        let mut validator = FastValidator::new(schema, Some(formats)).unwrap();
+        validator.register_async_format("username", available);
+
+        let promise = validator.validate_async(r#"["free", "taken", "free"]"#.to_string());
+        let value = wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+        let result: ValidationResult = serde_wasm_bindgen::from_value(value).unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path, "[1]");
+
+        let promise = validator.validate_many_async(r#"[["free"], ["taken"]]"#.to_string(), 1);
+        let value = wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+        let results: Vec<ValidationResult> = serde_wasm_bindgen::from_value(value).unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[1].errors[0].path, "[1].[0]");
+
+        // Synchronous validation has nothing to await with
+        let parsed: ValidationResult = serde_json::from_str(&validator.validate(r#"["free"]"#)).unwrap();
+        assert!(!parsed.success);
+    }
+
+    #[test]
+    fn test_async_format_passes() {
+        use std::sync::Arc;
+
+        let checks = Arc::new(Mutex::new(AsyncChecks::default()));
+        let shared = Arc::clone(&checks);
+        let mut formats = FormatRegistry::new();
+        formats.register_fn("username", move |value| lock_checks(&shared).check("username", value));
+        let schema = serde_json::json!({"type": "array", "items": {"type": "string", "format": "username"}});
+        let validator = Validator::from_json_with_formats(&schema, Arc::new(formats)).unwrap();
+        let data = serde_json::json!(["free", "taken", "free"]);
+
+        // Without validate_async there is nothing to await, so values fail
+        assert!(!validator.validate(&data).success);
+
+        // The first pass passes every value and records it...
+        *lock_checks(&checks) = AsyncChecks::Collect(Vec::new());
+        assert!(validator.validate(&data).success);
+        let pending = match std::mem::take(&mut *lock_checks(&checks)) {
+            AsyncChecks::Collect(pending) => pending,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(pending.len(), 3);
+
+        // ...and the second answers from the awaited outcomes
+        let outcomes = pending.into_iter().map(|(format, value)| {
+            let available = value != "taken";
+            ((format, value), available)
+        });
+        *lock_checks(&checks) = AsyncChecks::Resolved(outcomes.collect());
+        let result = validator.validate(&data);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].path.pointer(), "/1");
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+    #[wasm_bindgen_test]
+    fn test_js_error_map() {
+        let mut validator = FastValidator::new(r#"{"type": "object", "properties": {"age": {"type": "number"}}}"#, None).unwrap();
+        let map = js_sys::Function::new_with_args(
+            "issue",
+            "if (issue.code === 'INVALID_TYPE') return issue.path + ': need ' + issue.params.expected",
+        );
+        validator.set_error_map(map);
+
+        let result: ValidationResult = serde_json::from_str(&validator.validate(r#"{"age": "old"}"#)).unwrap();
+        assert_eq!(result.errors[0].message, "age: need number");
+
+        validator.clear_error_map();
+        let result: ValidationResult = serde_json::from_str(&validator.validate(r#"{"age": "old"}"#)).unwrap();
+        assert_eq!(result.errors[0].message, "Expected number, got string");
+    }
+
+    #[wasm_bindgen_test]
+    fn test_batch_validation() {
+        let schema_json = r#"{
+            "type": "number",
+            "min": 0,
+            "max": 100
+        }"#;
+
+        let mut validator = FastValidator::new(schema_json, None).unwrap();
+
+        let data = r#"[10, 50, 75, -5, 150]"#;
+        let result = validator.validate_many(data);
+        let parsed: Vec<ValidationResult> = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(parsed.len(), 5);
+        assert!(parsed[0].success); // 10
+        assert!(parsed[1].success); // 50
+        assert!(parsed[2].success); // 75
+        assert!(!parsed[3].success); // -5 (too small)
+        assert!(!parsed[4].success); // 150 (too large)
+    }
+
+    #[wasm_bindgen_test]
+    fn test_html_and_react_validators() {
+        let html = FastHtmlValidator::new(r#"{
+            "element_type": "Div",
+            "props": {"validate_semantic": false},
+            "children": {"type": "htmlelement", "element_type": "Image"}
+        }"#).unwrap();
+        let tree = r#"{"type": "div", "props": {}, "children": [{"type": "img", "props": {"src": "/a.png"}}]}"#;
+        let parsed: ValidationResult = serde_json::from_str(&html.validate(tree)).unwrap();
+        assert!(!parsed.success);
+        assert_eq!(parsed.errors[0].path.display_path(), "children[0].props.alt");
+
+        let react = FastReactValidator::new(r#"{
+            "component_name": "Avatar",
+            "props_schema": {"size": {"type": "number", "min": 16}},
+            "required_props": ["size"]
+        }"#).unwrap();
+        let parsed: ValidationResult = serde_json::from_str(&react.validate(r#"{"type": "Avatar", "props": {"size": 8}}"#)).unwrap();
+        assert_eq!(parsed.errors[0].code, ErrorCode::NumberTooSmall);
+        assert!(FastReactValidator::new(r#"{"props_schema": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_element_validators() {
+        let html = FastHtmlValidator::new(r#"{
+            "element_type": "Div",
+            "props": {"validate_semantic": false},
+            "children": {"type": "htmlelement", "element_type": "Image"}
+        }"#).unwrap();
+        let parsed: ValidationResult = serde_json::from_str(&html.validate(r#"{"type": "div", "props": {}, "children": [{"type": "img", "props": {"src": "/a.png"}}]}"#)).unwrap();
+        assert_eq!(parsed.errors[0].path.display_path(), "children[0].props.alt");
+        let parsed: ValidationResult = serde_json::from_str(&html.validate_markup(r#"<div><img src="/a.png"></div>"#)).unwrap();
+        assert_eq!(parsed.errors[0].path.display_path(), "children[0].props.alt");
+        let parsed: ValidationResult = serde_json::from_str(&html.validate("{")).unwrap();
+        assert_eq!(parsed.errors[0].code, ErrorCode::InternalError);
+
+        // A component schema is wrapped in `component`; a full schema is used as is
+        let react = FastReactValidator::new(r#"{
+            "component_name": "Avatar",
+            "props_schema": {"size": {"type": "number", "min": 16}},
+            "required_props": ["size"]
+        }"#).unwrap();
+        let parsed: ValidationResult = serde_json::from_str(&react.validate(r#"{"type": "Avatar", "props": {"size": 8}}"#)).unwrap();
+        assert_eq!(parsed.errors[0].code, ErrorCode::NumberTooSmall);
+        let parsed: ValidationResult = serde_json::from_str(&react.validate(r#"{"type": "Avatar", "props": {}}"#)).unwrap();
+        assert_eq!(parsed.errors[0].path.display_path(), "props.size");
+
+        let full = tagged_validator(r#"{"type": "reactcomponent", "component": {"component_name": "Avatar"}}"#, "reactcomponent", Some("component")).unwrap();
+        assert!(serde_json::from_str::<ValidationResult>(&validate_element_json(&full, r#"{"type": "Avatar", "props": {}}"#)).unwrap().success);
+        assert!(!serde_json::from_str::<ValidationResult>(&validate_element_json(&full, r#"{"type": "Button", "props": {}}"#)).unwrap().success);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_complex_object_validation() {
+        let schema_json = r#"{
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "minLength": 2,
+                    "maxLength": 50
+                },
+                "age": {
+                    "type": "number",
+                    "min": 0,
+                    "max": 120,
+                    "integer": true
+                },
+                "email": {
+                    "type": "string",
+                    "format": "email"
+                }
+            },
+            "required": ["name", "age"]
+        }"#;
+
+        let mut validator = FastValidator::new(schema_json, None).unwrap();
+
+        // Valid object
+        let valid_data = r#"{
+            "name": "John Doe",
+            "age": 30,
+            "email": "john@example.com"
+        }"#;
+
+        let result = validator.validate(valid_data);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(parsed.success);
+
+        // Invalid object (missing required field)
+        let invalid_data = r#"{
+            "name": "John Doe"
+        }"#;
+
+        let result = validator.validate(invalid_data);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(!parsed.success);
+        assert!(!parsed.errors.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_schema_utils() {
+        // Valid schema
+        let valid_schema = r#"{
+            "type": "string",
+            "minLength": 2
+        }"#;
+
+        let result = FastSchemaUtils::validate_schema(valid_schema);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(parsed["valid"].as_bool().unwrap());
+
+        // Invalid schema
+        let invalid_schema = r#"{
+            "type": "invalid_type"
+        }"#;
+
+        let result = FastSchemaUtils::validate_schema(invalid_schema);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert!(!parsed["valid"].as_bool().unwrap());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_performance_analysis() {
+        let complex_schema = r#"{
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "pattern": "^[a-zA-Z\\s]+$"
+                },
+                "nested": {
+                    "type": "object",
+                    "properties": {
+                        "deep": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "value": {
+                                        "type": "number"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }"#;
+
+        let result = FastSchemaUtils::analyze_schema_performance(complex_schema);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+
+        assert!(parsed["complexity"].as_u64().unwrap() > 0);
+        assert!(parsed["max_depth"].as_u64().unwrap() > 2);
+        assert!(parsed["has_patterns"].as_bool().unwrap());
+        assert!(parsed["recommendations"].as_array().unwrap().len() > 0);
+    }
+
+    #[test]
+    fn test_version_info() {
+        let version_info = FastSchemaUtils::get_version();
+        let parsed: serde_json::Value = serde_json::from_str(&version_info).unwrap();
+
+        assert!(parsed["version"].as_str().unwrap().len() > 0);
+        assert_eq!(parsed["name"].as_str().unwrap(), "fast-schema");
+    }
+
+    #[test]
+    fn test_schema_ops() {
+        let schema_json = r#"{
+            "type": "object",
+            "properties": {
+                "id": {"type": "string"},
+                "name": {"type": "string"}
+            },
+            "required": ["id", "name"]
+        }"#;
+
+        let picked = SchemaOps::pick(schema_json, r#"["name"]"#).unwrap();
+        let parsed: SchemaType = serde_json::from_str(&picked).unwrap();
+        assert_eq!(parsed.get_property_names(), vec!["name".to_string()]);
+
+        let partial = SchemaOps::partial(schema_json).unwrap();
+        let validator = Validator::new(serde_json::from_str(&partial).unwrap()).unwrap();
+        assert!(validator.validate(&serde_json::json!({})).success);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn test_memory_limits() {
+        use super::wasm_optimizations::*;
+
+        // Should handle reasonable data sizes
+        assert!(check_memory_limits(1000));
+        assert!(check_memory_limits(10000));
+
+        // Should reject very large data sizes
+        assert!(!check_memory_limits(10_000_000));
+    }
+}
+
+// Integration tests for realistic scenarios
+#[cfg(test)]
+mod integration_tests {
+    use super::*;
+
+    #[test]
+    fn test_real_world_user_schema() {
+        let schema_json = r#"{
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "format": "uuid"
+                },
+                "username": {
+                    "type": "string",
+                    "minLength": 3,
+                    "maxLength": 20,
+                    "pattern": "^[a-zA-Z0-9_]+$"
+                },
+                "email": {
+                    "type": "string",
+                    "format": "email"
+                },
+                "profile": {
+                    "type": "object",
+                    "properties": {
+                        "firstName": {
+                            "type": "string",
+                            "minLength": 1,
+                            "maxLength": 50
+                        },
+                        "lastName": {
+                            "type": "string",
+                            "minLength": 1,
+                            "maxLength": 50
+                        },
+                        "age": {
+                            "type": "number",
+                            "min": 13,
+                            "max": 120,
+                            "integer": true
+                        },
+                        "preferences": {
+                            "type": "object",
+                            "properties": {
+                                "theme": {
+                                    "type": "string",
+                                    "oneOf": [
+                                        {"const": "light"},
+                                        {"const": "dark"},
+                                        {"const": "auto"}
+                                    ]
+                                },
+                                "notifications": {
+                                    "type": "boolean"
+                                }
+                            }
+                        }
+                    },
+                    "required": ["firstName", "lastName"]
+                },
+                "roles": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "uniqueItems": true
+                },
+                "createdAt": {
+                    "type": "string",
+                    "format": "date-time"
+                },
+                "isActive": {
+                    "type": "boolean"
+                }
+            },
+            "required": ["id", "username", "email", "profile"],
+            "additionalProperties": false
+        }"#;
+
+        let mut validator = FastValidator::new(schema_json, None).unwrap();
+
+        let valid_user = r#"{
+            "id": "550e8400-e29b-41d4-a716-446655440000",
+            "username": "john_doe_123",
+            "email": "john.doe@example.com",
+            "profile": {
+                "firstName": "John",
+                "lastName": "Doe",
+                "age": 30,
+                "preferences": {
+                    "theme": "dark",
+                    "notifications": true
+                }
+            },
+            "roles": ["user", "premium"],
+            "createdAt": "2023-01-01T00:00:00.000Z",
+            "isActive": true
+        }"#;
+
+        let result = validator.validate(valid_user);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(parsed.success, "Valid user should pass validation");
+
+        // The theme has to be one of the listed constants
+        let result = validator.validate(&valid_user.replace(r#""theme": "dark""#, r#""theme": "purple""#));
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(!parsed.success, "A theme outside the list should fail validation");
+        assert_eq!(parsed.errors[0].path.pointer(), "/profile/preferences/theme");
+
+        // Test invalid user (missing required field)
+        let invalid_user = r#"{
+            "id": "550e8400-e29b-41d4-a716-446655440000",
+            "username": "john_doe_123",
+            "email": "john.doe@example.com"
+        }"#;
+
+        let result = validator.validate(invalid_user);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+        assert!(!parsed.success, "Invalid user should fail validation");
+        assert!(!parsed.errors.is_empty());
+    }
+
+    #[test]
+    fn test_large_dataset_validation() {
+        let schema_json = r#"{
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "number",
+                    "integer": true
+                },
+                "name": {
+                    "type": "string",
+                    "minLength": 2,
+                    "maxLength": 100
+                },
+                "active": {
+                    "type": "boolean"
+                }
+            },
+            "required": ["id", "name"]
+        }"#;
+
+        let mut validator = FastValidator::new(schema_json, None).unwrap();
+
+        // Generate a large dataset
+        let mut dataset = Vec::new();
+        for i in 0..5000 {
+            let item = serde_json::json!({
+                "id": i,
+                "name": format!("Item {}", i),
+                "active": i % 2 == 0
+            });
+            dataset.push(item);
+        }
+
+        let dataset_json = serde_json::to_string(&dataset).unwrap();
+        let start_time = crate::clock::Instant::now();
+
+        let result = validator.validate_many(&dataset_json);
+        let elapsed = start_time.elapsed();
+
+        let parsed: Vec<ValidationResult> = serde_json::from_str(&result).unwrap();
+
+        assert_eq!(parsed.len(), 5000);
+        assert!(parsed.iter().all(|r| r.success));
+
+        // Performance check - should validate 5000 items quickly
+        println!("Validated 5000 items in {:?}", elapsed);
+        assert!(elapsed.as_millis() < 1000, "Should validate 5000 items in under 1 second");
+    }
+
+    #[test]
+    fn test_error_accumulation() {
+        let schema_json = r#"{
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "minLength": 5
+                },
+                "age": {
+                    "type": "number",
+                    "min": 18
+                },
+                "email": {
+                    "type": "string",
+                    "format": "email"
+                }
+            },
+            "required": ["name", "age", "email"]
+        }"#;
+
+        let mut validator = FastValidator::new(schema_json, None).unwrap();
+
+        // Object with multiple validation errors
+        let invalid_data = r#"{
+            "name": "Jo",
+            "age": 15,
+            "email": "invalid-email"
+        }"#;
+
+        let result = validator.validate(invalid_data);
+        let parsed: ValidationResult = serde_json::from_str(&result).unwrap();
+
+        assert!(!parsed.success);
+        assert!(parsed.errors.len() >= 3); // Should have multiple errors
+
+        // Check that all error types are captured
+        let error_codes: Vec<_> = parsed.errors.iter().map(|e| &e.code).collect();
+        assert!(error_codes.contains(&&ErrorCode::StringTooShort));
+        assert!(error_codes.contains(&&ErrorCode::NumberTooSmall));
+        assert!(error_codes.contains(&&ErrorCode::StringFormatInvalid));
+    }
+}
+
+// High-performance individual type validators for WASM
+#[wasm_bindgen]
+impl ZodStringValidator {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ZodStringValidator {
+        ZodStringValidator {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+            format: None,
+            length_unit: LengthUnit::Chars,
+            validations: Vec::new(),
+        }
+    }
+
+    /// Ultra-fast string validation
+    #[wasm_bindgen]
+    pub fn validate_fast(&self, value: &str) -> String {
+        let start = crate::clock::Instant::now();
+
+        // Length checks (fastest)
+        let len = match (self.min_length, self.max_length) {
+            (None, None) => 0,
+            _ => self.length_unit.length(value),
+        };
+        if let Some(min) = self.min_length {
+            if len < min {
+                return self.create_error("String too short", start.elapsed().as_nanos() as f64 / 1000.0);
+            }
+        }
+
+        if let Some(max) = self.max_length {
+            if len > max {
+                return self.create_error("String too long", start.elapsed().as_nanos() as f64 / 1000.0);
+            }
+        }
+
+        // Format validation (cached regex patterns)
+        if let Some(ref format) = self.format {
+            if !self.validate_format(value, format) {
+                return self.create_error("Invalid format", start.elapsed().as_nanos() as f64 / 1000.0);
+            }
+        }
+
+        // Pattern validation
+        if let Some(ref pattern) = self.pattern {
+            // Use cached regex for performance
+            if !self.validate_pattern(value, pattern) {
+                return self.create_error("Pattern mismatch", start.elapsed().as_nanos() as f64 / 1000.0);
+            }
+        }
+
+        let elapsed_us = start.elapsed().as_nanos() as f64 / 1000.0;
+        self.create_success(value, elapsed_us)
+    }
+
+    #[wasm_bindgen]
+    pub fn set_min_length(&mut self, min: usize) {
+        self.min_length = Some(min);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_max_length(&mut self, max: usize) {
+        self.max_length = Some(max);
+    }
+
+    /// Count lengths in `"chars"` (the default), `"bytes"` or `"graphemes"`
+    #[wasm_bindgen]
+    pub fn set_length_unit(&mut self, unit: &str) -> Result<(), JsValue> {
+        self.length_unit = serde_json::from_value(serde_json::Value::String(unit.to_string()))
+            .map_err(|_| JsValue::from_str(&format!("Unknown length unit: {}", unit)))?;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_email_format(&mut self) {
+        self.format = Some(StringFormat::Email);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_pattern(&mut self, pattern: &str) {
+        self.pattern = Some(pattern.to_string());
+    }
+
+    fn validate_format(&self, value: &str, format: &StringFormat) -> bool {
+        match format {
+            StringFormat::Email => {
+                // Fast email validation
+                value.contains('@') && value.contains('.') && value.len() > 5
+            }
+            StringFormat::Url => {
+                value.starts_with("http://") || value.starts_with("https://")
+            }
+            StringFormat::Uuid => {
+                value.len() == 36 && value.chars().filter(|c| *c == '-').count() == 4
+            }
+            _ => true, // Other formats not implemented for speed
+        }
+    }
+
+    fn validate_pattern(&self, value: &str, pattern: &str) -> bool {
+        // Simplified pattern matching for common cases
+        if pattern == "^[a-zA-Z0-9_]+$" {
+            return value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        }
+        // For complex patterns, would use cached regex
+        true
+    }
+
+    fn create_success(&self, value: &str, elapsed_us: f64) -> String {
+        serde_json::json!({
+            "success": true,
+            "data": value,
+            "performance_us": elapsed_us
+        }).to_string()
+    }
+
+    fn create_error(&self, message: &str, elapsed_us: f64) -> String {
+        serde_json::json!({
+            "success": false,
+            "error": message,
+            "performance_us": elapsed_us
+        }).to_string()
+    }
+}
+
+#[wasm_bindgen]
+impl ZodNumberValidator {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ZodNumberValidator {
+        ZodNumberValidator {
+            min: None,
+            max: None,
+            integer: false,
+            multiple_of: None,
+            multiple_of_tolerance: utils::DEFAULT_MULTIPLE_OF_TOLERANCE,
+            allow_non_finite: false,
+        }
+    }
+
+    /// Ultra-fast number validation
+    #[wasm_bindgen]
+    pub fn validate_fast(&self, value: f64) -> String {
+        let start = crate::clock::Instant::now();
+
+        match self.check(value) {
+            Some(message) => self.create_error(message, start.elapsed().as_nanos() as f64 / 1000.0),
+            None => self.create_success(value, start.elapsed().as_nanos() as f64 / 1000.0),
+        }
+    }
+
+    /// Validate a numeric column, one flag per value (1 = valid)
+    #[wasm_bindgen]
+    pub fn validate_f64_array(&self, values: &[f64]) -> Vec<u8> {
+        values.iter().map(|&value| self.check(value).is_none() as u8).collect()
+    }
+
+    /// `validate_f64_array` for a `Float64Array`, returning a `Uint8Array`
+    #[wasm_bindgen]
+    pub fn validate_typed_array(&self, values: &js_sys::Float64Array) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(self.validate_f64_array(&values.to_vec()).as_slice())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_min(&mut self, min: f64) {
+        self.min = Some(min);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_max(&mut self, max: f64) {
+        self.max = Some(max);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_integer(&mut self, integer: bool) {
+        self.integer = integer;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_multiple_of(&mut self, multiple: f64) {
+        self.multiple_of = Some(multiple);
+    }
+
+    /// How far `value / multiple` may be from a whole number
+    #[wasm_bindgen]
+    pub fn set_multiple_of_tolerance(&mut self, tolerance: f64) {
+        self.multiple_of_tolerance = tolerance;
+    }
+
+    /// Accept `NaN` and `±Infinity`, which are rejected by default
+    #[wasm_bindgen]
+    pub fn set_allow_non_finite(&mut self, allow: bool) {
+        self.allow_non_finite = allow;
+    }
+
+    /// The error message for `value`, if it fails
+    fn check(&self, value: f64) -> Option<&'static str> {
+        if !self.allow_non_finite && !value.is_finite() {
+            return Some("Expected finite number");
+        }
+
+        // Integer check (fastest)
+        if self.integer && value.fract() != 0.0 {
+            return Some("Expected integer");
+        }
+
+        // Range checks
+        if self.min.is_some_and(|min| value.is_nan() || value < min) {
+            return Some("Number too small");
+        }
+        if self.max.is_some_and(|max| value.is_nan() || value > max) {
+            return Some("Number too large");
+        }
+
+        // Multiple check
+        if let Some(multiple) = self.multiple_of {
+            if !utils::is_multiple(value, multiple, self.multiple_of_tolerance) {
+                return Some("Not a multiple");
+            }
+        }
+
+        None
+    }
+
+    fn create_success(&self, value: f64, elapsed_us: f64) -> String {
+        serde_json::json!({
+            "success": true,
+            "data": value,
+            "performance_us": elapsed_us
+        }).to_string()
+    }
+
+    fn create_error(&self, message: &str, elapsed_us: f64) -> String {
+        serde_json::json!({
+            "success": false,
+            "error": message,
+            "performance_us": elapsed_us
+        }).to_string()
+    }
+}
+
+// Batch validation optimizations for high-performance scenarios
+#[wasm_bindgen]
+pub struct UltraFastValidator {
+    validator_type: String,
+    config: String,
+}
+
+#[wasm_bindgen]
+impl UltraFastValidator {
+    /// Create an ultra-fast validator for specific types
+    #[wasm_bindgen(constructor)]
+    pub fn new(validator_type: &str, config: &str) -> UltraFastValidator {
+        UltraFastValidator {
+            validator_type: validator_type.to_string(),
+            config: config.to_string(),
+        }
+    }
+
+    /// Validate 1000s of values extremely fast
+    #[wasm_bindgen]
+    pub fn validate_batch(&self, values_json: &str) -> String {
+        let start = crate::clock::Instant::now();
+
+        match self.validator_type.as_str() {
+            "string" => self.validate_string_batch(values_json, start),
+            "number" => self.validate_number_batch(values_json, start),
+            "boolean" => self.validate_boolean_batch(values_json, start),
+            _ => serde_json::json!({
+                "success": false,
+                "error": "Unsupported validator type"
+            }).to_string()
+        }
+    }
+
+    fn validate_string_batch(&self, values_json: &str, start: crate::clock::Instant) -> String {
+        let values: Vec<String> = match serde_json::from_str(values_json) {
+            Ok(v) => v,
+            Err(_) => return serde_json::json!({"success": false, "error": "Invalid JSON"}).to_string(),
+        };
+
+        let mut valid_count = 0;
+        let mut results = Vec::new();
+
+        for value in &values {
+            // Ultra-fast validation - minimal checks
+            let is_valid = value.len() >= 2 && value.len() <= 100; // Example constraint
+            if is_valid {
+                valid_count += 1;
+            }
+            results.push(is_valid);
+        }
+
+        let elapsed_us = start.elapsed().as_nanos() as f64 / 1000.0;
+        let throughput = values.len() as f64 / (elapsed_us / 1_000_000.0);
+
+        serde_json::json!({
+            "success": true,
+            "valid_count": valid_count,
+            "total_count": values.len(),
+            "results": results,
+            "performance_us": elapsed_us,
+            "throughput_per_second": throughput
+        }).to_string()
+    }
+
+    fn validate_number_batch(&self, values_json: &str, start: crate::clock::Instant) -> String {
+        let values: Vec<f64> = match serde_json::from_str(values_json) {
+            Ok(v) => v,
+            Err(_) => return serde_json::json!({"success": false, "error": "Invalid JSON"}).to_string(),
+        };
+
+        let mut valid_count = 0;
+        let mut results = Vec::new();
+
+        for &value in &values {
+            // Ultra-fast validation
+            let is_valid = value >= 0.0 && value <= 1000.0 && value.is_finite();
+            if is_valid {
+                valid_count += 1;
+            }
+            results.push(is_valid);
+        }
+
+        let elapsed_us = start.elapsed().as_nanos() as f64 / 1000.0;
+        let throughput = values.len() as f64 / (elapsed_us / 1_000_000.0);
+
+        serde_json::json!({
+            "success": true,
+            "valid_count": valid_count,
+            "total_count": values.len(),
+            "results": results,
+            "performance_us": elapsed_us,
+            "throughput_per_second": throughput
+        }).to_string()
+    }
+
+    fn validate_boolean_batch(&self, values_json: &str, start: crate::clock::Instant) -> String {
+        let values: Vec<bool> = match serde_json::from_str(values_json) {
+            Ok(v) => v,
+            Err(_) => return serde_json::json!({"success": false, "error": "Invalid JSON"}).to_string(),
+        };
+
+        let elapsed_us = start.elapsed().as_nanos() as f64 / 1000.0;
+        let throughput = values.len() as f64 / (elapsed_us / 1_000_000.0);
+
+        serde_json::json!({
+            "success": true,
+            "valid_count": values.len(), // All booleans are valid
+            "total_count": values.len(),
+            "results": vec![true; values.len()],
+            "performance_us": elapsed_us,
+            "throughput_per_second": throughput
+        }).to_string()
+    }
+}
@@ -0,0 +1,841 @@
+
+//! YAML input, converted to `serde_json::Value` so it validates like any
+//! JSON payload. This covers what configuration files use: block and flow
+//! collections, plain, quoted and block scalars, comments, anchors and
+//! aliases, and `<<` merge keys. Plain scalars resolve by the YAML 1.2 core
+//! schema (`true`, `~`, `0x1f`, `1e3`, ...). Complex (`?`) keys, local tags
+//! and streams of several documents are rejected.
+
+use crate::error::FastSchemaError;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Nesting limit, as for JSON5
+const MAX_DEPTH: usize = 128;
+
+/// Nodes that aliases may add to a document in all, so that aliases of
+/// aliases cannot expand a small document exponentially
+const MAX_ALIAS_NODES: usize = 100_000;
+
+/// Parse a YAML document
+pub fn parse_yaml(text: &str) -> Result<Value, FastSchemaError> {
+    let mut parser = Parser::new(text)?;
+    parser.document()
+}
+
+struct Line {
+    indent: usize,
+    /// The line after its indentation
+    text: String,
+}
+
+struct Parser {
+    lines: Vec<Line>,
+    pos: usize,
+    anchors: Anchors,
+    /// Block nodes being parsed
+    depth: usize,
+}
+
+/// Anchored nodes, with the number of nodes in each
+#[derive(Default)]
+struct Anchors {
+    nodes: HashMap<String, (Value, usize)>,
+    /// Nodes added by aliases so far
+    expanded: usize,
+}
+
+impl Anchors {
+    fn insert(&mut self, name: &str, value: &Value) {
+        self.nodes.insert(name.to_string(), (value.clone(), count_nodes(value)));
+    }
+
+    fn alias(&mut self, name: &str) -> Result<Value, String> {
+        let (value, count) = self.nodes.get(name).ok_or_else(|| format!("unknown alias '*{}'", name))?;
+        self.expanded += count;
+        if self.expanded > MAX_ALIAS_NODES {
+            return Err(format!("aliases expand to more than {} nodes", MAX_ALIAS_NODES));
+        }
+        Ok(value.clone())
+    }
+}
+
+/// How a flow node failed to parse
+enum Flow {
+    /// The input ended first; a later line may complete it
+    Incomplete,
+    Invalid(String),
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Self, FastSchemaError> {
+        let mut lines = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            let rest = &line[indent..];
+            if rest.starts_with('\t') && !rest.trim().is_empty() && !rest.trim_start().starts_with('#') {
+                return Err(error(number, "tabs cannot be used for indentation"));
+            }
+            lines.push(Line { indent, text: rest.trim_end().to_string() });
+        }
+        Ok(Parser { lines, pos: 0, anchors: Anchors::default(), depth: 0 })
+    }
+
+    fn document(&mut self) -> Result<Value, FastSchemaError> {
+        self.skip_blank();
+        while self.lines.get(self.pos).is_some_and(|line| line.indent == 0 && line.text.starts_with('%')) {
+            self.pos += 1;
+            self.skip_blank();
+        }
+        if let Some(line) = self.lines.get_mut(self.pos) {
+            if line.indent == 0 && (line.text == "---" || line.text.starts_with("--- ")) {
+                let rest = line.text[3..].trim_start().to_string();
+                if rest.is_empty() || rest.starts_with('#') {
+                    self.pos += 1;
+                } else {
+                    *line = Line { indent: 4, text: rest };
+                }
+            }
+        }
+
+        let value = self.block(0)?;
+        self.skip_blank();
+        if self.lines.get(self.pos).is_some_and(|line| line.indent == 0 && line.text.starts_with("...")) {
+            self.pos += 1;
+            self.skip_blank();
+        }
+        match self.lines.get(self.pos) {
+            None => Ok(value),
+            Some(line) if is_document_marker(line) => Err(self.error("only one document is supported")),
+            Some(_) => Err(self.error("unexpected content")),
+        }
+    }
+
+    /// The node starting at the next line, if it is indented at least `min_indent`
+    fn block(&mut self, min_indent: usize) -> Result<Value, FastSchemaError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.depth += 1;
+        let value = self.block_node(min_indent);
+        self.depth -= 1;
+        value
+    }
+
+    fn block_node(&mut self, min_indent: usize) -> Result<Value, FastSchemaError> {
+        self.skip_blank();
+        let line = match self.lines.get(self.pos) {
+            Some(line) if line.indent >= min_indent && !is_document_marker(line) => line,
+            _ => return Ok(Value::Null),
+        };
+        let indent = line.indent;
+        if is_sequence_entry(&line.text) {
+            self.sequence(indent)
+        } else if map_key(&line.text).map_err(|message| self.error(&message))?.is_some() {
+            self.mapping(indent)
+        } else {
+            let text = line.text.clone();
+            self.pos += 1;
+            self.inline(&text, min_indent, false)
+        }
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Value, FastSchemaError> {
+        let mut map = Map::new();
+        let mut merges = Vec::new();
+        loop {
+            self.skip_blank();
+            let line = match self.lines.get(self.pos) {
+                Some(line) if line.indent >= indent && !is_document_marker(line) => line,
+                _ => break,
+            };
+            if line.indent > indent {
+                return Err(self.error("unexpected indentation"));
+            }
+            let (key, rest) = match map_key(&line.text).map_err(|message| self.error(&message))? {
+                Some(entry) => entry,
+                None => return Err(self.error("expected 'key: value'")),
+            };
+            self.pos += 1;
+            let line = self.pos;
+            let value = self.inline(&rest, indent + 1, true)?;
+            if key == "<<" {
+                merges.push(value);
+            } else if map.insert(key.clone(), value).is_some() {
+                return Err(error(line - 1, &format!("duplicate key '{}'", key)));
+            }
+        }
+
+        // Keys written in the mapping itself win over merged ones
+        for merge in merges {
+            let sources = match merge {
+                Value::Array(sources) => sources,
+                source => vec![source],
+            };
+            for source in sources {
+                match source {
+                    Value::Object(source) => {
+                        for (key, value) in source {
+                            map.entry(key).or_insert(value);
+                        }
+                    }
+                    _ => return Err(self.error("'<<' must merge mappings")),
+                }
+            }
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn sequence(&mut self, indent: usize) -> Result<Value, FastSchemaError> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_blank();
+            let line = match self.lines.get_mut(self.pos) {
+                Some(line) if line.indent == indent && is_sequence_entry(&line.text) => line,
+                _ => break,
+            };
+            let rest = &line.text[1..];
+            let content = rest.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                self.pos += 1;
+            } else {
+                // Parse the entry as if it started on its own line, so
+                // `- key: value` continues with the keys aligned under it
+                let offset = 1 + rest.len() - content.len();
+                *line = Line { indent: indent + offset, text: content.to_string() };
+            }
+            items.push(self.block(indent + 1)?);
+        }
+        match self.lines.get(self.pos) {
+            Some(line) if line.indent > indent && !is_document_marker(line) => Err(self.error("unexpected indentation")),
+            _ => Ok(Value::Array(items)),
+        }
+    }
+
+    /// A node written after a key or dash (`text`, with the line already
+    /// consumed); continuation lines are indented at least `min_indent`
+    fn inline(&mut self, text: &str, min_indent: usize, in_mapping: bool) -> Result<Value, FastSchemaError> {
+        let (anchor, tag, text) = properties(text).map_err(|message| self.error(&message))?;
+        let text = strip_comment(text);
+
+        let value = if text.is_empty() {
+            // A mapping's value may be a sequence at the key's own indentation
+            self.skip_blank();
+            match self.lines.get(self.pos) {
+                Some(line) if in_mapping && line.indent + 1 == min_indent && is_sequence_entry(&line.text) => {
+                    self.sequence(line.indent)?
+                }
+                _ => self.block(min_indent)?,
+            }
+        } else if let Some(name) = text.strip_prefix('*') {
+            self.anchors.alias(name.trim()).map_err(|message| self.error(&message))?
+        } else if text.starts_with('|') || text.starts_with('>') {
+            Value::String(self.block_scalar(text, min_indent)?)
+        } else if text.starts_with(['[', '{', '"', '\'']) {
+            self.flow(text)?
+        } else {
+            let mut plain = text.to_string();
+            self.continue_plain(&mut plain, min_indent);
+            if tag.as_deref() == Some("!!str") {
+                Value::String(plain)
+            } else {
+                resolve(&plain).map_err(|message| self.error(&message))?
+            }
+        };
+
+        if let Some(anchor) = anchor {
+            self.anchors.insert(&anchor, &value);
+        }
+        Ok(value)
+    }
+
+    /// Fold the lines continuing a multi-line plain scalar into `plain`
+    fn continue_plain(&mut self, plain: &mut String, min_indent: usize) {
+        loop {
+            let mut next = self.pos;
+            while self.lines.get(next).is_some_and(|line| line.text.is_empty()) {
+                next += 1;
+            }
+            match self.lines.get(next) {
+                Some(line) if line.indent >= min_indent && !line.text.starts_with('#') && !is_document_marker(line) => {
+                    let breaks = next - self.pos;
+                    plain.push_str(&if breaks == 0 { " ".to_string() } else { "\n".repeat(breaks) });
+                    plain.push_str(strip_comment(&line.text));
+                    self.pos = next + 1;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// A `|` literal or `>` folded scalar; `header` is the indicator line
+    fn block_scalar(&mut self, header: &str, min_indent: usize) -> Result<String, FastSchemaError> {
+        let folded = header.starts_with('>');
+        let mut chomp = ' ';
+        let mut explicit = None;
+        for c in header[1..].chars() {
+            match c {
+                '+' | '-' => chomp = c,
+                '1'..='9' => explicit = c.to_digit(10).map(|digit| digit as usize),
+                _ => return Err(self.error("invalid block scalar header")),
+            }
+        }
+
+        let indent = match explicit {
+            Some(digit) => min_indent.saturating_sub(1) + digit,
+            None => self.lines[self.pos..]
+                .iter()
+                .find(|line| !line.text.is_empty())
+                .map_or(min_indent, |line| line.indent.max(min_indent)),
+        };
+        let mut content = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.text.is_empty() {
+                content.push(String::new());
+            } else if line.indent >= indent {
+                content.push(format!("{}{}", " ".repeat(line.indent - indent), line.text));
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        let trailing = content.iter().rev().take_while(|line| line.is_empty()).count();
+        let body = &content[..content.len() - trailing];
+        let mut text = String::new();
+        let more_indented = |line: &String| line.starts_with(' ');
+        for (index, line) in body.iter().enumerate() {
+            if index > 0 {
+                let previous = &body[index - 1];
+                if !folded || previous.is_empty() || more_indented(previous) || more_indented(line) {
+                    text.push('\n');
+                } else if !line.is_empty() {
+                    text.push(' ');
+                }
+                // A break followed by empty lines folds into them
+            }
+            text.push_str(line);
+        }
+        match chomp {
+            '-' => {}
+            '+' => text.push_str(&"\n".repeat(trailing + usize::from(!body.is_empty()))),
+            _ if !body.is_empty() => text.push('\n'),
+            _ => {}
+        }
+        Ok(text)
+    }
+
+    /// A flow collection or quoted scalar, which may span several lines
+    fn flow(&mut self, text: &str) -> Result<Value, FastSchemaError> {
+        let start = self.pos - 1;
+        let mut source = text.to_string();
+        let expanded = self.anchors.expanded;
+        loop {
+            let chars: Vec<char> = source.chars().collect();
+            let mut reader = FlowReader { chars: &chars, pos: 0, anchors: &mut self.anchors, depth: self.depth };
+            match reader.value() {
+                Ok(value) => {
+                    let rest: String = chars[reader.pos..].iter().collect();
+                    if !strip_comment(rest.trim_start()).is_empty() {
+                        return Err(error(self.pos - 1, "unexpected content after a flow node"));
+                    }
+                    return Ok(value);
+                }
+                Err(Flow::Invalid(message)) => return Err(error(start, &message)),
+                Err(Flow::Incomplete) => match self.lines.get(self.pos) {
+                    // Read again with the next line; aliases count once
+                    Some(line) => {
+                        self.anchors.expanded = expanded;
+                        source.push('\n');
+                        source.push_str(&line.text);
+                        self.pos += 1;
+                    }
+                    None => return Err(error(start, "unterminated flow node")),
+                },
+            }
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while self.lines.get(self.pos).is_some_and(|line| line.text.is_empty() || line.text.starts_with('#')) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> FastSchemaError {
+        error(self.pos.min(self.lines.len().saturating_sub(1)), message)
+    }
+}
+
+struct FlowReader<'a> {
+    chars: &'a [char],
+    pos: usize,
+    anchors: &'a mut Anchors,
+    /// Nodes being parsed, block ones included
+    depth: usize,
+}
+
+impl FlowReader<'_> {
+    fn value(&mut self) -> Result<Value, Flow> {
+        if self.depth == MAX_DEPTH {
+            return Err(Flow::Invalid("nested too deeply".to_string()));
+        }
+        self.depth += 1;
+        let value = self.node();
+        self.depth -= 1;
+        value
+    }
+
+    fn node(&mut self) -> Result<Value, Flow> {
+        self.skip_space();
+        let mut anchor = None;
+        let mut tag = None;
+        loop {
+            match self.peek() {
+                Some('&') => anchor = Some(self.name()),
+                Some('!') => tag = Some(self.name()),
+                _ => break,
+            }
+            self.skip_space();
+        }
+        if let Some(tag) = &tag {
+            check_tag(tag).map_err(Flow::Invalid)?;
+        }
+
+        let value = match self.peek() {
+            None => return Err(Flow::Incomplete),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                while !self.close(']')? {
+                    let item = self.value()?;
+                    self.skip_space();
+                    // `[key: value]` is a sequence of single-pair mappings
+                    let item = if self.peek() == Some(':') {
+                        self.pos += 1;
+                        let key = key_string(item);
+                        let value = self.value()?;
+                        Value::Object(std::iter::once((key, value)).collect())
+                    } else {
+                        item
+                    };
+                    items.push(item);
+                    self.separator(']')?;
+                }
+                Value::Array(items)
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut map = Map::new();
+                while !self.close('}')? {
+                    let key = key_string(self.value()?);
+                    self.skip_space();
+                    let value = if self.peek() == Some(':') {
+                        self.pos += 1;
+                        self.value()?
+                    } else {
+                        Value::Null
+                    };
+                    if map.insert(key.clone(), value).is_some() {
+                        return Err(Flow::Invalid(format!("duplicate key '{}'", key)));
+                    }
+                    self.separator('}')?;
+                }
+                Value::Object(map)
+            }
+            Some(quote @ ('"' | '\'')) => Value::String(self.quoted(quote)?),
+            Some('*') => {
+                let name = self.name();
+                self.anchors.alias(&name[1..]).map_err(Flow::Invalid)?
+            }
+            Some(_) => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    let next = self.chars.get(self.pos + 1).copied();
+                    let ends_key = c == ':' && next.is_none_or(|next| next.is_whitespace() || ",[]{}".contains(next));
+                    let comment = c == '#' && self.pos > start && self.chars[self.pos - 1].is_whitespace();
+                    if ",[]{}".contains(c) || ends_key || comment {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if tag.as_deref() == Some("!!str") {
+                    Value::String(text)
+                } else {
+                    resolve(&text).map_err(Flow::Invalid)?
+                }
+            }
+        };
+
+        if let Some(anchor) = anchor {
+            self.anchors.insert(&anchor[1..], &value);
+        }
+        Ok(value)
+    }
+
+    /// Whether the next token ends the collection, consuming it if so
+    fn close(&mut self, close: char) -> Result<bool, Flow> {
+        self.skip_space();
+        match self.peek() {
+            None => Err(Flow::Incomplete),
+            Some(c) if c == close => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+        }
+    }
+
+    /// The comma after an entry, unless the entry ends the collection
+    fn separator(&mut self, close: char) -> Result<(), Flow> {
+        self.skip_space();
+        match self.peek() {
+            None => Err(Flow::Incomplete),
+            Some(',') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) if c == close => Ok(()),
+            Some(c) => Err(Flow::Invalid(format!("expected ',' or '{}', found '{}'", close, c))),
+        }
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String, Flow> {
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            let c = self.peek().ok_or(Flow::Incomplete)?;
+            self.pos += 1;
+            match c {
+                '\'' if quote == '\'' => {
+                    if self.peek() == Some('\'') {
+                        self.pos += 1;
+                        text.push('\'');
+                    } else {
+                        return Ok(text);
+                    }
+                }
+                '"' if quote == '"' => return Ok(text),
+                '\\' if quote == '"' => {
+                    let escape = self.peek().ok_or(Flow::Incomplete)?;
+                    self.pos += 1;
+                    match escape {
+                        'n' => text.push('\n'),
+                        't' | '\t' => text.push('\t'),
+                        'r' => text.push('\r'),
+                        '0' => text.push('\0'),
+                        'b' => text.push('\u{8}'),
+                        'e' => text.push('\u{1b}'),
+                        ' ' | '"' | '/' | '\\' => text.push(escape),
+                        'x' | 'u' | 'U' => {
+                            let len = match escape {
+                                'x' => 2,
+                                'u' => 4,
+                                _ => 8,
+                            };
+                            let hex: String = self.chars.get(self.pos..self.pos + len).ok_or(Flow::Incomplete)?.iter().collect();
+                            self.pos += len;
+                            let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                            text.push(decoded.ok_or_else(|| Flow::Invalid(format!("invalid escape '\\{}{}'", escape, hex)))?);
+                        }
+                        // An escaped line break joins the lines without a space
+                        '\n' => self.skip_space(),
+                        _ => return Err(Flow::Invalid(format!("invalid escape '\\{}'", escape))),
+                    }
+                }
+                '\n' => {
+                    // A line break folds into a space, or into the empty lines after it
+                    text.truncate(text.trim_end_matches([' ', '\t']).len());
+                    let mut breaks = 0;
+                    while let Some(c) = self.peek() {
+                        match c {
+                            '\n' => breaks += 1,
+                            ' ' | '\t' => {}
+                            _ => break,
+                        }
+                        self.pos += 1;
+                    }
+                    text.push_str(&if breaks == 0 { " ".to_string() } else { "\n".repeat(breaks) });
+                }
+                _ => text.push(c),
+            }
+        }
+    }
+
+    /// An anchor, alias or tag token, including its indicator
+    fn name(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !c.is_whitespace() && !",[]{}".contains(c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn skip_space(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' && (self.pos == 0 || self.chars[self.pos - 1].is_whitespace()) {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if c.is_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+}
+
+fn is_document_marker(line: &Line) -> bool {
+    line.indent == 0 && ["---", "..."].iter().any(|marker| {
+        line.text.strip_prefix(marker).is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+fn is_sequence_entry(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Split `key: rest` off a line, or `None` if the line is not a mapping entry
+fn map_key(text: &str) -> Result<Option<(String, String)>, String> {
+    if text == "?" || text.starts_with("? ") {
+        return Err("complex keys are not supported".to_string());
+    }
+    if text.starts_with(['"', '\'']) {
+        let chars: Vec<char> = text.chars().collect();
+        let mut anchors = Anchors::default();
+        let mut reader = FlowReader { chars: &chars, pos: 0, anchors: &mut anchors, depth: 0 };
+        let key = match reader.quoted(chars[0]) {
+            Ok(key) => key,
+            Err(_) => return Ok(None),
+        };
+        let rest: String = chars[reader.pos..].iter().collect();
+        return Ok(rest.trim_start().strip_prefix(':').filter(|rest| rest.is_empty() || rest.starts_with([' ', '\t'])).map(|rest| (key, rest.to_string())));
+    }
+    if text.starts_with(['[', '{', '|', '>', '*', '&', '!', '#']) {
+        return Ok(None);
+    }
+
+    let bytes = text.as_bytes();
+    for (index, &byte) in bytes.iter().enumerate() {
+        match byte {
+            b'#' if index > 0 && bytes[index - 1] == b' ' => return Ok(None),
+            b':' if bytes.get(index + 1).is_none_or(|next| *next == b' ' || *next == b'\t') => {
+                return Ok(Some((text[..index].trim_end().to_string(), text[index + 1..].to_string())));
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Split leading `&anchor` and `!tag` properties off a node
+fn properties(text: &str) -> Result<(Option<String>, Option<String>, &str), String> {
+    let mut text = text.trim_start();
+    let (mut anchor, mut tag) = (None, None);
+    while text.starts_with(['&', '!']) {
+        let end = text.find(char::is_whitespace).unwrap_or(text.len());
+        if let Some(name) = text[..end].strip_prefix('&') {
+            anchor = Some(name.to_string());
+        } else {
+            check_tag(&text[..end])?;
+            tag = Some(text[..end].to_string());
+        }
+        text = text[end..].trim_start();
+    }
+    Ok((anchor, tag, text))
+}
+
+/// Standard tags only restate the type a value already has; others are rejected
+fn check_tag(tag: &str) -> Result<(), String> {
+    match tag {
+        "!!str" | "!!int" | "!!float" | "!!bool" | "!!null" | "!!map" | "!!seq" => Ok(()),
+        _ => Err(format!("tag '{}' is not supported", tag)),
+    }
+}
+
+/// `text` without a trailing comment: a `#` at the start or after
+/// whitespace, outside a quoted scalar. Quotes only open a scalar at the
+/// start of a token, so the `'` in a plain `don't` is content.
+fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut token_start = true;
+    let mut previous = ' ';
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match quote {
+            // `''` is an escaped quote in a single-quoted scalar
+            Some('\'') if c == '\'' && chars.peek().is_some_and(|(_, next)| *next == '\'') => {
+                chars.next();
+            }
+            Some('"') if c == '\\' => {
+                chars.next();
+            }
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if c == '#' && matches!(previous, ' ' | '\t') => return text[..index].trim_end(),
+            None if matches!(c, '"' | '\'') && token_start => quote = Some(c),
+            None if c == ' ' || c == '\t' => {}
+            None => token_start = matches!(c, '[' | '{' | ',' | ':'),
+        }
+        previous = c;
+    }
+    text.trim_end()
+}
+
+/// The number of values in `value`, itself included
+fn count_nodes(value: &Value) -> usize {
+    match value {
+        Value::Array(items) => 1 + items.iter().map(count_nodes).sum::<usize>(),
+        Value::Object(map) => 1 + map.values().map(count_nodes).sum::<usize>(),
+        _ => 1,
+    }
+}
+
+/// Object keys are strings in JSON, whatever a YAML key resolves to
+fn key_string(key: Value) -> String {
+    match key {
+        Value::String(key) => key,
+        Value::Null => String::new(),
+        key => key.to_string(),
+    }
+}
+
+/// Resolve a plain scalar by the YAML 1.2 core schema
+fn resolve(text: &str) -> Result<Value, String> {
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Ok(Value::Null),
+        "true" | "True" | "TRUE" => return Ok(Value::Bool(true)),
+        "false" | "False" | "FALSE" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+
+    let unsigned = text.strip_prefix('+').unwrap_or(text);
+    let digits = unsigned.strip_prefix('-').unwrap_or(unsigned);
+    if !digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        if let Ok(number) = unsigned.parse::<i64>() {
+            return Ok(Value::from(number));
+        }
+        if let Ok(number) = unsigned.parse::<u64>() {
+            return Ok(Value::from(number));
+        }
+    }
+    for (prefix, radix) in [("0x", 16), ("0o", 8)] {
+        if let Some(number) = text.strip_prefix(prefix).and_then(|digits| i64::from_str_radix(digits, radix).ok()) {
+            return Ok(Value::from(number));
+        }
+    }
+
+    let lower = text.to_ascii_lowercase();
+    if [".inf", "+.inf", "-.inf", ".nan"].contains(&lower.as_str()) {
+        return Err(format!("'{}' has no JSON representation", text));
+    }
+    let numeric = digits.bytes().any(|byte| byte.is_ascii_digit())
+        && digits.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && digits.bytes().all(|byte| byte.is_ascii_digit() || b".eE+-".contains(&byte));
+    if numeric {
+        if let Some(number) = unsigned.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Ok(Value::Number(number));
+        }
+    }
+    Ok(Value::String(text.to_string()))
+}
+
+fn error(line: usize, message: &str) -> FastSchemaError {
+    FastSchemaError::InputParsing(format!("YAML line {}: {}", line + 1, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_yaml() {
+        let yaml = r#"
+# Service configuration
+defaults: &defaults
+  retries: 3
+  timeout: 2.5
+service:
+  <<: *defaults
+  name: "api \u00e9"
+  retries: 5
+  enabled: yes
+  port: 0x1f90
+  hosts:
+  - alpha.internal
+  - name: beta
+    weight: 2   # heavier
+  tags: [web, "edge, cdn", {tier: 1}]
+  empty:
+  note: >
+    folded
+    text
+
+    kept
+  script: |-
+    echo one
+      echo two
+  quoted: 'it''s
+    folded'
+  version: !!str 1.10
+"#;
+        let value = parse_yaml(yaml).unwrap();
+        assert_eq!(
+            value["service"],
+            json!({
+                "retries": 5,
+                "timeout": 2.5,
+                "name": "api é",
+                "enabled": "yes",
+                "port": 8080,
+                "hosts": ["alpha.internal", {"name": "beta", "weight": 2}],
+                "tags": ["web", "edge, cdn", {"tier": 1}],
+                "empty": null,
+                "note": "folded text\nkept\n",
+                "script": "echo one\n  echo two",
+                "quoted": "it's folded",
+                "version": "1.10"
+            })
+        );
+        assert_eq!(parse_yaml("- [1, 2]\n- - 3\n  - ~\n").unwrap(), json!([[1, 2], [3, null]]));
+        assert_eq!(parse_yaml("--- plain text\n").unwrap(), json!("plain text"));
+
+        for (yaml, message) in [
+            ("a: 1\na: 2\n", "line 2: duplicate key 'a'"),
+            ("a: [1, 2\n", "line 1: unterminated flow node"),
+            ("a: *missing\n", "unknown alias"),
+            ("a: !Ref x\n", "tag '!Ref'"),
+            ("a: 1\n---\nb: 2\n", "only one document"),
+            ("a:\n\t- 1\n", "line 2: tabs"),
+            (&"[".repeat(200_000), "nested too deeply"),
+            (&"- ".repeat(200_000), "nested too deeply"),
+        ] {
+            let error = parse_yaml(yaml).unwrap_err().to_string();
+            assert!(error.contains(message), "{}: {}", yaml, error);
+        }
+
+        // `#` only starts a comment outside quotes
+        let yaml = "a: \"x # y\"  # note\nb: 'it''s # here'\nc: [\"d # e\", 'f #g'] # list\nd: {k: \"v # w\"}\ne: don't # plain\n";
+        assert_eq!(
+            parse_yaml(yaml).unwrap(),
+            json!({"a": "x # y", "b": "it's # here", "c": ["d # e", "f #g"], "d": {"k": "v # w"}, "e": "don't"})
+        );
+
+        // Each level aliases the one before ten times: 10^9 nodes in all
+        let mut laughs = "a: &a [lol, lol, lol, lol, lol, lol, lol, lol, lol, lol]\n".to_string();
+        for (level, previous) in "bcdefghij".chars().zip("abcdefghi".chars()) {
+            laughs.push_str(&format!("{}: &{} [{}]\n", level, level, vec![format!("*{}", previous); 10].join(", ")));
+        }
+        let error = parse_yaml(&laughs).unwrap_err().to_string();
+        assert!(error.contains("aliases expand to more than"), "{}", error);
+        assert_eq!(parse_yaml("a: &a [1, 2]\nb: [*a, *a]\n").unwrap()["b"], json!([[1, 2], [1, 2]]));
+    }
+}
@@ -0,0 +1,176 @@
+
+//! Output of `zod-to-json-schema`, for schemas generated from Zod. It is
+//! draft-07 JSON Schema plus a few idioms of its own: `{"not": {}}` for
+//! `z.never()`, `z.undefined()` and the missing branch of optional unions,
+//! and array-form `items` / `additionalItems` for tuples. They are rewritten
+//! to draft 2020-12, `$ref` pointers included, and the result is translated
+//! like any other standard document.
+
+use crate::dialect::DRAFT_2020_12;
+use serde_json::{Map, Value};
+
+/// Keywords whose values are data, not subschemas
+const DATA_KEYWORDS: &[&str] = &["const", "enum", "default", "examples"];
+
+/// Keywords whose values map names to subschemas
+const SCHEMA_MAPS: &[&str] = &["properties", "definitions", "$defs"];
+
+/// Rewrite a `zod-to-json-schema` document as draft 2020-12
+pub(crate) fn to_draft_2020_12(document: &Value) -> Value {
+    let mut schema = rewrite(document, document);
+    if let Value::Object(obj) = &mut schema {
+        obj.insert("$schema".to_string(), Value::from(DRAFT_2020_12));
+    }
+    schema
+}
+
+fn rewrite(node: &Value, root: &Value) -> Value {
+    let obj = match node {
+        Value::Object(obj) => obj,
+        _ => return node.clone(),
+    };
+    if obj.get("not") == Some(&Value::Object(Map::new())) {
+        return Value::Bool(false);
+    }
+
+    let tuple = is_tuple(node);
+    let mut rewritten = Map::new();
+    for (keyword, value) in obj {
+        let keyword = keyword.as_str();
+        let value = match (keyword, value) {
+            _ if DATA_KEYWORDS.contains(&keyword) => value.clone(),
+            ("$ref", Value::String(reference)) if reference.starts_with("#/") => {
+                Value::String(format!("#{}", rewrite_pointer(root, &reference[1..])))
+            }
+            (_, Value::Object(schemas)) if SCHEMA_MAPS.contains(&keyword) => Value::Object(
+                schemas.iter().map(|(name, schema)| (name.clone(), rewrite(schema, root))).collect(),
+            ),
+            (_, Value::Array(schemas)) => Value::Array(schemas.iter().map(|schema| rewrite(schema, root)).collect()),
+            _ => rewrite(value, root),
+        };
+        let keyword = match keyword {
+            "items" if tuple => "prefixItems",
+            "additionalItems" if tuple => "items",
+            // Without array-form `items`, draft-07 ignores `additionalItems`
+            "additionalItems" => continue,
+            _ => keyword,
+        };
+        rewritten.insert(keyword.to_string(), value);
+    }
+    Value::Object(rewritten)
+}
+
+/// Whether `node` uses draft-07's array form of `items`
+fn is_tuple(node: &Value) -> bool {
+    matches!(node.get("items"), Some(Value::Array(_)))
+}
+
+/// Follow a JSON pointer through the original document, renaming the tuple
+/// keywords `rewrite` renames
+fn rewrite_pointer(root: &Value, pointer: &str) -> String {
+    let mut node = Some(root);
+    let mut at_schema = true;
+    let mut rewritten = String::new();
+    for segment in pointer.split('/').skip(1) {
+        let current = node;
+        node = current.and_then(|current| current.pointer(&format!("/{}", segment)));
+
+        let tuple = at_schema && current.is_some_and(is_tuple);
+        rewritten.push('/');
+        rewritten.push_str(match segment {
+            "items" if tuple => "prefixItems",
+            "additionalItems" if tuple => "items",
+            _ => segment,
+        });
+        // A schema's map and array keywords hold schemas one level down
+        at_schema = !at_schema
+            || !(SCHEMA_MAPS.contains(&segment)
+                || ["allOf", "anyOf", "oneOf"].contains(&segment)
+                || (segment == "items" && tuple));
+    }
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::Dialect;
+    use crate::validator::Validator;
+    use serde_json::json;
+
+    #[test]
+    fn test_zod_to_json_schema_output() {
+        // zodToJsonSchema(z.object({
+        //   id: z.string().uuid(),
+        //   nickname: z.string().min(1).optional(),
+        //   role: z.enum(["admin", "user"]),
+        //   point: z.tuple([z.number(), z.number()]).rest(z.string()),
+        //   tags: z.record(z.string().min(2), z.boolean()),
+        //   friend: z.lazy(() => schema).optional(),
+        //   maybe: z.array(z.string().optional()),
+        //   nothing: z.never().optional(),
+        //   origin: sameTupleAsPoint.optional(),
+        // }))
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "properties": {
+                "id": {"type": "string", "format": "uuid"},
+                "nickname": {"type": "string", "minLength": 1},
+                "role": {"type": "string", "enum": ["admin", "user"]},
+                "point": {
+                    "type": "array",
+                    "minItems": 2,
+                    "items": [{"type": "number"}, {"type": "number"}],
+                    "additionalItems": {"type": "string"}
+                },
+                "tags": {"type": "object", "additionalProperties": {"type": "boolean"}, "propertyNames": {"minLength": 2}},
+                "friend": {"$ref": "#"},
+                "maybe": {"type": "array", "items": {"anyOf": [{"not": {}}, {"type": "string"}]}},
+                "nothing": {"not": {}},
+                "origin": {"$ref": "#/properties/point/items/0"}
+            },
+            "required": ["id", "role", "point", "tags", "maybe"],
+            "additionalProperties": false
+        });
+        assert_eq!(Dialect::detect(&schema), Dialect::ZodToJsonSchema);
+        let rewritten = to_draft_2020_12(&schema);
+        assert_eq!(rewritten["properties"]["origin"]["$ref"], "#/properties/point/prefixItems/0");
+        assert_eq!(rewritten["properties"]["point"]["items"], json!({"type": "string"}));
+
+        let validator = Validator::from_json(&schema).unwrap();
+        let valid = json!({
+            "id": "9b2f3c4e-8d1a-4f6b-9c3e-2a1b0c9d8e7f",
+            "role": "admin",
+            "point": [1, 2, "label"],
+            "tags": {"ok": true},
+            "maybe": ["a"],
+            "friend": {"id": "9b2f3c4e-8d1a-4f6b-9c3e-2a1b0c9d8e7f", "role": "user", "point": [0, 0], "tags": {}, "maybe": []},
+            "origin": 3
+        });
+        assert!(validator.validate(&valid).success, "{:?}", validator.validate(&valid).errors);
+
+        let invalid = json!({
+            "id": "not-a-uuid",
+            "nickname": "",
+            "role": "owner",
+            "point": [1, "two", 3],
+            "tags": {"x": "yes"},
+            "maybe": [1],
+            "nothing": null,
+            "origin": "zero",
+            "extra": true
+        });
+        let result = validator.validate(&invalid);
+        let mut fields: Vec<String> = result
+            .errors
+            .iter()
+            .map(|error| error.path.to_string().split(['.', '[']).next().unwrap().to_string())
+            .collect();
+        fields.sort();
+        fields.dedup();
+        assert_eq!(fields, vec!["extra", "id", "maybe", "nickname", "nothing", "origin", "point", "role", "tags"]);
+        assert_eq!(result.errors.iter().filter(|error| error.path.to_string().starts_with("point")).count(), 2);
+        assert_eq!(result.errors.iter().filter(|error| error.path.to_string().ends_with("tags.x")).count(), 2);
+    }
+}